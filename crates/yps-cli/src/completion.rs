@@ -1,15 +1,17 @@
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::BTreeSet;
 
 use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
-use rustyline::highlight::Highlighter;
+use rustyline::highlight::{CmdKind, Highlighter};
 use rustyline::hint::Hinter;
 use rustyline::validate::Validator;
 use rustyline::{Context, Helper};
 
 use yps_interpreter::builtins::builtin_names;
 use yps_lexer::{KEYWORDS, KeywordKind, Lexer, SourceFile, TokenKind};
+use yps_parser::highlight::{self, COMMENT, FUNCTION, KEYWORD, NUMBER, PARAMETER, READONLY, STRING};
 
 fn is_word_char(ch: char) -> bool {
     ch.is_alphanumeric() || ch == '_'
@@ -77,6 +79,38 @@ pub fn extract_declared_idents(source_text: &str) -> Vec<String> {
     idents
 }
 
+fn token_color(ty: u32, mods: u32) -> Option<&'static str> {
+    match ty {
+        KEYWORD => Some("\x1b[35m"),
+        STRING => Some("\x1b[32m"),
+        NUMBER => Some("\x1b[33m"),
+        COMMENT => Some("\x1b[90m"),
+        FUNCTION => Some("\x1b[34m"),
+        PARAMETER => Some("\x1b[36m"),
+        _ if mods & READONLY != 0 => Some("\x1b[1m"),
+        _ => None,
+    }
+}
+
+/// Colors `line` by the same token classes the language server reports as semantic tokens.
+pub fn highlight_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut last = 0;
+    for (span, ty, mods) in highlight::classify(line) {
+        let Some(color) = token_color(ty, mods) else { continue };
+        if span.start < last || span.end > line.len() {
+            continue;
+        }
+        out.push_str(&line[last..span.start]);
+        out.push_str(color);
+        out.push_str(&line[span.start..span.end]);
+        out.push_str("\x1b[0m");
+        last = span.end;
+    }
+    out.push_str(&line[last..]);
+    out
+}
+
 pub struct YpsHelper {
     locals: RefCell<BTreeSet<String>>,
 }
@@ -112,7 +146,15 @@ impl Hinter for YpsHelper {
     type Hint = String;
 }
 
-impl Highlighter for YpsHelper {}
+impl Highlighter for YpsHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        Cow::Owned(highlight_line(line))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _kind: CmdKind) -> bool {
+        true
+    }
+}
 
 impl Validator for YpsHelper {}
 
@@ -178,4 +220,27 @@ mod tests {
         let idents = extract_declared_idents("сказать(1);\n");
         assert!(idents.is_empty());
     }
+
+    #[test]
+    fn highlight_line_colors_keywords_literals_and_comments() {
+        let line = "гыы х = \"а\" + 1; // итог";
+        let colored = highlight_line(line);
+        assert!(colored.starts_with("\x1b[35mгыы\x1b[0m х = "), "{colored:?}");
+        assert!(colored.contains("\x1b[32m\"а\"\x1b[0m"), "{colored:?}");
+        assert!(colored.contains("\x1b[33m1\x1b[0m"), "{colored:?}");
+        assert!(colored.ends_with("\x1b[90m// итог\x1b[0m"), "{colored:?}");
+    }
+
+    #[test]
+    fn highlight_line_marks_functions_and_parameters() {
+        let colored = highlight_line("йопта ф(а) { отвечаю а; }");
+        assert!(colored.contains("\x1b[34mф\x1b[0m"), "{colored:?}");
+        assert!(colored.contains("\x1b[36mа\x1b[0m"), "{colored:?}");
+    }
+
+    #[test]
+    fn highlight_line_tolerates_incomplete_input() {
+        let colored = highlight_line("йопта ф(а) { отвечаю \"");
+        assert!(colored.starts_with("\x1b[35mйопта\x1b[0m"), "{colored:?}");
+    }
 }
@@ -0,0 +1,320 @@
+//! Renders diagnostics as source snippets with carets, in the style of rustc:
+//!
+//! ```text
+//! ошибка[E0012]: Ожидалась ';'
+//!  --> main.yopta:2:1
+//!   |
+//! 1 | сказать(1)
+//!   |          - инструкция должна закончиться здесь
+//! 2 | сказать(2);
+//!   | ^^^^^^^
+//! ```
+//!
+//! Columns are counted in characters, not bytes, so carets line up under Cyrillic text.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+
+use yps_interpreter::RuntimeError;
+use yps_lexer::{Diagnostic, LabelStyle, Severity, SourceFile, Span, codes};
+
+const TAB_WIDTH: usize = 4;
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const GREEN: &str = "\x1b[1;32m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Writes `diagnostics` to stderr, in color when stderr is a terminal and `NO_COLOR` is unset.
+pub(crate) fn emit(source: &SourceFile, diagnostics: &[Diagnostic]) {
+    let color = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    for d in diagnostics {
+        eprintln!("{}", render(source, d, color));
+    }
+}
+
+/// An interpreter error as a diagnostic: causes become notes, stack frames become secondary labels.
+pub(crate) fn from_runtime_error(e: &RuntimeError) -> Diagnostic {
+    let code = if e.thrown.is_some() { codes::UNCAUGHT_EXCEPTION } else { codes::RUNTIME_ERROR };
+    let mut d = Diagnostic::error(e.message.clone(), e.span).with_code(code);
+    let mut cause = e.cause.as_deref();
+    while let Some(c) = cause {
+        d = d.with_note(format!("причина: {}", c.message));
+        cause = c.cause.as_deref();
+    }
+    if let Some(help) = &e.help {
+        d = d.with_help(help.clone());
+    }
+    let mut seen = vec![e.span];
+    for frame in &e.stack {
+        if !seen.contains(&frame.span) {
+            seen.push(frame.span);
+            d = d.with_secondary(frame.span, format!("в «{}»", frame.name));
+        }
+    }
+    d
+}
+
+pub(crate) fn from_vm_error(e: &yps_vm::ExecError) -> Diagnostic {
+    match e {
+        yps_vm::ExecError::Compile(e) => Diagnostic::error(e.message.clone(), e.span)
+            .with_code(codes::UNSUPPORTED_BY_VM)
+            .with_help("запустите без --vm"),
+        yps_vm::ExecError::Runtime(e) => {
+            let code = if e.thrown.is_some() { codes::UNCAUGHT_EXCEPTION } else { codes::RUNTIME_ERROR };
+            let d = Diagnostic::error(e.message.clone(), e.span).with_code(code);
+            match &e.help {
+                Some(help) => d.with_help(help.clone()),
+                None => d,
+            }
+        }
+    }
+}
+
+struct Style {
+    color: bool,
+}
+
+impl Style {
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color && !text.is_empty() { format!("{code}{text}{RESET}") } else { text.to_string() }
+    }
+}
+
+/// A label resolved to a single source line: `[start_col, end_col)` in display columns.
+struct LineMark<'a> {
+    start_col: usize,
+    end_col: usize,
+    message: &'a str,
+    style: LabelStyle,
+}
+
+pub(crate) fn render(source: &SourceFile, d: &Diagnostic, color: bool) -> String {
+    let style = Style { color };
+    let accent = match d.severity {
+        Severity::Error => RED,
+        Severity::Warning => YELLOW,
+    };
+    let mut out = String::new();
+
+    let title = match d.severity {
+        Severity::Error => "ошибка",
+        Severity::Warning => "предупреждение",
+    };
+    let head = match d.code {
+        Some(code) => format!("{title}[{code}]"),
+        None => title.to_string(),
+    };
+    let _ = writeln!(out, "{}{}", style.paint(accent, &head), style.paint(BOLD, &format!(": {}", d.message)));
+
+    let text = source.source.as_str();
+    let primary_known = valid_span(text, d.span);
+    let (line, col) = source.position(if primary_known { d.span.start } else { 0 });
+    let mut lines: BTreeMap<usize, Vec<LineMark<'_>>> = BTreeMap::new();
+    if primary_known {
+        let message = d
+            .labels
+            .iter()
+            .find(|l| l.style == LabelStyle::Primary && l.span == d.span)
+            .map_or("", |l| l.message.as_str());
+        add_mark(text, d.span, message, LabelStyle::Primary, &mut lines);
+    }
+    for label in &d.labels {
+        if (label.style == LabelStyle::Primary && label.span == d.span) || !valid_span(text, label.span) {
+            continue;
+        }
+        add_mark(text, label.span, &label.message, label.style, &mut lines);
+    }
+
+    let width = lines.keys().next_back().map_or(1, |n| (n + 1).to_string().len());
+    let pad = " ".repeat(width);
+    let bar = style.paint(BLUE, "|");
+    if primary_known {
+        let _ = writeln!(out, "{pad}{} {}:{line}:{col}", style.paint(BLUE, "-->"), source.name);
+    } else {
+        let _ = writeln!(out, "{pad}{} {}", style.paint(BLUE, "-->"), source.name);
+    }
+
+    if !lines.is_empty() {
+        let _ = writeln!(out, "{pad} {bar}");
+        let mut previous: Option<usize> = None;
+        for (index, marks) in &mut lines {
+            if previous.is_some_and(|p| *index > p + 1) {
+                let _ = writeln!(out, "{}", style.paint(BLUE, "..."));
+            }
+            previous = Some(*index);
+            let number = style.paint(BLUE, &format!("{:>width$}", index + 1));
+            let _ = writeln!(out, "{}", format!("{number} {bar} {}", expand_tabs(line_text(text, *index))).trim_end());
+            marks.sort_by_key(|m| (m.style != LabelStyle::Primary, m.start_col));
+            for mark in marks.iter() {
+                let (symbol, paint) = match mark.style {
+                    LabelStyle::Primary => ('^', accent),
+                    LabelStyle::Secondary => ('-', BLUE),
+                };
+                let underline: String = std::iter::repeat_n(symbol, mark.end_col - mark.start_col).collect();
+                let mut row = format!("{}{}", " ".repeat(mark.start_col), style.paint(paint, &underline));
+                if !mark.message.is_empty() {
+                    row.push(' ');
+                    row.push_str(&style.paint(paint, mark.message));
+                }
+                let _ = writeln!(out, "{pad} {bar} {row}");
+            }
+        }
+    }
+
+    let extras: Vec<(&str, &String)> =
+        d.notes.iter().map(|n| ("примечание", n)).chain(d.help.iter().map(|h| ("помощь", h))).collect();
+    if !extras.is_empty() {
+        let _ = writeln!(out, "{pad} {bar}");
+        for (kind, message) in extras {
+            let _ = writeln!(out, "{pad} {} {}: {message}", style.paint(BLUE, "="), style.paint(BOLD, kind));
+        }
+    }
+
+    for suggestion in &d.suggestions {
+        let _ = writeln!(out, "{}: {}", style.paint(BOLD, "помощь"), suggestion.message);
+        if !valid_span(text, suggestion.span) {
+            continue;
+        }
+        let index = line_index(text, suggestion.span.start);
+        let start = line_start(text, suggestion.span.start);
+        let end = start + line_text(text, index).len();
+        if suggestion.span.end > end {
+            continue;
+        }
+        let patched = format!(
+            "{}{}{}",
+            &text[start..suggestion.span.start],
+            suggestion.replacement,
+            &text[suggestion.span.end..end]
+        );
+        let col = display_width(&text[start..suggestion.span.start]);
+        let symbol = if suggestion.span.start == suggestion.span.end { '+' } else { '~' };
+        let marker: String = std::iter::repeat_n(symbol, display_width(&suggestion.replacement).max(1)).collect();
+        let number = style.paint(BLUE, &format!("{:>width$}", index + 1));
+        let _ = writeln!(out, "{pad} {bar}");
+        let _ = writeln!(out, "{number} {bar} {}", expand_tabs(&patched));
+        let _ = writeln!(out, "{pad} {bar} {}{}", " ".repeat(col), style.paint(GREEN, &marker));
+    }
+
+    out
+}
+
+fn add_mark<'a>(
+    text: &str,
+    span: Span,
+    message: &'a str,
+    style: LabelStyle,
+    lines: &mut BTreeMap<usize, Vec<LineMark<'a>>>,
+) {
+    let index = line_index(text, span.start);
+    let start = line_start(text, span.start);
+    let line = line_text(text, index);
+    let end = span.end.min(start + line.len()).max(span.start);
+    let start_col = display_width(&text[start..span.start]);
+    let end_col = (start_col + display_width(&text[span.start..end])).max(start_col + 1);
+    lines.entry(index).or_default().push(LineMark { start_col, end_col, message, style });
+}
+
+fn valid_span(text: &str, span: Span) -> bool {
+    span.start <= span.end
+        && span.end <= text.len()
+        && text.is_char_boundary(span.start)
+        && text.is_char_boundary(span.end)
+}
+
+fn line_index(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count()
+}
+
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_text(text: &str, index: usize) -> &str {
+    let line = text.split('\n').nth(index).unwrap_or("");
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
+
+fn expand_tabs(s: &str) -> String {
+    s.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yps_interpreter::error::Frame;
+    use yps_lexer::Applicability;
+
+    fn file(text: &str) -> SourceFile {
+        SourceFile::new("тест.yopta".into(), text.into())
+    }
+
+    #[test]
+    fn carets_align_under_cyrillic_text() {
+        let source = file("гыы имя = ;\n");
+        let d = Diagnostic::error("Неожиданный токен", Span { start: 16, end: 17 })
+            .with_code(codes::UNEXPECTED_TOKEN)
+            .with_label("здесь ожидалось выражение");
+        let out = render(&source, &d, false);
+        assert_eq!(
+            out,
+            "ошибка[E0011]: Неожиданный токен\n \
+             --> тест.yopta:1:11\n  \
+             |\n\
+             1 | гыы имя = ;\n  \
+             |           ^ здесь ожидалось выражение\n"
+        );
+    }
+
+    #[test]
+    fn secondary_labels_notes_and_suggestions_are_rendered() {
+        let text = "йопта ф() {\n\tсказать(1)\n";
+        let source = file(text);
+        let open = text.find('{').unwrap();
+        let call_end = text.rfind(')').unwrap() + 1;
+        let d = Diagnostic::error("Ожидалась '}'", Span { start: text.len(), end: text.len() })
+            .with_code(codes::UNCLOSED_DELIMITER)
+            .with_secondary(Span { start: open, end: open + 1 }, "'{' открыта здесь")
+            .with_note("тело функции не закрыто")
+            .with_suggestion(
+                "добавьте ';'",
+                Span { start: call_end, end: call_end },
+                ";",
+                Applicability::MachineApplicable,
+            );
+        let out = render(&source, &d, false);
+        assert!(out.contains("1 | йопта ф() {\n  |           - '{' открыта здесь\n"), "{out}");
+        assert!(out.contains("  = примечание: тело функции не закрыто\n"), "{out}");
+        assert!(out.contains("помощь: добавьте ';'\n  |\n2 |     сказать(1);\n  |               +\n"), "{out}");
+    }
+
+    #[test]
+    fn runtime_errors_carry_code_causes_and_frames() {
+        let text = "йопта ф() { кидай 1; }\nф();\n";
+        let source = file(text);
+        let throw = text.find("кидай").unwrap();
+        let call = text.rfind("ф()").unwrap();
+        let mut e = RuntimeError::new("бум", Span { start: throw, end: throw + "кидай 1;".len() })
+            .with_cause(RuntimeError::new("глубже", Span { start: 0, end: 0 }));
+        let body = text.find('{').unwrap();
+        e.attach_stack(vec![Frame {
+            name: "ф".into(),
+            span: Span { start: call, end: call + "ф()".len() },
+            file: 0,
+            decl: Span { start: body, end: text.find('}').unwrap() + 1 },
+        }]);
+        let d = from_runtime_error(&e);
+        assert_eq!(d.code, Some(codes::RUNTIME_ERROR));
+        let out = render(&source, &d, false);
+        assert!(out.contains("2 | ф();\n  | --- в «ф»\n"), "{out}");
+        assert!(out.contains("= примечание: причина: глубже"), "{out}");
+    }
+}
@@ -1,48 +1,73 @@
+use std::cell::RefCell;
 use std::env;
 use std::fs;
 use std::io::{self, Read as IoRead, Write as IoWrite};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
-
-use yps_interpreter::{Interpreter, RuntimeError};
-use yps_lexer::{Diagnostic, Lexer, SourceFile};
+use std::rc::Rc;
+use std::time::Duration;
+
+use yps_interpreter::coverage;
+use yps_interpreter::heap_snapshot::{self, HeapGraph};
+use yps_interpreter::profile::{self, Profile, ProfileHook, Sampler};
+use yps_interpreter::sources;
+use yps_interpreter::{DebugAction, Interpreter, RuntimeError, trace};
+use yps_lexer::{Diagnostic, Lexer, SourceFile, codes};
 use yps_parser::{Parser, Program};
 
 mod completion;
+mod diagnostics;
 mod repl;
+mod test_runner;
 
 const INTERNAL_ERROR_EXIT_CODE: i32 = 70;
 
-pub(crate) fn print_diagnostics(source: &SourceFile, diagnostics: &[Diagnostic], name: &str) {
-    for d in diagnostics {
-        let (line, col) = source.position(d.span.start);
-        eprintln!("{name}:{line}:{col}: {:?}: {}", d.severity, d.message);
-    }
+type Profiler = (Rc<RefCell<Profile>>, Sampler);
+
+pub(crate) fn print_diagnostics(source: &SourceFile, diagnostics: &[Diagnostic]) {
+    diagnostics::emit(source, diagnostics);
 }
 
-pub(crate) fn print_runtime_error(source: &SourceFile, e: &RuntimeError, name: &str) {
-    let (line_n, col) = source.position(e.span.start);
-    eprintln!("{name}:{line_n}:{col}: {e}");
-    for frame in &e.stack {
-        let (fl, fc) = source.position(frame.span.start);
-        eprintln!("  в {}:{name}:{fl}:{fc}", frame.name);
-    }
+pub(crate) fn print_runtime_error(source: &SourceFile, e: &RuntimeError) {
+    diagnostics::emit(source, &[diagnostics::from_runtime_error(e)]);
+}
+
+pub(crate) fn print_vm_error(source: &SourceFile, e: &yps_vm::ExecError) {
+    diagnostics::emit(source, &[diagnostics::from_vm_error(e)]);
 }
 
 const HELP_TEXT: &str = "Использование: yps [ФЛАГИ] [ФАЙЛ]
-       yps repl
+       yps repl [--vm]
        yps fmt <файл.yopta> [--write|-w] [--check] [--source-map]
        yps ast <файл.yopta>
        yps disasm <файл.yopta>
        yps lint <файл.yopta>
+       yps check <файл.yopta>
        yps transpile <файл.yopta> [-o файл.js]
+       yps explain <код>
+       yps test [--vm] [--coverage[=КАТАЛОГ]] [ПУТИ...]
 
 Выполнение программы:
   yps ФАЙЛ                  выполнить файл на дереве интерпретации
   yps --vm ФАЙЛ             выполнить файл на байткодовой VM
   yps -e \"код\", --eval \"код\"  выполнить код, переданный строкой
   yps -                     выполнить код, прочитанный из stdin
+  yps --record ТРАССА ФАЙЛ  записать недетерминированные входы (время, рандом, stdin,
+                            ФС, Сеть, порядок таймеров) в файл трассы
+  yps --replay ТРАССА ФАЙЛ  воспроизвести запуск по ранее записанной трассе
+  yps --profile=out.cpuprofile ФАЙЛ
+                            снять профиль CPU (Chrome DevTools) и out.folded для флеймграфа
+  --profile-interval=МКС    интервал выборки профайлера в микросекундах (по умолчанию 1000)
+  yps --heap-snapshot=out.heapsnapshot ФАЙЛ
+                            записать снимок кучи (формат V8) по завершении программы,
+                            со счётчиком выделений по местам в коде
+  yps --coverage[=КАТАЛОГ] ФАЙЛ
+                            собрать покрытие строк, веток и функций (с импортированными
+                            модулями) и слить его в КАТАЛОГ/lcov.info и КАТАЛОГ/html
+                            (по умолчанию каталог coverage)
+  yps --no-eval ФАЙЛ        запретить выполнение кода из строк ('ебал', 'Функция')
   yps repl                  запустить интерактивный REPL
+  yps repl --vm             REPL на байткодовой VM
   yps                       без аргументов — тоже REPL
 
 Форматирование:
@@ -51,15 +76,24 @@ const HELP_TEXT: &str = "Использование: yps [ФЛАГИ] [ФАЙЛ]
   yps fmt <файл.yopta> --check      проверить, отформатирован ли файл (код выхода)
   yps fmt <файл.yopta> --source-map добавить source map к результату
 
+Тесты:
+  yps test [ПУТИ...]        выполнить все *.test.yopta в путях (по умолчанию в текущем
+                            каталоге); файл провален, если завершился с ошибкой
+  yps test --coverage       то же, с общим отчётом покрытия по всем тестам
+
 Отладка:
   yps ast <файл.yopta>      напечатать дерево разбора (AST) файла
   yps disasm <файл.yopta>   напечатать дизассемблированный байткод VM
   yps lint <файл.yopta>     проверить файл линтером (код выхода 1 при находках)
+  yps check <файл.yopta>    проверить типы (код выхода 1 при ошибках типов)
 
 Транспиляция:
   yps transpile <файл.yopta>             напечатать JS в stdout
   yps transpile <файл.yopta> -o файл.js  записать JS в файл
 
+Ошибки:
+  yps explain <код>         объяснить код ошибки, например E0012
+
 Прочее:
   -h, --help       показать эту справку
   -V, --version    показать версию";
@@ -68,7 +102,7 @@ fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
-        repl::run_repl();
+        repl::run_repl(&[]);
         return;
     }
 
@@ -83,8 +117,11 @@ fn main() {
         "ast" => run_ast(&args[2..]),
         "disasm" => run_disasm(&args[2..]),
         "lint" => run_lint(&args[2..]),
+        "check" => run_check(&args[2..]),
         "transpile" => run_transpile(&args[2..]),
-        "repl" => repl::run_repl(),
+        "explain" => run_explain(&args[2..]),
+        "test" => test_runner::run_tests(&args[2..]),
+        "repl" => repl::run_repl(&args[2..]),
         _ => run_program(&args[1..]),
     }
 }
@@ -123,8 +160,7 @@ fn run_disasm(args: &[String]) {
     match yps_vm::compile_program(&program) {
         Ok(proto) => println!("{}", yps_vm::disassemble(&proto)),
         Err(e) => {
-            let (line, col) = source.position(e.span.start);
-            eprintln!("{}:{line}:{col}: {e}", source.name);
+            print_vm_error(&source, &e.into());
             process::exit(1);
         }
     }
@@ -144,7 +180,7 @@ fn run_lint(args: &[String]) {
     let source = SourceFile::new(filename.clone(), code);
 
     if !result.parse_errors.is_empty() {
-        print_diagnostics(&source, &result.parse_errors, &filename);
+        print_diagnostics(&source, &result.parse_errors);
         process::exit(1);
     }
 
@@ -159,6 +195,23 @@ fn run_lint(args: &[String]) {
     process::exit(1);
 }
 
+fn run_check(args: &[String]) {
+    let filename = single_file_arg("check", args);
+    let (source, program) = load_program(&filename);
+    let info = yps_types::infer::infer(&program);
+    let errors = info.errors();
+    let mut found: Vec<Diagnostic> = errors
+        .iter()
+        .map(|(span, message)| Diagnostic::error(*message, *span).with_code(codes::TYPE_MISMATCH))
+        .collect();
+    found.extend(info.warnings().into_iter().map(|(span, message)| Diagnostic::warning(message, span)));
+    found.sort_by_key(|d| d.span.start);
+    print_diagnostics(&source, &found);
+    if !errors.is_empty() {
+        process::exit(1);
+    }
+}
+
 fn run_transpile(args: &[String]) {
     const USAGE: &str = "Использование: yps transpile <файл.yopta> [-o файл.js]";
 
@@ -202,8 +255,7 @@ fn run_transpile(args: &[String]) {
     let js = match yps_jsgen::transpile(&program) {
         Ok(js) => js,
         Err(e) => {
-            let (line, col) = source.position(e.span.start);
-            eprintln!("{filename}:{line}:{col}: {e}");
+            print_diagnostics(&source, &[Diagnostic::error(e.message, e.span)]);
             process::exit(1);
         }
     };
@@ -224,17 +276,31 @@ fn run_transpile(args: &[String]) {
     }
 }
 
+fn run_explain(args: &[String]) {
+    let [code] = args else {
+        eprintln!("Использование: yps explain <код>");
+        process::exit(1);
+    };
+    let Some(info) = yps_lexer::codes::lookup(code) else {
+        eprintln!("Неизвестный код ошибки: {code}");
+        process::exit(1);
+    };
+    println!("{}: {}\n\n{}", info.code, info.title, info.explanation);
+}
+
 fn run_program(args: &[String]) {
     let mut use_vm = false;
     let mut eval_code: Option<String> = None;
     let mut use_stdin = false;
     let mut file: Option<String> = None;
+    let mut options = RunOptions::default();
 
     let mut i = 0;
     while i < args.len() {
         let arg = args[i].as_str();
         match arg {
             "--vm" => use_vm = true,
+            "--no-eval" => options.no_eval = true,
             "-e" | "--eval" => {
                 i += 1;
                 match args.get(i) {
@@ -245,6 +311,37 @@ fn run_program(args: &[String]) {
                     }
                 }
             }
+            "--record" | "--replay" => {
+                i += 1;
+                let Some(path) = args.get(i) else {
+                    eprintln!("Флаг {arg} требует путь к файлу трассы");
+                    process::exit(1);
+                };
+                if arg == "--record" {
+                    options.record = Some(PathBuf::from(path));
+                } else {
+                    options.replay = Some(PathBuf::from(path));
+                }
+            }
+            other if other.starts_with("--profile=") => {
+                options.profile = Some(PathBuf::from(&other["--profile=".len()..]));
+            }
+            "--coverage" => options.coverage = Some(PathBuf::from(coverage::DEFAULT_DIR)),
+            other if other.starts_with("--coverage=") => {
+                options.coverage = Some(PathBuf::from(&other["--coverage=".len()..]));
+            }
+            other if other.starts_with("--heap-snapshot=") => {
+                options.heap_snapshot = Some(PathBuf::from(&other["--heap-snapshot=".len()..]));
+            }
+            other if other.starts_with("--profile-interval=") => {
+                match other["--profile-interval=".len()..].parse::<u64>() {
+                    Ok(us) if us > 0 => options.profile_interval = Some(Duration::from_micros(us)),
+                    _ => {
+                        eprintln!("Флаг --profile-interval ожидает положительное число микросекунд");
+                        process::exit(1);
+                    }
+                }
+            }
             "-" => use_stdin = true,
             other if other.starts_with('-') => {
                 eprintln!("Неизвестный флаг: {other}");
@@ -264,7 +361,7 @@ fn run_program(args: &[String]) {
     if let Some(code) = eval_code {
         let source = SourceFile::new("<eval>".to_string(), code);
         let program = parse_or_exit(&source);
-        execute(source, program, None, use_vm);
+        execute(source, program, None, use_vm, &options);
         return;
     }
 
@@ -276,7 +373,7 @@ fn run_program(args: &[String]) {
         }
         let source = SourceFile::new("<stdin>".to_string(), code);
         let program = parse_or_exit(&source);
-        execute(source, program, None, use_vm);
+        execute(source, program, None, use_vm, &options);
         return;
     }
 
@@ -284,7 +381,7 @@ fn run_program(args: &[String]) {
         Some(filename) => {
             let (source, program) = load_program(&filename);
             let base = PathBuf::from(&filename).parent().map(PathBuf::from);
-            execute(source, program, base, use_vm);
+            execute(source, program, base, use_vm, &options);
         }
         None => {
             eprintln!("Не указан файл для выполнения");
@@ -293,24 +390,112 @@ fn run_program(args: &[String]) {
     }
 }
 
-fn execute(source: SourceFile, program: Program, base: Option<PathBuf>, use_vm: bool) {
-    if use_vm {
-        run_vm(source, program, base);
+#[derive(Default)]
+struct RunOptions {
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    profile: Option<PathBuf>,
+    profile_interval: Option<Duration>,
+    heap_snapshot: Option<PathBuf>,
+    coverage: Option<PathBuf>,
+    no_eval: bool,
+}
+
+fn execute(source: SourceFile, program: Program, base: Option<PathBuf>, use_vm: bool, options: &RunOptions) {
+    start_trace(options);
+    let profiler = options.profile.as_ref().map(|_| {
+        let sampler = Sampler::start(options.profile_interval.unwrap_or(profile::DEFAULT_INTERVAL));
+        (Rc::new(RefCell::new(Profile::new())), sampler)
+    });
+    let profile = profiler.as_ref().map(|(p, _)| Rc::clone(p));
+    if options.heap_snapshot.is_some() {
+        heap_snapshot::start_tracking();
+    }
+    let snapshot = options.heap_snapshot.as_deref();
+    // Registered under its absolute path, so that coverage from runs started in different
+    // directories merges into the same record.
+    let path = fs::canonicalize(&source.name).map_or_else(|_| source.name.clone(), |p| p.display().to_string());
+    let entry = SourceFile::new(path, source.source.clone());
+    sources::start(entry.clone());
+    let coverage_file = options.coverage.as_ref().map(|dir| start_coverage(dir, &entry, &program));
+    let code = if use_vm {
+        run_vm(&source, program, base, profiler, snapshot, coverage_file, !options.no_eval)
     } else {
-        run_interpret(source, program, base);
+        run_interpret(&source, program, base, profiler, snapshot, !options.no_eval)
+    };
+    heap_snapshot::stop_tracking();
+    finish_trace(options);
+    if let (Some(dir), Err(e)) = (&options.coverage, coverage::save()) {
+        eprintln!("Не удалось записать покрытие в '{}': {e}", dir.display());
     }
+    if let (Some(path), Some(profile)) = (&options.profile, profile) {
+        write_profile(path, &profile.borrow(), &sources::files());
+    }
+    if code != 0 {
+        process::exit(code);
+    }
+}
+
+fn start_coverage(dir: &Path, entry: &SourceFile, program: &Program) -> usize {
+    coverage::start();
+    coverage::set_output_dir(dir.to_path_buf());
+    coverage::register_file(entry, program)
+}
+
+/// Writes the Chrome profile to `path` and folded stacks next to it with a `.folded` extension.
+fn write_profile(path: &Path, profile: &Profile, files: &[SourceFile]) {
+    let folded = path.with_extension("folded");
+    for (target, text) in [(path, profile.to_cpuprofile(files)), (folded.as_path(), profile.to_folded(files))] {
+        if let Err(e) = fs::write(target, text) {
+            eprintln!("Не удалось записать профиль '{}': {e}", target.display());
+        }
+    }
+}
+
+fn start_trace(options: &RunOptions) {
+    if let Some(path) = &options.replay {
+        let text = match fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("Не удалось прочитать трассу '{}': {e}", path.display());
+                process::exit(1);
+            }
+        };
+        match trace::Trace::parse(&text) {
+            Ok(t) => trace::start_replay(t),
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                process::exit(1);
+            }
+        }
+    } else if options.record.is_some() {
+        trace::start_recording();
+    }
+    if let Some(path) = &options.record {
+        trace::set_save_path(path.clone());
+    }
+}
+
+fn finish_trace(options: &RunOptions) {
+    if let Some(divergence) = trace::divergence() {
+        eprintln!("Воспроизведение разошлось с трассой: {divergence}");
+    }
+    if let (Some(path), Err(e)) = (&options.record, trace::save()) {
+        eprintln!("Не удалось записать трассу '{}': {e}", path.display());
+    }
+    trace::finish();
 }
 
 fn parse_or_exit(source: &SourceFile) -> Program {
     let (tokens, lex_diagnostics) = Lexer::new(source).tokenize();
     if !lex_diagnostics.is_empty() {
-        print_diagnostics(source, &lex_diagnostics, &source.name);
+        print_diagnostics(source, &lex_diagnostics);
         process::exit(1);
     }
 
     let (program, parse_diagnostics) = Parser::new(&tokens, source).parse_program();
     if !parse_diagnostics.is_empty() {
-        print_diagnostics(source, &parse_diagnostics, &source.name);
+        print_diagnostics(source, &parse_diagnostics);
         process::exit(1);
     }
 
@@ -331,19 +516,55 @@ fn load_program(filename: &str) -> (SourceFile, Program) {
     (source, program)
 }
 
-fn run_vm(source: SourceFile, program: Program, base: Option<PathBuf>) {
-    let name = source.name.clone();
-    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| yps_vm::execute_with_base(&program, base)));
+fn write_heap_snapshot(path: &Path, graph: &HeapGraph) {
+    if let Err(e) = graph.write(path) {
+        eprintln!("Не удалось записать снимок кучи '{}': {e}", path.display());
+    }
+}
+
+fn run_vm(
+    source: &SourceFile,
+    program: Program,
+    base: Option<PathBuf>,
+    profiler: Option<Profiler>,
+    heap_snapshot: Option<&Path>,
+    coverage_file: Option<usize>,
+    allow_eval: bool,
+) -> i32 {
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> Result<(), yps_vm::ExecError> {
+        let proto = yps_vm::compile_program(&program)?;
+        let mut vm = yps_vm::Vm::new();
+        vm.set_allow_eval(allow_eval);
+        if let Some(file) = coverage_file {
+            vm.track_coverage(&proto, file);
+        }
+        if let Some(base) = base {
+            vm.set_base_path(base);
+            vm.set_module_path(PathBuf::from(&source.name));
+        }
+        if let Some((profile, sampler)) = profiler {
+            vm.set_profiler(profile, sampler);
+        }
+        if heap_snapshot.is_some() {
+            vm.retain_script_scope();
+            vm.track_allocations();
+        }
+        let result = vm.run(proto);
+        if let Some(path) = heap_snapshot {
+            write_heap_snapshot(path, &vm.heap_snapshot());
+        }
+        result?;
+        Ok(())
+    }));
     match outcome {
-        Ok(Ok(())) => {}
+        Ok(Ok(())) => 0,
         Ok(Err(e)) => {
-            let (line, col) = source.position(e.span().start);
-            eprintln!("{name}:{line}:{col}: {e}");
-            process::exit(1);
+            print_vm_error(source, &e);
+            1
         }
         Err(_) => {
             eprintln!("Внутренняя ошибка VM: выполнение прервано");
-            process::exit(INTERNAL_ERROR_EXIT_CODE);
+            INTERNAL_ERROR_EXIT_CODE
         }
     }
 }
@@ -396,7 +617,7 @@ fn run_fmt(args: &[String]) {
         match e {
             yps_fmt::FormatError::ParseError(diags) => {
                 let sf = SourceFile::new(filename.clone(), source.clone());
-                print_diagnostics(&sf, &diags, filename);
+                print_diagnostics(&sf, &diags);
                 eprintln!("Форматирование отклонено: файл содержит синтаксические ошибки");
             }
             yps_fmt::FormatError::RoundTripFailed(msg) => {
@@ -465,22 +686,37 @@ fn run_fmt(args: &[String]) {
     }
 }
 
-fn run_interpret(source: SourceFile, program: Program, base: Option<PathBuf>) {
-    let name = source.name.clone();
+fn run_interpret(
+    source: &SourceFile,
+    program: Program,
+    base: Option<PathBuf>,
+    profiler: Option<Profiler>,
+    heap_snapshot: Option<&Path>,
+    allow_eval: bool,
+) -> i32 {
     let mut interpreter = Interpreter::new();
+    interpreter.set_allow_eval(allow_eval);
     if let Some(parent) = base {
         interpreter.set_base_path(parent);
+        interpreter.set_module_path(PathBuf::from(&source.name));
+    }
+    if let Some((profile, sampler)) = profiler {
+        interpreter.set_debug_hook(Box::new(ProfileHook::new(profile, sampler)));
+        interpreter.set_debug_resume(DebugAction::Continue);
     }
     let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| interpreter.run(&program)));
+    if let (Some(path), Ok(_)) = (heap_snapshot, &outcome) {
+        write_heap_snapshot(path, &interpreter.heap_snapshot());
+    }
     match outcome {
-        Ok(Ok(())) => {}
+        Ok(Ok(())) => 0,
         Ok(Err(e)) => {
-            print_runtime_error(&source, &e, &name);
-            process::exit(1);
+            print_runtime_error(source, &e);
+            1
         }
         Err(_) => {
             eprintln!("Внутренняя ошибка интерпретатора: выполнение прервано");
-            process::exit(INTERNAL_ERROR_EXIT_CODE);
+            INTERNAL_ERROR_EXIT_CODE
         }
     }
 }
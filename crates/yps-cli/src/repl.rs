@@ -1,4 +1,5 @@
 use std::env;
+use std::fs;
 use std::io::{self, BufRead, IsTerminal};
 use std::path::PathBuf;
 use std::process;
@@ -9,10 +10,11 @@ use rustyline::history::DefaultHistory;
 
 use yps_interpreter::Interpreter;
 use yps_lexer::{Lexer, SourceFile};
+use yps_parser::ast::Stmt;
 use yps_parser::{Parser, Program};
 
 use crate::completion::YpsHelper;
-use crate::{print_diagnostics, print_runtime_error};
+use crate::{print_diagnostics, print_runtime_error, print_vm_error};
 
 type YpsEditor = Editor<YpsHelper, DefaultHistory>;
 
@@ -38,7 +40,7 @@ fn check_and_report(source: &SourceFile, report_incomplete: bool) -> CheckOutcom
     let lexer = Lexer::new(source);
     let (tokens, lex_diags) = lexer.tokenize();
     if !lex_diags.is_empty() {
-        print_diagnostics(source, &lex_diags, "<repl>");
+        print_diagnostics(source, &lex_diags);
         return CheckOutcome::LexError;
     }
 
@@ -48,7 +50,7 @@ fn check_and_report(source: &SourceFile, report_incomplete: bool) -> CheckOutcom
         if unexpected_eof && !report_incomplete {
             return CheckOutcome::Incomplete;
         }
-        print_diagnostics(source, &parse_diags, "<repl>");
+        print_diagnostics(source, &parse_diags);
         return if unexpected_eof { CheckOutcome::Incomplete } else { CheckOutcome::ParseError };
     }
 
@@ -62,15 +64,29 @@ enum ReplCommand {
     Reset,
     Cancel,
     Repeat(usize),
+    Load(String),
+    Save(String),
+    Type(String),
+    Ast,
+    Bytecode,
 }
 
 fn parse_repl_command(input: &str) -> Option<ReplCommand> {
     let trimmed = input.trim();
+    let (head, arg) = trimmed.split_once(char::is_whitespace).map_or((trimmed, ""), |(h, a)| (h, a.trim()));
+    match head {
+        ":загрузить" => return Some(ReplCommand::Load(arg.to_string())),
+        ":сохранить" => return Some(ReplCommand::Save(arg.to_string())),
+        ":тип" => return Some(ReplCommand::Type(arg.to_string())),
+        _ => {}
+    }
     match trimmed {
         ":выход" => Some(ReplCommand::Exit),
         ":история" => Some(ReplCommand::History),
         ":сброс" => Some(ReplCommand::Reset),
         ":отмена" => Some(ReplCommand::Cancel),
+        ":ast" => Some(ReplCommand::Ast),
+        ":байткод" => Some(ReplCommand::Bytecode),
         s if s.starts_with('!') => {
             let num_str = s[1..].trim();
             num_str.parse::<usize>().ok().filter(|&n| n >= 1).map(ReplCommand::Repeat)
@@ -94,6 +110,85 @@ fn print_history(history: &[String]) {
     }
 }
 
+/// The backend a REPL session evaluates on; both keep their globals between inputs.
+enum Session {
+    Tree(Box<Interpreter>),
+    Vm(Box<yps_vm::Vm>),
+}
+
+impl Session {
+    fn new(use_vm: bool) -> Self {
+        let cwd = env::current_dir().ok();
+        if use_vm {
+            let mut vm = yps_vm::Vm::new();
+            if let Some(cwd) = cwd {
+                vm.set_base_path(cwd);
+            }
+            Session::Vm(Box::new(vm))
+        } else {
+            let mut interpreter = Interpreter::new();
+            if let Some(cwd) = cwd {
+                interpreter.set_base_path(cwd);
+            }
+            Session::Tree(Box::new(interpreter))
+        }
+    }
+
+    fn is_vm(&self) -> bool {
+        matches!(self, Session::Vm(_))
+    }
+
+    /// Runs `program`, reporting errors itself; yields the completion value.
+    fn eval(&mut self, source: &SourceFile, program: &Program) -> Result<Option<String>, ()> {
+        match self {
+            Session::Tree(interpreter) => match interpreter.run_repl(program) {
+                Ok(value) => Ok(value.map(|v| v.to_string())),
+                Err(e) => {
+                    print_runtime_error(source, &e);
+                    Err(())
+                }
+            },
+            Session::Vm(vm) => {
+                let result = yps_vm::compile_repl(program)
+                    .map_err(yps_vm::ExecError::from)
+                    .and_then(|proto| vm.run_repl(proto).map_err(yps_vm::ExecError::from));
+                match result {
+                    Ok(value) if matches!(program.items.last(), Some(Stmt::Expr { .. })) => Ok(Some(value.to_string())),
+                    Ok(_) => Ok(None),
+                    Err(e) => {
+                        print_vm_error(source, &e);
+                        Err(())
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The type `expr` would have after the inputs in `script`, inferred without running anything.
+fn static_type(script: &[String], expr: &str) -> String {
+    let mut text = script.join("\n");
+    text.push('\n');
+    text.push_str(expr);
+    let source = SourceFile::new("<repl>".to_string(), text);
+    let (tokens, _) = Lexer::new(&source).tokenize();
+    let (program, _) = Parser::new(&tokens, &source).parse_program();
+    yps_types::infer::completion_type(&program).to_string()
+}
+
+fn print_bytecode(program: &Program) {
+    match yps_vm::compile_repl(program) {
+        Ok(proto) => println!("{}", yps_vm::disassemble(&proto)),
+        Err(e) => eprintln!("Не удалось скомпилировать: {e}"),
+    }
+}
+
+fn save_script(path: &str, script: &[String]) -> io::Result<()> {
+    let mut text = script.join("\n");
+    text.push('\n');
+    fs::write(path, text)
+}
+
 enum LineEvent {
     Line(String),
     Cancelled,
@@ -164,17 +259,26 @@ impl InputSource {
     }
 }
 
-pub fn run_repl() {
-    let is_tty = io::stdin().is_terminal();
-    let mut interpreter = Interpreter::new();
-    if let Ok(cwd) = env::current_dir() {
-        interpreter.set_base_path(cwd);
+pub fn run_repl(args: &[String]) {
+    let mut use_vm = false;
+    for arg in args {
+        match arg.as_str() {
+            "--vm" => use_vm = true,
+            other => {
+                eprintln!("Неизвестный флаг: {other}");
+                process::exit(1);
+            }
+        }
     }
+    let is_tty = io::stdin().is_terminal();
+    let mut session = Session::new(use_vm);
 
     if is_tty {
         let version = env!("CARGO_PKG_VERSION");
-        println!("ЙоптаСкрипт v{version}");
+        let backend = if use_vm { " (VM)" } else { "" };
+        println!("ЙоптаСкрипт v{version}{backend}");
         println!("Введите `:выход` для выхода, `:история` для истории, `:сброс` для сброса состояния.");
+        println!("`:загрузить файл`, `:сохранить файл`, `:тип выражение`, `:ast`, `:байткод` — см. README.");
     }
 
     let mut input = if is_tty {
@@ -192,6 +296,9 @@ pub fn run_repl() {
         InputSource::Piped(io::stdin())
     };
     let mut history: Vec<String> = Vec::new();
+    // Inputs that actually ran, in order; `:сохранить` writes them out as a script.
+    let mut script: Vec<String> = Vec::new();
+    let mut last_program: Option<Program> = None;
     let mut buffer = String::new();
 
     loop {
@@ -228,10 +335,9 @@ pub fn run_repl() {
                         continue;
                     }
                     ReplCommand::Reset => {
-                        interpreter = Interpreter::new();
-                        if let Ok(cwd) = env::current_dir() {
-                            interpreter.set_base_path(cwd);
-                        }
+                        session = Session::new(session.is_vm());
+                        script.clear();
+                        last_program = None;
                         input.reset_locals();
                         if is_tty {
                             println!("Состояние сброшено.");
@@ -250,6 +356,62 @@ pub fn run_repl() {
                         }
                         push_line(&mut buffer, &repeated);
                     }
+                    ReplCommand::Load(path) => {
+                        if path.is_empty() {
+                            eprintln!("Использование: :загрузить ФАЙЛ");
+                            continue;
+                        }
+                        let text = match fs::read_to_string(&path) {
+                            Ok(text) => text,
+                            Err(e) => {
+                                eprintln!("Не удалось прочитать '{path}': {e}");
+                                continue;
+                            }
+                        };
+                        let source = SourceFile::new(path, text);
+                        if let CheckOutcome::Ready(program) = check_and_report(&source, true) {
+                            input.record_declarations(&source.source);
+                            if session.eval(&source, &program).is_ok() {
+                                script.push(source.source.trim_end_matches('\n').to_string());
+                            }
+                            last_program = Some(program);
+                        }
+                        continue;
+                    }
+                    ReplCommand::Save(path) => {
+                        if path.is_empty() {
+                            eprintln!("Использование: :сохранить ФАЙЛ");
+                        } else if let Err(e) = save_script(&path, &script) {
+                            eprintln!("Не удалось записать '{path}': {e}");
+                        } else if is_tty {
+                            println!("Сохранено в {path}.");
+                        }
+                        continue;
+                    }
+                    ReplCommand::Type(mut expr) => {
+                        if !expr.ends_with(';') {
+                            expr.push(';');
+                        }
+                        let source = SourceFile::new("<repl>".to_string(), expr);
+                        if let CheckOutcome::Ready(_) = check_and_report(&source, true) {
+                            println!("{}", static_type(&script, &source.source));
+                        }
+                        continue;
+                    }
+                    ReplCommand::Ast => {
+                        match &last_program {
+                            Some(program) => println!("{program:#?}"),
+                            None => eprintln!("Ещё нет выполненного ввода."),
+                        }
+                        continue;
+                    }
+                    ReplCommand::Bytecode => {
+                        match &last_program {
+                            Some(program) => print_bytecode(program),
+                            None => eprintln!("Ещё нет выполненного ввода."),
+                        }
+                        continue;
+                    }
                 }
             } else {
                 push_line(&mut buffer, &line);
@@ -280,16 +442,16 @@ pub fn run_repl() {
 
         let completed_input = buffer.trim_end_matches('\n').to_string();
         input.record_declarations(&completed_input);
-        history.push(completed_input);
+        history.push(completed_input.clone());
         buffer.clear();
 
-        match interpreter.run_repl(&program) {
-            Ok(Some(value)) => println!("{value}"),
-            Ok(None) => {}
-            Err(e) => {
-                print_runtime_error(&source, &e, "<repl>");
+        if let Ok(value) = session.eval(&source, &program) {
+            script.push(completed_input);
+            if let Some(value) = value {
+                println!("{value}");
             }
         }
+        last_program = Some(program);
     }
 
     input.save_history();
@@ -396,6 +558,25 @@ mod tests {
         assert_eq!(parse_repl_command(":неизвестно"), None);
     }
 
+    #[test]
+    fn parse_cmd_load_and_save_take_a_path() {
+        assert_eq!(parse_repl_command(":загрузить  lib.yopta "), Some(ReplCommand::Load("lib.yopta".to_string())));
+        assert_eq!(parse_repl_command(":сохранить сессия.yopta"), Some(ReplCommand::Save("сессия.yopta".to_string())));
+        assert_eq!(parse_repl_command(":загрузить"), Some(ReplCommand::Load(String::new())));
+    }
+
+    #[test]
+    fn parse_cmd_type_keeps_the_whole_expression() {
+        assert_eq!(parse_repl_command(":тип а + [1, 2]"), Some(ReplCommand::Type("а + [1, 2]".to_string())));
+    }
+
+    #[test]
+    fn parse_cmd_views_of_last_input() {
+        assert_eq!(parse_repl_command(":ast"), Some(ReplCommand::Ast));
+        assert_eq!(parse_repl_command(":байткод"), Some(ReplCommand::Bytecode));
+        assert_eq!(parse_repl_command(":ast лишнее"), None);
+    }
+
     #[test]
     fn format_history_line_first() {
         assert_eq!(format_history_line(0, "гыы х = 1;"), "1: гыы х = 1;");
@@ -0,0 +1,113 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+use yps_interpreter::coverage::{self, Report};
+
+const TEST_SUFFIX: &str = ".test.yopta";
+
+/// `yps test`: runs every `*.test.yopta` in its own process; a file fails if it exits non-zero.
+pub(crate) fn run_tests(args: &[String]) {
+    let mut use_vm = false;
+    let mut coverage_dir: Option<PathBuf> = None;
+    let mut roots = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--vm" => use_vm = true,
+            "--coverage" => coverage_dir = Some(PathBuf::from(coverage::DEFAULT_DIR)),
+            other if other.starts_with("--coverage=") => {
+                coverage_dir = Some(PathBuf::from(&other["--coverage=".len()..]));
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Неизвестный флаг: {other}");
+                process::exit(1);
+            }
+            other => roots.push(PathBuf::from(other)),
+        }
+    }
+    if roots.is_empty() {
+        roots.push(PathBuf::from("."));
+    }
+
+    let mut files = Vec::new();
+    for root in &roots {
+        if let Err(e) = collect_tests(root, &mut files) {
+            eprintln!("Не удалось обойти '{}': {e}", root.display());
+            process::exit(1);
+        }
+    }
+    files.sort();
+    files.dedup();
+    if files.is_empty() {
+        eprintln!("Тесты (*{TEST_SUFFIX}) не найдены");
+        process::exit(1);
+    }
+
+    let exe = env::current_exe().unwrap_or_else(|e| {
+        eprintln!("Не удалось найти исполняемый файл yps: {e}");
+        process::exit(1);
+    });
+    if let Some(dir) = &coverage_dir {
+        let _ = fs::remove_file(dir.join(coverage::LCOV_FILE));
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        let mut command = Command::new(&exe);
+        if use_vm {
+            command.arg("--vm");
+        }
+        if let Some(dir) = &coverage_dir {
+            command.arg(format!("--coverage={}", dir.display()));
+        }
+        let output = match command.arg(file).output() {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Не удалось запустить '{}': {e}", file.display());
+                process::exit(1);
+            }
+        };
+        if output.status.success() {
+            println!("ок     {}", file.display());
+        } else {
+            failed += 1;
+            println!("ПРОВАЛ {}", file.display());
+            let _ = io::stdout().write_all(&output.stdout);
+            let _ = io::stdout().write_all(&output.stderr);
+        }
+    }
+
+    println!("\nТестов: {}, прошло: {}, упало: {failed}", files.len(), files.len() - failed);
+    if let Some(dir) = &coverage_dir {
+        let lcov = dir.join(coverage::LCOV_FILE);
+        match fs::read_to_string(&lcov).map(|text| Report::parse_lcov(&text)) {
+            Ok(Ok(report)) => println!("Покрытие ({}): {}", lcov.display(), report.summary()),
+            Ok(Err(e)) => eprintln!("{e}"),
+            Err(e) => eprintln!("Не удалось прочитать '{}': {e}", lcov.display()),
+        }
+    }
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+fn collect_tests(path: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_file() {
+        out.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" && name != "node_modules" {
+                collect_tests(&path, out)?;
+            }
+        } else if name.ends_with(TEST_SUFFIX) {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
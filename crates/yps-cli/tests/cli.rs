@@ -35,6 +35,57 @@ fn reports_a_parse_error_with_a_location_and_exits_with_1() {
     assert!(out.stderr.contains(":1:"), "ожидалась позиция в stderr: {}", out.stderr);
 }
 
+#[test]
+fn reports_a_missing_semicolon_with_a_snippet_code_and_fix() {
+    let ws = Workspace::new("snippet");
+    let prog = ws.write("bad.yopta", "сказать(1)\nсказать(2);\n");
+
+    let out = run(&[prog.to_str().unwrap()], "");
+
+    assert_eq!(out.code, 1);
+    assert!(out.stderr.starts_with("ошибка[E0012]: "), "stderr: {}", out.stderr);
+    assert!(out.stderr.contains("1 | сказать(1)\n  |          - "), "stderr: {}", out.stderr);
+    assert!(out.stderr.contains("1 | сказать(1);\n  |           +\n"), "stderr: {}", out.stderr);
+}
+
+#[test]
+fn explain_prints_the_description_of_an_error_code() {
+    let out = run(&["explain", "E0012"], "");
+    assert_eq!(out.code, 0);
+    assert!(out.stdout.starts_with("E0012: Пропущена ';'"), "stdout: {}", out.stdout);
+
+    let unknown = run(&["explain", "E9999"], "");
+    assert_eq!(unknown.code, 1);
+    assert!(unknown.stderr.contains("Неизвестный код ошибки"), "stderr: {}", unknown.stderr);
+}
+
+#[test]
+fn check_reports_type_errors_and_exits_with_1() {
+    let ws = Workspace::new("check_err");
+    let prog = ws.write("bad.yopta", "гыы а: число = \"пять\";\nйопта ф(х: строка) {}\nф(1);\n");
+
+    let out = run(&["check", prog.to_str().unwrap()], "");
+
+    assert_eq!(out.code, 1);
+    assert!(
+        out.stderr.starts_with("ошибка[E0200]: Тип 'строка' нельзя присвоить типу 'число'"),
+        "stderr: {}",
+        out.stderr
+    );
+    assert!(out.stderr.contains("параметру 'х' типа 'строка'"), "stderr: {}", out.stderr);
+}
+
+#[test]
+fn check_accepts_well_typed_programs() {
+    let ws = Workspace::new("check_ok");
+    let prog = ws.write("ok.yopta", "тип Ид = число | строка;\nгыы а: Ид = Матан.пол(1.5);\nгыы б = а;\n");
+
+    let out = run(&["check", prog.to_str().unwrap()], "");
+
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(out.stderr, "");
+}
+
 #[test]
 fn reports_an_uncaught_exception_and_exits_with_1() {
     let ws = Workspace::new("throw");
@@ -213,6 +264,204 @@ fn fmt_source_map_emits_a_mapping_alongside_the_code() {
     assert!(out.stdout.contains("\"version\":3"), "ожидался source map: {}", out.stdout);
 }
 
+#[test]
+fn replay_reproduces_recorded_time_random_and_stdin() {
+    let ws = Workspace::new("record_replay");
+    let prog = ws.write("r.yopta", "сказать(Матан.рандом(), Дата.сейчас(), прочестьСтроку());\n");
+    let trace = ws.path("r.ypsr");
+
+    let recorded = run(&["--record", trace.to_str().unwrap(), prog.to_str().unwrap()], "первая\n");
+    assert_eq!(recorded.code, 0, "stderr: {}", recorded.stderr);
+    let text = std::fs::read_to_string(&trace).unwrap();
+    assert!(text.starts_with("ypsr 1\n"), "трасса: {text}");
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let replayed = run(&["--replay", trace.to_str().unwrap(), prog.to_str().unwrap()], "другая\n");
+    assert_eq!(replayed.code, 0, "stderr: {}", replayed.stderr);
+    assert_eq!(replayed.stdout, recorded.stdout);
+}
+
+#[test]
+fn replay_on_the_vm_uses_the_recorded_clock() {
+    let ws = Workspace::new("replay_vm");
+    let prog = ws.write("v.yopta", "сказать(Дата.сейчас());\n");
+    let trace = ws.write("v.ypsr", "ypsr 1\nвремя 1234\n");
+
+    let out = run(&["--replay", trace.to_str().unwrap(), "--vm", prog.to_str().unwrap()], "");
+
+    assert_eq!(out.stdout, "1234\n", "stderr: {}", out.stderr);
+}
+
+#[test]
+fn replay_rejects_a_file_that_is_not_a_trace() {
+    let ws = Workspace::new("replay_bad");
+    let prog = ws.write("p.yopta", "сказать(1);\n");
+    let bogus = ws.write("bad.ypsr", "это не трасса\n");
+
+    let out = run(&["--replay", bogus.to_str().unwrap(), prog.to_str().unwrap()], "");
+
+    assert_eq!(out.code, 1);
+    assert!(out.stderr.contains("ypsr 1"), "stderr: {}", out.stderr);
+}
+
+#[test]
+fn profile_writes_a_cpuprofile_and_folded_stacks_on_both_backends() {
+    let ws = Workspace::new("profile");
+    let prog = ws.write(
+        "p.yopta",
+        "йопта крутить(н) {\n    гыы с = 0;\n    го (гыы i = 0; i < н; i = i + 1) { с = с + i; }\n    отвечаю с;\n}\nсказать(крутить(20000));\n",
+    );
+
+    for backend in [None, Some("--vm")] {
+        let out_path = ws.path("out.cpuprofile");
+        let flag = format!("--profile={}", out_path.display());
+        let mut args = vec![flag.as_str(), "--profile-interval=100"];
+        args.extend(backend);
+        args.push(prog.to_str().unwrap());
+
+        let out = run(&args, "");
+
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+        assert_eq!(out.stdout, "199990000\n");
+        let profile = std::fs::read_to_string(&out_path).unwrap();
+        assert!(profile.contains("\"functionName\":\"крутить\""), "{backend:?}: {profile}");
+        let folded = std::fs::read_to_string(ws.path("out.folded")).unwrap();
+        assert!(folded.lines().any(|l| l.starts_with("(модуль):6;крутить:")), "{backend:?}: {folded}");
+    }
+}
+
+#[test]
+fn profile_keeps_same_named_functions_of_different_files_apart() {
+    let ws = Workspace::new("profile_modules");
+    ws.write(
+        "m.yopta",
+        "предъява йопта крутить(н) {\n    гыы с = 0;\n    го (гыы i = 0; i < н; i = i + 1) { с = с + 1; }\n    отвечаю с;\n}\n",
+    );
+    let prog = ws.write(
+        "p.yopta",
+        "спиздить * как м из \"./m\";\nйопта крутить(н) {\n    гыы с = 0;\n    го (гыы i = 0; i < н; i = i + 1) { с = с + i; }\n    отвечаю с;\n}\nсказать(крутить(20000) + м.крутить(20000));\n",
+    );
+
+    for backend in [None, Some("--vm")] {
+        let out_path = ws.path("out.cpuprofile");
+        let flag = format!("--profile={}", out_path.display());
+        let mut args = vec![flag.as_str(), "--profile-interval=100"];
+        args.extend(backend);
+        args.push(prog.to_str().unwrap());
+
+        let out = run(&args, "");
+
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+        assert_eq!(out.stdout, "200010000\n");
+        let folded = std::fs::read_to_string(ws.path("out.folded")).unwrap();
+        assert!(folded.lines().any(|l| l.starts_with("(модуль):7;крутить:")), "{backend:?}: {folded}");
+        assert!(folded.lines().any(|l| l.starts_with("(модуль):7;крутить:m.yopta:")), "{backend:?}: {folded}");
+        let profile = std::fs::read_to_string(&out_path).unwrap();
+        assert!(profile.contains("m.yopta\""), "{backend:?}: {profile}");
+    }
+}
+
+#[test]
+fn heap_snapshot_records_retainers_and_allocation_sites_on_both_backends() {
+    let ws = Workspace::new("heap");
+    let runtime_path = ws.path("rt.heapsnapshot");
+    ws.write("фабрика.yopta", "предъява йопта пара() {\n  отвечаю [1, 2];\n}\n");
+    let prog = ws.write(
+        "h.yopta",
+        &format!(
+            "спиздить {{ пара }} из \"./фабрика.yopta\";\nгыы п = пара();\nклёво Точка {{\n    Точка(х) {{ тырыпыры.х = х; }}\n}}\nгыы точки = [];\nгыы н = 0;\nпотрещим (н < 3) {{ точки.push(захуярить Точка(н)); н = н + 1; }}\nПроцесс.снимокКучи({:?});\nсказать(н);\n",
+            runtime_path.display().to_string()
+        ),
+    );
+
+    for backend in [None, Some("--vm")] {
+        let out_path = ws.path("out.heapsnapshot");
+        let flag = format!("--heap-snapshot={}", out_path.display());
+        let mut args = vec![flag.as_str()];
+        args.extend(backend);
+        args.push(prog.to_str().unwrap());
+
+        let out = run(&args, "");
+
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+        assert_eq!(out.stdout, "3\n");
+        for path in [&out_path, &runtime_path] {
+            let snapshot = std::fs::read_to_string(path).unwrap();
+            assert!(snapshot.starts_with("{\"snapshot\":{\"meta\":"), "{backend:?}: {snapshot}");
+            assert!(snapshot.contains("\"Точка\""), "{backend:?}: {snapshot}");
+        }
+        let snapshot = std::fs::read_to_string(&out_path).unwrap();
+        assert!(snapshot.contains("\"захуярить Точка(н) (8:31)\""), "{backend:?}: {snapshot}");
+        assert!(snapshot.contains("\"[1, 2] (2:11)\""), "{backend:?}: {snapshot}");
+        assert!(snapshot.contains("фабрика.yopta\""), "{backend:?}: {snapshot}");
+        assert!(snapshot.contains(",3,0,[]"), "три выделения в одном месте, {backend:?}: {snapshot}");
+    }
+}
+
+#[test]
+fn coverage_reports_lines_branches_and_imported_modules_on_both_backends() {
+    let ws = Workspace::new("coverage");
+    ws.write(
+        "lib.yopta",
+        "предъява йопта удвоить(х) {\n  вилкойвглаз (х > 10) {\n    отвечаю х;\n  }\n  отвечаю х * 2;\n}\n",
+    );
+    let prog = ws.write(
+        "main.yopta",
+        "спиздить { удвоить } из \"./lib.yopta\";\nйопта никогда() {\n  отвечаю 1;\n}\nгыы т = 0;\nго (гыы и = 0; и < 4; и++) {\n  т = т + удвоить(и);\n}\nсказать(т > 5 ? т : 0);\n",
+    );
+
+    for backend in [None, Some("--vm")] {
+        let dir = ws.path(if backend.is_some() { "cov-vm" } else { "cov" });
+        let flag = format!("--coverage={}", dir.display());
+        let mut args = vec![flag.as_str()];
+        args.extend(backend);
+        args.push(prog.to_str().unwrap());
+
+        let out = run(&args, "");
+
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+        assert_eq!(out.stdout, "12\n");
+        let lcov = std::fs::read_to_string(dir.join("lcov.info")).unwrap();
+        for record in [
+            "FNDA:0,никогда\n",
+            "DA:3,0\n",
+            "DA:6,1\n",
+            "DA:7,4\n",
+            "BRDA:9,0,0,1\nBRDA:9,0,1,0\n",
+            "lib.yopta\nFN:1,удвоить\nFNDA:4,удвоить\n",
+            "BRDA:2,0,0,0\nBRDA:2,0,1,4\n",
+        ] {
+            assert!(lcov.contains(record), "{backend:?}: нет {record:?} в\n{lcov}");
+        }
+        assert!(dir.join("html").join("index.html").exists());
+
+        run(&args, "");
+        let merged = std::fs::read_to_string(dir.join("lcov.info")).unwrap();
+        assert!(merged.contains("FNDA:8,удвоить\n"), "{backend:?}: {merged}");
+    }
+}
+
+#[test]
+fn test_subcommand_reports_failures_and_merges_coverage() {
+    let ws = Workspace::new("test_cmd");
+    ws.write("lib.yopta", "предъява йопта плюс(а, б) {\n  отвечаю а + б;\n}\n");
+    ws.write("a.test.yopta", "спиздить { плюс } из \"./lib.yopta\";\nплюс(1, 2);\n");
+    ws.write("b.test.yopta", "спиздить { плюс } из \"./lib.yopta\";\nплюс(1, 2);\nкидай \"упал\";\n");
+    ws.write("skip.yopta", "кидай \"не тест\";\n");
+    let dir = ws.path("cov");
+    let flag = format!("--coverage={}", dir.display());
+    let root = ws.path("");
+
+    let out = run(&["test", flag.as_str(), root.to_str().unwrap()], "");
+
+    assert_eq!(out.code, 1, "stdout: {}", out.stdout);
+    assert!(out.stdout.contains("ок     "), "{}", out.stdout);
+    assert!(out.stdout.contains("ПРОВАЛ "), "{}", out.stdout);
+    assert!(out.stdout.contains("Тестов: 2, прошло: 1, упало: 1"), "{}", out.stdout);
+    let lcov = std::fs::read_to_string(dir.join("lcov.info")).unwrap();
+    assert!(lcov.contains("FNDA:2,плюс\n"), "{lcov}");
+}
+
 #[test]
 fn repl_evaluates_and_prints_an_expression_value() {
     let out = run(&["repl"], "1 + 2;\n");
@@ -286,6 +535,47 @@ fn repl_recovers_after_a_parse_error() {
     assert_eq!(out.code, 0);
 }
 
+#[test]
+fn repl_awaits_top_level_promises_on_both_backends() {
+    let input = "йопта з(н) { отвечаю захуярить СловоПацана((р) => чутка(() => р(н), 5)); }\n\
+                 гыы в = сидетьНахуй з(41);\nв + 1;\n:тип в > 0\n";
+    for args in [&["repl"][..], &["repl", "--vm"]] {
+        let out = run(args, input);
+        assert_eq!(out.stdout, "42\nбулево\n", "{args:?} stderr: {}", out.stderr);
+        assert_eq!(out.code, 0);
+    }
+}
+
+#[test]
+fn repl_loads_a_file_and_saves_the_session_as_a_script() {
+    let ws = Workspace::new("repl_load_save");
+    let lib = ws.write("lib.yopta", "йопта удвоить(х) { отвечаю х * 2; }\n");
+    let saved = ws.path("сессия.yopta");
+    let input = format!(
+        ":загрузить {}\nгыы а = удвоить(4);\nгыы = ;\nкидай 1;\n:тип сказать(\"побочка\")\n:сохранить {}\n",
+        lib.display(),
+        saved.display()
+    );
+
+    let out = run(&["repl", "--vm"], &input);
+    assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+    assert_eq!(out.stdout, "неопределено\n", "':тип' не должен выполнять выражение");
+    let script = std::fs::read_to_string(&saved).unwrap();
+    assert_eq!(script, "йопта удвоить(х) { отвечаю х * 2; }\nгыы а = удвоить(4);\n");
+
+    let replayed = run(&[saved.to_str().unwrap()], "");
+    assert_eq!(replayed.code, 0, "stderr: {}", replayed.stderr);
+}
+
+#[test]
+fn repl_shows_ast_and_bytecode_of_the_last_input() {
+    let out = run(&["repl"], "1 + 2;\n:ast\n:байткод\n");
+
+    assert!(out.stdout.contains("Binary"), "stdout: {}", out.stdout);
+    assert!(out.stdout.contains("Add"), "stdout: {}", out.stdout);
+    assert_eq!(out.code, 0);
+}
+
 fn run(args: &[&str], stdin: &str) -> Run {
     let mut child = Command::new(env!("CARGO_BIN_EXE_yps-cli"))
         .args(args)
@@ -341,3 +631,61 @@ impl Drop for Workspace {
         let _ = std::fs::remove_dir_all(&self.dir);
     }
 }
+
+#[test]
+fn no_eval_flag_forbids_code_from_strings_on_both_backends() {
+    for backend in [&["--no-eval"][..], &["--no-eval", "--vm"][..]] {
+        let mut args = backend.to_vec();
+        args.extend(["-e", "сказать(ебал(\"1 + 2\"));"]);
+        let out = run(&args, "");
+
+        assert_eq!(out.code, 1);
+        assert!(out.stderr.contains("'ебал' запрещён"), "stderr: {}", out.stderr);
+    }
+}
+
+#[test]
+fn workers_exchange_messages_on_both_backends() {
+    let ws = Workspace::new("workers");
+    ws.write(
+        "задача.yopta",
+        "родитель.приСообщении(йопта(с) {\n  родитель.отправить({ сумма: с.числа.свернуть((а, б) => а + б, 0), рег: с.рег });\n  родитель.закрыть();\n});\n",
+    );
+    let prog = ws.write(
+        "main.yopta",
+        "гыы в = захуярить Воркер(\"./задача.yopta\");\nв.приСообщении(йопта(о) { сказать(о.сумма, о.рег); });\nв.приВыходе(йопта(код) { сказать(\"выход\", код); });\nв.отправить({ числа: [1, 2, 3], рег: /а+/g });\n",
+    );
+
+    for backend in [None, Some("--vm")] {
+        let mut args: Vec<&str> = backend.into_iter().collect();
+        args.push(prog.to_str().unwrap());
+        let out = run(&args, "");
+
+        assert_eq!(out.code, 0, "stderr: {}", out.stderr);
+        assert_eq!(out.stdout, "6 /а+/g\nвыход 0\n", "{backend:?}");
+    }
+}
+
+#[test]
+fn workers_keep_the_parents_eval_policy_and_stay_out_of_traces() {
+    let ws = Workspace::new("workers-policy");
+    ws.write("задача.yopta", "родитель.отправить(ебал(\"1 + 2\"));\n");
+    let prog = ws.write(
+        "main.yopta",
+        "гыы в = захуярить Воркер(\"./задача.yopta\");\nв.приСообщении(йопта(о) { сказать(\"итог\", о); });\n",
+    );
+
+    for backend in [&["--no-eval"][..], &["--no-eval", "--vm"][..]] {
+        let mut args = backend.to_vec();
+        args.push(prog.to_str().unwrap());
+        let out = run(&args, "");
+
+        assert!(!out.stdout.contains("итог"), "{backend:?}: {}", out.stdout);
+        assert!(out.stderr.contains("'ебал' запрещён"), "{backend:?}: {}", out.stderr);
+    }
+
+    let trace = ws.path("run.ypsr");
+    let out = run(&["--record", trace.to_str().unwrap(), prog.to_str().unwrap()], "");
+    assert_eq!(out.code, 1);
+    assert!(out.stderr.contains("недоступен при записи"), "stderr: {}", out.stderr);
+}
@@ -57,7 +57,7 @@ fn transpile_reports_unsupported_global_with_position() {
     assert!(!output.status.success());
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("Матан"), "stderr: {stderr}");
-    assert!(stderr.contains(":1:9:"), "stderr: {stderr}");
+    assert!(stderr.contains(":1:9\n"), "stderr: {stderr}");
     let _ = fs::remove_file(&path);
 }
 
@@ -80,7 +80,7 @@ fn transpile_reports_parse_errors() {
     assert!(!output.status.success());
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("Неожиданный токен"), "stderr: {stderr}");
-    assert!(stderr.contains(":1:9:"), "stderr: {stderr}");
+    assert!(stderr.contains(":1:9\n"), "stderr: {stderr}");
     let _ = fs::remove_file(&path);
 }
 
@@ -146,7 +146,7 @@ fn transpile_reports_date_used_as_a_namespace() {
     assert!(!output.status.success());
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("Дата"), "stderr: {stderr}");
-    assert!(stderr.contains(":1:9:"), "stderr: {stderr}");
+    assert!(stderr.contains(":1:9\n"), "stderr: {stderr}");
     let _ = fs::remove_file(&path);
 }
 
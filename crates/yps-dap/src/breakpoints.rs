@@ -2,7 +2,8 @@ use std::collections::BTreeSet;
 
 use yps_lexer::SourceFile;
 use yps_parser::ast::{
-    Block, ClassMember, ExportKind, Expr, Literal, ObjectEntry, Param, Pattern, Program, PropKey, Stmt, TemplatePart,
+    Block, ClassMember, ExportKind, Expr, Literal, MatchPattern, ObjectEntry, Param, Pattern, Program, PropKey, Stmt,
+    TemplatePart,
 };
 
 /// Every line on which a statement starts. Only statements are stepping/breakpoint targets,
@@ -90,12 +91,19 @@ impl Collector<'_> {
             Stmt::Expr { expr, .. } | Stmt::Throw { value: expr, .. } | Stmt::Using { init: expr, .. } => {
                 self.expr(expr);
             }
+            Stmt::EnumDecl { members, .. } => {
+                for init in members.iter().filter_map(|m| m.init.as_ref()) {
+                    self.expr(init);
+                }
+            }
             Stmt::Block(block) => self.block(block),
             Stmt::Empty { .. }
             | Stmt::Break { .. }
             | Stmt::Continue { .. }
             | Stmt::Debugger { .. }
-            | Stmt::Import { .. } => {}
+            | Stmt::Import { .. }
+            | Stmt::TypeAlias { .. }
+            | Stmt::Error { .. } => {}
             Stmt::If { condition, then_branch, else_branch, .. } => {
                 self.expr(condition);
                 self.stmt(then_branch);
@@ -242,7 +250,12 @@ impl Collector<'_> {
 
     fn expr(&mut self, expr: &Expr) {
         match expr {
-            Expr::Identifier(_) | Expr::This { .. } | Expr::Super { .. } => {}
+            Expr::Identifier(_)
+            | Expr::This { .. }
+            | Expr::Super { .. }
+            | Expr::NewTarget { .. }
+            | Expr::ImportMeta { .. }
+            | Expr::Error { .. } => {}
             Expr::Literal(literal) => self.literal(literal),
             Expr::Unary { expr, .. }
             | Expr::Postfix { expr, .. }
@@ -290,6 +303,26 @@ impl Collector<'_> {
                 }
             }
             Expr::Await { argument: inner, .. } | Expr::DynamicImport { source: inner, .. } => self.expr(inner),
+            Expr::Match { subject, arms, .. } => {
+                self.expr(subject);
+                for arm in arms {
+                    match &arm.pattern {
+                        MatchPattern::Bind(pattern) => self.pattern(pattern),
+                        MatchPattern::Instance { class, pattern, .. } => {
+                            self.expr(class);
+                            if let Some(pattern) = pattern {
+                                self.pattern(pattern);
+                            }
+                        }
+                        MatchPattern::Value(value) => self.expr(value),
+                        MatchPattern::Wildcard { .. } => {}
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.expr(guard);
+                    }
+                    self.expr(&arm.body);
+                }
+            }
         }
     }
 }
@@ -1,16 +1,23 @@
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use yps_interpreter::{DebugAction, DebugEvent, DebugHook, Interpreter};
+use yps_interpreter::trace::{self, Trace};
+use yps_interpreter::{DebugAction, DebugEvent, DebugHook, Interpreter, OutputSink, StdoutSink};
 use yps_lexer::{Lexer, SourceFile};
 use yps_parser::Parser;
 
 pub const MODULE_FRAME: &str = "(модуль)";
 
+/// Statements between two checkpoints of a live run to begin with; the session widens the
+/// spacing as the run grows.
+pub const CHECKPOINT_INTERVAL: usize = 64;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StopReason {
     Entry,
@@ -48,6 +55,8 @@ pub struct DapVar {
 #[derive(Debug, Clone)]
 pub struct StopInfo {
     pub reason: StopReason,
+    /// Index of the statement execution this stop happened at, counted from program start.
+    pub step: usize,
     pub frames: Vec<DapFrame>,
     pub locals: Vec<DapVar>,
 }
@@ -55,7 +64,11 @@ pub struct StopInfo {
 #[derive(Debug)]
 pub enum DebugMsg {
     Stopped(Box<StopInfo>),
-    Exited { error: Option<String> },
+    /// The run has reached this step for the first time; the session may park a replica there.
+    Checkpoint(usize),
+    Exited {
+        error: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -64,33 +77,94 @@ pub enum ResumeCmd {
     Next,
     StepIn,
     StepOut,
+    /// Run silently to a later step (or stay at this one) and stop there.
+    Rewind(Rewind),
     Terminate,
 }
 
+/// What the session has learned about the run so far, shared across restarts: the recorded
+/// nondeterministic inputs and the source line of every statement executed, in order.
+#[derive(Debug, Default)]
+pub struct History {
+    pub trace: Trace,
+    pub lines: Vec<usize>,
+}
+
+impl History {
+    /// Last step before `before` that executed one of `lines`.
+    #[must_use]
+    pub fn last_hit(&self, before: usize, lines: &HashSet<usize>) -> Option<usize> {
+        self.lines.iter().take(before).rposition(|line| lines.contains(line))
+    }
+}
+
+/// Target for `stepBack`/`reverseContinue`, and the step a checkpoint parks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rewind {
+    pub step: usize,
+    pub reason: StopReason,
+}
+
 pub struct DebuggeeHandle {
     pub resume_tx: Sender<ResumeCmd>,
     pub pause_flag: Arc<AtomicBool>,
+    /// Whether the run's messages reach the session; off for runs abandoned by a rewind, and for
+    /// checkpoints until they are sent their first `ResumeCmd::Rewind`.
+    pub attached: Arc<AtomicBool>,
 }
 
 struct DapHook {
     source: SourceFile,
     breakpoints: Arc<Mutex<HashSet<usize>>>,
     pause_flag: Arc<AtomicBool>,
+    attached: Arc<AtomicBool>,
     notify: Box<dyn Fn(DebugMsg) + Send>,
     resume_rx: Receiver<ResumeCmd>,
     entry_pending: bool,
+    history: Arc<Mutex<History>>,
+    rewind: Option<Rewind>,
+    step: usize,
+    /// Whether the current statement runs for the first time in the session.
+    fresh: Rc<Cell<bool>>,
 }
 
 impl DebugHook for DapHook {
     fn on_statement(&mut self, event: DebugEvent<'_>) -> Option<DebugAction> {
         let (line, column) = self.source.position(event.span.start);
+        let step = self.step;
+        self.step += 1;
+        let fresh = match self.history.lock() {
+            Ok(mut history) if history.lines.len() == step => {
+                history.lines.push(line);
+                if step.is_multiple_of(CHECKPOINT_INTERVAL) {
+                    history.trace = trace::snapshot();
+                }
+                true
+            }
+            _ => false,
+        };
+        self.fresh.set(fresh);
+        if fresh && step.is_multiple_of(CHECKPOINT_INTERVAL) {
+            (self.notify)(DebugMsg::Checkpoint(step));
+        }
+
+        let rewound = match self.rewind {
+            Some(rewind) if step < rewind.step => return None,
+            Some(rewind) => {
+                self.rewind = None;
+                Some(rewind.reason)
+            }
+            None => None,
+        };
         let paused = self.pause_flag.swap(false, Ordering::SeqCst);
         let hit_breakpoint = self.breakpoints.lock().is_ok_and(|set| set.contains(&line));
-        if !paused && !hit_breakpoint && !event.step_complete {
+        if rewound.is_none() && !paused && !hit_breakpoint && !event.step_complete {
             return None;
         }
 
-        let reason = if std::mem::take(&mut self.entry_pending) {
+        let reason = if let Some(reason) = rewound {
+            reason
+        } else if std::mem::take(&mut self.entry_pending) {
             StopReason::Entry
         } else if paused {
             StopReason::Pause
@@ -100,8 +174,10 @@ impl DebugHook for DapHook {
             StopReason::Step
         };
 
+        self.save_trace();
         let info = StopInfo {
             reason,
+            step,
             frames: self.build_frames(&event, line, column),
             locals: event
                 .interp
@@ -114,20 +190,50 @@ impl DebugHook for DapHook {
                 })
                 .collect(),
         };
-        (self.notify)(DebugMsg::Stopped(Box::new(info)));
-
-        match self.resume_rx.recv() {
-            Ok(ResumeCmd::Continue) => Some(DebugAction::Continue),
-            Ok(ResumeCmd::Next) => Some(DebugAction::StepOver),
-            Ok(ResumeCmd::StepIn) => Some(DebugAction::StepIn),
-            Ok(ResumeCmd::StepOut) => Some(DebugAction::StepOut),
-            // A dropped channel means the adapter is gone: stop the debuggee rather than hang.
-            Ok(ResumeCmd::Terminate) | Err(_) => Some(DebugAction::Terminate),
+        (self.notify)(DebugMsg::Stopped(Box::new(info.clone())));
+
+        loop {
+            let action = match self.resume_rx.recv() {
+                Ok(ResumeCmd::Continue) => DebugAction::Continue,
+                Ok(ResumeCmd::Next) => DebugAction::StepOver,
+                Ok(ResumeCmd::StepIn) => DebugAction::StepIn,
+                Ok(ResumeCmd::StepOut) => DebugAction::StepOut,
+                Ok(ResumeCmd::Rewind(rewind)) => {
+                    // Attaching only here keeps the stop a checkpoint parks at to itself, however
+                    // late it gets there. It was parked with the inputs known back then; the run
+                    // it now stands in for may have read more since.
+                    self.attached.store(true, Ordering::SeqCst);
+                    if let Ok(history) = self.history.lock() {
+                        trace::extend_replay(history.trace.clone());
+                    }
+                    if rewind.step <= step {
+                        self.save_trace();
+                        let info = StopInfo { reason: rewind.reason, ..info.clone() };
+                        (self.notify)(DebugMsg::Stopped(Box::new(info)));
+                        continue;
+                    }
+                    self.rewind = Some(rewind);
+                    DebugAction::Continue
+                }
+                // A dropped channel means the adapter is gone: stop the debuggee rather than hang.
+                Ok(ResumeCmd::Terminate) | Err(_) => DebugAction::Terminate,
+            };
+            return Some(action);
         }
     }
 }
 
 impl DapHook {
+    /// Keeps the session's copy of the inputs current; a detached replica replays an older copy
+    /// and must not overwrite it.
+    fn save_trace(&self) {
+        if self.attached.load(Ordering::SeqCst)
+            && let Ok(mut history) = self.history.lock()
+        {
+            history.trace = trace::snapshot();
+        }
+    }
+
     /// The interpreter records a call-site span per frame, so DAP frame `n` shows the name of
     /// the function being executed and the position of the call that led into frame `n - 1`.
     fn build_frames(&self, event: &DebugEvent<'_>, line: usize, column: usize) -> Vec<DapFrame> {
@@ -143,10 +249,35 @@ impl DapHook {
     }
 }
 
+/// Stdout that stays quiet for statements whose output the client has already seen, so replays
+/// (checkpoints and the runs they turn into) do not print anything a second time.
+struct ReplaySink {
+    fresh: Rc<Cell<bool>>,
+}
+
+impl OutputSink for ReplaySink {
+    fn write_line(&mut self, line: &str) {
+        if self.fresh.get() {
+            StdoutSink.write_line(line);
+        }
+    }
+
+    fn write_error_line(&mut self, line: &str) {
+        if self.fresh.get() {
+            StdoutSink.write_error_line(line);
+        }
+    }
+}
+
 pub struct LaunchConfig {
     pub program: PathBuf,
     pub stop_on_entry: bool,
     pub breakpoints: Arc<Mutex<HashSet<usize>>>,
+    pub history: Arc<Mutex<History>>,
+    /// Replay the recorded inputs and stop at this step without stopping anywhere before it.
+    pub rewind: Option<Rewind>,
+    /// False for a checkpoint: it stays silent until it is rewound to a target.
+    pub attached: bool,
 }
 
 /// Runs the program on its own thread. The interpreter is built inside that thread because it
@@ -155,6 +286,16 @@ pub fn spawn<N: Fn(DebugMsg) + Clone + Send + 'static>(config: LaunchConfig, not
     let (resume_tx, resume_rx) = std::sync::mpsc::channel();
     let pause_flag = Arc::new(AtomicBool::new(false));
     let hook_pause_flag = Arc::clone(&pause_flag);
+    let attached = Arc::new(AtomicBool::new(config.attached));
+    let hook_attached = Arc::clone(&attached);
+    let notify = {
+        let attached = Arc::clone(&attached);
+        move |msg| {
+            if attached.load(Ordering::SeqCst) {
+                notify(msg);
+            }
+        }
+    };
 
     thread::spawn(move || {
         let hook_notify = notify.clone();
@@ -179,19 +320,33 @@ pub fn spawn<N: Fn(DebugMsg) + Clone + Send + 'static>(config: LaunchConfig, not
             return;
         }
 
+        // Every run records its inputs; a rewound run replays them so it retraces the same path.
+        match (config.rewind, config.history.lock()) {
+            (Some(_), Ok(history)) => trace::start_replay(history.trace.clone()),
+            _ => trace::start_recording(),
+        }
+        let fresh = Rc::new(Cell::new(false));
+
         let mut interp = Interpreter::new();
         if let Some(parent) = config.program.parent() {
             interp.set_base_path(parent.to_path_buf());
         }
+        interp.set_output_sink(Box::new(ReplaySink { fresh: Rc::clone(&fresh) }));
+        let stop_on_entry = config.stop_on_entry && config.rewind.is_none();
         interp.set_debug_hook(Box::new(DapHook {
             source,
             breakpoints: config.breakpoints,
             pause_flag: hook_pause_flag,
+            attached: hook_attached,
             notify: Box::new(hook_notify),
             resume_rx,
-            entry_pending: config.stop_on_entry,
+            entry_pending: stop_on_entry,
+            history: config.history,
+            rewind: config.rewind,
+            step: 0,
+            fresh,
         }));
-        if !config.stop_on_entry {
+        if !stop_on_entry {
             interp.set_debug_resume(DebugAction::Continue);
         }
 
@@ -203,5 +358,5 @@ pub fn spawn<N: Fn(DebugMsg) + Clone + Send + 'static>(config: LaunchConfig, not
         notify(DebugMsg::Exited { error });
     });
 
-    DebuggeeHandle { resume_tx, pause_flag }
+    DebuggeeHandle { resume_tx, pause_flag, attached }
 }
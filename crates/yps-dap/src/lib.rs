@@ -13,6 +13,17 @@
 //! - A module-level variable that shadows a builtin name (e.g. `гыы длина = 99;`) is invisible in
 //!   `variables`: top-level script bindings share the same `EnvFrame` as builtins, so the debugger
 //!   filters out anything present in the pre-run global snapshot to avoid listing every builtin.
+//! - `stepBack` and `reverseContinue` resume from a checkpoint. The interpreter heap is a graph of
+//!   `Rc`s and a stop sits in the middle of the Rust call stack, so neither can be copied; a
+//!   checkpoint is instead a replica run parked on its own thread, which replays the recorded
+//!   inputs (`yps_interpreter::trace`) up to a step the live run has passed. Replicas are started
+//!   in the background every few dozen statements, so going back replays at most one interval.
+//!   A replica makes no `ФС.*` or `Сеть.*` calls of its own: their results come back from the
+//!   trace, and output the client has already seen is not printed again. Host effects outside the
+//!   trace, such as `Процесс.сменитьПапку` or the file `Процесс.снимокКучи` writes, do happen again
+//!   in each replica.
+//! - Since every session records its inputs, a program that creates a `Воркер` fails there: worker
+//!   messages are not part of a trace.
 //! - VS Code editor wiring (`contributes.debuggers`, `launch.json`) is a separate follow-up and
 //!   is not part of this crate.
 
@@ -10,11 +10,15 @@ use yps_lexer::{Lexer, SourceFile};
 use yps_parser::Parser;
 
 use crate::breakpoints;
-use crate::debuggee::{self, DebugMsg, DebuggeeHandle, LaunchConfig, ResumeCmd, StopInfo};
+use crate::debuggee::{self, DebugMsg, DebuggeeHandle, History, LaunchConfig, ResumeCmd, Rewind, StopInfo, StopReason};
 
 pub const THREAD_ID: i64 = 1;
 const LOCALS_SCOPE_BASE: i64 = 1000;
 const NOT_PAUSED: &str = "Программа не находится на паузе";
+const NO_PROGRAM: &str = "Программа не задана: сначала пришлите 'launch'";
+/// Parked replicas kept at once; past this, every other one is dropped and the spacing between
+/// new ones doubles, so a long run keeps an even grid of checkpoints.
+const MAX_CHECKPOINTS: usize = 32;
 
 /// What arrives on the adapter's single event queue.
 pub enum Incoming {
@@ -23,6 +27,13 @@ pub enum Incoming {
     Debug(DebugMsg),
 }
 
+/// A replica of the run, replaying the recorded inputs and parked at `step` until a rewind
+/// needs it.
+struct Checkpoint {
+    step: usize,
+    handle: DebuggeeHandle,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum State {
     Configuring,
@@ -41,6 +52,11 @@ pub struct Session {
     statement_lines: BTreeSet<usize>,
     breakpoints: Arc<Mutex<HashSet<usize>>>,
     debuggee: Option<DebuggeeHandle>,
+    /// Ordered by step.
+    checkpoints: Vec<Checkpoint>,
+    /// Steps between checkpoints, a multiple of `debuggee::CHECKPOINT_INTERVAL`.
+    checkpoint_every: usize,
+    history: Arc<Mutex<History>>,
     stopped: Option<StopInfo>,
     deferred: VecDeque<Value>,
     events_tx: Sender<Incoming>,
@@ -59,6 +75,9 @@ impl Session {
             statement_lines: BTreeSet::new(),
             breakpoints: Arc::new(Mutex::new(HashSet::new())),
             debuggee: None,
+            checkpoints: Vec::new(),
+            checkpoint_every: debuggee::CHECKPOINT_INTERVAL,
+            history: Arc::new(Mutex::new(History::default())),
             stopped: None,
             deferred: VecDeque::new(),
             events_tx,
@@ -132,9 +151,16 @@ impl Session {
                     }),
                 ));
             }
+            DebugMsg::Checkpoint(step) => {
+                if step.is_multiple_of(self.checkpoint_every) {
+                    self.spawn_checkpoint(step);
+                }
+                return out;
+            }
             DebugMsg::Exited { error } => {
                 self.state = State::Exited;
                 self.stopped = None;
+                self.drop_checkpoints();
                 let exit_code = if let Some(error) = error {
                     out.push(self.event("output", json!({ "category": "stderr", "output": format!("{error}\n") })));
                     1
@@ -175,6 +201,7 @@ impl Session {
                         "supportsEvaluateForHovers": false,
                         "supportsFunctionBreakpoints": false,
                         "supportsConditionalBreakpoints": false,
+                        "supportsStepBack": true,
                     }),
                 );
                 let initialized = self.event("initialized", json!({}));
@@ -202,6 +229,8 @@ impl Session {
             "next" => self.resume(request, ResumeCmd::Next, json!({})),
             "stepIn" => self.resume(request, ResumeCmd::StepIn, json!({})),
             "stepOut" => self.resume(request, ResumeCmd::StepOut, json!({})),
+            "stepBack" => self.handle_step_back(request),
+            "reverseContinue" => self.handle_reverse_continue(request),
             "pause" => {
                 if let Some(handle) = &self.debuggee {
                     handle.pause_flag.store(true, Ordering::SeqCst);
@@ -289,18 +318,102 @@ impl Session {
             return Vec::new();
         }
         let Some(program) = self.program.clone() else {
-            return vec![self.failure(request, "Программа не задана: сначала пришлите 'launch'")];
+            return vec![self.failure(request, NO_PROGRAM)];
         };
+        self.debuggee = Some(self.launch(program, None, true));
+        self.state = State::Running;
+        Vec::new()
+    }
+
+    fn launch(&self, program: PathBuf, rewind: Option<Rewind>, attached: bool) -> DebuggeeHandle {
         let tx = self.events_tx.clone();
-        let handle = debuggee::spawn(
-            LaunchConfig { program, stop_on_entry: self.stop_on_entry, breakpoints: Arc::clone(&self.breakpoints) },
+        debuggee::spawn(
+            LaunchConfig {
+                program,
+                stop_on_entry: self.stop_on_entry,
+                breakpoints: Arc::clone(&self.breakpoints),
+                history: Arc::clone(&self.history),
+                rewind,
+                attached,
+            },
             move |msg| {
                 let _ = tx.send(Incoming::Debug(msg));
             },
-        );
+        )
+    }
+
+    fn spawn_checkpoint(&mut self, step: usize) {
+        let Some(program) = self.program.clone() else {
+            return;
+        };
+        let handle = self.launch(program, Some(Rewind { step, reason: StopReason::Step }), false);
+        let index = self.checkpoints.partition_point(|c| c.step < step);
+        self.checkpoints.insert(index, Checkpoint { step, handle });
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            self.checkpoint_every *= 2;
+            let all = std::mem::take(&mut self.checkpoints);
+            for checkpoint in all {
+                if checkpoint.step.is_multiple_of(self.checkpoint_every) {
+                    self.checkpoints.push(checkpoint);
+                } else {
+                    let _ = checkpoint.handle.resume_tx.send(ResumeCmd::Terminate);
+                }
+            }
+        }
+    }
+
+    fn drop_checkpoints(&mut self) {
+        for checkpoint in self.checkpoints.drain(..) {
+            let _ = checkpoint.handle.resume_tx.send(ResumeCmd::Terminate);
+        }
+    }
+
+    fn handle_step_back(&mut self, request: &Value) -> Vec<Value> {
+        let Some(step) = self.stopped.as_ref().map(|info| info.step) else {
+            return vec![self.failure(request, NOT_PAUSED)];
+        };
+        let reason = if step == 0 { StopReason::Entry } else { StopReason::Step };
+        self.rewind(request, Rewind { step: step.saturating_sub(1), reason })
+    }
+
+    fn handle_reverse_continue(&mut self, request: &Value) -> Vec<Value> {
+        let Some(step) = self.stopped.as_ref().map(|info| info.step) else {
+            return vec![self.failure(request, NOT_PAUSED)];
+        };
+        let lines = self.breakpoints.lock().map(|set| set.clone()).unwrap_or_default();
+        let hit = self.history.lock().ok().and_then(|history| history.last_hit(step, &lines));
+        let rewind = match hit {
+            Some(step) => Rewind { step, reason: StopReason::Breakpoint },
+            None => Rewind { step: 0, reason: StopReason::Entry },
+        };
+        self.rewind(request, rewind)
+    }
+
+    /// Going backwards takes over the closest checkpoint at or before the target, which replays
+    /// at most `checkpoint_every` statements to get there; a fresh replica is parked in its place.
+    /// Without one the program is replayed from the start.
+    fn rewind(&mut self, request: &Value, rewind: Rewind) -> Vec<Value> {
+        let Some(program) = self.program.clone() else {
+            return vec![self.failure(request, NO_PROGRAM)];
+        };
+        if let Some(handle) = self.debuggee.take() {
+            handle.attached.store(false, Ordering::SeqCst);
+            let _ = handle.resume_tx.send(ResumeCmd::Terminate);
+        }
+        let index = self.checkpoints.partition_point(|c| c.step <= rewind.step);
+        let handle = match index.checked_sub(1) {
+            Some(index) => {
+                let checkpoint = self.checkpoints.remove(index);
+                let _ = checkpoint.handle.resume_tx.send(ResumeCmd::Rewind(rewind));
+                self.spawn_checkpoint(checkpoint.step);
+                checkpoint.handle
+            }
+            None => self.launch(program, Some(rewind), true),
+        };
         self.debuggee = Some(handle);
+        self.stopped = None;
         self.state = State::Running;
-        Vec::new()
+        vec![self.response(request, json!({}))]
     }
 
     fn resume(&mut self, request: &Value, cmd: ResumeCmd, body: Value) -> Vec<Value> {
@@ -324,6 +437,7 @@ impl Session {
             handle.pause_flag.store(true, Ordering::SeqCst);
             let _ = handle.resume_tx.send(ResumeCmd::Terminate);
         }
+        self.drop_checkpoints();
         self.stopped = None;
         self.state = State::Exited;
         self.deferred.clear();
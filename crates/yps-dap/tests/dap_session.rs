@@ -219,6 +219,83 @@ fn breakpoint_on_a_blank_line_snaps_to_the_next_statement() {
     client.wait_event("terminated");
 }
 
+#[test]
+fn step_back_returns_to_the_previous_statement() {
+    let mut client = Client::start();
+    client.handshake("call.yopta", true, &[]);
+    client.wait_event("stopped");
+
+    client.call("next", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    client.call("next", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(client.frames()[0]["line"], 6);
+
+    client.call("stepBack", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "step");
+    let frames = client.frames();
+    assert_eq!(frames.len(), 2, "шаг назад с 6-й строки попадает в последний оператор удвоить");
+    assert_eq!(frames[0]["name"], "удвоить");
+
+    client.call("disconnect", json!({}));
+}
+
+#[test]
+fn reverse_continue_goes_back_to_the_previous_breakpoint_hit() {
+    let mut client = Client::start();
+    client.handshake("loop.yopta", false, &[3]);
+    client.wait_event("stopped");
+    client.call("continue", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(local_value(&client.locals(1), "i").as_deref(), Some("1"));
+
+    client.call("reverseContinue", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "breakpoint");
+    assert_eq!(local_value(&client.locals(1), "i").as_deref(), Some("0"));
+
+    client.call("reverseContinue", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["body"]["reason"], "entry", "раньше точек останова нет — возврат в начало");
+
+    client.call("continue", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(local_value(&client.locals(1), "i").as_deref(), Some("0"));
+    client.call("disconnect", json!({}));
+}
+
+#[test]
+fn going_back_past_several_checkpoints_restores_the_state_there() {
+    let mut client = Client::start();
+    client.handshake("long_loop.yopta", false, &[5]);
+    client.wait_event("stopped");
+    assert_eq!(local_value(&client.locals(1), "сумма").as_deref(), Some("4950"));
+
+    client.call("stepBack", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(client.frames()[0]["line"], 3);
+    let locals = client.locals(1);
+    assert_eq!(local_value(&locals, "i").as_deref(), Some("99"));
+    assert_eq!(local_value(&locals, "сумма").as_deref(), Some("4851"));
+
+    client.call(
+        "setBreakpoints",
+        json!({ "source": { "path": fixture_path("long_loop.yopta") }, "breakpoints": [{ "line": 3 }] }),
+    );
+    client.call("reverseContinue", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(local_value(&client.locals(1), "i").as_deref(), Some("98"));
+    client.call("reverseContinue", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(local_value(&client.locals(1), "i").as_deref(), Some("97"));
+
+    client.call("continue", json!({ "threadId": 1 }));
+    client.wait_event("stopped");
+    assert_eq!(local_value(&client.locals(1), "i").as_deref(), Some("98"));
+    client.call("disconnect", json!({}));
+}
+
 #[test]
 fn unknown_command_is_rejected() {
     let mut client = Client::start();
@@ -1,6 +1,6 @@
 use yps_parser::{
-    Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, ObjectEntry, ObjectPatternProp, Param, Pattern, Program,
-    PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi,
+    Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, MatchArm, MatchPattern, ObjectEntry, ObjectPatternProp,
+    Param, Pattern, Program, PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi, TypeExpr, TypeField,
 };
 
 pub fn programs_equivalent(a: &Program, b: &Program) -> bool {
@@ -30,9 +30,9 @@ fn opt_block_eq(a: &Option<Block>, b: &Option<Block>) -> bool {
 fn stmt_eq(a: &Stmt, b: &Stmt) -> bool {
     match (a, b) {
         (
-            Stmt::VarDecl { pattern: p1, init: i1, is_const: c1, .. },
-            Stmt::VarDecl { pattern: p2, init: i2, is_const: c2, .. },
-        ) => c1 == c2 && pattern_eq(p1, p2) && expr_eq(i1, i2),
+            Stmt::VarDecl { pattern: p1, ty: t1, init: i1, is_const: c1, .. },
+            Stmt::VarDecl { pattern: p2, ty: t2, init: i2, is_const: c2, .. },
+        ) => c1 == c2 && pattern_eq(p1, p2) && opt_type_eq(t1.as_ref(), t2.as_ref()) && expr_eq(i1, i2),
         (
             Stmt::Using { name: n1, init: i1, is_await: a1, .. },
             Stmt::Using { name: n2, init: i2, is_await: a2, .. },
@@ -72,9 +72,20 @@ fn stmt_eq(a: &Stmt, b: &Stmt) -> bool {
             l1.name == l2.name && stmt_eq(b1, b2)
         }
         (
-            Stmt::FunctionDecl { name: n1, params: p1, body: bd1, is_generator: g1, is_async: a1, .. },
-            Stmt::FunctionDecl { name: n2, params: p2, body: bd2, is_generator: g2, is_async: a2, .. },
-        ) => n1.name == n2.name && g1 == g2 && a1 == a2 && params_eq(p1, p2) && block_eq(bd1, bd2),
+            Stmt::FunctionDecl {
+                name: n1, params: p1, return_type: r1, body: bd1, is_generator: g1, is_async: a1, ..
+            },
+            Stmt::FunctionDecl {
+                name: n2, params: p2, return_type: r2, body: bd2, is_generator: g2, is_async: a2, ..
+            },
+        ) => {
+            n1.name == n2.name
+                && g1 == g2
+                && a1 == a2
+                && params_eq(p1, p2)
+                && opt_type_eq(r1.as_ref(), r2.as_ref())
+                && block_eq(bd1, bd2)
+        }
         (Stmt::Return { value: v1, .. }, Stmt::Return { value: v2, .. }) => opt_expr_eq(v1, v2),
         (Stmt::Throw { value: v1, .. }, Stmt::Throw { value: v2, .. }) => expr_eq(v1, v2),
         (
@@ -89,7 +100,16 @@ fn stmt_eq(a: &Stmt, b: &Stmt) -> bool {
             Stmt::ClassDecl { name: n1, super_class: s1, members: m1, decorators: d1, .. },
             Stmt::ClassDecl { name: n2, super_class: s2, members: m2, decorators: d2, .. },
         ) => n1.name == n2.name && opt_expr_eq(s1, s2) && exprs_eq(d1, d2) && members_eq(m1, m2),
+        (Stmt::EnumDecl { name: n1, members: m1, .. }, Stmt::EnumDecl { name: n2, members: m2, .. }) => {
+            n1.name == n2.name
+                && m1.len() == m2.len()
+                && m1.iter().zip(m2.iter()).all(|(x, y)| x.name.name == y.name.name && opt_expr_eq(&x.init, &y.init))
+        }
         (Stmt::Debugger { .. }, Stmt::Debugger { .. }) => true,
+        (
+            Stmt::TypeAlias { name: n1, ty: t1, is_interface: i1, .. },
+            Stmt::TypeAlias { name: n2, ty: t2, is_interface: i2, .. },
+        ) => n1.name == n2.name && i1 == i2 && type_eq(t1, t2),
         (
             Stmt::Import { specifiers: s1, source: src1, attributes: a1, .. },
             Stmt::Import { specifiers: s2, source: src2, attributes: a2, .. },
@@ -123,16 +143,45 @@ fn member_eq(a: &ClassMember, b: &ClassMember) -> bool {
         ) => params_eq(p1, p2) && block_eq(b1, b2),
         (
             ClassMember::Method {
-                name: n1, params: p1, body: b1, is_static: s1, is_private: pr1, decorators: d1, ..
+                name: n1,
+                params: p1,
+                return_type: r1,
+                body: b1,
+                is_static: s1,
+                is_private: pr1,
+                decorators: d1,
+                ..
             },
             ClassMember::Method {
-                name: n2, params: p2, body: b2, is_static: s2, is_private: pr2, decorators: d2, ..
+                name: n2,
+                params: p2,
+                return_type: r2,
+                body: b2,
+                is_static: s2,
+                is_private: pr2,
+                decorators: d2,
+                ..
             },
-        ) => n1.name == n2.name && s1 == s2 && pr1 == pr2 && exprs_eq(d1, d2) && params_eq(p1, p2) && block_eq(b1, b2),
+        ) => {
+            n1.name == n2.name
+                && s1 == s2
+                && pr1 == pr2
+                && exprs_eq(d1, d2)
+                && params_eq(p1, p2)
+                && opt_type_eq(r1.as_ref(), r2.as_ref())
+                && block_eq(b1, b2)
+        }
         (
-            ClassMember::Field { name: n1, init: i1, is_static: s1, is_private: pr1, decorators: d1, .. },
-            ClassMember::Field { name: n2, init: i2, is_static: s2, is_private: pr2, decorators: d2, .. },
-        ) => n1.name == n2.name && s1 == s2 && pr1 == pr2 && exprs_eq(d1, d2) && opt_expr_eq(i1, i2),
+            ClassMember::Field { name: n1, ty: t1, init: i1, is_static: s1, is_private: pr1, decorators: d1, .. },
+            ClassMember::Field { name: n2, ty: t2, init: i2, is_static: s2, is_private: pr2, decorators: d2, .. },
+        ) => {
+            n1.name == n2.name
+                && s1 == s2
+                && pr1 == pr2
+                && exprs_eq(d1, d2)
+                && opt_type_eq(t1.as_ref(), t2.as_ref())
+                && opt_expr_eq(i1, i2)
+        }
         (
             ClassMember::Getter { name: n1, body: b1, is_static: s1, is_private: pr1, decorators: d1, .. },
             ClassMember::Getter { name: n2, body: b2, is_static: s2, is_private: pr2, decorators: d2, .. },
@@ -217,7 +266,45 @@ fn params_eq(a: &[Param], b: &[Param]) -> bool {
 }
 
 fn param_eq(a: &Param, b: &Param) -> bool {
-    a.name.name == b.name.name && a.is_rest == b.is_rest && opt_expr_eq(&a.default, &b.default)
+    a.name.name == b.name.name
+        && a.is_rest == b.is_rest
+        && opt_type_eq(a.ty.as_ref(), b.ty.as_ref())
+        && opt_expr_eq(&a.default, &b.default)
+}
+
+fn opt_type_eq(a: Option<&TypeExpr>, b: Option<&TypeExpr>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(x), Some(y)) => type_eq(x, y),
+        _ => false,
+    }
+}
+
+fn type_eq(a: &TypeExpr, b: &TypeExpr) -> bool {
+    match (a, b) {
+        (TypeExpr::Named { name: n1, args: a1, .. }, TypeExpr::Named { name: n2, args: a2, .. }) => {
+            n1.name == n2.name && types_eq(a1, a2)
+        }
+        (TypeExpr::Literal { value: v1, .. }, TypeExpr::Literal { value: v2, .. }) => literal_eq(v1, v2),
+        (TypeExpr::Array { element: e1, .. }, TypeExpr::Array { element: e2, .. }) => type_eq(e1, e2),
+        (TypeExpr::Union { types: t1, .. }, TypeExpr::Union { types: t2, .. }) => types_eq(t1, t2),
+        (TypeExpr::Object { fields: f1, .. }, TypeExpr::Object { fields: f2, .. }) => type_fields_eq(f1, f2),
+        (TypeExpr::Function { params: p1, ret: r1, .. }, TypeExpr::Function { params: p2, ret: r2, .. }) => {
+            type_fields_eq(p1, p2) && type_eq(r1, r2)
+        }
+        _ => false,
+    }
+}
+
+fn types_eq(a: &[TypeExpr], b: &[TypeExpr]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| type_eq(x, y))
+}
+
+fn type_fields_eq(a: &[TypeField], b: &[TypeField]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.name.name == y.name.name && x.optional == y.optional && type_eq(&x.ty, &y.ty))
 }
 
 fn opt_ident_eq(a: &Option<yps_parser::Identifier>, b: &Option<yps_parser::Identifier>) -> bool {
@@ -255,6 +342,8 @@ fn expr_eq(a: &Expr, b: &Expr) -> bool {
         (Expr::Literal(l1), Expr::Literal(l2)) => literal_eq(l1, l2),
         (Expr::This { .. }, Expr::This { .. }) => true,
         (Expr::Super { .. }, Expr::Super { .. }) => true,
+        (Expr::NewTarget { .. }, Expr::NewTarget { .. }) => true,
+        (Expr::ImportMeta { .. }, Expr::ImportMeta { .. }) => true,
         (Expr::Unary { op: o1, expr: e1, .. }, Expr::Unary { op: o2, expr: e2, .. }) => o1 == o2 && expr_eq(e1, e2),
         (Expr::Postfix { op: o1, expr: e1, .. }, Expr::Postfix { op: o2, expr: e2, .. }) => o1 == o2 && expr_eq(e1, e2),
         (Expr::Binary { op: o1, lhs: l1, rhs: r1, .. }, Expr::Binary { op: o2, lhs: l2, rhs: r2, .. }) => {
@@ -290,13 +379,24 @@ fn expr_eq(a: &Expr, b: &Expr) -> bool {
             Expr::OptionalMember { object: o2, property: p2, .. },
         ) => expr_eq(o1, o2) && p1.name == p2.name,
         (
-            Expr::ArrowFunction { params: p1, body: b1, is_async: a1, .. },
-            Expr::ArrowFunction { params: p2, body: b2, is_async: a2, .. },
-        ) => a1 == a2 && params_eq(p1, p2) && block_eq(b1, b2),
+            Expr::ArrowFunction { params: p1, return_type: r1, body: b1, is_async: a1, .. },
+            Expr::ArrowFunction { params: p2, return_type: r2, body: b2, is_async: a2, .. },
+        ) => a1 == a2 && params_eq(p1, p2) && opt_type_eq(r1.as_deref(), r2.as_deref()) && block_eq(b1, b2),
         (
-            Expr::FunctionExpr { name: n1, params: p1, body: b1, is_generator: g1, is_async: a1, .. },
-            Expr::FunctionExpr { name: n2, params: p2, body: b2, is_generator: g2, is_async: a2, .. },
-        ) => g1 == g2 && a1 == a2 && opt_ident_eq(n1, n2) && params_eq(p1, p2) && block_eq(b1, b2),
+            Expr::FunctionExpr {
+                name: n1, params: p1, return_type: r1, body: b1, is_generator: g1, is_async: a1, ..
+            },
+            Expr::FunctionExpr {
+                name: n2, params: p2, return_type: r2, body: b2, is_generator: g2, is_async: a2, ..
+            },
+        ) => {
+            g1 == g2
+                && a1 == a2
+                && opt_ident_eq(n1, n2)
+                && params_eq(p1, p2)
+                && opt_type_eq(r1.as_deref(), r2.as_deref())
+                && block_eq(b1, b2)
+        }
         (Expr::Spread { expr: e1, .. }, Expr::Spread { expr: e2, .. }) => expr_eq(e1, e2),
         (Expr::Yield { argument: a1, delegate: d1, .. }, Expr::Yield { argument: a2, delegate: d2, .. }) => {
             d1 == d2 && opt_boxed_expr_eq(a1, a2)
@@ -308,10 +408,27 @@ fn expr_eq(a: &Expr, b: &Expr) -> bool {
             Expr::TaggedTemplate { tag: t1, quasis: q1, expressions: e1, .. },
             Expr::TaggedTemplate { tag: t2, quasis: q2, expressions: e2, .. },
         ) => expr_eq(t1, t2) && quasis_eq(q1, q2) && exprs_eq(e1, e2),
+        (Expr::Match { subject: s1, arms: a1, .. }, Expr::Match { subject: s2, arms: a2, .. }) => {
+            expr_eq(s1, s2) && a1.len() == a2.len() && a1.iter().zip(a2.iter()).all(|(x, y)| match_arm_eq(x, y))
+        }
         _ => false,
     }
 }
 
+fn match_arm_eq(a: &MatchArm, b: &MatchArm) -> bool {
+    let patterns_eq = match (&a.pattern, &b.pattern) {
+        (MatchPattern::Wildcard { .. }, MatchPattern::Wildcard { .. }) => true,
+        (MatchPattern::Bind(p1), MatchPattern::Bind(p2)) => pattern_eq(p1, p2),
+        (MatchPattern::Value(e1), MatchPattern::Value(e2)) => expr_eq(e1, e2),
+        (
+            MatchPattern::Instance { class: c1, pattern: p1, .. },
+            MatchPattern::Instance { class: c2, pattern: p2, .. },
+        ) => expr_eq(c1, c2) && opt_pattern_eq(p1, p2),
+        _ => false,
+    };
+    patterns_eq && opt_expr_eq(&a.guard, &b.guard) && expr_eq(&a.body, &b.body)
+}
+
 fn opt_boxed_expr_eq(a: &Option<Box<Expr>>, b: &Option<Box<Expr>>) -> bool {
     match (a, b) {
         (None, None) => true,
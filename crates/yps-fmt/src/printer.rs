@@ -3,8 +3,9 @@ use yps_parser::{
     binary_is_right_assoc, binary_precedence,
 };
 use yps_parser::{
-    BinaryOp, Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, ObjectEntry, ObjectPatternProp, Param,
-    Pattern, PostfixOp, Program, PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi, UnaryOp,
+    BinaryOp, Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, MatchArm, MatchPattern, ObjectEntry,
+    ObjectPatternProp, Param, Pattern, PostfixOp, Program, PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi,
+    TypeExpr, TypeField, UnaryOp,
 };
 
 use crate::comments::CommentMap;
@@ -205,10 +206,11 @@ impl Printer<'_> {
         self.write("}");
     }
 
-    fn print_var_decl(&mut self, pattern: &Pattern, init: &Expr, is_const: bool) {
+    fn print_var_decl(&mut self, pattern: &Pattern, ty: Option<&TypeExpr>, init: &Expr, is_const: bool) {
         self.write(if is_const { "ясенХуй" } else { "гыы" });
         self.write(" ");
         self.print_pattern(pattern);
+        self.print_annotation(ty);
         self.write(" = ");
         self.print_expr(init, 0);
         self.write(";");
@@ -223,8 +225,8 @@ impl Printer<'_> {
     fn print_stmt(&mut self, stmt: &Stmt) {
         self.record_mapping(stmt.span().start);
         match stmt {
-            Stmt::VarDecl { pattern, init, is_const, .. } => {
-                self.print_var_decl(pattern, init, *is_const);
+            Stmt::VarDecl { pattern, ty, init, is_const, .. } => {
+                self.print_var_decl(pattern, ty.as_ref(), init, *is_const);
             }
             Stmt::Using { name, init, is_await, .. } => {
                 self.write("юзай ");
@@ -344,7 +346,7 @@ impl Printer<'_> {
                 self.write(": ");
                 self.print_stmt(body);
             }
-            Stmt::FunctionDecl { name, params, body, is_generator, is_async, .. } => {
+            Stmt::FunctionDecl { name, params, return_type, body, is_generator, is_async, .. } => {
                 if *is_async {
                     self.write("ассо ");
                 }
@@ -352,6 +354,7 @@ impl Printer<'_> {
                 self.write(" ");
                 self.write(&name.name);
                 self.print_params(params);
+                self.print_annotation(return_type.as_ref());
                 self.write(" ");
                 self.print_block(body);
             }
@@ -437,9 +440,63 @@ impl Printer<'_> {
                 self.indent();
                 self.write("}");
             }
+            Stmt::EnumDecl { name, members, .. } => {
+                self.write("еээ ");
+                self.write(&name.name);
+                self.write(" {");
+                if members.is_empty() {
+                    self.write("}");
+                    return;
+                }
+                self.newline();
+                self.depth += 1;
+                for member in members {
+                    self.indent();
+                    self.write(&member.name.name);
+                    if let Some(init) = &member.init {
+                        self.write(" = ");
+                        self.print_expr(init, 0);
+                    }
+                    self.write(",");
+                    self.newline();
+                }
+                self.depth -= 1;
+                self.indent();
+                self.write("}");
+            }
             Stmt::Debugger { .. } => {
                 self.write("логопед;");
             }
+            Stmt::TypeAlias { name, ty, is_interface, .. } => {
+                if *is_interface && let TypeExpr::Object { fields, .. } = ty {
+                    self.write("хуёво ");
+                    self.write(&name.name);
+                    self.write(" {");
+                    if fields.is_empty() {
+                        self.write("}");
+                        return;
+                    }
+                    self.newline();
+                    self.depth += 1;
+                    for field in fields {
+                        self.indent();
+                        self.print_type_field(field);
+                        self.write(";");
+                        self.newline();
+                    }
+                    self.depth -= 1;
+                    self.indent();
+                    self.write("}");
+                } else {
+                    self.write("тип ");
+                    self.write(&name.name);
+                    self.write(" = ");
+                    self.print_type(ty);
+                    self.write(";");
+                }
+            }
+            // Programs with syntax errors are never formatted; the marker only keeps printing total.
+            Stmt::Error { .. } => self.write("/* ошибка разбора */"),
             Stmt::Import { specifiers, source, attributes, .. } => {
                 self.write("спиздить ");
                 self.print_import_specifiers(specifiers);
@@ -502,8 +559,8 @@ impl Printer<'_> {
 
     fn print_for_init(&mut self, stmt: &Stmt) {
         match stmt {
-            Stmt::VarDecl { pattern, init, is_const, .. } => {
-                self.print_var_decl(pattern, init, *is_const);
+            Stmt::VarDecl { pattern, ty, init, is_const, .. } => {
+                self.print_var_decl(pattern, ty.as_ref(), init, *is_const);
             }
             Stmt::Expr { expr, .. } => {
                 self.print_expr(expr, 0);
@@ -534,21 +591,23 @@ impl Printer<'_> {
                 self.print_block(body);
                 self.newline();
             }
-            ClassMember::Method { name, params, body, is_static, decorators, .. } => {
+            ClassMember::Method { name, params, return_type, body, is_static, decorators, .. } => {
                 self.print_member_decorators(decorators);
                 self.indent();
                 self.print_static_prefix(*is_static);
                 self.write(&name.name);
                 self.print_params(params);
+                self.print_annotation(return_type.as_ref());
                 self.write(" ");
                 self.print_block(body);
                 self.newline();
             }
-            ClassMember::Field { name, init, is_static, decorators, .. } => {
+            ClassMember::Field { name, ty, init, is_static, decorators, .. } => {
                 self.print_member_decorators(decorators);
                 self.indent();
                 self.print_static_prefix(*is_static);
                 self.write(&name.name);
+                self.print_annotation(ty.as_ref());
                 if let Some(init) = init {
                     self.write(" = ");
                     self.print_expr(init, 0);
@@ -636,12 +695,86 @@ impl Printer<'_> {
             self.write("...");
         }
         self.write(&param.name.name);
+        self.print_annotation(param.ty.as_ref());
         if let Some(default) = &param.default {
             self.write(" = ");
             self.print_expr(default, 0);
         }
     }
 
+    fn print_annotation(&mut self, ty: Option<&TypeExpr>) {
+        if let Some(ty) = ty {
+            self.write(": ");
+            self.print_type(ty);
+        }
+    }
+
+    fn print_type(&mut self, ty: &TypeExpr) {
+        match ty {
+            TypeExpr::Named { name, args, .. } => {
+                self.write(&name.name);
+                if !args.is_empty() {
+                    self.write("<");
+                    for (i, arg) in args.iter().enumerate() {
+                        if i > 0 {
+                            self.write(", ");
+                        }
+                        self.print_type(arg);
+                    }
+                    self.write(">");
+                }
+            }
+            TypeExpr::Literal { value, .. } => self.print_literal(value),
+            TypeExpr::Array { element, .. } => {
+                let wrap = matches!(**element, TypeExpr::Union { .. } | TypeExpr::Function { .. });
+                if wrap {
+                    self.write("(");
+                }
+                self.print_type(element);
+                if wrap {
+                    self.write(")");
+                }
+                self.write("[]");
+            }
+            TypeExpr::Union { types, .. } => {
+                for (i, ty) in types.iter().enumerate() {
+                    if i > 0 {
+                        self.write(" | ");
+                    }
+                    let wrap = matches!(ty, TypeExpr::Function { .. });
+                    if wrap {
+                        self.write("(");
+                    }
+                    self.print_type(ty);
+                    if wrap {
+                        self.write(")");
+                    }
+                }
+            }
+            TypeExpr::Object { fields, .. } => {
+                self.print_delimited("{", "}", true, fields.len(), |p, i| {
+                    p.print_type_field(&fields[i]);
+                });
+            }
+            TypeExpr::Function { params, ret, .. } => {
+                self.print_delimited("(", ")", false, params.len(), |p, i| {
+                    p.print_type_field(&params[i]);
+                });
+                self.write(" => ");
+                self.print_type(ret);
+            }
+        }
+    }
+
+    fn print_type_field(&mut self, field: &TypeField) {
+        self.write(&field.name.name);
+        if field.optional {
+            self.write("?");
+        }
+        self.write(": ");
+        self.print_type(&field.ty);
+    }
+
     fn print_pattern(&mut self, pattern: &Pattern) {
         match pattern {
             Pattern::Identifier(id) => self.write(&id.name),
@@ -716,6 +849,9 @@ impl Printer<'_> {
             Expr::Literal(literal) => self.print_literal(literal),
             Expr::This { .. } => self.write("тырыпыры"),
             Expr::Super { .. } => self.write("яга"),
+            Expr::NewTarget { .. } => self.write("захуярить.цель"),
+            Expr::ImportMeta { .. } => self.write("спиздить.мета"),
+            Expr::Error { .. } => self.write("/* ошибка разбора */"),
             Expr::Unary { op, expr: operand, .. } => {
                 let wrap = UNARY_PRECEDENCE < parent_prec;
                 if wrap {
@@ -828,7 +964,7 @@ impl Printer<'_> {
                 self.write("?.");
                 self.write(&property.name);
             }
-            Expr::ArrowFunction { params, body, is_async, .. } => {
+            Expr::ArrowFunction { params, return_type, body, is_async, .. } => {
                 let wrap = parent_prec > ASSIGN_PRECEDENCE;
                 if wrap {
                     self.write("(");
@@ -837,13 +973,14 @@ impl Printer<'_> {
                     self.write("ассо ");
                 }
                 self.print_params(params);
+                self.print_annotation(return_type.as_deref());
                 self.write(" => ");
                 self.print_arrow_body(body);
                 if wrap {
                     self.write(")");
                 }
             }
-            Expr::FunctionExpr { name, params, body, is_generator, is_async, .. } => {
+            Expr::FunctionExpr { name, params, return_type, body, is_generator, is_async, .. } => {
                 if *is_async {
                     self.write("ассо ");
                 }
@@ -853,6 +990,7 @@ impl Printer<'_> {
                     self.write(&name.name);
                 }
                 self.print_params(params);
+                self.print_annotation(return_type.as_deref());
                 self.write(" ");
                 self.print_block(body);
             }
@@ -897,7 +1035,56 @@ impl Printer<'_> {
                 self.print_expr(tag, CALL_PRECEDENCE);
                 self.print_tagged_template(quasis, expressions);
             }
+            Expr::Match { subject, arms, .. } => {
+                self.write("базарпо (");
+                self.print_expr(subject, 0);
+                self.write(") {");
+                if arms.is_empty() {
+                    self.write("}");
+                    return;
+                }
+                self.newline();
+                self.depth += 1;
+                for arm in arms {
+                    self.indent();
+                    self.print_match_arm(arm);
+                    self.write(",");
+                    self.newline();
+                }
+                self.depth -= 1;
+                self.indent();
+                self.write("}");
+            }
+        }
+    }
+
+    fn print_match_arm(&mut self, arm: &MatchArm) {
+        match &arm.pattern {
+            MatchPattern::Wildcard { .. } => self.write("нуичо"),
+            MatchPattern::Bind(pattern) => {
+                self.write("тема ");
+                self.print_pattern(pattern);
+            }
+            MatchPattern::Value(value) => {
+                self.write("тема ");
+                self.print_expr(value, 0);
+            }
+            MatchPattern::Instance { class, pattern, .. } => {
+                self.write("тема шкура ");
+                self.print_expr(class, CALL_PRECEDENCE);
+                if let Some(pattern) = pattern {
+                    self.write(" ");
+                    self.print_pattern(pattern);
+                }
+            }
+        }
+        if let Some(guard) = &arm.guard {
+            self.write(" вилкойвглаз (");
+            self.print_expr(guard, 0);
+            self.write(")");
         }
+        self.write(" => ");
+        self.print_expr(&arm.body, ASSIGN_PRECEDENCE);
     }
 
     fn print_args(&mut self, args: &[Expr]) {
@@ -1026,7 +1213,7 @@ impl Printer<'_> {
 }
 
 fn is_top_level_decl(stmt: &Stmt) -> bool {
-    matches!(stmt, Stmt::FunctionDecl { .. } | Stmt::ClassDecl { .. })
+    matches!(stmt, Stmt::FunctionDecl { .. } | Stmt::ClassDecl { .. } | Stmt::EnumDecl { .. })
 }
 
 fn is_ident_named(pattern: &Pattern, name: &str) -> bool {
@@ -1036,7 +1223,10 @@ fn is_ident_named(pattern: &Pattern, name: &str) -> bool {
 fn stmt_expr_needs_parens(expr: &Expr) -> bool {
     matches!(
         starting_expr(expr),
-        Expr::Literal(Literal::Object { .. }) | Expr::ArrowFunction { .. } | Expr::FunctionExpr { .. }
+        Expr::Literal(Literal::Object { .. })
+            | Expr::ArrowFunction { .. }
+            | Expr::FunctionExpr { .. }
+            | Expr::Match { .. }
     )
 }
 
@@ -761,6 +761,25 @@ mod suite {
         assert_eq!(out, parse_and_format(&out), "идемпотентность нарушена для статического блока");
     }
 
+    #[test]
+    fn enum_prints_one_member_per_line() {
+        let out = parse_and_format("еээ Цвет жЫ Красный, Зелёный=5, Синий }");
+        assert_eq!(out, "еээ Цвет {\n    Красный,\n    Зелёный = 5,\n    Синий,\n}\n");
+        assert_eq!(out, parse_and_format(&out), "идемпотентность нарушена для еээ");
+        assert_eq!(parse_and_format("еээ Пусто {}"), "еээ Пусто {}\n");
+    }
+
+    #[test]
+    fn match_prints_one_arm_per_line() {
+        let out =
+            parse_and_format("гыы р = базарпо(х){тема [а,б] вилкойвглаз(а>б)=>а,тема шкура Точка {х}=>х,нуичо=>0};");
+        assert_eq!(
+            out,
+            "гыы р = базарпо (х) {\n    тема [а, б] вилкойвглаз (а > б) => а,\n    тема шкура Точка { х } => х,\n    нуичо => 0,\n};\n"
+        );
+        assert_eq!(out, parse_and_format(&out), "идемпотентность нарушена для базарпо");
+    }
+
     #[test]
     fn using_sync_stays_without_await() {
         let src = "{\n    юзай р = получить();\n}\n";
@@ -802,4 +821,41 @@ mod suite {
         assert!(out.contains("= ассо пиздюли()"), "форматтер должен печатать 'ассо пиздюли()' выражение: {out:?}");
         assert_eq!(out, parse_and_format(&out), "идемпотентность нарушена для асинхронного генератора");
     }
+
+    #[test]
+    fn type_annotations_round_trip() {
+        let src = concat!(
+            "тип Ид = число | строка[] | ((а: число) => куку);\n",
+            "хуёво Точка {\n",
+            "    x: число;\n",
+            "    y?: Карта<строка, Помойка<число>>;\n",
+            "}\n\n",
+            "йопта ф(а: (число | строка)[], б: число = 1): Точка {\n",
+            "    гыы в: число = б;\n",
+            "    отвечаю { x: в };\n",
+            "}\n\n",
+            "клёво К {\n",
+            "    поле: строка = \"\";\n\n",
+            "    метод(): куку {}\n",
+            "}\n\n",
+            "гыы г = (х: число): число => х;\n",
+        );
+        let out = parse_and_format(src);
+        assert_eq!(out, src, "форматтер должен сохранять аннотации типов");
+        assert!(programs_equivalent_str(src, &out), "round-trip нарушен для аннотаций типов: {out:?}");
+    }
+
+    #[test]
+    fn type_annotation_mismatch_is_not_equivalent() {
+        assert!(!programs_equivalent_str("гыы а: число = 1;", "гыы а: строка = 1;"));
+        assert!(!programs_equivalent_str("тип А = число;", "тип А = строка;"));
+    }
+
+    #[test]
+    fn meta_properties_round_trip() {
+        let src = "сказать(спиздить.мета.путь);\n\nклёво А {\n    А() {\n        сказать(захуярить.цель);\n    }\n}\n";
+        let out = parse_and_format(src);
+        assert_eq!(out, src);
+        assert!(programs_equivalent_str(src, &out));
+    }
 }
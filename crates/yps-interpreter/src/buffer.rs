@@ -0,0 +1,207 @@
+//! Byte storage behind `ОбластьБайтов` and `ОбщийБуфер`. A local buffer lives on one thread; a
+//! shared one is seen by every `Воркер` it was sent to, and `Атомики` operate on it under its lock.
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub enum SharedBuffer {
+    Local(Rc<RefCell<Vec<u8>>>),
+    Shared(Arc<SharedMemory>),
+}
+
+impl SharedBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        SharedBuffer::Local(Rc::new(RefCell::new(bytes)))
+    }
+
+    pub fn new_shared(bytes: Vec<u8>) -> Self {
+        SharedBuffer::Shared(Arc::new(SharedMemory::new(bytes)))
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self, SharedBuffer::Shared(_))
+    }
+
+    /// Identity of the storage, for `===`, hashing and the structured clone's memo.
+    pub fn id(&self) -> usize {
+        match self {
+            SharedBuffer::Local(rc) => Rc::as_ptr(rc) as *const () as usize,
+            SharedBuffer::Shared(arc) => Arc::as_ptr(arc) as *const () as usize,
+        }
+    }
+
+    pub fn ptr_eq(&self, other: &SharedBuffer) -> bool {
+        self.id() == other.id()
+    }
+
+    pub fn borrow(&self) -> Bytes<'_> {
+        match self {
+            SharedBuffer::Local(rc) => Bytes::Local(rc.borrow()),
+            SharedBuffer::Shared(memory) => Bytes::Shared(memory.read()),
+        }
+    }
+
+    pub fn borrow_mut(&self) -> BytesMut<'_> {
+        match self {
+            SharedBuffer::Local(rc) => BytesMut::Local(rc.borrow_mut()),
+            SharedBuffer::Shared(memory) => BytesMut::Shared(memory.write()),
+        }
+    }
+}
+
+pub enum Bytes<'a> {
+    Local(Ref<'a, Vec<u8>>),
+    Shared(RwLockReadGuard<'a, Vec<u8>>),
+}
+
+impl Deref for Bytes<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        match self {
+            Bytes::Local(bytes) => bytes,
+            Bytes::Shared(bytes) => bytes,
+        }
+    }
+}
+
+pub enum BytesMut<'a> {
+    Local(RefMut<'a, Vec<u8>>),
+    Shared(RwLockWriteGuard<'a, Vec<u8>>),
+}
+
+impl Deref for BytesMut<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        match self {
+            BytesMut::Local(bytes) => bytes,
+            BytesMut::Shared(bytes) => bytes,
+        }
+    }
+}
+
+impl DerefMut for BytesMut<'_> {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        match self {
+            BytesMut::Local(bytes) => bytes,
+            BytesMut::Shared(bytes) => bytes,
+        }
+    }
+}
+
+/// How `Атомики.ждать` ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    Woken,
+    NotEqual,
+    TimedOut,
+    Interrupted,
+}
+
+impl WaitOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WaitOutcome::Woken => "готово",
+            WaitOutcome::NotEqual => "неравно",
+            WaitOutcome::TimedOut | WaitOutcome::Interrupted => "истекло",
+        }
+    }
+}
+
+/// How often a waiting thread looks at its interrupt flag.
+const WAIT_SLICE: Duration = Duration::from_millis(20);
+
+/// Bytes of an `ОбщийБуфер`. Every access takes the lock, so all of them, atomic or not, happen
+/// in one total order.
+pub struct SharedMemory {
+    bytes: RwLock<Vec<u8>>,
+    waiters: Mutex<WaitList>,
+    woken: Condvar,
+}
+
+#[derive(Default)]
+struct WaitList {
+    /// Byte index and ticket of every sleeping `ждать`, oldest first.
+    queue: Vec<(usize, u64)>,
+    next_ticket: u64,
+}
+
+impl SharedMemory {
+    fn new(bytes: Vec<u8>) -> Self {
+        SharedMemory { bytes: RwLock::new(bytes), waiters: Mutex::default(), woken: Condvar::new() }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Vec<u8>> {
+        self.bytes.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<u8>> {
+        self.bytes.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, WaitList> {
+        self.waiters.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Sleeps on `index` while `holds` is true of the bytes, until `wake` picks this waiter, the
+    /// timeout passes or `interrupted` turns true. The check and the sleep happen under the wait
+    /// list's lock, so a store followed by `wake` cannot slip in between.
+    pub fn wait(
+        &self,
+        index: usize,
+        holds: impl Fn(&[u8]) -> bool,
+        timeout: Option<Duration>,
+        interrupted: impl Fn() -> bool,
+    ) -> WaitOutcome {
+        let mut list = self.waiters();
+        if !holds(&self.read()) {
+            return WaitOutcome::NotEqual;
+        }
+        let ticket = list.next_ticket;
+        list.next_ticket += 1;
+        list.queue.push((index, ticket));
+        let deadline = timeout.map(|t| Instant::now() + t);
+        loop {
+            if !list.queue.iter().any(|&(_, t)| t == ticket) {
+                return WaitOutcome::Woken;
+            }
+            let mut slice = WAIT_SLICE;
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    list.queue.retain(|&(_, t)| t != ticket);
+                    return WaitOutcome::TimedOut;
+                }
+                slice = slice.min(deadline - now);
+            }
+            if interrupted() {
+                list.queue.retain(|&(_, t)| t != ticket);
+                return WaitOutcome::Interrupted;
+            }
+            list = self.woken.wait_timeout(list, slice).unwrap_or_else(PoisonError::into_inner).0;
+        }
+    }
+
+    /// Wakes up to `count` waiters sleeping on `index`, oldest first; returns how many woke.
+    pub fn wake(&self, index: usize, count: usize) -> usize {
+        let mut list = self.waiters();
+        let mut woken = 0;
+        list.queue.retain(|&(i, _)| {
+            if i == index && woken < count {
+                woken += 1;
+                false
+            } else {
+                true
+            }
+        });
+        if woken > 0 {
+            self.woken.notify_all();
+        }
+        woken
+    }
+}
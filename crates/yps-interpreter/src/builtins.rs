@@ -34,6 +34,9 @@ pub fn call_builtin_with_sink(
         "Дата" => crate::stdlib::date::construct(args, span),
         "прочестьСтроку" => stdlib::stdio::read_line(span),
         "прочестьВсё" => stdlib::stdio::read_all(span),
+        "структурныйКлон" => structured_clone(args, span),
+        "вБазу64" => stdlib::encoding::base64_builtin(args, span),
+        "изБазы64" => stdlib::encoding::from_base64_builtin(args, span),
         "длина" => {
             if args.len() != 1 {
                 return Err(RuntimeError::new("'длина' принимает 1 аргумент", span));
@@ -41,7 +44,7 @@ pub fn call_builtin_with_sink(
             match &args[0] {
                 Value::String(s) => Ok(Value::Number(s.chars().count() as f64)),
                 Value::Array(a) => Ok(Value::Number(a.borrow().len() as f64)),
-                Value::TypedArray(ta) => Ok(Value::Number(ta.length as f64)),
+                Value::TypedArray(ta) => Ok(Value::Number(ta.live_length() as f64)),
                 Value::Object(map) => {
                     let len_val = {
                         let m = map.borrow();
@@ -136,6 +139,11 @@ pub fn builtin_names() -> &'static [&'static str] {
         "сОчередить",
         "прочестьСтроку",
         "прочестьВсё",
+        "ебал",
+        "Функция",
+        "структурныйКлон",
+        "вБазу64",
+        "изБазы64",
         "сказать.ошибка",
         "сказать.предупреждение",
         "сказать.инфо",
@@ -146,6 +154,14 @@ pub fn builtin_names() -> &'static [&'static str] {
     ]
 }
 
+/// A deep copy with shared and cyclic references kept, made the way a `Воркер` message is.
+fn structured_clone(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let value = args.into_iter().next().unwrap_or(Value::Undefined);
+    crate::worker::Message::new(&value, &[])
+        .map(crate::worker::Message::into_value)
+        .map_err(|m| RuntimeError::new(format!("'структурныйКлон': {m}"), span))
+}
+
 fn construct_regexp(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     let mut it = args.into_iter();
     let first = match it.next() {
@@ -0,0 +1,744 @@
+//! Line, branch and function coverage shared by both backends (`yps --coverage`).
+//!
+//! Backends only report "this span was executed" together with a site that tells apart several
+//! recordings of one span within a single evaluation (two VM instructions carrying the same span,
+//! or an `отвечаю` statement and its own expression). A span's count is the maximum over its sites.
+//! The report is derived afterwards from the AST of every registered file: a statement ran as
+//! many times as its most executed expression, and branch counts compare the arms of
+//! `вилкойвглаз`, `?:`, `&&`/`||`/`??` and `базарпо` cases. Like `trace`, the state is thread-local because the
+//! VM reaches the stdlib through a fresh interpreter per bridged call.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use yps_lexer::{SourceFile, Span};
+use yps_parser::{
+    BinaryOp, Block, ClassMember, ExportKind, Expr, Literal, MatchPattern, ObjectEntry, Param, Program, PropKey, Stmt,
+};
+
+use crate::profile::ANONYMOUS_FRAME;
+
+pub const DEFAULT_DIR: &str = "coverage";
+pub const LCOV_FILE: &str = "lcov.info";
+pub const HTML_DIR: &str = "html";
+
+/// Site used by the tree-walker for statements and for expressions respectively.
+pub const STMT_SITE: (usize, usize) = (0, 0);
+pub const EXPR_SITE: (usize, usize) = (0, 1);
+
+type SpanKey = (usize, usize);
+
+struct FileData {
+    source: SourceFile,
+    program: Program,
+    hits: HashMap<(SpanKey, (usize, usize)), u64>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Keyed by `sources` id.
+    files: HashMap<usize, FileData>,
+    output: Option<PathBuf>,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+pub fn start() {
+    STATE.with(|s| *s.borrow_mut() = Some(State::default()));
+}
+
+#[must_use]
+pub fn is_active() -> bool {
+    STATE.with(|s| s.borrow().is_some())
+}
+
+/// Directory that `save` merges the report into; kept here so that `Процесс.выход` can flush it.
+pub fn set_output_dir(dir: PathBuf) {
+    STATE.with(|s| {
+        if let Some(state) = s.borrow_mut().as_mut() {
+            state.output = Some(dir);
+        }
+    });
+}
+
+/// Id under which spans of `source` are reported, the one `sources` knows it by; registering a
+/// file twice returns the same id.
+pub fn register_file(source: &SourceFile, program: &Program) -> usize {
+    let id = crate::sources::register(source);
+    STATE.with(|s| {
+        if let Some(state) = s.borrow_mut().as_mut() {
+            state.files.entry(id).or_insert_with(|| FileData {
+                source: source.clone(),
+                program: program.clone(),
+                hits: HashMap::new(),
+            });
+        }
+    });
+    id
+}
+
+pub fn hit(file: usize, span: Span, site: (usize, usize)) {
+    STATE.with(|s| {
+        if let Some(data) = s.borrow_mut().as_mut().and_then(|state| state.files.get_mut(&file)) {
+            *data.hits.entry(((span.start, span.end), site)).or_default() += 1;
+        }
+    });
+}
+
+/// Stops collecting and builds the report for every registered file.
+pub fn finish() -> Report {
+    let state = STATE.with(|s| s.borrow_mut().take()).unwrap_or_default();
+    let mut files: Vec<FileCoverage> = state.files.values().map(analyze).collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Report { files }
+}
+
+/// Merges the report into `lcov.info` of the output directory and rewrites the HTML next to it.
+pub fn save() -> io::Result<()> {
+    let Some(dir) = STATE.with(|s| s.borrow().as_ref().and_then(|state| state.output.clone())) else {
+        return Ok(());
+    };
+    let mut report = finish();
+    fs::create_dir_all(&dir)?;
+    let lcov = dir.join(LCOV_FILE);
+    if let Ok(text) = fs::read_to_string(&lcov) {
+        let previous = Report::parse_lcov(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        report.merge(previous);
+    }
+    fs::write(&lcov, report.to_lcov())?;
+    report.write_html(&dir.join(HTML_DIR))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionHit {
+    pub name: String,
+    pub line: usize,
+    pub hits: u64,
+}
+
+/// One arm of a decision; `taken` is `None` when the decision itself never ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchHit {
+    pub line: usize,
+    pub block: usize,
+    pub branch: usize,
+    pub taken: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    pub path: String,
+    pub functions: Vec<FunctionHit>,
+    pub branches: Vec<BranchHit>,
+    pub lines: BTreeMap<usize, u64>,
+}
+
+impl FileCoverage {
+    fn merge(&mut self, other: Self) {
+        for function in other.functions {
+            match self.functions.iter_mut().find(|f| f.name == function.name) {
+                Some(existing) => existing.hits += function.hits,
+                None => self.functions.push(function),
+            }
+        }
+        for branch in other.branches {
+            let same = |b: &&mut BranchHit| (b.line, b.block, b.branch) == (branch.line, branch.block, branch.branch);
+            match self.branches.iter_mut().find(same) {
+                Some(existing) => {
+                    existing.taken = match (existing.taken, branch.taken) {
+                        (None, None) => None,
+                        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+                    };
+                }
+                None => self.branches.push(branch),
+            }
+        }
+        for (line, hits) in other.lines {
+            *self.lines.entry(line).or_default() += hits;
+        }
+    }
+
+    fn totals(&self) -> [(usize, usize); 3] {
+        [
+            (self.lines.values().filter(|h| **h > 0).count(), self.lines.len()),
+            (self.functions.iter().filter(|f| f.hits > 0).count(), self.functions.len()),
+            (self.branches.iter().filter(|b| b.taken.is_some_and(|t| t > 0)).count(), self.branches.len()),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub files: Vec<FileCoverage>,
+}
+
+impl Report {
+    pub fn parse_lcov(text: &str) -> Result<Self, String> {
+        let mut files = Vec::new();
+        let mut current: Option<FileCoverage> = None;
+        for (index, line) in text.lines().enumerate() {
+            let bad = || format!("{LCOV_FILE}:{}: не разобрать строку '{line}'", index + 1);
+            let (tag, rest) = line.split_once(':').unwrap_or((line, ""));
+            if tag == "SF" {
+                current = Some(FileCoverage { path: rest.to_string(), ..FileCoverage::default() });
+                continue;
+            }
+            if tag == "end_of_record" {
+                files.extend(current.take());
+                continue;
+            }
+            let Some(file) = current.as_mut() else { continue };
+            match tag {
+                "FN" => {
+                    let (line, name) = rest.split_once(',').ok_or_else(bad)?;
+                    let line = line.parse().map_err(|_| bad())?;
+                    file.functions.push(FunctionHit { name: name.to_string(), line, hits: 0 });
+                }
+                "FNDA" => {
+                    let (hits, name) = rest.split_once(',').ok_or_else(bad)?;
+                    let hits: u64 = hits.parse().map_err(|_| bad())?;
+                    if let Some(function) = file.functions.iter_mut().find(|f| f.name == name) {
+                        function.hits += hits;
+                    }
+                }
+                "BRDA" => {
+                    let parts: Vec<&str> = rest.split(',').collect();
+                    let [line, block, branch, taken] = parts[..] else { return Err(bad()) };
+                    file.branches.push(BranchHit {
+                        line: line.parse().map_err(|_| bad())?,
+                        block: block.parse().map_err(|_| bad())?,
+                        branch: branch.parse().map_err(|_| bad())?,
+                        taken: if taken == "-" { None } else { Some(taken.parse().map_err(|_| bad())?) },
+                    });
+                }
+                "DA" => {
+                    let mut parts = rest.split(',');
+                    let line = parts.next().and_then(|l| l.parse().ok()).ok_or_else(bad)?;
+                    let hits: u64 = parts.next().and_then(|h| h.parse().ok()).ok_or_else(bad)?;
+                    *file.lines.entry(line).or_default() += hits;
+                }
+                _ => {}
+            }
+        }
+        Ok(Self { files })
+    }
+
+    /// One-line totals over all files, e.g. for the end of `yps test --coverage`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut sums = [(0, 0); 3];
+        for file in &self.files {
+            for (sum, (hit, total)) in sums.iter_mut().zip(file.totals()) {
+                sum.0 += hit;
+                sum.1 += total;
+            }
+        }
+        let [lines, functions, branches] = sums.map(|(hit, total)| percent(hit, total));
+        format!("строки {lines}, функции {functions}, ветки {branches}")
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        for file in other.files {
+            match self.files.iter_mut().find(|f| f.path == file.path) {
+                Some(existing) => existing.merge(file),
+                None => self.files.push(file),
+            }
+        }
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    #[must_use]
+    pub fn to_lcov(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            let [lines, functions, branches] = file.totals();
+            let _ = writeln!(out, "TN:\nSF:{}", file.path);
+            for function in &file.functions {
+                let _ = writeln!(out, "FN:{},{}", function.line, function.name);
+            }
+            for function in &file.functions {
+                let _ = writeln!(out, "FNDA:{},{}", function.hits, function.name);
+            }
+            let _ = writeln!(out, "FNF:{}\nFNH:{}", functions.1, functions.0);
+            for branch in &file.branches {
+                let taken = branch.taken.map_or_else(|| "-".to_string(), |t| t.to_string());
+                let _ = writeln!(out, "BRDA:{},{},{},{taken}", branch.line, branch.block, branch.branch);
+            }
+            let _ = writeln!(out, "BRF:{}\nBRH:{}", branches.1, branches.0);
+            for (line, hits) in &file.lines {
+                let _ = writeln!(out, "DA:{line},{hits}");
+            }
+            let _ = writeln!(out, "LF:{}\nLH:{}\nend_of_record", lines.1, lines.0);
+        }
+        out
+    }
+
+    /// `index.html` with per-file totals plus one annotated source page per file.
+    pub fn write_html(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut index = page_header("Покрытие кода");
+        index.push_str("<table><tr><th>Файл</th><th>Строки</th><th>Функции</th><th>Ветки</th></tr>\n");
+        for (i, file) in self.files.iter().enumerate() {
+            let page = format!("file{i}.html");
+            let _ = write!(index, "<tr><td><a href=\"{page}\">{}</a></td>", escape(&file.path));
+            for (hit, total) in file.totals() {
+                let _ = write!(index, "<td>{}</td>", percent(hit, total));
+            }
+            index.push_str("</tr>\n");
+            fs::write(dir.join(page), file_page(file))?;
+        }
+        index.push_str("</table></body></html>\n");
+        fs::write(dir.join("index.html"), index)
+    }
+}
+
+fn percent(hit: usize, total: usize) -> String {
+    if total == 0 {
+        return "—".to_string();
+    }
+    format!("{:.1}% ({hit}/{total})", hit as f64 * 100.0 / total as f64)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn page_header(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>\
+         body{{font-family:sans-serif}}table{{border-collapse:collapse}}td,th{{padding:0 .6em;text-align:left}}\
+         pre{{margin:0}}.hit{{background:#dfd}}.miss{{background:#fdd}}.part{{background:#ffd}}\
+         .n{{color:#888;text-align:right}}</style></head><body><h1>{}</h1>\n",
+        escape(title),
+        escape(title)
+    )
+}
+
+fn file_page(file: &FileCoverage) -> String {
+    let mut out = page_header(&file.path);
+    out.push_str("<p><a href=\"index.html\">← все файлы</a></p>\n");
+    let Ok(source) = fs::read_to_string(&file.path) else {
+        out.push_str("<p>Исходник недоступен.</p></body></html>\n");
+        return out;
+    };
+    let mut partial = HashSet::new();
+    for branch in &file.branches {
+        if branch.taken.is_none_or(|t| t == 0) {
+            partial.insert(branch.line);
+        }
+    }
+    out.push_str("<table>\n");
+    for (i, text) in source.lines().enumerate() {
+        let line = i + 1;
+        let (class, hits) = match file.lines.get(&line) {
+            Some(0) => ("miss", "0".to_string()),
+            Some(n) if partial.contains(&line) => ("part", n.to_string()),
+            Some(n) => ("hit", n.to_string()),
+            None => ("", String::new()),
+        };
+        let _ = writeln!(
+            out,
+            "<tr class=\"{class}\"><td class=\"n\">{line}</td><td class=\"n\">{hits}</td><td><pre>{}</pre></td></tr>",
+            escape(text)
+        );
+    }
+    out.push_str("</table></body></html>\n");
+    out
+}
+
+fn analyze(data: &FileData) -> FileCoverage {
+    let mut hits: HashMap<SpanKey, u64> = HashMap::new();
+    for ((span, _), count) in &data.hits {
+        let entry = hits.entry(*span).or_default();
+        *entry = (*entry).max(*count);
+    }
+    let mut analysis = Analysis {
+        hits,
+        source: &data.source,
+        file: FileCoverage { path: data.source.name.clone(), ..FileCoverage::default() },
+        names: HashSet::new(),
+        blocks: 0,
+    };
+    for stmt in &data.program.items {
+        analysis.visit_stmt(stmt);
+    }
+    analysis.file
+}
+
+struct Analysis<'a> {
+    hits: HashMap<SpanKey, u64>,
+    source: &'a SourceFile,
+    file: FileCoverage,
+    names: HashSet<String>,
+    blocks: usize,
+}
+
+impl Analysis<'_> {
+    fn hit(&self, span: Span) -> u64 {
+        self.hits.get(&(span.start, span.end)).copied().unwrap_or(0)
+    }
+
+    fn line(&self, span: Span) -> usize {
+        self.source.position(span.start).0
+    }
+
+    /// How many times `expr` was evaluated: sub-expressions never run more often than their
+    /// parent, except inside function bodies, which are not descended into.
+    fn expr_count(&self, expr: &Expr) -> u64 {
+        child_exprs(expr).into_iter().fold(self.hit(expr.span()), |n, child| n.max(self.expr_count(child)))
+    }
+
+    fn block_count(&self, stmts: &[Stmt]) -> u64 {
+        stmts.iter().map(|s| self.stmt_count(s)).max().unwrap_or(0)
+    }
+
+    /// How many times control entered `stmt`. Loops are derived from their parts only: the VM
+    /// jumps back under the loop's own span on every iteration.
+    fn stmt_count(&self, stmt: &Stmt) -> u64 {
+        match stmt {
+            Stmt::Block(block) => self.block_count(&block.stmts),
+            Stmt::While { condition, body, .. } => {
+                let (cond, body) = (self.expr_count(condition), self.stmt_count(body));
+                cond.saturating_sub(body).max(u64::from(cond > 0))
+            }
+            Stmt::For { init: Some(init), .. } => {
+                own_exprs(init).into_iter().map(|e| self.expr_count(e)).max().unwrap_or(0)
+            }
+            Stmt::For { condition, body, .. } => {
+                let cond = condition.as_ref().map_or(0, |c| self.expr_count(c));
+                let body = self.stmt_count(body);
+                cond.saturating_sub(body).max(u64::from(cond > 0 || body > 0))
+            }
+            Stmt::DoWhile { body, condition, .. } => {
+                let body = self.stmt_count(body);
+                body.saturating_sub(self.expr_count(condition)).max(u64::from(body > 0))
+            }
+            Stmt::ForIn { iterable, .. } | Stmt::ForOf { iterable, .. } | Stmt::ForAwaitOf { iterable, .. } => {
+                self.expr_count(iterable)
+            }
+            Stmt::Labeled { body, .. } => self.stmt_count(body),
+            Stmt::TryCatch { try_block, .. } => self.block_count(&try_block.stmts),
+            Stmt::Export { kind: ExportKind::Declaration(inner), .. } => self.stmt_count(inner),
+            _ => own_exprs(stmt).into_iter().map(|e| self.expr_count(e)).fold(self.hit(stmt.span()), u64::max),
+        }
+    }
+
+    fn branch(&mut self, span: Span, evaluated: bool, counts: &[u64]) {
+        let line = self.line(span);
+        for (branch, count) in counts.iter().enumerate() {
+            let taken = evaluated.then_some(*count);
+            self.file.branches.push(BranchHit { line, block: self.blocks, branch, taken });
+        }
+        self.blocks += 1;
+    }
+
+    fn function(&mut self, name: Option<&str>, span: Span, params: &[Param], body: &Block) {
+        let line = self.line(span);
+        let mut name =
+            name.filter(|n| !n.is_empty()).map_or_else(|| format!("{ANONYMOUS_FRAME}:{line}"), str::to_string);
+        if !self.names.insert(name.clone()) {
+            name = format!("{name}:{line}");
+            self.names.insert(name.clone());
+        }
+        let hits = self.block_count(&body.stmts);
+        self.file.functions.push(FunctionHit { name, line, hits });
+        for default in params.iter().filter_map(|p| p.default.as_ref()) {
+            self.visit_expr(default);
+        }
+        for stmt in &body.stmts {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        let span = stmt.span();
+        if !matches!(stmt, Stmt::Block(_) | Stmt::Empty { .. }) {
+            let count = self.stmt_count(stmt);
+            let entry = self.file.lines.entry(self.line(span)).or_default();
+            *entry = (*entry).max(count);
+        }
+        match stmt {
+            Stmt::If { condition, then_branch, else_branch, .. } => {
+                let cond = self.expr_count(condition);
+                let then = self.stmt_count(then_branch);
+                let other = else_branch.as_ref().map_or(cond.saturating_sub(then), |e| self.stmt_count(e));
+                self.branch(span, cond > 0, &[then, other]);
+            }
+            Stmt::Switch { expr, cases, default, .. } => {
+                let mut counts: Vec<u64> = cases.iter().map(|c| self.block_count(&c.body.stmts)).collect();
+                counts.extend(default.iter().map(|d| self.block_count(&d.stmts)));
+                self.branch(span, self.expr_count(expr) > 0, &counts);
+            }
+            Stmt::FunctionDecl { name, params, body, .. } => self.function(Some(&name.name), span, params, body),
+            Stmt::ClassDecl { name, members, .. } => self.class_members(&name.name, members),
+            _ => {}
+        }
+        for expr in own_exprs(stmt) {
+            self.visit_expr(expr);
+        }
+        for child in child_stmts(stmt) {
+            self.visit_stmt(child);
+        }
+    }
+
+    fn class_members(&mut self, class: &str, members: &[ClassMember]) {
+        for member in members {
+            match member {
+                ClassMember::Constructor { params, body, span } => {
+                    self.function(Some(&format!("{class}.конструктор")), *span, params, body);
+                }
+                ClassMember::Method { name, params, body, span, .. } => {
+                    self.function(Some(&format!("{class}.{}", name.name)), *span, params, body);
+                }
+                ClassMember::Getter { name, body, span, .. } => {
+                    self.function(Some(&format!("{class}.{}", name.name)), *span, &[], body);
+                }
+                ClassMember::Setter { name, param, body, span, .. } => {
+                    self.function(Some(&format!("{class}.{}", name.name)), *span, std::slice::from_ref(param), body);
+                }
+                ClassMember::StaticBlock { body, .. } => {
+                    for stmt in &body.stmts {
+                        self.visit_stmt(stmt);
+                    }
+                }
+                ClassMember::Field { init, .. } => {
+                    if let Some(init) = init {
+                        self.visit_expr(init);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Conditional { condition, then_expr, else_expr, span } => {
+                let counts = [self.expr_count(then_expr), self.expr_count(else_expr)];
+                self.branch(*span, self.expr_count(condition) > 0, &counts);
+            }
+            Expr::Binary { op: BinaryOp::And | BinaryOp::Or | BinaryOp::NullishCoalescing, lhs, rhs, span } => {
+                let (evaluated, right) = (self.expr_count(lhs), self.expr_count(rhs));
+                self.branch(*span, evaluated > 0, &[right, evaluated.saturating_sub(right)]);
+            }
+            Expr::Match { subject, arms, span } => {
+                let counts: Vec<u64> = arms.iter().map(|arm| self.expr_count(&arm.body)).collect();
+                self.branch(*span, self.expr_count(subject) > 0, &counts);
+            }
+            Expr::ArrowFunction { params, body, span, .. } => self.function(None, *span, params, body),
+            Expr::FunctionExpr { name, params, body, span, .. } => {
+                self.function(name.as_ref().map(|n| n.name.as_str()), *span, params, body);
+            }
+            Expr::Literal(Literal::Object { entries, .. }) => {
+                for entry in entries {
+                    match entry {
+                        ObjectEntry::Getter { key, body, span } => self.function(key_name(key), *span, &[], body),
+                        ObjectEntry::Setter { key, param, body, span } => {
+                            self.function(key_name(key), *span, std::slice::from_ref(param), body);
+                        }
+                        ObjectEntry::Property { .. } | ObjectEntry::Spread(_) => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        for child in child_exprs(expr) {
+            self.visit_expr(child);
+        }
+    }
+}
+
+fn key_name(key: &PropKey) -> Option<&str> {
+    match key {
+        PropKey::Identifier(id) => Some(&id.name),
+        PropKey::Computed(_) => None,
+    }
+}
+
+/// Expressions evaluated as part of `stmt` itself, outside of its nested statements.
+fn own_exprs(stmt: &Stmt) -> Vec<&Expr> {
+    match stmt {
+        Stmt::VarDecl { init, .. } | Stmt::Using { init, .. } => vec![init],
+        Stmt::Expr { expr, .. } | Stmt::Throw { value: expr, .. } => vec![expr],
+        Stmt::Return { value, .. } => value.iter().collect(),
+        Stmt::If { condition, .. } | Stmt::While { condition, .. } | Stmt::DoWhile { condition, .. } => {
+            vec![condition]
+        }
+        // The VM re-binds a `гыы` loop variable on every iteration under the declaration's span,
+        // so only the initializer expressions stand for the loop entry.
+        Stmt::For { init, condition, update, .. } => {
+            init.as_deref().map(own_exprs).unwrap_or_default().into_iter().chain(condition).chain(update).collect()
+        }
+        Stmt::ForIn { iterable, .. } | Stmt::ForOf { iterable, .. } | Stmt::ForAwaitOf { iterable, .. } => {
+            vec![iterable]
+        }
+        Stmt::Switch { expr, cases, .. } => std::iter::once(expr).chain(cases.iter().map(|c| &c.value)).collect(),
+        Stmt::ClassDecl { super_class, decorators, .. } => super_class.iter().chain(decorators).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn child_stmts(stmt: &Stmt) -> Vec<&Stmt> {
+    match stmt {
+        Stmt::Block(block) => block.stmts.iter().collect(),
+        Stmt::If { then_branch, else_branch, .. } => {
+            std::iter::once(then_branch.as_ref()).chain(else_branch.as_deref()).collect()
+        }
+        Stmt::While { body, .. }
+        | Stmt::DoWhile { body, .. }
+        | Stmt::ForIn { body, .. }
+        | Stmt::ForOf { body, .. }
+        | Stmt::ForAwaitOf { body, .. }
+        | Stmt::Labeled { body, .. }
+        | Stmt::For { body, .. } => vec![body],
+        Stmt::TryCatch { try_block, catch_block, finally_block, .. } => {
+            std::iter::once(try_block).chain(catch_block).chain(finally_block).flat_map(|b| b.stmts.iter()).collect()
+        }
+        Stmt::Switch { cases, default, .. } => {
+            cases.iter().map(|c| &c.body).chain(default).flat_map(|b| b.stmts.iter()).collect()
+        }
+        Stmt::Export { kind: ExportKind::Declaration(inner), .. } => vec![inner],
+        _ => Vec::new(),
+    }
+}
+
+/// Sub-expressions evaluated together with `expr`; function bodies are left out.
+fn child_exprs(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Literal(Literal::Array { elements, .. }) => elements.iter().collect(),
+        Expr::Literal(Literal::Object { entries, .. }) => entries
+            .iter()
+            .flat_map(|entry| match entry {
+                ObjectEntry::Property { key, value } => match key {
+                    PropKey::Computed(key) => vec![key, value],
+                    PropKey::Identifier(_) => vec![value],
+                },
+                ObjectEntry::Spread(value) => vec![value],
+                ObjectEntry::Getter { key, .. } | ObjectEntry::Setter { key, .. } => match key {
+                    PropKey::Computed(key) => vec![key],
+                    PropKey::Identifier(_) => Vec::new(),
+                },
+            })
+            .collect(),
+        Expr::Unary { expr, .. }
+        | Expr::Postfix { expr, .. }
+        | Expr::Grouping { expr, .. }
+        | Expr::Spread { expr, .. }
+        | Expr::Assignment { value: expr, .. }
+        | Expr::Member { object: expr, .. }
+        | Expr::OptionalMember { object: expr, .. }
+        | Expr::Await { argument: expr, .. }
+        | Expr::DynamicImport { source: expr, .. } => vec![expr],
+        Expr::Binary { lhs, rhs, .. } => vec![lhs, rhs],
+        Expr::Index { object, index, .. } | Expr::OptionalIndex { object, index, .. } => vec![object, index],
+        Expr::Call { callee, args, .. } | Expr::OptionalCall { callee, args, .. } | Expr::New { callee, args, .. } => {
+            std::iter::once(callee.as_ref()).chain(args).collect()
+        }
+        Expr::Conditional { condition, then_expr, else_expr, .. } => vec![condition, then_expr, else_expr],
+        Expr::TemplateLiteral { parts, .. } => parts
+            .iter()
+            .filter_map(|part| match part {
+                yps_parser::TemplatePart::Expr(e) => Some(e.as_ref()),
+                yps_parser::TemplatePart::Str(_) => None,
+            })
+            .collect(),
+        Expr::TaggedTemplate { tag, expressions, .. } => std::iter::once(tag.as_ref()).chain(expressions).collect(),
+        Expr::Yield { argument, .. } => argument.as_deref().into_iter().collect(),
+        Expr::Match { subject, arms, .. } => {
+            let mut children = vec![subject.as_ref()];
+            for arm in arms {
+                match &arm.pattern {
+                    MatchPattern::Value(value) | MatchPattern::Instance { class: value, .. } => children.push(value),
+                    MatchPattern::Wildcard { .. } | MatchPattern::Bind(_) => {}
+                }
+                children.extend(&arm.guard);
+                children.push(&arm.body);
+            }
+            children
+        }
+        Expr::Identifier(_)
+        | Expr::Literal(_)
+        | Expr::ArrowFunction { .. }
+        | Expr::FunctionExpr { .. }
+        | Expr::This { .. }
+        | Expr::ImportMeta { .. }
+        | Expr::NewTarget { .. }
+        | Expr::Super { .. }
+        | Expr::Error { .. } => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(code: &str) -> (SourceFile, Program) {
+        let source = SourceFile::new("т.yopta".to_string(), code.to_string());
+        let (tokens, _) = yps_lexer::Lexer::new(&source).tokenize();
+        let (program, diagnostics) = yps_parser::Parser::new(&tokens, &source).parse_program();
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        (source, program)
+    }
+
+    fn span_of(source: &SourceFile, text: &str) -> Span {
+        let start = source.source.find(text).expect("фрагмент есть в исходнике");
+        Span { start, end: start + text.len() }
+    }
+
+    #[test]
+    fn if_without_else_counts_the_fallthrough_branch() {
+        let (source, program) = parse("гыы а = 1;\nвилкойвглаз (а > 5) {\n  а = 2;\n}\n");
+        start();
+        let file = register_file(&source, &program);
+        hit(file, span_of(&source, "а > 5"), EXPR_SITE);
+        hit(file, span_of(&source, "1"), EXPR_SITE);
+        let report = finish();
+
+        let cov = &report.files[0];
+        assert_eq!(cov.lines.get(&1), Some(&1));
+        assert_eq!(cov.lines.get(&2), Some(&1));
+        assert_eq!(cov.lines.get(&3), Some(&0));
+        let taken: Vec<Option<u64>> = cov.branches.iter().map(|b| b.taken).collect();
+        assert_eq!(taken, [Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn sites_of_one_span_are_not_summed() {
+        let (source, program) = parse("гыы а = 1;\n");
+        start();
+        let file = register_file(&source, &program);
+        let literal = span_of(&source, "1");
+        hit(file, literal, (7, 0));
+        hit(file, literal, (7, 1));
+        assert_eq!(finish().files[0].lines.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn lcov_round_trips_and_merges_by_summing() {
+        let file = FileCoverage {
+            path: "/п/а.yopta".to_string(),
+            functions: vec![FunctionHit { name: "ф".to_string(), line: 1, hits: 2 }],
+            branches: vec![
+                BranchHit { line: 2, block: 0, branch: 0, taken: Some(1) },
+                BranchHit { line: 2, block: 0, branch: 1, taken: None },
+            ],
+            lines: BTreeMap::from([(1, 2), (2, 0)]),
+        };
+        let mut report = Report { files: vec![file] };
+        let text = report.to_lcov();
+        assert!(text.contains("BRDA:2,0,1,-\n"), "{text}");
+        let parsed = Report::parse_lcov(&text).unwrap();
+        assert_eq!(parsed, report);
+
+        report.merge(parsed);
+        let merged = &report.files[0];
+        assert_eq!(merged.functions[0].hits, 4);
+        assert_eq!(merged.branches[1].taken, None);
+        assert_eq!(merged.lines.get(&1), Some(&4));
+    }
+}
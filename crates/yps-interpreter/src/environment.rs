@@ -11,6 +11,9 @@ pub struct EnvFrame {
     tdz: HashSet<String>,
     disposables: Vec<(Value, bool)>,
     parent: Option<Rc<RefCell<EnvFrame>>>,
+    /// `sources` id of the file the code running in this scope came from; inherited by nested
+    /// scopes.
+    file: usize,
 }
 
 pub(crate) enum Lookup {
@@ -108,6 +111,7 @@ impl Environment {
             tdz: HashSet::new(),
             disposables: Vec::new(),
             parent: None,
+            file: 0,
         }));
         registry.register(&current);
         Self { current, registry }
@@ -119,6 +123,7 @@ impl Environment {
             constants: HashSet::new(),
             tdz: HashSet::new(),
             disposables: Vec::new(),
+            file: self.current.borrow().file,
             parent: Some(Rc::clone(&self.current)),
         };
         self.current = Rc::new(RefCell::new(new_frame));
@@ -141,12 +146,21 @@ impl Environment {
                 tdz: frame.tdz.clone(),
                 disposables: Vec::new(),
                 parent: frame.parent.clone(),
+                file: frame.file,
             }
         };
         self.current = Rc::new(RefCell::new(new_frame));
         self.registry.register(&self.current);
     }
 
+    pub(crate) fn file(&self) -> usize {
+        self.current.borrow().file
+    }
+
+    pub(crate) fn set_file(&self, file: usize) {
+        self.current.borrow_mut().file = file;
+    }
+
     pub fn snapshot(&self) -> Rc<RefCell<EnvFrame>> {
         Rc::clone(&self.current)
     }
@@ -199,6 +213,18 @@ impl Environment {
         }
     }
 
+    /// Every name bound in this scope or an enclosing one, globals included.
+    pub(crate) fn visible_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut frame = Some(Rc::clone(&self.current));
+        while let Some(frame_rc) = frame {
+            let frame_ref = frame_rc.borrow();
+            names.extend(frame_ref.bindings.keys().cloned());
+            frame = frame_ref.parent.clone();
+        }
+        names
+    }
+
     pub fn is_const(&self, name: &str) -> bool {
         let mut frame_rc = Rc::clone(&self.current);
         loop {
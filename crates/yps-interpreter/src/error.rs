@@ -10,7 +10,12 @@ pub const MAX_STACK_DEPTH: usize = 64;
 #[derive(Debug, Clone)]
 pub struct Frame {
     pub name: Rc<str>,
+    /// Where the function was called from.
     pub span: Span,
+    /// `sources` id of the file the function is declared in.
+    pub file: usize,
+    /// The function's body, which tells apart functions of one file that share a name.
+    pub decl: Span,
 }
 
 #[derive(Debug)]
@@ -20,11 +25,13 @@ pub struct RuntimeError {
     pub cause: Option<Box<RuntimeError>>,
     pub thrown: Option<Box<Value>>,
     pub stack: Vec<Frame>,
+    /// A hint for fixing the error, e.g. the name a misspelled one probably meant.
+    pub help: Option<String>,
 }
 
 impl RuntimeError {
     pub fn new(message: impl Into<String>, span: Span) -> Self {
-        Self { message: message.into(), span, cause: None, thrown: None, stack: Vec::new() }
+        Self { message: message.into(), span, cause: None, thrown: None, stack: Vec::new(), help: None }
     }
 
     pub fn thrown(value: Value, span: Span) -> Self {
@@ -34,6 +41,7 @@ impl RuntimeError {
             cause: None,
             thrown: Some(Box::new(value)),
             stack: Vec::new(),
+            help: None,
         }
     }
 
@@ -54,6 +62,21 @@ impl RuntimeError {
         self.cause = Some(Box::new(cause));
         self
     }
+
+    #[must_use]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Adds "возможно, имелось в виду …" when one of `candidates` is close to `name`.
+    #[must_use]
+    pub fn with_did_you_mean<'a>(self, name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Self {
+        match yps_lexer::suggest::closest(name, candidates) {
+            Some(found) => self.with_help(format!("возможно, имелось в виду '{found}'")),
+            None => self,
+        }
+    }
 }
 
 impl fmt::Display for RuntimeError {
@@ -64,6 +87,9 @@ impl fmt::Display for RuntimeError {
             write!(f, "\n  причина: {}", c.message)?;
             current = c.cause.as_deref();
         }
+        if let Some(help) = &self.help {
+            write!(f, "\n  помощь: {help}")?;
+        }
         Ok(())
     }
 }
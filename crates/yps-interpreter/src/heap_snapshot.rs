@@ -0,0 +1,477 @@
+//! V8 `.heapsnapshot` writer shared by both backends (`yps --heap-snapshot`, `Процесс.снимокКучи`)
+//! and the per-site allocation counter behind it.
+//!
+//! Each backend walks the same roots its collector marks and feeds the graph here; this module
+//! only knows about nodes, edges and the JSON layout DevTools expects. Allocation sites are
+//! tracked thread-locally (like `trace`) so that the VM's bridged calls see the same state.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::rc::{Rc, Weak};
+
+use yps_lexer::{SourceFile, Span};
+
+use crate::sources;
+use crate::stdlib::json::write_json_string;
+use crate::value::Value;
+
+const NODE_TYPES: [&str; 10] =
+    ["hidden", "array", "string", "object", "code", "closure", "regexp", "number", "native", "synthetic"];
+const EDGE_TYPES: [&str; 7] = ["context", "element", "property", "internal", "hidden", "shortcut", "weak"];
+const NODE_FIELD_COUNT: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeType {
+    Hidden = 0,
+    Array = 1,
+    String = 2,
+    Object = 3,
+    Code = 4,
+    Closure = 5,
+    Regexp = 6,
+    Number = 7,
+    Native = 8,
+    Synthetic = 9,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeType {
+    Context = 0,
+    Element = 1,
+    Property = 2,
+    Internal = 3,
+    Hidden = 4,
+    Shortcut = 5,
+    Weak = 6,
+}
+
+enum EdgeName {
+    Index(usize),
+    Name(usize),
+}
+
+struct Edge {
+    kind: EdgeType,
+    name: EdgeName,
+    to: usize,
+}
+
+struct Node {
+    kind: NodeType,
+    name: usize,
+    self_size: usize,
+    site: Option<usize>,
+    edges: Vec<Edge>,
+}
+
+/// Object graph in V8 snapshot terms. Node 0 is the synthetic root, node 1 is `(GC roots)`.
+pub struct HeapGraph {
+    nodes: Vec<Node>,
+    strings: Vec<String>,
+    string_ids: HashMap<String, usize>,
+    by_ptr: HashMap<usize, usize>,
+}
+
+pub const GC_ROOTS: usize = 1;
+
+impl Default for HeapGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeapGraph {
+    #[must_use]
+    pub fn new() -> Self {
+        let mut graph =
+            Self { nodes: Vec::new(), strings: Vec::new(), string_ids: HashMap::new(), by_ptr: HashMap::new() };
+        let root = graph.add_node(NodeType::Synthetic, "", 0);
+        let gc_roots = graph.add_node(NodeType::Synthetic, "(GC roots)", 0);
+        graph.add_element(root, 1, gc_roots);
+        graph
+    }
+
+    fn string_id(&mut self, s: &str) -> usize {
+        if let Some(&id) = self.string_ids.get(s) {
+            return id;
+        }
+        let id = self.strings.len();
+        self.strings.push(s.to_string());
+        self.string_ids.insert(s.to_string(), id);
+        id
+    }
+
+    pub fn add_node(&mut self, kind: NodeType, name: &str, self_size: usize) -> usize {
+        let name = self.string_id(name);
+        self.nodes.push(Node { kind, name, self_size, site: None, edges: Vec::new() });
+        self.nodes.len() - 1
+    }
+
+    /// Node for the heap cell at `ptr`, created on first sight. The flag tells the walker whether
+    /// the cell still has to be expanded.
+    pub fn node_for(&mut self, ptr: usize, kind: NodeType, name: &str, self_size: usize) -> (usize, bool) {
+        if let Some(&index) = self.by_ptr.get(&ptr) {
+            return (index, false);
+        }
+        let index = self.add_node(kind, name, self_size);
+        self.nodes[index].site = site_of(ptr);
+        self.by_ptr.insert(ptr, index);
+        (index, true)
+    }
+
+    pub fn add_edge(&mut self, from: usize, kind: EdgeType, name: &str, to: usize) {
+        let name = EdgeName::Name(self.string_id(name));
+        self.nodes[from].edges.push(Edge { kind, name, to });
+    }
+
+    pub fn add_element(&mut self, from: usize, index: usize, to: usize) {
+        self.nodes[from].edges.push(Edge { kind: EdgeType::Element, name: EdgeName::Index(index), to });
+    }
+
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Direct retainers of the objects named `name`, as `владелец.ребро`; mostly for tests.
+    #[must_use]
+    pub fn retainers_of(&self, name: &str) -> Vec<String> {
+        let Some(&target) = self.string_ids.get(name) else { return Vec::new() };
+        let mut out = Vec::new();
+        for node in &self.nodes {
+            for edge in &node.edges {
+                let to = &self.nodes[edge.to];
+                if to.name != target || to.kind != NodeType::Object {
+                    continue;
+                }
+                let owner = &self.strings[node.name];
+                match edge.name {
+                    EdgeName::Index(i) => out.push(format!("{owner}[{i}]")),
+                    EdgeName::Name(n) => out.push(format!("{owner}.{}", self.strings[n])),
+                }
+            }
+        }
+        out.sort();
+        out
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let sites = ALLOCATIONS.with(|a| a.borrow().as_ref().map(|t| t.sites.clone())).unwrap_or_default();
+        let files = sources::files();
+        let mut strings = self.strings.clone();
+        let mut intern = |s: String| {
+            strings.push(s);
+            strings.len() - 1
+        };
+
+        let mut out = String::from("{\"snapshot\":{\"meta\":{");
+        out.push_str("\"node_fields\":[\"type\",\"name\",\"id\",\"self_size\",\"edge_count\",\"trace_node_id\",\"detachedness\"],");
+        out.push_str("\"node_types\":[[");
+        push_quoted(&mut out, NODE_TYPES.iter());
+        out.push_str("],\"string\",\"number\",\"number\",\"number\",\"number\",\"number\"],");
+        out.push_str("\"edge_fields\":[\"type\",\"name_or_index\",\"to_node\"],\"edge_types\":[[");
+        push_quoted(&mut out, EDGE_TYPES.iter());
+        out.push_str("],\"string_or_number\",\"node\"],");
+        out.push_str("\"trace_function_info_fields\":[\"function_id\",\"name\",\"script_name\",\"script_id\",\"line\",\"column\"],");
+        out.push_str("\"trace_node_fields\":[\"id\",\"function_info_index\",\"count\",\"size\",\"children\"],");
+        out.push_str("\"sample_fields\":[\"timestamp_us\",\"last_assigned_id\"],");
+        out.push_str("\"location_fields\":[\"object_index\",\"script_id\",\"line\",\"column\"]},");
+        let edge_count: usize = self.nodes.iter().map(|n| n.edges.len()).sum();
+        let _ = write!(
+            out,
+            "\"node_count\":{},\"edge_count\":{edge_count},\"trace_function_count\":{}}},",
+            self.nodes.len(),
+            sites.len() + 1
+        );
+
+        out.push_str("\"nodes\":[");
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let trace_node = node.site.map_or(0, |s| s + 2);
+            let _ = write!(
+                out,
+                "{},{},{},{},{},{trace_node},0",
+                node.kind as usize,
+                node.name,
+                i * 2 + 1,
+                node.self_size,
+                node.edges.len()
+            );
+        }
+        out.push_str("],\"edges\":[");
+        let mut first = true;
+        for node in &self.nodes {
+            for edge in &node.edges {
+                if !first {
+                    out.push(',');
+                }
+                first = false;
+                let name = match edge.name {
+                    EdgeName::Index(i) | EdgeName::Name(i) => i,
+                };
+                let _ = write!(out, "{},{name},{}", edge.kind as usize, edge.to * NODE_FIELD_COUNT);
+            }
+        }
+
+        // Scripts are numbered from 1, as `sources` id + 1.
+        let unnamed = intern(String::new());
+        let script_names: Vec<usize> = files.iter().map(|f| intern(f.name.clone())).collect();
+        let script_name = |file: usize| script_names.get(file).copied().unwrap_or(unnamed);
+        let position = |file: usize, span: Span| files.get(file).map_or((0, 0), |f| f.position(span.start));
+        out.push_str("],\"trace_function_infos\":[1,");
+        let root_name = intern("(root)".to_string());
+        let _ = write!(out, "{root_name},{},1,0,0", script_name(0));
+        for (i, site) in sites.iter().enumerate() {
+            let (line, column) = position(site.file, site.span);
+            let label = files.get(site.file).map_or_else(|| format!("{line}:{column}"), |f| site_label(f, site.span));
+            let name = intern(label);
+            let _ = write!(out, ",{},{name},{},{},{line},{column}", i + 2, script_name(site.file), site.file + 1);
+        }
+        out.push_str("],\"trace_tree\":[1,0,0,0,[");
+        for (i, Site { count, .. }) in sites.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{},{},{count},0,[]", i + 2, i + 1);
+        }
+        out.push_str("]],\"samples\":[],\"locations\":[");
+        let mut first = true;
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(site) = node.site {
+                if !first {
+                    out.push(',');
+                }
+                first = false;
+                let Site { file, span, .. } = sites[site];
+                let (line, column) = position(file, span);
+                let (line, column) = (line.saturating_sub(1), column.saturating_sub(1));
+                let _ = write!(out, "{},{},{line},{column}", i * NODE_FIELD_COUNT, file + 1);
+            }
+        }
+        out.push_str("],\"strings\":[");
+        for (i, s) in strings.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_string(&mut out, s);
+        }
+        out.push_str("]}");
+        out
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
+
+fn push_quoted<'a>(out: &mut String, items: impl Iterator<Item = &'a &'a str>) {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json_string(out, item);
+    }
+}
+
+/// Short source excerpt naming an allocation site, e.g. `новый Точка(1, 2)`.
+fn site_label(source: &SourceFile, span: Span) -> String {
+    const MAX_CHARS: usize = 40;
+    let text = source.source.get(span.start..span.end).unwrap_or_default();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (line, column) = source.position(span.start);
+    if text.is_empty() {
+        format!("{line}:{column}")
+    } else if text.chars().count() > MAX_CHARS {
+        let cut: String = text.chars().take(MAX_CHARS).collect();
+        format!("{cut}… ({line}:{column})")
+    } else {
+        format!("{text} ({line}:{column})")
+    }
+}
+
+/// Where allocations happened: a span of the file with `sources` id `file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Site {
+    pub file: usize,
+    pub span: Span,
+    pub count: u64,
+}
+
+struct AllocationTracker {
+    sites: Vec<Site>,
+    site_index: HashMap<(usize, usize, usize), usize>,
+    /// Allocation site of every tracked heap cell, by address. The weak reference keeps the
+    /// address from being reused while the entry exists and tells a freed cell apart; entries of
+    /// freed cells are dropped whenever the map has doubled since the last sweep.
+    cells: HashMap<usize, (usize, Weak<dyn Any>)>,
+    sweep_at: usize,
+}
+
+thread_local! {
+    static ALLOCATIONS: RefCell<Option<AllocationTracker>> = const { RefCell::new(None) };
+}
+
+/// Smallest map size worth sweeping.
+const FIRST_SWEEP: usize = 1024;
+
+/// Turns on per-site allocation counting; the snapshot resolves sites through `sources`.
+pub fn start_tracking() {
+    ALLOCATIONS.with(|a| {
+        *a.borrow_mut() = Some(AllocationTracker {
+            sites: Vec::new(),
+            site_index: HashMap::new(),
+            cells: HashMap::new(),
+            sweep_at: FIRST_SWEEP,
+        });
+    });
+}
+
+pub fn stop_tracking() {
+    ALLOCATIONS.with(|a| *a.borrow_mut() = None);
+}
+
+#[must_use]
+pub fn is_tracking() -> bool {
+    ALLOCATIONS.with(|a| a.borrow().is_some())
+}
+
+/// Counts one allocation of `cell` at `span` of the file with `sources` id `file`.
+pub fn record_allocation_at<T: 'static>(cell: &Rc<T>, file: usize, span: Span) {
+    ALLOCATIONS.with(|a| {
+        if let Some(tracker) = a.borrow_mut().as_mut() {
+            let key = (file, span.start, span.end);
+            let site = match tracker.site_index.get(&key) {
+                Some(&site) => site,
+                None => {
+                    tracker.sites.push(Site { file, span, count: 0 });
+                    tracker.site_index.insert(key, tracker.sites.len() - 1);
+                    tracker.sites.len() - 1
+                }
+            };
+            tracker.sites[site].count += 1;
+            let weak: Weak<dyn Any> = Rc::downgrade(cell) as Weak<T>;
+            tracker.cells.insert(Rc::as_ptr(cell) as usize, (site, weak));
+            if tracker.cells.len() >= tracker.sweep_at {
+                tracker.cells.retain(|_, (_, weak)| weak.strong_count() > 0);
+                tracker.sweep_at = (tracker.cells.len() * 2).max(FIRST_SWEEP);
+            }
+        }
+    });
+}
+
+/// Counts `value` when it is a heap cell; `file` is the `sources` id of the code creating it.
+pub(crate) fn record_allocation(value: &Value, file: usize, span: Span) {
+    match value {
+        Value::Array(rc) => record_allocation_at(rc, file, span),
+        Value::Object(rc) => record_allocation_at(rc, file, span),
+        Value::Function(rc) => record_allocation_at(rc, file, span),
+        Value::Map(rc) => record_allocation_at(rc, file, span),
+        Value::Set(rc) => record_allocation_at(rc, file, span),
+        Value::Promise { state } => record_allocation_at(state, file, span),
+        _ => {}
+    }
+}
+
+/// Allocation counts per source location, most frequent first.
+#[must_use]
+pub fn allocation_counts() -> Vec<Site> {
+    let mut counts = ALLOCATIONS.with(|a| a.borrow().as_ref().map(|t| t.sites.clone()).unwrap_or_default());
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then(a.file.cmp(&b.file)).then(a.span.start.cmp(&b.span.start)));
+    counts
+}
+
+/// Site of the live cell at `ptr`, if it was tracked.
+fn site_of(ptr: usize) -> Option<usize> {
+    ALLOCATIONS.with(|a| {
+        let tracker = a.borrow();
+        let (site, weak) = tracker.as_ref()?.cells.get(&ptr)?;
+        (weak.strong_count() > 0).then_some(*site)
+    })
+}
+
+/// Number of cells the tracker still holds an entry for; mostly for tests.
+#[must_use]
+pub fn tracked_cells() -> usize {
+    ALLOCATIONS.with(|a| a.borrow().as_ref().map_or(0, |t| t.cells.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Covers one Cyrillic letter.
+    fn span(start: usize) -> Span {
+        Span { start, end: start + 2 }
+    }
+
+    #[test]
+    fn nodes_are_shared_by_address() {
+        let mut graph = HeapGraph::new();
+        let (a, fresh) = graph.node_for(0x10, NodeType::Object, "Объект", 1);
+        assert!(fresh);
+        assert_eq!(graph.node_for(0x10, NodeType::Object, "Объект", 1), (a, false));
+        graph.add_edge(GC_ROOTS, EdgeType::Property, "а", a);
+        assert_eq!(graph.retainers_of("Объект"), ["(GC roots).а"]);
+        assert_eq!(graph.node_count(), 3);
+    }
+
+    #[test]
+    fn allocations_are_counted_per_site_of_each_file() {
+        sources::start(SourceFile::new("к.yopta".to_string(), "а\nб\n".to_string()));
+        let module = sources::register(&SourceFile::new("м.yopta".to_string(), "в\n".to_string()));
+        start_tracking();
+        let cells: Vec<Rc<u8>> = (0..4).map(Rc::new).collect();
+        record_allocation_at(&cells[0], 0, span(3));
+        record_allocation_at(&cells[1], 0, span(3));
+        record_allocation_at(&cells[2], 0, span(0));
+        record_allocation_at(&cells[3], module, span(0));
+        let counts: Vec<_> = allocation_counts().iter().map(|s| (s.file, s.span, s.count)).collect();
+        assert_eq!(counts, [(0, span(3), 2), (0, span(0), 1), (module, span(0), 1)]);
+
+        let mut graph = HeapGraph::new();
+        let (node, _) = graph.node_for(Rc::as_ptr(&cells[1]) as usize, NodeType::Array, "Массив", 0);
+        graph.add_element(GC_ROOTS, 0, node);
+        let (node, _) = graph.node_for(Rc::as_ptr(&cells[3]) as usize, NodeType::Array, "Массив", 0);
+        graph.add_element(GC_ROOTS, 1, node);
+        let json = graph.to_json();
+        stop_tracking();
+
+        assert!(json.contains("\"trace_tree\":[1,0,0,0,[2,1,2,0,[],3,2,1,0,[],4,3,1,0,[]]]"), "{json}");
+        assert!(json.contains("\"locations\":[14,1,1,0,21,2,0,0]"), "{json}");
+        assert!(json.contains("\"б (2:1)\""), "{json}");
+        assert!(json.contains("\"в (1:1)\""), "{json}");
+    }
+
+    #[test]
+    fn freed_cells_are_forgotten() {
+        start_tracking();
+        for i in 0..FIRST_SWEEP * 3 {
+            record_allocation_at(&Rc::new(i), 0, span(0));
+        }
+        let cell = Rc::new(0);
+        record_allocation_at(&cell, 0, span(0));
+        let ptr = Rc::as_ptr(&cell) as usize;
+        assert_eq!(site_of(ptr), Some(0));
+        drop(cell);
+        assert_eq!(site_of(ptr), None);
+        assert!(tracked_cells() <= FIRST_SWEEP, "{}", tracked_cells());
+        stop_tracking();
+    }
+
+    #[test]
+    fn json_edges_point_at_node_offsets() {
+        stop_tracking();
+        let mut graph = HeapGraph::new();
+        let node = graph.add_node(NodeType::String, "строка", 12);
+        graph.add_edge(GC_ROOTS, EdgeType::Internal, "x", node);
+        let json = graph.to_json();
+        assert!(json.contains("\"node_count\":3,\"edge_count\":2"), "{json}");
+        assert!(json.contains("\"edges\":[1,1,7,3,3,14]"), "{json}");
+    }
+}
@@ -277,7 +277,7 @@ impl Interpreter {
         if let Some(param) = params.first() {
             self.env.define(param.name.name.clone(), value, false);
         }
-        self.push_frame(name, span);
+        self.push_frame(name, span, body.span);
         let mut result = self.exec_block_stmts(&body.stmts);
         if let Err(e) = &mut result {
             e.attach_stack(self.snapshot_stack());
@@ -342,7 +342,9 @@ impl Interpreter {
         if is_const {
             return Err(RuntimeError::new(format!("Нельзя изменить константу '{root_name}'"), span));
         }
-        let root = root.ok_or_else(|| RuntimeError::new(format!("Переменная '{root_name}' не определена"), span))?;
+        let root = root
+            .or_else(|| self.implicit_global(&root_name))
+            .ok_or_else(|| self.undefined_variable(&root_name, span))?;
         Self::set_at_path(root, &path, value.clone(), span)?;
         Ok(value)
     }
@@ -423,7 +425,7 @@ impl Interpreter {
                 }
                 if n.is_finite() && *n >= 0.0 && n.fract() == 0.0 {
                     let i = *n as usize;
-                    if i < ta.length {
+                    if i < ta.live_length() {
                         crate::stdlib::typed_array::write_element(
                             &ta.buffer,
                             ta.kind,
@@ -540,10 +542,7 @@ impl Interpreter {
         let Expr::Identifier(ident) = expr else {
             return Err(RuntimeError::new("'++' / '--' можно применить только к переменной", span));
         };
-        let old = self
-            .env
-            .get(&ident.name)
-            .ok_or_else(|| RuntimeError::new(format!("Переменная '{}' не определена", ident.name), span))?;
+        let old = self.env.get(&ident.name).ok_or_else(|| self.undefined_variable(&ident.name, span))?;
         let Value::Number(n) = old else {
             return Err(RuntimeError::new(format!("'++' / '--' требует число, получено '{}'", old.type_name()), span));
         };
@@ -560,7 +559,7 @@ impl Interpreter {
             return Err(RuntimeError::new(format!("Нельзя изменить константу '{name}'"), span));
         }
         if !self.env.set(name, value) {
-            return Err(RuntimeError::new(format!("Переменная '{name}' не определена"), span));
+            return Err(self.undefined_variable(name, span));
         }
         Ok(())
     }
@@ -46,7 +46,12 @@ const fn is_short_circuit(op: BinaryOp) -> bool {
 fn has_await(e: &Expr) -> bool {
     match e {
         Expr::Await { .. } => true,
-        Expr::Identifier(_) | Expr::This { .. } | Expr::Super { .. } => false,
+        Expr::Identifier(_)
+        | Expr::This { .. }
+        | Expr::ImportMeta { .. }
+        | Expr::NewTarget { .. }
+        | Expr::Super { .. }
+        | Expr::Error { .. } => false,
         Expr::ArrowFunction { .. } | Expr::FunctionExpr { .. } => false,
         Expr::Literal(lit) => match lit {
             Literal::Array { elements, .. } => elements.iter().any(has_await),
@@ -80,6 +85,10 @@ fn has_await(e: &Expr) -> bool {
         }),
         Expr::TaggedTemplate { tag, expressions, .. } => has_await(tag) || expressions.iter().any(has_await),
         Expr::Yield { argument, .. } => argument.as_deref().is_some_and(has_await),
+        Expr::Match { subject, arms, .. } => {
+            has_await(subject)
+                || arms.iter().any(|arm| arm.guard.as_ref().is_some_and(has_await) || has_await(&arm.body))
+        }
     }
 }
 
@@ -142,6 +151,7 @@ impl Lowerer {
         let id = self.fresh(span);
         out.push(Stmt::VarDecl {
             pattern: Pattern::Identifier(id.clone()),
+            ty: None,
             init: Expr::Literal(Literal::Boolean { value: true, span }),
             is_const: false,
             span,
@@ -151,7 +161,7 @@ impl Lowerer {
 
     fn bind(&mut self, init: Expr, span: Span, out: &mut Vec<Stmt>) -> Expr {
         let id = self.fresh(span);
-        out.push(Stmt::VarDecl { pattern: Pattern::Identifier(id.clone()), init, is_const: false, span });
+        out.push(Stmt::VarDecl { pattern: Pattern::Identifier(id.clone()), ty: None, init, is_const: false, span });
         Expr::Identifier(id)
     }
 
@@ -183,9 +193,15 @@ impl Lowerer {
             return;
         }
         match s {
-            Stmt::VarDecl { pattern, init, is_const, span } => {
+            Stmt::VarDecl { pattern, ty, init, is_const, span } => {
                 let init = self.expr(init, out);
-                out.push(Stmt::VarDecl { pattern: pattern.clone(), init, is_const: *is_const, span: *span });
+                out.push(Stmt::VarDecl {
+                    pattern: pattern.clone(),
+                    ty: ty.clone(),
+                    init,
+                    is_const: *is_const,
+                    span: *span,
+                });
             }
             Stmt::Expr { expr: Expr::Await { argument, span: aw }, span } => {
                 let argument = self.expr(argument, out);
@@ -464,6 +480,7 @@ impl Lowerer {
                     let slot = self.fresh(*span);
                     out.push(Stmt::VarDecl {
                         pattern: Pattern::Identifier(slot.clone()),
+                        ty: None,
                         init: Expr::Literal(Literal::Undefined { span: *span }),
                         is_const: false,
                         span: *span,
@@ -572,6 +589,11 @@ impl Lowerer {
                 let tag = lowered.remove(0);
                 Expr::TaggedTemplate { tag: Box::new(tag), quasis: quasis.clone(), expressions: lowered, span: *span }
             }
+            // Arms bind their own names, so only the subject is hoisted; awaits inside arms run in place.
+            Expr::Match { subject, arms, span } => {
+                let subject = self.expr(subject, out);
+                Expr::Match { subject: Box::new(subject), arms: arms.clone(), span: *span }
+            }
             other => other.clone(),
         }
     }
@@ -630,7 +652,13 @@ impl Lowerer {
         if is_short_circuit(op) && has_await(rhs) {
             let head = self.expr(lhs, out);
             let slot = self.fresh(span);
-            out.push(Stmt::VarDecl { pattern: Pattern::Identifier(slot.clone()), init: head, is_const: false, span });
+            out.push(Stmt::VarDecl {
+                pattern: Pattern::Identifier(slot.clone()),
+                ty: None,
+                init: head,
+                is_const: false,
+                span,
+            });
             let condition = match op {
                 BinaryOp::And => Expr::Identifier(slot.clone()),
                 BinaryOp::Or => Expr::Unary { op: UnaryOp::Not, expr: Box::new(Expr::Identifier(slot.clone())), span },
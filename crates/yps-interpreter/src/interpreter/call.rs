@@ -88,6 +88,9 @@ impl Interpreter {
                 if let Some(res) = self.try_call_timer_builtin(&name, args.clone(), span) {
                     return res;
                 }
+                if let Some(res) = self.try_call_code_builtin(&name, args.clone(), span) {
+                    return res;
+                }
                 if let Some(res) = crate::stdlib::call_static_namespaced(self, &name, args.clone(), span) {
                     return res;
                 }
@@ -107,6 +110,7 @@ impl Interpreter {
                 let env = Rc::clone(&func.env);
                 let is_generator = func.is_generator;
                 let is_async = func.is_async;
+                let is_arrow = func.is_arrow;
                 drop(func);
                 if self.call_stack.len() >= super::MAX_CALL_DEPTH {
                     return Err(RuntimeError::new("Превышена максимальная глубина рекурсии", span));
@@ -128,6 +132,9 @@ impl Interpreter {
                 let saved_env = self.env.clone();
                 self.env = Environment::from_snapshot(env, self.env.registry());
                 self.env.push_scope();
+                if !is_arrow {
+                    self.bind_arguments(&params, &body, &args);
+                }
 
                 self.bind_params(&params, &args, true, span)?;
                 self.env.mark_tdz(crate::resolver::lexical_declarations(&body.stmts));
@@ -150,7 +157,7 @@ impl Interpreter {
                     self.drive_async(&coroutine, &outer_state, GenInput::Send(Value::Undefined), span)?;
                     Ok(outer)
                 } else {
-                    self.push_frame(name, span);
+                    self.push_frame(name, span, body.span);
                     let mut result = self.exec_block_stmts(&body.stmts);
                     if let Err(e) = &mut result {
                         e.attach_stack(self.snapshot_stack());
@@ -239,12 +246,17 @@ impl Interpreter {
         super_class: Option<Rc<crate::value::ClassDef>>,
         span: Span,
     ) -> Result<Value, RuntimeError> {
+        let new_target = self.pending_new_target.take();
         if self.call_stack.len() >= super::MAX_CALL_DEPTH {
             return Err(RuntimeError::new("Превышена максимальная глубина рекурсии", span));
         }
         let saved_env = self.env.clone();
         self.env = Environment::from_snapshot(Rc::clone(env), self.env.registry());
         self.env.push_scope();
+        if let Some(target) = new_target {
+            self.env.define(symbols::NEW_TARGET.to_string(), target, false);
+        }
+        self.bind_arguments(params, body, &args);
 
         if let Some(this) = &this_val {
             self.env.define(symbols::THIS.to_string(), this.clone(), false);
@@ -259,7 +271,7 @@ impl Interpreter {
         self.bind_params(params, &args, true, span)?;
         self.env.mark_tdz(crate::resolver::lexical_declarations(&body.stmts));
 
-        self.push_frame(name, span);
+        self.push_frame(name, span, body.span);
         let mut result = self.exec_block_stmts(&body.stmts);
         if let Err(e) = &mut result {
             e.attach_stack(self.snapshot_stack());
@@ -311,7 +323,7 @@ impl Interpreter {
                     return Ok(Value::Undefined);
                 }
                 let i = *n as usize;
-                if i >= ta.length {
+                if i >= ta.live_length() {
                     return Ok(Value::Undefined);
                 }
                 let bytes = ta.buffer.borrow();
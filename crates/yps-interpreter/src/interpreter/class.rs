@@ -182,6 +182,7 @@ impl Interpreter {
                         env: self.env.snapshot(),
                         is_generator: false,
                         is_async: false,
+                        is_arrow: false,
                     }));
                     let (decorated, inits) = self.apply_member_decorators(
                         method_fn,
@@ -216,6 +217,7 @@ impl Interpreter {
                         env: self.env.snapshot(),
                         is_generator: false,
                         is_async: false,
+                        is_arrow: false,
                     }));
                     let (decorated, inits) = self.apply_member_decorators(
                         getter_fn,
@@ -250,6 +252,7 @@ impl Interpreter {
                         env: self.env.snapshot(),
                         is_generator: false,
                         is_async: false,
+                        is_arrow: false,
                     }));
                     let (decorated, inits) = self.apply_member_decorators(
                         setter_fn,
@@ -442,6 +445,8 @@ impl Interpreter {
             self.env.push_scope();
 
             self.env.define(symbols::THIS.to_string(), instance_val.clone(), false);
+            self.env.define(symbols::NEW_TARGET.to_string(), class_val.clone(), false);
+            self.bind_arguments(params, body, &args);
 
             if let Some(parent) = &class_def.parent {
                 self.env.define(symbols::SUPER.to_string(), Value::Class(Rc::clone(parent)), false);
@@ -464,7 +469,7 @@ impl Interpreter {
 
             self.bind_params(params, &args, false, span)?;
 
-            self.push_frame(Rc::from(class_def.name.as_str()), span);
+            self.push_frame(Rc::from(class_def.name.as_str()), span, body.span);
             let mut result = self.exec_block_stmts(&body.stmts);
             if let Err(e) = &mut result {
                 e.attach_stack(self.snapshot_stack());
@@ -533,6 +538,12 @@ impl Interpreter {
         self.env = Environment::from_snapshot(env, self.env.registry());
         self.env.push_scope();
         self.env.define(symbols::THIS.to_string(), child_instance.clone(), false);
+        if let Value::Object(map) = &child_instance
+            && let Some(target) = map.borrow().get(symbols::PROTO)
+        {
+            self.env.define(symbols::NEW_TARGET.to_string(), target.clone(), false);
+        }
+        self.bind_arguments(&params, &body, &args);
         if let Some(grandparent) = &parent_def.parent {
             self.env.define(symbols::SUPER.to_string(), Value::Class(Rc::clone(grandparent)), false);
         }
@@ -31,11 +31,9 @@ impl Interpreter {
                     return Ok(Value::Boolean(removed));
                 }
                 match &obj {
-                    Value::Object(map) => {
-                        if map.borrow().can_delete() {
-                            let key = idx.to_string();
-                            map.borrow_mut().shift_remove(&key);
-                        }
+                    Value::Object(map) if map.borrow().can_delete() => {
+                        let key = idx.to_string();
+                        map.borrow_mut().shift_remove(&key);
                     }
                     Value::Array(arr) => {
                         if let Value::Number(n) = idx
@@ -0,0 +1,128 @@
+use std::rc::Rc;
+
+use yps_lexer::{Lexer, SourceFile, Span};
+use yps_parser::Parser;
+use yps_parser::ast::{Program, Stmt};
+
+use crate::environment::Environment;
+use crate::error::RuntimeError;
+use crate::resolver::RootResolution;
+use crate::value::Value;
+
+use super::Interpreter;
+
+pub const EVAL: &str = "ебал";
+pub const FUNCTION_CTOR: &str = "Функция";
+
+/// Parses code given to `ебал` or built by `Функция`. As in the REPL, the last statement of the
+/// code or of a block needs no `;`. The error is the first lex or parse error.
+pub fn parse_code(what: &str, text: &str) -> Result<Program, String> {
+    let source = SourceFile::new(format!("<{what}>"), text.to_string());
+    let (tokens, lex_errors) = Lexer::new(&source).tokenize();
+    let (program, parse_errors) = Parser::new(&tokens, &source).with_optional_semicolons().parse_program();
+    match lex_errors.iter().chain(&parse_errors).next() {
+        Some(first) => Err(format!("Ошибка разбора в '{what}': {}", first.message)),
+        None => Ok(program),
+    }
+}
+
+/// The source of `Функция(...params, body)`: an anonymous function expression.
+pub fn function_source(params: &[String], body: &str) -> String {
+    format!("(йопта анонимный({}\n) {{\n{body}\n}})", params.join(", "))
+}
+
+impl Interpreter {
+    /// Hosts that run untrusted programs switch this off: `ебал` and `Функция` then throw instead
+    /// of turning strings into code.
+    pub fn set_allow_eval(&mut self, allow: bool) {
+        self.allow_eval = allow;
+    }
+
+    /// Whether strings may be compiled; workers started from this run inherit it.
+    #[must_use]
+    pub fn allow_eval(&self) -> bool {
+        self.allow_eval
+    }
+
+    pub(super) fn try_call_code_builtin(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+        span: Span,
+    ) -> Option<Result<Value, RuntimeError>> {
+        match name {
+            EVAL => Some(self.eval_code(args.into_iter().next().unwrap_or(Value::Undefined), false, span)),
+            FUNCTION_CTOR => Some(self.function_constructor(args, span)),
+            _ => None,
+        }
+    }
+
+    /// `ебал(код)`: a direct call sees the caller's scope, any other call only the global one.
+    /// Declarations stay inside the evaluated code; anything but a string comes back unchanged.
+    pub(super) fn eval_code(&mut self, code: Value, direct: bool, span: Span) -> Result<Value, RuntimeError> {
+        let Value::String(text) = code else {
+            return Ok(code);
+        };
+        let program = self.parse_code(EVAL, &text, span)?;
+        if direct { self.run_code(&program, span) } else { self.in_global_scope(|this| this.run_code(&program, span)) }
+    }
+
+    /// `Функция("а", "б", "отвечаю а + б")`: the last argument is the body, the others name the
+    /// parameters. The function closes over the global scope only.
+    fn function_constructor(&mut self, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+        let (body, params) = match args.split_last() {
+            Some((body, params)) => (body.to_string(), params.iter().map(Value::to_string).collect::<Vec<_>>()),
+            None => (String::new(), Vec::new()),
+        };
+        let program = self.parse_code(FUNCTION_CTOR, &function_source(&params, &body), span)?;
+        self.in_global_scope(|this| this.run_code(&program, span))
+    }
+
+    fn parse_code(&mut self, what: &str, text: &str, span: Span) -> Result<Program, RuntimeError> {
+        if !self.allow_eval {
+            return Err(RuntimeError::new(format!("'{what}' запрещён: выполнение кода из строки отключено"), span));
+        }
+        let program = parse_code(what, text).map_err(|message| RuntimeError::new(message, span))?;
+        // Spans of the new code overlap those of the program, so root reads can no longer be told apart.
+        self.resolution = RootResolution::default();
+        Ok(program)
+    }
+
+    fn in_global_scope<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let global = Environment::from_snapshot(Rc::clone(&self.global_root), self.env.registry());
+        let saved = std::mem::replace(&mut self.env, global);
+        let result = f(self);
+        self.env = saved;
+        result
+    }
+
+    /// Runs the code in a scope of its own and returns the value of its last expression statement.
+    /// Errors without a thrown value point into the evaluated text, so they are moved to the call.
+    fn run_code(&mut self, program: &Program, span: Span) -> Result<Value, RuntimeError> {
+        self.env.push_scope();
+        self.env.mark_tdz(crate::resolver::lexical_declarations(&program.items));
+        let result = self.run_code_items(&program.items, span);
+        self.env.pop_scope();
+        result.map_err(|mut err| {
+            if err.thrown.is_none() {
+                err.span = span;
+            }
+            err
+        })
+    }
+
+    fn run_code_items(&mut self, items: &[Stmt], span: Span) -> Result<Value, RuntimeError> {
+        self.hoist_functions(items);
+        let mut last = Value::Undefined;
+        for stmt in items {
+            if let Stmt::Expr { expr, .. } = stmt {
+                last = self.eval_expr(expr)?;
+                continue;
+            }
+            if let Some(flow) = self.exec_stmt(stmt)? {
+                return self.finish_call(Some(flow), self.snapshot_stack(), span);
+            }
+        }
+        Ok(last)
+    }
+}
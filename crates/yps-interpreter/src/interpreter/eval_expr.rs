@@ -7,12 +7,14 @@ use yps_parser::ast::{BinaryOp, Expr, Literal, ObjectEntry, Param, PropKey, Temp
 
 use crate::environment::{Environment, Lookup};
 use crate::error::RuntimeError;
+use crate::heap_snapshot;
 use crate::symbols;
 use crate::value::{ClassDef, FunctionData, MethodDef, RegExpData, Value, to_int_n, to_uint_n};
 
 use super::Interpreter;
 use super::call::RelOp;
 use super::coercion;
+use super::eval_code::EVAL;
 
 impl Interpreter {
     pub(super) fn eval_expr(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
@@ -109,6 +111,9 @@ impl Interpreter {
     }
 
     fn eval_expr_inner(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
+        if self.coverage {
+            crate::coverage::hit(self.env.file(), expr.span(), crate::coverage::EXPR_SITE);
+        }
         match expr {
             Expr::Literal(lit) => self.eval_literal(lit),
             Expr::Identifier(ident) => match self.lookup_read(ident) {
@@ -117,9 +122,7 @@ impl Interpreter {
                     format!("Обращение к переменной '{}' до её инициализации", ident.name),
                     ident.span,
                 )),
-                Lookup::Missing => {
-                    Err(RuntimeError::new(format!("Переменная '{}' не определена", ident.name), ident.span))
-                }
+                Lookup::Missing => Err(self.undefined_variable(&ident.name, ident.span)),
             },
             Expr::Unary { op, expr, span } => {
                 if *op == UnaryOp::Typeof {
@@ -263,6 +266,7 @@ impl Interpreter {
                         let arg_values = self.eval_args(args)?;
                         let this_val = self.env.get(symbols::THIS);
                         let grandparent = cls.parent.clone();
+                        self.pending_new_target = self.env.get(symbols::NEW_TARGET);
                         return self.call_method_with_this_super(
                             Rc::from("<конструктор>"),
                             params,
@@ -278,6 +282,13 @@ impl Interpreter {
                 } else {
                     let func = self.eval_expr(callee)?;
                     let arg_values = self.eval_args(args)?;
+                    if let Expr::Identifier(ident) = callee.as_ref()
+                        && ident.name == EVAL
+                        && matches!(&func, Value::BuiltinFunction(name) if name == EVAL)
+                    {
+                        let code = arg_values.into_iter().next().unwrap_or(Value::Undefined);
+                        return self.eval_code(code, true, *span);
+                    }
                     self.call_function(func, arg_values, *span)
                 }
             }
@@ -320,7 +331,7 @@ impl Interpreter {
                 let cond = self.eval_expr(condition)?;
                 if cond.is_truthy() { self.eval_expr(then_expr) } else { self.eval_expr(else_expr) }
             }
-            Expr::ArrowFunction { params, body, is_async, .. } => {
+            Expr::ArrowFunction { params, body, is_async, span, .. } => {
                 let func = Value::Function(Rc::new(FunctionData {
                     name: Rc::from(""),
                     params: params.clone(),
@@ -328,10 +339,12 @@ impl Interpreter {
                     env: self.env.snapshot(),
                     is_generator: false,
                     is_async: *is_async,
+                    is_arrow: true,
                 }));
+                heap_snapshot::record_allocation(&func, self.env.file(), *span);
                 Ok(func)
             }
-            Expr::FunctionExpr { name, params, body, is_generator, is_async, .. } => match name {
+            Expr::FunctionExpr { name, params, body, is_generator, is_async, span, .. } => match name {
                 Some(ident) => {
                     let mut fn_env = Environment::from_snapshot(self.env.snapshot(), self.env.registry());
                     fn_env.push_scope();
@@ -342,23 +355,31 @@ impl Interpreter {
                         env: fn_env.snapshot(),
                         is_generator: *is_generator,
                         is_async: *is_async,
+                        is_arrow: false,
                     }));
                     fn_env.define(ident.name.clone(), func.clone(), false);
+                    heap_snapshot::record_allocation(&func, self.env.file(), *span);
+                    Ok(func)
+                }
+                None => {
+                    let func = Value::Function(Rc::new(FunctionData {
+                        name: Rc::from(""),
+                        params: params.clone(),
+                        body: body.clone(),
+                        env: self.env.snapshot(),
+                        is_generator: *is_generator,
+                        is_async: *is_async,
+                        is_arrow: false,
+                    }));
+                    heap_snapshot::record_allocation(&func, self.env.file(), *span);
                     Ok(func)
                 }
-                None => Ok(Value::Function(Rc::new(FunctionData {
-                    name: Rc::from(""),
-                    params: params.clone(),
-                    body: body.clone(),
-                    env: self.env.snapshot(),
-                    is_generator: *is_generator,
-                    is_async: *is_async,
-                }))),
             },
             Expr::Await { argument, span } => {
                 let val = self.eval_expr(argument)?;
                 self.do_await(val, *span)
             }
+            Expr::Match { subject, arms, span } => self.eval_match(subject, arms, *span),
             Expr::TemplateLiteral { parts, .. } => {
                 let mut result = String::new();
                 for part in parts {
@@ -399,6 +420,7 @@ impl Interpreter {
                 "Оператор '...' допустим только в массивах, объектах или аргументах вызова",
                 *span,
             )),
+            Expr::Error { span } => Err(RuntimeError::new("Синтаксическая ошибка в этом месте", *span)),
             Expr::This { span } => self
                 .env
                 .get(symbols::THIS)
@@ -406,8 +428,12 @@ impl Interpreter {
             Expr::New { callee, args, span } => {
                 let class_val = self.eval_expr(callee)?;
                 let arg_values = self.eval_args(args)?;
-                self.construct_instance(class_val, arg_values, *span)
+                let instance = self.construct_instance(class_val, arg_values, *span)?;
+                heap_snapshot::record_allocation(&instance, self.env.file(), *span);
+                Ok(instance)
             }
+            Expr::NewTarget { .. } => Ok(self.env.get(symbols::NEW_TARGET).unwrap_or(Value::Undefined)),
+            Expr::ImportMeta { .. } => Ok(self.import_meta()),
             Expr::Super { span } => self
                 .env
                 .get(symbols::SUPER)
@@ -459,7 +485,7 @@ impl Interpreter {
             Literal::Boolean { value, .. } => Ok(Value::Boolean(*value)),
             Literal::Null { .. } => Ok(Value::Null),
             Literal::Undefined { .. } => Ok(Value::Undefined),
-            Literal::Array { elements, .. } => {
+            Literal::Array { elements, span } => {
                 let mut values = Vec::with_capacity(elements.len());
                 for el in elements {
                     if let Expr::Spread { expr, span } = el {
@@ -507,7 +533,9 @@ impl Interpreter {
                         values.push(self.eval_expr(el)?);
                     }
                 }
-                Ok(Value::array(values))
+                let array = Value::array(values);
+                heap_snapshot::record_allocation(&array, self.env.file(), *span);
+                Ok(array)
             }
             Literal::Object { entries, span } => {
                 let mut map = IndexMap::new();
@@ -555,6 +583,7 @@ impl Interpreter {
                                 env: self.env.snapshot(),
                                 is_generator: false,
                                 is_async: false,
+                                is_arrow: false,
                             }));
                             map.insert(symbols::getter_key(&key_str), getter_fn);
                         }
@@ -562,17 +591,20 @@ impl Interpreter {
                             let key_str = self.eval_prop_key(key)?;
                             let setter_fn = Value::Function(Rc::new(FunctionData {
                                 name: Rc::from(format!("set {key_str}").as_str()),
-                                params: Rc::from([param.clone()]),
+                                params: Rc::from([(**param).clone()]),
                                 body: Rc::new(body.clone()),
                                 env: self.env.snapshot(),
                                 is_generator: false,
                                 is_async: false,
+                                is_arrow: false,
                             }));
                             map.insert(symbols::setter_key(&key_str), setter_fn);
                         }
                     }
                 }
-                Ok(Value::object(map))
+                let object = Value::object(map);
+                heap_snapshot::record_allocation(&object, self.env.file(), *span);
+                Ok(object)
             }
             Literal::RegExp { pattern, flags, span } => {
                 let compiled = crate::stdlib::regexp::compile(pattern, flags, *span)?;
@@ -1,14 +1,16 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use indexmap::IndexMap;
 
 use yps_lexer::Span;
-use yps_parser::ast::{Block, ExportKind, Expr, Identifier, ImportSpec, Pattern, Stmt};
+use yps_parser::ast::{Block, EnumMember, ExportKind, Expr, Identifier, ImportSpec, Pattern, Stmt};
 
 use crate::error::RuntimeError;
 use crate::symbols;
-use crate::value::{FunctionData, Value};
+use crate::value::{FunctionData, ObjectStore, Value};
 
+use super::coercion::number_to_string;
 use super::{ControlFlow, Interpreter, LoopOp};
 
 impl Interpreter {
@@ -20,9 +22,17 @@ impl Interpreter {
         if self.debug_hook.is_some() {
             self.debug_before_stmt(stmt.span())?;
         }
+        if self.coverage {
+            crate::coverage::hit(self.env.file(), stmt.span(), crate::coverage::STMT_SITE);
+        }
+        if let Some(flag) = &self.interrupt
+            && flag.load(std::sync::atomic::Ordering::Relaxed)
+        {
+            return Err(RuntimeError::new(crate::worker::WORKER_TERMINATED, stmt.span()));
+        }
         let incoming_label = self.pending_label.take();
         match stmt {
-            Stmt::VarDecl { pattern, init, is_const, span } => {
+            Stmt::VarDecl { pattern, init, is_const, span, .. } => {
                 let value = self.eval_expr(init)?;
                 self.destructure_pattern(pattern, value, *is_const, *span)?;
                 Ok(None)
@@ -32,7 +42,7 @@ impl Interpreter {
                 Ok(None)
             }
             Stmt::Block(block) => self.exec_block(block),
-            Stmt::Empty { .. } => Ok(None),
+            Stmt::Empty { .. } | Stmt::TypeAlias { .. } => Ok(None),
             Stmt::If { condition, then_branch, else_branch, .. } => {
                 let cond = self.eval_expr(condition)?;
                 if cond.is_truthy() {
@@ -103,7 +113,7 @@ impl Interpreter {
                     other => Ok(other),
                 }
             }
-            Stmt::FunctionDecl { name, params, body, is_generator, is_async, .. } => {
+            Stmt::FunctionDecl { name, params, body, is_generator, is_async, span, .. } => {
                 let func = Value::Function(Rc::new(FunctionData {
                     name: Rc::from(name.name.as_str()),
                     params: params.clone(),
@@ -111,7 +121,9 @@ impl Interpreter {
                     env: self.env.snapshot(),
                     is_generator: *is_generator,
                     is_async: *is_async,
+                    is_arrow: false,
                 }));
+                crate::heap_snapshot::record_allocation(&func, self.env.file(), *span);
                 self.env.define(name.name.clone(), func, false);
                 Ok(None)
             }
@@ -203,6 +215,11 @@ impl Interpreter {
             Stmt::ClassDecl { name, super_class, members, decorators, span } => {
                 self.exec_class_decl(name, super_class.as_ref(), members, decorators, *span)
             }
+            Stmt::EnumDecl { name, members, .. } => {
+                let value = self.eval_enum(members)?;
+                self.env.define(name.name.clone(), value, true);
+                Ok(None)
+            }
             Stmt::TryCatch { try_block, catch_param, catch_block, finally_block, .. } => {
                 let stack_depth = self.call_stack.len();
                 let try_result = self.exec_block(try_block);
@@ -257,6 +274,7 @@ impl Interpreter {
                 result
             }
             Stmt::Debugger { .. } => Ok(None),
+            Stmt::Error { span } => Err(RuntimeError::new("Синтаксическая ошибка в этом месте", *span)),
             Stmt::Using { name, init, is_await, span } => {
                 let value = self.eval_expr(init)?;
                 if !matches!(value, Value::Null | Value::Undefined) {
@@ -346,6 +364,34 @@ impl Interpreter {
         }
     }
 
+    /// Builds the frozen object of an `еээ`: names map to values and numeric values map back to names.
+    fn eval_enum(&mut self, members: &[EnumMember]) -> Result<Value, RuntimeError> {
+        let mut map = IndexMap::new();
+        let mut next = Some(0.0);
+        for member in members {
+            let value = match (&member.init, next) {
+                (Some(init), _) => self.eval_expr(init)?,
+                (None, Some(n)) => Value::Number(n),
+                (None, None) => {
+                    let message =
+                        format!("Элементу '{}' нужно значение: предыдущий элемент не число", member.name.name);
+                    return Err(RuntimeError::new(message, member.span));
+                }
+            };
+            next = match value {
+                Value::Number(n) => Some(n + 1.0),
+                _ => None,
+            };
+            if let Value::Number(n) = value {
+                map.insert(number_to_string(n), Value::String(member.name.name.clone().into()));
+            }
+            map.insert(member.name.name.clone(), value);
+        }
+        let mut store = ObjectStore::new(map);
+        store.freeze();
+        Ok(Value::Object(Rc::new(RefCell::new(store))))
+    }
+
     fn run_catch(
         &mut self,
         catch_block: &Block,
@@ -395,6 +441,7 @@ impl Interpreter {
                     env: self.env.snapshot(),
                     is_generator: *is_generator,
                     is_async: *is_async,
+                    is_arrow: false,
                 }));
                 self.env.define(name.name.clone(), func, false);
             }
@@ -695,7 +742,9 @@ fn collect_decl_names(stmt: &Stmt) -> Vec<String> {
             collect_pattern_names(pattern, &mut names);
             names
         }
-        Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } => vec![name.name.clone()],
+        Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } | Stmt::EnumDecl { name, .. } => {
+            vec![name.name.clone()]
+        }
         _ => Vec::new(),
     }
 }
@@ -741,7 +790,7 @@ mod tests {
         let src = r#"
             гыы r = "нет";
             базарпо (нихуя) {
-                тема (нихуя): r = "есть";
+                тема (нихуя): { r = "есть"; }
             }
             r;
         "#;
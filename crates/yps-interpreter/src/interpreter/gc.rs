@@ -41,6 +41,9 @@ impl Interpreter {
         for root in self.macrotasks.roots() {
             marker.push_root(root);
         }
+        for port in crate::worker::ports() {
+            marker.push_value(&Value::Worker(port));
+        }
         marker.run();
 
         let registry = self.env.registry();
@@ -239,7 +242,12 @@ impl Marker {
                     }
                 }
             }
-            Value::WeakSet(_) | Value::WeakRef(_) => {}
+            Value::WeakSet(_) | Value::WeakRef(_) | Value::TextCodec(_) | Value::Intl(_) | Value::Temporal(_) => {}
+            Value::Worker(port) => {
+                for handler in port.handlers() {
+                    self.push_value(&handler);
+                }
+            }
             Value::FinalizationRegistry(rc) => {
                 if self.seen.insert(Rc::as_ptr(rc) as usize) {
                     let state = rc.borrow();
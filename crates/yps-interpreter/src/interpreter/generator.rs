@@ -53,6 +53,7 @@ pub(crate) fn build_generator(name: Rc<str>, env: Environment, body: &Rc<Block>,
     };
     GenState {
         name,
+        decl: body.span,
         env,
         frames: vec![GenFrame::Block { stmts, idx: 0, owns_scope: false, label: None }],
         completed: false,
@@ -80,7 +81,7 @@ pub(crate) fn step_generator(
 
     let saved_env = std::mem::replace(&mut interp.env, g.env.clone());
     let saved_stack = std::mem::take(&mut interp.call_stack);
-    interp.push_frame(Rc::clone(&g.name), span);
+    interp.push_frame(Rc::clone(&g.name), span, g.decl);
     let mut result = match input {
         GenInput::Send(v) => {
             if std::mem::take(&mut g.pending_return) {
@@ -521,7 +522,7 @@ fn step_block_stmt(
                 Ok(Some(GenStep::Yielded(val)))
             }
         }
-        Stmt::VarDecl { pattern, init, is_const, span: vs } => {
+        Stmt::VarDecl { pattern, init, is_const, span: vs, .. } => {
             if let Expr::Await { argument, .. } = init
                 && g.is_async
             {
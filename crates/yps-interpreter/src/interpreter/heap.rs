@@ -0,0 +1,298 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::environment::EnvFrame;
+use crate::heap_snapshot::{EdgeType, GC_ROOTS, HeapGraph, NodeType};
+use crate::profile::frame_name;
+use crate::symbols;
+use crate::value::{ClassDef, PromiseState, Value};
+
+use super::Interpreter;
+use super::gc::GcRoot;
+
+const CONTEXT_NODE: &str = "(контекст)";
+
+impl Interpreter {
+    /// Heap graph reachable from the same roots `collect_cycles` marks.
+    #[must_use]
+    pub fn heap_snapshot(&self) -> HeapGraph {
+        let mut walker = Walker { graph: HeapGraph::new(), work: Vec::new() };
+        let mut index = 0;
+        let mut root = |walker: &mut Walker, to: Option<usize>| {
+            if let Some(to) = to {
+                walker.graph.add_element(GC_ROOTS, index, to);
+                index += 1;
+            }
+        };
+
+        let scope = walker.frame(&self.env.snapshot());
+        root(&mut walker, Some(scope));
+        for value in &self.pending_initializers {
+            let node = walker.value(value);
+            root(&mut walker, node);
+        }
+        for value in self.current_exports.values() {
+            let node = walker.value(value);
+            root(&mut walker, node);
+        }
+        for module in self.module_cache.borrow().values() {
+            let mut exports = Vec::new();
+            module.for_each_export_value(|value| exports.push(value.clone()));
+            for value in &exports {
+                let node = walker.value(value);
+                root(&mut walker, node);
+            }
+        }
+        let tasks = self.microtasks.iter().flat_map(|task| task.roots.iter()).chain(self.macrotasks.roots());
+        for GcRoot::Value(value) in tasks {
+            let node = walker.value(value);
+            root(&mut walker, node);
+        }
+        walker.run();
+        walker.graph
+    }
+}
+
+enum Expand {
+    Value(Value),
+    Frame(Rc<RefCell<EnvFrame>>),
+    Class(Rc<ClassDef>),
+    Promise(Rc<RefCell<PromiseState>>),
+}
+
+struct Walker {
+    graph: HeapGraph,
+    work: Vec<(usize, Expand)>,
+}
+
+impl Walker {
+    fn run(&mut self) {
+        while let Some((node, item)) = self.work.pop() {
+            match item {
+                Expand::Value(value) => self.expand_value(node, &value),
+                Expand::Frame(frame) => self.expand_frame(node, &frame),
+                Expand::Class(class) => self.expand_class(node, &class),
+                Expand::Promise(state) => self.expand_promise(node, &state),
+            }
+        }
+    }
+
+    fn frame(&mut self, frame: &Rc<RefCell<EnvFrame>>) -> usize {
+        let (node, fresh) = self.graph.node_for(Rc::as_ptr(frame) as usize, NodeType::Hidden, CONTEXT_NODE, 0);
+        if fresh {
+            self.work.push((node, Expand::Frame(Rc::clone(frame))));
+        }
+        node
+    }
+
+    fn class(&mut self, class: &Rc<ClassDef>) -> usize {
+        let (node, fresh) = self.graph.node_for(Rc::as_ptr(class) as usize, NodeType::Closure, &class.name, 0);
+        if fresh {
+            self.work.push((node, Expand::Class(Rc::clone(class))));
+        }
+        node
+    }
+
+    fn promise(&mut self, state: &Rc<RefCell<PromiseState>>) -> usize {
+        let (node, fresh) = self.graph.node_for(Rc::as_ptr(state) as usize, NodeType::Object, "Обещание", 0);
+        if fresh {
+            self.work.push((node, Expand::Promise(Rc::clone(state))));
+        }
+        node
+    }
+
+    /// Node for a heap value; `None` for primitives that live inline.
+    fn value(&mut self, value: &Value) -> Option<usize> {
+        let (ptr, kind, name, size) = match value {
+            Value::String(s) => (Rc::as_ptr(s).cast::<u8>() as usize, NodeType::String, s.to_string(), s.len()),
+            Value::Array(rc) => (Rc::as_ptr(rc) as usize, NodeType::Object, "Массив".to_string(), rc.borrow().len()),
+            Value::Object(rc) => {
+                let store = rc.borrow();
+                let name = match store.map.get(symbols::CLASS_TAG) {
+                    Some(Value::String(class)) => class.to_string(),
+                    _ => "Объект".to_string(),
+                };
+                (Rc::as_ptr(rc) as usize, NodeType::Object, name, store.map.len())
+            }
+            Value::Map(rc) => (Rc::as_ptr(rc) as usize, NodeType::Object, "Карта".to_string(), rc.borrow().len()),
+            Value::Set(rc) => (Rc::as_ptr(rc) as usize, NodeType::Object, "Множество".to_string(), rc.borrow().len()),
+            Value::Function(func) => {
+                (Rc::as_ptr(func) as usize, NodeType::Closure, frame_name(&func.name).to_string(), func.params.len())
+            }
+            Value::Class(class) => return Some(self.class(class)),
+            Value::Promise { state } | Value::PromiseCapability { state, .. } => return Some(self.promise(state)),
+            Value::Number(_)
+            | Value::BigInt(_)
+            | Value::Boolean(_)
+            | Value::Symbol { .. }
+            | Value::Undefined
+            | Value::Null
+            | Value::WeakClass(_)
+            | Value::BuiltinFunction(_)
+            | Value::AbortCancelTimer { .. } => return None,
+            _ => {
+                let node = self.graph.add_node(NodeType::Native, value.type_name(), 0);
+                self.work.push((node, Expand::Value(value.clone())));
+                return Some(node);
+            }
+        };
+        let (node, fresh) = self.graph.node_for(ptr, kind, &name, size);
+        if fresh {
+            self.work.push((node, Expand::Value(value.clone())));
+        }
+        Some(node)
+    }
+
+    fn edge(&mut self, from: usize, kind: EdgeType, name: &str, value: &Value) {
+        if let Some(to) = self.value(value) {
+            self.graph.add_edge(from, kind, name, to);
+        }
+    }
+
+    fn element(&mut self, from: usize, index: usize, value: &Value) {
+        if let Some(to) = self.value(value) {
+            self.graph.add_element(from, index, to);
+        }
+    }
+
+    fn expand_value(&mut self, node: usize, value: &Value) {
+        match value {
+            Value::Array(rc) => {
+                let elements: Vec<Value> = rc.borrow().iter().cloned().collect();
+                for (i, element) in elements.iter().enumerate() {
+                    self.element(node, i, element);
+                }
+            }
+            Value::Object(rc) => {
+                let entries: Vec<(String, Value)> =
+                    rc.borrow().map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                for (key, value) in &entries {
+                    let kind = if key == symbols::PROTO || key == symbols::CLASS_TAG {
+                        EdgeType::Internal
+                    } else {
+                        EdgeType::Property
+                    };
+                    self.edge(node, kind, key, value);
+                }
+            }
+            Value::Map(rc) => {
+                let entries: Vec<(Value, Value)> =
+                    rc.borrow().iter().map(|(k, v)| (k.as_value().clone(), v.clone())).collect();
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    self.element(node, 2 * i, key);
+                    self.element(node, 2 * i + 1, value);
+                }
+            }
+            Value::Set(rc) => {
+                let elements: Vec<Value> = rc.borrow().iter().map(|v| v.as_value().clone()).collect();
+                for (i, element) in elements.iter().enumerate() {
+                    self.element(node, i, element);
+                }
+            }
+            Value::Function(func) => {
+                let context = self.frame(&func.env);
+                self.graph.add_edge(node, EdgeType::Internal, "context", context);
+            }
+            Value::BoundMethod { receiver, .. } => self.edge(node, EdgeType::Internal, "receiver", receiver),
+            Value::PromiseThenHandler(data) => {
+                self.edge(node, EdgeType::Internal, "handler", &data.handler);
+                self.edge(node, EdgeType::Internal, "resolve", &data.resolve);
+                self.edge(node, EdgeType::Internal, "reject", &data.reject);
+            }
+            Value::PromiseFinallyHandler { cb, cap } => {
+                self.edge(node, EdgeType::Internal, "callback", cb);
+                self.edge(node, EdgeType::Internal, "capability", cap);
+            }
+            Value::AsyncResume(data) => {
+                let outer = self.promise(&data.outer);
+                self.graph.add_edge(node, EdgeType::Internal, "promise", outer);
+                let context = self.frame(&data.coroutine.borrow().env.snapshot());
+                self.graph.add_edge(node, EdgeType::Context, "context", context);
+            }
+            Value::AbortRejectPromise { reject_cap, .. } => self.edge(node, EdgeType::Internal, "reject", reject_cap),
+            Value::Proxy { target, handler } => {
+                self.edge(node, EdgeType::Internal, "target", target);
+                self.edge(node, EdgeType::Internal, "handler", handler);
+            }
+            Value::WeakMap(rc) => {
+                let values: Vec<Value> = rc.borrow().values().map(|(_, v)| v.clone()).collect();
+                for (i, value) in values.iter().enumerate() {
+                    self.element(node, i, value);
+                }
+            }
+            Value::FinalizationRegistry(rc) => {
+                let state = rc.borrow();
+                let (callback, held): (Value, Vec<Value>) =
+                    (state.callback.clone(), state.entries.iter().map(|e| e.held.clone()).collect());
+                drop(state);
+                self.edge(node, EdgeType::Internal, "callback", &callback);
+                for (i, value) in held.iter().enumerate() {
+                    self.element(node, i, value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn expand_frame(&mut self, node: usize, frame: &Rc<RefCell<EnvFrame>>) {
+        let (bindings, parent) = {
+            let frame = frame.borrow();
+            (frame.debug_bindings(), frame.debug_parent())
+        };
+        for (name, value) in &bindings {
+            self.edge(node, EdgeType::Context, name, value);
+        }
+        if let Some(parent) = parent {
+            let parent = self.frame(&parent);
+            self.graph.add_edge(node, EdgeType::Internal, "previous", parent);
+        }
+    }
+
+    fn expand_class(&mut self, node: usize, class: &ClassDef) {
+        let methods = class
+            .constructor
+            .iter()
+            .map(|m| ("constructor", m))
+            .chain(class.methods.iter().map(|(k, m)| (k.as_str(), m)))
+            .chain(class.static_methods.iter().map(|(k, m)| (k.as_str(), m)))
+            .chain(class.getters.iter().map(|(k, m)| (k.as_str(), m)))
+            .chain(class.setters.iter().map(|(k, m)| (k.as_str(), m)))
+            .chain(class.static_getters.iter().map(|(k, m)| (k.as_str(), m)))
+            .chain(class.static_setters.iter().map(|(k, m)| (k.as_str(), m)));
+        let contexts: Vec<(String, Rc<RefCell<EnvFrame>>)> =
+            methods.map(|(name, m)| (name.to_string(), Rc::clone(&m.env))).collect();
+        for (name, env) in &contexts {
+            let context = self.frame(env);
+            self.graph.add_edge(node, EdgeType::Context, name, context);
+        }
+        let statics: Vec<(String, Value)> =
+            class.static_fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        for (name, value) in &statics {
+            self.edge(node, EdgeType::Property, name, value);
+        }
+        for (name, _, default) in &class.field_inits {
+            if let Some(value) = default {
+                self.edge(node, EdgeType::Internal, name, value);
+            }
+        }
+        for (i, value) in class.instance_initializers.iter().enumerate() {
+            self.element(node, i, value);
+        }
+        if let Some(parent) = &class.parent {
+            let parent = self.class(parent);
+            self.graph.add_edge(node, EdgeType::Internal, "parent", parent);
+        }
+    }
+
+    fn expand_promise(&mut self, node: usize, state: &Rc<RefCell<PromiseState>>) {
+        let (values, name): (Vec<Value>, &str) = match &*state.borrow() {
+            PromiseState::Pending { on_resolve, on_reject } => {
+                (on_resolve.iter().chain(on_reject.iter()).cloned().collect(), "reaction")
+            }
+            PromiseState::Fulfilled(value) | PromiseState::Rejected(value) => (vec![value.clone()], "value"),
+        };
+        for value in &values {
+            self.edge(node, EdgeType::Internal, name, value);
+        }
+    }
+}
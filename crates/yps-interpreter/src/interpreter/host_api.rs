@@ -84,7 +84,7 @@ impl Interpreter {
                 };
                 if n.is_finite() && n >= 0.0 && n.fract() == 0.0 {
                     let i = n as usize;
-                    if i < ta.length {
+                    if i < ta.live_length() {
                         crate::stdlib::typed_array::write_element(
                             &ta.buffer,
                             ta.kind,
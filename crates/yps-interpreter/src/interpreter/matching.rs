@@ -0,0 +1,96 @@
+use yps_lexer::Span;
+use yps_parser::ast::{BinaryOp, Expr, MatchArm, MatchPattern, Pattern};
+
+use crate::error::RuntimeError;
+use crate::value::Value;
+
+use super::Interpreter;
+
+impl Interpreter {
+    pub(super) fn eval_match(&mut self, subject: &Expr, arms: &[MatchArm], span: Span) -> Result<Value, RuntimeError> {
+        let value = self.eval_expr(subject)?;
+        for arm in arms {
+            let pattern = match &arm.pattern {
+                MatchPattern::Wildcard { .. } => None,
+                MatchPattern::Bind(pattern) => Some(pattern),
+                MatchPattern::Value(expected) => {
+                    if self.eval_expr(expected)? != value {
+                        continue;
+                    }
+                    None
+                }
+                MatchPattern::Instance { class, pattern, span } => {
+                    let class = self.eval_expr(class)?;
+                    if !self.eval_binary(BinaryOp::Instanceof, value.clone(), class, *span)?.is_truthy() {
+                        continue;
+                    }
+                    pattern.as_ref()
+                }
+            };
+            if pattern.is_some_and(|pattern| !fits(pattern, &value)) {
+                continue;
+            }
+            self.env.push_scope();
+            let result = self.eval_arm(arm, pattern, &value);
+            self.env.pop_scope();
+            if let Some(result) = result? {
+                return Ok(result);
+            }
+        }
+        Err(RuntimeError::new(
+            format!("Ни одна ветка 'базарпо' не подошла для значения типа '{}'", value.type_name()),
+            span,
+        ))
+    }
+
+    /// Binds the arm's names in the current scope; `None` when the guard rejects the value.
+    fn eval_arm(
+        &mut self,
+        arm: &MatchArm,
+        pattern: Option<&Pattern>,
+        value: &Value,
+    ) -> Result<Option<Value>, RuntimeError> {
+        if let Some(pattern) = pattern {
+            self.destructure_pattern(pattern, value.clone(), false, arm.span)?;
+        }
+        if let Some(guard) = &arm.guard
+            && !self.eval_expr(guard)?.is_truthy()
+        {
+            return Ok(None);
+        }
+        self.eval_expr(&arm.body).map(Some)
+    }
+}
+
+/// Whether `value` has the shape `pattern` destructures: arrays of the right length and
+/// objects that own every named key. Missing values are fine where the pattern has a default.
+fn fits(pattern: &Pattern, value: &Value) -> bool {
+    match pattern {
+        Pattern::Identifier(_) => true,
+        Pattern::Default { pattern, .. } => matches!(value, Value::Undefined) || fits(pattern, value),
+        Pattern::Array { elements, rest, .. } => {
+            let Value::Array(arr) = value else {
+                return false;
+            };
+            let items = arr.borrow().0.clone();
+            let length_ok = if rest.is_some() { items.len() >= elements.len() } else { items.len() == elements.len() };
+            length_ok
+                && elements.iter().zip(&items).all(|(element, item)| element.as_ref().is_none_or(|p| fits(p, item)))
+        }
+        Pattern::Object { properties, .. } => {
+            let Value::Object(map) = value else {
+                return false;
+            };
+            properties.iter().all(|prop| {
+                let item = map.borrow().get(&prop.key.name).cloned().unwrap_or(Value::Undefined);
+                match &prop.value {
+                    Some(pattern) => {
+                        (!matches!(item, Value::Undefined) || matches!(pattern, Pattern::Default { .. }))
+                            && fits(pattern, &item)
+                    }
+                    None => !matches!(item, Value::Undefined),
+                }
+            })
+        }
+    }
+}
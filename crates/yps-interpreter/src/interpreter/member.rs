@@ -154,6 +154,7 @@ impl Interpreter {
                             env: Rc::clone(env),
                             is_generator: false,
                             is_async: false,
+                            is_arrow: false,
                         })));
                     }
                 }
@@ -184,6 +185,7 @@ impl Interpreter {
                         env: Rc::clone(env),
                         is_generator: false,
                         is_async: false,
+                        is_arrow: false,
                     })));
                 }
                 Ok(Value::Undefined)
@@ -196,10 +198,12 @@ impl Interpreter {
             }
             Value::Date(_) => Ok(Value::Undefined),
             Value::TypedArray(ta) => match property {
-                "length" | "длина" => Ok(Value::Number(ta.length as f64)),
-                "byteLength" | "длинаБайт" => Ok(Value::Number((ta.length * ta.kind.element_size()) as f64)),
+                "length" | "длина" => Ok(Value::Number(ta.live_length() as f64)),
+                "byteLength" | "длинаБайт" => {
+                    Ok(Value::Number((ta.live_length() * ta.kind.element_size()) as f64))
+                }
                 "byteOffset" | "смещениеБайт" => Ok(Value::Number(ta.offset as f64)),
-                "buffer" | "область" => Ok(Value::ArrayBuffer(std::rc::Rc::clone(&ta.buffer))),
+                "buffer" | "область" => Ok(Value::ArrayBuffer(ta.buffer.clone())),
                 _ => Ok(Value::Undefined),
             },
             Value::ArrayBuffer(buffer) => match property {
@@ -209,7 +213,14 @@ impl Interpreter {
             Value::DataView { buffer, offset, length } => match property {
                 "byteLength" | "длинаБайт" => Ok(Value::Number(*length as f64)),
                 "byteOffset" | "смещениеБайт" => Ok(Value::Number(*offset as f64)),
-                "buffer" | "область" => Ok(Value::ArrayBuffer(std::rc::Rc::clone(buffer))),
+                "buffer" | "область" => Ok(Value::ArrayBuffer(buffer.clone())),
+                _ => Ok(Value::Undefined),
+            },
+            Value::Intl(formatter) => Ok(crate::stdlib::intl::get_property(formatter, property)),
+            Value::Temporal(t) => Ok(crate::stdlib::temporal::get_property(t, property)),
+            Value::TextCodec(codec) => match property {
+                "encoding" | "кодировка" => Ok(Value::String(codec.encoding.label().into())),
+                "fatal" | "фатально" if codec.decoder => Ok(Value::Boolean(codec.fatal)),
                 _ => Ok(Value::Undefined),
             },
             _ => Err(RuntimeError::new(format!("Нельзя получить свойство у типа '{}'", obj.type_name()), span)),
@@ -255,6 +266,7 @@ impl Interpreter {
                                 env: Rc::clone(env),
                                 is_generator: false,
                                 is_async: false,
+                                is_arrow: false,
                             }))
                         });
                     }
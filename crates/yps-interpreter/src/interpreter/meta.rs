@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use yps_parser::ast::{Block, Param};
+
+use crate::symbols;
+use crate::value::Value;
+
+use super::Interpreter;
+
+impl Interpreter {
+    /// The file this program was loaded from, reported by `спиздить.мета`.
+    pub fn set_module_path(&mut self, path: PathBuf) {
+        self.module_path = Some(path.canonicalize().unwrap_or(path));
+        let meta = self.build_import_meta();
+        self.global_root.borrow_mut().rebind(symbols::IMPORT_META.to_string(), meta);
+    }
+
+    /// `спиздить.мета` of the module the running code was written in: functions exported from a
+    /// module find that module's object through their closure.
+    pub(super) fn import_meta(&mut self) -> Value {
+        if let Some(meta) = self.env.get(symbols::IMPORT_META) {
+            return meta;
+        }
+        let meta = self.build_import_meta();
+        self.global_root.borrow_mut().rebind(symbols::IMPORT_META.to_string(), meta.clone());
+        meta
+    }
+
+    /// Without a file (`-e`, stdin) only `главный` is set.
+    fn build_import_meta(&self) -> Value {
+        let mut map = IndexMap::new();
+        let path = self.module_path.as_ref();
+        let text = |s: String| Value::String(s.into());
+        map.insert("путь".to_string(), path.map_or(Value::Undefined, |p| text(p.display().to_string())));
+        map.insert(
+            "каталог".to_string(),
+            path.and_then(|p| p.parent()).map_or(Value::Undefined, |p| text(p.display().to_string())),
+        );
+        map.insert("url".to_string(), path.map_or(Value::Undefined, |p| text(format!("file://{}", p.display()))));
+        map.insert("главный".to_string(), Value::Boolean(self.is_main_module));
+        Value::object(map)
+    }
+
+    /// `глобалЭтот`: a fresh object with every global binding at the moment it is read.
+    pub(super) fn global_this(&self) -> Value {
+        let bindings = self.global_root.borrow().debug_bindings();
+        Value::object(bindings.into_iter().filter(|(name, _)| !name.starts_with("__") && !name.contains('.')).collect())
+    }
+
+    /// Binds `аргументы` in the current scope if the function reads it; the scan runs once per body.
+    pub(super) fn bind_arguments(&mut self, params: &[Param], body: &Rc<Block>, args: &[Value]) {
+        let key = Rc::as_ptr(body) as usize;
+        let reads = match self.arguments_readers.get(&key) {
+            Some((body, reads)) if body.strong_count() > 0 => *reads,
+            _ => {
+                let reads = crate::resolver::reads_arguments(params, body);
+                self.arguments_readers.insert(key, (Rc::downgrade(body), reads));
+                reads
+            }
+        };
+        if reads {
+            self.env.define(symbols::ARGUMENTS.to_string(), Value::array(args.to_vec()), false);
+        }
+    }
+}
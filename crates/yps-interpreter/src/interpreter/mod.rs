@@ -33,13 +33,17 @@ mod class;
 pub mod coercion;
 pub mod debug;
 mod delete;
+pub mod eval_code;
 mod eval_expr;
 mod event_loop;
 mod exec_stmt;
 mod gc;
 pub(crate) mod generator;
+mod heap;
 mod host_api;
+mod matching;
 mod member;
+mod meta;
 mod module_loader;
 mod promise_rt;
 mod proxy;
@@ -48,6 +52,8 @@ mod user_iter;
 
 use event_loop::MacrotaskQueue;
 
+pub use module_loader::resolve_import;
+
 pub(crate) use gc::GcRoot;
 pub(super) use types::{AccessSegment, ControlFlow, LoopOp};
 
@@ -73,8 +79,22 @@ pub struct Interpreter {
     pub(super) debug_action: debug::DebugAction,
     pub(super) debug_depth: usize,
     pub(super) debug_globals_baseline: std::collections::HashSet<String>,
+    /// Fixed at construction so that runs without `--coverage` only pay for a `bool` check.
+    pub(super) coverage: bool,
     /// `None` keeps `сказать` on real stdout/stderr; hosts without a console (WASM) install a sink.
     pub(super) output_sink: Option<Box<dyn crate::output::OutputSink>>,
+    /// Whether `ебал` and `Функция` may compile strings; see [`Interpreter::set_allow_eval`].
+    pub(super) allow_eval: bool,
+    /// The file `спиздить.мета` describes; `None` for `-e` and stdin.
+    pub(super) module_path: Option<PathBuf>,
+    /// False in modules loaded by an import; `спиздить.мета.главный` reports it.
+    pub(super) is_main_module: bool,
+    /// Function bodies by address, with whether they read `аргументы`.
+    pub(super) arguments_readers: HashMap<usize, (std::rc::Weak<yps_parser::ast::Block>, bool)>,
+    /// `захуярить.цель` handed from a `яга(...)` call to the parent constructor it runs.
+    pub(super) pending_new_target: Option<Value>,
+    /// Raised by the parent of a worker to stop it; checked before every statement when set.
+    pub(super) interrupt: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
 }
 
 pub(super) const MAX_AWAIT_DEPTH: usize = 16;
@@ -124,7 +144,14 @@ impl Interpreter {
             debug_action: debug::DebugAction::Continue,
             debug_depth: 0,
             debug_globals_baseline: std::collections::HashSet::new(),
+            coverage: crate::coverage::is_active(),
             output_sink: None,
+            allow_eval: true,
+            module_path: None,
+            is_main_module: true,
+            arguments_readers: HashMap::new(),
+            pending_new_target: None,
+            interrupt: None,
         }
     }
 
@@ -132,6 +159,11 @@ impl Interpreter {
         self.output_sink = Some(sink);
     }
 
+    /// Once `flag` is raised the run stops with [`crate::worker::WORKER_TERMINATED`].
+    pub fn set_interrupt(&mut self, flag: std::sync::Arc<std::sync::atomic::AtomicBool>) {
+        self.interrupt = Some(flag);
+    }
+
     #[inline]
     pub(super) fn lookup_read(&self, ident: &Identifier) -> crate::environment::Lookup {
         use crate::environment::Lookup;
@@ -144,15 +176,35 @@ impl Interpreter {
         {
             return Lookup::Found(value);
         }
-        self.env.lookup_read(&ident.name)
+        match self.env.lookup_read(&ident.name) {
+            Lookup::Missing => self.implicit_global(&ident.name).map_or(Lookup::Missing, Lookup::Found),
+            other => other,
+        }
+    }
+
+    /// Names that exist only while no binding shadows them: `глобалЭтот` and, in a worker, `родитель`.
+    pub(super) fn implicit_global(&self, name: &str) -> Option<Value> {
+        match name {
+            crate::symbols::GLOBAL_THIS => Some(self.global_this()),
+            crate::symbols::PARENT_PORT => crate::worker::parent_port().map(Value::Worker),
+            _ => None,
+        }
+    }
+
+    /// "Переменная не определена", with the closest name in scope, global or keyword as a hint.
+    pub(super) fn undefined_variable(&self, name: &str, span: Span) -> RuntimeError {
+        let names = self.env.visible_names();
+        RuntimeError::new(format!("Переменная '{name}' не определена"), span)
+            .with_did_you_mean(name, names.iter().map(String::as_str).chain(yps_lexer::KEYWORDS.iter().copied()))
     }
 
     pub(crate) fn register_finalization_registry(&mut self, state: &Rc<RefCell<FinRegState>>) {
         self.finalization_registries.push(Rc::downgrade(state));
     }
 
-    pub(super) fn push_frame(&mut self, name: Rc<str>, span: Span) {
-        self.call_stack.push(Frame { name, span });
+    /// Expects the callee's scope to be current already, since that scope knows its file.
+    pub(super) fn push_frame(&mut self, name: Rc<str>, span: Span, decl: Span) {
+        self.call_stack.push(Frame { name, span, file: self.env.file(), decl });
     }
 
     pub(super) fn pop_frame(&mut self) {
@@ -43,15 +43,24 @@ pub(crate) struct DeferredLink {
     pub(crate) imported: String,
 }
 
+/// Resolves an import specifier against the importing module's directory. A specifier
+/// without an extension means `.yopta`; the path is canonical, so every importer agrees on it.
+pub fn resolve_import(base: &Path, source: &str) -> std::io::Result<PathBuf> {
+    let mut candidate = base.join(source);
+    if candidate.extension().is_none() {
+        candidate.set_extension("yopta");
+    }
+    candidate.canonicalize()
+}
+
 impl Interpreter {
+    /// The directory relative paths in this module resolve against.
+    pub(crate) fn base_dir(&self) -> PathBuf {
+        self.base_path.clone().unwrap_or_else(|| PathBuf::from("."))
+    }
+
     fn resolve_module_path(&self, source: &str, span: Span) -> Result<PathBuf, RuntimeError> {
-        let base = self.base_path.clone().unwrap_or_else(|| PathBuf::from("."));
-        let mut candidate = base.join(source);
-        if candidate.extension().is_none() {
-            candidate.set_extension("yopta");
-        }
-        candidate
-            .canonicalize()
+        resolve_import(&self.base_dir(), source)
             .map_err(|e| RuntimeError::new(format!("Не удалось разрешить путь модуля '{source}': {e}"), span))
     }
 
@@ -117,7 +126,15 @@ impl Interpreter {
         sub.module_cache = Rc::clone(&self.module_cache);
         sub.module_links = Rc::clone(&self.module_links);
         sub.base_path = resolved.parent().map(Path::to_path_buf);
+        sub.is_main_module = false;
+        sub.macrotasks.ignore_worker_events();
+        sub.set_module_path(resolved.clone());
         sub.export_cell = Some(Rc::clone(&export_cell));
+        sub.env.set_file(if sub.coverage {
+            crate::coverage::register_file(&source_file, &program)
+        } else {
+            crate::sources::register(&source_file)
+        });
 
         self.module_cache.borrow_mut().insert(resolved.clone(), ModuleState::Loading(Rc::clone(&export_cell)));
         match sub.run_module(&program, &resolved) {
@@ -0,0 +1,94 @@
+use super::*;
+
+#[test]
+fn structured_clone_keeps_cycles_and_shared_references() {
+    let interp = run_code(
+        r#"
+        гыы общий = [1, 2];
+        гыы о = {а: общий, б: общий, к: захуярить Карта([["ключ", общий]])};
+        о.я = о;
+        гыы к = структурныйКлон(о);
+        гыы цикл = к.я === к;
+        гыы копия = к !== о && к.а !== общий;
+        гыы делят = к.а === к.б && к.к.get("ключ") === к.а;
+        к.а[0] = 9;
+        гыы исходный = общий[0];
+        "#,
+    );
+    assert_eq!(interp.get("цикл"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("копия"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("делят"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("исходный"), Some(Value::Number(1.0)));
+}
+
+#[test]
+fn structured_clone_rejects_functions_and_symbols() {
+    let err = run_code_err("структурныйКлон({ф: йопта() {}});");
+    assert!(err.message.contains("'функция' нельзя клонировать"), "{}", err.message);
+    let err = run_code_err(r#"структурныйКлон([Симбол("с")]);"#);
+    assert!(err.message.contains("'символ' нельзя клонировать"), "{}", err.message);
+}
+
+#[test]
+fn text_codecs_round_trip_each_encoding() {
+    let interp = run_code(
+        r#"
+        гыы утф8 = захуярить КодировщикТекста().закодировать("Ёж 😀");
+        гыы байт8 = утф8.длина;
+        гыы назад8 = захуярить ДекодировщикТекста().раскодировать(утф8);
+        гыы утф16 = захуярить КодировщикТекста("utf-16le").закодировать("Ёж");
+        гыы хекс16 = утф16.вХекс();
+        гыы назад16 = захуярить ДекодировщикТекста("utf-16le").раскодировать(утф16.область);
+        гыы ср = захуярить КодировщикТекста("windows-1251").закодировать("Ёжик №1");
+        гыы хексСр = ср.вХекс();
+        гыы д = захуярить ДекодировщикТекста("cp1251");
+        гыы назадСр = д.раскодировать(ср);
+        гыы имя = д.кодировка;
+        "#,
+    );
+    assert_eq!(interp.get("байт8"), Some(Value::Number(9.0)));
+    assert_eq!(interp.get("назад8"), Some(Value::String("Ёж 😀".into())));
+    assert_eq!(interp.get("хекс16"), Some(Value::String("01043604".into())));
+    assert_eq!(interp.get("назад16"), Some(Value::String("Ёж".into())));
+    assert_eq!(interp.get("хексСр"), Some(Value::String("a8e6e8ea20b931".into())));
+    assert_eq!(interp.get("назадСр"), Some(Value::String("Ёжик №1".into())));
+    assert_eq!(interp.get("имя"), Some(Value::String("windows-1251".into())));
+}
+
+#[test]
+fn decoder_replaces_or_rejects_malformed_input() {
+    let interp = run_code(
+        r#"
+        гыы с = захуярить ДекодировщикТекста().раскодировать(Ц8Массив.изХекса("61ff62"));
+        "#,
+    );
+    assert_eq!(interp.get("с"), Some(Value::String("a\u{FFFD}b".into())));
+    let err = run_code_err(
+        r#"захуярить ДекодировщикТекста("utf-8", {фатально: правда}).раскодировать(Ц8Массив.изХекса("61ff62"));"#,
+    );
+    assert!(err.message.contains("неверная последовательность UTF-8"), "{}", err.message);
+    let err = run_code_err(r#"захуярить КодировщикТекста("koi8-r");"#);
+    assert!(err.message.contains("неизвестная кодировка 'koi8-r'"), "{}", err.message);
+    let err = run_code_err(r#"захуярить КодировщикТекста("windows-1251").закодировать("日");"#);
+    assert!(err.message.contains("нельзя записать в windows-1251"), "{}", err.message);
+}
+
+#[test]
+fn base64_and_hex_helpers() {
+    let interp = run_code(
+        r#"
+        гыы б64 = вБазу64("Привет");
+        гыы изСтроки = захуярить ДекодировщикТекста().раскодировать(изБазы64(б64));
+        гыы урл = изБазы64("-_8").вХекс();
+        гыы изБайт = вБазу64(Ц8Массив.изХекса("cafe"));
+        гыы метод = Ц8Массив.изБазы64("yv4=").вБазу64();
+        "#,
+    );
+    assert_eq!(interp.get("б64"), Some(Value::String("0J/RgNC40LLQtdGC".into())));
+    assert_eq!(interp.get("изСтроки"), Some(Value::String("Привет".into())));
+    assert_eq!(interp.get("урл"), Some(Value::String("fbff".into())));
+    assert_eq!(interp.get("изБайт"), Some(Value::String("yv4=".into())));
+    assert_eq!(interp.get("метод"), Some(Value::String("yv4=".into())));
+    let err = run_code_err(r#"Ц8Массив.изХекса("abc");"#);
+    assert!(err.message.contains("нечётное число цифр"), "{}", err.message);
+}
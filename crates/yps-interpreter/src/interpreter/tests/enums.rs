@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn enum_members_count_up_from_the_last_number() {
+    let interp = run_code(
+        r#"
+        еээ Цвет жЫ Красный, Зелёный = 5, Синий }
+        гыы к = Цвет.Красный;
+        гыы з = Цвет.Зелёный;
+        гыы с = Цвет.Синий;
+        гыы имя = Цвет[6];
+        "#,
+    );
+    assert_eq!(interp.get("к"), Some(Value::Number(0.0)));
+    assert_eq!(interp.get("з"), Some(Value::Number(5.0)));
+    assert_eq!(interp.get("с"), Some(Value::Number(6.0)));
+    assert_eq!(interp.get("имя"), Some(Value::String("Синий".into())));
+}
+
+#[test]
+fn enum_is_frozen_and_string_members_have_no_reverse_mapping() {
+    let interp = run_code(
+        r#"
+        еээ Режим { Чтение = "r", Запись = "w" }
+        гыы р = Режим;
+        р.Чтение = "x";
+        гыы заморожен = Кент.заморожен(Режим);
+        гыы ключи = Кент.ключи(Режим).length;
+        гыы чтение = Режим.Чтение;
+        "#,
+    );
+    assert_eq!(interp.get("заморожен"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("ключи"), Some(Value::Number(2.0)));
+    assert_eq!(interp.get("чтение"), Some(Value::String("r".into())));
+}
+
+#[test]
+fn enum_binding_is_constant() {
+    let err = run_code_err("еээ Ц { А }\nЦ = 1;");
+    assert!(err.message.contains("Ц"), "{}", err.message);
+}
+
+#[test]
+fn enum_member_after_a_string_needs_a_value() {
+    let err = run_code_err("еээ Ц { А = \"а\", Б }");
+    assert_eq!(err.message, "Элементу 'Б' нужно значение: предыдущий элемент не число");
+}
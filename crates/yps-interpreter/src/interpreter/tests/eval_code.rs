@@ -0,0 +1,106 @@
+use super::*;
+
+#[test]
+fn direct_eval_reads_and_writes_the_callers_variables() {
+    let interp = run_code(
+        r#"
+        гыы х = 10;
+        йопта ф(а) {
+            гыы б = 5;
+            гыы сумма = ебал("а + б + х");
+            ебал("б = 100");
+            отвечаю [сумма, б];
+        }
+        гыы р = ф(1);
+        гыы сумма = р[0];
+        гыы б = р[1];
+        "#,
+    );
+    assert_eq!(interp.get("сумма"), Some(Value::Number(16.0)));
+    assert_eq!(interp.get("б"), Some(Value::Number(100.0)));
+}
+
+#[test]
+fn indirect_eval_sees_only_globals() {
+    let err = run_code_err(
+        r#"
+        гыы е = ебал;
+        йопта ф() {
+            гыы местная = 1;
+            отвечаю е("местная");
+        }
+        ф();
+        "#,
+    );
+    assert!(err.message.contains("местная"), "{}", err.message);
+    let interp = run_code("гыы х = 4;\nйопта ф() { гыы х = 1; гыы е = ебал; отвечаю е(\"х * 2\"); }\nгыы р = ф();");
+    assert_eq!(interp.get("р"), Some(Value::Number(8.0)));
+}
+
+#[test]
+fn eval_declarations_stay_inside_and_non_strings_pass_through() {
+    let interp = run_code(
+        r#"
+        гыы р = ебал("гыы в = 3; в * 2");
+        гыы видна = 1;
+        хапнуть { в; } гоп (е) { видна = 0; }
+        гыы ч = ебал(42);
+        "#,
+    );
+    assert_eq!(interp.get("р"), Some(Value::Number(6.0)));
+    assert_eq!(interp.get("видна"), Some(Value::Number(0.0)));
+    assert_eq!(interp.get("ч"), Some(Value::Number(42.0)));
+}
+
+#[test]
+fn function_constructor_builds_a_global_function() {
+    let interp = run_code(
+        r#"
+        гыы х = 100;
+        гыы сумма = Функция("а", "б", "отвечаю а + б + х;");
+        гыы р = сумма(1, 2);
+        гыы с = захуярить Функция("отвечаю 7;")();
+        "#,
+    );
+    assert_eq!(interp.get("р"), Some(Value::Number(103.0)));
+    assert_eq!(interp.get("с"), Some(Value::Number(7.0)));
+}
+
+#[test]
+fn code_strings_need_no_semicolon_before_a_closing_brace() {
+    let interp = run_code(
+        r#"
+        гыы сумма = Функция("а", "б", "отвечаю а + б");
+        гыы р = сумма(2, 3);
+        гыы блок = 0;
+        ебал("{ блок = 7 }");
+        гыы г = ебал("йопта г() { отвечаю 5 }\nг()");
+        "#,
+    );
+    assert_eq!(interp.get("р"), Some(Value::Number(5.0)));
+    assert_eq!(interp.get("блок"), Some(Value::Number(7.0)));
+    assert_eq!(interp.get("г"), Some(Value::Number(5.0)));
+}
+
+#[test]
+fn eval_syntax_errors_are_catchable() {
+    let interp = run_code(
+        r#"
+        гыы сообщение = "";
+        хапнуть { ебал("1 +"); } гоп (е) { сообщение = е.message; }
+        "#,
+    );
+    let Some(Value::String(message)) = interp.get("сообщение") else { panic!("нет сообщения") };
+    assert!(message.starts_with("Ошибка разбора в 'ебал'"), "{message}");
+}
+
+#[test]
+fn eval_can_be_disabled() {
+    let source = SourceFile::new("test".to_string(), "ебал(\"1\");".to_string());
+    let (tokens, _) = Lexer::new(&source).tokenize();
+    let (program, _) = Parser::new(&tokens, &source).parse_program();
+    let mut interp = Interpreter::new();
+    interp.set_allow_eval(false);
+    let err = interp.run(&program).unwrap_err();
+    assert!(err.message.contains("'ебал' запрещён"), "{}", err.message);
+}
@@ -189,3 +189,25 @@ fn repl_collect_keeps_returned_closure_alive() {
     let r = i.call_function(v, vec![], yps_lexer::Span { start: 0, end: 0 }).expect("вызов замыкания после сборки");
     assert_eq!(r, Value::Number(42.0));
 }
+
+#[test]
+fn heap_snapshot_links_instances_to_their_retainers() {
+    let i = run_code(
+        r#"
+        клёво Точка {
+            Точка(х) { тырыпыры.х = х; }
+        }
+        йопта держатель() {
+            гыы т = захуярить Точка(1);
+            отвечаю () => т;
+        }
+        гыы ф = держатель();
+        гыы список = [{ вложенный: захуярить Точка(2) }];
+        "#,
+    );
+    let graph = i.heap_snapshot();
+    assert_eq!(graph.retainers_of("Точка"), ["(контекст).т", "Объект.вложенный"]);
+    assert!(graph.retainers_of("Объект").contains(&"Массив[0]".to_string()));
+    let json = graph.to_json();
+    assert!(json.starts_with("{\"snapshot\":{\"meta\":{\"node_fields\":"), "{json}");
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+fn string(interp: &Interpreter, name: &str) -> String {
+    match interp.get(name) {
+        Some(Value::String(s)) => s.to_string(),
+        other => panic!("'{name}': ожидалась строка, получено {other:?}"),
+    }
+}
+
+#[test]
+fn number_format_groups_currency_percent_and_compact() {
+    let interp = run_code(
+        r#"
+        гыы группы = захуярить Интл.ФорматЧисла("ru").форматировать(1234567.891);
+        гыы рубли = захуярить Интл.ФорматЧисла("ru", {стиль: "валюта", валюта: "RUB"}).форматировать(1234.5);
+        гыы доллары = захуярить Интл.ФорматЧисла("en-US", {style: "currency", currency: "USD"}).format(-1234.5);
+        гыы проценты = захуярить Интл.ФорматЧисла("ru", {стиль: "процент"}).форматировать(0.256);
+        гыы кратко = захуярить Интл.ФорматЧисла("ru", {нотация: "компактная"}).форматировать(1234);
+        гыы коротко = захуярить Интл.ФорматЧисла("en", {notation: "compact"}).format(999999);
+        гыы знаки = захуярить Интл.ФорматЧисла("en", {минимумДробных: 2}).format(1.005);
+        "#,
+    );
+    assert_eq!(string(&interp, "группы"), "1\u{202F}234\u{202F}567,891");
+    assert_eq!(string(&interp, "рубли"), "1\u{202F}234,50\u{A0}₽");
+    assert_eq!(string(&interp, "доллары"), "-$1,234.50");
+    assert_eq!(string(&interp, "проценты"), "26\u{A0}%");
+    assert_eq!(string(&interp, "кратко"), "1,2\u{A0}тыс.");
+    assert_eq!(string(&interp, "коротко"), "1M");
+    assert_eq!(string(&interp, "знаки"), "1.005");
+}
+
+#[test]
+fn date_format_uses_genitive_months() {
+    let interp = run_code(
+        r#"
+        гыы д = захуярить Дата("2026-10-17T14:05:09Z");
+        гыы длинно = захуярить Интл.ФорматДаты("ru", {стильДаты: "длинный"}).форматировать(д);
+        гыы полно = захуярить Интл.ФорматДаты("ru", {стильДаты: "полный", стильВремени: "короткий"}).форматировать(д);
+        гыы месяц = захуярить Интл.ФорматДаты("ru", {месяц: "длинный", год: "числом"}).форматировать(д);
+        гыы англ = захуярить Интл.ФорматДаты("en", {dateStyle: "long", timeStyle: "short"}).format(д);
+        гыы строка = д.вЛокальнуюСтроку();
+        гыы дата = д.вЛокальнуюДату("en-US");
+        "#,
+    );
+    assert_eq!(string(&interp, "длинно"), "17 октября 2026 г.");
+    assert_eq!(string(&interp, "полно"), "суббота, 17 октября 2026 г., 14:05");
+    assert_eq!(string(&interp, "месяц"), "октябрь 2026 г.");
+    assert_eq!(string(&interp, "англ"), "October 17, 2026 at 2:05\u{202F}PM");
+    assert_eq!(string(&interp, "строка"), "17.10.2026, 14:05:09");
+    assert_eq!(string(&interp, "дата"), "10/17/2026");
+}
+
+#[test]
+fn collator_sorts_yo_next_to_ye() {
+    let interp = run_code(
+        r#"
+        гыы с = захуярить Интл.Сравнитель("ru");
+        гыы слова = ["ёж", "яблоко", "Ель", "ель", "еда", "ещё"].сортировать((а, б) => с.сравнить(а, б)).склеить(" ");
+        гыы числа = захуярить Интл.Сравнитель("ru", {числовое: правда}).сравнить("файл10", "файл2");
+        гыы основа = захуярить Интл.Сравнитель("ru", {чувствительность: "основа"}).сравнить("Ёж", "еж");
+        "#,
+    );
+    assert_eq!(string(&interp, "слова"), "еда ёж ель Ель ещё яблоко");
+    assert_eq!(interp.get("числа"), Some(Value::Number(1.0)));
+    assert_eq!(interp.get("основа"), Some(Value::Number(0.0)));
+}
+
+#[test]
+fn plural_rules_and_relative_time() {
+    let interp = run_code(
+        r#"
+        гыы п = захуярить Интл.ПравилаМножественного("ru");
+        гыы категории = [1, 3, 11, 21, 5, 1.5].преобразовать(н => п.выбрать(н)).склеить(" ");
+        гыы порядок = захуярить Интл.ПравилаМножественного("en", {type: "ordinal"}).select(22);
+        гыы о = захуярить Интл.ОтносительноеВремя("ru");
+        гыы через = о.форматировать(3, "день");
+        гыы назад = о.форматировать(-5, "год");
+        гыы вчера = захуярить Интл.ОтносительноеВремя("ru", {числа: "авто"}).форматировать(-1, "день");
+        "#,
+    );
+    assert_eq!(string(&interp, "категории"), "one few many one many other");
+    assert_eq!(string(&interp, "порядок"), "two");
+    assert_eq!(string(&interp, "через"), "через 3 дня");
+    assert_eq!(string(&interp, "назад"), "5 лет назад");
+    assert_eq!(string(&interp, "вчера"), "вчера");
+}
+
+#[test]
+fn intl_rejects_bad_options() {
+    let err = run_code_err(r#"захуярить Интл.ФорматЧисла("ru", {стиль: "валюта"});"#);
+    assert!(err.message.contains("нужен код"), "{}", err.message);
+    let err = run_code_err(r#"захуярить Интл.ОтносительноеВремя("ru").форматировать(1, "век");"#);
+    assert!(err.message.contains("неизвестная единица времени 'век'"), "{}", err.message);
+}
@@ -0,0 +1,98 @@
+use super::*;
+
+#[test]
+fn match_picks_the_first_fitting_arm() {
+    let interp = run_code(
+        r#"
+        йопта описать(х) {
+            отвечаю базарпо (х) {
+                тема 0 => "ноль",
+                тема [а, б] => а + б,
+                тема { имя } => имя,
+                нуичо => "другое",
+            };
+        }
+        гыы а = описать(0);
+        гыы б = описать([2, 3]);
+        гыы в = описать({ имя: "Вася" });
+        гыы г = описать([1, 2, 3]);
+        "#,
+    );
+    assert_eq!(interp.get("а"), Some(Value::String("ноль".into())));
+    assert_eq!(interp.get("б"), Some(Value::Number(5.0)));
+    assert_eq!(interp.get("в"), Some(Value::String("Вася".into())));
+    assert_eq!(interp.get("г"), Some(Value::String("другое".into())));
+}
+
+#[test]
+fn match_guard_falls_through_to_the_next_arm() {
+    let interp = run_code(
+        r#"
+        гыы знак = (н) => базарпо (н) {
+            тема х вилкойвглаз (х < 0) => "минус",
+            тема х вилкойвглаз (х > 0) => "плюс",
+            нуичо => "ноль",
+        };
+        гыы а = знак(-3);
+        гыы б = знак(4);
+        гыы в = знак(0);
+        "#,
+    );
+    assert_eq!(interp.get("а"), Some(Value::String("минус".into())));
+    assert_eq!(interp.get("б"), Some(Value::String("плюс".into())));
+    assert_eq!(interp.get("в"), Some(Value::String("ноль".into())));
+}
+
+#[test]
+fn match_rest_and_defaults_relax_the_shape() {
+    let interp = run_code(
+        r#"
+        гыы хвост = базарпо ([1, 2, 3]) { тема [_, ...ост] => ост.length };
+        гыы возраст = базарпо ({ имя: "Петя" }) { тема { имя, возраст = 18 } => возраст };
+        "#,
+    );
+    assert_eq!(interp.get("хвост"), Some(Value::Number(2.0)));
+    assert_eq!(interp.get("возраст"), Some(Value::Number(18.0)));
+}
+
+#[test]
+fn match_instance_arm_checks_the_class() {
+    let interp = run_code(
+        r#"
+        клёво Круг { Круг(р) { тырыпыры.р = р; } }
+        клёво Квадрат { Квадрат(с) { тырыпыры.с = с; } }
+        гыы площадь = (ф) => базарпо (ф) {
+            тема шкура Круг { р } => 3 * р * р,
+            тема шкура Квадрат { с } => с * с,
+        };
+        гыы а = площадь(захуярить Круг(2));
+        гыы б = площадь(захуярить Квадрат(3));
+        "#,
+    );
+    assert_eq!(interp.get("а"), Some(Value::Number(12.0)));
+    assert_eq!(interp.get("б"), Some(Value::Number(9.0)));
+}
+
+#[test]
+fn match_bindings_stay_inside_the_arm() {
+    let err = run_code_err("гыы а = базарпо (1) { тема х => х };\nсказать(х);");
+    assert!(err.message.contains("х"), "{}", err.message);
+}
+
+#[test]
+fn match_without_fitting_arm_is_catchable() {
+    let interp = run_code(
+        r#"
+        гыы текст = "";
+        хапнуть {
+            базарпо ("строка") { тема 1 => 1 };
+        } гоп (е) {
+            текст = е.message;
+        }
+        "#,
+    );
+    assert_eq!(
+        interp.get("текст"),
+        Some(Value::String("Ни одна ветка 'базарпо' не подошла для значения типа 'строка'".into()))
+    );
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+#[test]
+fn new_target_is_the_constructed_class() {
+    let interp = run_code(
+        r#"
+        гыы цели = [];
+        клёво Зверь {
+            Зверь() { цели.втолкнуть(захуярить.цель); }
+        }
+        клёво Пёс батя Зверь {
+            Пёс() {
+                яга();
+                участковый стрелка = () => захуярить.цель;
+                цели.втолкнуть(стрелка());
+            }
+        }
+        захуярить Зверь();
+        захуярить Пёс();
+        гыы первый = цели[0] == Зверь;
+        гыы изРодителя = цели[1] == Пёс;
+        гыы изСтрелки = цели[2] == Пёс;
+        йопта обычная() { отвечаю захуярить.цель; }
+        гыы безНью = обычная();
+        "#,
+    );
+    assert_eq!(interp.get("первый"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("изРодителя"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("изСтрелки"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("безНью"), Some(Value::Undefined));
+}
+
+#[test]
+fn arguments_holds_every_passed_value() {
+    let interp = run_code(
+        r#"
+        йопта сколько(а) { отвечаю аргументы.длина; }
+        йопта внешняя() { отвечаю (() => аргументы[1])(); }
+        йопта тень(аргументы) { отвечаю аргументы; }
+        клёво К { метод() { отвечаю аргументы[0]; } }
+        гыы н = сколько(1, 2, 3);
+        гыы изСтрелки = внешняя("а", "б");
+        гыы параметр = тень(7, 8);
+        гыы изМетода = захуярить К().метод("м");
+        "#,
+    );
+    assert_eq!(interp.get("н"), Some(Value::Number(3.0)));
+    assert_eq!(interp.get("изСтрелки"), Some(Value::String("б".into())));
+    assert_eq!(interp.get("параметр"), Some(Value::Number(7.0)));
+    assert_eq!(interp.get("изМетода"), Some(Value::String("м".into())));
+}
+
+#[test]
+fn arguments_outside_a_function_is_undeclared() {
+    let err = run_code_err("сказать(аргументы);");
+    assert!(err.message.contains("аргументы"), "{}", err.message);
+}
+
+#[test]
+fn global_this_reflects_globals_and_builtins() {
+    let interp = run_code(
+        r#"
+        гыы х = 5;
+        гыы г = глобалЭтот;
+        гыы значение = г.х;
+        гыы естьМатан = г.Матан == Матан;
+        гыы скрытые = Кент.ключи(г).отфильтровать((к) => к.начинаетсяС("__")).длина;
+        "#,
+    );
+    assert_eq!(interp.get("значение"), Some(Value::Number(5.0)));
+    assert_eq!(interp.get("естьМатан"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("скрытые"), Some(Value::Number(0.0)));
+}
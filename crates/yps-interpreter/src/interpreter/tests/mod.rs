@@ -106,13 +106,19 @@ mod collections;
 mod control_flow;
 mod decorators;
 mod destructure;
+mod encoding;
+mod enums;
+mod eval_code;
 mod event_loop;
 mod expressions;
 mod functions;
 mod gc;
 mod generators;
+mod intl;
 mod iterators;
 mod limits;
+mod matching;
+mod meta;
 mod modules;
 mod operators;
 mod output_sink;
@@ -126,7 +132,9 @@ mod runtime_errors;
 mod stack_traces;
 mod stdlib_core;
 mod strings;
+mod temporal;
 mod try_catch;
 mod typed_arrays;
 mod using_symbol;
 mod weak;
+mod workers;
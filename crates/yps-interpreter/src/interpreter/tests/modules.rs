@@ -178,3 +178,24 @@ fn import_attributes_russian_alias_satr() {
     let interp = run_with_data_file("a.json", json, main);
     assert_eq!(interp.get("значение"), Some(Value::Number(7.0)));
 }
+
+#[test]
+fn import_meta_belongs_to_the_module_that_reads_it() {
+    let module = r#"
+        предъява ясенХуй главныйЛи = спиздить.мета.главный;
+        предъява йопта путь() { отвечаю спиздить.мета.путь; }
+    "#;
+    let main = r#"
+        спиздить { главныйЛи, путь } из "./модуль";
+        гыы модульГлавный = главныйЛи;
+        гыы путьМодуля = путь();
+        гыы сам = спиздить.мета.главный;
+        гыы свойПуть = спиздить.мета.путь;
+    "#;
+    let interp = run_with_module(module, main);
+    assert_eq!(interp.get("модульГлавный"), Some(Value::Boolean(false)));
+    assert_eq!(interp.get("сам"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("свойПуть"), Some(Value::Undefined));
+    let Some(Value::String(path)) = interp.get("путьМодуля") else { panic!("нет пути модуля") };
+    assert!(path.ends_with("модуль.yopta"), "{path}");
+}
@@ -119,3 +119,29 @@ fn instanceof_operator_requires_class_on_right() {
     );
     assert!(err.message.contains("шкура"));
 }
+
+#[test]
+fn undefined_variable_suggests_a_binding_in_scope() {
+    let err = run_code_err("гыы счётчик = 1;\nйопта ф() { отвечаю счетчик + 1; }\nф();");
+    assert_eq!(err.help.as_deref(), Some("возможно, имелось в виду 'счётчик'"));
+}
+
+#[test]
+fn undefined_variable_suggests_globals_and_keywords() {
+    assert_eq!(run_code_err("матан.корень(4);").help.as_deref(), Some("возможно, имелось в виду 'Матан'"));
+    assert_eq!(run_code_err("гыы х = правдо;").help.as_deref(), Some("возможно, имелось в виду 'правда'"));
+    assert_eq!(run_code_err("гыы х = совсемНепохожее;").help, None);
+}
+
+#[test]
+fn unknown_method_suggests_a_real_method_of_the_receiver() {
+    let err = run_code_err("гыы м = [1]; м.добавит(2);");
+    assert!(err.message.contains("нет метода 'добавит'"), "got: {}", err.message);
+    assert_eq!(err.help.as_deref(), Some("возможно, имелось в виду 'добавить'"));
+
+    let err = run_code_err("гыы к = захуярить Карта(); к.поставит(1, 2);");
+    assert_eq!(err.help.as_deref(), Some("возможно, имелось в виду 'поставить'"));
+
+    let err = run_code_err("\"аб\".вВерхнийРегистер();");
+    assert_eq!(err.help.as_deref(), Some("возможно, имелось в виду 'вВерхнийРегистр'"));
+}
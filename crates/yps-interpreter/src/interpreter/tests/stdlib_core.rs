@@ -623,3 +623,33 @@ fn test_stdlib_chained_methods() {
     );
     assert_eq!(interp.get("рез"), Some(Value::Number(54.0)));
 }
+
+#[test]
+fn method_lists_match_what_call_method_dispatches() {
+    let mut interp = run_code(
+        r#"
+        гыы массив = [1, 2];
+        гыы строкаЗн = "аб";
+        гыы карта = захуярить Карта();
+        гыы набор = захуярить Набор();
+        гыы дата = захуярить Дата(0);
+        гыы обещание = захуярить СловоПацана((решить) => решить(1));
+        гыы регекс = /а/;
+        гыы байты = захуярить Ц8Массив(4);
+        гыы итератор = массив.значения();
+        гыы символ = Симбол("с");
+        "#,
+    );
+    let span = yps_lexer::Span { start: 0, end: 0 };
+    let receivers =
+        ["массив", "строкаЗн", "карта", "набор", "дата", "обещание", "регекс", "байты", "итератор", "символ"];
+    let mut values: Vec<Value> = receivers.iter().map(|name| interp.get(name).expect(name)).collect();
+    values.push(Value::Number(1.0));
+    for value in values {
+        for method in crate::stdlib::method_names(&value) {
+            if let Err(e) = crate::stdlib::call_method(&mut interp, value.clone(), method, vec![], span) {
+                assert!(!e.message.contains("метода"), "'{method}' есть в списке, но не вызывается: {}", e.message);
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use super::*;
+
+fn string(interp: &Interpreter, name: &str) -> String {
+    match interp.get(name) {
+        Some(Value::String(s)) => s.to_string(),
+        other => panic!("'{name}': ожидалась строка, получено {other:?}"),
+    }
+}
+
+fn number(interp: &Interpreter, name: &str) -> f64 {
+    match interp.get(name) {
+        Some(Value::Number(n)) => n,
+        other => panic!("'{name}': ожидалось число, получено {other:?}"),
+    }
+}
+
+/// Windows runners have no system tzdata; tests of IANA zones skip there.
+fn has_tzdata() -> bool {
+    std::path::Path::new("/usr/share/zoneinfo/Europe/Berlin").exists()
+}
+
+#[test]
+fn zoned_arithmetic_across_spring_forward() {
+    if !has_tzdata() {
+        return;
+    }
+    let interp = run_code(
+        r#"
+        гыы з = Время.ЗонированноеВремя.от("2026-03-28T12:00:00+01:00[Europe/Berlin]");
+        гыы день = з.прибавить({дни: 1}).вСтроку();
+        гыы сутки = з.прибавить({часы: 24}).вСтроку();
+        гыы часов = з.прибавить({дни: 1}).часовВДне;
+        гыы разница = з.доМомента(з.прибавить({дни: 1})).вСтроку();
+        гыы вДнях = з.доМомента(з.прибавить({дни: 1}), {наибольшаяЕдиница: "день"}).вСтроку();
+        гыы секунда = Время.ЗонированноеВремя.от("2026-03-29T01:59:59+01:00[Europe/Berlin]").прибавить("PT1S").вСтроку();
+        "#,
+    );
+    assert_eq!(string(&interp, "день"), "2026-03-29T12:00:00+02:00[Europe/Berlin]");
+    assert_eq!(string(&interp, "сутки"), "2026-03-29T13:00:00+02:00[Europe/Berlin]");
+    assert_eq!(number(&interp, "часов"), 23.0);
+    assert_eq!(string(&interp, "разница"), "PT23H");
+    assert_eq!(string(&interp, "вДнях"), "P1D");
+    assert_eq!(string(&interp, "секунда"), "2026-03-29T03:00:00+02:00[Europe/Berlin]");
+}
+
+#[test]
+fn gaps_and_overlaps_follow_disambiguation() {
+    if !has_tzdata() {
+        return;
+    }
+    let interp = run_code(
+        r#"
+        гыы поля = {год: 2026, месяц: 3, день: 29, час: 2, минута: 30, зона: "Europe/Berlin"};
+        гыы дыра = Время.ЗонированноеВремя.от(поля).вСтроку();
+        гыы раньше = Время.ЗонированноеВремя.от(поля, {неоднозначность: "раньше"}).вСтроку();
+        гыы осень = {год: 2026, месяц: 10, день: 25, час: 2, минута: 30, зона: "Europe/Berlin"};
+        гыы первое = Время.ЗонированноеВремя.от(осень).смещение;
+        гыы второе = Время.ЗонированноеВремя.от(осень, {неоднозначность: "позже"}).смещение;
+        гыы длинный = Время.ЗонированноеВремя.от(осень).часовВДне;
+        гыы ошибка = "";
+        хапнуть { Время.ЗонированноеВремя.от(поля, {неоднозначность: "отклонить"}); } гоп (е) { ошибка = е.message; }
+        "#,
+    );
+    assert_eq!(string(&interp, "дыра"), "2026-03-29T03:30:00+02:00[Europe/Berlin]");
+    assert_eq!(string(&interp, "раньше"), "2026-03-29T01:30:00+01:00[Europe/Berlin]");
+    assert_eq!(string(&interp, "первое"), "+02:00");
+    assert_eq!(string(&interp, "второе"), "+01:00");
+    assert_eq!(number(&interp, "длинный"), 25.0);
+    assert!(string(&interp, "ошибка").contains("такого местного времени нет"));
+}
+
+#[test]
+fn plain_dates_clamp_and_measure_months() {
+    let interp = run_code(
+        r#"
+        гыы а = Время.ПростаяДата.от("2024-01-31");
+        гыы февраль = а.прибавить({месяцы: 1}).вСтроку();
+        гыы разница = а.доМомента("2026-10-17", {наибольшаяЕдиница: "год"}).вСтроку();
+        гыы назад = Время.ПростаяДата.от("2026-10-17").сМомента("2026-01-01", {наибольшаяЕдиница: "месяц"}).вСтроку();
+        гыы месяцы = Время.ПростаяДата.от("2026-01-01").доМомента("2026-10-17", {наибольшаяЕдиница: "год", наименьшаяЕдиница: "месяц", режимОкругления: "ближайшее"}).вСтроку();
+        гыы порядок = Время.ПростаяДата.сравнить("2024-01-01", захуярить Время.ПростаяДата(2025, 1, 1));
+        гыы день = а.деньНедели;
+        гыы високосный = а.високосный;
+        "#,
+    );
+    assert_eq!(string(&interp, "февраль"), "2024-02-29");
+    assert_eq!(string(&interp, "разница"), "P2Y8M17D");
+    assert_eq!(string(&interp, "назад"), "P9M16D");
+    assert_eq!(string(&interp, "месяцы"), "P10M");
+    assert_eq!(number(&interp, "порядок"), -1.0);
+    assert_eq!(number(&interp, "день"), 3.0);
+    assert_eq!(interp.get("високосный"), Some(Value::Boolean(true)));
+}
+
+#[test]
+fn instants_round_and_convert_to_zones() {
+    let interp = run_code(
+        r#"
+        гыы м = Время.Мгновение.от("2026-10-17T10:20:30.123456789Z");
+        гыы строка = м.вСтроку();
+        гыы мс = м.эпохаМс;
+        гыы секунда = м.округлить("секунда").вСтроку();
+        гыы четверть = м.округлить({наименьшаяЕдиница: "минута", шагОкругления: 15}).вСтроку();
+        гыы смещение = м.вЗону("+05:30").вСтроку();
+        гыы прошло = м.сМомента("2026-10-17T00:00:00Z", {наибольшаяЕдиница: "час", наименьшаяЕдиница: "минута"}).вСтроку();
+        гыы эпоха = захуярить Время.Мгновение(0n).вСтроку();
+        гыы жсон = Жсон.вСтроку({м: Время.Мгновение.изЭпохиМс(-1)});
+        "#,
+    );
+    assert_eq!(string(&interp, "строка"), "2026-10-17T10:20:30.123456789Z");
+    assert_eq!(number(&interp, "мс"), 1792232430123.0);
+    assert_eq!(string(&interp, "секунда"), "2026-10-17T10:20:30Z");
+    assert_eq!(string(&interp, "четверть"), "2026-10-17T10:15:00Z");
+    assert_eq!(string(&interp, "смещение"), "2026-10-17T15:50:30.123456789+05:30[+05:30]");
+    assert_eq!(string(&interp, "прошло"), "PT10H20M");
+    assert_eq!(string(&interp, "эпоха"), "1970-01-01T00:00:00Z");
+    assert_eq!(string(&interp, "жсон"), r#"{"м":"1969-12-31T23:59:59.999Z"}"#);
+}
+
+#[test]
+fn durations_parse_balance_and_total() {
+    let interp = run_code(
+        r#"
+        гыы д = Время.Длительность.от("P1Y2M3DT4H5M6.007S");
+        гыы строка = д.обратная().вСтроку();
+        гыы годы = д.годы;
+        гыы дни = Время.Длительность.от({часы: 90}).округлить({наибольшаяЕдиница: "день"}).вСтроку();
+        гыы сумма = Время.Длительность.от({дни: 1}).прибавить({часы: 25}).вСтроку();
+        гыы минуты = Время.Длительность.от("PT1H30M").итого("минута");
+        гыы февраль = Время.Длительность.от({месяцы: 1}).итого({единица: "день", относительно: "2024-02-01"});
+        гыы порядок = Время.Длительность.сравнить("PT90M", "PT1H");
+        гыы ошибка = "";
+        хапнуть { Время.Длительность.от("P1M").итого("день"); } гоп (е) { ошибка = е.message; }
+        "#,
+    );
+    assert_eq!(string(&interp, "строка"), "-P1Y2M3DT4H5M6.007S");
+    assert_eq!(number(&interp, "годы"), 1.0);
+    assert_eq!(string(&interp, "дни"), "P3DT18H");
+    assert_eq!(string(&interp, "сумма"), "P2DT1H");
+    assert_eq!(number(&interp, "минуты"), 90.0);
+    assert_eq!(number(&interp, "февраль"), 29.0);
+    assert_eq!(number(&interp, "порядок"), 1.0);
+    assert!(string(&interp, "ошибка").contains("относительно"));
+}
+
+#[test]
+fn temporal_rejects_bad_input() {
+    let err = run_code_err(r#"Время.ПростаяДата.от("2026-02-30");"#);
+    assert!(err.message.contains("не удалось разобрать '2026-02-30'"), "{}", err.message);
+    let err = run_code_err(r#"Время.ПростаяДата.от({год: 2026, месяц: 13, день: 1}, {переполнение: "отклонить"});"#);
+    assert!(err.message.contains("месяц должен быть от 1 до 12"), "{}", err.message);
+    let err = run_code_err(r#"Время.ЗонированноеВремя.от("2026-03-29T02:30[Mars/Olympus]");"#);
+    assert!(err.message.contains("неизвестная часовая зона 'Mars/Olympus'"), "{}", err.message);
+    let err = run_code_err(r#"Время.Мгновение.от("2026-10-17T10:00").прибавить({дни: 1});"#);
+    assert!(err.message.contains("дата и смещение"), "{}", err.message);
+    let err = run_code_err(r#"Время.Мгновение.от("2026-10-17T10:00Z").прибавить({дни: 1});"#);
+    assert!(err.message.contains("только часы"), "{}", err.message);
+}
@@ -616,3 +616,69 @@ fn dv_with_offset_and_length() {
     assert_eq!(interp.get("дл"), Some(Value::Number(4.0)));
     assert_eq!(interp.get("см"), Some(Value::Number(4.0)));
 }
+
+#[test]
+fn shared_buffer_backs_typed_arrays_and_data_views() {
+    let interp = run_code(
+        r#"
+        гыы б = захуярить ОбщийБуфер(8);
+        гыы та = захуярить Ц8Массив(б);
+        гыы в = захуярить ОбзорБайтов(б);
+        в.задатьЦ8(3, 9);
+        гыы р = та[3];
+        гыы дл = б.byteLength;
+        "#,
+    );
+    assert_eq!(interp.get("р"), Some(Value::Number(9.0)));
+    assert_eq!(interp.get("дл"), Some(Value::Number(8.0)));
+}
+
+#[test]
+fn atomics_read_modify_write_return_old_values() {
+    let interp = run_code(
+        r#"
+        гыы м = захуярить Ч32Массив(захуярить ОбщийБуфер(8));
+        гыы а = Атомики.добавить(м, 0, 5);
+        гыы б = Атомики.сравнитьИОбменять(м, 0, 5, 8);
+        гыы в = Атомики.сравнитьИОбменять(м, 0, 5, 100);
+        гыы г = Атомики.загрузить(м, 0);
+        гыы д = Атомики.сохранить(м, 1, 3.9);
+        гыы е = м[1];
+        гыы ц = захуярить Ц8Массив(2);
+        Атомики.добавить(ц, 0, 300);
+        гыы ж = ц[0];
+        "#,
+    );
+    assert_eq!(interp.get("а"), Some(Value::Number(0.0)));
+    assert_eq!(interp.get("б"), Some(Value::Number(5.0)));
+    assert_eq!(interp.get("в"), Some(Value::Number(8.0)));
+    assert_eq!(interp.get("г"), Some(Value::Number(8.0)));
+    assert_eq!(interp.get("д"), Some(Value::Number(3.0)));
+    assert_eq!(interp.get("е"), Some(Value::Number(3.0)));
+    assert_eq!(interp.get("ж"), Some(Value::Number(44.0)));
+}
+
+#[test]
+fn atomics_wait_reports_mismatch_and_timeout() {
+    let interp = run_code(
+        r#"
+        гыы м = захуярить Ч32Массив(захуярить ОбщийБуфер(4));
+        гыы а = Атомики.ждать(м, 0, 1);
+        гыы б = Атомики.ждать(м, 0, 0, 5);
+        гыы в = Атомики.разбудить(м, 0);
+        "#,
+    );
+    assert_eq!(interp.get("а"), Some(Value::String("неравно".into())));
+    assert_eq!(interp.get("б"), Some(Value::String("истекло".into())));
+    assert_eq!(interp.get("в"), Some(Value::Number(0.0)));
+}
+
+#[test]
+fn atomics_reject_float_arrays_and_local_wait() {
+    let err = run_code_err("Атомики.добавить(захуярить Др64Массив(1), 0, 1);");
+    assert!(err.message.contains("целочисленный"), "{}", err.message);
+    let err = run_code_err("Атомики.ждать(захуярить Ч32Массив(1), 0, 0);");
+    assert!(err.message.contains("ОбщийБуфер"), "{}", err.message);
+    let err = run_code_err("Атомики.загрузить(захуярить Ч32Массив(1), 1);");
+    assert!(err.message.contains("вне массива"), "{}", err.message);
+}
@@ -0,0 +1,175 @@
+use super::*;
+
+/// Runs `main_src` next to a `задача.yopta` holding `worker_src`.
+fn run_with_worker(worker_src: &str, main_src: &str) -> Interpreter {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let id = SEQ.fetch_add(1, Ordering::SeqCst);
+    let dir = std::env::temp_dir().join(format!("yps_worker_{}_{id}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("задача.yopta"), worker_src).unwrap();
+
+    let source = SourceFile::new("test".to_string(), main_src.to_string());
+    let (tokens, lex_diags) = Lexer::new(&source).tokenize();
+    assert!(lex_diags.is_empty(), "Ошибки лексера: {lex_diags:?}");
+    let (program, parse_diags) = Parser::new(&tokens, &source).parse_program();
+    assert!(parse_diags.is_empty(), "Ошибки парсера: {parse_diags:?}");
+    let mut interp = Interpreter::new();
+    interp.set_base_path(dir.clone());
+    interp.run(&program).expect("Ошибка интерпретатора");
+    let _ = std::fs::remove_dir_all(&dir);
+    interp
+}
+
+#[test]
+fn worker_round_trips_structured_clone() {
+    let worker = r#"
+        родитель.приСообщении(йопта(с) {
+            родитель.отправить({ сумма: с.числа.свернуть((а, б) => а + б, 0), карта: с.карта, сам: с.сам === с });
+            родитель.закрыть();
+        });
+    "#;
+    let main = r#"
+        гыы сумма = 0;
+        гыы изКарты = 0;
+        гыы цикл = лож;
+        гыы код = -1;
+        гыы в = захуярить Воркер("./задача.yopta");
+        в.приСообщении(йопта(о) { сумма = о.сумма; изКарты = о.карта.взять("а"); цикл = о.сам; });
+        в.приВыходе(йопта(к) { код = к; });
+        гыы к = захуярить Карта();
+        к.поставить("а", 7);
+        гыы сообщение = { числа: [1, 2, 3], карта: к };
+        сообщение.сам = сообщение;
+        в.отправить(сообщение);
+    "#;
+    let interp = run_with_worker(worker, main);
+    assert_eq!(interp.get("сумма"), Some(Value::Number(6.0)));
+    assert_eq!(interp.get("изКарты"), Some(Value::Number(7.0)));
+    assert_eq!(interp.get("цикл"), Some(Value::Boolean(true)));
+    assert_eq!(interp.get("код"), Some(Value::Number(0.0)));
+}
+
+#[test]
+fn worker_transfer_detaches_buffer() {
+    let worker = r#"
+        родитель.приСообщении(йопта(б) {
+            родитель.отправить(захуярить Ц8Массив(б).length);
+            родитель.закрыть();
+        });
+    "#;
+    let main = r#"
+        гыы там = 0;
+        гыы буфер = захуярить ОбластьБайтов(8);
+        гыы в = захуярить Воркер("./задача.yopta");
+        в.приСообщении(йопта(н) { там = н; });
+        в.отправить(буфер, [буфер]);
+        гыы здесь = буфер.byteLength;
+    "#;
+    let interp = run_with_worker(worker, main);
+    assert_eq!(interp.get("здесь"), Some(Value::Number(0.0)));
+    assert_eq!(interp.get("там"), Some(Value::Number(8.0)));
+}
+
+#[test]
+fn worker_error_reaches_parent() {
+    let worker = r#"кидай "упс";"#;
+    let main = r#"
+        гыы текст = "";
+        гыы код = -1;
+        гыы в = захуярить Воркер("./задача.yopta");
+        в.приОшибке(йопта(о) { текст = о.message; });
+        в.приВыходе(йопта(к) { код = к; });
+    "#;
+    let interp = run_with_worker(worker, main);
+    assert_eq!(interp.get("текст"), Some(Value::String("Необработанное исключение: упс".into())));
+    assert_eq!(interp.get("код"), Some(Value::Number(1.0)));
+}
+
+#[test]
+fn worker_terminate_stops_busy_loop() {
+    let worker = r#"
+        родитель.отправить("старт");
+        потрещим (правда) {}
+    "#;
+    let main = r#"
+        гыы код = -1;
+        гыы в = захуярить Воркер("./задача.yopta");
+        в.приСообщении(йопта(с) { в.завершить(); });
+        в.приВыходе(йопта(к) { код = к; });
+    "#;
+    let interp = run_with_worker(worker, main);
+    assert_eq!(interp.get("код"), Some(Value::Number(1.0)));
+}
+
+#[test]
+fn worker_reports_missing_file() {
+    let err = run_code_err(
+        r#"
+        гыы в = захуярить Воркер("./нет_такого.yopta");
+    "#,
+    );
+    assert!(err.message.contains("нет_такого"), "{}", err.message);
+}
+
+#[test]
+fn worker_rejects_functions_in_messages() {
+    let main = r#"
+        гыы текст = "";
+        гыы в = захуярить Воркер("./задача.yopta");
+        хапнуть {
+            в.отправить({ ф: йопта() {} });
+        } гоп (о) {
+            текст = о.message;
+        }
+        в.завершить();
+    "#;
+    let interp = run_with_worker("", main);
+    let Some(Value::String(text)) = interp.get("текст") else { panic!("ожидалась строка") };
+    assert!(text.contains("нельзя клонировать"), "{text}");
+}
+
+#[test]
+fn shared_buffer_is_updated_atomically_by_both_threads() {
+    let worker = r#"
+        родитель.приСообщении(йопта(буфер) {
+            гыы м = захуярить Ч32Массив(буфер);
+            го (гыы и = 0; и < 1000; и++) { Атомики.добавить(м, 0, 1); }
+            Атомики.сохранить(м, 1, 1);
+            Атомики.разбудить(м, 1);
+            родитель.закрыть();
+        });
+    "#;
+    let main = r#"
+        гыы итог = 0;
+        гыы м = захуярить Ч32Массив(захуярить ОбщийБуфер(8));
+        гыы в = захуярить Воркер("./задача.yopta");
+        в.приВыходе(йопта(к) { итог = Атомики.загрузить(м, 0); });
+        в.отправить(м.buffer);
+        го (гыы и = 0; и < 1000; и++) { Атомики.добавить(м, 0, 1); }
+        гыы ожидание = Атомики.ждать(м, 1, 0, 5000);
+        гыы флаг = Атомики.загрузить(м, 1);
+    "#;
+    let interp = run_with_worker(worker, main);
+    assert_eq!(interp.get("итог"), Some(Value::Number(2000.0)));
+    assert_eq!(interp.get("флаг"), Some(Value::Number(1.0)));
+    assert_ne!(interp.get("ожидание"), Some(Value::String("истекло".into())));
+}
+
+#[test]
+fn shared_buffer_cannot_be_transferred() {
+    let main = r#"
+        гыы текст = "";
+        гыы б = захуярить ОбщийБуфер(4);
+        гыы в = захуярить Воркер("./задача.yopta");
+        хапнуть {
+            в.отправить(б, [б]);
+        } гоп (о) {
+            текст = о.message;
+        }
+        в.завершить();
+    "#;
+    let interp = run_with_worker("", main);
+    let Some(Value::String(text)) = interp.get("текст") else { panic!("ожидалась строка") };
+    assert!(text.contains("и так общий"), "{text}");
+}
@@ -1,16 +1,24 @@
+pub mod buffer;
 pub mod builtins;
+pub mod coverage;
 pub mod environment;
 pub mod error;
+pub mod heap_snapshot;
 pub mod host_callback;
 pub mod interpreter;
 pub mod output;
+pub mod profile;
 mod resolver;
+pub mod sources;
 pub mod stdlib;
 pub mod symbols;
+pub mod trace;
 pub mod value;
+pub mod worker;
 
 pub use error::RuntimeError;
 pub use interpreter::Interpreter;
 pub use interpreter::debug::{DEBUG_TERMINATED, DebugAction, DebugEvent, DebugHook};
 pub use output::{BufferSink, OutputSink, StdoutSink};
+pub use resolver::reads_arguments;
 pub use value::Value;
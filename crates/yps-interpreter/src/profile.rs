@@ -0,0 +1,388 @@
+//! Sampling CPU profiler shared by both backends (`yps --profile=out.cpuprofile`).
+//!
+//! The runtime is single-threaded and built on `Rc`, so the stack cannot be read from another
+//! thread. Instead a ticker thread raises a flag every interval and the executing backend takes
+//! the sample itself at the next statement (interpreter) or instruction (VM). Each sample is a
+//! root-to-leaf list of frames, each naming its function, the file it is in (a `sources` id)
+//! and its current span in that file.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use yps_lexer::{SourceFile, Span};
+
+use crate::interpreter::debug::{DebugAction, DebugEvent, DebugHook};
+use crate::stdlib::json::write_json_string;
+
+pub const MODULE_FRAME: &str = "(модуль)";
+pub const ANONYMOUS_FRAME: &str = "(анонимная)";
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1);
+/// `SampleFrame::function` of the module's top level in the interpreter.
+pub const MODULE_FUNCTION: usize = usize::MAX;
+
+/// Raises a flag every `interval` on a helper thread until dropped.
+pub struct Sampler {
+    due: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Sampler {
+    #[must_use]
+    pub fn start(interval: Duration) -> Self {
+        let due = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tick_due, tick_stop) = (Arc::clone(&due), Arc::clone(&stop));
+        thread::spawn(move || {
+            while !tick_stop.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+                tick_due.store(true, Ordering::Relaxed);
+            }
+        });
+        Self { due, stop }
+    }
+
+    /// `true` once per elapsed interval.
+    #[inline]
+    pub fn take(&self) -> bool {
+        self.due.load(Ordering::Relaxed) && self.due.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// One frame of a sample.
+#[derive(Debug, Clone)]
+pub struct SampleFrame {
+    pub name: Rc<str>,
+    /// `sources` id of the file the function is in; `span` points into it.
+    pub file: usize,
+    /// Tells apart functions of one file that share a name: the start of the body for the
+    /// interpreter, the address of the prototype for the VM.
+    pub function: usize,
+    pub span: Span,
+}
+
+struct Node {
+    name: Rc<str>,
+    file: usize,
+    parent: usize,
+    children: Vec<usize>,
+    first_span: Span,
+    self_hits: u64,
+}
+
+/// Call tree built from samples; node 0 is the synthetic `(root)`.
+pub struct Profile {
+    nodes: Vec<Node>,
+    /// `(parent, file, function)` to node.
+    index: HashMap<(usize, usize, usize), usize>,
+    samples: Vec<usize>,
+    times: Vec<Instant>,
+    /// Position of every frame of each sample, used to attribute time to source lines.
+    sample_spans: Vec<Box<[Span]>>,
+    start: Instant,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profile {
+    #[must_use]
+    pub fn new() -> Self {
+        let root = Node {
+            name: Rc::from("(root)"),
+            file: 0,
+            parent: 0,
+            children: Vec::new(),
+            first_span: Span { start: 0, end: 0 },
+            self_hits: 0,
+        };
+        Self {
+            nodes: vec![root],
+            index: HashMap::new(),
+            samples: Vec::new(),
+            times: Vec::new(),
+            sample_spans: Vec::new(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Records one sample; `stack` goes from the outermost frame to the innermost one.
+    pub fn sample(&mut self, stack: &[SampleFrame]) {
+        let mut node = 0;
+        for frame in stack {
+            let key = (node, frame.file, frame.function);
+            node = match self.index.get(&key) {
+                Some(&id) => id,
+                None => {
+                    let id = self.nodes.len();
+                    self.nodes.push(Node {
+                        name: Rc::clone(&frame.name),
+                        file: frame.file,
+                        parent: node,
+                        children: Vec::new(),
+                        first_span: frame.span,
+                        self_hits: 0,
+                    });
+                    self.nodes[node].children.push(id);
+                    self.index.insert(key, id);
+                    id
+                }
+            };
+        }
+        self.nodes[node].self_hits += 1;
+        self.samples.push(node);
+        self.times.push(Instant::now());
+        self.sample_spans.push(stack.iter().map(|frame| frame.span).collect());
+    }
+
+    #[must_use]
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Chrome DevTools `.cpuprofile` JSON, with `files` indexed by `sources` id and each one
+    /// its own script. Line and column numbers are 0-based as DevTools expects; per-line `self`
+    /// time goes into `positionTicks` (1-based lines).
+    #[must_use]
+    pub fn to_cpuprofile(&self, files: &[SourceFile]) -> String {
+        let mut line_hits: Vec<HashMap<usize, u64>> = vec![HashMap::new(); self.nodes.len()];
+        for (node, spans) in self.samples.iter().zip(&self.sample_spans) {
+            if let (Some(leaf), Some(source)) = (spans.last(), files.get(self.nodes[*node].file)) {
+                *line_hits[*node].entry(source.position(leaf.start).0).or_default() += 1;
+            }
+        }
+
+        let mut out = String::from("{\"nodes\":[");
+        for (id, node) in self.nodes.iter().enumerate() {
+            if id > 0 {
+                out.push(',');
+            }
+            let source = files.get(node.file).filter(|_| id > 0);
+            let (line, column) = source.map_or((-1, -1), |source| position_zero_based(source, node.first_span.start));
+            let _ = write!(out, "{{\"id\":{},\"callFrame\":{{\"functionName\":", id + 1);
+            write_json_string(&mut out, &node.name);
+            let _ = write!(out, ",\"scriptId\":\"{}\",\"url\":", if id == 0 { 0 } else { node.file + 1 });
+            write_json_string(&mut out, source.map_or("", |source| &source.name));
+            let _ = write!(out, ",\"lineNumber\":{line},\"columnNumber\":{column}}},\"hitCount\":{}", node.self_hits);
+            out.push_str(",\"children\":[");
+            push_joined(&mut out, node.children.iter().map(|c| c + 1));
+            out.push(']');
+            let mut ticks: Vec<(usize, u64)> = line_hits[id].iter().map(|(l, n)| (*l, *n)).collect();
+            if !ticks.is_empty() {
+                ticks.sort_unstable();
+                out.push_str(",\"positionTicks\":[");
+                for (i, (line, n)) in ticks.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    let _ = write!(out, "{{\"line\":{line},\"ticks\":{n}}}");
+                }
+                out.push(']');
+            }
+            out.push('}');
+        }
+
+        let end = self.times.last().copied().unwrap_or(self.start);
+        let _ =
+            write!(out, "],\"startTime\":0,\"endTime\":{},\"samples\":[", end.duration_since(self.start).as_micros());
+        push_joined(&mut out, self.samples.iter().map(|s| s + 1));
+        out.push_str("],\"timeDeltas\":[");
+        let mut previous = self.start;
+        push_joined(
+            &mut out,
+            self.times.iter().map(|t| {
+                let delta = t.duration_since(previous).as_micros();
+                previous = *t;
+                delta
+            }),
+        );
+        out.push_str("]}");
+        out
+    }
+
+    /// Folded stacks (`кадр;кадр;кадр число`) for `flamegraph.pl` / inferno; each frame is
+    /// `имя:строка` so hot lines inside one function become separate boxes, and frames outside
+    /// the entry script (`files[0]`) are `имя:файл:строка`.
+    #[must_use]
+    pub fn to_folded(&self, files: &[SourceFile]) -> String {
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for (node, spans) in self.samples.iter().zip(&self.sample_spans) {
+            let mut chain = Vec::new();
+            let mut current = *node;
+            while current != 0 {
+                chain.push(current);
+                current = self.nodes[current].parent;
+            }
+            let mut key = String::new();
+            for (i, (id, span)) in chain.iter().rev().zip(spans.iter()).enumerate() {
+                if i > 0 {
+                    key.push(';');
+                }
+                let node = &self.nodes[*id];
+                let _ = write!(key, "{}:", node.name);
+                if node.file > 0 {
+                    let _ = write!(key, "{}:", files.get(node.file).map_or("?", |source| short_name(&source.name)));
+                }
+                let _ = write!(key, "{}", files.get(node.file).map_or(0, |source| source.position(span.start).0));
+            }
+            *counts.entry(key).or_default() += 1;
+        }
+        let mut lines: Vec<(String, u64)> = counts.into_iter().collect();
+        lines.sort();
+        let mut out = String::new();
+        for (stack, n) in lines {
+            let _ = writeln!(out, "{stack} {n}");
+        }
+        out
+    }
+}
+
+fn position_zero_based(source: &SourceFile, offset: usize) -> (i64, i64) {
+    let (line, column) = source.position(offset);
+    (line as i64 - 1, column as i64 - 1)
+}
+
+fn short_name(path: &str) -> &str {
+    std::path::Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or(path)
+}
+
+fn push_joined<T: std::fmt::Display>(out: &mut String, items: impl Iterator<Item = T>) {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "{item}");
+    }
+}
+
+/// Display name of a frame: empty names belong to anonymous functions and arrows.
+#[must_use]
+pub fn frame_name(name: &str) -> Rc<str> {
+    if name.is_empty() { Rc::from(ANONYMOUS_FRAME) } else { Rc::from(name) }
+}
+
+/// Samples the tree-walking interpreter through its per-statement debug hook.
+pub struct ProfileHook {
+    profile: Rc<RefCell<Profile>>,
+    sampler: Sampler,
+}
+
+impl ProfileHook {
+    #[must_use]
+    pub fn new(profile: Rc<RefCell<Profile>>, sampler: Sampler) -> Self {
+        Self { profile, sampler }
+    }
+}
+
+impl DebugHook for ProfileHook {
+    fn on_statement(&mut self, event: DebugEvent<'_>) -> Option<DebugAction> {
+        if !self.sampler.take() {
+            return None;
+        }
+        // Frame `n` records where frame `n - 1` was called from, so each function's current
+        // position is the call-site span of the frame above it.
+        // Imported modules run their top level in an interpreter of their own, so the module
+        // frame here is always the entry script's.
+        let calls = event.interp.debug_call_stack();
+        let mut stack = Vec::with_capacity(calls.len() + 1);
+        stack.push(SampleFrame {
+            name: Rc::from(MODULE_FRAME),
+            file: 0,
+            function: MODULE_FUNCTION,
+            span: calls.first().map_or(event.span, |f| f.span),
+        });
+        for (i, frame) in calls.iter().enumerate() {
+            stack.push(SampleFrame {
+                name: frame_name(&frame.name),
+                file: frame.file,
+                function: frame.decl.start,
+                span: calls.get(i + 1).map_or(event.span, |f| f.span),
+            });
+        }
+        self.profile.borrow_mut().sample(&stack);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(name: &str, file: usize, function: usize, start: usize) -> SampleFrame {
+        SampleFrame { name: Rc::from(name), file, function, span: Span { start, end: start + 1 } }
+    }
+
+    fn module(start: usize) -> SampleFrame {
+        frame(MODULE_FRAME, 0, MODULE_FUNCTION, start)
+    }
+
+    fn files() -> Vec<SourceFile> {
+        vec![
+            SourceFile::new("п.yopta".to_string(), "а\nб\nв\n".to_string()),
+            SourceFile::new("/либ/мод.yopta".to_string(), "г\nд\n".to_string()),
+        ]
+    }
+
+    #[test]
+    fn samples_build_a_shared_call_tree() {
+        let mut profile = Profile::new();
+        profile.sample(&[module(0), frame("ф", 0, 2, 3)]);
+        profile.sample(&[module(0), frame("ф", 0, 2, 6)]);
+        profile.sample(&[module(0)]);
+
+        assert_eq!(profile.sample_count(), 3);
+        assert_eq!(profile.nodes.len(), 3, "(root), (модуль) и ф");
+        assert_eq!(profile.nodes[2].self_hits, 2);
+    }
+
+    #[test]
+    fn functions_sharing_a_name_get_nodes_of_their_own() {
+        let mut profile = Profile::new();
+        profile.sample(&[module(0), frame("ф", 0, 2, 3)]);
+        profile.sample(&[module(0), frame("ф", 1, 2, 0)]);
+        profile.sample(&[module(0), frame("ф", 0, 4, 6)]);
+
+        assert_eq!(profile.nodes.len(), 5, "(root), (модуль) и три разные ф");
+        let folded = profile.to_folded(&files());
+        assert_eq!(folded, "(модуль):1;ф:2 1\n(модуль):1;ф:3 1\n(модуль):1;ф:мод.yopta:1 1\n");
+        let json = profile.to_cpuprofile(&files());
+        assert!(json.contains("\"scriptId\":\"2\",\"url\":\"/либ/мод.yopta\""), "{json}");
+    }
+
+    #[test]
+    fn folded_output_counts_identical_stacks_by_line() {
+        let mut profile = Profile::new();
+        profile.sample(&[module(0), frame("ф", 0, 2, 3)]);
+        profile.sample(&[module(0), frame("ф", 0, 2, 3)]);
+        profile.sample(&[module(0), frame("ф", 0, 2, 6)]);
+
+        let folded = profile.to_folded(&files());
+        assert_eq!(folded, "(модуль):1;ф:2 2\n(модуль):1;ф:3 1\n");
+    }
+
+    #[test]
+    fn cpuprofile_lists_nodes_samples_and_line_ticks() {
+        let mut profile = Profile::new();
+        profile.sample(&[module(3)]);
+
+        let json = profile.to_cpuprofile(&files());
+        assert!(json.starts_with("{\"nodes\":[{\"id\":1,"), "{json}");
+        assert!(json.contains("\"functionName\":\"(модуль)\""), "{json}");
+        assert!(json.contains("\"positionTicks\":[{\"line\":2,\"ticks\":1}]"), "{json}");
+        assert!(json.contains("\"samples\":[2]"), "{json}");
+    }
+}
@@ -1,10 +1,12 @@
 use std::collections::HashSet;
 
 use yps_parser::ast::{
-    Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, ObjectEntry, Param, Pattern, Program, PropKey, Stmt,
-    TemplatePart,
+    Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, MatchPattern, ObjectEntry, Param, Pattern, Program,
+    PropKey, Stmt, TemplatePart,
 };
 
+use crate::symbols;
+
 #[derive(Default)]
 pub(crate) struct RootResolution {
     reads: HashSet<usize>,
@@ -21,20 +23,30 @@ impl RootResolution {
 }
 
 pub(crate) fn resolve(program: &Program) -> RootResolution {
-    let mut resolver = Resolver { reads: HashSet::new(), scopes: Vec::new(), disabled: false };
+    let mut resolver = Resolver::default();
     for stmt in &program.items {
         resolver.walk_stmt(stmt);
     }
     if resolver.disabled { RootResolution::default() } else { RootResolution { reads: resolver.reads } }
 }
 
+/// Whether a non-arrow function reads its `аргументы`, directly or from a nested arrow function.
+#[must_use]
+pub fn reads_arguments(params: &[Param], body: &Block) -> bool {
+    let mut resolver = Resolver::default();
+    resolver.walk_callable(None, params, body, true);
+    resolver.arguments_read
+}
+
 const STACK_RED_ZONE: usize = 256 * 1024;
 const STACK_GROW_SIZE: usize = 8 * 1024 * 1024;
 
+#[derive(Default)]
 struct Resolver {
     reads: HashSet<usize>,
     scopes: Vec<HashSet<String>>,
     disabled: bool,
+    arguments_read: bool,
 }
 
 impl Resolver {
@@ -42,14 +54,25 @@ impl Resolver {
         if self.scopes.iter().any(|scope| scope.contains(name)) {
             return;
         }
+        if name == symbols::ARGUMENTS {
+            self.arguments_read = true;
+        }
         self.reads.insert(start);
     }
 
     fn walk_function(&mut self, own_name: Option<&str>, params: &[Param], body: &Block) {
+        self.walk_callable(own_name, params, body, false);
+    }
+
+    /// Arrow functions see the `аргументы` of the function around them; other functions have their own.
+    fn walk_callable(&mut self, own_name: Option<&str>, params: &[Param], body: &Block, is_arrow: bool) {
         let mut locals = HashSet::new();
         if let Some(name) = own_name {
             locals.insert(name.to_string());
         }
+        if !is_arrow {
+            locals.insert(symbols::ARGUMENTS.to_string());
+        }
         for param in params {
             collect_param_names(param, &mut locals);
         }
@@ -115,7 +138,12 @@ impl Resolver {
             }
             Stmt::Expr { expr, .. } => self.walk_expr(expr),
             Stmt::Block(block) => self.walk_block(block),
-            Stmt::Empty { .. } | Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Debugger { .. } => {}
+            Stmt::Empty { .. }
+            | Stmt::Break { .. }
+            | Stmt::Continue { .. }
+            | Stmt::Debugger { .. }
+            | Stmt::TypeAlias { .. }
+            | Stmt::Error { .. } => {}
             Stmt::If { condition, then_branch, else_branch, .. } => {
                 self.walk_expr(condition);
                 self.walk_stmt(then_branch);
@@ -210,6 +238,11 @@ impl Resolver {
                 }
             }
             Stmt::Using { init, .. } => self.walk_expr(init),
+            Stmt::EnumDecl { members, .. } => {
+                for init in members.iter().filter_map(|m| m.init.as_ref()) {
+                    self.walk_expr(init);
+                }
+            }
             Stmt::Import { .. } => self.disabled = true,
             Stmt::Export { kind, .. } => match kind {
                 ExportKind::Declaration(decl) => self.walk_stmt(decl),
@@ -257,7 +290,13 @@ impl Resolver {
 
     fn walk_expr_inner(&mut self, expr: &Expr) {
         match expr {
-            Expr::Identifier(ident) => self.record_read(&ident.name, ident.span.start),
+            Expr::Identifier(ident) => {
+                // Code compiled at run time has spans of its own that may collide with these.
+                if matches!(ident.name.as_str(), "ебал" | "Функция") {
+                    self.disabled = true;
+                }
+                self.record_read(&ident.name, ident.span.start)
+            }
             Expr::Literal(literal) => self.walk_literal(literal),
             Expr::Unary { expr, .. }
             | Expr::Postfix { expr, .. }
@@ -286,7 +325,7 @@ impl Resolver {
                 self.walk_expr(then_expr);
                 self.walk_expr(else_expr);
             }
-            Expr::ArrowFunction { params, body, .. } => self.walk_function(None, params, body),
+            Expr::ArrowFunction { params, body, .. } => self.walk_callable(None, params, body, true),
             Expr::FunctionExpr { name, params, body, .. } => {
                 let own_name = name.as_ref().map(|name| name.name.as_str());
                 self.walk_function(own_name, params, body);
@@ -304,7 +343,11 @@ impl Resolver {
                     self.walk_expr(expr);
                 }
             }
-            Expr::This { .. } | Expr::Super { .. } => {}
+            Expr::This { .. }
+            | Expr::Super { .. }
+            | Expr::ImportMeta { .. }
+            | Expr::NewTarget { .. }
+            | Expr::Error { .. } => {}
             Expr::Yield { argument, .. } => {
                 if let Some(argument) = argument {
                     self.walk_expr(argument);
@@ -315,6 +358,36 @@ impl Resolver {
                 self.disabled = true;
                 self.walk_expr(source);
             }
+            Expr::Match { subject, arms, .. } => {
+                self.walk_expr(subject);
+                for arm in arms {
+                    let mut locals = HashSet::new();
+                    let pattern = match &arm.pattern {
+                        MatchPattern::Bind(pattern) => Some(pattern),
+                        MatchPattern::Instance { class, pattern, .. } => {
+                            self.walk_expr(class);
+                            pattern.as_ref()
+                        }
+                        MatchPattern::Value(value) => {
+                            self.walk_expr(value);
+                            None
+                        }
+                        MatchPattern::Wildcard { .. } => None,
+                    };
+                    if let Some(pattern) = pattern {
+                        collect_pattern_names(pattern, &mut locals);
+                    }
+                    self.scopes.push(locals);
+                    if let Some(pattern) = pattern {
+                        self.walk_pattern_defaults(pattern);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.walk_expr(guard);
+                    }
+                    self.walk_expr(&arm.body);
+                    self.scopes.pop();
+                }
+            }
         }
     }
 
@@ -402,12 +475,12 @@ pub(crate) fn lexical_declarations(stmts: &[Stmt]) -> Vec<String> {
     for stmt in stmts {
         match stmt {
             Stmt::VarDecl { pattern, .. } => collect_pattern_names(pattern, &mut out),
-            Stmt::ClassDecl { name, .. } | Stmt::Using { name, .. } => {
+            Stmt::ClassDecl { name, .. } | Stmt::Using { name, .. } | Stmt::EnumDecl { name, .. } => {
                 out.insert(name.name.clone());
             }
             Stmt::Export { kind: ExportKind::Declaration(decl), .. } => match decl.as_ref() {
                 Stmt::VarDecl { pattern, .. } => collect_pattern_names(pattern, &mut out),
-                Stmt::ClassDecl { name, .. } => {
+                Stmt::ClassDecl { name, .. } | Stmt::EnumDecl { name, .. } => {
                     out.insert(name.name.clone());
                 }
                 _ => {}
@@ -427,7 +500,10 @@ fn collect_block_locals(block: &Block, out: &mut HashSet<String>) {
 fn collect_stmt_locals(stmt: &Stmt, out: &mut HashSet<String>) {
     match stmt {
         Stmt::VarDecl { pattern, .. } => collect_pattern_names(pattern, out),
-        Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } | Stmt::Using { name, .. } => {
+        Stmt::FunctionDecl { name, .. }
+        | Stmt::ClassDecl { name, .. }
+        | Stmt::Using { name, .. }
+        | Stmt::EnumDecl { name, .. } => {
             out.insert(name.name.clone());
         }
         Stmt::Block(block) => collect_block_locals(block, out),
@@ -0,0 +1,59 @@
+//! Files whose code runs on this thread. A `Span` is only an offset into one file, so coverage
+//! and the profiler, which see code from every imported module, also record the id a file was
+//! registered under here. Id 0 is the entry script, which is also the file of any scope nobody
+//! assigned one to. Like `coverage`, the state is thread-local because the VM reaches the stdlib
+//! through a fresh interpreter per bridged call.
+
+use std::cell::RefCell;
+
+use yps_lexer::SourceFile;
+
+thread_local! {
+    static FILES: RefCell<Vec<SourceFile>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Forgets the files of any previous run and registers `entry` as id 0.
+pub fn start(entry: SourceFile) {
+    FILES.with(|files| *files.borrow_mut() = vec![entry]);
+}
+
+/// Id of `source`; registering a file twice returns the same id. Without `start`, id 0 stays
+/// reserved for an unnamed entry script.
+pub fn register(source: &SourceFile) -> usize {
+    FILES.with(|files| {
+        let mut files = files.borrow_mut();
+        if files.is_empty() {
+            files.push(SourceFile::new(String::new(), String::new()));
+        }
+        if let Some(id) = files.iter().position(|f| f.name == source.name) {
+            return id;
+        }
+        files.push(source.clone());
+        files.len() - 1
+    })
+}
+
+/// Every registered file, indexed by id.
+#[must_use]
+pub fn files() -> Vec<SourceFile> {
+    FILES.with(|files| files.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> SourceFile {
+        SourceFile::new(name.to_string(), String::new())
+    }
+
+    #[test]
+    fn the_entry_keeps_id_zero_and_modules_are_numbered_once() {
+        start(file("главный.yopta"));
+        assert_eq!(register(&file("мод.yopta")), 1);
+        assert_eq!(register(&file("главный.yopta")), 0);
+        assert_eq!(register(&file("мод.yopta")), 1);
+        let names: Vec<String> = files().into_iter().map(|f| f.name).collect();
+        assert_eq!(names, ["главный.yopta", "мод.yopta"]);
+    }
+}
@@ -163,6 +163,12 @@ pub fn subscribe_timer_cancel(state: &Rc<RefCell<AbortState>>, timer_id: u64) {
     st.listeners.push((id, Value::AbortCancelTimer { timer_id }));
 }
 
+/// Methods of `КонтроллёрОтмены`.
+pub const CONTROLLER_METHODS: &[&str] = &["отменить"];
+
+/// Methods of `СигналОтмены`.
+pub const SIGNAL_METHODS: &[&str] = &["подписатьсяНаОтмену", "выкинутьЕслиОтменён"];
+
 pub fn call(
     interp: &mut Interpreter,
     receiver: Value,
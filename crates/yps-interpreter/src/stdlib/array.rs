@@ -137,6 +137,10 @@ pub fn call(
             let found = snapshot.iter().skip(start).any(|v| same_value_zero(v, target));
             Ok(Value::Boolean(found))
         }
+        "toLocaleString" | "вЛокальнуюСтроку" => {
+            let snapshot = rc.borrow().clone();
+            Ok(Value::String(super::intl::array_to_locale_string(&snapshot, &args, span)?.into()))
+        }
         "join" | "склеить" => {
             let sep = if args.is_empty() {
                 ",".to_string()
@@ -510,83 +514,86 @@ pub fn call(
     }
 }
 
+pub const METHODS: &[&str] = &[
+    "push",
+    "добавить",
+    "втолкнуть",
+    "pop",
+    "вытолкнуть",
+    "shift",
+    "снять",
+    "unshift",
+    "подсунуть",
+    "slice",
+    "отрезать",
+    "indexOf",
+    "найтиИндекс",
+    "lastIndexOf",
+    "найтиПоследнийПо",
+    "includes",
+    "включает",
+    "join",
+    "склеить",
+    "toLocaleString",
+    "вЛокальнуюСтроку",
+    "reverse",
+    "перевернуть",
+    "concat",
+    "склеитьМассивы",
+    "sort",
+    "сортировать",
+    "map",
+    "преобразовать",
+    "filter",
+    "отфильтровать",
+    "reduce",
+    "свернуть",
+    "reduceRight",
+    "свернутьСправа",
+    "forEach",
+    "каждый",
+    "find",
+    "найти",
+    "findIndex",
+    "найтиИндексПо",
+    "some",
+    "некоторые",
+    "every",
+    "все",
+    "at",
+    "поИндексу",
+    "flat",
+    "плоский",
+    "flatMap",
+    "плоскоПреобразовать",
+    "findLast",
+    "найтиПоследний",
+    "findLastIndex",
+    "найтиПоследнийИндекс",
+    "toReversed",
+    "перевёрнутый",
+    "toSorted",
+    "отсортированный",
+    "splice",
+    "вырезать",
+    "toSpliced",
+    "вырезанный",
+    "with",
+    "сЗаменой",
+    "fill",
+    "заполнить",
+    "copyWithin",
+    "копироватьВнутри",
+    "entries",
+    "записи",
+    "keys",
+    "ключи",
+    "values",
+    "значения",
+];
+
 pub fn method_exists(name: &str) -> bool {
-    matches!(
-        name,
-        "push"
-            | "добавить"
-            | "втолкнуть"
-            | "pop"
-            | "вытолкнуть"
-            | "shift"
-            | "снять"
-            | "unshift"
-            | "подсунуть"
-            | "slice"
-            | "отрезать"
-            | "indexOf"
-            | "найтиИндекс"
-            | "lastIndexOf"
-            | "найтиПоследнийПо"
-            | "includes"
-            | "включает"
-            | "join"
-            | "склеить"
-            | "reverse"
-            | "перевернуть"
-            | "concat"
-            | "склеитьМассивы"
-            | "sort"
-            | "сортировать"
-            | "map"
-            | "преобразовать"
-            | "filter"
-            | "отфильтровать"
-            | "reduce"
-            | "свернуть"
-            | "reduceRight"
-            | "свернутьСправа"
-            | "forEach"
-            | "каждый"
-            | "find"
-            | "найти"
-            | "findIndex"
-            | "найтиИндексПо"
-            | "some"
-            | "некоторые"
-            | "every"
-            | "все"
-            | "at"
-            | "поИндексу"
-            | "flat"
-            | "плоский"
-            | "flatMap"
-            | "плоскоПреобразовать"
-            | "findLast"
-            | "найтиПоследний"
-            | "findLastIndex"
-            | "найтиПоследнийИндекс"
-            | "toReversed"
-            | "перевёрнутый"
-            | "toSorted"
-            | "отсортированный"
-            | "splice"
-            | "вырезать"
-            | "toSpliced"
-            | "вырезанный"
-            | "with"
-            | "сЗаменой"
-            | "fill"
-            | "заполнить"
-            | "copyWithin"
-            | "копироватьВнутри"
-            | "entries"
-            | "записи"
-            | "keys"
-            | "ключи"
-            | "values"
-            | "значения"
-    )
+    METHODS.contains(&name)
 }
 
 fn sort_snapshot(
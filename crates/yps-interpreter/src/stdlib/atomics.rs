@@ -0,0 +1,139 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use yps_lexer::Span;
+
+use crate::buffer::{SharedBuffer, WaitOutcome};
+use crate::error::RuntimeError;
+use crate::value::{TypedArrayData, TypedArrayKind, Value, to_int_n};
+
+use super::typed_array::to_number_input;
+use super::{builtin, object_of};
+
+pub fn build_object() -> Value {
+    object_of(&[
+        ("добавить", builtin("Атомики.добавить")),
+        ("сравнитьИОбменять", builtin("Атомики.сравнитьИОбменять")),
+        ("загрузить", builtin("Атомики.загрузить")),
+        ("сохранить", builtin("Атомики.сохранить")),
+        ("ждать", builtin("Атомики.ждать")),
+        ("разбудить", builtin("Атомики.разбудить")),
+    ])
+}
+
+/// Every operation holds the buffer's lock from its read to its write, so on an `ОбщийБуфер`
+/// they are sequentially consistent with each other and with plain element accesses.
+pub fn call_static(method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let name = format!("Атомики.{method}");
+    let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Undefined);
+    let ta = integer_array(&arg(0), &name, span)?;
+    let pos = element_position(&ta, &arg(1), &name, span)?;
+    let kind = ta.kind;
+    match method {
+        "загрузить" => Ok(Value::Number(kind.read_le(&ta.buffer.borrow(), pos))),
+        "сохранить" => {
+            let value = to_number_input(&arg(2), span)?;
+            kind.write_le(&mut ta.buffer.borrow_mut(), pos, value);
+            Ok(Value::Number(if value.is_nan() { 0.0 } else { value.trunc() + 0.0 }))
+        }
+        "добавить" => {
+            let delta = to_number_input(&arg(2), span)?;
+            let mut bytes = ta.buffer.borrow_mut();
+            let old = kind.read_le(&bytes, pos);
+            kind.write_le(&mut bytes, pos, old + coerce(kind, delta));
+            Ok(Value::Number(old))
+        }
+        "сравнитьИОбменять" => {
+            let expected = coerce(kind, to_number_input(&arg(2), span)?);
+            let replacement = to_number_input(&arg(3), span)?;
+            let mut bytes = ta.buffer.borrow_mut();
+            let old = kind.read_le(&bytes, pos);
+            if old == expected {
+                kind.write_le(&mut bytes, pos, replacement);
+            }
+            Ok(Value::Number(old))
+        }
+        "ждать" => wait(&ta, pos, &arg(2), &arg(3), &name, span),
+        "разбудить" => {
+            let count = match arg(2) {
+                Value::Undefined => usize::MAX,
+                other => {
+                    let n = to_number_input(&other, span)?;
+                    if n.is_nan() || n <= 0.0 { 0 } else { n.min(usize::MAX as f64) as usize }
+                }
+            };
+            let woken = match &ta.buffer {
+                SharedBuffer::Shared(memory) if kind == TypedArrayKind::I32 => memory.wake(pos, count),
+                _ => 0,
+            };
+            Ok(Value::Number(woken as f64))
+        }
+        _ => Err(RuntimeError::new(format!("Неизвестный метод 'Атомики.{method}'"), span)),
+    }
+}
+
+fn wait(
+    ta: &TypedArrayData,
+    pos: usize,
+    value: &Value,
+    timeout: &Value,
+    name: &str,
+    span: Span,
+) -> Result<Value, RuntimeError> {
+    let SharedBuffer::Shared(memory) = &ta.buffer else {
+        return Err(RuntimeError::new(format!("'{name}' ждёт только на 'ОбщийБуфер'"), span));
+    };
+    if ta.kind != TypedArrayKind::I32 {
+        return Err(RuntimeError::new(
+            format!("'{name}' ожидает 'Ч32Массив', получено '{}'", ta.kind.type_name()),
+            span,
+        ));
+    }
+    let expected = to_int_n(to_number_input(value, span)?, 32) as f64;
+    let timeout = match timeout {
+        Value::Undefined => None,
+        other => {
+            let ms = to_number_input(other, span)?;
+            if ms.is_nan() || ms == f64::INFINITY { None } else { Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0)) }
+        }
+    };
+    let holds = |bytes: &[u8]| TypedArrayKind::I32.read_le(bytes, pos) == expected;
+    match memory.wait(pos, holds, timeout, crate::worker::stop_requested) {
+        WaitOutcome::Interrupted => Err(RuntimeError::new(crate::worker::WORKER_TERMINATED, span)),
+        outcome => Ok(Value::String(outcome.as_str().into())),
+    }
+}
+
+/// What `value` reads back as once stored into an element of `kind`.
+fn coerce(kind: TypedArrayKind, value: f64) -> f64 {
+    let mut scratch = [0u8; 8];
+    kind.write_le(&mut scratch, 0, value);
+    kind.read_le(&scratch, 0)
+}
+
+fn integer_array(value: &Value, name: &str, span: Span) -> Result<Rc<TypedArrayData>, RuntimeError> {
+    match value {
+        Value::TypedArray(ta)
+            if !matches!(ta.kind, TypedArrayKind::U8Clamped | TypedArrayKind::F32 | TypedArrayKind::F64) =>
+        {
+            Ok(Rc::clone(ta))
+        }
+        other => Err(RuntimeError::new(
+            format!("'{name}' ожидает целочисленный типизированный массив, получено '{}'", other.type_name()),
+            span,
+        )),
+    }
+}
+
+/// Byte position of element `index`, which must lie inside the array.
+fn element_position(ta: &TypedArrayData, index: &Value, name: &str, span: Span) -> Result<usize, RuntimeError> {
+    let i = match index {
+        Value::Undefined => 0.0,
+        other => to_number_input(other, span)?,
+    };
+    let i = if i.is_nan() { 0.0 } else { i.trunc() };
+    if i < 0.0 || i >= ta.live_length() as f64 {
+        return Err(RuntimeError::new(format!("'{name}': индекс {i} вне массива длины {}", ta.live_length()), span));
+    }
+    Ok(ta.offset + i as usize * ta.kind.element_size())
+}
@@ -47,6 +47,42 @@ pub fn construct(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     Ok(Value::DataView { buffer, offset, length })
 }
 
+/// Methods of `ОбзорБайтов`.
+pub const METHODS: &[&str] = &[
+    "взятьЦ8",
+    "getUint8",
+    "взятьЧ8",
+    "getInt8",
+    "взятьЦ16",
+    "getUint16",
+    "взятьЧ16",
+    "getInt16",
+    "взятьЦ32",
+    "getUint32",
+    "взятьЧ32",
+    "getInt32",
+    "взятьДр32",
+    "getFloat32",
+    "взятьДр64",
+    "getFloat64",
+    "задатьЦ8",
+    "setUint8",
+    "задатьЧ8",
+    "setInt8",
+    "задатьЦ16",
+    "setUint16",
+    "задатьЧ16",
+    "setInt16",
+    "задатьЦ32",
+    "setUint32",
+    "задатьЧ32",
+    "setInt32",
+    "задатьДр32",
+    "setFloat32",
+    "задатьДр64",
+    "setFloat64",
+];
+
 pub fn call(
     _interp: &mut Interpreter,
     receiver: Value,
@@ -57,6 +93,9 @@ pub fn call(
     let Value::DataView { buffer, offset, length } = receiver else {
         return Err(RuntimeError::new(format!("Тип '{}' не является ОбзорБайтов", receiver.type_name()), span));
     };
+    if offset + length > buffer.borrow().len() {
+        return Err(RuntimeError::new("'ОбзорБайтов': область байтов передана в Воркер", span));
+    }
     match method {
         "взятьЦ8" | "getUint8" => {
             let raw = read_byte(&buffer, offset, &args, length, method, span)?;
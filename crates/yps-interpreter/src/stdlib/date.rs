@@ -6,7 +6,9 @@ use yps_lexer::Span;
 
 use crate::error::RuntimeError;
 use crate::interpreter::Interpreter;
+use crate::stdlib::intl::{DateDefaults, date_to_locale_string};
 use crate::stdlib::{as_number, require_args};
+use crate::trace::{self, EventKind};
 use crate::value::Value;
 
 const MAX_TIME: f64 = 8.64e15;
@@ -20,10 +22,10 @@ const FIELD_SECONDS: usize = 5;
 const FIELD_MILLIS: usize = 6;
 
 pub fn now_ms() -> f64 {
-    match SystemTime::now().duration_since(UNIX_EPOCH) {
+    trace::number(EventKind::Time, || match SystemTime::now().duration_since(UNIX_EPOCH) {
         Ok(d) => d.as_millis() as f64,
         Err(e) => -(e.duration().as_millis() as f64),
-    }
+    })
 }
 
 pub struct Components {
@@ -37,7 +39,7 @@ pub struct Components {
     pub millis: u32,
 }
 
-fn civil_from_days(z: i64) -> (i64, u32, u32) {
+pub(crate) fn civil_from_days(z: i64) -> (i64, u32, u32) {
     let z = z + 719_468;
     let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
     let doe = (z - era * 146_097) as u64;
@@ -107,7 +109,7 @@ fn parse_iso(s: &str) -> f64 {
     parse_iso_opt(s.trim()).unwrap_or(f64::NAN)
 }
 
-fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+pub(crate) fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
     let y = if m <= 2 { y - 1 } else { y };
     let era = if y >= 0 { y } else { y - 399 } / 400;
     let yoe = (y - era * 400) as u64;
@@ -343,6 +345,52 @@ pub fn call_static(
     }
 }
 
+/// Methods of `Дата` instances.
+pub const INSTANCE_METHODS: &[&str] = &[
+    "времяМс",
+    "вЧисло",
+    "год",
+    "годUTC",
+    "месяц",
+    "месяцUTC",
+    "день",
+    "деньUTC",
+    "деньНедели",
+    "деньНеделиUTC",
+    "часы",
+    "часыUTC",
+    "минуты",
+    "минутыUTC",
+    "секунды",
+    "секундыUTC",
+    "миллисекунды",
+    "миллисекундыUTC",
+    "смещениеЧасовогоПояса",
+    "вИСО",
+    "вСтроку",
+    "вЛокальнуюСтроку",
+    "toLocaleString",
+    "вЛокальнуюДату",
+    "toLocaleDateString",
+    "вЛокальноеВремя",
+    "toLocaleTimeString",
+    "поставитьВремя",
+    "поставитьГод",
+    "поставитьГодUTC",
+    "поставитьМесяц",
+    "поставитьМесяцUTC",
+    "поставитьДень",
+    "поставитьДеньUTC",
+    "поставитьЧасы",
+    "поставитьЧасыUTC",
+    "поставитьМинуты",
+    "поставитьМинутыUTC",
+    "поставитьСекунды",
+    "поставитьСекундыUTC",
+    "поставитьМиллисекунды",
+    "поставитьМиллисекундыUTC",
+];
+
 pub fn call_instance(
     _interp: &mut Interpreter,
     receiver: Value,
@@ -377,6 +425,22 @@ pub fn call_instance(
             Some(c) => format_components(c).into(),
             None => "Invalid Date".into(),
         }),
+        "вЛокальнуюСтроку"
+        | "toLocaleString"
+        | "вЛокальнуюДату"
+        | "toLocaleDateString"
+        | "вЛокальноеВремя"
+        | "toLocaleTimeString" => {
+            let defaults = match method {
+                "вЛокальнуюДату" | "toLocaleDateString" => DateDefaults::Date,
+                "вЛокальноеВремя" | "toLocaleTimeString" => DateDefaults::Time,
+                _ => DateDefaults::DateTime,
+            };
+            Value::String(match comp {
+                Some(_) => date_to_locale_string(ms, defaults, &args, method, span)?.into(),
+                None => "Invalid Date".into(),
+            })
+        }
         "поставитьВремя" => Value::Number(apply_set_time(cell, &args, span, "поставитьВремя")?),
         "поставитьГод" | "поставитьГодUTC" => {
             Value::Number(apply_set(cell, &args, span, method, FIELD_YEAR)?)
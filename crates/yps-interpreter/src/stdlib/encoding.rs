@@ -0,0 +1,349 @@
+use std::rc::Rc;
+
+use yps_lexer::Span;
+
+use crate::error::RuntimeError;
+use crate::value::{SharedBuffer, TypedArrayData, TypedArrayKind, Value};
+
+/// Text encodings `КодировщикТекста` and `ДекодировщикТекста` understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Windows1251,
+}
+
+impl Encoding {
+    pub fn from_label(label: &str) -> Option<Encoding> {
+        match label.trim().to_lowercase().as_str() {
+            "utf-8" | "utf8" | "unicode-1-1-utf-8" => Some(Encoding::Utf8),
+            "utf-16le" | "utf-16" => Some(Encoding::Utf16Le),
+            "windows-1251" | "cp1251" | "x-cp1251" => Some(Encoding::Windows1251),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Windows1251 => "windows-1251",
+        }
+    }
+}
+
+/// A `КодировщикТекста` or a `ДекодировщикТекста`.
+#[derive(Debug)]
+pub struct TextCodec {
+    pub encoding: Encoding,
+    pub decoder: bool,
+    /// Malformed input is an error instead of U+FFFD.
+    pub fatal: bool,
+}
+
+impl TextCodec {
+    pub fn type_name(&self) -> &'static str {
+        if self.decoder { "декодировщикТекста" } else { "кодировщикТекста" }
+    }
+}
+
+pub const ENCODER_METHODS: &[&str] = &["закодировать", "encode"];
+
+pub const DECODER_METHODS: &[&str] = &["раскодировать", "decode"];
+
+/// `захуярить КодировщикТекста(кодировка)`; without an argument the encoding is UTF-8.
+pub fn construct_encoder(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let encoding = encoding_arg("КодировщикТекста", args.first(), span)?;
+    Ok(Value::TextCodec(Rc::new(TextCodec { encoding, decoder: false, fatal: false })))
+}
+
+/// `захуярить ДекодировщикТекста(кодировка, { фатально: правда })`.
+pub fn construct_decoder(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let encoding = encoding_arg("ДекодировщикТекста", args.first(), span)?;
+    let fatal = match args.get(1) {
+        Some(Value::Object(options)) => {
+            let options = options.borrow();
+            options.get("фатально").or_else(|| options.get("fatal")).is_some_and(Value::is_truthy)
+        }
+        None | Some(Value::Undefined) => false,
+        Some(other) => {
+            return Err(RuntimeError::new(
+                format!("'ДекодировщикТекста' ожидает объект настроек, получено '{}'", other.type_name()),
+                span,
+            ));
+        }
+    };
+    Ok(Value::TextCodec(Rc::new(TextCodec { encoding, decoder: true, fatal })))
+}
+
+fn encoding_arg(ctor: &str, label: Option<&Value>, span: Span) -> Result<Encoding, RuntimeError> {
+    match label {
+        None | Some(Value::Undefined) => Ok(Encoding::Utf8),
+        Some(Value::String(label)) => Encoding::from_label(label)
+            .ok_or_else(|| RuntimeError::new(format!("'{ctor}': неизвестная кодировка '{label}'"), span)),
+        Some(other) => Err(RuntimeError::new(
+            format!("'{ctor}' ожидает название кодировки строкой, получено '{}'", other.type_name()),
+            span,
+        )),
+    }
+}
+
+pub fn call(codec: &TextCodec, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let arg = args.into_iter().next().unwrap_or(Value::Undefined);
+    match method {
+        "закодировать" | "encode" if !codec.decoder => {
+            let text = match arg {
+                Value::Undefined => String::new(),
+                other => other.to_string(),
+            };
+            let bytes =
+                encode(codec.encoding, &text).map_err(|m| RuntimeError::new(format!("'{method}': {m}"), span))?;
+            Ok(u8_array(bytes))
+        }
+        "раскодировать" | "decode" if codec.decoder => {
+            let bytes = match arg {
+                Value::Undefined => Vec::new(),
+                other => bytes_of(&other).ok_or_else(|| {
+                    RuntimeError::new(format!("'{method}' ожидает байты, получено '{}'", other.type_name()), span)
+                })?,
+            };
+            let text = decode(codec.encoding, &bytes, codec.fatal)
+                .map_err(|m| RuntimeError::new(format!("'{method}': {m}"), span))?;
+            Ok(Value::String(text.into()))
+        }
+        _ => Err(RuntimeError::new(format!("У '{}' нет метода '{method}'", codec.type_name()), span)),
+    }
+}
+
+pub fn encode(encoding: Encoding, text: &str) -> Result<Vec<u8>, String> {
+    match encoding {
+        Encoding::Utf8 => Ok(text.as_bytes().to_vec()),
+        Encoding::Utf16Le => Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+        Encoding::Windows1251 => text
+            .chars()
+            .map(|c| to_windows_1251(c).ok_or_else(|| format!("символ '{c}' нельзя записать в windows-1251")))
+            .collect(),
+    }
+}
+
+/// Decodes `bytes`, dropping a leading byte order mark.
+pub fn decode(encoding: Encoding, bytes: &[u8], fatal: bool) -> Result<String, String> {
+    match encoding {
+        Encoding::Utf8 => {
+            let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+            match std::str::from_utf8(bytes) {
+                Ok(text) => Ok(text.to_string()),
+                Err(e) if fatal => Err(format!("неверная последовательность UTF-8 на байте {}", e.valid_up_to())),
+                Err(_) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+            }
+        }
+        Encoding::Utf16Le => {
+            let bytes = bytes.strip_prefix(b"\xFF\xFE").unwrap_or(bytes);
+            if fatal && !bytes.len().is_multiple_of(2) {
+                return Err("нечётное число байт в UTF-16LE".to_string());
+            }
+            let units = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+            let mut text = String::with_capacity(bytes.len() / 2);
+            for c in char::decode_utf16(units) {
+                match c {
+                    Ok(c) => text.push(c),
+                    Err(e) if fatal => {
+                        return Err(format!("одиночный суррогат {:#06x} в UTF-16LE", e.unpaired_surrogate()));
+                    }
+                    Err(_) => text.push(char::REPLACEMENT_CHARACTER),
+                }
+            }
+            if !bytes.len().is_multiple_of(2) {
+                text.push(char::REPLACEMENT_CHARACTER);
+            }
+            Ok(text)
+        }
+        Encoding::Windows1251 => Ok(bytes.iter().map(|&b| from_windows_1251(b)).collect()),
+    }
+}
+
+/// Code points of windows-1251 bytes 0x80..=0xBF; 0xC0..=0xFF are А..я in order.
+const WINDOWS_1251_HIGH: [char; 64] = [
+    'Ђ', 'Ѓ', '‚', 'ѓ', '„', '…', '†', '‡', '€', '‰', 'Љ', '‹', 'Њ', 'Ќ', 'Ћ', 'Џ', //
+    'ђ', '‘', '’', '“', '”', '•', '–', '—', '\u{98}', '™', 'љ', '›', 'њ', 'ќ', 'ћ', 'џ', //
+    '\u{A0}', 'Ў', 'ў', 'Ј', '¤', 'Ґ', '¦', '§', 'Ё', '©', 'Є', '«', '¬', '\u{AD}', '®', 'Ї', //
+    '°', '±', 'І', 'і', 'ґ', 'µ', '¶', '·', 'ё', '№', 'є', '»', 'ј', 'Ѕ', 'ѕ', 'ї',
+];
+
+fn from_windows_1251(byte: u8) -> char {
+    match byte {
+        0..=0x7F => byte as char,
+        0x80..=0xBF => WINDOWS_1251_HIGH[(byte - 0x80) as usize],
+        _ => char::from_u32(0x0410 + u32::from(byte - 0xC0)).unwrap_or(char::REPLACEMENT_CHARACTER),
+    }
+}
+
+fn to_windows_1251(c: char) -> Option<u8> {
+    match c {
+        '\0'..='\x7F' => Some(c as u8),
+        'А'..='я' => Some(0xC0 + (c as u32 - 0x0410) as u8),
+        _ => WINDOWS_1251_HIGH.iter().position(|&high| high == c).map(|i| 0x80 + i as u8),
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Accepts both the standard and the URL-safe alphabet; padding is optional, whitespace ignored.
+pub fn from_base64(text: &str) -> Result<Vec<u8>, String> {
+    let body = text.trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace());
+    let mut out = Vec::with_capacity(body.len() / 4 * 3);
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for c in body.chars().filter(|c| !c.is_ascii_whitespace()) {
+        let digit = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            '0'..='9' => c as u32 - '0' as u32 + 52,
+            '+' | '-' => 62,
+            '/' | '_' => 63,
+            _ => return Err(format!("недопустимый символ '{c}' в base64")),
+        };
+        acc = acc << 6 | digit;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    if bits >= 6 {
+        return Err("base64 обрывается посреди байта".to_string());
+    }
+    Ok(out)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("в шестнадцатеричной строке нечётное число цифр".to_string());
+    }
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| format!("'{}' — не шестнадцатеричный байт", String::from_utf8_lossy(pair)))
+        })
+        .collect()
+}
+
+/// The bytes a typed array, `ОбластьБайтов` or `ОбзорБайтов` looks at.
+pub fn bytes_of(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::TypedArray(ta) => {
+            let start = ta.offset;
+            let end = start + ta.live_length() * ta.kind.element_size();
+            Some(ta.buffer.borrow().get(start..end).unwrap_or_default().to_vec())
+        }
+        Value::ArrayBuffer(buffer) => Some(buffer.borrow().clone()),
+        Value::DataView { buffer, offset, length } => {
+            Some(buffer.borrow().get(*offset..offset + length).unwrap_or_default().to_vec())
+        }
+        _ => None,
+    }
+}
+
+pub fn u8_array(bytes: Vec<u8>) -> Value {
+    let length = bytes.len();
+    Value::TypedArray(Rc::new(TypedArrayData {
+        buffer: SharedBuffer::new(bytes),
+        offset: 0,
+        length,
+        kind: TypedArrayKind::U8,
+    }))
+}
+
+/// `вБазу64(значение)`: a string is encoded as UTF-8 first.
+pub fn base64_builtin(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let bytes = match args.into_iter().next().unwrap_or(Value::Undefined) {
+        Value::String(text) => text.as_bytes().to_vec(),
+        other => bytes_of(&other).ok_or_else(|| {
+            RuntimeError::new(format!("'вБазу64' ожидает строку или байты, получено '{}'", other.type_name()), span)
+        })?,
+    };
+    Ok(Value::String(to_base64(&bytes).into()))
+}
+
+/// `изБазы64(строка)`: the decoded bytes as a `Ц8Массив`.
+pub fn from_base64_builtin(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let text = string_arg("изБазы64", args.first(), span)?;
+    from_base64(&text).map(u8_array).map_err(|m| RuntimeError::new(format!("'изБазы64': {m}"), span))
+}
+
+/// Static methods of `Ц8Массив`.
+pub fn call_u8_static(method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let name = format!("Ц8Массив.{method}");
+    let decoded = match method {
+        "изХекса" | "fromHex" => from_hex(&string_arg(&name, args.first(), span)?),
+        "изБазы64" | "fromBase64" => from_base64(&string_arg(&name, args.first(), span)?),
+        _ => return Err(RuntimeError::new(format!("У 'Ц8Массив' нет статического метода '{method}'"), span)),
+    };
+    decoded.map(u8_array).map_err(|m| RuntimeError::new(format!("'{name}': {m}"), span))
+}
+
+fn string_arg(name: &str, value: Option<&Value>, span: Span) -> Result<String, RuntimeError> {
+    match value {
+        Some(Value::String(text)) => Ok(text.to_string()),
+        other => Err(RuntimeError::new(
+            format!("'{name}' ожидает строку, получено '{}'", other.unwrap_or(&Value::Undefined).type_name()),
+            span,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_round_trips_every_tail_length() {
+        for text in ["", "ф", "фы", "фыв", "hello, мир"] {
+            let encoded = to_base64(text.as_bytes());
+            assert_eq!(from_base64(&encoded).unwrap(), text.as_bytes(), "{encoded}");
+        }
+        assert_eq!(to_base64(b"Man"), "TWFu");
+        assert_eq!(to_base64(b"Ma"), "TWE=");
+        assert_eq!(from_base64("TWE").unwrap(), b"Ma");
+        assert!(from_base64("T").is_err());
+        assert!(from_base64("TW!=").is_err());
+    }
+
+    #[test]
+    fn windows_1251_covers_cyrillic() {
+        let bytes = encode(Encoding::Windows1251, "Ёжик №5 ё").unwrap();
+        assert_eq!(bytes, [0xA8, 0xE6, 0xE8, 0xEA, 0x20, 0xB9, 0x35, 0x20, 0xB8]);
+        assert_eq!(decode(Encoding::Windows1251, &bytes, true).unwrap(), "Ёжик №5 ё");
+        assert!(encode(Encoding::Windows1251, "日").is_err());
+    }
+
+    #[test]
+    fn hex_rejects_odd_and_non_hex_input() {
+        assert_eq!(from_hex("00ff7A").unwrap(), [0x00, 0xFF, 0x7A]);
+        assert_eq!(to_hex(&[0x00, 0xFF, 0x7A]), "00ff7a");
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+}
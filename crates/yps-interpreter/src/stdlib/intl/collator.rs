@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+
+use crate::error::RuntimeError;
+
+use super::{Locale, Options};
+
+/// Which differences `сравнить` looks at, from coarsest to finest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sensitivity {
+    /// Letters only: `е` = `ё` = `Е`.
+    Base,
+    /// Letters and diacritics: `е` < `ё`, `е` = `Е`.
+    Accent,
+    /// Letters and case: `е` = `ё`, `е` < `Е`.
+    Case,
+    Variant,
+}
+
+/// Compares strings the way a dictionary orders them: `ё` sorts with `е`, lowercase before
+/// uppercase, and accents and case only break ties between otherwise equal words.
+#[derive(Debug)]
+pub struct Collator {
+    pub locale: Locale,
+    sensitivity: Sensitivity,
+    numeric: bool,
+}
+
+/// One collation element: a letter with its three weights, or a run of digits in numeric mode.
+#[derive(Debug, PartialEq, Eq)]
+enum Element {
+    Char { base: char, accent: bool, upper: bool },
+    Number(String),
+}
+
+impl Element {
+    fn primary_cmp(&self, other: &Element) -> Ordering {
+        match (self, other) {
+            (Element::Char { base: a, .. }, Element::Char { base: b, .. }) => a.cmp(b),
+            (Element::Number(a), Element::Number(b)) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+            (Element::Number(_), Element::Char { base, .. }) => '0'.cmp(base).then(Ordering::Less),
+            (Element::Char { base, .. }, Element::Number(_)) => base.cmp(&'0').then(Ordering::Greater),
+        }
+    }
+
+    fn accent(&self) -> bool {
+        matches!(self, Element::Char { accent: true, .. })
+    }
+
+    fn upper(&self) -> bool {
+        matches!(self, Element::Char { upper: true, .. })
+    }
+}
+
+impl Collator {
+    pub(crate) fn new(locale: Locale, options: &Options) -> Result<Collator, RuntimeError> {
+        let sensitivity = match options.choice(
+            "чувствительность",
+            "sensitivity",
+            &[("основа", "base"), ("акценты", "accent"), ("регистр", "case"), ("всё", "variant")],
+        )? {
+            Some("base") => Sensitivity::Base,
+            Some("accent") => Sensitivity::Accent,
+            Some("case") => Sensitivity::Case,
+            _ => Sensitivity::Variant,
+        };
+        Ok(Collator { locale, sensitivity, numeric: options.flag("числовое", "numeric").unwrap_or(false) })
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (a, b) = (self.elements(a), self.elements(b));
+        let primary = a.iter().zip(&b).map(|(x, y)| x.primary_cmp(y)).find(|o| o.is_ne());
+        let primary = primary.unwrap_or_else(|| a.len().cmp(&b.len()));
+        if primary.is_ne() || self.sensitivity == Sensitivity::Base {
+            return primary;
+        }
+        if matches!(self.sensitivity, Sensitivity::Accent | Sensitivity::Variant) {
+            let accents = a.iter().map(Element::accent).cmp(b.iter().map(Element::accent));
+            if accents.is_ne() {
+                return accents;
+            }
+        }
+        if matches!(self.sensitivity, Sensitivity::Case | Sensitivity::Variant) {
+            return a.iter().map(Element::upper).cmp(b.iter().map(Element::upper));
+        }
+        Ordering::Equal
+    }
+
+    fn elements(&self, text: &str) -> Vec<Element> {
+        let mut out = Vec::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if self.numeric && c.is_ascii_digit() {
+                let mut digits = c.to_string();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                    chars.next();
+                }
+                let trimmed = digits.trim_start_matches('0');
+                out.push(Element::Number(if trimmed.is_empty() { "0".to_string() } else { trimmed.to_string() }));
+                continue;
+            }
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let (base, accent) = fold(lower);
+            out.push(Element::Char { base, accent, upper: lower != c });
+        }
+        out
+    }
+}
+
+/// The letter a character sorts as, and whether it carries a diacritic that ranks it after.
+fn fold(c: char) -> (char, bool) {
+    let base = match c {
+        'ё' => 'е',
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => return (c, false),
+    };
+    (base, true)
+}
@@ -0,0 +1,301 @@
+use crate::error::RuntimeError;
+use crate::stdlib::date::{Components, ms_to_components};
+
+use super::{Locale, Options};
+
+/// Month names of `ru` in the genitive case, as they follow a day: «17 октября».
+const RU_MONTHS_GENITIVE: [&str; 12] = [
+    "января",
+    "февраля",
+    "марта",
+    "апреля",
+    "мая",
+    "июня",
+    "июля",
+    "августа",
+    "сентября",
+    "октября",
+    "ноября",
+    "декабря",
+];
+const RU_MONTHS: [&str; 12] = [
+    "январь",
+    "февраль",
+    "март",
+    "апрель",
+    "май",
+    "июнь",
+    "июль",
+    "август",
+    "сентябрь",
+    "октябрь",
+    "ноябрь",
+    "декабрь",
+];
+const RU_MONTHS_SHORT_GENITIVE: [&str; 12] =
+    ["янв.", "февр.", "мар.", "апр.", "мая", "июн.", "июл.", "авг.", "сент.", "окт.", "нояб.", "дек."];
+const RU_MONTHS_SHORT: [&str; 12] =
+    ["янв.", "февр.", "март", "апр.", "май", "июнь", "июль", "авг.", "сент.", "окт.", "нояб.", "дек."];
+const RU_WEEKDAYS: [&str; 7] = ["воскресенье", "понедельник", "вторник", "среда", "четверг", "пятница", "суббота"];
+const RU_WEEKDAYS_SHORT: [&str; 7] = ["вс", "пн", "вт", "ср", "чт", "пт", "сб"];
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const EN_MONTHS_SHORT: [&str; 12] =
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+const EN_WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+const EN_WEEKDAYS_SHORT: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Width {
+    Numeric,
+    TwoDigit,
+    Long,
+    Short,
+}
+
+/// Which fields a format shows when its options name none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateDefaults {
+    Date,
+    Time,
+    DateTime,
+}
+
+#[derive(Debug, Default)]
+struct Fields {
+    weekday: Option<Width>,
+    year: Option<Width>,
+    month: Option<Width>,
+    day: Option<Width>,
+    hour: Option<Width>,
+    minute: Option<Width>,
+    second: Option<Width>,
+}
+
+impl Fields {
+    fn has_date(&self) -> bool {
+        self.weekday.is_some() || self.year.is_some() || self.month.is_some() || self.day.is_some()
+    }
+
+    fn has_time(&self) -> bool {
+        self.hour.is_some() || self.minute.is_some() || self.second.is_some()
+    }
+}
+
+/// Dates are shown in UTC, like the getters of `Дата`.
+#[derive(Debug)]
+pub struct DateFormat {
+    pub locale: Locale,
+    fields: Fields,
+    hour12: bool,
+    /// `en` joins a long date and a time with " at ".
+    long_date: bool,
+}
+
+const STYLES: &[(&str, &str)] =
+    &[("полный", "full"), ("длинный", "long"), ("средний", "medium"), ("короткий", "short")];
+
+impl DateFormat {
+    pub(crate) fn new(locale: Locale, options: &Options, defaults: DateDefaults) -> Result<DateFormat, RuntimeError> {
+        let text = &[("длинный", "long"), ("короткий", "short")];
+        let numeric = &[("числом", "numeric"), ("двеЦифры", "2-digit")];
+        let month = &[("числом", "numeric"), ("двеЦифры", "2-digit"), ("длинный", "long"), ("короткий", "short")];
+        let mut fields = Fields {
+            weekday: options.choice("деньНедели", "weekday", text)?.map(width),
+            year: options.choice("год", "year", numeric)?.map(width),
+            month: options.choice("месяц", "month", month)?.map(width),
+            day: options.choice("день", "day", numeric)?.map(width),
+            hour: options.choice("час", "hour", numeric)?.map(width),
+            minute: options.choice("минута", "minute", numeric)?.map(width),
+            second: options.choice("секунда", "second", numeric)?.map(width),
+        };
+        let date_style = options.choice("стильДаты", "dateStyle", STYLES)?;
+        let time_style = options.choice("стильВремени", "timeStyle", STYLES)?;
+        if (date_style.is_some() || time_style.is_some()) && (fields.has_date() || fields.has_time()) {
+            return Err(options.error("'стильДаты'/'стильВремени' нельзя сочетать с отдельными полями".to_string()));
+        }
+        if let Some(style) = date_style {
+            let (month, day) = match style {
+                "full" | "long" => (Width::Long, Width::Numeric),
+                "medium" => (Width::Short, Width::Numeric),
+                _ => (Width::TwoDigit, Width::TwoDigit),
+            };
+            fields.weekday = (style == "full").then_some(Width::Long);
+            fields.year = Some(if style == "short" && locale == Locale::En { Width::TwoDigit } else { Width::Numeric });
+            fields.month = Some(month);
+            fields.day = Some(day);
+        }
+        if let Some(style) = time_style {
+            fields.hour = Some(Width::Numeric);
+            fields.minute = Some(Width::TwoDigit);
+            fields.second = (style != "short").then_some(Width::TwoDigit);
+        }
+        if !fields.has_date() && !fields.has_time() {
+            if defaults != DateDefaults::Time {
+                fields.year = Some(Width::Numeric);
+                fields.month = Some(Width::Numeric);
+                fields.day = Some(Width::Numeric);
+            }
+            if defaults != DateDefaults::Date {
+                fields.hour = Some(Width::Numeric);
+                fields.minute = Some(Width::TwoDigit);
+                fields.second = Some(Width::TwoDigit);
+            }
+        }
+        let hour12 = options.flag("час12", "hour12").unwrap_or(locale == Locale::En);
+        Ok(DateFormat { locale, fields, hour12, long_date: matches!(date_style, Some("full" | "long")) })
+    }
+
+    pub fn format(&self, ms: f64) -> Result<String, String> {
+        let c = ms_to_components(ms).ok_or_else(|| "недопустимая дата".to_string())?;
+        let date = self.fields.has_date().then(|| match self.locale {
+            Locale::Ru => self.ru_date(&c),
+            Locale::En => self.en_date(&c),
+        });
+        let time = self.fields.has_time().then(|| self.time(&c));
+        Ok(match (date, time) {
+            (Some(date), Some(time)) if self.locale == Locale::En && self.long_date => format!("{date} at {time}"),
+            (Some(date), Some(time)) => format!("{date}, {time}"),
+            (Some(part), None) | (None, Some(part)) => part,
+            (None, None) => String::new(),
+        })
+    }
+
+    fn ru_date(&self, c: &Components) -> String {
+        let f = &self.fields;
+        let month = (c.month - 1) as usize;
+        let mut out = String::new();
+        if let Some(weekday) = f.weekday {
+            out.push_str(if weekday == Width::Long { RU_WEEKDAYS } else { RU_WEEKDAYS_SHORT }[c.weekday as usize]);
+        }
+        match f.month {
+            Some(width @ (Width::Long | Width::Short)) => {
+                let mut parts = Vec::new();
+                if let Some(day) = f.day {
+                    parts.push(number(c.day, day));
+                }
+                let names = match (width, f.day.is_some()) {
+                    (Width::Long, true) => RU_MONTHS_GENITIVE,
+                    (Width::Long, false) => RU_MONTHS,
+                    (_, true) => RU_MONTHS_SHORT_GENITIVE,
+                    (_, false) => RU_MONTHS_SHORT,
+                };
+                parts.push(names[month].to_string());
+                if let Some(year) = f.year {
+                    parts.push(format!("{} г.", year_text(c.year, year)));
+                }
+                join_after(&mut out, ", ", &parts.join(" "));
+            }
+            _ => {
+                let mut parts = Vec::new();
+                if let Some(day) = f.day {
+                    parts.push(if f.month.is_some() { number(c.day, Width::TwoDigit) } else { number(c.day, day) });
+                }
+                if f.month.is_some() {
+                    parts.push(number(c.month, Width::TwoDigit));
+                }
+                if let Some(year) = f.year {
+                    let year = year_text(c.year, year);
+                    parts.push(if parts.is_empty() { format!("{year} г.") } else { year });
+                }
+                join_after(&mut out, ", ", &parts.join("."));
+            }
+        }
+        out
+    }
+
+    fn en_date(&self, c: &Components) -> String {
+        let f = &self.fields;
+        let month = (c.month - 1) as usize;
+        let mut out = String::new();
+        if let Some(weekday) = f.weekday {
+            out.push_str(if weekday == Width::Long { EN_WEEKDAYS } else { EN_WEEKDAYS_SHORT }[c.weekday as usize]);
+        }
+        match f.month {
+            Some(width @ (Width::Long | Width::Short)) => {
+                let mut text = if width == Width::Long { EN_MONTHS } else { EN_MONTHS_SHORT }[month].to_string();
+                if let Some(day) = f.day {
+                    text = format!("{text} {}", number(c.day, day));
+                }
+                if let Some(year) = f.year {
+                    let sep = if f.day.is_some() { ", " } else { " " };
+                    text = format!("{text}{sep}{}", year_text(c.year, year));
+                }
+                join_after(&mut out, ", ", &text);
+            }
+            month_width => {
+                let mut parts = Vec::new();
+                if let Some(month) = month_width {
+                    parts.push(number(c.month, month));
+                }
+                if let Some(day) = f.day {
+                    parts.push(number(c.day, day));
+                }
+                if let Some(year) = f.year {
+                    parts.push(year_text(c.year, year));
+                }
+                join_after(&mut out, ", ", &parts.join("/"));
+            }
+        }
+        out
+    }
+
+    fn time(&self, c: &Components) -> String {
+        let f = &self.fields;
+        let mut parts = Vec::new();
+        if f.hour.is_some() {
+            let hour = if self.hour12 { (c.hours + 11) % 12 + 1 } else { c.hours };
+            let pad = !self.hour12 && (self.locale == Locale::Ru || f.hour == Some(Width::TwoDigit));
+            parts.push(number(hour, if pad { Width::TwoDigit } else { Width::Numeric }));
+        }
+        if f.minute.is_some() {
+            parts.push(number(c.minutes, if f.hour.is_some() { Width::TwoDigit } else { Width::Numeric }));
+        }
+        if f.second.is_some() {
+            parts.push(number(c.seconds, if parts.is_empty() { Width::Numeric } else { Width::TwoDigit }));
+        }
+        let mut out = parts.join(":");
+        if self.hour12 && f.hour.is_some() {
+            out.push('\u{202F}');
+            out.push_str(if c.hours < 12 { "AM" } else { "PM" });
+        }
+        out
+    }
+}
+
+fn width(name: &str) -> Width {
+    match name {
+        "2-digit" => Width::TwoDigit,
+        "long" => Width::Long,
+        "short" => Width::Short,
+        _ => Width::Numeric,
+    }
+}
+
+fn number(n: u32, width: Width) -> String {
+    if width == Width::TwoDigit { format!("{n:02}") } else { n.to_string() }
+}
+
+fn year_text(year: i64, width: Width) -> String {
+    if width == Width::TwoDigit { format!("{:02}", year.rem_euclid(100)) } else { year.to_string() }
+}
+
+fn join_after(out: &mut String, sep: &str, part: &str) {
+    if !out.is_empty() && !part.is_empty() {
+        out.push_str(sep);
+    }
+    out.push_str(part);
+}
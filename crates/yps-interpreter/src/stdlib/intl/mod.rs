@@ -0,0 +1,298 @@
+//! `Интл`: number and date formatting, collation, plural rules and relative time for the
+//! compiled-in `ru` and `en` locales.
+
+mod collator;
+mod date;
+mod number;
+mod plural;
+mod relative;
+
+use std::rc::Rc;
+
+use yps_lexer::Span;
+
+use crate::error::RuntimeError;
+use crate::value::Value;
+
+use super::{builtin, object_of};
+
+pub use collator::Collator;
+pub use date::{DateDefaults, DateFormat};
+pub use number::NumberFormat;
+pub use plural::PluralRules;
+pub use relative::RelativeTimeFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Ru,
+    En,
+}
+
+impl Locale {
+    /// The first supported locale among `requested`; `ru` when there is none.
+    pub fn resolve(requested: &Value, ctx: &str, span: Span) -> Result<Locale, RuntimeError> {
+        let tags = match requested {
+            Value::Undefined => return Ok(Locale::Ru),
+            Value::String(tag) => vec![tag.to_string()],
+            Value::Array(items) => items.borrow().iter().map(|tag| tag.to_string()).collect(),
+            other => {
+                return Err(RuntimeError::new(
+                    format!("'{ctx}' ожидает локаль строкой, получено '{}'", other.type_name()),
+                    span,
+                ));
+            }
+        };
+        Ok(tags.iter().find_map(|tag| Locale::from_tag(tag)).unwrap_or(Locale::Ru))
+    }
+
+    fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "ru" => Some(Locale::Ru),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::Ru => "ru",
+            Locale::En => "en",
+        }
+    }
+}
+
+/// An object made by one of the `Интл` constructors.
+#[derive(Debug)]
+pub enum Formatter {
+    Number(NumberFormat),
+    Date(DateFormat),
+    Collator(Collator),
+    Plural(PluralRules),
+    Relative(RelativeTimeFormat),
+}
+
+impl Formatter {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Formatter::Number(_) => "форматЧисла",
+            Formatter::Date(_) => "форматДаты",
+            Formatter::Collator(_) => "сравнитель",
+            Formatter::Plural(_) => "правилаМножественного",
+            Formatter::Relative(_) => "относительноеВремя",
+        }
+    }
+
+    pub fn locale(&self) -> Locale {
+        match self {
+            Formatter::Number(f) => f.locale,
+            Formatter::Date(f) => f.locale,
+            Formatter::Collator(f) => f.locale,
+            Formatter::Plural(f) => f.locale,
+            Formatter::Relative(f) => f.locale,
+        }
+    }
+
+    pub fn methods(&self) -> &'static [&'static str] {
+        match self {
+            Formatter::Number(_) | Formatter::Date(_) | Formatter::Relative(_) => FORMAT_METHODS,
+            Formatter::Collator(_) => COLLATOR_METHODS,
+            Formatter::Plural(_) => PLURAL_METHODS,
+        }
+    }
+}
+
+pub const FORMAT_METHODS: &[&str] = &["форматировать", "format"];
+
+pub const COLLATOR_METHODS: &[&str] = &["сравнить", "compare"];
+
+pub const PLURAL_METHODS: &[&str] = &["выбрать", "select"];
+
+pub fn build_object() -> Value {
+    object_of(&[
+        ("ФорматЧисла", builtin("Интл.ФорматЧисла")),
+        ("ФорматДаты", builtin("Интл.ФорматДаты")),
+        ("Сравнитель", builtin("Интл.Сравнитель")),
+        ("ПравилаМножественного", builtin("Интл.ПравилаМножественного")),
+        ("ОтносительноеВремя", builtin("Интл.ОтносительноеВремя")),
+    ])
+}
+
+/// `захуярить Интл.X(локаль, опции)`.
+pub fn call_static(name: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let ctx = format!("Интл.{name}");
+    let locale = Locale::resolve(args.first().unwrap_or(&Value::Undefined), &ctx, span)?;
+    let options = Options::new(args.get(1), &ctx, span)?;
+    let formatter = match name {
+        "ФорматЧисла" => Formatter::Number(NumberFormat::new(locale, &options)?),
+        "ФорматДаты" => Formatter::Date(DateFormat::new(locale, &options, DateDefaults::Date)?),
+        "Сравнитель" => Formatter::Collator(Collator::new(locale, &options)?),
+        "ПравилаМножественного" => Formatter::Plural(PluralRules::new(locale, &options)?),
+        "ОтносительноеВремя" => Formatter::Relative(RelativeTimeFormat::new(locale, &options)?),
+        _ => return Err(RuntimeError::new(format!("У 'Интл' нет конструктора '{name}'"), span)),
+    };
+    Ok(Value::Intl(Rc::new(formatter)))
+}
+
+pub fn call(formatter: &Formatter, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Undefined);
+    match (formatter, method) {
+        (Formatter::Number(f), "форматировать" | "format") => {
+            let n = number_arg(&arg(0), method, span)?;
+            Ok(Value::String(f.format(n).into()))
+        }
+        (Formatter::Date(f), "форматировать" | "format") => {
+            let ms = match arg(0) {
+                Value::Undefined => super::date::now_ms(),
+                Value::Date(cell) => cell.get(),
+                other => number_arg(&other, method, span)?,
+            };
+            f.format(ms)
+                .map(|s| Value::String(s.into()))
+                .map_err(|m| RuntimeError::new(format!("'{method}': {m}"), span))
+        }
+        (Formatter::Relative(f), "форматировать" | "format") => {
+            let n = number_arg(&arg(0), method, span)?;
+            let unit = match arg(1) {
+                Value::String(unit) => unit,
+                other => {
+                    return Err(RuntimeError::new(
+                        format!("'{method}' ожидает единицу времени строкой, получено '{}'", other.type_name()),
+                        span,
+                    ));
+                }
+            };
+            f.format(n, &unit)
+                .map(|s| Value::String(s.into()))
+                .map_err(|m| RuntimeError::new(format!("'{method}': {m}"), span))
+        }
+        (Formatter::Collator(f), "сравнить" | "compare") => {
+            let (a, b) = (arg(0).to_string(), arg(1).to_string());
+            Ok(Value::Number(f.compare(&a, &b) as i8 as f64))
+        }
+        (Formatter::Plural(f), "выбрать" | "select") => {
+            let n = number_arg(&arg(0), method, span)?;
+            Ok(Value::String(f.select(n).into()))
+        }
+        _ => Err(RuntimeError::new(format!("У '{}' нет метода '{method}'", formatter.type_name()), span)),
+    }
+}
+
+/// Properties of `Интл` objects.
+pub fn get_property(formatter: &Formatter, property: &str) -> Value {
+    match property {
+        "локаль" | "locale" => Value::String(formatter.locale().tag().into()),
+        _ => Value::Undefined,
+    }
+}
+
+/// `число.вЛокальнуюСтроку(локаль, опции)`.
+pub fn number_to_locale_string(n: f64, args: &[Value], span: Span) -> Result<String, RuntimeError> {
+    let ctx = "вЛокальнуюСтроку";
+    let locale = Locale::resolve(args.first().unwrap_or(&Value::Undefined), ctx, span)?;
+    Ok(NumberFormat::new(locale, &Options::new(args.get(1), ctx, span)?)?.format(n))
+}
+
+/// `дата.вЛокальнуюСтроку(локаль, опции)` and its date-only and time-only siblings.
+pub fn date_to_locale_string(
+    ms: f64,
+    defaults: DateDefaults,
+    args: &[Value],
+    ctx: &str,
+    span: Span,
+) -> Result<String, RuntimeError> {
+    let locale = Locale::resolve(args.first().unwrap_or(&Value::Undefined), ctx, span)?;
+    let format = DateFormat::new(locale, &Options::new(args.get(1), ctx, span)?, defaults)?;
+    format.format(ms).map_err(|m| RuntimeError::new(format!("'{ctx}': {m}"), span))
+}
+
+/// `массив.вЛокальнуюСтроку(локаль, опции)`: elements formatted one by one, joined with commas.
+pub fn array_to_locale_string(items: &[Value], args: &[Value], span: Span) -> Result<String, RuntimeError> {
+    let parts = items
+        .iter()
+        .map(|item| match item {
+            Value::Null | Value::Undefined => Ok(String::new()),
+            Value::Number(n) => number_to_locale_string(*n, args, span),
+            Value::Date(cell) => {
+                date_to_locale_string(cell.get(), DateDefaults::DateTime, args, "вЛокальнуюСтроку", span)
+            }
+            Value::Array(inner) => array_to_locale_string(&inner.borrow().clone(), args, span),
+            other => Ok(other.to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(parts.join(","))
+}
+
+fn number_arg(value: &Value, method: &str, span: Span) -> Result<f64, RuntimeError> {
+    match value {
+        Value::Number(n) => Ok(*n),
+        Value::BigInt(n) => Ok(*n as f64),
+        other => Err(RuntimeError::new(format!("'{method}' ожидает число, получено '{}'", other.type_name()), span)),
+    }
+}
+
+/// An options object, as taken by `Интл` and `Время`; every key has a Russian and an English name.
+pub(crate) struct Options<'a> {
+    entries: Vec<(String, Value)>,
+    ctx: &'a str,
+    span: Span,
+}
+
+impl<'a> Options<'a> {
+    pub(crate) fn new(value: Option<&Value>, ctx: &'a str, span: Span) -> Result<Options<'a>, RuntimeError> {
+        let entries = match value {
+            None | Some(Value::Undefined) => Vec::new(),
+            Some(Value::Object(map)) => map.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            Some(other) => {
+                return Err(RuntimeError::new(
+                    format!("'{ctx}' ожидает объект настроек, получено '{}'", other.type_name()),
+                    span,
+                ));
+            }
+        };
+        Ok(Options { entries, ctx, span })
+    }
+
+    pub(crate) fn get(&self, ru: &str, en: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, v)| (k == ru || k == en) && !matches!(v, Value::Undefined)).map(|(_, v)| v)
+    }
+
+    pub(crate) fn error(&self, message: String) -> RuntimeError {
+        RuntimeError::new(format!("'{}': {message}", self.ctx), self.span)
+    }
+
+    /// A string option whose value must be one of `choices`, each given as (Russian, English);
+    /// the English spelling is returned.
+    pub(crate) fn choice(
+        &self,
+        ru: &str,
+        en: &str,
+        choices: &[(&str, &'static str)],
+    ) -> Result<Option<&'static str>, RuntimeError> {
+        let Some(value) = self.get(ru, en) else {
+            return Ok(None);
+        };
+        let text = value.to_string();
+        choices
+            .iter()
+            .find(|(r, e)| text == *r || text == *e)
+            .map(|(_, e)| Some(*e))
+            .ok_or_else(|| self.error(format!("недопустимое значение '{text}' для '{ru}'")))
+    }
+
+    fn flag(&self, ru: &str, en: &str) -> Option<bool> {
+        self.get(ru, en).map(Value::is_truthy)
+    }
+
+    pub(crate) fn string(&self, ru: &str, en: &str) -> Option<String> {
+        self.get(ru, en).map(ToString::to_string)
+    }
+
+    fn digits(&self, ru: &str, en: &str, max: usize) -> Result<Option<usize>, RuntimeError> {
+        match self.get(ru, en) {
+            None => Ok(None),
+            Some(Value::Number(n)) if n.fract() == 0.0 && (0.0..=max as f64).contains(n) => Ok(Some(*n as usize)),
+            Some(other) => Err(self.error(format!("'{ru}' должно быть целым от 0 до {max}, получено '{other}'"))),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::error::RuntimeError;
+
+use super::{Locale, Options};
+
+/// Group separator of `ru`: a narrow no-break space.
+const RU_GROUP: char = '\u{202F}';
+
+const NBSP: char = '\u{A0}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Decimal,
+    Percent,
+    Currency,
+}
+
+#[derive(Debug)]
+pub struct NumberFormat {
+    pub locale: Locale,
+    style: Style,
+    currency: String,
+    compact: bool,
+    grouping: bool,
+    min_fraction: usize,
+    max_fraction: usize,
+}
+
+impl NumberFormat {
+    pub(crate) fn new(locale: Locale, options: &Options) -> Result<NumberFormat, RuntimeError> {
+        let style = match options.choice(
+            "стиль",
+            "style",
+            &[("десятичный", "decimal"), ("процент", "percent"), ("валюта", "currency")],
+        )? {
+            Some("percent") => Style::Percent,
+            Some("currency") => Style::Currency,
+            _ => Style::Decimal,
+        };
+        let currency = options.string("валюта", "currency").map(|code| code.to_uppercase());
+        if currency.as_ref().is_some_and(|code| code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic())) {
+            return Err(options.error(format!("'{}' — не код валюты", currency.unwrap_or_default())));
+        }
+        if style == Style::Currency && currency.is_none() {
+            return Err(options.error("для стиля 'валюта' нужен код в 'валюта'".to_string()));
+        }
+        let compact = options.choice("нотация", "notation", &[("обычная", "standard"), ("компактная", "compact")])?
+            == Some("compact");
+        let (default_min, default_max) = match style {
+            Style::Decimal => (0, 3),
+            Style::Percent => (0, 0),
+            Style::Currency => (2, 2),
+        };
+        let min = options.digits("минимумДробных", "minimumFractionDigits", 20)?;
+        let max = options.digits("максимумДробных", "maximumFractionDigits", 20)?;
+        let (min_fraction, max_fraction) = match (min, max) {
+            (Some(min), Some(max)) if min > max => {
+                return Err(options.error(format!("'минимумДробных' ({min}) больше 'максимумДробных' ({max})")));
+            }
+            (Some(min), Some(max)) => (min, max),
+            (Some(min), None) => (min, min.max(default_max)),
+            (None, Some(max)) => (default_min.min(max), max),
+            (None, None) => (default_min, default_max),
+        };
+        Ok(NumberFormat {
+            locale,
+            style,
+            currency: currency.unwrap_or_default(),
+            compact,
+            grouping: options.flag("группировка", "useGrouping").unwrap_or(true),
+            min_fraction,
+            max_fraction,
+        })
+    }
+
+    /// A plain decimal format of `locale`, as used inside other formatters.
+    pub(crate) fn decimal(locale: Locale) -> NumberFormat {
+        NumberFormat {
+            locale,
+            style: Style::Decimal,
+            currency: String::new(),
+            compact: false,
+            grouping: true,
+            min_fraction: 0,
+            max_fraction: 3,
+        }
+    }
+
+    pub fn format(&self, n: f64) -> String {
+        if n.is_nan() {
+            return match self.locale {
+                Locale::Ru => "не число",
+                Locale::En => "NaN",
+            }
+            .to_string();
+        }
+        let negative = n.is_sign_negative();
+        let magnitude = if self.style == Style::Percent { n.abs() * 100.0 } else { n.abs() };
+        let body = if magnitude.is_infinite() {
+            "∞".to_string()
+        } else if self.compact {
+            self.compact_digits(magnitude)
+        } else {
+            self.digits(magnitude, self.min_fraction, self.max_fraction)
+        };
+        let sign = if negative { "-" } else { "" };
+        match (self.style, self.locale) {
+            (Style::Decimal, _) => format!("{sign}{body}"),
+            (Style::Percent, Locale::Ru) => format!("{sign}{body}{NBSP}%"),
+            (Style::Percent, Locale::En) => format!("{sign}{body}%"),
+            (Style::Currency, Locale::Ru) => format!("{sign}{body}{NBSP}{}", self.currency_symbol()),
+            (Style::Currency, Locale::En) => match self.currency_symbol() {
+                symbol if symbol == self.currency => format!("{sign}{symbol}{NBSP}{body}"),
+                symbol => format!("{sign}{symbol}{body}"),
+            },
+        }
+    }
+
+    fn currency_symbol(&self) -> &str {
+        match (self.currency.as_str(), self.locale) {
+            ("RUB", Locale::Ru) => "₽",
+            ("USD", _) => "$",
+            ("EUR", _) => "€",
+            (code, _) => code,
+        }
+    }
+
+    /// `1,2 тыс.` / `1.2K`: two significant digits below ten units, whole units above.
+    fn compact_digits(&self, magnitude: f64) -> String {
+        let suffixes: [(&str, &str); 4] = [("тыс.", "K"), ("млн", "M"), ("млрд", "B"), ("трлн", "T")];
+        let mut scale = 0;
+        let mut scaled = magnitude;
+        loop {
+            let fraction = if round_half_up(scaled, 0) < 10.0 { 1 } else { 0 };
+            if scale < suffixes.len() && round_half_up(scaled, fraction) >= 1000.0 {
+                scale += 1;
+                scaled = magnitude / 1000f64.powi(scale as i32);
+                continue;
+            }
+            let body = self.digits(scaled, 0, fraction);
+            return match (scale, self.locale) {
+                (0, _) => body,
+                (_, Locale::Ru) => format!("{body}{NBSP}{}", suffixes[scale - 1].0),
+                (_, Locale::En) => format!("{body}{}", suffixes[scale - 1].1),
+            };
+        }
+    }
+
+    /// `magnitude` rounded half-up to `max` fraction digits, padded to `min`, with this locale's
+    /// separators.
+    fn digits(&self, magnitude: f64, min: usize, max: usize) -> String {
+        let (int, mut frac) = split_rounded(magnitude, max);
+        while frac.len() > min && frac.ends_with('0') {
+            frac.pop();
+        }
+        while frac.len() < min {
+            frac.push('0');
+        }
+        let (group, point) = match self.locale {
+            Locale::Ru => (RU_GROUP, ','),
+            Locale::En => (',', '.'),
+        };
+        let mut out = String::with_capacity(int.len() + frac.len() + 4);
+        for (i, digit) in int.chars().enumerate() {
+            if self.grouping && i > 0 && (int.len() - i).is_multiple_of(3) {
+                out.push(group);
+            }
+            out.push(digit);
+        }
+        if !frac.is_empty() {
+            out.push(point);
+            out.push_str(&frac);
+        }
+        out
+    }
+}
+
+/// Integer and fraction digits of `magnitude` rounded half-up to `max` fraction digits. Works on
+/// the shortest decimal form, so `1.005` is seen as written rather than as its binary value.
+fn split_rounded(magnitude: f64, max: usize) -> (String, String) {
+    let text = magnitude.to_string();
+    let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+    let mut digits: Vec<u8> = int.bytes().chain(frac.bytes()).map(|b| b - b'0').collect();
+    let mut int_len = int.len();
+    let keep = int_len + max;
+    if digits.len() > keep {
+        let round_up = digits[keep] >= 5;
+        digits.truncate(keep);
+        if round_up && increment(&mut digits) {
+            int_len += 1;
+        }
+    }
+    let as_text = |ds: &[u8]| ds.iter().map(|d| char::from(b'0' + d)).collect::<String>();
+    (as_text(&digits[..int_len]), as_text(&digits[int_len..]))
+}
+
+/// Adds one to the last digit; true when the carry adds a new leading digit.
+fn increment(digits: &mut Vec<u8>) -> bool {
+    for digit in digits.iter_mut().rev() {
+        if *digit == 9 {
+            *digit = 0;
+        } else {
+            *digit += 1;
+            return false;
+        }
+    }
+    digits.insert(0, 1);
+    true
+}
+
+fn round_half_up(magnitude: f64, max: usize) -> f64 {
+    let (int, frac) = split_rounded(magnitude, max);
+    format!("{int}.{frac}0").parse().unwrap_or(magnitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_half_up_on_the_decimal_form() {
+        assert_eq!(split_rounded(1.005, 2), ("1".to_string(), "01".to_string()));
+        assert_eq!(split_rounded(0.125, 2), ("0".to_string(), "13".to_string()));
+        assert_eq!(split_rounded(9.996, 2), ("10".to_string(), "00".to_string()));
+        assert_eq!(split_rounded(999.5, 0), ("1000".to_string(), String::new()));
+        assert_eq!(split_rounded(12.0, 3), ("12".to_string(), String::new()));
+    }
+
+    #[test]
+    fn groups_with_locale_separators() {
+        let ru = NumberFormat::decimal(Locale::Ru);
+        assert_eq!(ru.format(1234567.891), "1\u{202F}234\u{202F}567,891");
+        assert_eq!(ru.format(-0.5), "-0,5");
+        let en = NumberFormat::decimal(Locale::En);
+        assert_eq!(en.format(1234567.891), "1,234,567.891");
+        assert_eq!(en.format(f64::NEG_INFINITY), "-∞");
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::RuntimeError;
+
+use super::{Locale, Options};
+
+/// CLDR plural categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl Category {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Category::One => "one",
+            Category::Two => "two",
+            Category::Few => "few",
+            Category::Many => "many",
+            Category::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PluralRules {
+    pub locale: Locale,
+    ordinal: bool,
+}
+
+impl PluralRules {
+    pub(crate) fn new(locale: Locale, options: &Options) -> Result<PluralRules, RuntimeError> {
+        let kind = options.choice("тип", "type", &[("количественное", "cardinal"), ("порядковое", "ordinal")])?;
+        Ok(PluralRules { locale, ordinal: kind == Some("ordinal") })
+    }
+
+    pub fn select(&self, n: f64) -> &'static str {
+        if self.ordinal { ordinal(self.locale, n) } else { cardinal(self.locale, n) }.as_str()
+    }
+}
+
+/// «1 день», «2 дня», «5 дней», «1,5 дня»: fractions take `other`.
+pub fn cardinal(locale: Locale, n: f64) -> Category {
+    let n = n.abs();
+    if !n.is_finite() || n.fract() != 0.0 {
+        return Category::Other;
+    }
+    match locale {
+        Locale::En if n == 1.0 => Category::One,
+        Locale::En => Category::Other,
+        Locale::Ru => {
+            let (i10, i100) = ((n % 10.0) as u8, (n % 100.0) as u8);
+            if i10 == 1 && i100 != 11 {
+                Category::One
+            } else if (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+                Category::Few
+            } else {
+                Category::Many
+            }
+        }
+    }
+}
+
+/// «1st», «2nd», «3rd», «4th»; Russian ordinals do not change with the number.
+fn ordinal(locale: Locale, n: f64) -> Category {
+    let n = n.abs();
+    if locale == Locale::Ru || !n.is_finite() || n.fract() != 0.0 {
+        return Category::Other;
+    }
+    match ((n % 10.0) as u8, (n % 100.0) as u8) {
+        (1, i100) if i100 != 11 => Category::One,
+        (2, i100) if i100 != 12 => Category::Two,
+        (3, i100) if i100 != 13 => Category::Few,
+        _ => Category::Other,
+    }
+}
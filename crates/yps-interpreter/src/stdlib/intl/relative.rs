@@ -0,0 +1,150 @@
+use crate::error::RuntimeError;
+
+use super::number::NumberFormat;
+use super::plural::{Category, cardinal};
+use super::{Locale, Options};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Unit {
+    fn parse(name: &str) -> Option<Unit> {
+        Some(match name {
+            "second" | "seconds" | "секунда" | "секунды" => Unit::Second,
+            "minute" | "minutes" | "минута" | "минуты" => Unit::Minute,
+            "hour" | "hours" | "час" | "часы" => Unit::Hour,
+            "day" | "days" | "день" | "дни" => Unit::Day,
+            "week" | "weeks" | "неделя" | "недели" => Unit::Week,
+            "month" | "months" | "месяц" | "месяцы" => Unit::Month,
+            "quarter" | "quarters" | "квартал" | "кварталы" => Unit::Quarter,
+            "year" | "years" | "год" | "годы" => Unit::Year,
+            _ => return None,
+        })
+    }
+
+    /// Russian forms after a number, in the accusative: one, few, many.
+    fn ru_forms(self) -> [&'static str; 3] {
+        match self {
+            Unit::Second => ["секунду", "секунды", "секунд"],
+            Unit::Minute => ["минуту", "минуты", "минут"],
+            Unit::Hour => ["час", "часа", "часов"],
+            Unit::Day => ["день", "дня", "дней"],
+            Unit::Week => ["неделю", "недели", "недель"],
+            Unit::Month => ["месяц", "месяца", "месяцев"],
+            Unit::Quarter => ["квартал", "квартала", "кварталов"],
+            Unit::Year => ["год", "года", "лет"],
+        }
+    }
+
+    fn en_name(self) -> &'static str {
+        match self {
+            Unit::Second => "second",
+            Unit::Minute => "minute",
+            Unit::Hour => "hour",
+            Unit::Day => "day",
+            Unit::Week => "week",
+            Unit::Month => "month",
+            Unit::Quarter => "quarter",
+            Unit::Year => "year",
+        }
+    }
+}
+
+/// «через 3 дня», «2 часа назад»; with `числа: "авто"` also «вчера» and «в следующем году».
+#[derive(Debug)]
+pub struct RelativeTimeFormat {
+    pub locale: Locale,
+    auto: bool,
+}
+
+impl RelativeTimeFormat {
+    pub(crate) fn new(locale: Locale, options: &Options) -> Result<RelativeTimeFormat, RuntimeError> {
+        let numeric = options.choice("числа", "numeric", &[("всегда", "always"), ("авто", "auto")])?;
+        Ok(RelativeTimeFormat { locale, auto: numeric == Some("auto") })
+    }
+
+    pub fn format(&self, value: f64, unit: &str) -> Result<String, String> {
+        let unit = Unit::parse(unit).ok_or_else(|| format!("неизвестная единица времени '{unit}'"))?;
+        if !value.is_finite() {
+            return Err(format!("значение должно быть конечным числом, получено {value}"));
+        }
+        if self.auto
+            && value.fract() == 0.0
+            && let Some(phrase) = self.phrase(unit, value as i64)
+        {
+            return Ok(phrase.to_string());
+        }
+        let past = value.is_sign_negative();
+        let number = NumberFormat::decimal(self.locale).format(value.abs());
+        Ok(match self.locale {
+            Locale::Ru => {
+                let [one, few, many] = unit.ru_forms();
+                let form = match cardinal(Locale::Ru, value) {
+                    Category::One => one,
+                    Category::Many => many,
+                    _ => few,
+                };
+                if past { format!("{number} {form} назад") } else { format!("через {number} {form}") }
+            }
+            Locale::En => {
+                let plural = if cardinal(Locale::En, value) == Category::One { "" } else { "s" };
+                let name = unit.en_name();
+                if past { format!("{number} {name}{plural} ago") } else { format!("in {number} {name}{plural}") }
+            }
+        })
+    }
+
+    /// Words used instead of a number by `числа: "авто"`.
+    fn phrase(&self, unit: Unit, value: i64) -> Option<&'static str> {
+        Some(match (self.locale, unit, value) {
+            (Locale::Ru, Unit::Day, -2) => "позавчера",
+            (Locale::Ru, Unit::Day, -1) => "вчера",
+            (Locale::Ru, Unit::Day, 0) => "сегодня",
+            (Locale::Ru, Unit::Day, 1) => "завтра",
+            (Locale::Ru, Unit::Day, 2) => "послезавтра",
+            (Locale::Ru, Unit::Week, -1) => "на прошлой неделе",
+            (Locale::Ru, Unit::Week, 0) => "на этой неделе",
+            (Locale::Ru, Unit::Week, 1) => "на следующей неделе",
+            (Locale::Ru, Unit::Month, -1) => "в прошлом месяце",
+            (Locale::Ru, Unit::Month, 0) => "в этом месяце",
+            (Locale::Ru, Unit::Month, 1) => "в следующем месяце",
+            (Locale::Ru, Unit::Quarter, -1) => "в прошлом квартале",
+            (Locale::Ru, Unit::Quarter, 0) => "в текущем квартале",
+            (Locale::Ru, Unit::Quarter, 1) => "в следующем квартале",
+            (Locale::Ru, Unit::Year, -1) => "в прошлом году",
+            (Locale::Ru, Unit::Year, 0) => "в этом году",
+            (Locale::Ru, Unit::Year, 1) => "в следующем году",
+            (Locale::Ru, Unit::Hour, 0) => "в этот час",
+            (Locale::Ru, Unit::Minute, 0) => "в эту минуту",
+            (Locale::Ru, Unit::Second, 0) => "сейчас",
+            (Locale::En, Unit::Day, -1) => "yesterday",
+            (Locale::En, Unit::Day, 0) => "today",
+            (Locale::En, Unit::Day, 1) => "tomorrow",
+            (Locale::En, Unit::Week, -1) => "last week",
+            (Locale::En, Unit::Week, 0) => "this week",
+            (Locale::En, Unit::Week, 1) => "next week",
+            (Locale::En, Unit::Month, -1) => "last month",
+            (Locale::En, Unit::Month, 0) => "this month",
+            (Locale::En, Unit::Month, 1) => "next month",
+            (Locale::En, Unit::Quarter, -1) => "last quarter",
+            (Locale::En, Unit::Quarter, 0) => "this quarter",
+            (Locale::En, Unit::Quarter, 1) => "next quarter",
+            (Locale::En, Unit::Year, -1) => "last year",
+            (Locale::En, Unit::Year, 0) => "this year",
+            (Locale::En, Unit::Year, 1) => "next year",
+            (Locale::En, Unit::Hour, 0) => "this hour",
+            (Locale::En, Unit::Minute, 0) => "this minute",
+            (Locale::En, Unit::Second, 0) => "now",
+            _ => return None,
+        })
+    }
+}
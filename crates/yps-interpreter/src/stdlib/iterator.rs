@@ -83,6 +83,36 @@ pub fn call_static(
     }
 }
 
+/// Methods of iterators, helpers included.
+pub const METHODS: &[&str] = &[
+    "следующий",
+    "next",
+    "вернуть",
+    "return",
+    "кинуть",
+    "throw",
+    "map",
+    "преобразовать",
+    "filter",
+    "отфильтровать",
+    "take",
+    "взять",
+    "drop",
+    "пропустить",
+    "toArray",
+    "вМассив",
+    "forEach",
+    "каждый",
+    "reduce",
+    "свернуть",
+    "some",
+    "некоторые",
+    "every",
+    "все",
+    "find",
+    "найти",
+];
+
 pub fn call(
     interp: &mut Interpreter,
     receiver: Value,
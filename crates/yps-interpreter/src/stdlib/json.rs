@@ -46,7 +46,7 @@ pub fn call_static(
     }
 }
 
-fn stringify(v: &Value, span: Span) -> Result<Value, RuntimeError> {
+pub(crate) fn stringify(v: &Value, span: Span) -> Result<Value, RuntimeError> {
     let mut out = String::new();
     let mut seen: HashSet<*const ()> = HashSet::new();
     stringify_into(v, &mut out, span, &mut seen, 0)?;
@@ -165,11 +165,12 @@ fn stringify_into(
             }
         }
         Value::TypedArray(ta) => {
-            let TypedArrayData { buffer, offset, length, kind } = &**ta;
+            let TypedArrayData { buffer, offset, kind, .. } = &**ta;
+            let length = ta.live_length();
             out.push('[');
             let bytes = buffer.borrow();
             let size = kind.element_size();
-            for i in 0..*length {
+            for i in 0..length {
                 if i > 0 {
                     out.push(',');
                 }
@@ -192,12 +193,22 @@ fn stringify_into(
         | Value::AbortRejectPromise { .. } => {
             return Err(RuntimeError::new("КонтроллёрОтмены/СигналОтмены нельзя сериализовать в JSON", span));
         }
+        Value::Worker(_) => {
+            return Err(RuntimeError::new("Воркер нельзя сериализовать в JSON", span));
+        }
+        Value::Intl(_) => {
+            return Err(RuntimeError::new("Объекты 'Интл' нельзя сериализовать в JSON", span));
+        }
+        Value::Temporal(t) => write_json_string(out, &crate::stdlib::temporal::to_json_string(t)),
+        Value::TextCodec(_) => {
+            return Err(RuntimeError::new("КодировщикТекста/ДекодировщикТекста нельзя сериализовать в JSON", span));
+        }
         Value::Proxy { target, .. } => stringify_into(target, out, span, seen, depth + 1)?,
     }
     Ok(())
 }
 
-fn write_json_string(out: &mut String, s: &str) {
+pub(crate) fn write_json_string(out: &mut String, s: &str) {
     out.push('"');
     for ch in s.chars() {
         match ch {
@@ -50,6 +50,34 @@ pub fn call_static(
     }
 }
 
+/// Methods of `Карта` instances.
+pub const METHODS: &[&str] = &[
+    "set",
+    "поставить",
+    "get",
+    "взять",
+    "has",
+    "имеет",
+    "delete",
+    "удалить",
+    "clear",
+    "очистить",
+    "size",
+    "размер",
+    "keys",
+    "ключи",
+    "values",
+    "значения",
+    "entries",
+    "записи",
+    "getOrInsert",
+    "взятьИлиВставить",
+    "getOrInsertComputed",
+    "взятьИлиВычислить",
+    "forEach",
+    "каждый",
+];
+
 pub fn call(
     interp: &mut Interpreter,
     receiver: Value,
@@ -5,6 +5,7 @@ use yps_lexer::Span;
 use crate::error::RuntimeError;
 use crate::interpreter::Interpreter;
 use crate::stdlib::{as_number, builtin, object_of, require_args};
+use crate::trace::{self, EventKind};
 use crate::value::{Value, to_int_n, to_uint_n};
 
 pub fn build_object() -> Value {
@@ -120,7 +121,7 @@ pub fn call_static(
             require_args(&args, 1, span, "Матан.корень")?;
             Ok(Value::Number(as_number(&args[0], span, "Матан.корень")?.sqrt()))
         }
-        "рандом" => Ok(Value::Number(xorshift_random())),
+        "рандом" => Ok(Value::Number(trace::number(EventKind::Random, xorshift_random))),
         "знак" => {
             require_args(&args, 1, span, "Матан.знак")?;
             let n = as_number(&args[0], span, "Матан.знак")?;
@@ -1,10 +1,13 @@
 pub mod abort;
 pub mod array;
+pub mod atomics;
 pub mod console;
 pub mod data_view;
 pub mod date;
+pub mod encoding;
 pub mod error;
 pub mod fs;
+pub mod intl;
 pub mod iterator;
 pub mod json;
 pub mod map;
@@ -22,14 +25,17 @@ pub mod stdio;
 pub mod string;
 pub mod string_ns;
 pub mod symbol;
+pub mod temporal;
 pub mod typed_array;
 pub mod weak;
+pub mod worker;
 
 use indexmap::IndexMap;
 use yps_lexer::Span;
 
 use crate::error::RuntimeError;
 use crate::interpreter::Interpreter;
+use crate::trace::{self, EventKind};
 use crate::value::Value;
 
 pub fn call_method(
@@ -39,7 +45,8 @@ pub fn call_method(
     args: Vec<Value>,
     span: Span,
 ) -> Result<(Value, Option<Value>), RuntimeError> {
-    match &receiver {
+    let known = method_names(&receiver);
+    let result = match &receiver {
         Value::Array(_) => array::call(interp, receiver, method, args, span).map(|v| (v, None)),
         Value::String(_) => string::call(interp, receiver, method, args, span),
         Value::Number(_) => number::call_instance(interp, receiver, method, args, span),
@@ -57,7 +64,43 @@ pub fn call_method(
         Value::WeakSet(_) => weak::call_weak_set(receiver, method, args, span).map(|v| (v, None)),
         Value::WeakRef(_) => weak::call_weak_ref(receiver, method, args, span).map(|v| (v, None)),
         Value::FinalizationRegistry(_) => weak::call_registry(receiver, method, args, span).map(|v| (v, None)),
+        Value::Worker(port) => worker::call(port, method, args, span).map(|v| (v, None)),
+        Value::TextCodec(codec) => encoding::call(codec, method, args, span).map(|v| (v, None)),
+        Value::Intl(formatter) => intl::call(formatter, method, args, span).map(|v| (v, None)),
+        Value::Temporal(t) => temporal::call(t, method, args, span).map(|v| (v, None)),
         _ => Err(RuntimeError::new(format!("Тип '{}' не имеет метода '{method}'", receiver.type_name()), span)),
+    };
+    result.map_err(|e| if known.contains(&method) { e } else { e.with_did_you_mean(method, known.iter().copied()) })
+}
+
+/// The methods [`call_method`] knows for `receiver`.
+pub fn method_names(receiver: &Value) -> &'static [&'static str] {
+    match receiver {
+        Value::Array(_) => array::METHODS,
+        Value::String(_) => string::METHODS,
+        Value::Number(_) => number::INSTANCE_METHODS,
+        Value::Map(_) => map::METHODS,
+        Value::Set(_) => set::METHODS,
+        Value::Symbol { .. } => symbol::INSTANCE_METHODS,
+        Value::Date(_) => date::INSTANCE_METHODS,
+        Value::Promise { .. } => promise::METHODS,
+        Value::Iterator(_) => iterator::METHODS,
+        Value::RegExp(_) => regexp::METHODS,
+        Value::TypedArray(_) => typed_array::METHODS,
+        Value::DataView { .. } => data_view::METHODS,
+        Value::AbortController { .. } => abort::CONTROLLER_METHODS,
+        Value::AbortSignal { .. } => abort::SIGNAL_METHODS,
+        Value::WeakMap(_) => weak::WEAK_MAP_METHODS,
+        Value::WeakSet(_) => weak::WEAK_SET_METHODS,
+        Value::WeakRef(_) => weak::WEAK_REF_METHODS,
+        Value::FinalizationRegistry(_) => weak::REGISTRY_METHODS,
+        Value::Worker(port) if port.is_worker() => worker::WORKER_METHODS,
+        Value::Worker(_) => worker::PARENT_METHODS,
+        Value::TextCodec(codec) if codec.decoder => encoding::DECODER_METHODS,
+        Value::TextCodec(_) => encoding::ENCODER_METHODS,
+        Value::Intl(formatter) => formatter.methods(),
+        Value::Temporal(t) => t.methods(),
+        _ => &[],
     }
 }
 
@@ -82,6 +125,10 @@ pub(crate) fn has_builtin_methods(value: &Value) -> bool {
             | Value::WeakSet(_)
             | Value::WeakRef(_)
             | Value::FinalizationRegistry(_)
+            | Value::Worker(_)
+            | Value::TextCodec(_)
+            | Value::Intl(_)
+            | Value::Temporal(_)
     )
 }
 
@@ -121,6 +168,18 @@ pub fn call_static_namespaced(
     if let Some(stripped) = namespaced.strip_prefix("Итератор.") {
         return Some(iterator::call_static(interp, stripped, args, span));
     }
+    if let Some(stripped) = namespaced.strip_prefix("Атомики.") {
+        return Some(atomics::call_static(stripped, args, span));
+    }
+    if let Some(stripped) = namespaced.strip_prefix("Интл.") {
+        return Some(intl::call_static(stripped, args, span));
+    }
+    if let Some(stripped) = namespaced.strip_prefix("Время.") {
+        return Some(temporal::call_static(stripped, args, span));
+    }
+    if let Some(stripped) = namespaced.strip_prefix("Ц8Массив.") {
+        return Some(encoding::call_u8_static(stripped, args, span));
+    }
     if let Some(stripped) = namespaced.strip_prefix("Отражение.") {
         return Some(reflect::call_static(interp, stripped, args, span));
     }
@@ -131,13 +190,13 @@ pub fn call_static_namespaced(
         return Some(error::call_static(interp, stripped, args, span));
     }
     if let Some(stripped) = namespaced.strip_prefix("ФС.") {
-        return Some(fs::call_static(interp, stripped, args, span));
+        return Some(trace::result(EventKind::Fs, span, || fs::call_static(interp, stripped, args, span)));
     }
     if let Some(stripped) = namespaced.strip_prefix("Процесс.") {
         return Some(process::call_static(interp, stripped, args, span));
     }
     if let Some(stripped) = namespaced.strip_prefix("Сеть.") {
-        return Some(network::call_static(interp, stripped, args, span));
+        return Some(trace::result(EventKind::Network, span, || network::call_static(interp, stripped, args, span)));
     }
     if let Some(stripped) = namespaced.strip_prefix("СигналОтмены.") {
         if stripped == "любой" {
@@ -202,12 +261,24 @@ pub fn call_static_namespaced(
     if namespaced == "ОбластьБайтов" {
         return Some(typed_array::construct_array_buffer(args, span));
     }
+    if namespaced == "ОбщийБуфер" {
+        return Some(typed_array::construct_shared_array_buffer(args, span));
+    }
     if namespaced == "ОбзорБайтов" {
         return Some(data_view::construct(args, span));
     }
     if let Some(kind) = typed_array::kind_from_name(namespaced) {
         return Some(typed_array::construct(kind, args, span));
     }
+    if namespaced == "КодировщикТекста" {
+        return Some(encoding::construct_encoder(args, span));
+    }
+    if namespaced == "ДекодировщикТекста" {
+        return Some(encoding::construct_decoder(args, span));
+    }
+    if namespaced == "Воркер" {
+        return Some(worker::construct(&interp.base_dir(), args, crate::worker::run_file, interp.allow_eval(), span));
+    }
     None
 }
 
@@ -247,6 +318,13 @@ pub fn build_globals() -> Vec<(String, Value)> {
         ("Др32Массив".to_string(), Value::BuiltinFunction("Др32Массив".to_string())),
         ("Др64Массив".to_string(), Value::BuiltinFunction("Др64Массив".to_string())),
         ("ОбзорБайтов".to_string(), Value::BuiltinFunction("ОбзорБайтов".to_string())),
+        ("ОбщийБуфер".to_string(), Value::BuiltinFunction("ОбщийБуфер".to_string())),
+        ("Атомики".to_string(), atomics::build_object()),
+        ("Воркер".to_string(), Value::BuiltinFunction("Воркер".to_string())),
+        ("КодировщикТекста".to_string(), Value::BuiltinFunction("КодировщикТекста".to_string())),
+        ("ДекодировщикТекста".to_string(), Value::BuiltinFunction("ДекодировщикТекста".to_string())),
+        ("Интл".to_string(), intl::build_object()),
+        ("Время".to_string(), temporal::build_object()),
     ]
 }
 
@@ -283,3 +361,85 @@ pub(crate) fn as_string<'a>(v: &'a Value, span: Span, ctx: &str) -> Result<&'a s
         _ => Err(RuntimeError::new(format!("'{ctx}' ожидает строку, получено '{}'", v.type_name()), span)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Calls every name [`method_names`] lists for the value `src` evaluates to and checks that
+    /// none of them falls through to the "no such method" arm of the dispatch.
+    fn assert_listed_methods_dispatch(src: &str) {
+        let source = yps_lexer::SourceFile::new("test".to_string(), src.to_string());
+        let (tokens, _) = yps_lexer::Lexer::new(&source).tokenize();
+        let (program, _) = yps_parser::Parser::new(&tokens, &source).parse_program();
+        let mut interp = Interpreter::new();
+        let receiver = interp.run_repl(&program).unwrap().unwrap();
+        let names = method_names(&receiver);
+        assert!(!names.is_empty(), "'{src}' не перечисляет методов");
+        for name in names {
+            let span = Span { start: 0, end: 0 };
+            if let Err(e) = call_method(&mut interp, receiver.clone(), name, Vec::new(), span) {
+                assert!(!e.message.contains(&format!("метода '{name}'")), "'{src}': {}", e.message);
+            }
+        }
+    }
+
+    #[test]
+    fn map_methods_dispatch() {
+        assert_listed_methods_dispatch("захуярить Карта([[1, 2]]);");
+    }
+
+    #[test]
+    fn set_methods_dispatch() {
+        assert_listed_methods_dispatch("захуярить Набор([1, 2]);");
+    }
+
+    #[test]
+    fn date_methods_dispatch() {
+        assert_listed_methods_dispatch("захуярить Дата(0);");
+    }
+
+    #[test]
+    fn number_methods_dispatch() {
+        assert_listed_methods_dispatch("1.5;");
+    }
+
+    #[test]
+    fn promise_methods_dispatch() {
+        assert_listed_methods_dispatch("СловоПацана.решить(1);");
+    }
+
+    #[test]
+    fn iterator_methods_dispatch() {
+        assert_listed_methods_dispatch("[1, 2, 3].значения();");
+    }
+
+    #[test]
+    fn regexp_methods_dispatch() {
+        assert_listed_methods_dispatch("/а+/g;");
+    }
+
+    #[test]
+    fn typed_array_methods_dispatch() {
+        assert_listed_methods_dispatch("захуярить Ц8Массив(4);");
+    }
+
+    #[test]
+    fn data_view_methods_dispatch() {
+        assert_listed_methods_dispatch("захуярить ОбзорБайтов(захуярить ОбластьБайтов(16));");
+    }
+
+    #[test]
+    fn encoding_methods_dispatch() {
+        assert_listed_methods_dispatch("захуярить КодировщикТекста();");
+        assert_listed_methods_dispatch("захуярить ДекодировщикТекста();");
+    }
+
+    #[test]
+    fn intl_methods_dispatch() {
+        for name in ["ФорматЧисла", "ФорматДаты", "Сравнитель", "ПравилаМножественного", "ОтносительноеВремя"]
+        {
+            assert_listed_methods_dispatch(&format!("захуярить Интл.{name}(\"ru\");"));
+        }
+    }
+}
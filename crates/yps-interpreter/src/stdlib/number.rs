@@ -151,6 +151,9 @@ fn parse_float(s: &str) -> Value {
     trimmed[..i].parse::<f64>().map(Value::Number).unwrap_or(Value::Number(f64::NAN))
 }
 
+/// Methods of number values.
+pub const INSTANCE_METHODS: &[&str] = &["вСтроку", "фиксированный", "вЛокальнуюСтроку", "toLocaleString"];
+
 pub fn call_instance(
     _interp: &mut Interpreter,
     receiver: Value,
@@ -172,6 +175,9 @@ pub fn call_instance(
             let digits = digits as usize;
             Ok((Value::String(format!("{n:.*}", digits).into()), None))
         }
+        "вЛокальнуюСтроку" | "toLocaleString" => {
+            Ok((Value::String(super::intl::number_to_locale_string(n, &args, span)?.into()), None))
+        }
         _ => Err(RuntimeError::new(format!("У числа нет метода '{method}'"), span)),
     }
 }
@@ -5,6 +5,7 @@ use indexmap::IndexMap;
 use yps_lexer::Span;
 
 use crate::error::RuntimeError;
+use crate::heap_snapshot::HeapGraph;
 use crate::interpreter::Interpreter;
 use crate::stdlib::{as_string, builtin, object_of};
 use crate::value::Value;
@@ -17,6 +18,7 @@ pub fn build_object() -> Value {
         ("выход", builtin("Процесс.выход")),
         ("сменитьПапку", builtin("Процесс.сменитьПапку")),
         ("перем", builtin("Процесс.перем")),
+        ("снимокКучи", builtin("Процесс.снимокКучи")),
     ])
 }
 
@@ -49,7 +51,7 @@ fn cwd_value() -> Value {
 }
 
 pub fn call_static(
-    _interp: &mut Interpreter,
+    interp: &mut Interpreter,
     method: &str,
     args: Vec<Value>,
     span: Span,
@@ -66,6 +68,12 @@ pub fn call_static(
                     ));
                 }
             };
+            if let Err(e) = crate::trace::save() {
+                eprintln!("Не удалось сохранить трассу: {e}");
+            }
+            if let Err(e) = crate::coverage::save() {
+                eprintln!("Не удалось сохранить покрытие: {e}");
+            }
             std::process::exit(code);
         }
         "сменитьПапку" => {
@@ -84,10 +92,25 @@ pub fn call_static(
             };
             Ok(env::var(name).map(Value::string).unwrap_or(Value::Null))
         }
+        "снимокКучи" => {
+            let path = match args.first() {
+                Some(v) => as_string(v, span, "Процесс.снимокКучи")?,
+                None => return Err(RuntimeError::new("'Процесс.снимокКучи' требует путь", span)),
+            };
+            write_heap_snapshot(&interp.heap_snapshot(), path, span)?;
+            Ok(Value::string(path))
+        }
         _ => Err(RuntimeError::new(format!("У 'Процесс' нет метода '{method}'"), span)),
     }
 }
 
+/// Shared with the VM, which builds the graph from its own roots.
+pub fn write_heap_snapshot(graph: &HeapGraph, path: &str, span: Span) -> Result<(), RuntimeError> {
+    graph
+        .write(std::path::Path::new(path))
+        .map_err(|e| RuntimeError::new(format!("'Процесс.снимокКучи' не смогла записать '{path}': {e}"), span))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +117,9 @@ pub fn call_static(
     }
 }
 
+/// Methods of promises.
+pub const METHODS: &[&str] = &["потом", "ловить", "наконец"];
+
 pub fn call(
     interp: &mut Interpreter,
     receiver: Value,
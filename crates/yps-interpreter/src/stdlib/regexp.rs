@@ -623,6 +623,10 @@ impl<'a> ReplacementTemplate<'a> {
     }
 }
 
+/// Methods of regex values.
+pub const METHODS: &[&str] =
+    &["проверить", "test", "найти", "exec", "вСтроку", "toString", "источник", "source", "флаги", "flags"];
+
 pub fn call(
     _interp: &mut Interpreter,
     receiver: Value,
@@ -30,6 +30,42 @@ pub fn construct(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     }
 }
 
+/// Methods of `Набор` instances.
+pub const METHODS: &[&str] = &[
+    "add",
+    "добавить",
+    "has",
+    "имеет",
+    "delete",
+    "удалить",
+    "clear",
+    "очистить",
+    "size",
+    "размер",
+    "values",
+    "значения",
+    "keys",
+    "ключи",
+    "entries",
+    "записи",
+    "forEach",
+    "каждый",
+    "union",
+    "объединение",
+    "intersection",
+    "пересечение",
+    "difference",
+    "разница",
+    "symmetricDifference",
+    "симметричнаяРазница",
+    "isSubsetOf",
+    "подмножествоОт",
+    "isSupersetOf",
+    "надмножествоОт",
+    "isDisjointFrom",
+    "непересекаетсяС",
+];
+
 pub fn call(
     interp: &mut Interpreter,
     receiver: Value,
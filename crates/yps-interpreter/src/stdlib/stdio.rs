@@ -3,19 +3,24 @@ use std::io::{self, BufRead, Read, Write};
 use yps_lexer::Span;
 
 use crate::error::RuntimeError;
+use crate::trace::{self, EventKind};
 use crate::value::Value;
 
 pub fn read_line(span: Span) -> Result<Value, RuntimeError> {
     io::stdout().flush().ok();
-    let stdin = io::stdin();
-    let mut locked = stdin.lock();
-    read_line_from(&mut locked, span)
+    trace::result(EventKind::Stdin, span, || {
+        let stdin = io::stdin();
+        let mut locked = stdin.lock();
+        read_line_from(&mut locked, span)
+    })
 }
 
 pub fn read_all(span: Span) -> Result<Value, RuntimeError> {
-    let stdin = io::stdin();
-    let mut locked = stdin.lock();
-    read_all_from(&mut locked, span)
+    trace::result(EventKind::Stdin, span, || {
+        let stdin = io::stdin();
+        let mut locked = stdin.lock();
+        read_all_from(&mut locked, span)
+    })
 }
 
 fn read_line_from<R: BufRead>(reader: &mut R, span: Span) -> Result<Value, RuntimeError> {
@@ -410,64 +410,65 @@ pub fn call(
     }
 }
 
+pub const METHODS: &[&str] = &[
+    "charAt",
+    "символВ",
+    "charCodeAt",
+    "кодСимволаВ",
+    "indexOf",
+    "найтиПодстроку",
+    "lastIndexOf",
+    "найтиПодстрокуСконца",
+    "includes",
+    "содержит",
+    "slice",
+    "отрезать",
+    "substring",
+    "подстрока",
+    "toUpperCase",
+    "вВерхнийРегистр",
+    "toLowerCase",
+    "вНижнийРегистр",
+    "trim",
+    "обрезать",
+    "trimStart",
+    "обрезатьСлева",
+    "trimEnd",
+    "обрезатьСправа",
+    "split",
+    "разбить",
+    "replace",
+    "заменить",
+    "replaceAll",
+    "заменитьВсе",
+    "match",
+    "совпадает",
+    "matchAll",
+    "найтиВсе",
+    "search",
+    "найтиИндекс",
+    "startsWith",
+    "начинаетсяС",
+    "endsWith",
+    "заканчиваетсяНа",
+    "repeat",
+    "повторить",
+    "padStart",
+    "дополнитьСлева",
+    "padEnd",
+    "дополнитьСправа",
+    "at",
+    "поИндексу",
+    "concat",
+    "присоединить",
+    "codePointAt",
+    "кодТочки",
+    "normalize",
+    "нормализовать",
+];
+
 pub fn method_exists(name: &str) -> bool {
-    matches!(
-        name,
-        "charAt"
-            | "символВ"
-            | "charCodeAt"
-            | "кодСимволаВ"
-            | "indexOf"
-            | "найтиПодстроку"
-            | "lastIndexOf"
-            | "найтиПодстрокуСконца"
-            | "includes"
-            | "содержит"
-            | "slice"
-            | "отрезать"
-            | "substring"
-            | "подстрока"
-            | "toUpperCase"
-            | "вВерхнийРегистр"
-            | "toLowerCase"
-            | "вНижнийРегистр"
-            | "trim"
-            | "обрезать"
-            | "trimStart"
-            | "обрезатьСлева"
-            | "trimEnd"
-            | "обрезатьСправа"
-            | "split"
-            | "разбить"
-            | "replace"
-            | "заменить"
-            | "replaceAll"
-            | "заменитьВсе"
-            | "match"
-            | "совпадает"
-            | "matchAll"
-            | "найтиВсе"
-            | "search"
-            | "найтиИндекс"
-            | "startsWith"
-            | "начинаетсяС"
-            | "endsWith"
-            | "заканчиваетсяНа"
-            | "repeat"
-            | "повторить"
-            | "padStart"
-            | "дополнитьСлева"
-            | "padEnd"
-            | "дополнитьСправа"
-            | "at"
-            | "поИндексу"
-            | "concat"
-            | "присоединить"
-            | "codePointAt"
-            | "кодТочки"
-            | "normalize"
-            | "нормализовать"
-    )
+    METHODS.contains(&name)
 }
 
 fn clamped_unit_index(arg: &Value, len: usize, span: Span, method: &str) -> Result<usize, RuntimeError> {
@@ -88,6 +88,9 @@ pub fn call_static(
     }
 }
 
+/// Methods of symbol values.
+pub const INSTANCE_METHODS: &[&str] = &["вСтроку"];
+
 pub fn call_instance(
     _interp: &mut Interpreter,
     receiver: Value,
@@ -0,0 +1,192 @@
+use std::cmp::Ordering;
+
+use crate::stdlib::date::{civil_from_days, days_from_civil};
+
+pub const NS_PER_DAY: i128 = 86_400_000_000_000;
+
+/// Dates further than this many days from 1970-01-01 are out of range, as in Temporal.
+const MAX_EPOCH_DAYS: i64 = 100_000_000;
+
+/// A calendar date in the ISO calendar, without a time or zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PlainDate {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// A wall-clock time as nanoseconds since midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PlainTime {
+    pub nanos: i64,
+}
+
+pub fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_year(year: i64) -> u32 {
+    if is_leap(year) { 366 } else { 365 }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl PlainDate {
+    /// A date from its fields; out-of-range days are clamped when `constrain` is set.
+    pub fn new(year: i64, month: i64, day: i64, constrain: bool) -> Result<PlainDate, String> {
+        let valid_month = (1..=12).contains(&month);
+        if !valid_month && !constrain {
+            return Err(format!("месяц должен быть от 1 до 12, получено {month}"));
+        }
+        let month = month.clamp(1, 12) as u32;
+        let max_day = days_in_month(year, month) as i64;
+        if !(1..=max_day).contains(&day) && !constrain {
+            return Err(format!("день должен быть от 1 до {max_day}, получено {day}"));
+        }
+        let date = PlainDate { year, month, day: day.clamp(1, max_day) as u32 };
+        date.check_range()?;
+        Ok(date)
+    }
+
+    pub fn from_epoch_days(days: i64) -> PlainDate {
+        let (year, month, day) = civil_from_days(days);
+        PlainDate { year, month, day }
+    }
+
+    pub fn epoch_days(&self) -> i64 {
+        days_from_civil(self.year, self.month as i64, self.day as i64)
+    }
+
+    fn check_range(&self) -> Result<(), String> {
+        if self.year.abs() > 300_000 || self.epoch_days().abs() > MAX_EPOCH_DAYS {
+            return Err(format!("дата {self} вне допустимого диапазона"));
+        }
+        Ok(())
+    }
+
+    /// 1 for Monday through 7 for Sunday.
+    pub fn day_of_week(&self) -> u32 {
+        ((self.epoch_days() + 3).rem_euclid(7) + 1) as u32
+    }
+
+    pub fn day_of_year(&self) -> u32 {
+        (self.epoch_days() - PlainDate { year: self.year, month: 1, day: 1 }.epoch_days()) as u32 + 1
+    }
+
+    pub fn days_in_month(&self) -> u32 {
+        days_in_month(self.year, self.month)
+    }
+
+    /// Adds years and months first, clamping the day to the new month unless `constrain` is
+    /// off, then weeks and days.
+    pub fn add(&self, years: i64, months: i64, weeks: i64, days: i64, constrain: bool) -> Result<PlainDate, String> {
+        let total = self.year as i128 * 12 + self.month as i128 - 1 + years as i128 * 12 + months as i128;
+        let year = i64::try_from(total.div_euclid(12)).map_err(|_| "дата вне допустимого диапазона".to_string())?;
+        let month = total.rem_euclid(12) as i64 + 1;
+        let moved = PlainDate::new(year, month, self.day as i64, constrain)?;
+        let shift = weeks.checked_mul(7).and_then(|w| w.checked_add(days));
+        let days = shift.and_then(|s| moved.epoch_days().checked_add(s)).filter(|d| d.abs() <= MAX_EPOCH_DAYS);
+        let date = PlainDate::from_epoch_days(days.ok_or_else(|| "дата вне допустимого диапазона".to_string())?);
+        date.check_range()?;
+        Ok(date)
+    }
+
+    /// Whole months from `self` to `other` such that adding them does not pass `other`.
+    pub fn months_until(&self, other: &PlainDate) -> i64 {
+        let sign = match other.cmp(self) {
+            Ordering::Equal => return 0,
+            Ordering::Greater => 1,
+            Ordering::Less => -1,
+        };
+        let passed = |date: PlainDate| if sign > 0 { date > *other } else { date < *other };
+        let mut months = (other.year - self.year) * 12 + other.month as i64 - self.month as i64;
+        while months != 0 && self.add(0, months, 0, 0, true).is_ok_and(passed) {
+            months -= sign;
+        }
+        months
+    }
+}
+
+impl std::fmt::Display for PlainDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if (0..=9999).contains(&self.year) {
+            write!(f, "{:04}", self.year)?;
+        } else {
+            let sign = if self.year < 0 { '-' } else { '+' };
+            write!(f, "{sign}{:06}", self.year.abs())?;
+        }
+        write!(f, "-{:02}-{:02}", self.month, self.day)
+    }
+}
+
+impl PlainTime {
+    pub const MIDNIGHT: PlainTime = PlainTime { nanos: 0 };
+
+    pub fn new(fields: [i64; 6], constrain: bool) -> Result<PlainTime, String> {
+        const NAMES: [&str; 6] = ["час", "минута", "секунда", "миллисекунда", "микросекунда", "наносекунда"];
+        const LIMITS: [i64; 6] = [23, 59, 59, 999, 999, 999];
+        const SCALE: [i64; 6] = [3_600_000_000_000, 60_000_000_000, 1_000_000_000, 1_000_000, 1_000, 1];
+        let mut nanos = 0;
+        for i in 0..6 {
+            let value = fields[i];
+            if !(0..=LIMITS[i]).contains(&value) && !constrain {
+                return Err(format!("{} должна быть от 0 до {}, получено {value}", NAMES[i], LIMITS[i]));
+            }
+            nanos += value.clamp(0, LIMITS[i]) * SCALE[i];
+        }
+        Ok(PlainTime { nanos })
+    }
+
+    /// Hour, minute, second, millisecond, microsecond and nanosecond.
+    pub fn fields(&self) -> [i64; 6] {
+        let n = self.nanos;
+        [
+            n / 3_600_000_000_000,
+            n / 60_000_000_000 % 60,
+            n / 1_000_000_000 % 60,
+            n / 1_000_000 % 1000,
+            n / 1000 % 1000,
+            n % 1000,
+        ]
+    }
+}
+
+impl std::fmt::Display for PlainTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [h, m, s, ..] = self.fields();
+        write!(f, "{h:02}:{m:02}:{s:02}{}", fraction(self.nanos % 1_000_000_000))
+    }
+}
+
+/// `.5`, `.123456789` or nothing: the sub-second digits without trailing zeros.
+pub fn fraction(nanos: i64) -> String {
+    if nanos == 0 {
+        return String::new();
+    }
+    let digits = format!("{:09}", nanos.abs());
+    format!(".{}", digits.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn months_clamp_to_the_end_of_the_month() {
+        let jan31 = PlainDate { year: 2024, month: 1, day: 31 };
+        assert_eq!(jan31.add(0, 1, 0, 0, true).unwrap(), PlainDate { year: 2024, month: 2, day: 29 });
+        assert!(jan31.add(0, 1, 0, 0, false).is_err());
+        assert_eq!(jan31.add(1, 1, 0, 1, true).unwrap(), PlainDate { year: 2025, month: 3, day: 1 });
+        assert_eq!(jan31.months_until(&PlainDate { year: 2024, month: 3, day: 30 }), 1);
+        assert_eq!(PlainDate { year: 2024, month: 3, day: 30 }.months_until(&jan31), -1);
+        assert_eq!(PlainDate { year: 2026, month: 10, day: 17 }.day_of_week(), 6);
+        assert_eq!(PlainDate { year: -1, month: 1, day: 1 }.to_string(), "-000001-01-01");
+    }
+}
@@ -0,0 +1,344 @@
+use super::calendar::{NS_PER_DAY, fraction};
+
+/// Units from the largest to the smallest; a unit's position is its index in `Duration::fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+}
+
+pub const UNITS: [Unit; 10] = [
+    Unit::Year,
+    Unit::Month,
+    Unit::Week,
+    Unit::Day,
+    Unit::Hour,
+    Unit::Minute,
+    Unit::Second,
+    Unit::Millisecond,
+    Unit::Microsecond,
+    Unit::Nanosecond,
+];
+
+/// Property names of each field of a duration, Russian then English.
+pub const FIELD_NAMES: [(&str, &str); 10] = [
+    ("годы", "years"),
+    ("месяцы", "months"),
+    ("недели", "weeks"),
+    ("дни", "days"),
+    ("часы", "hours"),
+    ("минуты", "minutes"),
+    ("секунды", "seconds"),
+    ("миллисекунды", "milliseconds"),
+    ("микросекунды", "microseconds"),
+    ("наносекунды", "nanoseconds"),
+];
+
+impl Unit {
+    pub fn parse(name: &str) -> Option<Unit> {
+        let unit = match name {
+            "год" | "year" => Unit::Year,
+            "месяц" | "month" => Unit::Month,
+            "неделя" | "week" => Unit::Week,
+            "день" | "day" => Unit::Day,
+            "час" | "hour" => Unit::Hour,
+            "минута" | "minute" => Unit::Minute,
+            "секунда" | "second" => Unit::Second,
+            "миллисекунда" | "millisecond" => Unit::Millisecond,
+            "микросекунда" | "microsecond" => Unit::Microsecond,
+            "наносекунда" | "nanosecond" => Unit::Nanosecond,
+            _ => {
+                return UNITS
+                    .into_iter()
+                    .zip(FIELD_NAMES)
+                    .find(|(_, (ru, en))| name == *ru || name == *en)
+                    .map(|(u, _)| u);
+            }
+        };
+        Some(unit)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Year => "год",
+            Unit::Month => "месяц",
+            Unit::Week => "неделя",
+            Unit::Day => "день",
+            Unit::Hour => "час",
+            Unit::Minute => "минута",
+            Unit::Second => "секунда",
+            Unit::Millisecond => "миллисекунда",
+            Unit::Microsecond => "микросекунда",
+            Unit::Nanosecond => "наносекунда",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Length in nanoseconds; a day counts as 24 hours.
+    pub fn nanos(self) -> i128 {
+        match self {
+            Unit::Year | Unit::Month | Unit::Week => 0,
+            Unit::Day => NS_PER_DAY,
+            Unit::Hour => 3_600_000_000_000,
+            Unit::Minute => 60_000_000_000,
+            Unit::Second => 1_000_000_000,
+            Unit::Millisecond => 1_000_000,
+            Unit::Microsecond => 1_000,
+            Unit::Nanosecond => 1,
+        }
+    }
+
+    pub fn is_calendar(self) -> bool {
+        self <= Unit::Week
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    Ceil,
+    Floor,
+    Expand,
+    Trunc,
+    HalfExpand,
+    HalfEven,
+}
+
+pub const ROUNDING_MODES: &[(&str, &str)] = &[
+    ("вверх", "ceil"),
+    ("вниз", "floor"),
+    ("отНуля", "expand"),
+    ("кНулю", "trunc"),
+    ("ближайшее", "halfExpand"),
+    ("кЧётному", "halfEven"),
+];
+
+impl RoundingMode {
+    pub fn from_name(name: &str) -> RoundingMode {
+        match name {
+            "ceil" => RoundingMode::Ceil,
+            "floor" => RoundingMode::Floor,
+            "expand" => RoundingMode::Expand,
+            "trunc" => RoundingMode::Trunc,
+            "halfEven" => RoundingMode::HalfEven,
+            _ => RoundingMode::HalfExpand,
+        }
+    }
+
+    /// `n / d` rounded to an integer; `d` is positive.
+    pub fn divide(self, n: i128, d: i128) -> i128 {
+        let (q, r) = (n.div_euclid(d), n.rem_euclid(d));
+        if r == 0 {
+            return q;
+        }
+        let up = match self {
+            RoundingMode::Ceil => true,
+            RoundingMode::Floor => false,
+            RoundingMode::Expand => n > 0,
+            RoundingMode::Trunc => n < 0,
+            RoundingMode::HalfExpand | RoundingMode::HalfEven => match (2 * r).cmp(&d) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Less => false,
+                _ if self == RoundingMode::HalfExpand => n > 0,
+                _ => q % 2 != 0,
+            },
+        };
+        q + up as i128
+    }
+}
+
+/// A length of time in calendar and exact units; all non-zero fields share one sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Duration {
+    pub fields: [i64; 10],
+}
+
+/// No field of a duration may exceed this, which keeps every total inside `i128`.
+const MAX_FIELD: i64 = 1 << 53;
+
+impl Duration {
+    pub fn new(fields: [i64; 10]) -> Result<Duration, String> {
+        if fields.iter().any(|f| f.abs() >= MAX_FIELD) {
+            return Err("поле длительности слишком велико".to_string());
+        }
+        let positive = fields.iter().any(|&f| f > 0);
+        let negative = fields.iter().any(|&f| f < 0);
+        if positive && negative {
+            return Err("у всех полей длительности должен быть один знак".to_string());
+        }
+        Ok(Duration { fields })
+    }
+
+    pub fn get(&self, unit: Unit) -> i64 {
+        self.fields[unit.index()]
+    }
+
+    pub fn sign(&self) -> i64 {
+        self.fields.iter().find(|&&f| f != 0).map_or(0, |f| f.signum())
+    }
+
+    pub fn negated(&self) -> Duration {
+        Duration { fields: self.fields.map(|f| -f) }
+    }
+
+    pub fn has_calendar_units(&self) -> bool {
+        self.get(Unit::Year) != 0 || self.get(Unit::Month) != 0 || self.get(Unit::Week) != 0
+    }
+
+    pub fn largest_unit(&self) -> Unit {
+        UNITS.into_iter().find(|u| self.get(*u) != 0).unwrap_or(Unit::Nanosecond)
+    }
+
+    /// Hours through nanoseconds, in nanoseconds.
+    pub fn time_nanos(&self) -> i128 {
+        UNITS[Unit::Hour.index()..].iter().map(|u| self.get(*u) as i128 * u.nanos()).sum()
+    }
+
+    /// Days through nanoseconds with 24-hour days; calendar units must be zero.
+    pub fn exact_nanos(&self) -> i128 {
+        self.get(Unit::Day) as i128 * NS_PER_DAY + self.time_nanos()
+    }
+
+    /// Splits `nanos` into `largest` and every smaller unit, days counting as 24 hours.
+    pub fn balance(nanos: i128, largest: Unit) -> Result<Duration, String> {
+        let mut fields = [0i64; 10];
+        let mut rest = nanos;
+        for unit in UNITS.into_iter().filter(|u| *u >= largest.max(Unit::Day)) {
+            let size = unit.nanos();
+            let whole = rest / size;
+            rest -= whole * size;
+            fields[unit.index()] = i64::try_from(whole).map_err(|_| "длительность слишком велика".to_string())?;
+        }
+        Duration::new(fields)
+    }
+
+    /// ISO 8601: `P1Y2M3DT4H5M6.5S`, `-PT1H`, `PT0S`.
+    pub fn parse(text: &str) -> Option<Duration> {
+        let (sign, rest) = match text.as_bytes().first()? {
+            b'-' => (-1, &text[1..]),
+            b'+' => (1, &text[1..]),
+            _ => (1, text),
+        };
+        let rest = rest.strip_prefix(['P', 'p'])?;
+        let (date, time) = match rest.find(['T', 't']) {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+        let mut fields = [0i64; 10];
+        let mut any = false;
+        let mut fraction_seen = false;
+        let mut parse_part = |part: &str, designators: &[(char, Unit)]| -> Option<()> {
+            let mut rest = part;
+            let mut position = 0;
+            while !rest.is_empty() {
+                if fraction_seen {
+                    return None;
+                }
+                let len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',')).unwrap_or(rest.len());
+                let number = rest[..len].replace(',', ".");
+                let designator = rest[len..].chars().next()?.to_ascii_uppercase();
+                rest = &rest[len + designator.len_utf8()..];
+                let offset = designators[position..].iter().position(|(d, _)| *d == designator)?;
+                position += offset + 1;
+                let unit = designators[position - 1].1;
+                let (whole, frac) = number.split_once('.').unwrap_or((&number, ""));
+                if whole.is_empty() || frac.len() > 9 || (!frac.is_empty() && unit < Unit::Hour) {
+                    return None;
+                }
+                fields[unit.index()] = whole.parse::<i64>().ok()?;
+                if !frac.is_empty() {
+                    fraction_seen = true;
+                    let nanos = format!("{frac:0<9}").parse::<i128>().ok()? * unit.nanos() / 1_000_000_000;
+                    let mut rest_nanos = nanos;
+                    for smaller in UNITS.into_iter().filter(|u| *u > unit) {
+                        fields[smaller.index()] = (rest_nanos / smaller.nanos()) as i64;
+                        rest_nanos %= smaller.nanos();
+                    }
+                }
+                any = true;
+            }
+            Some(())
+        };
+        parse_part(date, &[('Y', Unit::Year), ('M', Unit::Month), ('W', Unit::Week), ('D', Unit::Day)])?;
+        if let Some(time) = time {
+            if time.is_empty() {
+                return None;
+            }
+            parse_part(time, &[('H', Unit::Hour), ('M', Unit::Minute), ('S', Unit::Second)])?;
+        }
+        if !any {
+            return None;
+        }
+        Duration::new(fields.map(|f| f * sign)).ok()
+    }
+}
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.sign() < 0 {
+            f.write_str("-")?;
+        }
+        f.write_str("P")?;
+        for (unit, designator) in [(Unit::Year, 'Y'), (Unit::Month, 'M'), (Unit::Week, 'W'), (Unit::Day, 'D')] {
+            if self.get(unit) != 0 {
+                write!(f, "{}{designator}", self.get(unit).abs())?;
+            }
+        }
+        let sub_second =
+            UNITS[Unit::Second.index()..].iter().map(|u| self.get(*u).abs() as i128 * u.nanos()).sum::<i128>();
+        let (seconds, nanos) = (sub_second / 1_000_000_000, (sub_second % 1_000_000_000) as i64);
+        let (hours, minutes) = (self.get(Unit::Hour).abs(), self.get(Unit::Minute).abs());
+        let blank_date = UNITS[..Unit::Hour.index()].iter().all(|u| self.get(*u) == 0);
+        if hours != 0 || minutes != 0 || sub_second != 0 || blank_date {
+            f.write_str("T")?;
+            if hours != 0 {
+                write!(f, "{hours}H")?;
+            }
+            if minutes != 0 {
+                write!(f, "{minutes}M")?;
+            }
+            if sub_second != 0 || (hours == 0 && minutes == 0) {
+                write!(f, "{seconds}{}S", fraction(nanos))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso_round_trip() {
+        for text in ["P1Y2M3W4DT5H6M7.5S", "-PT1H30M", "PT0S", "P3D", "PT0.000000001S", "PT1M0.25S"] {
+            assert_eq!(Duration::parse(text).unwrap().to_string(), text);
+        }
+        assert_eq!(Duration::parse("PT1.5H").unwrap().to_string(), "PT1H30M");
+        assert!(Duration::parse("P").is_none());
+        assert!(Duration::parse("PT").is_none());
+        assert!(Duration::parse("P1D2Y").is_none());
+        assert!(Duration::parse("P1.5D").is_none());
+    }
+
+    #[test]
+    fn rounding_modes_divide() {
+        let half = |mode: RoundingMode| [-15, -5, 5, 15, 16].map(|n| mode.divide(n, 10));
+        assert_eq!(half(RoundingMode::HalfExpand), [-2, -1, 1, 2, 2]);
+        assert_eq!(half(RoundingMode::HalfEven), [-2, 0, 0, 2, 2]);
+        assert_eq!(half(RoundingMode::Trunc), [-1, 0, 0, 1, 1]);
+        assert_eq!(half(RoundingMode::Floor), [-2, -1, 0, 1, 1]);
+        assert_eq!(half(RoundingMode::Ceil), [-1, 0, 1, 2, 2]);
+        assert_eq!(half(RoundingMode::Expand), [-2, -1, 1, 2, 2]);
+    }
+}
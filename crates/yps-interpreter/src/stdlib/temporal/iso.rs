@@ -0,0 +1,116 @@
+use super::calendar::{PlainDate, PlainTime};
+use super::zone::parse_offset;
+
+/// The pieces of an ISO 8601 / RFC 9557 string such as `2026-03-29T02:30:00+01:00[Europe/Berlin]`.
+#[derive(Debug, Default)]
+pub struct Parsed {
+    pub date: Option<PlainDate>,
+    pub time: Option<PlainTime>,
+    /// `Z` is `Some(0)` with `utc` set.
+    pub offset: Option<i64>,
+    pub utc: bool,
+    pub zone: Option<String>,
+}
+
+pub fn parse(text: &str) -> Option<Parsed> {
+    let mut rest = text.trim();
+    let mut parsed = Parsed::default();
+    if let Some(open) = rest.find('[') {
+        let annotations = &rest[open..];
+        rest = &rest[..open];
+        for annotation in annotations.split_terminator(']') {
+            let body = annotation.strip_prefix('[')?.trim_start_matches('!');
+            // `[u-ca=iso8601]` and other key=value annotations carry nothing we use.
+            if !body.contains('=') && parsed.zone.replace(body.to_string()).is_some() {
+                return None;
+            }
+        }
+    }
+    let time_start = rest.find(['T', 't', ' ']).or_else(|| (rest.as_bytes().get(2) == Some(&b':')).then_some(0));
+    let (date, time) = match time_start {
+        Some(0) => ("", rest),
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, ""),
+    };
+    if !date.is_empty() {
+        parsed.date = Some(parse_date(date)?);
+    }
+    if !time.is_empty() {
+        let offset_at = time.find(['Z', 'z', '+', '-']).unwrap_or(time.len());
+        parsed.time = Some(parse_time(&time[..offset_at])?);
+        let offset = &time[offset_at..];
+        if offset.eq_ignore_ascii_case("z") {
+            parsed.offset = Some(0);
+            parsed.utc = true;
+        } else if !offset.is_empty() {
+            parsed.offset = Some(parse_offset(offset)?);
+        }
+    }
+    (parsed.date.is_some() || parsed.time.is_some()).then_some(parsed)
+}
+
+/// `2026-10-17`, `+012026-10-17` or `20261017`.
+fn parse_date(text: &str) -> Option<PlainDate> {
+    let (sign, digits) = match text.as_bytes().first()? {
+        b'+' => (1, &text[1..]),
+        b'-' => (-1, &text[1..]),
+        _ => (1, text),
+    };
+    let year_len = if sign == 1 && !text.starts_with('+') { 4 } else { 6 };
+    let compact: String = digits.chars().filter(|&c| c != '-').collect();
+    let extended = digits.contains('-');
+    if compact.len() != year_len + 4 || !compact.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if extended && digits.len() != year_len + 6 {
+        return None;
+    }
+    let year: i64 = compact[..year_len].parse().ok()?;
+    if sign < 0 && year == 0 {
+        return None;
+    }
+    let month = compact[year_len..year_len + 2].parse().ok()?;
+    let day = compact[year_len + 2..].parse().ok()?;
+    PlainDate::new(sign * year, month, day, false).ok()
+}
+
+/// `14:05`, `14:05:09.5`, `140509`.
+fn parse_time(text: &str) -> Option<PlainTime> {
+    let (clock, frac) = match text.find(['.', ',']) {
+        Some(i) => (&text[..i], &text[i + 1..]),
+        None => (text, ""),
+    };
+    let digits: String = clock.chars().filter(|&c| c != ':').collect();
+    if !matches!(digits.len(), 2 | 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !frac.is_empty() && (digits.len() != 6 || frac.len() > 9 || !frac.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+    let part = |i: usize| digits.get(i..i + 2).map_or(0, |p| p.parse::<i64>().unwrap_or(0));
+    // A leap second reads as the last second of its minute.
+    let second = part(4).min(59);
+    let nanos: i64 = if frac.is_empty() { 0 } else { format!("{frac:0<9}").parse().ok()? };
+    PlainTime::new([part(0), part(2), second, nanos / 1_000_000, nanos / 1000 % 1000, nanos % 1000], false).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dates_times_offsets_and_zones() {
+        let p = parse("2026-03-29T02:30:15.25+01:00[Europe/Berlin]").unwrap();
+        assert_eq!(p.date.unwrap().to_string(), "2026-03-29");
+        assert_eq!(p.time.unwrap().to_string(), "02:30:15.25");
+        assert_eq!(p.offset, Some(3600));
+        assert_eq!(p.zone.as_deref(), Some("Europe/Berlin"));
+        let p = parse("-000044-03-15").unwrap();
+        assert_eq!(p.date.unwrap().year, -44);
+        assert!(parse("14:05").unwrap().date.is_none());
+        assert!(parse("2026-02-30").is_none());
+        assert!(parse("2026-1-5").is_none());
+        assert!(parse("2026-10-17T25:00").is_none());
+        assert!(parse("2026-10-17T10:00Z").unwrap().utc);
+    }
+}
@@ -0,0 +1,1237 @@
+//! `Время`: Temporal-style instants, plain dates and times, zoned date-times and durations.
+//! Zones are fixed offsets or IANA zones read from the system tzdata, so arithmetic across
+//! DST transitions follows the real rules of each zone.
+
+mod calendar;
+mod duration;
+mod iso;
+mod zone;
+
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+use yps_lexer::Span;
+
+use crate::error::RuntimeError;
+use crate::trace::{self, EventKind};
+use crate::value::Value;
+
+use super::intl::Options;
+use super::{builtin, object_of};
+
+pub use calendar::{PlainDate, PlainTime};
+pub use duration::{Duration, Unit};
+pub use zone::{Disambiguation, TimeZone};
+
+use calendar::{NS_PER_DAY, days_in_year, is_leap};
+use duration::{FIELD_NAMES, ROUNDING_MODES, RoundingMode};
+use zone::format_offset;
+
+const NS_PER_SECOND: i128 = 1_000_000_000;
+const NS_PER_MS: i128 = 1_000_000;
+
+/// Instants further than 10^8 days from the epoch are out of range, as in Temporal.
+const MAX_EPOCH_NS: i128 = 100_000_000 * NS_PER_DAY;
+
+const DATE_FIELDS: [(&str, &str); 3] = [("год", "year"), ("месяц", "month"), ("день", "day")];
+const TIME_FIELDS: [(&str, &str); 6] = [
+    ("час", "hour"),
+    ("минута", "minute"),
+    ("секунда", "second"),
+    ("миллисекунда", "millisecond"),
+    ("микросекунда", "microsecond"),
+    ("наносекунда", "nanosecond"),
+];
+
+/// An object made by one of the `Время` constructors. All of them are immutable.
+#[derive(Debug)]
+pub enum Temporal {
+    /// Epoch nanoseconds.
+    Instant(i128),
+    PlainDate(PlainDate),
+    PlainTime(PlainTime),
+    Zoned(Zoned),
+    Duration(Duration),
+}
+
+/// An instant together with the zone it is viewed in.
+#[derive(Debug, Clone)]
+pub struct Zoned {
+    pub nanos: i128,
+    pub zone: Rc<TimeZone>,
+}
+
+impl Temporal {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Temporal::Instant(_) => "мгновение",
+            Temporal::PlainDate(_) => "простаяДата",
+            Temporal::PlainTime(_) => "простоеВремя",
+            Temporal::Zoned(_) => "зонированноеВремя",
+            Temporal::Duration(_) => "длительность",
+        }
+    }
+
+    pub fn methods(&self) -> &'static [&'static str] {
+        match self {
+            Temporal::Instant(_) => INSTANT_METHODS,
+            Temporal::PlainDate(_) => PLAIN_DATE_METHODS,
+            Temporal::PlainTime(_) => PLAIN_TIME_METHODS,
+            Temporal::Zoned(_) => ZONED_METHODS,
+            Temporal::Duration(_) => DURATION_METHODS,
+        }
+    }
+}
+
+impl std::fmt::Display for Temporal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Temporal::Instant(nanos) => {
+                let utc = Zoned { nanos: *nanos, zone: TimeZone::utc() };
+                write!(f, "{}T{}Z", utc.date(), utc.time())
+            }
+            Temporal::PlainDate(date) => write!(f, "{date}"),
+            Temporal::PlainTime(time) => write!(f, "{time}"),
+            Temporal::Zoned(z) => {
+                write!(f, "{}T{}{}[{}]", z.date(), z.time(), format_offset(z.offset()), z.zone.id)
+            }
+            Temporal::Duration(d) => write!(f, "{d}"),
+        }
+    }
+}
+
+pub const INSTANT_METHODS: &[&str] = &[
+    "прибавить",
+    "add",
+    "вычесть",
+    "subtract",
+    "доМомента",
+    "until",
+    "сМомента",
+    "since",
+    "округлить",
+    "round",
+    "равно",
+    "equals",
+    "вЗону",
+    "toZonedDateTimeISO",
+    "вСтроку",
+    "toString",
+    "вЖсон",
+    "toJSON",
+];
+
+pub const PLAIN_DATE_METHODS: &[&str] = &[
+    "прибавить",
+    "add",
+    "вычесть",
+    "subtract",
+    "доМомента",
+    "until",
+    "сМомента",
+    "since",
+    "сЗаменой",
+    "with",
+    "равно",
+    "equals",
+    "вЗону",
+    "toZonedDateTime",
+    "вСтроку",
+    "toString",
+    "вЖсон",
+    "toJSON",
+];
+
+pub const PLAIN_TIME_METHODS: &[&str] = &[
+    "прибавить",
+    "add",
+    "вычесть",
+    "subtract",
+    "доМомента",
+    "until",
+    "сМомента",
+    "since",
+    "округлить",
+    "round",
+    "сЗаменой",
+    "with",
+    "равно",
+    "equals",
+    "вСтроку",
+    "toString",
+    "вЖсон",
+    "toJSON",
+];
+
+pub const ZONED_METHODS: &[&str] = &[
+    "прибавить",
+    "add",
+    "вычесть",
+    "subtract",
+    "доМомента",
+    "until",
+    "сМомента",
+    "since",
+    "округлить",
+    "round",
+    "сЗаменой",
+    "with",
+    "равно",
+    "equals",
+    "вЗону",
+    "withTimeZone",
+    "вМгновение",
+    "toInstant",
+    "вПростуюДату",
+    "toPlainDate",
+    "вПростоеВремя",
+    "toPlainTime",
+    "началоДня",
+    "startOfDay",
+    "вСтроку",
+    "toString",
+    "вЖсон",
+    "toJSON",
+];
+
+pub const DURATION_METHODS: &[&str] = &[
+    "прибавить",
+    "add",
+    "вычесть",
+    "subtract",
+    "обратная",
+    "negated",
+    "модуль",
+    "abs",
+    "округлить",
+    "round",
+    "итого",
+    "total",
+    "вСтроку",
+    "toString",
+    "вЖсон",
+    "toJSON",
+];
+
+pub fn build_object() -> Value {
+    let now = object_of(&[
+        ("мгновение", builtin("Время.сейчас.мгновение")),
+        ("instant", builtin("Время.сейчас.мгновение")),
+        ("зонированное", builtin("Время.сейчас.зонированное")),
+        ("zonedDateTimeISO", builtin("Время.сейчас.зонированное")),
+        ("простаяДата", builtin("Время.сейчас.простаяДата")),
+        ("plainDateISO", builtin("Время.сейчас.простаяДата")),
+        ("простоеВремя", builtin("Время.сейчас.простоеВремя")),
+        ("plainTimeISO", builtin("Время.сейчас.простоеВремя")),
+        ("зона", builtin("Время.сейчас.зона")),
+        ("timeZoneId", builtin("Время.сейчас.зона")),
+    ]);
+    object_of(&[
+        ("Мгновение", builtin("Время.Мгновение")),
+        ("ПростаяДата", builtin("Время.ПростаяДата")),
+        ("ПростоеВремя", builtin("Время.ПростоеВремя")),
+        ("ЗонированноеВремя", builtin("Время.ЗонированноеВремя")),
+        ("Длительность", builtin("Время.Длительность")),
+        ("сейчас", now),
+    ])
+}
+
+fn value(t: Temporal) -> Value {
+    Value::Temporal(Rc::new(t))
+}
+
+fn check_instant(nanos: i128) -> Result<i128, String> {
+    if nanos.abs() > MAX_EPOCH_NS {
+        return Err("мгновение вне допустимого диапазона".to_string());
+    }
+    Ok(nanos)
+}
+
+fn local_nanos(date: PlainDate, time: PlainTime) -> i128 {
+    date.epoch_days() as i128 * NS_PER_DAY + time.nanos as i128
+}
+
+/// `n / d` as a float without losing the integer part to rounding.
+fn ratio(n: i128, d: i128) -> f64 {
+    (n / d) as f64 + (n % d) as f64 / d as f64
+}
+
+fn now_nanos() -> i128 {
+    let micros =
+        trace::number(EventKind::Time, || match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_micros() as f64,
+            Err(e) => -(e.duration().as_micros() as f64),
+        });
+    micros as i128 * 1000
+}
+
+impl Zoned {
+    fn new(nanos: i128, zone: Rc<TimeZone>) -> Result<Zoned, String> {
+        Ok(Zoned { nanos: check_instant(nanos)?, zone })
+    }
+
+    /// A plain date read as midnight UTC, so that date arithmetic can share the zoned code.
+    fn at_utc_midnight(date: PlainDate) -> Zoned {
+        Zoned { nanos: date.epoch_days() as i128 * NS_PER_DAY, zone: TimeZone::utc() }
+    }
+
+    fn offset(&self) -> i64 {
+        self.zone.offset_at(self.nanos.div_euclid(NS_PER_SECOND) as i64)
+    }
+
+    fn local(&self) -> i128 {
+        self.nanos + self.offset() as i128 * NS_PER_SECOND
+    }
+
+    pub fn date(&self) -> PlainDate {
+        PlainDate::from_epoch_days(self.local().div_euclid(NS_PER_DAY) as i64)
+    }
+
+    pub fn time(&self) -> PlainTime {
+        PlainTime { nanos: self.local().rem_euclid(NS_PER_DAY) as i64 }
+    }
+
+    fn from_local(
+        date: PlainDate,
+        time: PlainTime,
+        zone: Rc<TimeZone>,
+        disambiguation: Disambiguation,
+    ) -> Result<Zoned, String> {
+        let nanos = zone.instant_for(local_nanos(date, time), disambiguation)?;
+        Zoned::new(nanos, zone)
+    }
+
+    /// Like `from_local`, but keeps `offset` when the local time is ambiguous and it is one of
+    /// the choices, as when a field of an existing zoned time changes.
+    fn from_local_preferring(
+        local: i128,
+        offset: i64,
+        zone: Rc<TimeZone>,
+        disambiguation: Disambiguation,
+    ) -> Result<Zoned, String> {
+        let preferred = zone
+            .possible_instants(local)
+            .into_iter()
+            .find(|&n| zone.offset_at(n.div_euclid(NS_PER_SECOND) as i64) == offset);
+        let nanos = match preferred {
+            Some(nanos) => nanos,
+            None => zone.instant_for(local, disambiguation)?,
+        };
+        Zoned::new(nanos, zone)
+    }
+
+    fn start_of_day(&self) -> Result<Zoned, String> {
+        Zoned::from_local(self.date(), PlainTime::MIDNIGHT, Rc::clone(&self.zone), Disambiguation::Compatible)
+    }
+
+    fn next_day_start(&self) -> Result<Zoned, String> {
+        let tomorrow = self.date().add(0, 0, 0, 1, true)?;
+        Zoned::from_local(tomorrow, PlainTime::MIDNIGHT, Rc::clone(&self.zone), Disambiguation::Compatible)
+    }
+
+    /// Years, months, weeks and days move the wall clock, so `+1 день` across a DST change
+    /// keeps the time of day; hours and smaller units are exact.
+    fn add(&self, d: &Duration) -> Result<Zoned, String> {
+        let mut out = self.clone();
+        if d.has_calendar_units() || d.get(Unit::Day) != 0 {
+            let date =
+                self.date().add(d.get(Unit::Year), d.get(Unit::Month), d.get(Unit::Week), d.get(Unit::Day), true)?;
+            out = Zoned::from_local(date, self.time(), Rc::clone(&self.zone), Disambiguation::Compatible)?;
+        }
+        Zoned::new(out.nanos + d.time_nanos(), out.zone)
+    }
+
+    /// The duration from `self` to `end` in units no larger than `largest`.
+    fn until(&self, end: &Zoned, largest: Unit) -> Result<Duration, String> {
+        let diff = end.nanos - self.nanos;
+        if largest > Unit::Day {
+            return Duration::balance(diff, largest);
+        }
+        if self.zone.id != end.zone.id {
+            return Err(format!(
+                "дни и большие единицы считаются только в одной зоне, а здесь '{}' и '{}'",
+                self.zone.id, end.zone.id
+            ));
+        }
+        let sign = diff.signum() as i64;
+        if sign == 0 {
+            return Ok(Duration::default());
+        }
+        // When the end's wall clock is behind ours in the direction of travel, its last day is
+        // incomplete; a DST change can take one more day away.
+        let behind = (end.time().nanos - self.time().nanos).signum() == -sign;
+        for shift in behind as i64..=behind as i64 + 1 {
+            let target = end.date().add(0, 0, 0, -sign * shift, true)?;
+            let mut d = date_until(self.date(), target, largest);
+            let mid = self.add(&d)?;
+            let rest = end.nanos - mid.nanos;
+            if rest.signum() as i64 != -sign {
+                let time = Duration::balance(rest, Unit::Hour)?;
+                d.fields[Unit::Hour.index()..].copy_from_slice(&time.fields[Unit::Hour.index()..]);
+                return Duration::new(d.fields);
+            }
+        }
+        Err("не удалось вычислить разницу дат".to_string())
+    }
+}
+
+/// Whole years, months, weeks and days from `a` to `b`, down from `largest`.
+fn date_until(a: PlainDate, b: PlainDate, largest: Unit) -> Duration {
+    let mut d = Duration::default();
+    let mut from = a;
+    if largest <= Unit::Month {
+        let months = a.months_until(&b);
+        from = a.add(0, months, 0, 0, true).unwrap_or(a);
+        if largest == Unit::Year {
+            d.fields[Unit::Year.index()] = months / 12;
+            d.fields[Unit::Month.index()] = months % 12;
+        } else {
+            d.fields[Unit::Month.index()] = months;
+        }
+    }
+    let mut days = b.epoch_days() - from.epoch_days();
+    if largest == Unit::Week {
+        d.fields[Unit::Week.index()] = days / 7;
+        days %= 7;
+    }
+    d.fields[Unit::Day.index()] = days;
+    d
+}
+
+/// What `округлить`, `доМомента` and `сМомента` were asked to do.
+struct Rounding {
+    largest: Unit,
+    smallest: Unit,
+    mode: RoundingMode,
+    increment: i64,
+}
+
+impl Rounding {
+    fn read(
+        options: &Options,
+        default_largest: Unit,
+        default_mode: RoundingMode,
+        allowed: RangeInclusive<Unit>,
+    ) -> Result<Rounding, RuntimeError> {
+        let smallest = unit_option(options, "наименьшаяЕдиница", "smallestUnit")?.unwrap_or(*allowed.end());
+        let largest =
+            unit_option(options, "наибольшаяЕдиница", "largestUnit")?.unwrap_or(default_largest.min(smallest));
+        for unit in [smallest, largest] {
+            if !allowed.contains(&unit) {
+                return Err(options.error(format!("единица '{}' здесь недопустима", unit.name())));
+            }
+        }
+        if largest > smallest {
+            return Err(options.error(format!(
+                "наибольшая единица '{}' меньше наименьшей '{}'",
+                largest.name(),
+                smallest.name()
+            )));
+        }
+        let mode = options.choice("режимОкругления", "roundingMode", ROUNDING_MODES)?;
+        let increment = match options.get("шагОкругления", "roundingIncrement") {
+            None => 1,
+            Some(Value::Number(n)) if n.fract() == 0.0 && (1.0..=1e9).contains(n) => *n as i64,
+            Some(other) => {
+                return Err(options.error(format!("'шагОкругления' должен быть целым от 1, получено '{other}'")));
+            }
+        };
+        Ok(Rounding { largest, smallest, mode: mode.map_or(default_mode, RoundingMode::from_name), increment })
+    }
+
+    /// `округлить` of a point in time takes a unit name or an options object.
+    fn read_round(
+        arg: Option<&Value>,
+        ctx: &str,
+        span: Span,
+        allowed: RangeInclusive<Unit>,
+    ) -> Result<Rounding, RuntimeError> {
+        let options = match arg {
+            Some(Value::String(unit)) => {
+                let mut map = indexmap::IndexMap::new();
+                map.insert("наименьшаяЕдиница".to_string(), Value::String(unit.clone()));
+                Value::object(map)
+            }
+            Some(other) => other.clone(),
+            None => Value::Undefined,
+        };
+        let options = Options::new(Some(&options), ctx, span)?;
+        if options.get("наименьшаяЕдиница", "smallestUnit").is_none() {
+            return Err(options.error("нужна 'наименьшаяЕдиница'".to_string()));
+        }
+        let rounding = Rounding::read(&options, *allowed.start(), RoundingMode::HalfExpand, allowed)?;
+        let per_parent = match rounding.smallest {
+            Unit::Day => 1,
+            Unit::Hour => 24,
+            Unit::Minute | Unit::Second => 60,
+            _ => 1000,
+        };
+        let fits = per_parent % rounding.increment == 0 && (rounding.increment < per_parent || per_parent == 1);
+        if !fits {
+            return Err(options.error(format!(
+                "шаг {} не делит {} без остатка",
+                rounding.increment,
+                if rounding.smallest == Unit::Hour { "сутки" } else { "старшую единицу" }
+            )));
+        }
+        Ok(rounding)
+    }
+
+    fn size(&self) -> i128 {
+        self.smallest.nanos() * self.increment as i128
+    }
+
+    fn negated(mut self) -> Rounding {
+        self.mode = match self.mode {
+            RoundingMode::Ceil => RoundingMode::Floor,
+            RoundingMode::Floor => RoundingMode::Ceil,
+            mode => mode,
+        };
+        self
+    }
+}
+
+/// The duration from `anchor` to `end`, rounded; calendar units are measured from `anchor`, so
+/// a month is as long as the month it covers.
+fn round_relative(anchor: &Zoned, end: &Zoned, r: &Rounding) -> Result<Duration, String> {
+    let d = anchor.until(end, r.largest)?;
+    if r.smallest == Unit::Nanosecond && r.increment == 1 {
+        return Ok(d);
+    }
+    let sign = (end.nanos - anchor.nanos).signum() as i64;
+    if sign == 0 {
+        return Ok(d);
+    }
+    let mut whole = d;
+    if r.smallest > Unit::Day {
+        whole.fields[Unit::Hour.index()..].fill(0);
+        let mid = anchor.add(&whole)?;
+        let size = r.size();
+        let rounded = r.mode.divide(end.nanos - mid.nanos, size) * size;
+        return anchor.until(&Zoned::new(mid.nanos + rounded, Rc::clone(&end.zone))?, r.largest);
+    }
+    let index = r.smallest.index();
+    whole.fields[index + 1..].fill(0);
+    let base = whole.fields[index] - whole.fields[index] % r.increment;
+    whole.fields[index] = base;
+    let mid = anchor.add(&whole)?;
+    whole.fields[index] = base + sign * r.increment;
+    let next = anchor.add(&whole)?;
+    let progress = (end.nanos - mid.nanos) * sign as i128;
+    let span = (next.nanos - mid.nanos) * sign as i128;
+    let steps = r.mode.divide((base / r.increment) as i128 * span + sign as i128 * progress, span);
+    whole.fields[index] = (steps * r.increment as i128) as i64;
+    anchor.until(&anchor.add(&whole)?, r.largest)
+}
+
+/// `длительность.итого(единица)`, relative to `anchor` when there is one.
+fn total_relative(anchor: &Zoned, end: &Zoned, unit: Unit) -> Result<f64, String> {
+    if unit > Unit::Day {
+        return Ok(ratio(end.nanos - anchor.nanos, unit.nanos()));
+    }
+    let d = anchor.until(end, unit)?;
+    let sign = (end.nanos - anchor.nanos).signum() as i64;
+    if sign == 0 {
+        return Ok(0.0);
+    }
+    let whole = d.get(unit);
+    let mut step = Duration::default();
+    step.fields[unit.index()] = whole;
+    let mid = anchor.add(&step)?;
+    step.fields[unit.index()] = whole + sign;
+    let next = anchor.add(&step)?;
+    Ok(whole as f64 + sign as f64 * ratio((end.nanos - mid.nanos).abs(), (next.nanos - mid.nanos).abs()))
+}
+
+fn unit_option(options: &Options, ru: &str, en: &str) -> Result<Option<Unit>, RuntimeError> {
+    match options.get(ru, en) {
+        None => Ok(None),
+        Some(value) => {
+            let name = value.to_string();
+            Unit::parse(&name).map(Some).ok_or_else(|| options.error(format!("неизвестная единица времени '{name}'")))
+        }
+    }
+}
+
+fn integer(options: &Options, ru: &str, en: &str) -> Result<Option<i64>, RuntimeError> {
+    match options.get(ru, en) {
+        None => Ok(None),
+        Some(Value::Number(n)) if n.fract() == 0.0 && n.abs() < 9e15 => Ok(Some(*n as i64)),
+        Some(other) => Err(options.error(format!("'{ru}' должно быть целым числом, получено '{other}'"))),
+    }
+}
+
+/// `переполнение: "отклонить"` turns an out-of-range day or month into an error instead of
+/// clamping it.
+fn constrain(options: &Options) -> Result<bool, RuntimeError> {
+    let overflow =
+        options.choice("переполнение", "overflow", &[("ограничить", "constrain"), ("отклонить", "reject")])?;
+    Ok(overflow != Some("reject"))
+}
+
+fn disambiguation(options: &Options) -> Result<Disambiguation, RuntimeError> {
+    let choice = options.choice(
+        "неоднозначность",
+        "disambiguation",
+        &[("совместимо", "compatible"), ("раньше", "earlier"), ("позже", "later"), ("отклонить", "reject")],
+    )?;
+    Ok(match choice {
+        Some("earlier") => Disambiguation::Earlier,
+        Some("later") => Disambiguation::Later,
+        Some("reject") => Disambiguation::Reject,
+        _ => Disambiguation::Compatible,
+    })
+}
+
+fn date_from_fields(options: &Options, base: Option<PlainDate>, constrain: bool) -> Result<PlainDate, RuntimeError> {
+    let base = base.map(|d| [d.year, d.month as i64, d.day as i64]);
+    let mut fields = [0i64; 3];
+    for (i, (ru, en)) in DATE_FIELDS.into_iter().enumerate() {
+        fields[i] = match (integer(options, ru, en)?, base) {
+            (Some(v), _) => v,
+            (None, Some(base)) => base[i],
+            (None, None) => return Err(options.error(format!("нужно поле '{ru}'"))),
+        };
+    }
+    PlainDate::new(fields[0], fields[1], fields[2], constrain).map_err(|m| options.error(m))
+}
+
+fn time_from_fields(options: &Options, base: PlainTime, constrain: bool) -> Result<PlainTime, RuntimeError> {
+    let mut fields = base.fields();
+    for (i, (ru, en)) in TIME_FIELDS.into_iter().enumerate() {
+        if let Some(v) = integer(options, ru, en)? {
+            fields[i] = v;
+        }
+    }
+    PlainTime::new(fields, constrain).map_err(|m| options.error(m))
+}
+
+fn has_any(options: &Options, names: &[(&str, &str)]) -> bool {
+    names.iter().any(|(ru, en)| options.get(ru, en).is_some())
+}
+
+fn fail(ctx: &str, span: Span) -> impl Fn(String) -> RuntimeError + '_ {
+    move |message| RuntimeError::new(format!("'{ctx}': {message}"), span)
+}
+
+fn type_error(ctx: &str, expected: &str, got: &Value, span: Span) -> RuntimeError {
+    RuntimeError::new(format!("'{ctx}' ожидает {expected}, получено '{}'", got.type_name()), span)
+}
+
+fn parse_string(text: &str, ctx: &str, span: Span) -> Result<iso::Parsed, RuntimeError> {
+    iso::parse(text).ok_or_else(|| RuntimeError::new(format!("'{ctx}': не удалось разобрать '{text}'"), span))
+}
+
+fn to_instant(value: &Value, ctx: &str, span: Span) -> Result<i128, RuntimeError> {
+    match value {
+        Value::Temporal(t) => match &**t {
+            Temporal::Instant(nanos) => return Ok(*nanos),
+            Temporal::Zoned(z) => return Ok(z.nanos),
+            _ => {}
+        },
+        Value::String(text) => {
+            let parsed = parse_string(text, ctx, span)?;
+            let (Some(date), Some(offset)) = (parsed.date, parsed.offset) else {
+                return Err(RuntimeError::new(format!("'{ctx}': в '{text}' нужны дата и смещение или Z"), span));
+            };
+            let local = local_nanos(date, parsed.time.unwrap_or(PlainTime::MIDNIGHT));
+            return check_instant(local - offset as i128 * NS_PER_SECOND).map_err(fail(ctx, span));
+        }
+        _ => {}
+    }
+    Err(type_error(ctx, "мгновение или строку", value, span))
+}
+
+fn to_date(value: &Value, options: &Options, ctx: &str, span: Span) -> Result<PlainDate, RuntimeError> {
+    match value {
+        Value::Temporal(t) => match &**t {
+            Temporal::PlainDate(date) => return Ok(*date),
+            Temporal::Zoned(z) => return Ok(z.date()),
+            _ => {}
+        },
+        Value::String(text) => {
+            let date = parse_string(text, ctx, span)?.date;
+            return date.ok_or_else(|| RuntimeError::new(format!("'{ctx}': в '{text}' нет даты"), span));
+        }
+        Value::Object(_) => {
+            let fields = Options::new(Some(value), ctx, span)?;
+            return date_from_fields(&fields, None, constrain(options)?);
+        }
+        _ => {}
+    }
+    Err(type_error(ctx, "дату, строку или объект с полями", value, span))
+}
+
+fn to_time(value: &Value, options: &Options, ctx: &str, span: Span) -> Result<PlainTime, RuntimeError> {
+    match value {
+        Value::Temporal(t) => match &**t {
+            Temporal::PlainTime(time) => return Ok(*time),
+            Temporal::Zoned(z) => return Ok(z.time()),
+            _ => {}
+        },
+        Value::String(text) => {
+            let time = parse_string(text, ctx, span)?.time;
+            return time.ok_or_else(|| RuntimeError::new(format!("'{ctx}': в '{text}' нет времени"), span));
+        }
+        Value::Object(_) => {
+            let fields = Options::new(Some(value), ctx, span)?;
+            if !has_any(&fields, &TIME_FIELDS) {
+                return Err(fields.error("нужно хотя бы одно поле времени".to_string()));
+            }
+            return time_from_fields(&fields, PlainTime::MIDNIGHT, constrain(options)?);
+        }
+        _ => {}
+    }
+    Err(type_error(ctx, "время, строку или объект с полями", value, span))
+}
+
+fn to_zone(value: &Value, ctx: &str, span: Span) -> Result<Rc<TimeZone>, RuntimeError> {
+    if let Value::Temporal(t) = value
+        && let Temporal::Zoned(z) = &**t
+    {
+        return Ok(Rc::clone(&z.zone));
+    }
+    match value {
+        Value::String(id) => TimeZone::get(id).map_err(fail(ctx, span)),
+        other => Err(type_error(ctx, "часовую зону строкой", other, span)),
+    }
+}
+
+fn to_zoned(value: &Value, options: &Options, ctx: &str, span: Span) -> Result<Zoned, RuntimeError> {
+    let disambiguation = disambiguation(options)?;
+    let offset_mode = options.choice(
+        "смещение",
+        "offset",
+        &[("использовать", "use"), ("предпочесть", "prefer"), ("игнорировать", "ignore"), ("отклонить", "reject")],
+    )?;
+    let (date, time, zone, offset, exact) = match value {
+        Value::Temporal(t) => match &**t {
+            Temporal::Zoned(z) => return Ok(z.clone()),
+            _ => return Err(type_error(ctx, "зонированное время, строку или объект с полями", value, span)),
+        },
+        Value::String(text) => {
+            let parsed = parse_string(text, ctx, span)?;
+            let (Some(date), Some(zone)) = (parsed.date, &parsed.zone) else {
+                return Err(RuntimeError::new(format!("'{ctx}': в '{text}' нужны дата и зона в [скобках]"), span));
+            };
+            let zone = TimeZone::get(zone).map_err(fail(ctx, span))?;
+            (date, parsed.time, zone, parsed.offset, parsed.utc)
+        }
+        Value::Object(_) => {
+            let fields = Options::new(Some(value), ctx, span)?;
+            let zone = fields.get("зона", "timeZone").cloned().unwrap_or(Value::Undefined);
+            let zone = to_zone(&zone, ctx, span)?;
+            let date = date_from_fields(&fields, None, constrain(options)?)?;
+            let time = time_from_fields(&fields, PlainTime::MIDNIGHT, constrain(options)?)?;
+            let offset = match fields.string("смещение", "offset") {
+                Some(text) => {
+                    Some(zone::parse_offset(&text).ok_or_else(|| fields.error(format!("неверное смещение '{text}'")))?)
+                }
+                None => None,
+            };
+            (date, Some(time), zone, offset, false)
+        }
+        other => return Err(type_error(ctx, "зонированное время, строку или объект с полями", other, span)),
+    };
+    let local = local_nanos(date, time.unwrap_or(PlainTime::MIDNIGHT));
+    let zoned = match (offset, offset_mode) {
+        (Some(offset), _) if exact || offset_mode == Some("use") => {
+            Zoned::new(local - offset as i128 * NS_PER_SECOND, zone)
+        }
+        (Some(offset), Some("prefer") | Some("reject") | None) => {
+            let matching = zone
+                .possible_instants(local)
+                .into_iter()
+                .find(|&n| zone.offset_at(n.div_euclid(NS_PER_SECOND) as i64) == offset);
+            match (matching, offset_mode) {
+                (Some(nanos), _) => Zoned::new(nanos, zone),
+                (None, Some("prefer")) => {
+                    zone.instant_for(local, disambiguation).and_then(|nanos| Zoned::new(nanos, zone))
+                }
+                (None, _) => Err(format!("смещение {} не подходит для зоны '{}'", format_offset(offset), zone.id)),
+            }
+        }
+        _ => zone.instant_for(local, disambiguation).and_then(|nanos| Zoned::new(nanos, zone)),
+    };
+    zoned.map_err(fail(ctx, span))
+}
+
+fn to_duration(value: &Value, ctx: &str, span: Span) -> Result<Duration, RuntimeError> {
+    if let Value::Temporal(t) = value
+        && let Temporal::Duration(d) = &**t
+    {
+        return Ok(*d);
+    }
+    match value {
+        Value::String(text) => Duration::parse(text)
+            .ok_or_else(|| RuntimeError::new(format!("'{ctx}': '{text}' — не длительность ISO 8601"), span)),
+        Value::Object(_) => {
+            let fields = Options::new(Some(value), ctx, span)?;
+            if !has_any(&fields, &FIELD_NAMES) {
+                return Err(fields.error("нужно хотя бы одно поле длительности".to_string()));
+            }
+            let mut out = [0i64; 10];
+            for (i, (ru, en)) in FIELD_NAMES.into_iter().enumerate() {
+                out[i] = integer(&fields, ru, en)?.unwrap_or(0);
+            }
+            Duration::new(out).map_err(|m| fields.error(m))
+        }
+        other => Err(type_error(ctx, "длительность, строку или объект с полями", other, span)),
+    }
+}
+
+/// The anchor of `относительно` for durations with years, months or weeks.
+fn relative_to(options: &Options, ctx: &str, span: Span) -> Result<Option<Zoned>, RuntimeError> {
+    let Some(anchor) = options.get("относительно", "relativeTo") else {
+        return Ok(None);
+    };
+    let none = Options::new(None, ctx, span)?;
+    let zoned = match anchor {
+        Value::Temporal(t) if matches!(&**t, Temporal::Zoned(_)) => to_zoned(anchor, &none, ctx, span)?,
+        Value::String(text) if text.contains('[') => to_zoned(anchor, &none, ctx, span)?,
+        _ => Zoned::at_utc_midnight(to_date(anchor, &none, ctx, span)?),
+    };
+    Ok(Some(zoned))
+}
+
+fn needs_anchor(ctx: &str, span: Span) -> RuntimeError {
+    RuntimeError::new(format!("'{ctx}': для лет, месяцев и недель нужна опора 'относительно'"), span)
+}
+
+/// `захуярить Время.X(...)` and the static functions `Время.X.от`, `Время.X.сравнить`, `Время.сейчас.*`.
+pub fn call_static(name: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let ctx = format!("Время.{name}");
+    let ctx = ctx.as_str();
+    let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Undefined);
+    let options = |i: usize| Options::new(args.get(i), ctx, span);
+    let int_arg = |i: usize, field: &str| -> Result<i64, RuntimeError> {
+        match arg(i) {
+            Value::Undefined => Ok(0),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 9e15 => Ok(n as i64),
+            other => {
+                Err(RuntimeError::new(format!("'{ctx}': '{field}' должно быть целым числом, получено '{other}'"), span))
+            }
+        }
+    };
+    let epoch_nanos = |v: &Value| -> Result<i128, RuntimeError> {
+        match v {
+            Value::BigInt(n) => check_instant(*n).map_err(fail(ctx, span)),
+            Value::Number(n) if n.fract() == 0.0 => check_instant(*n as i128).map_err(fail(ctx, span)),
+            other => Err(type_error(ctx, "наносекунды эпохи бигцелым", other, span)),
+        }
+    };
+    let result = match name {
+        "Мгновение" => Temporal::Instant(epoch_nanos(&arg(0))?),
+        "Мгновение.от" | "Мгновение.from" => Temporal::Instant(to_instant(&arg(0), ctx, span)?),
+        "Мгновение.изЭпохиМс" | "Мгновение.fromEpochMilliseconds" => match arg(0) {
+            Value::Number(ms) if ms.fract() == 0.0 => {
+                Temporal::Instant(check_instant(ms as i128 * NS_PER_MS).map_err(fail(ctx, span))?)
+            }
+            other => return Err(type_error(ctx, "миллисекунды целым числом", &other, span)),
+        },
+        "Мгновение.изЭпохиНс" | "Мгновение.fromEpochNanoseconds" => {
+            Temporal::Instant(epoch_nanos(&arg(0))?)
+        }
+        "ПростаяДата" => {
+            let (y, m, d) = (int_arg(0, "год")?, int_arg(1, "месяц")?, int_arg(2, "день")?);
+            Temporal::PlainDate(PlainDate::new(y, m, d, false).map_err(fail(ctx, span))?)
+        }
+        "ПростаяДата.от" | "ПростаяДата.from" => {
+            Temporal::PlainDate(to_date(&arg(0), &options(1)?, ctx, span)?)
+        }
+        "ПростоеВремя" => {
+            let mut fields = [0i64; 6];
+            for (i, (ru, _)) in TIME_FIELDS.into_iter().enumerate() {
+                fields[i] = int_arg(i, ru)?;
+            }
+            Temporal::PlainTime(PlainTime::new(fields, false).map_err(fail(ctx, span))?)
+        }
+        "ПростоеВремя.от" | "ПростоеВремя.from" => {
+            Temporal::PlainTime(to_time(&arg(0), &options(1)?, ctx, span)?)
+        }
+        "ЗонированноеВремя" => {
+            let nanos = epoch_nanos(&arg(0))?;
+            Temporal::Zoned(Zoned { nanos, zone: to_zone(&arg(1), ctx, span)? })
+        }
+        "ЗонированноеВремя.от" | "ЗонированноеВремя.from" => {
+            Temporal::Zoned(to_zoned(&arg(0), &options(1)?, ctx, span)?)
+        }
+        "Длительность" => {
+            let mut fields = [0i64; 10];
+            for (i, (ru, _)) in FIELD_NAMES.into_iter().enumerate() {
+                fields[i] = int_arg(i, ru)?;
+            }
+            Temporal::Duration(Duration::new(fields).map_err(fail(ctx, span))?)
+        }
+        "Длительность.от" | "Длительность.from" => {
+            Temporal::Duration(to_duration(&arg(0), ctx, span)?)
+        }
+        "Мгновение.сравнить" | "Мгновение.compare" => {
+            let (a, b) = (to_instant(&arg(0), ctx, span)?, to_instant(&arg(1), ctx, span)?);
+            return Ok(ordering(a.cmp(&b)));
+        }
+        "ПростаяДата.сравнить" | "ПростаяДата.compare" => {
+            let none = options(2)?;
+            let (a, b) = (to_date(&arg(0), &none, ctx, span)?, to_date(&arg(1), &none, ctx, span)?);
+            return Ok(ordering(a.cmp(&b)));
+        }
+        "ПростоеВремя.сравнить" | "ПростоеВремя.compare" => {
+            let none = options(2)?;
+            let (a, b) = (to_time(&arg(0), &none, ctx, span)?, to_time(&arg(1), &none, ctx, span)?);
+            return Ok(ordering(a.cmp(&b)));
+        }
+        "ЗонированноеВремя.сравнить" | "ЗонированноеВремя.compare" => {
+            let none = options(2)?;
+            let (a, b) = (to_zoned(&arg(0), &none, ctx, span)?, to_zoned(&arg(1), &none, ctx, span)?);
+            return Ok(ordering(a.nanos.cmp(&b.nanos)));
+        }
+        "Длительность.сравнить" | "Длительность.compare" => {
+            let (a, b) = (to_duration(&arg(0), ctx, span)?, to_duration(&arg(1), ctx, span)?);
+            let anchor = relative_to(&options(2)?, ctx, span)?;
+            let order = match anchor {
+                Some(anchor) => {
+                    let end_a = anchor.add(&a).map_err(fail(ctx, span))?;
+                    let end_b = anchor.add(&b).map_err(fail(ctx, span))?;
+                    end_a.nanos.cmp(&end_b.nanos)
+                }
+                None if a.has_calendar_units() || b.has_calendar_units() => return Err(needs_anchor(ctx, span)),
+                None => a.exact_nanos().cmp(&b.exact_nanos()),
+            };
+            return Ok(ordering(order));
+        }
+        "сейчас.мгновение" => Temporal::Instant(now_nanos()),
+        "сейчас.зонированное" | "сейчас.простаяДата" | "сейчас.простоеВремя" =>
+        {
+            let zone = match arg(0) {
+                Value::Undefined => TimeZone::system(),
+                other => to_zone(&other, ctx, span)?,
+            };
+            let now = Zoned { nanos: now_nanos(), zone };
+            match name {
+                "сейчас.простаяДата" => Temporal::PlainDate(now.date()),
+                "сейчас.простоеВремя" => Temporal::PlainTime(now.time()),
+                _ => Temporal::Zoned(now),
+            }
+        }
+        "сейчас.зона" => return Ok(Value::String(TimeZone::system().id.clone().into())),
+        _ => return Err(RuntimeError::new(format!("У 'Время' нет '{name}'"), span)),
+    };
+    Ok(value(result))
+}
+
+fn ordering(order: Ordering) -> Value {
+    Value::Number(order as i8 as f64)
+}
+
+pub fn call(t: &Temporal, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let ctx = method;
+    let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Undefined);
+    let options = |i: usize| Options::new(args.get(i), ctx, span);
+    let err = fail(ctx, span);
+    match method {
+        "вСтроку" | "toString" | "вЖсон" | "toJSON" => return Ok(Value::String(t.to_string().into())),
+        "доМомента" | "until" => return difference(t, &args, false, ctx, span),
+        "сМомента" | "since" => return difference(t, &args, true, ctx, span),
+        _ => {}
+    }
+    let result = match (t, method) {
+        (_, "прибавить" | "add" | "вычесть" | "subtract") => {
+            let mut d = to_duration(&arg(0), ctx, span)?;
+            if matches!(method, "вычесть" | "subtract") {
+                d = d.negated();
+            }
+            add(t, &d, &options(1)?, ctx, span)?
+        }
+        (Temporal::Instant(nanos), "округлить" | "round") => {
+            let r = Rounding::read_round(args.first(), ctx, span, Unit::Hour..=Unit::Nanosecond)?;
+            Temporal::Instant(check_instant(r.mode.divide(*nanos, r.size()) * r.size()).map_err(err)?)
+        }
+        (Temporal::PlainTime(time), "округлить" | "round") => {
+            let r = Rounding::read_round(args.first(), ctx, span, Unit::Hour..=Unit::Nanosecond)?;
+            let rounded = r.mode.divide(time.nanos as i128, r.size()) * r.size();
+            Temporal::PlainTime(PlainTime { nanos: rounded.rem_euclid(NS_PER_DAY) as i64 })
+        }
+        (Temporal::Zoned(z), "округлить" | "round") => {
+            let r = Rounding::read_round(args.first(), ctx, span, Unit::Day..=Unit::Nanosecond)?;
+            let zoned = if r.smallest == Unit::Day {
+                let (start, next) = (z.start_of_day().map_err(&err)?, z.next_day_start().map_err(&err)?);
+                let up = r.mode.divide(z.nanos - start.nanos, next.nanos - start.nanos) == 1;
+                if up { next } else { start }
+            } else {
+                let local = r.mode.divide(z.local(), r.size()) * r.size();
+                Zoned::from_local_preferring(local, z.offset(), Rc::clone(&z.zone), Disambiguation::Compatible)
+                    .map_err(&err)?
+            };
+            Temporal::Zoned(zoned)
+        }
+        (Temporal::PlainDate(date), "сЗаменой" | "with") => {
+            let fields = Options::new(args.first(), ctx, span)?;
+            Temporal::PlainDate(date_from_fields(&fields, Some(*date), constrain(&options(1)?)?)?)
+        }
+        (Temporal::PlainTime(time), "сЗаменой" | "with") => {
+            let fields = Options::new(args.first(), ctx, span)?;
+            Temporal::PlainTime(time_from_fields(&fields, *time, constrain(&options(1)?)?)?)
+        }
+        (Temporal::Zoned(z), "сЗаменой" | "with") => {
+            let fields = Options::new(args.first(), ctx, span)?;
+            let opts = options(1)?;
+            let date = date_from_fields(&fields, Some(z.date()), constrain(&opts)?)?;
+            let time = time_from_fields(&fields, z.time(), constrain(&opts)?)?;
+            let local = local_nanos(date, time);
+            let zoned = Zoned::from_local_preferring(local, z.offset(), Rc::clone(&z.zone), disambiguation(&opts)?);
+            Temporal::Zoned(zoned.map_err(err)?)
+        }
+        (_, "равно" | "equals") => {
+            let none = options(1)?;
+            let equal = match t {
+                Temporal::Instant(nanos) => *nanos == to_instant(&arg(0), ctx, span)?,
+                Temporal::PlainDate(date) => *date == to_date(&arg(0), &none, ctx, span)?,
+                Temporal::PlainTime(time) => *time == to_time(&arg(0), &none, ctx, span)?,
+                Temporal::Zoned(z) => {
+                    let other = to_zoned(&arg(0), &none, ctx, span)?;
+                    z.nanos == other.nanos && z.zone.id == other.zone.id
+                }
+                Temporal::Duration(_) => return Err(err(format!("у '{}' нет метода '{method}'", t.type_name()))),
+            };
+            return Ok(Value::Boolean(equal));
+        }
+        (Temporal::Instant(nanos), "вЗону" | "toZonedDateTimeISO") => {
+            Temporal::Zoned(Zoned { nanos: *nanos, zone: to_zone(&arg(0), ctx, span)? })
+        }
+        (Temporal::Zoned(z), "вЗону" | "withTimeZone") => {
+            Temporal::Zoned(Zoned { nanos: z.nanos, zone: to_zone(&arg(0), ctx, span)? })
+        }
+        (Temporal::PlainDate(date), "вЗону" | "toZonedDateTime") => {
+            let (zone, time) = match arg(0) {
+                Value::Object(_) => {
+                    let fields = Options::new(args.first(), ctx, span)?;
+                    let zone = to_zone(fields.get("зона", "timeZone").unwrap_or(&Value::Undefined), ctx, span)?;
+                    let none = options(1)?;
+                    let time = match fields.get("время", "plainTime") {
+                        Some(time) => Some(to_time(time, &none, ctx, span)?),
+                        None => None,
+                    };
+                    (zone, time)
+                }
+                other => (to_zone(&other, ctx, span)?, None),
+            };
+            let zoned = match time {
+                Some(time) => Zoned::from_local(*date, time, zone, Disambiguation::Compatible),
+                None => Zoned::from_local(*date, PlainTime::MIDNIGHT, zone, Disambiguation::Compatible),
+            };
+            Temporal::Zoned(zoned.map_err(err)?)
+        }
+        (Temporal::Zoned(z), "вМгновение" | "toInstant") => Temporal::Instant(z.nanos),
+        (Temporal::Zoned(z), "вПростуюДату" | "toPlainDate") => Temporal::PlainDate(z.date()),
+        (Temporal::Zoned(z), "вПростоеВремя" | "toPlainTime") => Temporal::PlainTime(z.time()),
+        (Temporal::Zoned(z), "началоДня" | "startOfDay") => Temporal::Zoned(z.start_of_day().map_err(err)?),
+        (Temporal::Duration(d), "обратная" | "negated") => Temporal::Duration(d.negated()),
+        (Temporal::Duration(d), "модуль" | "abs") => {
+            Temporal::Duration(Duration { fields: d.fields.map(i64::abs) })
+        }
+        (Temporal::Duration(d), "округлить" | "round") => {
+            Temporal::Duration(round_duration(d, args.first(), ctx, span)?)
+        }
+        (Temporal::Duration(d), "итого" | "total") => {
+            let opts = match arg(0) {
+                Value::String(unit) => {
+                    let mut map = indexmap::IndexMap::new();
+                    map.insert("единица".to_string(), Value::String(unit));
+                    Value::object(map)
+                }
+                other => other,
+            };
+            let opts = Options::new(Some(&opts), ctx, span)?;
+            let unit =
+                unit_option(&opts, "единица", "unit")?.ok_or_else(|| opts.error("нужна 'единица'".to_string()))?;
+            let total = match relative_to(&opts, ctx, span)? {
+                Some(anchor) => {
+                    let end = anchor.add(d).map_err(&err)?;
+                    total_relative(&anchor, &end, unit).map_err(err)?
+                }
+                None if d.has_calendar_units() || unit.is_calendar() => return Err(needs_anchor(ctx, span)),
+                None => ratio(d.exact_nanos(), unit.nanos()),
+            };
+            return Ok(Value::Number(total));
+        }
+        _ => return Err(RuntimeError::new(format!("У '{}' нет метода '{method}'", t.type_name()), span)),
+    };
+    Ok(value(result))
+}
+
+fn add(t: &Temporal, d: &Duration, options: &Options, ctx: &str, span: Span) -> Result<Temporal, RuntimeError> {
+    let err = fail(ctx, span);
+    Ok(match t {
+        Temporal::Instant(nanos) => {
+            if d.has_calendar_units() || d.get(Unit::Day) != 0 {
+                return Err(err("к мгновению можно прибавлять только часы и меньшие единицы".to_string()));
+            }
+            Temporal::Instant(check_instant(nanos + d.time_nanos()).map_err(err)?)
+        }
+        Temporal::PlainDate(date) => {
+            // Hours and smaller units count only as whole days.
+            let days = d.get(Unit::Day) + (d.time_nanos() / NS_PER_DAY) as i64;
+            let (y, m, w) = (d.get(Unit::Year), d.get(Unit::Month), d.get(Unit::Week));
+            Temporal::PlainDate(date.add(y, m, w, days, constrain(options)?).map_err(err)?)
+        }
+        Temporal::PlainTime(time) => {
+            let nanos = (time.nanos as i128 + d.time_nanos()).rem_euclid(NS_PER_DAY);
+            Temporal::PlainTime(PlainTime { nanos: nanos as i64 })
+        }
+        Temporal::Zoned(z) => Temporal::Zoned(z.add(d).map_err(err)?),
+        Temporal::Duration(a) => {
+            let anchor = relative_to(options, ctx, span)?;
+            let largest = a.largest_unit().min(d.largest_unit());
+            let sum = match anchor {
+                Some(anchor) => {
+                    let end = anchor.add(a).and_then(|mid| mid.add(d)).map_err(&err)?;
+                    anchor.until(&end, largest)
+                }
+                None if a.has_calendar_units() || d.has_calendar_units() => return Err(needs_anchor(ctx, span)),
+                None => Duration::balance(a.exact_nanos() + d.exact_nanos(), largest),
+            };
+            Temporal::Duration(sum.map_err(err)?)
+        }
+    })
+}
+
+/// `доМомента` and `сМомента`: `a.сМомента(б)` is `a.доМомента(б)` negated, rounding away
+/// from `a` in both cases.
+fn difference(t: &Temporal, args: &[Value], since: bool, ctx: &str, span: Span) -> Result<Value, RuntimeError> {
+    let other = args.first().cloned().unwrap_or(Value::Undefined);
+    let options = Options::new(args.get(1), ctx, span)?;
+    let none = Options::new(None, ctx, span)?;
+    let utc = TimeZone::utc;
+    let (anchor, end, default_largest, allowed) = match t {
+        Temporal::Instant(nanos) => (
+            Zoned { nanos: *nanos, zone: utc() },
+            Zoned { nanos: to_instant(&other, ctx, span)?, zone: utc() },
+            Unit::Second,
+            Unit::Hour..=Unit::Nanosecond,
+        ),
+        Temporal::PlainDate(date) => (
+            Zoned::at_utc_midnight(*date),
+            Zoned::at_utc_midnight(to_date(&other, &none, ctx, span)?),
+            Unit::Day,
+            Unit::Year..=Unit::Day,
+        ),
+        Temporal::PlainTime(time) => (
+            Zoned { nanos: time.nanos as i128, zone: utc() },
+            Zoned { nanos: to_time(&other, &none, ctx, span)?.nanos as i128, zone: utc() },
+            Unit::Hour,
+            Unit::Hour..=Unit::Nanosecond,
+        ),
+        Temporal::Zoned(z) => {
+            (z.clone(), to_zoned(&other, &none, ctx, span)?, Unit::Hour, Unit::Year..=Unit::Nanosecond)
+        }
+        Temporal::Duration(_) => {
+            return Err(RuntimeError::new(format!("У 'длительность' нет метода '{ctx}'"), span));
+        }
+    };
+    let rounding = Rounding::read(&options, default_largest, RoundingMode::Trunc, allowed)?;
+    let d = if since {
+        round_relative(&anchor, &end, &rounding.negated()).map(|d| d.negated())
+    } else {
+        round_relative(&anchor, &end, &rounding)
+    };
+    Ok(value(Temporal::Duration(d.map_err(fail(ctx, span))?)))
+}
+
+fn round_duration(d: &Duration, arg: Option<&Value>, ctx: &str, span: Span) -> Result<Duration, RuntimeError> {
+    let options = match arg {
+        Some(Value::String(unit)) => {
+            let mut map = indexmap::IndexMap::new();
+            map.insert("наименьшаяЕдиница".to_string(), Value::String(unit.clone()));
+            Value::object(map)
+        }
+        Some(other) => other.clone(),
+        None => Value::Undefined,
+    };
+    let options = Options::new(Some(&options), ctx, span)?;
+    if options.get("наименьшаяЕдиница", "smallestUnit").is_none()
+        && options.get("наибольшаяЕдиница", "largestUnit").is_none()
+    {
+        return Err(options.error("нужна 'наименьшаяЕдиница' или 'наибольшаяЕдиница'".to_string()));
+    }
+    let rounding = Rounding::read(&options, d.largest_unit(), RoundingMode::HalfExpand, Unit::Year..=Unit::Nanosecond)?;
+    let err = fail(ctx, span);
+    match relative_to(&options, ctx, span)? {
+        Some(anchor) => {
+            let end = anchor.add(d).map_err(&err)?;
+            round_relative(&anchor, &end, &rounding).map_err(err)
+        }
+        None if d.has_calendar_units() || rounding.largest.is_calendar() => Err(needs_anchor(ctx, span)),
+        None => {
+            let size = rounding.size();
+            Duration::balance(rounding.mode.divide(d.exact_nanos(), size) * size, rounding.largest).map_err(err)
+        }
+    }
+}
+
+/// Properties of `Время` objects.
+pub fn get_property(t: &Temporal, property: &str) -> Value {
+    let number = |n: i64| Value::Number(n as f64);
+    let date = match t {
+        Temporal::PlainDate(date) => Some(*date),
+        Temporal::Zoned(z) => Some(z.date()),
+        _ => None,
+    };
+    if let Some(date) = date {
+        let found = match property {
+            "год" | "year" => Some(number(date.year)),
+            "месяц" | "month" => Some(number(date.month as i64)),
+            "день" | "day" => Some(number(date.day as i64)),
+            "деньНедели" | "dayOfWeek" => Some(number(date.day_of_week() as i64)),
+            "деньГода" | "dayOfYear" => Some(number(date.day_of_year() as i64)),
+            "днейВМесяце" | "daysInMonth" => Some(number(date.days_in_month() as i64)),
+            "днейВГоду" | "daysInYear" => Some(number(days_in_year(date.year) as i64)),
+            "високосный" | "inLeapYear" => Some(Value::Boolean(is_leap(date.year))),
+            _ => None,
+        };
+        if let Some(found) = found {
+            return found;
+        }
+    }
+    let time = match t {
+        Temporal::PlainTime(time) => Some(*time),
+        Temporal::Zoned(z) => Some(z.time()),
+        _ => None,
+    };
+    if let Some(time) = time
+        && let Some(i) = TIME_FIELDS.iter().position(|(ru, en)| property == *ru || property == *en)
+    {
+        return number(time.fields()[i]);
+    }
+    match (t, property) {
+        (Temporal::Instant(nanos), "эпохаМс" | "epochMilliseconds")
+        | (Temporal::Zoned(Zoned { nanos, .. }), "эпохаМс" | "epochMilliseconds") => {
+            Value::Number(nanos.div_euclid(NS_PER_MS) as f64)
+        }
+        (Temporal::Instant(nanos), "эпохаНс" | "epochNanoseconds")
+        | (Temporal::Zoned(Zoned { nanos, .. }), "эпохаНс" | "epochNanoseconds") => Value::BigInt(*nanos),
+        (Temporal::Zoned(z), "зона" | "timeZoneId") => Value::String(z.zone.id.clone().into()),
+        (Temporal::Zoned(z), "смещение" | "offset") => Value::String(format_offset(z.offset()).into()),
+        (Temporal::Zoned(z), "смещениеНс" | "offsetNanoseconds") => {
+            Value::Number((z.offset() as i128 * NS_PER_SECOND) as f64)
+        }
+        (Temporal::Zoned(z), "часовВДне" | "hoursInDay") => match (z.start_of_day(), z.next_day_start()) {
+            (Ok(start), Ok(next)) => Value::Number(ratio(next.nanos - start.nanos, Unit::Hour.nanos())),
+            _ => Value::Undefined,
+        },
+        (Temporal::Duration(d), "знак" | "sign") => number(d.sign()),
+        (Temporal::Duration(d), "пустая" | "blank") => Value::Boolean(d.sign() == 0),
+        (Temporal::Duration(d), _) => {
+            match FIELD_NAMES.iter().position(|(ru, en)| property == *ru || property == *en) {
+                Some(i) => number(d.fields[i]),
+                None => Value::Undefined,
+            }
+        }
+        _ => Value::Undefined,
+    }
+}
+
+/// `Время` objects in JSON, as their ISO strings.
+pub fn to_json_string(t: &Temporal) -> String {
+    t.to_string()
+}
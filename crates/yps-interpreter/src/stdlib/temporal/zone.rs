@@ -0,0 +1,417 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use super::calendar::{PlainDate, days_in_year};
+
+const DAY_SECONDS: i64 = 86_400;
+
+thread_local! {
+    static ZONES: RefCell<HashMap<String, Rc<TimeZone>>> = RefCell::new(HashMap::new());
+}
+
+/// How a local time that occurs twice, or not at all, is turned into an instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disambiguation {
+    /// The earlier of two instants; in a gap, the time shifted forward by the gap's length.
+    Compatible,
+    Earlier,
+    Later,
+    Reject,
+}
+
+/// A time zone: a fixed offset or an IANA zone read from the system tzdata.
+#[derive(Debug)]
+pub struct TimeZone {
+    pub id: String,
+    rule: Rule,
+}
+
+#[derive(Debug)]
+enum Rule {
+    Fixed(i64),
+    Tzif(Tzif),
+}
+
+/// The transitions of a TZif file, and the POSIX rule that continues them.
+#[derive(Debug)]
+struct Tzif {
+    /// Offset before the first transition.
+    initial: i64,
+    /// Epoch seconds of each transition and the offset that starts there.
+    transitions: Vec<(i64, i64)>,
+    tail: Option<Posix>,
+}
+
+impl TimeZone {
+    /// `"UTC"`, a fixed offset such as `"+03:00"`, or an IANA name such as `"Europe/Moscow"`.
+    pub fn get(id: &str) -> Result<Rc<TimeZone>, String> {
+        if id.eq_ignore_ascii_case("utc") || id == "Z" {
+            return Ok(TimeZone::utc());
+        }
+        if id.starts_with(['+', '-']) {
+            let offset = parse_offset(id).ok_or_else(|| format!("неверное смещение зоны '{id}'"))?;
+            return Ok(Rc::new(TimeZone { id: format_offset(offset), rule: Rule::Fixed(offset) }));
+        }
+        if let Some(zone) = ZONES.with(|zones| zones.borrow().get(id).cloned()) {
+            return Ok(zone);
+        }
+        let valid = !id.is_empty()
+            && !id.starts_with('/')
+            && id.split('/').all(|part| part != ".." && part != ".")
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'));
+        let unknown = || format!("неизвестная часовая зона '{id}'");
+        if !valid {
+            return Err(unknown());
+        }
+        let bytes = std::fs::read(zoneinfo_dir().join(id)).map_err(|_| unknown())?;
+        let tzif = parse_tzif(&bytes).ok_or_else(|| format!("файл часовой зоны '{id}' повреждён"))?;
+        let zone = Rc::new(TimeZone { id: id.to_string(), rule: Rule::Tzif(tzif) });
+        ZONES.with(|zones| zones.borrow_mut().insert(id.to_string(), Rc::clone(&zone)));
+        Ok(zone)
+    }
+
+    /// The zone of this machine: `$TZ`, else the target of `/etc/localtime`, else UTC.
+    pub fn system() -> Rc<TimeZone> {
+        let from_env = std::env::var("TZ").ok().map(|tz| tz.trim_start_matches(':').to_string());
+        let from_link = || {
+            let target = std::fs::read_link("/etc/localtime").ok()?;
+            Some(target.to_string_lossy().into_owned())
+        };
+        from_env
+            .filter(|tz| !tz.is_empty())
+            .or_else(from_link)
+            .map(|name| match name.rsplit_once("zoneinfo/") {
+                Some((_, id)) => id.to_string(),
+                None => name,
+            })
+            .and_then(|id| TimeZone::get(&id).ok())
+            .unwrap_or_else(TimeZone::utc)
+    }
+
+    pub fn utc() -> Rc<TimeZone> {
+        Rc::new(TimeZone { id: "UTC".to_string(), rule: Rule::Fixed(0) })
+    }
+
+    /// UTC offset in seconds at an instant given in epoch seconds.
+    pub fn offset_at(&self, epoch_seconds: i64) -> i64 {
+        let tzif = match &self.rule {
+            Rule::Fixed(offset) => return *offset,
+            Rule::Tzif(tzif) => tzif,
+        };
+        let after = tzif.transitions.partition_point(|&(at, _)| at <= epoch_seconds);
+        match (after, &tzif.tail) {
+            (0, _) => tzif.initial,
+            (n, Some(tail)) if n == tzif.transitions.len() => tail.offset_at(epoch_seconds),
+            (n, _) => tzif.transitions[n - 1].1,
+        }
+    }
+
+    /// Every instant (epoch nanoseconds) whose local time in this zone is `local_ns`: one
+    /// normally, two in a fold, none in a gap.
+    pub fn possible_instants(&self, local_ns: i128) -> Vec<i128> {
+        let local_seconds = local_ns.div_euclid(1_000_000_000) as i64;
+        let before = self.offset_at(local_seconds - DAY_SECONDS);
+        let after = self.offset_at(local_seconds + DAY_SECONDS);
+        let mut out = Vec::with_capacity(2);
+        for offset in [before, after] {
+            let instant = local_ns - offset as i128 * 1_000_000_000;
+            if self.offset_at(instant.div_euclid(1_000_000_000) as i64) == offset && !out.contains(&instant) {
+                out.push(instant);
+            }
+        }
+        out.sort_unstable();
+        out
+    }
+
+    pub fn instant_for(&self, local_ns: i128, disambiguation: Disambiguation) -> Result<i128, String> {
+        let candidates = self.possible_instants(local_ns);
+        match (candidates.as_slice(), disambiguation) {
+            ([only], _) => Ok(*only),
+            ([_, _], Disambiguation::Reject) => Err(format!("местное время неоднозначно в зоне '{}'", self.id)),
+            ([earlier, _], Disambiguation::Compatible | Disambiguation::Earlier) => Ok(*earlier),
+            ([_, later], _) => Ok(*later),
+            (_, Disambiguation::Reject) => Err(format!("такого местного времени нет в зоне '{}'", self.id)),
+            (_, _) => {
+                let local_seconds = local_ns.div_euclid(1_000_000_000) as i64;
+                let offset = match disambiguation {
+                    Disambiguation::Earlier => self.offset_at(local_seconds + DAY_SECONDS),
+                    _ => self.offset_at(local_seconds - DAY_SECONDS),
+                };
+                Ok(local_ns - offset as i128 * 1_000_000_000)
+            }
+        }
+    }
+}
+
+fn zoneinfo_dir() -> PathBuf {
+    std::env::var_os("TZDIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("/usr/share/zoneinfo"))
+}
+
+/// `±ЧЧ:ММ[:СС]` or `±ЧЧММ` in seconds.
+pub fn parse_offset(text: &str) -> Option<i64> {
+    let sign = match text.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits: String = text[1..].chars().filter(|&c| c != ':').collect();
+    if !matches!(digits.len(), 2 | 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let part = |i: usize| digits.get(i..i + 2).map_or(0, |p| p.parse::<i64>().unwrap_or(0));
+    let (h, m, s) = (part(0), part(2), part(4));
+    (h < 24 && m < 60 && s < 60).then_some(sign * (h * 3600 + m * 60 + s))
+}
+
+pub fn format_offset(offset: i64) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let abs = offset.abs();
+    let (h, m, s) = (abs / 3600, abs / 60 % 60, abs % 60);
+    if s == 0 { format!("{sign}{h:02}:{m:02}") } else { format!("{sign}{h:02}:{m:02}:{s:02}") }
+}
+
+fn parse_tzif(bytes: &[u8]) -> Option<Tzif> {
+    let header = Header::read(bytes)?;
+    if header.version == 0 {
+        return header.data(bytes, 44, 4);
+    }
+    // Version 2+ repeats the data with 64-bit times after the 32-bit block, then adds a footer.
+    let second = 44 + header.block_len(4);
+    let header2 = Header::read(bytes.get(second..)?)?;
+    let mut tzif = header2.data(&bytes[second..], 44, 8)?;
+    let footer_start = second + 44 + header2.block_len(8);
+    let footer = bytes.get(footer_start..)?;
+    let footer = std::str::from_utf8(footer).ok()?.trim_matches('\n');
+    tzif.tail = Posix::parse(footer);
+    Some(tzif)
+}
+
+struct Header {
+    version: u8,
+    isut: usize,
+    isstd: usize,
+    leap: usize,
+    time: usize,
+    types: usize,
+    chars: usize,
+}
+
+impl Header {
+    fn read(bytes: &[u8]) -> Option<Header> {
+        if bytes.get(..4)? != b"TZif" {
+            return None;
+        }
+        let count = |i: usize| -> Option<usize> {
+            let b = bytes.get(20 + i * 4..24 + i * 4)?;
+            Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        };
+        Some(Header {
+            version: bytes[4].saturating_sub(b'0'),
+            isut: count(0)?,
+            isstd: count(1)?,
+            leap: count(2)?,
+            time: count(3)?,
+            types: count(4)?,
+            chars: count(5)?,
+        })
+    }
+
+    fn block_len(&self, time_size: usize) -> usize {
+        self.time * (time_size + 1) + self.types * 6 + self.chars + self.leap * (time_size + 4) + self.isstd + self.isut
+    }
+
+    fn data(&self, bytes: &[u8], start: usize, time_size: usize) -> Option<Tzif> {
+        let times = bytes.get(start..start + self.time * time_size)?;
+        let indices = bytes.get(start + self.time * time_size..start + self.time * (time_size + 1))?;
+        let types_start = start + self.time * (time_size + 1);
+        let types = bytes.get(types_start..types_start + self.types * 6)?;
+        let offsets: Vec<i64> =
+            types.chunks_exact(6).map(|t| i32::from_be_bytes([t[0], t[1], t[2], t[3]]) as i64).collect();
+        let transitions = times
+            .chunks_exact(time_size)
+            .zip(indices)
+            .map(|(t, &index)| {
+                let at = if time_size == 8 {
+                    i64::from_be_bytes(t.try_into().ok()?)
+                } else {
+                    i32::from_be_bytes(t.try_into().ok()?) as i64
+                };
+                Some((at, *offsets.get(index as usize)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Tzif { initial: *offsets.first()?, transitions, tail: None })
+    }
+}
+
+/// A POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`, which TZif files use for times
+/// after their last transition.
+#[derive(Debug, PartialEq)]
+struct Posix {
+    std_offset: i64,
+    dst: Option<(i64, PosixDate, i64, PosixDate, i64)>,
+}
+
+/// The day a POSIX rule switches on.
+#[derive(Debug, PartialEq)]
+enum PosixDate {
+    /// `Jn`: day 1–365, never counting February 29.
+    Julian(u32),
+    /// `n`: day 0–365, counting February 29.
+    Ordinal(u32),
+    /// `Mm.w.d`: weekday `d` of week `w` (5 = last) of month `m`.
+    Weekday { month: u32, week: u32, weekday: u32 },
+}
+
+impl Posix {
+    fn parse(text: &str) -> Option<Posix> {
+        let mut rest = text;
+        skip_name(&mut rest)?;
+        let std_offset = -posix_time(&mut rest)?;
+        if rest.is_empty() {
+            return Some(Posix { std_offset, dst: None });
+        }
+        skip_name(&mut rest)?;
+        let dst_offset = if rest.starts_with(',') { std_offset + 3600 } else { -posix_time(&mut rest)? };
+        let mut rule = || -> Option<(PosixDate, i64)> {
+            rest = rest.strip_prefix(',')?;
+            let date = if let Some(r) = rest.strip_prefix('M') {
+                rest = r;
+                let month = posix_number(&mut rest)?;
+                rest = rest.strip_prefix('.')?;
+                let week = posix_number(&mut rest)?;
+                rest = rest.strip_prefix('.')?;
+                let weekday = posix_number(&mut rest)?;
+                PosixDate::Weekday { month, week, weekday }
+            } else if let Some(r) = rest.strip_prefix('J') {
+                rest = r;
+                PosixDate::Julian(posix_number(&mut rest)?)
+            } else {
+                PosixDate::Ordinal(posix_number(&mut rest)?)
+            };
+            let time = match rest.strip_prefix('/') {
+                Some(r) => {
+                    rest = r;
+                    posix_time(&mut rest)?
+                }
+                None => 7200,
+            };
+            Some((date, time))
+        };
+        let (start, start_time) = rule()?;
+        let (end, end_time) = rule()?;
+        Some(Posix { std_offset, dst: Some((dst_offset, start, start_time, end, end_time)) })
+    }
+
+    fn offset_at(&self, epoch_seconds: i64) -> i64 {
+        let Some((dst_offset, start, start_time, end, end_time)) = &self.dst else {
+            return self.std_offset;
+        };
+        let year = PlainDate::from_epoch_days((epoch_seconds + self.std_offset).div_euclid(DAY_SECONDS)).year;
+        // The switch to DST happens at a standard-time wall clock, the switch back at a DST one.
+        let start = start.epoch_days(year) * DAY_SECONDS + start_time - self.std_offset;
+        let end = end.epoch_days(year) * DAY_SECONDS + end_time - dst_offset;
+        let in_dst =
+            if start < end { (start..end).contains(&epoch_seconds) } else { !(end..start).contains(&epoch_seconds) };
+        if in_dst { *dst_offset } else { self.std_offset }
+    }
+}
+
+impl PosixDate {
+    fn epoch_days(&self, year: i64) -> i64 {
+        let jan1 = PlainDate { year, month: 1, day: 1 }.epoch_days();
+        match *self {
+            PosixDate::Julian(n) => {
+                let leap_shift = (days_in_year(year) == 366 && n >= 60) as i64;
+                jan1 + n as i64 - 1 + leap_shift
+            }
+            PosixDate::Ordinal(n) => jan1 + n as i64,
+            PosixDate::Weekday { month, week, weekday } => {
+                let first = PlainDate { year, month, day: 1 };
+                let first_days = first.epoch_days();
+                // 1970-01-01 was a Thursday; POSIX counts weekdays from Sunday.
+                let first_weekday = (first_days + 4).rem_euclid(7);
+                let mut day = first_days + (weekday as i64 - first_weekday).rem_euclid(7) + (week as i64 - 1) * 7;
+                let next_month = first_days + first.days_in_month() as i64;
+                while day >= next_month {
+                    day -= 7;
+                }
+                day
+            }
+        }
+    }
+}
+
+/// Skips a zone abbreviation: letters, or anything inside `<…>`.
+fn skip_name(rest: &mut &str) -> Option<()> {
+    if let Some(r) = rest.strip_prefix('<') {
+        *rest = &r[r.find('>')? + 1..];
+    } else {
+        let len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        if len < 3 {
+            return None;
+        }
+        *rest = &rest[len..];
+    }
+    Some(())
+}
+
+fn posix_number(rest: &mut &str) -> Option<u32> {
+    let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let n = rest[..len].parse().ok()?;
+    *rest = &rest[len..];
+    Some(n)
+}
+
+/// `[+-]ч[:мм[:сс]]` in seconds.
+fn posix_time(rest: &mut &str) -> Option<i64> {
+    let sign = match rest.as_bytes().first() {
+        Some(b'-') => -1,
+        Some(b'+') => 1,
+        _ => 0,
+    };
+    if sign != 0 {
+        *rest = &rest[1..];
+    }
+    let mut seconds = posix_number(rest)? as i64 * 3600;
+    for scale in [60, 1] {
+        match rest.strip_prefix(':') {
+            Some(r) => {
+                *rest = r;
+                seconds += posix_number(rest)? as i64 * scale;
+            }
+            None => break,
+        }
+    }
+    Some(if sign < 0 { -seconds } else { seconds })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posix_rule_switches_on_the_last_sunday() {
+        let berlin = Posix::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let at = |y, m, d, h: i64| PlainDate { year: y, month: m, day: d }.epoch_days() * DAY_SECONDS + h * 3600;
+        // 2026-03-29 01:00 UTC is 03:00 CEST; one second earlier is 01:59:59 CET.
+        assert_eq!(berlin.offset_at(at(2026, 3, 29, 1) - 1), 3600);
+        assert_eq!(berlin.offset_at(at(2026, 3, 29, 1)), 7200);
+        assert_eq!(berlin.offset_at(at(2026, 10, 25, 1) - 1), 7200);
+        assert_eq!(berlin.offset_at(at(2026, 10, 25, 1)), 3600);
+        let santiago = Posix::parse("<-04>4<-03>,M9.1.6/24,M4.1.6/24").unwrap();
+        assert_eq!(santiago.offset_at(at(2026, 1, 15, 12)), -3 * 3600);
+        assert_eq!(santiago.offset_at(at(2026, 6, 15, 12)), -4 * 3600);
+        assert_eq!(Posix::parse("<+03>-3").unwrap().offset_at(0), 3 * 3600);
+    }
+
+    #[test]
+    fn offsets_parse_and_format() {
+        assert_eq!(parse_offset("+03:00"), Some(10_800));
+        assert_eq!(parse_offset("-0530"), Some(-19_800));
+        assert_eq!(parse_offset("+24:00"), None);
+        assert_eq!(format_offset(-19_800), "-05:30");
+        assert_eq!(format_offset(7_322), "+02:02:02");
+    }
+}
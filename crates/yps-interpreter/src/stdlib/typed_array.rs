@@ -1,4 +1,3 @@
-use std::cell::RefCell;
 use std::rc::Rc;
 
 use yps_lexer::Span;
@@ -30,7 +29,7 @@ pub fn kind_from_name(name: &str) -> Option<TypedArrayKind> {
     }
 }
 
-fn to_number_input(v: &Value, span: Span) -> Result<f64, RuntimeError> {
+pub(crate) fn to_number_input(v: &Value, span: Span) -> Result<f64, RuntimeError> {
     match v {
         Value::Number(n) => Ok(*n),
         Value::Null => Ok(0.0),
@@ -67,7 +66,7 @@ pub fn construct(kind: TypedArrayKind, args: Vec<Value>, span: Span) -> Result<V
     let first = it.next();
     match first {
         None => Ok(Value::TypedArray(Rc::new(TypedArrayData {
-            buffer: Rc::new(RefCell::new(Vec::new())),
+            buffer: SharedBuffer::new(Vec::new()),
             offset: 0,
             length: 0,
             kind,
@@ -78,7 +77,7 @@ pub fn construct(kind: TypedArrayKind, args: Vec<Value>, span: Span) -> Result<V
             }
             let length = n as usize;
             let byte_len = checked_byte_len(length, size, span)?;
-            let buffer = Rc::new(RefCell::new(vec![0u8; byte_len]));
+            let buffer = SharedBuffer::new(vec![0u8; byte_len]);
             Ok(Value::TypedArray(Rc::new(TypedArrayData { buffer, offset: 0, length, kind })))
         }
         Some(Value::ArrayBuffer(buffer)) => {
@@ -134,7 +133,7 @@ pub fn construct(kind: TypedArrayKind, args: Vec<Value>, span: Span) -> Result<V
             let snapshot = arr.borrow().clone();
             let length = snapshot.len();
             let byte_len = checked_byte_len(length, size, span)?;
-            let buffer = Rc::new(RefCell::new(vec![0u8; byte_len]));
+            let buffer = SharedBuffer::new(vec![0u8; byte_len]);
             for (i, el) in snapshot.iter().enumerate() {
                 write_element(&buffer, kind, i * size, el, span)?;
             }
@@ -143,10 +142,10 @@ pub fn construct(kind: TypedArrayKind, args: Vec<Value>, span: Span) -> Result<V
         Some(Value::TypedArray(src_ta)) => {
             let src = src_ta.buffer.clone();
             let src_off = src_ta.offset;
-            let length = src_ta.length;
+            let length = src_ta.live_length();
             let src_kind = src_ta.kind;
             let byte_len = checked_byte_len(length, size, span)?;
-            let new_buffer = Rc::new(RefCell::new(vec![0u8; byte_len]));
+            let new_buffer = SharedBuffer::new(vec![0u8; byte_len]);
             let src_size = src_kind.element_size();
             let src_bytes = src.borrow();
             let mut dst = new_buffer.borrow_mut();
@@ -164,26 +163,37 @@ pub fn construct(kind: TypedArrayKind, args: Vec<Value>, span: Span) -> Result<V
 }
 
 pub fn construct_array_buffer(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let byte_len = byte_length_arg("ОбластьБайтов", args, span)?;
+    Ok(Value::ArrayBuffer(SharedBuffer::new(vec![0u8; byte_len])))
+}
+
+/// `захуярить ОбщийБуфер(n)`: bytes that stay shared with every `Воркер` they are sent to.
+pub fn construct_shared_array_buffer(args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let byte_len = byte_length_arg("ОбщийБуфер", args, span)?;
+    Ok(Value::ArrayBuffer(SharedBuffer::new_shared(vec![0u8; byte_len])))
+}
+
+fn byte_length_arg(name: &str, args: Vec<Value>, span: Span) -> Result<usize, RuntimeError> {
     let n = match args.into_iter().next() {
         None | Some(Value::Undefined) => 0.0,
         Some(Value::Number(n)) => n,
         Some(other) => {
             return Err(RuntimeError::new(
-                format!("'ОбластьБайтов' ожидает число байт, получено '{}'", other.type_name()),
+                format!("'{name}' ожидает число байт, получено '{}'", other.type_name()),
                 span,
             ));
         }
     };
     if !n.is_finite() || n < 0.0 || n.fract() != 0.0 {
-        return Err(RuntimeError::new("'ОбластьБайтов' ожидает неотрицательное целое число байт", span));
+        return Err(RuntimeError::new(format!("'{name}' ожидает неотрицательное целое число байт"), span));
     }
-    let byte_len = checked_byte_len(n as usize, 1, span)?;
-    Ok(Value::ArrayBuffer(Rc::new(RefCell::new(vec![0u8; byte_len]))))
+    checked_byte_len(n as usize, 1, span)
 }
 
 pub fn ta_elements(buffer: &SharedBuffer, offset: usize, length: usize, kind: TypedArrayKind) -> Vec<Value> {
     let bytes = buffer.borrow();
     let size = kind.element_size();
+    let length = if offset + length * size <= bytes.len() { length } else { 0 };
     (0..length).map(|i| Value::Number(kind.read_le(&bytes, offset + i * size))).collect()
 }
 
@@ -208,6 +218,10 @@ fn relative_index(arg: Option<&Value>, length: usize, default: usize) -> usize {
     }
 }
 
+/// Methods of typed arrays.
+pub const METHODS: &[&str] =
+    &["набор", "set", "подмассив", "subarray", "срез", "slice", "вХекс", "toHex", "вБазу64", "toBase64"];
+
 pub fn call(
     _interp: &mut crate::interpreter::Interpreter,
     receiver: Value,
@@ -220,7 +234,7 @@ pub fn call(
     };
     let buffer = ta.buffer.clone();
     let offset = ta.offset;
-    let length = ta.length;
+    let length = ta.live_length();
     let kind = ta.kind;
     let size = kind.element_size();
     match method {
@@ -271,13 +285,21 @@ pub fn call(
                 let src = buffer.borrow();
                 let start_byte = offset + begin * size;
                 let end_byte = start_byte + new_length * size;
-                Rc::new(RefCell::new(src[start_byte..end_byte].to_vec()))
+                SharedBuffer::new(src[start_byte..end_byte].to_vec())
             };
             Ok((
                 Value::TypedArray(Rc::new(TypedArrayData { buffer: new_buffer, offset: 0, length: new_length, kind })),
                 None,
             ))
         }
+        "вХекс" | "toHex" | "вБазу64" | "toBase64" => {
+            let bytes = super::encoding::bytes_of(&receiver).unwrap_or_default();
+            let text = match method {
+                "вХекс" | "toHex" => super::encoding::to_hex(&bytes),
+                _ => super::encoding::to_base64(&bytes),
+            };
+            Ok((Value::String(text.into()), None))
+        }
         _ => Err(RuntimeError::new(format!("У типизированного массива нет метода '{method}'"), span)),
     }
 }
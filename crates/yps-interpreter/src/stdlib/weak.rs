@@ -96,6 +96,9 @@ fn prune_set(store: &WeakSetStore) {
     store.borrow_mut().retain(|_, key| key.is_alive());
 }
 
+/// Methods of `СлабаяКарта`.
+pub const WEAK_MAP_METHODS: &[&str] = &["set", "поставить", "get", "взять", "has", "имеет", "delete", "удалить"];
+
 pub fn call_weak_map(receiver: Value, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     let store = match receiver {
         Value::WeakMap(store) => store,
@@ -131,6 +134,9 @@ pub fn call_weak_map(receiver: Value, method: &str, args: Vec<Value>, span: Span
     }
 }
 
+/// Methods of `СлабыйНабор`.
+pub const WEAK_SET_METHODS: &[&str] = &["add", "добавить", "has", "имеет", "delete", "удалить"];
+
 pub fn call_weak_set(receiver: Value, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     let store = match receiver {
         Value::WeakSet(store) => store,
@@ -159,6 +165,9 @@ pub fn call_weak_set(receiver: Value, method: &str, args: Vec<Value>, span: Span
     }
 }
 
+/// Methods of `СлабаяСсылка`.
+pub const WEAK_REF_METHODS: &[&str] = &["deref", "разыменовать"];
+
 pub fn call_weak_ref(receiver: Value, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     let key = match receiver {
         Value::WeakRef(key) => key,
@@ -173,6 +182,9 @@ pub fn call_weak_ref(receiver: Value, method: &str, args: Vec<Value>, span: Span
     }
 }
 
+/// Methods of `РеестрФинализации`.
+pub const REGISTRY_METHODS: &[&str] = &["register", "зарегистрировать", "unregister", "снять"];
+
 pub fn call_registry(receiver: Value, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
     let state = match receiver {
         Value::FinalizationRegistry(state) => state,
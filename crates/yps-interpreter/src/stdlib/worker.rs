@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use yps_lexer::Span;
+
+use crate::error::RuntimeError;
+use crate::value::{SharedBuffer, Value};
+use crate::worker::{self, Handler, Message, Runner, WorkerPort};
+
+/// Methods of `Воркер`.
+pub const WORKER_METHODS: &[&str] = &["отправить", "приСообщении", "приОшибке", "приВыходе", "завершить"];
+
+/// Methods of `родитель` inside a worker.
+pub const PARENT_METHODS: &[&str] = &["отправить", "приСообщении", "закрыть"];
+
+/// `захуярить Воркер("задача.yopta")`: the path is resolved like an import from `base`. A worker
+/// runs under the parent's eval policy. Its messages reach the parent in an order no trace
+/// records, so a recorded or replayed run refuses to start one.
+pub fn construct(
+    base: &Path,
+    args: Vec<Value>,
+    runner: Runner,
+    allow_eval: bool,
+    span: Span,
+) -> Result<Value, RuntimeError> {
+    if crate::trace::is_active() {
+        return Err(RuntimeError::new(
+            "'Воркер' недоступен при записи и воспроизведении трассы: его сообщения в трассу не попадают",
+            span,
+        ));
+    }
+    let file = match args.into_iter().next() {
+        Some(Value::String(file)) => file,
+        other => {
+            return Err(RuntimeError::new(
+                format!(
+                    "'Воркер' ожидает путь к файлу строкой, получено '{}'",
+                    other.unwrap_or(Value::Undefined).type_name()
+                ),
+                span,
+            ));
+        }
+    };
+    let path = crate::interpreter::resolve_import(base, &file)
+        .map_err(|e| RuntimeError::new(format!("Не удалось найти файл воркера '{file}': {e}"), span))?;
+    let port = worker::spawn(path, runner, allow_eval).map_err(|message| RuntimeError::new(message, span))?;
+    Ok(Value::Worker(port))
+}
+
+pub fn call(receiver: &WorkerPort, method: &str, args: Vec<Value>, span: Span) -> Result<Value, RuntimeError> {
+    let name = if receiver.is_worker() { "Воркер" } else { "родитель" };
+    let known = if receiver.is_worker() { WORKER_METHODS } else { PARENT_METHODS };
+    if !known.contains(&method) {
+        return Err(RuntimeError::new(format!("У '{name}' нет метода '{method}'"), span));
+    }
+    let mut it = args.into_iter();
+    match method {
+        "отправить" => {
+            let value = it.next().unwrap_or(Value::Undefined);
+            let transfer = transfer_list(it.next(), span)?;
+            let message =
+                Message::new(&value, &transfer).map_err(|m| RuntimeError::new(format!("'отправить': {m}"), span))?;
+            receiver.post(message);
+        }
+        "приСообщении" | "приОшибке" | "приВыходе" => {
+            let handler = it.next().unwrap_or(Value::Undefined);
+            if !handler.is_callable() {
+                return Err(RuntimeError::new(
+                    format!("'{method}' ожидает функцию, получено '{}'", handler.type_name()),
+                    span,
+                ));
+            }
+            let slot = match method {
+                "приСообщении" => Handler::Message,
+                "приОшибке" => Handler::Error,
+                _ => Handler::Exit,
+            };
+            receiver.set_handler(slot, handler);
+        }
+        "завершить" => receiver.terminate(),
+        _ => receiver.close(),
+    }
+    Ok(Value::Undefined)
+}
+
+fn transfer_list(value: Option<Value>, span: Span) -> Result<Vec<SharedBuffer>, RuntimeError> {
+    let items = match value {
+        None | Some(Value::Undefined) => return Ok(Vec::new()),
+        Some(Value::Array(items)) => items.borrow().0.clone(),
+        Some(other) => {
+            return Err(RuntimeError::new(
+                format!("'отправить': список передачи должен быть массивом, получено '{}'", other.type_name()),
+                span,
+            ));
+        }
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::ArrayBuffer(buffer) if buffer.is_shared() => {
+                Err(RuntimeError::new("'отправить': 'ОбщийБуфер' нельзя передать, он и так общий", span))
+            }
+            Value::ArrayBuffer(buffer) => Ok(buffer),
+            other => Err(RuntimeError::new(
+                format!("'отправить': передать можно только 'ОбластьБайтов', получено '{}'", other.type_name()),
+                span,
+            )),
+        })
+        .collect()
+}
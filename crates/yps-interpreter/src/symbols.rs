@@ -2,6 +2,11 @@ pub const CLASS_TAG: &str = "__class__";
 pub const SUPER: &str = "__super__";
 pub const THIS: &str = "тырыпыры";
 pub const PROTO: &str = "__proto__";
+pub const NEW_TARGET: &str = "__new_target__";
+pub const IMPORT_META: &str = "__import_meta__";
+pub const ARGUMENTS: &str = "аргументы";
+pub const GLOBAL_THIS: &str = "глобалЭтот";
+pub const PARENT_PORT: &str = "родитель";
 
 pub const ERROR_NAME: &str = "Косяк";
 pub const ERROR_NAME_FIELD: &str = "name";
@@ -0,0 +1,417 @@
+//! Record/replay of nondeterministic inputs (`yps --record` / `yps --replay`).
+//!
+//! Every source of nondeterminism the runtime sees — wall clock, `Матан.рандом`, stdin, `ФС.*`
+//! and `Сеть.*` results, and the order in which ready macrotasks are popped — goes through this
+//! module. While recording, each live value is appended to the trace; while replaying, the next
+//! event of the same kind is returned instead. The state is thread-local rather than per
+//! `Interpreter` because the VM reaches the stdlib through a fresh interpreter per bridged call.
+//!
+//! Once a replayed trace is exhausted the run continues live and keeps recording, so a trace
+//! taken from a replay is always complete. A mismatch between the expected and the actual event
+//! kind, or a recorded event that cannot be used, is remembered as a divergence and the live
+//! value is used from then on.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
+
+use yps_lexer::Span;
+
+use crate::error::RuntimeError;
+use crate::stdlib::json;
+use crate::value::Value;
+
+const HEADER: &str = "ypsr 1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Time,
+    Random,
+    Stdin,
+    Fs,
+    Network,
+    Task,
+}
+
+impl EventKind {
+    const ALL: [Self; 6] = [Self::Time, Self::Random, Self::Stdin, Self::Fs, Self::Network, Self::Task];
+
+    #[must_use]
+    pub const fn tag(self) -> &'static str {
+        match self {
+            Self::Time => "время",
+            Self::Random => "рандом",
+            Self::Stdin => "ввод",
+            Self::Fs => "фс",
+            Self::Network => "сеть",
+            Self::Task => "задача",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.tag() == tag)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub payload: String,
+}
+
+/// A recorded run: one event per line after a version header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub events: Vec<Event>,
+}
+
+impl Trace {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        match lines.next() {
+            Some(HEADER) => {}
+            _ => return Err(format!("Это не трасса yps: ожидался заголовок '{HEADER}'")),
+        }
+        let mut events = Vec::new();
+        for (index, line) in lines.enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let (tag, payload) = line.split_once(' ').unwrap_or((line, ""));
+            let kind =
+                EventKind::from_tag(tag).ok_or_else(|| format!("Строка {}: неизвестное событие '{tag}'", index + 2))?;
+            events.push(Event { kind, payload: payload.to_string() });
+        }
+        Ok(Self { events })
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for event in &self.events {
+            writeln!(f, "{} {}", event.kind.tag(), event.payload)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+enum Mode {
+    #[default]
+    Off,
+    Record,
+    Replay {
+        cursor: usize,
+    },
+}
+
+#[derive(Default)]
+struct State {
+    mode: Mode,
+    trace: Trace,
+    divergence: Option<String>,
+    save_to: Option<PathBuf>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+pub fn start_recording() {
+    STATE.with(|s| *s.borrow_mut() = State { mode: Mode::Record, ..State::default() });
+}
+
+pub fn start_replay(trace: Trace) {
+    STATE.with(|s| *s.borrow_mut() = State { mode: Mode::Replay { cursor: 0 }, trace, ..State::default() });
+}
+
+/// Swaps in a longer trace to replay from the current position on, e.g. one that a live run has
+/// recorded further since this run started from an older copy of it.
+pub fn extend_replay(trace: Trace) {
+    STATE.with(|s| {
+        let mut state = s.borrow_mut();
+        let cursor = match state.mode {
+            Mode::Replay { cursor } => cursor,
+            Mode::Record => state.trace.events.len(),
+            Mode::Off => return,
+        };
+        if state.divergence.is_none() && trace.events.len() >= cursor {
+            state.trace = trace;
+            state.mode = Mode::Replay { cursor };
+        }
+    });
+}
+
+/// File that `save` writes the trace to; kept here so that `Процесс.выход` can flush it too.
+pub fn set_save_path(path: PathBuf) {
+    STATE.with(|s| s.borrow_mut().save_to = Some(path));
+}
+
+pub fn save() -> std::io::Result<()> {
+    let (path, text) = STATE.with(|s| {
+        let state = s.borrow();
+        (state.save_to.clone(), state.trace.to_string())
+    });
+    match path {
+        Some(path) => std::fs::write(path, text),
+        None => Ok(()),
+    }
+}
+
+/// Stops recording or replaying and hands back the full trace.
+pub fn finish() -> Trace {
+    STATE.with(|s| std::mem::take(&mut *s.borrow_mut()).trace)
+}
+
+/// Copy of everything recorded so far, without stopping.
+#[must_use]
+pub fn snapshot() -> Trace {
+    STATE.with(|s| s.borrow().trace.clone())
+}
+
+#[must_use]
+pub fn is_active() -> bool {
+    STATE.with(|s| !matches!(s.borrow().mode, Mode::Off))
+}
+
+#[must_use]
+pub fn is_replaying() -> bool {
+    STATE.with(|s| matches!(s.borrow().mode, Mode::Replay { .. }))
+}
+
+/// First place where the replayed run stopped matching the trace, if any.
+#[must_use]
+pub fn divergence() -> Option<String> {
+    STATE.with(|s| s.borrow().divergence.clone())
+}
+
+/// Payload of the next replayed event of `kind`, or `None` when the run is live.
+fn next_replayed(kind: EventKind) -> Option<String> {
+    STATE.with(|s| {
+        let mut state = s.borrow_mut();
+        let State { mode, trace, divergence, .. } = &mut *state;
+        let Mode::Replay { cursor } = mode else { return None };
+        match trace.events.get(*cursor) {
+            Some(event) if event.kind == kind => {
+                *cursor += 1;
+                Some(event.payload.clone())
+            }
+            Some(event) => {
+                let why = format!("в трассе '{}', а программа запросила '{}'", event.kind.tag(), kind.tag());
+                let index = *cursor;
+                go_live(mode, trace, divergence, index, why);
+                None
+            }
+            None => {
+                *mode = Mode::Record;
+                None
+            }
+        }
+    })
+}
+
+/// The event just replayed could not be used (`why` says why); like a kind mismatch, this is
+/// remembered as a divergence and the run records live events from that one on.
+pub fn reject_replayed(why: String) {
+    STATE.with(|s| {
+        let mut state = s.borrow_mut();
+        let State { mode, trace, divergence, .. } = &mut *state;
+        let Mode::Replay { cursor } = mode else { return };
+        let index = cursor.saturating_sub(1);
+        go_live(mode, trace, divergence, index, why);
+    });
+}
+
+fn go_live(mode: &mut Mode, trace: &mut Trace, divergence: &mut Option<String>, index: usize, why: String) {
+    if divergence.is_none() {
+        *divergence = Some(format!("событие {}: {why}", index + 1));
+    }
+    trace.events.truncate(index);
+    *mode = Mode::Record;
+}
+
+fn push(kind: EventKind, payload: String) {
+    STATE.with(|s| {
+        let mut state = s.borrow_mut();
+        if matches!(state.mode, Mode::Record) {
+            state.trace.events.push(Event { kind, payload });
+        }
+    });
+}
+
+pub(crate) fn number(kind: EventKind, live: impl FnOnce() -> f64) -> f64 {
+    if let Some(payload) = next_replayed(kind) {
+        match payload.parse() {
+            Ok(n) => return n,
+            Err(_) => reject_replayed(format!("'{payload}' не число")),
+        }
+    }
+    let n = live();
+    if is_active() {
+        push(kind, n.to_string());
+    }
+    n
+}
+
+/// Sequence number of the macrotask the recorded run popped next, if replaying.
+#[must_use]
+pub fn replayed_task() -> Option<u64> {
+    let payload = next_replayed(EventKind::Task)?;
+    let seq = payload.parse().ok();
+    if seq.is_none() {
+        reject_replayed(format!("'{payload}' не номер задачи"));
+    }
+    seq
+}
+
+pub fn record_task(seq: u64) {
+    push(EventKind::Task, seq.to_string());
+}
+
+/// Host call whose result (value or error message) is stored as JSON.
+pub(crate) fn result(
+    kind: EventKind,
+    span: Span,
+    live: impl FnOnce() -> Result<Value, RuntimeError>,
+) -> Result<Value, RuntimeError> {
+    if let Some(payload) = next_replayed(kind) {
+        return decode_result(&payload, span);
+    }
+    let result = live();
+    if is_active() {
+        push(kind, encode_result(&result, span));
+    }
+    result
+}
+
+fn encode_result(result: &Result<Value, RuntimeError>, span: Span) -> String {
+    match result {
+        Ok(value) => match json::stringify(value, span) {
+            Ok(Value::String(s)) => format!("ок {s}"),
+            _ => "ок null".to_string(),
+        },
+        Err(err) => {
+            let mut out = String::from("ошибка ");
+            json::write_json_string(&mut out, &err.message);
+            out
+        }
+    }
+}
+
+fn decode_result(payload: &str, span: Span) -> Result<Value, RuntimeError> {
+    if let Some(rest) = payload.strip_prefix("ок ") {
+        return json::parse_str(rest, span);
+    }
+    if let Some(rest) = payload.strip_prefix("ошибка ") {
+        let message = match json::parse_str(rest, span)? {
+            Value::String(s) => s.to_string(),
+            other => other.to_string(),
+        };
+        return Err(RuntimeError::new(message, span));
+    }
+    Err(RuntimeError::new(format!("Повреждённая запись в трассе: '{payload}'"), span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_SPAN: Span = Span { start: 0, end: 0 };
+
+    #[test]
+    fn trace_round_trips_through_text() {
+        let trace = Trace {
+            events: vec![
+                Event { kind: EventKind::Time, payload: "1700000000000".into() },
+                Event { kind: EventKind::Fs, payload: "ок \"строка\\nвторая\"".into() },
+            ],
+        };
+        assert_eq!(Trace::parse(&trace.to_string()).unwrap(), trace);
+    }
+
+    #[test]
+    fn parse_rejects_missing_header() {
+        assert!(Trace::parse("время 1\n").is_err());
+    }
+
+    #[test]
+    fn replay_returns_recorded_values() {
+        start_recording();
+        let a = number(EventKind::Random, || 0.25);
+        let r = result(EventKind::Stdin, NO_SPAN, || Ok(Value::String("ввод".into())));
+        let trace = finish();
+        assert_eq!(trace.events.len(), 2);
+
+        start_replay(trace);
+        assert_eq!(number(EventKind::Random, || 0.75), a);
+        assert_eq!(result(EventKind::Stdin, NO_SPAN, || Ok(Value::Null)).unwrap(), r.unwrap());
+        assert!(divergence().is_none());
+        finish();
+    }
+
+    #[test]
+    fn replay_reproduces_errors() {
+        start_recording();
+        let _ = result(EventKind::Fs, NO_SPAN, || Err(RuntimeError::new("нет файла", NO_SPAN)));
+        let trace = finish();
+        start_replay(trace);
+        let err = result(EventKind::Fs, NO_SPAN, || Ok(Value::Null)).unwrap_err();
+        assert_eq!(err.message, "нет файла");
+        finish();
+    }
+
+    #[test]
+    fn exhausted_replay_keeps_recording() {
+        start_replay(Trace { events: vec![Event { kind: EventKind::Time, payload: "5".into() }] });
+        assert_eq!(number(EventKind::Time, || 1.0), 5.0);
+        assert_eq!(number(EventKind::Time, || 7.0), 7.0);
+        assert_eq!(finish().events.len(), 2);
+    }
+
+    #[test]
+    fn unusable_events_are_reported_as_divergence() {
+        start_replay(Trace {
+            events: vec![
+                Event { kind: EventKind::Random, payload: "не число".into() },
+                Event { kind: EventKind::Random, payload: "0.1".into() },
+            ],
+        });
+        assert_eq!(number(EventKind::Random, || 0.5), 0.5);
+        assert_eq!(divergence().as_deref(), Some("событие 1: 'не число' не число"));
+        assert_eq!(number(EventKind::Random, || 0.75), 0.75);
+        assert_eq!(
+            finish().events,
+            vec![
+                Event { kind: EventKind::Random, payload: "0.5".into() },
+                Event { kind: EventKind::Random, payload: "0.75".into() },
+            ]
+        );
+
+        start_replay(Trace { events: vec![Event { kind: EventKind::Task, payload: "7".into() }] });
+        assert_eq!(replayed_task(), Some(7));
+        reject_replayed("задачи 7 нет в очереди".to_string());
+        assert_eq!(divergence().as_deref(), Some("событие 1: задачи 7 нет в очереди"));
+        record_task(3);
+        assert_eq!(finish().events, vec![Event { kind: EventKind::Task, payload: "3".into() }]);
+    }
+
+    #[test]
+    fn extended_replay_continues_from_the_cursor() {
+        let time = |payload: &str| Event { kind: EventKind::Time, payload: payload.into() };
+        start_replay(Trace { events: vec![time("1")] });
+        assert_eq!(number(EventKind::Time, || 9.0), 1.0);
+        extend_replay(Trace { events: vec![time("1"), time("2")] });
+        assert_eq!(number(EventKind::Time, || 9.0), 2.0);
+        assert!(divergence().is_none());
+        finish();
+    }
+
+    #[test]
+    fn kind_mismatch_is_reported_as_divergence() {
+        start_replay(Trace { events: vec![Event { kind: EventKind::Time, payload: "5".into() }] });
+        assert_eq!(number(EventKind::Random, || 0.5), 0.5);
+        assert!(divergence().is_some());
+        finish();
+    }
+}
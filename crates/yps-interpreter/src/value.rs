@@ -27,6 +27,7 @@ impl Default for AbortState {
     }
 }
 
+use yps_lexer::Span;
 use yps_parser::ast::{Block, Expr, Param, Pattern, Stmt};
 
 use crate::environment::{EnvFrame, Environment};
@@ -49,6 +50,8 @@ pub enum IteratorState {
 #[derive(Clone)]
 pub struct GenState {
     pub name: Rc<str>,
+    /// Body of the generator function, for `Frame::decl`.
+    pub decl: Span,
     pub env: Environment,
     pub frames: Vec<GenFrame>,
     pub completed: bool,
@@ -185,7 +188,7 @@ pub struct MethodDef {
     pub env: Rc<RefCell<EnvFrame>>,
 }
 
-pub type SharedBuffer = Rc<RefCell<Vec<u8>>>;
+pub use crate::buffer::SharedBuffer;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TypedArrayKind {
@@ -541,6 +544,8 @@ pub struct FunctionData {
     pub env: Rc<RefCell<EnvFrame>>,
     pub is_generator: bool,
     pub is_async: bool,
+    /// Arrow functions have no `аргументы` of their own.
+    pub is_arrow: bool,
 }
 
 pub struct RegExpData {
@@ -566,6 +571,14 @@ pub struct TypedArrayData {
     pub kind: TypedArrayKind,
 }
 
+impl TypedArrayData {
+    /// The elements its buffer still holds: none once the buffer was transferred to a `Воркер`.
+    pub fn live_length(&self) -> usize {
+        let end = self.offset + self.length * self.kind.element_size();
+        if end <= self.buffer.borrow().len() { self.length } else { 0 }
+    }
+}
+
 #[derive(Clone)]
 pub enum Value {
     Number(f64),
@@ -605,6 +618,10 @@ pub enum Value {
     WeakSet(WeakSetStore),
     WeakRef(Rc<WeakKey>),
     FinalizationRegistry(Rc<RefCell<FinRegState>>),
+    Worker(Rc<crate::worker::WorkerPort>),
+    TextCodec(Rc<crate::stdlib::encoding::TextCodec>),
+    Intl(Rc<crate::stdlib::intl::Formatter>),
+    Temporal(Rc<crate::stdlib::temporal::Temporal>),
     Undefined,
     Null,
 }
@@ -741,7 +758,11 @@ impl Value {
             | Value::WeakMap(_)
             | Value::WeakSet(_)
             | Value::WeakRef(_)
-            | Value::FinalizationRegistry(_) => "объект",
+            | Value::FinalizationRegistry(_)
+            | Value::Worker(_)
+            | Value::TextCodec(_)
+            | Value::Intl(_)
+            | Value::Temporal(_) => "объект",
             Value::Symbol { .. } => "символ",
             Value::AbortController { .. } => "контроллёрОтмены",
             Value::AbortSignal { .. } => "сигналОтмены",
@@ -794,6 +815,7 @@ impl Value {
             Value::Iterator(_) => "итератор",
             Value::RegExp(_) => "регэксп",
             Value::Date(_) => "дата",
+            Value::ArrayBuffer(buf) if buf.is_shared() => "ОбщийБуфер",
             Value::ArrayBuffer(_) => "ОбластьБайтов",
             Value::TypedArray(ta) => ta.kind.type_name(),
             Value::DataView { .. } => "ОбзорБайтов",
@@ -808,6 +830,16 @@ impl Value {
             Value::WeakSet(_) => "слабыйНабор",
             Value::WeakRef(_) => "слабаяСсылка",
             Value::FinalizationRegistry(_) => "реестрФинализации",
+            Value::Worker(port) => {
+                if port.is_worker() {
+                    "воркер"
+                } else {
+                    "портРодителя"
+                }
+            }
+            Value::TextCodec(codec) => codec.type_name(),
+            Value::Intl(formatter) => formatter.type_name(),
+            Value::Temporal(t) => t.type_name(),
             Value::Undefined => "неопределено",
             Value::Null => "нулл",
         }
@@ -881,6 +913,7 @@ impl fmt::Debug for Value {
             Value::AbortRejectPromise { reason_from_signal, .. } => {
                 write!(f, "AbortRejectPromise(from_signal={reason_from_signal})")
             }
+            Value::ArrayBuffer(buf) if buf.is_shared() => write!(f, "SharedArrayBuffer({})", buf.borrow().len()),
             Value::ArrayBuffer(buf) => write!(f, "ArrayBuffer({})", buf.borrow().len()),
             Value::TypedArray(ta) => {
                 write!(f, "TypedArray({}, offset={}, length={})", ta.kind.type_name(), ta.offset, ta.length)
@@ -895,6 +928,10 @@ impl fmt::Debug for Value {
             Value::FinalizationRegistry(state) => {
                 write!(f, "FinalizationRegistry(entries={})", state.borrow().entries.len())
             }
+            Value::Worker(_) => write!(f, "Worker({})", self.type_name()),
+            Value::TextCodec(codec) => write!(f, "TextCodec({})", codec.encoding.label()),
+            Value::Intl(formatter) => write!(f, "Intl({}, {})", formatter.type_name(), formatter.locale().tag()),
+            Value::Temporal(t) => write!(f, "Temporal({t})"),
             Value::Undefined => write!(f, "Undefined"),
             Value::Null => write!(f, "Null"),
         }
@@ -1052,13 +1089,14 @@ impl Value {
                     write!(f, "[сигналОтмены активен]")
                 }
             }
-            Value::ArrayBuffer(buf) => write!(f, "ОбластьБайтов({})", buf.borrow().len()),
+            Value::ArrayBuffer(buf) => write!(f, "{}({})", self.type_name(), buf.borrow().len()),
             Value::TypedArray(ta) => {
-                let TypedArrayData { buffer, offset, length, kind } = &**ta;
+                let TypedArrayData { buffer, offset, kind, .. } = &**ta;
+                let length = ta.live_length();
                 write!(f, "{}[", kind.type_name())?;
                 let bytes = buffer.borrow();
                 let size = kind.element_size();
-                for i in 0..*length {
+                for i in 0..length {
                     if i > 0 {
                         write!(f, ", ")?;
                     }
@@ -1081,6 +1119,8 @@ impl Value {
             Value::WeakSet(_) => write!(f, "[слабыйНабор]"),
             Value::WeakRef(_) => write!(f, "[слабаяСсылка]"),
             Value::FinalizationRegistry(_) => write!(f, "[реестрФинализации]"),
+            Value::Worker(_) | Value::TextCodec(_) | Value::Intl(_) => write!(f, "[{}]", self.type_name()),
+            Value::Temporal(t) => write!(f, "{t}"),
         }
     }
 }
@@ -1208,21 +1248,21 @@ impl Hash for MapKey {
                 13u8.hash(state);
                 (Rc::as_ptr(rc) as *const () as usize).hash(state);
             }
-            Value::ArrayBuffer(rc) => {
+            Value::ArrayBuffer(buffer) => {
                 14u8.hash(state);
-                hash_rc_ptr(rc, state);
+                buffer.id().hash(state);
             }
             Value::TypedArray(ta) => {
                 let TypedArrayData { buffer, offset, length, kind } = &**ta;
                 15u8.hash(state);
-                hash_rc_ptr(buffer, state);
+                buffer.id().hash(state);
                 offset.hash(state);
                 length.hash(state);
                 (*kind as u8).hash(state);
             }
             Value::DataView { buffer, offset, length } => {
                 16u8.hash(state);
-                hash_rc_ptr(buffer, state);
+                buffer.id().hash(state);
                 offset.hash(state);
                 length.hash(state);
             }
@@ -1264,6 +1304,22 @@ impl Hash for MapKey {
                 30u8.hash(state);
                 hash_rc_ptr(rc, state);
             }
+            Value::Worker(rc) => {
+                31u8.hash(state);
+                hash_rc_ptr(rc, state);
+            }
+            Value::TextCodec(rc) => {
+                32u8.hash(state);
+                hash_rc_ptr(rc, state);
+            }
+            Value::Intl(rc) => {
+                33u8.hash(state);
+                hash_rc_ptr(rc, state);
+            }
+            Value::Temporal(rc) => {
+                34u8.hash(state);
+                hash_rc_ptr(rc, state);
+            }
             Value::Undefined => 22u8.hash(state),
             Value::Null => 23u8.hash(state),
             Value::Function(func) => {
@@ -1306,12 +1362,12 @@ impl PartialEq for Value {
             (Value::AbortUnsubscribe { state: a, token: ta }, Value::AbortUnsubscribe { state: b, token: tb }) => {
                 Rc::ptr_eq(a, b) && ta == tb
             }
-            (Value::ArrayBuffer(a), Value::ArrayBuffer(b)) => Rc::ptr_eq(a, b),
+            (Value::ArrayBuffer(a), Value::ArrayBuffer(b)) => a.ptr_eq(b),
             (Value::TypedArray(a), Value::TypedArray(b)) => Rc::ptr_eq(a, b),
             (
                 Value::DataView { buffer: ba, offset: oa, length: la },
                 Value::DataView { buffer: bb, offset: ob, length: lb },
-            ) => Rc::ptr_eq(ba, bb) && oa == ob && la == lb,
+            ) => ba.ptr_eq(bb) && oa == ob && la == lb,
             (Value::Proxy { target: ta, handler: ha }, Value::Proxy { target: tb, handler: hb }) => {
                 Rc::ptr_eq(ta, tb) && Rc::ptr_eq(ha, hb)
             }
@@ -1322,6 +1378,10 @@ impl PartialEq for Value {
             (Value::WeakSet(a), Value::WeakSet(b)) => Rc::ptr_eq(a, b),
             (Value::WeakRef(a), Value::WeakRef(b)) => Rc::ptr_eq(a, b),
             (Value::FinalizationRegistry(a), Value::FinalizationRegistry(b)) => Rc::ptr_eq(a, b),
+            (Value::Worker(a), Value::Worker(b)) => Rc::ptr_eq(a, b),
+            (Value::TextCodec(a), Value::TextCodec(b)) => Rc::ptr_eq(a, b),
+            (Value::Intl(a), Value::Intl(b)) => Rc::ptr_eq(a, b),
+            (Value::Temporal(a), Value::Temporal(b)) => Rc::ptr_eq(a, b),
             (Value::Undefined, Value::Undefined) => true,
             (Value::Null, Value::Null) => true,
             _ => false,
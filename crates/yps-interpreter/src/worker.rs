@@ -0,0 +1,626 @@
+//! Worker threads. Every thread that runs a program may own a hub: the inbox its ports' events
+//! arrive in and the ports themselves. Values cross threads only as a [`Message`], the structured
+//! clone of a [`Value`]; both runtimes turn hub events into macrotasks via [`dispatch`].
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use indexmap::{IndexMap, IndexSet};
+use yps_lexer::Span;
+use yps_parser::ast::Program;
+
+use crate::buffer::SharedMemory;
+use crate::symbols;
+use crate::value::{MapKey, RegExpData, SharedBuffer, TypedArrayData, TypedArrayKind, Value};
+
+/// The id `родитель` has in a worker's own hub; ports a hub hands out start at 1.
+const PARENT_PORT: u64 = 0;
+
+/// The error a run stops with once `завершить` raised its interrupt flag.
+pub const WORKER_TERMINATED: &str = "Воркер завершён";
+
+/// Runs the file a worker was started with, on the worker's thread, until its event loop drains.
+/// The `bool` is the parent's eval policy, which the worker's runtime keeps.
+pub type Runner = fn(&Path, Arc<AtomicBool>, bool) -> Result<(), String>;
+
+enum Cloned {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    BigInt(i128),
+    String(String),
+    Array(Vec<Cloned>),
+    Object(Vec<(String, Cloned)>),
+    Map(Vec<(Cloned, Cloned)>),
+    Set(Vec<Cloned>),
+    Date(f64),
+    RegExp {
+        pattern: String,
+        flags: String,
+    },
+    /// Index into [`Message::buffers`].
+    ArrayBuffer(usize),
+    /// An `ОбщийБуфер`: both threads keep the same bytes.
+    SharedMemory(Arc<SharedMemory>),
+    TypedArray {
+        buffer: Box<Cloned>,
+        offset: usize,
+        length: usize,
+        kind: TypedArrayKind,
+    },
+    DataView {
+        buffer: Box<Cloned>,
+        offset: usize,
+        length: usize,
+    },
+    /// A value met earlier in the same message, numbered in the order values were first met.
+    Seen(usize),
+}
+
+/// A structured clone of one value: plain data that can move to another thread, with shared and
+/// cyclic references kept.
+pub struct Message {
+    root: Cloned,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl Message {
+    /// Clones `value`. The bytes of every buffer in `transfer` move into the message instead of
+    /// being copied, and the buffer is left empty.
+    pub fn new(value: &Value, transfer: &[SharedBuffer]) -> Result<Message, String> {
+        let mut cloner = Cloner { seen: HashMap::new(), next: 0, buffers: Vec::new(), transfer, taken: Vec::new() };
+        match cloner.clone_value(value) {
+            Ok(root) => Ok(Message { root, buffers: cloner.buffers }),
+            Err(message) => {
+                for (buffer, slot) in cloner.taken {
+                    *buffer.borrow_mut() = std::mem::take(&mut cloner.buffers[slot]);
+                }
+                Err(message)
+            }
+        }
+    }
+
+    pub fn into_value(self) -> Value {
+        let mut restorer = Restorer { made: Vec::new(), buffers: self.buffers };
+        restorer.restore(self.root)
+    }
+}
+
+struct Cloner<'a> {
+    seen: HashMap<usize, usize>,
+    next: usize,
+    buffers: Vec<Vec<u8>>,
+    transfer: &'a [SharedBuffer],
+    taken: Vec<(SharedBuffer, usize)>,
+}
+
+impl Cloner<'_> {
+    /// `Err` with the earlier number if `ptr` was already cloned; otherwise numbers it.
+    fn visit(&mut self, ptr: usize) -> Result<(), usize> {
+        if let Some(&index) = self.seen.get(&ptr) {
+            return Err(index);
+        }
+        self.seen.insert(ptr, self.next);
+        self.next += 1;
+        Ok(())
+    }
+
+    fn clone_value(&mut self, value: &Value) -> Result<Cloned, String> {
+        Ok(match value {
+            Value::Undefined => Cloned::Undefined,
+            Value::Null => Cloned::Null,
+            Value::Boolean(b) => Cloned::Bool(*b),
+            Value::Number(n) => Cloned::Number(*n),
+            Value::BigInt(n) => Cloned::BigInt(*n),
+            Value::String(s) => Cloned::String(s.to_string()),
+            Value::Array(rc) => {
+                if let Err(index) = self.visit(Rc::as_ptr(rc) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                let items = rc.borrow().0.clone();
+                Cloned::Array(items.iter().map(|item| self.clone_value(item)).collect::<Result<_, _>>()?)
+            }
+            Value::Object(rc) => {
+                if let Err(index) = self.visit(Rc::as_ptr(rc) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                let entries: Vec<(String, Value)> = rc
+                    .borrow()
+                    .map
+                    .iter()
+                    .filter(|(key, _)| !symbols::is_internal_key(key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                let mut out = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    out.push((key, self.clone_value(&value)?));
+                }
+                Cloned::Object(out)
+            }
+            Value::Map(rc) => {
+                if let Err(index) = self.visit(Rc::as_ptr(rc) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                let entries: Vec<(Value, Value)> =
+                    rc.borrow().0.iter().map(|(key, value)| (key.as_value().clone(), value.clone())).collect();
+                let mut out = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    out.push((self.clone_value(&key)?, self.clone_value(&value)?));
+                }
+                Cloned::Map(out)
+            }
+            Value::Set(rc) => {
+                if let Err(index) = self.visit(Rc::as_ptr(rc) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                let items: Vec<Value> = rc.borrow().0.iter().map(|key| key.as_value().clone()).collect();
+                Cloned::Set(items.iter().map(|item| self.clone_value(item)).collect::<Result<_, _>>()?)
+            }
+            Value::Date(cell) => {
+                if let Err(index) = self.visit(Rc::as_ptr(cell) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                Cloned::Date(cell.get())
+            }
+            Value::RegExp(re) => {
+                if let Err(index) = self.visit(Rc::as_ptr(re) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                Cloned::RegExp { pattern: re.pattern.clone(), flags: re.flags.clone() }
+            }
+            Value::ArrayBuffer(buffer) => self.clone_buffer(buffer),
+            Value::TypedArray(ta) => {
+                if let Err(index) = self.visit(Rc::as_ptr(ta) as usize) {
+                    return Ok(Cloned::Seen(index));
+                }
+                let buffer = Box::new(self.clone_buffer(&ta.buffer));
+                Cloned::TypedArray { buffer, offset: ta.offset, length: ta.length, kind: ta.kind }
+            }
+            Value::DataView { buffer, offset, length } => {
+                Cloned::DataView { buffer: Box::new(self.clone_buffer(buffer)), offset: *offset, length: *length }
+            }
+            other => return Err(format!("значение типа '{}' нельзя клонировать", other.type_name())),
+        })
+    }
+
+    fn clone_buffer(&mut self, buffer: &SharedBuffer) -> Cloned {
+        if let Err(index) = self.visit(buffer.id()) {
+            return Cloned::Seen(index);
+        }
+        if let SharedBuffer::Shared(memory) = buffer {
+            return Cloned::SharedMemory(Arc::clone(memory));
+        }
+        let slot = self.buffers.len();
+        if self.transfer.iter().any(|t| t.ptr_eq(buffer)) {
+            self.buffers.push(std::mem::take(&mut *buffer.borrow_mut()));
+            self.taken.push((buffer.clone(), slot));
+        } else {
+            self.buffers.push(buffer.borrow().clone());
+        }
+        Cloned::ArrayBuffer(slot)
+    }
+}
+
+struct Restorer {
+    made: Vec<Value>,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl Restorer {
+    fn restore(&mut self, cloned: Cloned) -> Value {
+        match cloned {
+            Cloned::Undefined => Value::Undefined,
+            Cloned::Null => Value::Null,
+            Cloned::Bool(b) => Value::Boolean(b),
+            Cloned::Number(n) => Value::Number(n),
+            Cloned::BigInt(n) => Value::BigInt(n),
+            Cloned::String(s) => Value::String(s.into()),
+            Cloned::Array(items) => {
+                let array = Value::array(Vec::with_capacity(items.len()));
+                self.made.push(array.clone());
+                for item in items {
+                    let value = self.restore(item);
+                    if let Value::Array(rc) = &array {
+                        rc.borrow_mut().push(value);
+                    }
+                }
+                array
+            }
+            Cloned::Object(entries) => {
+                let object = Value::object(IndexMap::new());
+                self.made.push(object.clone());
+                for (key, item) in entries {
+                    let value = self.restore(item);
+                    if let Value::Object(rc) = &object {
+                        rc.borrow_mut().map.insert(key, value);
+                    }
+                }
+                object
+            }
+            Cloned::Map(entries) => {
+                let map = Value::map(IndexMap::new());
+                self.made.push(map.clone());
+                for (key, item) in entries {
+                    let key = MapKey::new(self.restore(key));
+                    let value = self.restore(item);
+                    if let Value::Map(rc) = &map {
+                        rc.borrow_mut().0.insert(key, value);
+                    }
+                }
+                map
+            }
+            Cloned::Set(items) => {
+                let set = Value::set(IndexSet::new());
+                self.made.push(set.clone());
+                for item in items {
+                    let key = MapKey::new(self.restore(item));
+                    if let Value::Set(rc) = &set {
+                        rc.borrow_mut().0.insert(key);
+                    }
+                }
+                set
+            }
+            Cloned::Date(time) => self.remember(Value::Date(Rc::new(Cell::new(time)))),
+            Cloned::RegExp { pattern, flags } => {
+                let compiled = crate::stdlib::regexp::compile(&pattern, &flags, Span { start: 0, end: 0 })
+                    .expect("a regexp that compiled on the sending side compiles again");
+                let last_index = Rc::new(RefCell::new(0));
+                self.remember(Value::RegExp(Rc::new(RegExpData { pattern, flags, compiled, last_index })))
+            }
+            Cloned::ArrayBuffer(slot) => {
+                let bytes = std::mem::take(&mut self.buffers[slot]);
+                self.remember(Value::ArrayBuffer(SharedBuffer::new(bytes)))
+            }
+            Cloned::SharedMemory(memory) => self.remember(Value::ArrayBuffer(SharedBuffer::Shared(memory))),
+            Cloned::TypedArray { buffer, offset, length, kind } => {
+                let index = self.made.len();
+                self.made.push(Value::Undefined);
+                let Value::ArrayBuffer(buffer) = self.restore(*buffer) else {
+                    unreachable!("a typed array is cloned together with its buffer")
+                };
+                let view = Value::TypedArray(Rc::new(TypedArrayData { buffer, offset, length, kind }));
+                self.made[index] = view.clone();
+                view
+            }
+            Cloned::DataView { buffer, offset, length } => {
+                let Value::ArrayBuffer(buffer) = self.restore(*buffer) else {
+                    unreachable!("a data view is cloned together with its buffer")
+                };
+                Value::DataView { buffer, offset, length }
+            }
+            Cloned::Seen(index) => self.made[index].clone(),
+        }
+    }
+
+    fn remember(&mut self, value: Value) -> Value {
+        self.made.push(value.clone());
+        value
+    }
+}
+
+enum Event {
+    Message(Message),
+    Error(String),
+    Exit(i32),
+    Terminate,
+}
+
+/// An event addressed to one port of the receiving thread's hub.
+pub struct Envelope {
+    port: u64,
+    event: Event,
+}
+
+/// What a runtime should do with an [`Envelope`] it received.
+pub enum Delivery {
+    /// Call a handler the script installed.
+    Call(Value, Vec<Value>),
+    /// The parent terminated this worker: drop every pending task.
+    Stop,
+    Skip,
+}
+
+#[derive(Clone, Copy)]
+pub enum Handler {
+    Message,
+    Error,
+    Exit,
+}
+
+/// One end of a worker channel: a `Воркер` in the thread that started it, `родитель` inside it.
+pub struct WorkerPort {
+    id: u64,
+    peer: Sender<Envelope>,
+    peer_port: u64,
+    /// `Some` for a `Воркер`; `завершить` raises it, and the worker checks it as it runs.
+    stop: Option<Arc<AtomicBool>>,
+    thread: RefCell<Option<JoinHandle<()>>>,
+    on_message: RefCell<Value>,
+    on_error: RefCell<Value>,
+    on_exit: RefCell<Value>,
+    closed: Cell<bool>,
+}
+
+impl WorkerPort {
+    fn new(id: u64, peer: Sender<Envelope>, peer_port: u64, stop: Option<Arc<AtomicBool>>) -> Self {
+        WorkerPort {
+            id,
+            peer,
+            peer_port,
+            stop,
+            thread: RefCell::new(None),
+            on_message: RefCell::new(Value::Undefined),
+            on_error: RefCell::new(Value::Undefined),
+            on_exit: RefCell::new(Value::Undefined),
+            closed: Cell::new(false),
+        }
+    }
+
+    /// Whether this is the `Воркер` handle rather than a worker's `родитель`.
+    pub fn is_worker(&self) -> bool {
+        self.stop.is_some()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    /// A running worker keeps its parent's event loop alive; `родитель` keeps the worker's alive
+    /// while it listens for messages.
+    fn keeps_alive(&self) -> bool {
+        !self.closed.get() && (self.is_worker() || self.on_message.borrow().is_callable())
+    }
+
+    pub fn post(&self, message: Message) {
+        if !self.closed.get() {
+            let _ = self.peer.send(Envelope { port: self.peer_port, event: Event::Message(message) });
+        }
+    }
+
+    pub fn set_handler(&self, handler: Handler, value: Value) {
+        *self.slot(handler).borrow_mut() = value;
+    }
+
+    fn slot(&self, handler: Handler) -> &RefCell<Value> {
+        match handler {
+            Handler::Message => &self.on_message,
+            Handler::Error => &self.on_error,
+            Handler::Exit => &self.on_exit,
+        }
+    }
+
+    pub fn handlers(&self) -> [Value; 3] {
+        [self.on_message.borrow().clone(), self.on_error.borrow().clone(), self.on_exit.borrow().clone()]
+    }
+
+    /// `завершить`: the worker stops at its next statement, or at once if it is waiting for events.
+    /// The port stays live until the worker's exit arrives.
+    pub fn terminate(&self) {
+        if let Some(stop) = &self.stop {
+            stop.store(true, Ordering::SeqCst);
+            let _ = self.peer.send(Envelope { port: PARENT_PORT, event: Event::Terminate });
+        }
+    }
+
+    /// `родитель.закрыть`: stop listening, so the worker ends once its own tasks run out.
+    pub fn close(&self) {
+        self.closed.set(true);
+    }
+}
+
+impl Drop for WorkerPort {
+    /// A worker never outlives the thread that started it.
+    fn drop(&mut self) {
+        if !self.closed.get() {
+            self.terminate();
+        }
+    }
+}
+
+struct Hub {
+    inbox: Sender<Envelope>,
+    receiver: Receiver<Envelope>,
+    ports: HashMap<u64, Rc<WorkerPort>>,
+    next_id: u64,
+}
+
+impl Hub {
+    fn new() -> Self {
+        let (inbox, receiver) = mpsc::channel();
+        Hub { inbox, receiver, ports: HashMap::new(), next_id: PARENT_PORT + 1 }
+    }
+
+    fn has_live_ports(&self) -> bool {
+        self.ports.values().any(|port| port.keeps_alive())
+    }
+}
+
+thread_local! {
+    static HUB: RefCell<Option<Hub>> = const { RefCell::new(None) };
+    /// The flag `завершить` raises for the worker running on this thread.
+    static STOP: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Whether the worker on this thread was told to stop; blocking calls give up when it was.
+pub fn stop_requested() -> bool {
+    STOP.with(|stop| stop.borrow().as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst)))
+}
+
+/// Starts `path` on a new thread with `runner` and returns the parent's end of the channel.
+pub fn spawn(path: PathBuf, runner: Runner, allow_eval: bool) -> Result<Rc<WorkerPort>, String> {
+    let (id, to_parent) = HUB.with(|hub| {
+        let mut hub = hub.borrow_mut();
+        let hub = hub.get_or_insert_with(Hub::new);
+        let id = hub.next_id;
+        hub.next_id += 1;
+        (id, hub.inbox.clone())
+    });
+    let (to_worker, worker_inbox) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stop);
+    let own_inbox = to_worker.clone();
+    let thread = std::thread::Builder::new()
+        .name(format!("воркер-{id}"))
+        .spawn(move || {
+            let parent = Rc::new(WorkerPort::new(PARENT_PORT, to_parent.clone(), id, None));
+            HUB.with(|hub| {
+                *hub.borrow_mut() = Some(Hub {
+                    inbox: own_inbox,
+                    receiver: worker_inbox,
+                    ports: HashMap::from([(PARENT_PORT, parent)]),
+                    next_id: PARENT_PORT + 1,
+                });
+            });
+            STOP.with(|stop| *stop.borrow_mut() = Some(Arc::clone(&flag)));
+            let outcome = runner(&path, Arc::clone(&flag), allow_eval);
+            let hub = HUB.with(|hub| hub.borrow_mut().take());
+            drop(hub);
+            let code = match outcome {
+                Ok(()) if !flag.load(Ordering::SeqCst) => 0,
+                Err(message) if !flag.load(Ordering::SeqCst) => {
+                    let _ = to_parent.send(Envelope { port: id, event: Event::Error(message) });
+                    1
+                }
+                _ => 1,
+            };
+            let _ = to_parent.send(Envelope { port: id, event: Event::Exit(code) });
+        })
+        .map_err(|e| format!("Не удалось запустить воркер: {e}"))?;
+    let port = Rc::new(WorkerPort::new(id, to_worker, PARENT_PORT, Some(stop)));
+    *port.thread.borrow_mut() = Some(thread);
+    HUB.with(|hub| {
+        if let Some(hub) = hub.borrow_mut().as_mut() {
+            hub.ports.insert(id, Rc::clone(&port));
+        }
+    });
+    Ok(port)
+}
+
+/// `родитель` on a worker's thread; `None` on the main thread.
+pub fn parent_port() -> Option<Rc<WorkerPort>> {
+    HUB.with(|hub| hub.borrow().as_ref().and_then(|hub| hub.ports.get(&PARENT_PORT).cloned()))
+}
+
+/// Every port of this thread, for the garbage collector to keep their handlers alive.
+pub fn ports() -> Vec<Rc<WorkerPort>> {
+    HUB.with(|hub| hub.borrow().as_ref().map(|hub| hub.ports.values().cloned().collect()).unwrap_or_default())
+}
+
+pub fn has_live_ports() -> bool {
+    HUB.with(|hub| hub.borrow().as_ref().is_some_and(Hub::has_live_ports))
+}
+
+/// An event that already arrived, without waiting.
+pub fn poll() -> Option<Envelope> {
+    HUB.with(|hub| hub.borrow().as_ref().and_then(|hub| hub.receiver.try_recv().ok()))
+}
+
+/// Waits up to `timeout` for an event; a thread without ports just sleeps. With no timeout it
+/// waits only while some port is live, and returns `None` at once otherwise.
+pub fn wait(timeout: Option<Duration>) -> Option<Envelope> {
+    let has_hub = HUB.with(|hub| hub.borrow().is_some());
+    match timeout {
+        Some(duration) if !has_hub => {
+            std::thread::sleep(duration);
+            None
+        }
+        Some(duration) => {
+            HUB.with(|hub| hub.borrow().as_ref().and_then(|hub| hub.receiver.recv_timeout(duration).ok()))
+        }
+        None => HUB.with(|hub| {
+            let hub = hub.borrow();
+            let hub = hub.as_ref()?;
+            if hub.has_live_ports() { hub.receiver.recv().ok() } else { None }
+        }),
+    }
+}
+
+/// Applies an event to its port and says which handler, if any, the runtime should call.
+pub fn dispatch(envelope: Envelope) -> Delivery {
+    let Some(port) = HUB.with(|hub| hub.borrow().as_ref().and_then(|hub| hub.ports.get(&envelope.port).cloned()))
+    else {
+        return Delivery::Skip;
+    };
+    match envelope.event {
+        Event::Message(message) => {
+            let handler = port.on_message.borrow().clone();
+            if port.closed.get() || !handler.is_callable() {
+                return Delivery::Skip;
+            }
+            Delivery::Call(handler, vec![message.into_value()])
+        }
+        Event::Error(message) => {
+            let handler = port.on_error.borrow().clone();
+            if !handler.is_callable() {
+                eprintln!("необработанное исключение в 'Воркер': {message}");
+                return Delivery::Skip;
+            }
+            let error = crate::stdlib::error::construct(vec![Value::String(message.into())], Span { start: 0, end: 0 })
+                .unwrap_or(Value::Undefined);
+            Delivery::Call(handler, vec![error])
+        }
+        Event::Exit(code) => {
+            port.closed.set(true);
+            HUB.with(|hub| {
+                if let Some(hub) = hub.borrow_mut().as_mut() {
+                    hub.ports.remove(&port.id);
+                }
+            });
+            if let Some(thread) = port.thread.borrow_mut().take() {
+                let _ = thread.join();
+            }
+            let handler = port.on_exit.borrow().clone();
+            if handler.is_callable() {
+                Delivery::Call(handler, vec![Value::Number(code as f64)])
+            } else {
+                Delivery::Skip
+            }
+        }
+        Event::Terminate => {
+            for port in ports() {
+                port.terminate();
+                port.close();
+            }
+            Delivery::Stop
+        }
+    }
+}
+
+/// Reads and parses a worker's file, the way a module is loaded.
+pub fn parse_file(path: &Path) -> Result<Program, String> {
+    let code = std::fs::read_to_string(path)
+        .map_err(|e| format!("Не удалось прочитать файл воркера '{}': {e}", path.display()))?;
+    let source_file = yps_lexer::SourceFile::new(path.display().to_string(), code);
+    let (tokens, lex_diags) = yps_lexer::Lexer::new(&source_file).tokenize();
+    let messages =
+        |diags: &[yps_lexer::Diagnostic]| diags.iter().map(|d| d.message.as_str()).collect::<Vec<_>>().join("; ");
+    if !lex_diags.is_empty() {
+        return Err(format!("Ошибки лексера в воркере '{}': {}", path.display(), messages(&lex_diags)));
+    }
+    let (program, parse_diags) = yps_parser::Parser::new(&tokens, &source_file).parse_program();
+    if !parse_diags.is_empty() {
+        return Err(format!("Ошибки парсера в воркере '{}': {}", path.display(), messages(&parse_diags)));
+    }
+    Ok(program)
+}
+
+/// The interpreter's [`Runner`].
+pub fn run_file(path: &Path, stop: Arc<AtomicBool>, allow_eval: bool) -> Result<(), String> {
+    let program = parse_file(path)?;
+    let mut interp = crate::Interpreter::new();
+    interp.set_allow_eval(allow_eval);
+    if let Some(dir) = path.parent() {
+        interp.set_base_path(dir.to_path_buf());
+    }
+    interp.set_module_path(path.to_path_buf());
+    interp.set_interrupt(stop);
+    interp.run(&program).map_err(|e| e.message)
+}
@@ -7,6 +7,7 @@ pub(crate) enum Helper {
     Sleep,
     ReadLine,
     ReadAll,
+    Enum,
 }
 
 impl Helper {
@@ -19,6 +20,7 @@ impl Helper {
             Self::Sleep => "__ypsSleep",
             Self::ReadLine => "__ypsReadLine",
             Self::ReadAll => "__ypsReadAll",
+            Self::Enum => "__ypsEnum",
         }
     }
 
@@ -31,6 +33,7 @@ impl Helper {
             Self::Sleep => SLEEP_SRC,
             Self::ReadLine => READ_LINE_SRC,
             Self::ReadAll => READ_ALL_SRC,
+            Self::Enum => ENUM_SRC,
         }
     }
 
@@ -125,6 +128,23 @@ const READ_ALL_SRC: &str = r"function __ypsReadAll() {
   return rest;
 }";
 
+/// Строит замороженный объект `еээ`: `[имя]` берёт предыдущее число плюс один, `[имя, значение]` —
+/// вычисленный инициализатор; у числовых значений есть обратное отображение в имя.
+const ENUM_SRC: &str = r#"function __ypsEnum(members) {
+  const e = {};
+  let next = 0;
+  for (const m of members) {
+    if (m.length < 2 && next === undefined) {
+      throw new TypeError(`Элементу '${m[0]}' нужно значение: предыдущий элемент не число`);
+    }
+    const value = m.length < 2 ? next : m[1];
+    if (typeof value === "number") e[value] = m[0];
+    e[m[0]] = value;
+    next = typeof value === "number" ? value + 1 : undefined;
+  }
+  return Object.freeze(e);
+}"#;
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum Builtin {
     Plain(&'static str),
@@ -164,11 +184,23 @@ pub(crate) fn lookup(name: &str) -> Option<Builtin> {
         "сОчередить" => Builtin::Plain("queueMicrotask"),
         "прочестьСтроку" => Builtin::Helper(Helper::ReadLine),
         "прочестьВсё" => Builtin::Helper(Helper::ReadAll),
+        "глобалЭтот" => Builtin::Plain("globalThis"),
+        "аргументы" => Builtin::Plain("arguments"),
         _ => return None,
     };
     Some(mapped)
 }
 
+/// Keys of `спиздить.мета` as Node names them on `import.meta`.
+pub(crate) fn import_meta_key(name: &str) -> &str {
+    match name {
+        "путь" => "filename",
+        "каталог" => "dirname",
+        "главный" => "main",
+        other => other,
+    }
+}
+
 pub(crate) const CONSOLE_MEMBERS: &[&str] =
     &["ошибка", "предупреждение", "инфо", "отладка", "таблица", "время", "времяСтоп"];
 
@@ -206,6 +238,19 @@ const UNSUPPORTED_GLOBALS: &[&str] = &[
     "Др64Массив",
     "ОбластьБайтов",
     "ОбзорБайтов",
+    "ебал",
+    "Функция",
+    "Воркер",
+    "родитель",
+    "ОбщийБуфер",
+    "Атомики",
+    "КодировщикТекста",
+    "ДекодировщикТекста",
+    "структурныйКлон",
+    "вБазу64",
+    "изБазы64",
+    "Интл",
+    "Время",
 ];
 
 pub(crate) fn is_unsupported_global(name: &str) -> bool {
@@ -8,12 +8,13 @@ use yps_parser::{
     binary_is_right_assoc, binary_precedence,
 };
 use yps_parser::{
-    BinaryOp, Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, ObjectEntry, ObjectPatternProp,
-    Param, Pattern, PostfixOp, Program, PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi, UnaryOp,
+    BinaryOp, Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, MatchArm, MatchPattern,
+    ObjectEntry, ObjectPatternProp, Param, Pattern, PostfixOp, Program, PropKey, Stmt, SwitchCase, TemplatePart,
+    TemplateQuasi, UnaryOp,
 };
 
 use crate::TranspileError;
-use crate::builtins::{Builtin, CONSOLE_MEMBERS, Helper, STDIN_SRC, is_unsupported_global, lookup};
+use crate::builtins::{Builtin, CONSOLE_MEMBERS, Helper, STDIN_SRC, import_meta_key, is_unsupported_global, lookup};
 use crate::scope::collect_declared;
 
 const INDENT: &str = "  ";
@@ -26,6 +27,8 @@ pub fn print_program(program: &Program) -> Result<String, TranspileError> {
         helpers: BTreeSet::new(),
         error: None,
         switch_counter: 0,
+        match_counter: 0,
+        saw_await: false,
     };
     printer.print_program(program);
     if let Some(error) = printer.error {
@@ -41,6 +44,9 @@ struct Printer {
     helpers: BTreeSet<Helper>,
     error: Option<TranspileError>,
     switch_counter: usize,
+    match_counter: usize,
+    /// Set when an `await` is printed outside any nested function; see `print_match`.
+    saw_await: bool,
 }
 
 impl Printer {
@@ -122,6 +128,10 @@ impl Printer {
     }
 
     fn print_stmt_line(&mut self, stmt: &Stmt) {
+        // Type declarations have no JavaScript counterpart.
+        if matches!(stmt, Stmt::TypeAlias { .. }) {
+            return;
+        }
         self.indent();
         self.print_stmt(stmt);
         self.newline();
@@ -173,12 +183,34 @@ impl Printer {
                 self.print_expr(init, 0);
                 self.write(";");
             }
+            Stmt::EnumDecl { name, members, .. } => {
+                self.write("const ");
+                self.write(&name.name);
+                self.write(" = ");
+                let helper = self.helper(Helper::Enum);
+                self.write(helper);
+                self.write("([");
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.write("[");
+                    self.write_quoted(&member.name.name);
+                    if let Some(init) = &member.init {
+                        self.write(", ");
+                        self.print_expr(init, ASSIGN_PRECEDENCE);
+                    }
+                    self.write("]");
+                }
+                self.write("]);");
+            }
             Stmt::Expr { expr, .. } => {
                 self.wrapped(stmt_expr_needs_parens(expr), |p| p.print_expr(expr, 0));
                 self.write(";");
             }
             Stmt::Block(block) => self.print_block(block),
             Stmt::Empty { .. } => self.write(";"),
+            Stmt::TypeAlias { .. } => {}
             Stmt::If { condition, then_branch, else_branch, .. } => {
                 self.write("if (");
                 self.print_expr(condition, 0);
@@ -320,6 +352,7 @@ impl Printer {
                 self.write("}");
             }
             Stmt::Debugger { .. } => self.write("debugger;"),
+            Stmt::Error { .. } => self.write("/* ошибка разбора */"),
             Stmt::Import { specifiers, source, attributes, .. } => {
                 self.print_import(specifiers, source, attributes);
             }
@@ -667,6 +700,9 @@ impl Printer {
             Expr::Literal(literal) => self.print_literal(literal),
             Expr::This { .. } => self.write("this"),
             Expr::Super { .. } => self.write("super"),
+            Expr::NewTarget { .. } => self.write("new.target"),
+            Expr::ImportMeta { .. } => self.write("import.meta"),
+            Expr::Error { .. } => self.write("undefined /* ошибка разбора */"),
             Expr::Unary { op, expr: operand, .. } => {
                 let wrap = UNARY_PRECEDENCE < parent_prec;
                 self.wrapped(wrap, |p| {
@@ -737,7 +773,10 @@ impl Printer {
                 self.reject_date_namespace(object);
                 self.print_expr(object, CALL_PRECEDENCE);
                 self.write(".");
-                self.write(&property.name);
+                match strip_grouping(object) {
+                    Expr::ImportMeta { .. } => self.write(import_meta_key(&property.name)),
+                    _ => self.write(&property.name),
+                }
             }
             Expr::OptionalMember { object, property, .. } => {
                 self.reject_date_namespace(object);
@@ -747,6 +786,7 @@ impl Printer {
             }
             Expr::ArrowFunction { params, body, is_async, .. } => {
                 let wrap = parent_prec > ASSIGN_PRECEDENCE;
+                let saw_await = self.saw_await;
                 self.wrapped(wrap, |p| {
                     if *is_async {
                         p.write("async ");
@@ -755,12 +795,15 @@ impl Printer {
                     p.write(" => ");
                     p.print_arrow_body(body);
                 });
+                self.saw_await = saw_await;
             }
             Expr::FunctionExpr { name, params, body, is_generator, is_async, .. } => {
+                let saw_await = self.saw_await;
                 self.print_function_head(*is_async, *is_generator, name.as_ref().map(|n| n.name.as_str()));
                 self.print_params(params);
                 self.write(" ");
                 self.print_block(body);
+                self.saw_await = saw_await;
             }
             Expr::Spread { expr, .. } => {
                 self.write("...");
@@ -777,6 +820,7 @@ impl Printer {
                 });
             }
             Expr::Await { argument, .. } => {
+                self.saw_await = true;
                 let wrap = UNARY_PRECEDENCE < parent_prec;
                 self.wrapped(wrap, |p| {
                     p.write("await ");
@@ -793,6 +837,110 @@ impl Printer {
                 self.print_expr(tag, CALL_PRECEDENCE);
                 self.print_tagged_template(quasis, expressions);
             }
+            Expr::Match { subject, arms, .. } => self.print_match(subject, arms),
+        }
+    }
+
+    /// `базарпо`-выражение превращается в стрелку, вызванную на значении: каждая ветка —
+    /// `if` с проверкой формы, деструктуризацией и `return`. Если в ветках есть `await`,
+    /// стрелка становится `async`, а вызов — `await`.
+    fn print_match(&mut self, subject: &Expr, arms: &[MatchArm]) {
+        let tmp = format!("__ypsMatch{}", self.match_counter);
+        self.match_counter += 1;
+        let start = self.out.len();
+        let outer_await = std::mem::replace(&mut self.saw_await, false);
+        self.write("((");
+        self.write(&tmp);
+        self.write(") => {");
+        self.newline();
+        self.depth += 1;
+        for arm in arms {
+            self.print_match_arm(arm, &tmp);
+        }
+        self.indent();
+        self.write("throw new Error(\"Ни одна ветка 'базарпо' не подошла\");");
+        self.newline();
+        self.depth -= 1;
+        self.indent();
+        self.write("})(");
+        let inner_await = std::mem::replace(&mut self.saw_await, outer_await);
+        self.print_expr(subject, ASSIGN_PRECEDENCE);
+        self.write(")");
+        if inner_await {
+            self.out.insert_str(start + 1, "async ");
+            self.out.insert_str(start, "(await ");
+            self.write(")");
+            self.saw_await = true;
+        }
+    }
+
+    fn print_match_arm(&mut self, arm: &MatchArm, tmp: &str) {
+        let (head, pattern) = match &arm.pattern {
+            MatchPattern::Wildcard { .. } => (false, None),
+            MatchPattern::Bind(pattern) => (false, Some(pattern)),
+            MatchPattern::Value(_) => (true, None),
+            MatchPattern::Instance { pattern, .. } => (true, pattern.as_ref()),
+        };
+        let mut tests = Vec::new();
+        if let Some(pattern) = pattern {
+            shape_tests(pattern, tmp, &mut tests);
+        }
+        self.indent();
+        if head || !tests.is_empty() {
+            self.write("if (");
+            if head {
+                self.print_match_head(&arm.pattern, tmp);
+            }
+            for (i, test) in tests.iter().enumerate() {
+                if head || i > 0 {
+                    self.write(" && ");
+                }
+                self.write(test);
+            }
+            self.write(") ");
+        }
+        self.write("{");
+        self.newline();
+        self.depth += 1;
+        if let Some(pattern) = pattern {
+            self.indent();
+            self.write("const ");
+            self.print_pattern(pattern);
+            self.write(" = ");
+            self.write(tmp);
+            self.write(";");
+            self.newline();
+        }
+        self.indent();
+        if let Some(guard) = &arm.guard {
+            self.write("if (");
+            self.print_expr(guard, 0);
+            self.write(") ");
+        }
+        self.write("return ");
+        self.print_expr(&arm.body, 0);
+        self.write(";");
+        self.newline();
+        self.depth -= 1;
+        self.indent();
+        self.write("}");
+        self.newline();
+    }
+
+    /// The `===` or `instanceof` check of a literal or `шкура` arm.
+    fn print_match_head(&mut self, pattern: &MatchPattern, tmp: &str) {
+        match pattern {
+            MatchPattern::Value(value) => {
+                self.write(tmp);
+                self.write(" === ");
+                self.print_expr(value, binary_precedence(BinaryOp::StrictEquals) + 1);
+            }
+            MatchPattern::Instance { class, .. } => {
+                self.write(tmp);
+                self.write(" instanceof ");
+                self.print_expr(class, binary_precedence(BinaryOp::Instanceof) + 1);
+            }
+            MatchPattern::Wildcard { .. } | MatchPattern::Bind(_) => {}
         }
     }
 
@@ -1170,6 +1318,43 @@ fn needs_js_parens(operand: &Expr, parent_op: BinaryOp, is_left: bool) -> bool {
     }
 }
 
+/// Shape checks for `pattern` against the JS expression `access`, mirroring the runtimes:
+/// arrays need the exact length (or at least it with a rest), objects every named key.
+fn shape_tests(pattern: &Pattern, access: &str, out: &mut Vec<String>) {
+    match pattern {
+        Pattern::Identifier(_) => {}
+        Pattern::Default { pattern, .. } => {
+            let mut inner = Vec::new();
+            shape_tests(pattern, access, &mut inner);
+            if !inner.is_empty() {
+                out.push(format!("({access} === undefined || {})", inner.join(" && ")));
+            }
+        }
+        Pattern::Array { elements, rest, .. } => {
+            let op = if rest.is_some() { ">=" } else { "===" };
+            out.push(format!("Array.isArray({access})"));
+            out.push(format!("{access}.length {op} {}", elements.len()));
+            for (i, element) in elements.iter().enumerate() {
+                if let Some(element) = element {
+                    shape_tests(element, &format!("{access}[{i}]"), out);
+                }
+            }
+        }
+        Pattern::Object { properties, .. } => {
+            out.push(format!("typeof {access} === \"object\" && {access} !== null && !Array.isArray({access})"));
+            for prop in properties {
+                let item = format!("{access}[\"{}\"]", prop.key.name);
+                if !matches!(prop.value, Some(Pattern::Default { .. })) {
+                    out.push(format!("{item} !== undefined"));
+                }
+                if let Some(value) = &prop.value {
+                    shape_tests(value, &item, out);
+                }
+            }
+        }
+    }
+}
+
 fn stmt_expr_needs_parens(expr: &Expr) -> bool {
     matches!(
         starting_expr(expr),
@@ -1557,6 +1742,25 @@ mod tests {
         assert_contains(&js("гыы а = этоКосяк(е);\n"), "let а = ((е) instanceof Error);");
     }
 
+    #[test]
+    fn enum_becomes_a_frozen_object_built_by_a_helper() {
+        let out = js("еээ Цвет { Красный, Зелёный = 5, Синий }\nсказать(Цвет.Синий);\n");
+        assert!(out.starts_with("function __ypsEnum(members) {"), "пролог отсутствует:\n{out}");
+        assert_contains(&out, r#"const Цвет = __ypsEnum([["Красный"], ["Зелёный", 5], ["Синий"]]);"#);
+        assert_contains(&out, "console.log(Цвет.Синий);");
+    }
+
+    #[test]
+    fn match_becomes_an_immediately_called_arrow() {
+        let out = js("гыы р = базарпо (х) { тема 1 => \"один\", тема [а] вилкойвглаз (а > 0) => а, нуичо => 0 };\n");
+        assert_contains(&out, "let р = ((__ypsMatch0) => {");
+        assert_contains(&out, "if (__ypsMatch0 === 1) {");
+        assert_contains(&out, "if (Array.isArray(__ypsMatch0) && __ypsMatch0.length === 1) {");
+        assert_contains(&out, "const [а] = __ypsMatch0;");
+        assert_contains(&out, "if (а > 0) return а;");
+        assert_contains(&out, "})(х);");
+    }
+
     #[test]
     fn prelude_helpers_are_emitted_only_when_used() {
         let plain = js("сказать(1);\n");
@@ -1614,4 +1818,25 @@ mod tests {
         assert_contains(&out, "function длина(а) {");
         assert_contains(&out, "console.log(длина(2));");
     }
+
+    #[test]
+    fn type_annotations_are_erased() {
+        let out = js(
+            "тип Ид = число;\nхуёво Т { x: число }\nйопта ф(а: число, б: Ид = 1): строка { гыы в: число = а; отвечаю \"\" + в; }\nгыы г = (х: число): число => х;",
+        );
+        assert!(!out.contains("Ид") && !out.contains("число") && !out.contains("строка"), "{out}");
+        assert_contains(&out, "function ф(а, б = 1) {");
+        assert_contains(&out, "let в = а;");
+        assert_contains(&out, "let г = (х) => х;");
+    }
+
+    #[test]
+    fn meta_properties_and_implicit_names_map_to_js() {
+        let out = js(
+            "сказать(спиздить.мета.путь, спиздить.мета.главный);\nйопта ф() { отвечаю [захуярить.цель, аргументы]; }\nсказать(глобалЭтот);\n",
+        );
+        assert_contains(&out, "console.log(import.meta.filename, import.meta.main);");
+        assert_contains(&out, "return [new.target, arguments];");
+        assert_contains(&out, "console.log(globalThis);");
+    }
 }
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
 use yps_parser::{
-    Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, ObjectEntry, Param, Pattern, Program, PropKey, Stmt,
-    TemplatePart,
+    Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, MatchPattern, ObjectEntry, Param, Pattern, Program,
+    PropKey, Stmt, TemplatePart,
 };
 
 /// Best-effort whole-program collection of every name bound anywhere in the file.
@@ -79,9 +79,20 @@ fn collect_stmt(stmt: &Stmt, names: &mut HashSet<String>) {
             names.insert(name.name.clone());
             collect_expr(init, names);
         }
+        Stmt::EnumDecl { name, members, .. } => {
+            names.insert(name.name.clone());
+            for init in members.iter().filter_map(|m| m.init.as_ref()) {
+                collect_expr(init, names);
+            }
+        }
         Stmt::Expr { expr, .. } | Stmt::Throw { value: expr, .. } => collect_expr(expr, names),
         Stmt::Block(block) => collect_block(block, names),
-        Stmt::Empty { .. } | Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Debugger { .. } => {}
+        Stmt::Empty { .. }
+        | Stmt::Break { .. }
+        | Stmt::Continue { .. }
+        | Stmt::Debugger { .. }
+        | Stmt::TypeAlias { .. }
+        | Stmt::Error { .. } => {}
         Stmt::If { condition, then_branch, else_branch, .. } => {
             collect_expr(condition, names);
             collect_stmt(then_branch, names);
@@ -209,7 +220,12 @@ fn collect_decorators(decorators: &[Expr], names: &mut HashSet<String>) {
 
 fn collect_expr(expr: &Expr, names: &mut HashSet<String>) {
     match expr {
-        Expr::Identifier(_) | Expr::This { .. } | Expr::Super { .. } => {}
+        Expr::Identifier(_)
+        | Expr::This { .. }
+        | Expr::Super { .. }
+        | Expr::NewTarget { .. }
+        | Expr::ImportMeta { .. }
+        | Expr::Error { .. } => {}
         Expr::Literal(literal) => collect_literal(literal, names),
         Expr::Unary { expr, .. }
         | Expr::Postfix { expr, .. }
@@ -266,6 +282,26 @@ fn collect_expr(expr: &Expr, names: &mut HashSet<String>) {
             }
         }
         Expr::Await { argument: inner, .. } | Expr::DynamicImport { source: inner, .. } => collect_expr(inner, names),
+        Expr::Match { subject, arms, .. } => {
+            collect_expr(subject, names);
+            for arm in arms {
+                match &arm.pattern {
+                    MatchPattern::Bind(pattern) => collect_pattern(pattern, names),
+                    MatchPattern::Instance { class, pattern, .. } => {
+                        collect_expr(class, names);
+                        if let Some(pattern) = pattern {
+                            collect_pattern(pattern, names);
+                        }
+                    }
+                    MatchPattern::Value(value) => collect_expr(value, names),
+                    MatchPattern::Wildcard { .. } => {}
+                }
+                if let Some(guard) = &arm.guard {
+                    collect_expr(guard, names);
+                }
+                collect_expr(&arm.body, names);
+            }
+        }
     }
 }
 
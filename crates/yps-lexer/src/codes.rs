@@ -0,0 +1,176 @@
+//! Stable diagnostic codes shared by the lexer, the parser and the runtimes. Codes never change
+//! meaning once published; `yps explain <код>` prints the long explanation.
+
+pub const UNTERMINATED_STRING: &str = "E0001";
+pub const UNTERMINATED_TEMPLATE: &str = "E0002";
+pub const UNTERMINATED_COMMENT: &str = "E0003";
+pub const UNTERMINATED_REGEX: &str = "E0004";
+pub const INVALID_NUMBER: &str = "E0005";
+pub const UNKNOWN_CHARACTER: &str = "E0006";
+
+pub const SYNTAX_ERROR: &str = "E0010";
+pub const UNEXPECTED_TOKEN: &str = "E0011";
+pub const MISSING_SEMICOLON: &str = "E0012";
+pub const UNCLOSED_DELIMITER: &str = "E0013";
+pub const EXPECTED_IDENTIFIER: &str = "E0014";
+pub const NESTING_TOO_DEEP: &str = "E0015";
+
+pub const UNCAUGHT_EXCEPTION: &str = "E0100";
+pub const RUNTIME_ERROR: &str = "E0101";
+pub const UNSUPPORTED_BY_VM: &str = "E0102";
+
+pub const TYPE_MISMATCH: &str = "E0200";
+
+pub struct CodeInfo {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub explanation: &'static str,
+}
+
+pub const ALL: &[CodeInfo] = &[
+    CodeInfo {
+        code: UNTERMINATED_STRING,
+        title: "Незакрытая строка",
+        explanation: "Строковый литерал начался кавычкой, но файл закончился раньше закрывающей.\n\n\
+            гыы а = \"привет;\n\n\
+            Закройте строку той же кавычкой, которой она открыта: \"привет\".",
+    },
+    CodeInfo {
+        code: UNTERMINATED_TEMPLATE,
+        title: "Незакрытая шаблонная строка",
+        explanation: "Шаблонная строка (в обратных кавычках) или подстановка ${...} внутри неё не закрыта.\n\n\
+            гыы а = `сумма: ${1 + 2};\n\n\
+            Закройте подстановку '}' и строку '`'.",
+    },
+    CodeInfo {
+        code: UNTERMINATED_COMMENT,
+        title: "Незакрытый блочный комментарий",
+        explanation: "Комментарий /* ... не закрыт до конца файла, поэтому весь остаток файла стал комментарием.\n\n\
+            Добавьте */ там, где комментарий должен заканчиваться.",
+    },
+    CodeInfo {
+        code: UNTERMINATED_REGEX,
+        title: "Незавершённый regex-литерал",
+        explanation: "Регулярное выражение /.../ не закрыто до конца строки.\n\n\
+            гыы р = /а+;\n\n\
+            Закройте литерал '/' и при необходимости добавьте флаги: /а+/g.",
+    },
+    CodeInfo {
+        code: INVALID_NUMBER,
+        title: "Невалидное число",
+        explanation: "После префикса 0x, 0o или 0b нет ни одной цифры подходящей системы счисления.\n\n\
+            гыы а = 0x;\n\n\
+            Допишите цифры (0xff) или уберите префикс.",
+    },
+    CodeInfo {
+        code: UNKNOWN_CHARACTER,
+        title: "Неизвестный символ",
+        explanation: "В коде встретился символ, который не входит ни в один токен языка, например '§' или \
+            неразрывный пробел, скопированный из документа.\n\n\
+            Удалите символ или замените его обычным пробелом.",
+    },
+    CodeInfo {
+        code: SYNTAX_ERROR,
+        title: "Синтаксическая ошибка",
+        explanation: "Конструкция записана не так, как ожидает парсер: пропущено ключевое слово, оператор или \
+            скобка. Текст ошибки говорит, что именно ожидалось.\n\n\
+            вилкойвглаз х > 1) { ... }\n\n\
+            Здесь после 'вилкойвглаз' не хватает '('.",
+    },
+    CodeInfo {
+        code: UNEXPECTED_TOKEN,
+        title: "Неожиданный токен",
+        explanation: "На месте выражения оказался токен, с которого выражение начинаться не может: закрывающая \
+            скобка, запятая или конец файла.\n\n\
+            гыы а = ;\n\
+            сказать(1, );\n\n\
+            Допишите пропущенное значение или уберите лишний разделитель.",
+    },
+    CodeInfo {
+        code: MISSING_SEMICOLON,
+        title: "Пропущена ';'",
+        explanation: "Инструкции в YoptaScript заканчиваются ';' (или 'нах'), автоматической расстановки точек с \
+            запятой нет.\n\n\
+            сказать(1)\n\
+            сказать(2);\n\n\
+            Добавьте ';' после первой инструкции. Исправление помечено как машинно применимое.",
+    },
+    CodeInfo {
+        code: UNCLOSED_DELIMITER,
+        title: "Незакрытая скобка",
+        explanation: "Открывающей скобке '(', '[' или '{' не нашлось парной закрывающей. Вторая метка в сообщении \
+            показывает, где скобка была открыта.\n\n\
+            йопта ф() {\n\
+            \x20   отвечаю 1;\n\n\
+            Добавьте '}' в конце тела функции.",
+    },
+    CodeInfo {
+        code: EXPECTED_IDENTIFIER,
+        title: "Ожидался идентификатор",
+        explanation: "На месте имени переменной, функции, класса или свойства стоит ключевое слово, число или \
+            знак.\n\n\
+            гыы 1а = 2;\n\
+            йопта вилкойвглаз() {}\n\n\
+            Имена не могут начинаться с цифры и совпадать с ключевыми словами.",
+    },
+    CodeInfo {
+        code: NESTING_TOO_DEEP,
+        title: "Слишком глубокая вложенность",
+        explanation: "Выражения или блоки вложены глубже, чем парсер готов разбирать (200 уровней). Обычно это \
+            сгенерированный код или забытая закрывающая скобка.\n\n\
+            Вынесите часть вложенных выражений в переменные или функции.",
+    },
+    CodeInfo {
+        code: UNCAUGHT_EXCEPTION,
+        title: "Необработанное исключение",
+        explanation: "Значение, брошенное через 'кидай', дошло до верхнего уровня программы, не встретив 'хапнуть'/'гоп'.\n\n\
+            кидай захуярить Ошибка(\"бум\");\n\n\
+            Оберните опасный код в 'хапнуть { ... } гоп (е) { ... }' или устраните причину исключения. \
+            Метки в сообщении показывают стек вызовов.",
+    },
+    CodeInfo {
+        code: RUNTIME_ERROR,
+        title: "Ошибка выполнения",
+        explanation: "Программа разобралась, но операция не смогла выполниться: обращение к необъявленной \
+            переменной, вызов не-функции, присваивание константе, чтение свойства у 'неибу' и т. п.\n\n\
+            ясенХуй а = 1;\n\
+            а = 2;\n\n\
+            Текст ошибки описывает конкретную причину, метки показывают стек вызовов.",
+    },
+    CodeInfo {
+        code: UNSUPPORTED_BY_VM,
+        title: "Конструкция не поддерживается VM",
+        explanation: "Байткодовая VM (флаг --vm) пока компилирует не все конструкции языка.\n\n\
+            Запустите программу без --vm, на дереве интерпретации.",
+    },
+    CodeInfo {
+        code: TYPE_MISMATCH,
+        title: "Несовпадение типов",
+        explanation: "`yps check` нашёл значение, которое не подходит под записанный тип: переменной, параметра, \
+            поля или результата функции.\n\n\
+            гыы а: число = \"пять\";\n\n\
+            Исправьте значение или тип. Аннотации необязательны: код без них проверяется по выведенным типам, \
+            а 'неизвестно' подходит под любой тип.",
+    },
+];
+
+/// The registry entry for `code`; case-insensitive (`e0012` works too).
+#[must_use]
+pub fn lookup(code: &str) -> Option<&'static CodeInfo> {
+    ALL.iter().find(|info| info.code.eq_ignore_ascii_case(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_well_formed() {
+        for (i, info) in ALL.iter().enumerate() {
+            assert!(info.code.len() == 5 && info.code.starts_with('E'), "{}", info.code);
+            assert!(ALL[i + 1..].iter().all(|other| other.code != info.code), "дубль {}", info.code);
+        }
+        assert_eq!(lookup("e0012").map(|info| info.title), Some("Пропущена ';'"));
+        assert!(lookup("E9999").is_none());
+    }
+}
@@ -6,9 +6,156 @@ pub enum Severity {
     Warning,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelStyle {
+    /// Marks where the problem is; rendered with `^`.
+    Primary,
+    /// Points at related code, e.g. where an unclosed bracket was opened; rendered with `-`.
+    Secondary,
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub style: LabelStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// The replacement is certainly what was meant and can be applied without review.
+    MachineApplicable,
+    /// The replacement is a guess.
+    MaybeIncorrect,
+}
+
+/// A proposed edit: replace `span` with `replacement`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+/// A problem found in the source. `span` is the primary location; `labels`, `notes`, `help` and
+/// `suggestions` add detail for renderers that can show it.
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
     pub severity: Severity,
+    /// Stable code from [`crate::codes`], e.g. `E0012`.
+    pub code: Option<&'static str>,
     pub message: String,
     pub span: Span,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn new(severity: Severity, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            span,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    #[must_use]
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, message, span)
+    }
+
+    #[must_use]
+    pub const fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Text shown under the primary span.
+    #[must_use]
+    pub fn with_label(mut self, message: impl Into<String>) -> Self {
+        self.labels.push(Label { span: self.span, message: message.into(), style: LabelStyle::Primary });
+        self
+    }
+
+    #[must_use]
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { span, message: message.into(), style: LabelStyle::Secondary });
+        self
+    }
+
+    #[must_use]
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_suggestion(
+        mut self,
+        message: impl Into<String>,
+        span: Span,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+            applicability,
+        });
+        self
+    }
+
+    /// The same diagnostic with every span passed through `f`.
+    #[must_use]
+    pub fn map_spans(mut self, f: impl Fn(Span) -> Span) -> Self {
+        self.span = f(self.span);
+        for label in &mut self.labels {
+            label.span = f(label.span);
+        }
+        for suggestion in &mut self.suggestions {
+            suggestion.span = f(suggestion.span);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders_attach_details_and_spans_move_together() {
+        let d = Diagnostic::error("Ожидалась ';'", Span { start: 4, end: 5 })
+            .with_code("E0012")
+            .with_label("здесь")
+            .with_secondary(Span { start: 0, end: 1 }, "начало")
+            .with_suggestion("добавьте ';'", Span { start: 3, end: 3 }, ";", Applicability::MachineApplicable)
+            .map_spans(|s| Span { start: s.start + 10, end: s.end + 10 });
+        assert_eq!(d.code, Some("E0012"));
+        assert_eq!(d.span, Span { start: 14, end: 15 });
+        assert_eq!(d.labels[0].span, d.span);
+        assert_eq!(d.labels[1].span, Span { start: 10, end: 11 });
+        assert_eq!(d.suggestions[0].span, Span { start: 13, end: 13 });
+    }
 }
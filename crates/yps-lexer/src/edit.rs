@@ -0,0 +1,67 @@
+use crate::Span;
+
+/// A text replacement: the old `span` became `new_len` bytes of new text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    pub span: Span,
+    pub new_len: usize,
+}
+
+impl Edit {
+    /// End of the replacement in the new text.
+    #[must_use]
+    pub const fn new_end(self) -> usize {
+        self.span.start + self.new_len
+    }
+
+    /// Moves an old offset at or after the edited range to its place in the new text.
+    #[must_use]
+    pub const fn shift(self, offset: usize) -> usize {
+        offset + self.new_len - (self.span.end - self.span.start)
+    }
+
+    #[must_use]
+    pub const fn shift_span(self, span: Span) -> Span {
+        Span { start: self.shift(span.start), end: self.shift(span.end) }
+    }
+
+    /// Moves an old span whose bytes the edit does not touch: what lies after the edit shifts, what
+    /// lies before stays, and a span enclosing the edit grows or shrinks with it.
+    #[must_use]
+    pub const fn move_span(self, span: Span) -> Span {
+        if span.end <= self.span.start {
+            span
+        } else if span.start >= self.span.end {
+            self.shift_span(span)
+        } else {
+            Span { start: span.start, end: self.shift(span.end) }
+        }
+    }
+
+    /// Where an old span ends up in the new text; `None` when the edit touches it.
+    #[must_use]
+    pub const fn map_span(self, span: Span) -> Option<Span> {
+        if span.end <= self.span.start {
+            Some(span)
+        } else if span.start >= self.span.end {
+            Some(self.shift_span(span))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_after_the_edit_move_by_the_length_difference() {
+        let edit = Edit { span: Span { start: 4, end: 6 }, new_len: 5 };
+        assert_eq!(edit.new_end(), 9);
+        assert_eq!(edit.map_span(Span { start: 0, end: 4 }), Some(Span { start: 0, end: 4 }));
+        assert_eq!(edit.map_span(Span { start: 6, end: 8 }), Some(Span { start: 9, end: 11 }));
+        assert_eq!(edit.map_span(Span { start: 5, end: 8 }), None);
+        assert_eq!(edit.move_span(Span { start: 1, end: 8 }), Span { start: 1, end: 11 });
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
-    Diagnostic, KeywordKind, OperatorKind, PunctuationKind, Severity, SourceFile, Span, Token, TokenKind, Trivia,
-    TriviaKind,
+    Diagnostic, Edit, KeywordKind, OperatorKind, PunctuationKind, SourceFile, Span, Token, TokenKind, Trivia,
+    TriviaKind, codes,
 };
 
 const KEYWORD_TABLE: &[(&str, KeywordKind)] = &[
+    ("Еээ", KeywordKind::Enum),
     ("ЯсенХуй", KeywordKind::YasenHuy),
     ("аеслинайду", KeywordKind::Case),
     ("апохуй", KeywordKind::Default),
@@ -22,6 +23,7 @@ const KEYWORD_TABLE: &[(&str, KeywordKind)] = &[
     ("ебанное", KeywordKind::Public),
     ("ебнуть", KeywordKind::Delete),
     ("естьчо", KeywordKind::Switch),
+    ("еээ", KeywordKind::Enum),
     ("захуярить", KeywordKind::New),
     ("из", KeywordKind::In),
     ("иливжопураз", KeywordKind::Ilivzhopuraz),
@@ -128,6 +130,22 @@ const KEYWORDS_ARRAY: [&str; KEYWORD_TABLE.len()] = {
 
 pub const KEYWORDS: &[&str] = &KEYWORDS_ARRAY;
 
+/// The keyword or word operator `word` is probably a misspelling of, e.g. `иливжопураз` for
+/// `илижопураз`.
+#[must_use]
+pub fn keyword_suggestion(word: &str) -> Option<&'static str> {
+    let words = KEYWORD_TABLE
+        .iter()
+        .map(|(w, _)| *w)
+        .chain(OPERATOR_WORD_TABLE.iter().map(|(w, _)| *w))
+        .chain(PUNCT_WORD_TABLE.iter().map(|(w, _)| *w));
+    crate::suggest::closest(word, words)
+}
+
+/// How far past its end a token may look (a few characters of up to four bytes each); tokens this
+/// close to an edit are lexed again.
+const RELEX_MARGIN: usize = 16;
+
 pub struct Lexer<'src> {
     source: &'src SourceFile,
     position: usize,
@@ -164,6 +182,74 @@ impl<'src> Lexer<'src> {
         self.run()
     }
 
+    /// Re-lexes only around `edit`, given the tokens and diagnostics of the text before it. Lexing
+    /// restarts after the last untouched token outside template literals and stops as soon as it
+    /// reaches a token boundary where the old and new lexer states agree; the old tail is shifted.
+    #[must_use]
+    pub fn relex(
+        mut self,
+        previous: &[Token],
+        diagnostics: &[Diagnostic],
+        edit: Edit,
+    ) -> (Vec<Token>, Vec<Diagnostic>) {
+        // Open template literals after each old token, to know where the lexer state is plain.
+        let mut open_after = Vec::with_capacity(previous.len());
+        let mut open = 0usize;
+        for token in previous {
+            match token.kind {
+                TokenKind::TemplateHead => open += 1,
+                TokenKind::TemplateTail => open = open.saturating_sub(1),
+                _ => {}
+            }
+            open_after.push(open);
+        }
+        let kept = previous
+            .iter()
+            .zip(&open_after)
+            .take_while(|(token, _)| token.kind != TokenKind::Eof && token.span.end + RELEX_MARGIN < edit.span.start)
+            .enumerate()
+            .filter(|(_, (_, open))| **open == 0)
+            .last()
+            .map_or(0, |(i, _)| i + 1);
+
+        let mut tokens = previous[..kept].to_vec();
+        if let Some(last) = tokens.last() {
+            self.position = last.span.end;
+            self.last_kind = Some(last.kind.clone());
+        }
+        let restart = self.position;
+        self.diagnostics = diagnostics.iter().filter(|d| d.span.start < restart).cloned().collect();
+
+        loop {
+            let token = self.next_token();
+            if token.kind == TokenKind::Eof {
+                tokens.push(token);
+                return (tokens, self.diagnostics);
+            }
+            self.last_kind = Some(token.kind.clone());
+            let end = token.span.end;
+            let kind = token.kind.clone();
+            tokens.push(token);
+            if !self.template_brace_depth.is_empty() || end < edit.new_end() {
+                continue;
+            }
+            let old_end = end + (edit.span.end - edit.span.start) - edit.new_len;
+            let j = previous.partition_point(|t| t.span.end < old_end);
+            if j < previous.len() && previous[j].span.end == old_end && previous[j].kind == kind && open_after[j] == 0 {
+                tokens.extend(
+                    previous[j + 1..].iter().map(|t| Token { kind: t.kind.clone(), span: edit.shift_span(t.span) }),
+                );
+                self.diagnostics.extend(
+                    diagnostics
+                        .iter()
+                        .filter(|d| d.span.start >= old_end)
+                        .map(|d| d.clone().map_spans(|span| edit.shift_span(span))),
+                );
+                return (tokens, self.diagnostics);
+            }
+        }
+    }
+
     fn run(mut self) -> (Vec<Token>, Vec<Trivia>, Vec<Diagnostic>) {
         let mut tokens = Vec::new();
 
@@ -256,7 +342,7 @@ impl<'src> Lexer<'src> {
             self.advance();
         }
         if !closed {
-            self.error(Span { start, end: self.position }, "Незавершённый regex-литерал");
+            self.error(codes::UNTERMINATED_REGEX, Span { start, end: self.position }, "Незавершённый regex-литерал");
             return Token { kind: TokenKind::Unknown, span: Span { start, end: self.position } };
         }
         while !self.is_at_end() && self.current_char().is_ascii_alphabetic() {
@@ -357,7 +443,7 @@ impl<'src> Lexer<'src> {
         }
 
         if self.is_at_end() {
-            self.error(Span { start, end: self.position }, "Незакрытая строка");
+            self.error(codes::UNTERMINATED_STRING, Span { start, end: self.position }, "Незакрытая строка");
         } else {
             self.advance();
         }
@@ -388,7 +474,7 @@ impl<'src> Lexer<'src> {
                 let span = Span { start, end };
                 if digits == 0 {
                     let raw = self.source.slice(span);
-                    self.error(span, format!("Невалидное число: '{raw}'"));
+                    self.error(codes::INVALID_NUMBER, span, format!("Невалидное число: '{raw}'"));
                 }
                 return Token { kind: TokenKind::Number, span };
             }
@@ -495,7 +581,11 @@ impl<'src> Lexer<'src> {
                     self.advance();
                     loop {
                         if self.is_at_end() {
-                            self.error(Span { start, end: self.position }, "Незакрытый блочный комментарий");
+                            self.error(
+                                codes::UNTERMINATED_COMMENT,
+                                Span { start, end: self.position },
+                                "Незакрытый блочный комментарий",
+                            );
                             break;
                         }
                         if self.current_char() == '*' && self.peek_char(1) == '/' {
@@ -691,7 +781,11 @@ impl<'src> Lexer<'src> {
             }
             '@' => TokenKind::Punctuation(PunctuationKind::At),
             _ => {
-                self.error(Span { start, end: self.position }, format!("Неизвестный символ: '{ch}'"));
+                self.error(
+                    codes::UNKNOWN_CHARACTER,
+                    Span { start, end: self.position },
+                    format!("Неизвестный символ: '{ch}'"),
+                );
                 TokenKind::Unknown
             }
         };
@@ -714,7 +808,11 @@ impl<'src> Lexer<'src> {
     fn read_template_chars(&mut self) -> bool {
         loop {
             if self.is_at_end() {
-                self.error(Span { start: self.position, end: self.position }, "Незакрытая шаблонная строка");
+                self.error(
+                    codes::UNTERMINATED_TEMPLATE,
+                    Span { start: self.position, end: self.position },
+                    "Незакрытая шаблонная строка",
+                );
                 return false;
             }
             let ch = self.current_char();
@@ -775,8 +873,8 @@ impl<'src> Lexer<'src> {
         self.position >= self.source.source.len()
     }
 
-    fn error(&mut self, span: Span, message: impl Into<String>) {
-        self.diagnostics.push(Diagnostic { severity: Severity::Error, message: message.into(), span });
+    fn error(&mut self, code: &'static str, span: Span, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic::error(message, span).with_code(code));
     }
 
     fn consume_digit_run(&mut self, radix: u32) -> usize {
@@ -800,6 +898,7 @@ impl<'src> Lexer<'src> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Severity;
 
     #[test]
     fn word_tables_are_sorted_and_unique() {
@@ -864,6 +963,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn keyword_typos_suggest_the_intended_spelling() {
+        assert_eq!(keyword_suggestion("илижопураз"), Some("иливжопураз"));
+        assert_eq!(keyword_suggestion("вилкойвглас"), Some("вилкойвглаз"));
+        assert_eq!(keyword_suggestion("пабрадски"), Some("пабратски"));
+        assert_eq!(keyword_suggestion("сказать"), None);
+    }
+
     #[test]
     fn test_at_token() {
         let source = SourceFile::new("test.yopta".to_string(), "@декоратор".to_string());
@@ -1222,4 +1329,32 @@ mod tests {
     fn string_with_an_escaped_quote_is_a_single_token() {
         assert_eq!(lex_kinds(r#""а\"б""#), vec![TokenKind::StringLiteral, TokenKind::Eof]);
     }
+
+    fn relex_matches_full_lexing(before: &str, start: usize, end: usize, insert: &str) {
+        let old = SourceFile::new("test.yopta".to_string(), before.to_string());
+        let (tokens, diags) = Lexer::new(&old).tokenize();
+        let mut after = before.to_string();
+        after.replace_range(start..end, insert);
+        let new = SourceFile::new("test.yopta".to_string(), after.clone());
+        let edit = Edit { span: Span { start, end }, new_len: insert.len() };
+        let (relexed, relexed_diags) = Lexer::new(&new).relex(&tokens, &diags, edit);
+        let (full, full_diags) = Lexer::new(&new).tokenize();
+        let view = |ts: &[Token]| ts.iter().map(|t| (t.kind.clone(), t.span)).collect::<Vec<_>>();
+        assert_eq!(view(&relexed), view(&full), "{after:?}");
+        let spans = |ds: &[Diagnostic]| ds.iter().map(|d| d.span).collect::<Vec<_>>();
+        assert_eq!(spans(&relexed_diags), spans(&full_diags), "{after:?}");
+    }
+
+    #[test]
+    fn relex_agrees_with_full_lexing() {
+        let src = "гыы а = 1;\nгыы б = `х${а}у`;\nйопта ф() { отвечаю а / 2; }\nгыы в = /ре/г;\n";
+        let at = |needle: &str| src.find(needle).unwrap();
+        relex_matches_full_lexing(src, at("1;"), at("1;") + 1, "12");
+        relex_matches_full_lexing(src, at("а}"), at("а}"), "а + ");
+        relex_matches_full_lexing(src, at("отвечаю"), at("отвечаю"), "/* ");
+        relex_matches_full_lexing(src, at("/ 2"), at("/ 2") + 1, "");
+        relex_matches_full_lexing(src, at("`х"), at("`х") + 1, "");
+        relex_matches_full_lexing(src, src.len(), src.len(), "сказать(в);");
+        relex_matches_full_lexing(src, 0, 0, "\"");
+    }
 }
@@ -1,12 +1,16 @@
+pub mod codes;
 mod diagnostic;
+mod edit;
 mod lexer;
 mod source;
 mod span;
+pub mod suggest;
 mod token;
 mod trivia;
 
-pub use diagnostic::{Diagnostic, Severity};
-pub use lexer::{KEYWORDS, Lexer};
+pub use diagnostic::{Applicability, Diagnostic, Label, LabelStyle, Severity, Suggestion};
+pub use edit::Edit;
+pub use lexer::{KEYWORDS, Lexer, keyword_suggestion};
 pub use source::SourceFile;
 pub use span::Span;
 pub use token::{KeywordKind, OperatorKind, PunctuationKind, Token, TokenKind};
@@ -0,0 +1,72 @@
+//! "Возможно, имелось в виду …" — picking the closest spelling for a typo.
+
+/// Edit distance between `a` and `b` in characters, counting a swap of two adjacent characters as
+/// one edit (optimal string alignment).
+#[must_use]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// The candidate `name` is most likely a misspelling of: one differing only in case, or else the
+/// nearest one within a third of the name's length. Ties go to the alphabetically first candidate,
+/// so the answer does not depend on the order of `candidates`.
+#[must_use]
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() + 1) / 3;
+    let lowered = name.to_lowercase();
+    let mut best: Option<(usize, &'a str)> = None;
+    for candidate in candidates {
+        if candidate == name {
+            continue;
+        }
+        let distance = if candidate.to_lowercase() == lowered { 0 } else { edit_distance(name, candidate) };
+        if distance > max_distance {
+            continue;
+        }
+        if best.is_none_or(|(d, c)| (distance, candidate) < (d, c)) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_chars_and_adjacent_swaps() {
+        assert_eq!(edit_distance("иливжопураз", "иливжопураз"), 0);
+        assert_eq!(edit_distance("иливжпоураз", "иливжопураз"), 1);
+        assert_eq!(edit_distance("чётко", "четко"), 1);
+        assert_eq!(edit_distance("", "гыы"), 3);
+    }
+
+    #[test]
+    fn closest_prefers_case_then_distance_and_rejects_far_names() {
+        assert_eq!(closest("матан", ["Матан", "Мата"]), Some("Матан"));
+        assert_eq!(closest("пабратск", ["побратски", "пабратски"]), Some("пабратски"));
+        assert_eq!(closest("счётчик", ["счетчик", "сумма"]), Some("счетчик"));
+        assert_eq!(closest("сумма", ["итог", "результат"]), None);
+        assert_eq!(closest("х", ["у"]), None);
+    }
+}
@@ -46,6 +46,7 @@ pub enum KeywordKind {
     Delete,
     Void,
     Using,
+    Enum,
     Debugger,
     Private,
     Protected,
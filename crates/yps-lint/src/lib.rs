@@ -1,13 +1,15 @@
 mod linter;
 
 use yps_lexer::{Diagnostic, Lexer, Severity, SourceFile, Span};
-use yps_parser::Parser;
+use yps_parser::{Parser, Program};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Rule {
     UnusedVariable,
     UnreachableCode,
     ShadowedDeclaration,
+    /// A `базарпо` over an `еээ` without `апохуй` that skips some of its members.
+    NonExhaustiveSwitch,
 }
 
 impl Rule {
@@ -17,6 +19,7 @@ impl Rule {
             Self::UnusedVariable => "unused-variable",
             Self::UnreachableCode => "unreachable-code",
             Self::ShadowedDeclaration => "shadowed-declaration",
+            Self::NonExhaustiveSwitch => "non-exhaustive-switch",
         }
     }
 }
@@ -49,12 +52,18 @@ pub fn lint_source(source: &str) -> LintResult {
 
     let mut parse_errors = lex_diags;
     parse_errors.extend(parse_diags);
+    lint_parsed(&program, parse_errors)
+}
 
+/// Lints an already parsed program; `parse_errors` are the lexer and parser diagnostics it came with.
+/// A program with syntax errors is linted on the parts that parsed, except for unused variables:
+/// their reads may sit in the code the parser had to skip.
+#[must_use]
+pub fn lint_parsed(program: &Program, parse_errors: Vec<Diagnostic>) -> LintResult {
+    let mut diagnostics = linter::lint_program(program);
     if parse_errors.iter().any(|d| d.severity == Severity::Error) {
-        return LintResult { diagnostics: Vec::new(), parse_errors };
+        diagnostics.retain(|d| d.rule != Rule::UnusedVariable);
     }
-
-    let diagnostics = linter::lint_program(&program);
     LintResult { diagnostics, parse_errors }
 }
 
@@ -80,10 +89,11 @@ mod tests {
     }
 
     #[test]
-    fn parse_error_yields_no_lint() {
-        let result = lint_source("гыы = ;\n");
+    fn parse_error_keeps_linting_the_valid_parts() {
+        let result = lint_source("йопта ф() {\n  отвечаю 1;\n  сказать(2);\n}\nгыы = ;\nгыы х = 1;\n");
         assert!(!result.parse_errors.is_empty());
-        assert!(result.diagnostics.is_empty());
+        let rules: Vec<Rule> = result.diagnostics.iter().map(|d| d.rule).collect();
+        assert_eq!(rules, [Rule::UnreachableCode]);
     }
 
     #[test]
@@ -91,6 +101,7 @@ mod tests {
         assert_eq!(Rule::UnusedVariable.code(), "unused-variable");
         assert_eq!(Rule::UnreachableCode.code(), "unreachable-code");
         assert_eq!(Rule::ShadowedDeclaration.code(), "shadowed-declaration");
+        assert_eq!(Rule::NonExhaustiveSwitch.code(), "non-exhaustive-switch");
     }
 
     #[test]
@@ -302,4 +313,35 @@ mod tests {
         let src = "гыы а = 1;\nсказать(а);\nйопта ф() { гыы б = 2; сказать(б); }\n";
         assert_eq!(count(src, Rule::ShadowedDeclaration), 0);
     }
+
+    const ENUM: &str = "еээ Цвет { Красный, Зелёный, Синий }\n";
+
+    #[test]
+    fn switch_over_enum_names_missing_members() {
+        let src = format!("{ENUM}базарпо (Цвет.Красный) {{ лещ Цвет.Красный: {{ сказать(1); }} }}\n");
+        let diags = diagnostics(&src);
+        let hint = diags.iter().find(|d| d.rule == Rule::NonExhaustiveSwitch).expect("подсказка");
+        assert_eq!(hint.message, "«базарпо» не обрабатывает Цвет: Зелёный, Синий");
+    }
+
+    #[test]
+    fn switch_over_enum_silent_when_exhaustive_or_defaulted() {
+        let all =
+            format!("{ENUM}базарпо (1) {{ лещ Цвет.Красный: {{}} лещ Цвет.Зелёный: {{}} лещ Цвет.Синий: {{}} }}\n");
+        assert_eq!(count(&all, Rule::NonExhaustiveSwitch), 0);
+        let defaulted = format!("{ENUM}базарпо (1) {{ лещ Цвет.Красный: {{}} апохуй {{}} }}\n");
+        assert_eq!(count(&defaulted, Rule::NonExhaustiveSwitch), 0);
+        let not_enum = "ясенХуй К = {а: 1};\nбазарпо (1) { лещ К.а: {} }\n";
+        assert_eq!(count(not_enum, Rule::NonExhaustiveSwitch), 0);
+    }
+
+    #[test]
+    fn match_arm_after_catch_all_is_unreachable() {
+        let src = "гыы х = 1;\nсказать(базарпо (х) { тема у => у, тема 2 => 3 });\n";
+        let diags = diagnostics(src);
+        let hint = diags.iter().find(|d| d.rule == Rule::UnreachableCode).expect("предупреждение");
+        assert_eq!(hint.message, "недостижимая ветка: предыдущая подходит для любого значения");
+        let guarded = "гыы х = 1;\nсказать(базарпо (х) { тема у вилкойвглаз (у > 0) => у, нуичо => 3 });\n";
+        assert_eq!(count(guarded, Rule::UnreachableCode), 0);
+    }
 }
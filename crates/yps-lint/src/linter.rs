@@ -1,7 +1,7 @@
 use yps_lexer::Span;
 use yps_parser::{
-    BinaryOp, Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, ObjectEntry, Param, Pattern, Program, PropKey,
-    Stmt, TemplatePart,
+    BinaryOp, Block, ClassMember, ExportKind, Expr, ImportSpec, Literal, MatchArm, MatchPattern, ObjectEntry, Param,
+    Pattern, Program, PropKey, Stmt, SwitchCase, TemplatePart,
 };
 
 use crate::{LintDiagnostic, LintSeverity, Rule};
@@ -62,6 +62,8 @@ struct DeclInfo {
 
 struct Scope {
     decls: Vec<DeclInfo>,
+    /// Member names of the `еээ` declared in this scope.
+    enums: Vec<(String, Vec<String>)>,
 }
 
 struct Linter {
@@ -71,7 +73,7 @@ struct Linter {
 
 impl Linter {
     fn push_scope(&mut self) {
-        self.scopes.push(Scope { decls: Vec::new() });
+        self.scopes.push(Scope { decls: Vec::new(), enums: Vec::new() });
     }
 
     fn pop_scope(&mut self) {
@@ -147,6 +149,40 @@ impl Linter {
         self.scopes[..top].iter().any(|scope| scope.decls.iter().any(|d| d.name == name))
     }
 
+    /// Members of the enum `name` resolves to, if it resolves to one.
+    fn enum_members(&self, name: &str) -> Option<&[String]> {
+        let scope = self.scopes.iter().rev().find(|scope| scope.decls.iter().any(|d| d.name == name))?;
+        scope.enums.iter().find(|(enum_name, _)| enum_name == name).map(|(_, members)| members.as_slice())
+    }
+
+    /// A `базарпо` without `апохуй` whose cases are all `Enum.Member` should cover every member.
+    fn check_exhaustive(&mut self, expr: &Expr, cases: &[SwitchCase]) {
+        let mut enum_name = None;
+        let mut covered = Vec::new();
+        for case in cases {
+            let Expr::Member { object, property, .. } = &case.value else { return };
+            let Expr::Identifier(object) = object.as_ref() else { return };
+            if enum_name.is_some_and(|name| name != object.name.as_str()) {
+                return;
+            }
+            enum_name = Some(object.name.as_str());
+            covered.push(property.name.as_str());
+        }
+        let Some(enum_name) = enum_name else { return };
+        let Some(members) = self.enum_members(enum_name) else { return };
+        let missing: Vec<&str> =
+            members.iter().map(String::as_str).filter(|member| !covered.contains(member)).collect();
+        if missing.is_empty() {
+            return;
+        }
+        self.diags.push(LintDiagnostic {
+            span: expr.span(),
+            rule: Rule::NonExhaustiveSwitch,
+            severity: LintSeverity::Hint,
+            message: format!("«базарпо» не обрабатывает {}: {}", enum_name, missing.join(", ")),
+        });
+    }
+
     fn read(&mut self, name: &str) {
         for scope in self.scopes.iter_mut().rev() {
             let mut found = false;
@@ -174,7 +210,10 @@ impl Linter {
         let mut terminator: Option<&'static str> = None;
         for stmt in stmts {
             if let Some(keyword) = terminator {
-                if matches!(stmt, Stmt::FunctionDecl { .. } | Stmt::Empty { .. }) {
+                if matches!(
+                    stmt,
+                    Stmt::FunctionDecl { .. } | Stmt::TypeAlias { .. } | Stmt::Empty { .. } | Stmt::Error { .. }
+                ) {
                     continue;
                 }
                 self.diags.push(LintDiagnostic {
@@ -204,6 +243,12 @@ impl Linter {
             Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } | Stmt::Using { name, .. } => {
                 self.declare(&name.name, name.span, DeclKind::NonFlag, exported, None);
             }
+            Stmt::EnumDecl { name, members, .. } => {
+                self.declare(&name.name, name.span, DeclKind::NonFlag, exported, None);
+                let members = members.iter().map(|m| m.name.name.clone()).collect();
+                let scope = self.scopes.last_mut().expect("активная область");
+                scope.enums.push((name.name.clone(), members));
+            }
             Stmt::Import { specifiers, .. } => {
                 for spec in specifiers {
                     let local = match spec {
@@ -282,9 +327,19 @@ impl Linter {
                 self.visit_expr(init);
             }
             Stmt::Using { init, .. } => self.visit_expr(init),
+            Stmt::EnumDecl { members, .. } => {
+                for init in members.iter().filter_map(|m| m.init.as_ref()) {
+                    self.visit_expr(init);
+                }
+            }
             Stmt::Expr { expr, .. } => self.visit_discarded_expr(expr),
             Stmt::Block(block) => self.visit_block(block),
-            Stmt::Empty { .. } | Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Debugger { .. } => {}
+            Stmt::Empty { .. }
+            | Stmt::Break { .. }
+            | Stmt::Continue { .. }
+            | Stmt::Debugger { .. }
+            | Stmt::TypeAlias { .. }
+            | Stmt::Error { .. } => {}
             Stmt::If { condition, then_branch, else_branch, .. } => {
                 self.visit_expr(condition);
                 self.visit_branch(then_branch);
@@ -349,6 +404,9 @@ impl Linter {
             }
             Stmt::Switch { expr, cases, default, .. } => {
                 self.visit_expr(expr);
+                if default.is_none() {
+                    self.check_exhaustive(expr, cases);
+                }
                 self.push_scope();
                 for case in cases {
                     for inner in &case.body.stmts {
@@ -585,7 +643,50 @@ impl Linter {
             }
             Expr::Await { argument, .. } => self.visit_expr(argument),
             Expr::DynamicImport { source, .. } => self.visit_expr(source),
-            Expr::This { .. } | Expr::Super { .. } => {}
+            Expr::Match { subject, arms, .. } => self.visit_match(subject, arms),
+            Expr::This { .. }
+            | Expr::Super { .. }
+            | Expr::NewTarget { .. }
+            | Expr::ImportMeta { .. }
+            | Expr::Error { .. } => {}
+        }
+    }
+
+    fn visit_match(&mut self, subject: &Expr, arms: &[MatchArm]) {
+        self.visit_expr(subject);
+        if let Some(last) = arms.iter().position(|arm| arm.guard.is_none() && arm.pattern.is_irrefutable())
+            && let Some(unreachable) = arms.get(last + 1)
+        {
+            self.diags.push(LintDiagnostic {
+                span: unreachable.span,
+                rule: Rule::UnreachableCode,
+                severity: LintSeverity::Warning,
+                message: "недостижимая ветка: предыдущая подходит для любого значения".to_string(),
+            });
+        }
+        for arm in arms {
+            self.push_scope();
+            let pattern = match &arm.pattern {
+                MatchPattern::Bind(pattern) => Some(pattern),
+                MatchPattern::Instance { class, pattern, .. } => {
+                    self.visit_expr(class);
+                    pattern.as_ref()
+                }
+                MatchPattern::Value(value) => {
+                    self.visit_expr(value);
+                    None
+                }
+                MatchPattern::Wildcard { .. } => None,
+            };
+            if let Some(pattern) = pattern {
+                self.declare_pattern(pattern, DeclKind::Var, false);
+                self.visit_pattern_exprs(pattern);
+            }
+            if let Some(guard) = &arm.guard {
+                self.visit_expr(guard);
+            }
+            self.visit_expr(&arm.body);
+            self.pop_scope();
         }
     }
 
@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tower_lsp::lsp_types::{TextDocumentContentChangeEvent, Url};
+
+use crate::Analyzed;
+use crate::document::Document;
+
+/// How long a document has to stay unchanged before it is analyzed.
+pub const DEBOUNCE: Duration = Duration::from_millis(150);
+
+enum Job {
+    Open { uri: Url, text: String },
+    Change { uri: Url, changes: Vec<TextDocumentContentChangeEvent> },
+    Close { uri: Url },
+}
+
+impl Job {
+    fn uri(&self) -> &Url {
+        match self {
+            Self::Open { uri, .. } | Self::Change { uri, .. } | Self::Close { uri } => uri,
+        }
+    }
+}
+
+/// Stamp of the latest job per open document. Stamps come from one counter, so a document that
+/// is closed and opened again never gets back a stamp an old analysis started with.
+#[derive(Default)]
+struct Generations {
+    next: u64,
+    latest: HashMap<Url, u64>,
+}
+
+impl Generations {
+    fn bump(&mut self, job: &Job) {
+        self.next += 1;
+        match job {
+            Job::Close { uri } => self.latest.remove(uri),
+            _ => self.latest.insert(job.uri().clone(), self.next),
+        };
+    }
+
+    fn current(&self, uri: &Url) -> Option<u64> {
+        self.latest.get(uri).copied()
+    }
+}
+
+/// Handle to the background analysis thread. Edits are applied to the thread's syntax trees as
+/// they arrive; a document is analyzed once it has been quiet for [`DEBOUNCE`], and a newer edit
+/// of the same document cancels its analysis in flight.
+pub struct Analyzer {
+    jobs: mpsc::Sender<Job>,
+    generations: Arc<Mutex<Generations>>,
+}
+
+impl Analyzer {
+    /// Starts the thread; `publish` receives every finished analysis.
+    pub fn spawn(publish: impl Fn(Url, Analyzed) + Send + 'static) -> Self {
+        let (jobs, receiver) = mpsc::channel();
+        let generations = Arc::new(Mutex::new(Generations::default()));
+        let current = Arc::clone(&generations);
+        thread::Builder::new()
+            .name("yps-analysis".to_string())
+            .spawn(move || run(&receiver, &current, &publish))
+            .expect("не удалось запустить поток анализа");
+        Self { jobs, generations }
+    }
+
+    /// Starts tracking `text` without analyzing it: the caller analyzes a freshly opened
+    /// document itself, so that requests have results right away.
+    pub fn open(&self, uri: Url, text: String) {
+        self.send(Job::Open { uri, text });
+    }
+
+    pub fn change(&self, uri: Url, changes: Vec<TextDocumentContentChangeEvent>) {
+        self.send(Job::Change { uri, changes });
+    }
+
+    pub fn close(&self, uri: Url) {
+        self.send(Job::Close { uri });
+    }
+
+    fn send(&self, job: Job) {
+        if let Ok(mut generations) = self.generations.lock() {
+            generations.bump(&job);
+        }
+        // The thread only stops when this handle is dropped, so a failed send cannot happen.
+        let _ = self.jobs.send(job);
+    }
+}
+
+fn run(receiver: &mpsc::Receiver<Job>, generations: &Mutex<Generations>, publish: &dyn Fn(Url, Analyzed)) {
+    let mut documents: HashMap<Url, Document> = HashMap::new();
+    let mut dirty: HashSet<Url> = HashSet::new();
+    loop {
+        let job = if dirty.is_empty() {
+            receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            receiver.recv_timeout(DEBOUNCE)
+        };
+        match job {
+            Ok(Job::Open { uri, text }) => {
+                documents.insert(uri, Document::new(&text));
+            }
+            Ok(Job::Change { uri, changes }) => {
+                if let Some(document) = documents.get_mut(&uri) {
+                    for change in &changes {
+                        document.apply(change);
+                    }
+                    dirty.insert(uri);
+                }
+            }
+            Ok(Job::Close { uri }) => {
+                documents.remove(&uri);
+                dirty.remove(&uri);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let current = |uri: &Url| generations.lock().ok().and_then(|g| g.current(uri));
+                for uri in dirty.clone() {
+                    let started = current(&uri);
+                    let cancelled = || current(&uri) != started;
+                    // A cancelled document stays dirty and is picked up after its newer edits.
+                    if let Some(analyzed) = documents.get(&uri).and_then(|doc| doc.analyze(cancelled)) {
+                        dirty.remove(&uri);
+                        publish(uri, analyzed);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    #[test]
+    fn bursts_of_edits_are_analyzed_once() {
+        let (sender, results) = mpsc::channel();
+        let analyzer = Analyzer::spawn(move |uri, analyzed| sender.send((uri, analyzed.text)).unwrap());
+        let uri = Url::parse("file:///tmp/a.yopta").unwrap();
+        analyzer.open(uri.clone(), "гыы а = 1;".to_string());
+        for (i, digit) in ["2", "3", "4"].into_iter().enumerate() {
+            let at = Position::new(0, 8 + i as u32 + 1);
+            analyzer.change(
+                uri.clone(),
+                vec![TextDocumentContentChangeEvent {
+                    range: Some(Range { start: at, end: at }),
+                    range_length: None,
+                    text: digit.to_string(),
+                }],
+            );
+        }
+        let (published, text) = results.recv_timeout(Duration::from_secs(5)).expect("analysis");
+        assert_eq!(published, uri);
+        assert_eq!(text, "гыы а = 1234;");
+        assert!(results.recv_timeout(DEBOUNCE * 2).is_err());
+    }
+
+    #[test]
+    fn opening_a_document_leaves_its_analysis_to_the_caller() {
+        let (sender, results) = mpsc::channel();
+        let analyzer = Analyzer::spawn(move |uri, _| sender.send(uri).unwrap());
+        analyzer.open(Url::parse("file:///tmp/a.yopta").unwrap(), "гыы а = 1;".to_string());
+        assert!(results.recv_timeout(DEBOUNCE * 3).is_err());
+    }
+
+    #[test]
+    fn edits_only_move_the_generation_of_their_own_document() {
+        let a = Url::parse("file:///tmp/a.yopta").unwrap();
+        let b = Url::parse("file:///tmp/b.yopta").unwrap();
+        let mut generations = Generations::default();
+        generations.bump(&Job::Open { uri: a.clone(), text: String::new() });
+        let started = generations.current(&a);
+        generations.bump(&Job::Change { uri: b.clone(), changes: Vec::new() });
+        assert_eq!(generations.current(&a), started);
+
+        generations.bump(&Job::Close { uri: a.clone() });
+        generations.bump(&Job::Open { uri: a.clone(), text: String::new() });
+        assert_ne!(generations.current(&a), started, "a reopened document starts a new generation");
+    }
+}
@@ -24,6 +24,11 @@ pub fn builtin_doc(name: &str) -> Option<&'static str> {
         "сОчередить" => Some("**queueMicrotask** — поставить колбэк в микроочередь, вернуть промис"),
         "прочестьСтроку" => Some("**readline** — прочитать одну строку из stdin"),
         "прочестьВсё" => Some("**read all stdin** — прочитать весь ввод из stdin"),
+        "ебал" => Some("**eval** — выполнить строку как код и вернуть значение последнего выражения"),
+        "Функция" => Some("**Function** — собрать функцию из имён параметров и строки с телом"),
+        "структурныйКлон" => Some("**structuredClone** — глубокая копия значения с сохранением циклов"),
+        "вБазу64" => Some("**btoa** — строка (в UTF-8) или байты в base64"),
+        "изБазы64" => Some("**atob** — base64 в `Ц8Массив`"),
         "сказать.ошибка" => Some("**console.error** — вывести сообщение об ошибке"),
         "сказать.предупреждение" => Some("**console.warn** — вывести предупреждение"),
         "сказать.инфо" => Some("**console.info** — вывести информационное сообщение"),
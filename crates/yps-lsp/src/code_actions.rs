@@ -9,7 +9,7 @@ pub fn quick_fix(text: &str, diag: &LintDiagnostic) -> Option<(String, Vec<TextE
     match diag.rule {
         Rule::UnusedVariable => unused_variable_fix(text, diag),
         Rule::UnreachableCode => Some(unreachable_code_fix(text, diag)),
-        Rule::ShadowedDeclaration => None,
+        Rule::ShadowedDeclaration | Rule::NonExhaustiveSwitch => None,
     }
 }
 
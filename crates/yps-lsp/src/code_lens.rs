@@ -0,0 +1,158 @@
+use serde_json::json;
+use tower_lsp::lsp_types::{CodeLens, Command, Location, Url};
+use yps_lexer::{Lexer, SourceFile, Span};
+use yps_parser::Parser;
+use yps_parser::ast::{ExportKind, Stmt};
+
+use crate::commands::{DEBUG, RUN, SHOW_REFERENCES};
+use crate::position::span_to_range;
+
+/// Test files, as picked up by `yps test`.
+pub const TEST_SUFFIX: &str = ".test.yopta";
+
+/// Function names that mark an entry point.
+const MAIN_NAMES: &[&str] = &["главная", "main"];
+
+/// "▶ Запустить | 🐞 Отладить" above the entry point: a `главная`/`main` function, the first
+/// statement of a test file, or else the first top-level statement that does something.
+#[must_use]
+pub fn run_lenses(uri: &Url, text: &str) -> Vec<CodeLens> {
+    let sf = SourceFile::new("inline".to_string(), text.to_string());
+    let (tokens, _) = Lexer::new(&sf).tokenize();
+    let (program, _) = Parser::new(&tokens, &sf).parse_program();
+
+    let main = program.items.iter().find_map(|stmt| match stmt {
+        Stmt::FunctionDecl { name, .. } if MAIN_NAMES.contains(&name.name.as_str()) => Some(stmt.span()),
+        _ => None,
+    });
+    let anchor = main.or_else(|| {
+        if uri.path().ends_with(TEST_SUFFIX) {
+            program.items.first().map(Stmt::span)
+        } else {
+            program.items.iter().find(|stmt| is_executable(stmt)).map(Stmt::span)
+        }
+    });
+    let Some(anchor) = anchor else { return Vec::new() };
+
+    let range = span_to_range(text, Span { start: anchor.start, end: anchor.start });
+    let lens = |title: &str, command: &str| CodeLens {
+        range,
+        command: Some(Command {
+            title: title.to_string(),
+            command: command.to_string(),
+            arguments: Some(vec![json!(uri)]),
+        }),
+        data: None,
+    };
+    vec![lens("▶ Запустить", RUN), lens("🐞 Отладить", DEBUG)]
+}
+
+fn is_executable(stmt: &Stmt) -> bool {
+    !matches!(
+        stmt,
+        Stmt::FunctionDecl { .. }
+            | Stmt::ClassDecl { .. }
+            | Stmt::Import { .. }
+            | Stmt::Export { .. }
+            | Stmt::Empty { .. }
+            | Stmt::VarDecl { .. }
+    )
+}
+
+/// Name spans of the functions and classes the module exports.
+#[must_use]
+pub fn exported_declarations(text: &str) -> Vec<Span> {
+    let sf = SourceFile::new("inline".to_string(), text.to_string());
+    let (tokens, _) = Lexer::new(&sf).tokenize();
+    let (program, _) = Parser::new(&tokens, &sf).parse_program();
+
+    let declared = |name: &str| {
+        program.items.iter().find_map(|stmt| match stmt {
+            Stmt::FunctionDecl { name: decl, .. } | Stmt::ClassDecl { name: decl, .. } if decl.name == name => {
+                Some(decl.span)
+            }
+            _ => None,
+        })
+    };
+    let mut out = Vec::new();
+    for stmt in &program.items {
+        match stmt {
+            Stmt::Export { kind: ExportKind::Declaration(inner), .. } => {
+                if let Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } = inner.as_ref() {
+                    out.push(name.span);
+                }
+            }
+            Stmt::Export { kind: ExportKind::Named(names), .. } => {
+                out.extend(names.iter().filter_map(|name| declared(&name.name)));
+            }
+            _ => {}
+        }
+    }
+    out.sort_by_key(|span| span.start);
+    out.dedup();
+    out
+}
+
+/// "N ссылок" above an exported declaration; clicking it lists `references`.
+#[must_use]
+pub fn references_lens(uri: &Url, text: &str, name: Span, references: &[Location]) -> CodeLens {
+    let range = span_to_range(text, name);
+    CodeLens {
+        range,
+        command: Some(Command {
+            title: references_title(references.len()),
+            command: SHOW_REFERENCES.to_string(),
+            arguments: Some(vec![json!(uri), json!(range.start), json!(references)]),
+        }),
+        data: None,
+    }
+}
+
+fn references_title(count: usize) -> String {
+    let word = match (count % 10, count % 100) {
+        (1, n) if n != 11 => "ссылка",
+        (2..=4, n) if !(12..=14).contains(&n) => "ссылки",
+        _ => "ссылок",
+    };
+    format!("{count} {word}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(lenses: &[CodeLens]) -> Vec<(u32, &str)> {
+        lenses.iter().map(|l| (l.range.start.line, l.command.as_ref().unwrap().title.as_str())).collect()
+    }
+
+    #[test]
+    fn entry_files_get_run_and_debug_lenses() {
+        let uri = Url::parse("file:///проект/app.yopta").unwrap();
+        let src = "йопта помочь() {}\nгыы х = 1;\nсказать(х);\n";
+        assert_eq!(titles(&run_lenses(&uri, src)), [(2, "▶ Запустить"), (2, "🐞 Отладить")]);
+
+        let with_main = "сказать(1);\nйопта главная() {}\n";
+        assert_eq!(run_lenses(&uri, with_main)[0].range.start.line, 1);
+
+        let library = "предъява йопта помочь() {}\n";
+        assert!(run_lenses(&uri, library).is_empty());
+        let test = Url::parse("file:///проект/lib.test.yopta").unwrap();
+        assert_eq!(run_lenses(&test, library).len(), 2);
+    }
+
+    #[test]
+    fn exported_functions_and_classes_are_listed() {
+        let src = "предъява йопта а() {}\nклёво Б {}\nйопта в() {}\nпредъява ясенХуй г = 1;\nпредъява { Б };\n";
+        let names: Vec<&str> = exported_declarations(src).iter().map(|s| &src[s.start..s.end]).collect();
+        assert_eq!(names, ["а", "Б"]);
+    }
+
+    #[test]
+    fn reference_titles_are_pluralized() {
+        let titles: Vec<String> = [0, 1, 2, 5, 11, 21, 22, 112].into_iter().map(references_title).collect();
+        assert_eq!(
+            titles,
+            ["0 ссылок", "1 ссылка", "2 ссылки", "5 ссылок", "11 ссылок", "21 ссылка", "22 ссылки", "112 ссылок"]
+        );
+    }
+}
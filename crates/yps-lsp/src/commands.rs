@@ -0,0 +1,90 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use serde_json::{Value, json};
+use tokio::process::Command;
+use tower_lsp::lsp_types::Url;
+
+/// Runs a file: `[uri, { "vm": bool }?]`; output goes to `window/logMessage`.
+pub const RUN: &str = "yps.run";
+/// Returns a `yps-dap` launch configuration for the file `[uri]`; the editor starts the session.
+pub const DEBUG: &str = "yps.debug";
+/// Client-side command listing references: `[uri, position, locations]`.
+pub const SHOW_REFERENCES: &str = "yps.showReferences";
+
+/// Commands answered by `workspace/executeCommand`.
+pub const SERVER_COMMANDS: &[&str] = &[RUN, DEBUG];
+
+/// The program a command targets, from its first argument.
+#[must_use]
+pub fn program_path(arguments: &[Value]) -> Option<PathBuf> {
+    let uri = Url::parse(arguments.first()?.as_str()?).ok()?;
+    uri.to_file_path().ok()
+}
+
+/// Whether the optional `{ "vm": true }` argument asks for the bytecode VM.
+#[must_use]
+pub fn use_vm(arguments: &[Value]) -> bool {
+    arguments.get(1).and_then(|options| options["vm"].as_bool()).unwrap_or(false)
+}
+
+/// A tool installed next to the server binary (`yps-cli`, `yps-dap`), or the bare name for `PATH`.
+#[must_use]
+pub fn tool_path(name: &str) -> PathBuf {
+    let file = format!("{name}{}", env::consts::EXE_SUFFIX);
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(&file)))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(file))
+}
+
+/// `yps-cli [--vm] <program>` with its output piped back to the server.
+#[must_use]
+pub fn run_command(program: &Path, vm: bool) -> Command {
+    let mut command = Command::new(tool_path("yps-cli"));
+    if vm {
+        command.arg("--vm");
+    }
+    command.arg(program).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    if let Some(dir) = program.parent() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// Launch configuration for `yps-dap`.
+#[must_use]
+pub fn debug_configuration(program: &Path) -> Value {
+    let name = program.file_name().map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    json!({
+        "type": "yoptascript",
+        "request": "launch",
+        "name": format!("Отладка {name}"),
+        "program": program,
+        "debugAdapter": tool_path("yps-dap"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_name_the_program_and_the_engine() {
+        let args = [json!("file:///tmp/%D0%BF.yopta"), json!({ "vm": true })];
+        assert_eq!(program_path(&args), Some(PathBuf::from("/tmp/п.yopta")));
+        assert!(use_vm(&args));
+        assert!(!use_vm(&args[..1]));
+        assert_eq!(program_path(&[json!(1)]), None);
+    }
+
+    #[test]
+    fn debug_configuration_launches_the_program() {
+        let config = debug_configuration(Path::new("/tmp/игра.yopta"));
+        assert_eq!(config["request"], "launch");
+        assert_eq!(config["program"], "/tmp/игра.yopta");
+        assert_eq!(config["name"], "Отладка игра.yopta");
+    }
+}
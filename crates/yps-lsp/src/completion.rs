@@ -7,19 +7,28 @@ use yps_interpreter::builtins::builtin_names;
 use yps_lexer::KEYWORDS;
 
 use crate::builtins::builtin_doc;
-use crate::position::member_receiver;
-use crate::types::{global_type_items, is_known_global, member_items_for};
+use crate::position::member_receiver_span;
+use crate::types::{global_type_items, member_items_for, table_member_items};
+use yps_types::catalog::is_known_global;
+use yps_types::infer::{Type, TypeInfo, infer_source};
 
 fn markdown(value: &str) -> Documentation {
     Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value: value.to_string() })
 }
 
+/// Names the runtime provides without a declaration or a builtin function behind them.
+const IMPLICIT_NAMES: &[(&str, &str)] = &[
+    ("глобалЭтот", "**globalThis** — объект со всеми глобальными именами"),
+    ("аргументы", "**arguments** — массив аргументов вызова в обычной (не стрелочной) функции"),
+    ("родитель", "**parentPort** — порт связи с запустившим потоком внутри `Воркер`"),
+];
+
 #[must_use]
 pub fn completion_items(symbols: &[DocumentSymbol], text: &str, cursor: Option<usize>) -> Vec<CompletionItem> {
     if let Some(byte) = cursor
-        && let Some(receiver) = member_receiver(text, byte)
+        && let Some(span) = member_receiver_span(text, byte)
     {
-        return member_completion(receiver);
+        return member_completion(text, byte, &text[span.start..span.end]);
     }
 
     let mut items: Vec<CompletionItem> = KEYWORDS
@@ -42,6 +51,16 @@ pub fn completion_items(symbols: &[DocumentSymbol], text: &str, cursor: Option<u
         });
     }
 
+    for (name, doc) in IMPLICIT_NAMES {
+        seen.insert((*name).to_string());
+        items.push(CompletionItem {
+            label: (*name).to_string(),
+            kind: Some(CompletionItemKind::VARIABLE),
+            documentation: Some(markdown(doc)),
+            ..Default::default()
+        });
+    }
+
     for item in global_type_items() {
         if seen.insert(item.label.clone()) {
             items.push(item);
@@ -62,7 +81,15 @@ pub fn completion_items(symbols: &[DocumentSymbol], text: &str, cursor: Option<u
     items
 }
 
-fn member_completion(receiver: &str) -> Vec<CompletionItem> {
+fn member_completion(text: &str, cursor: usize, receiver: &str) -> Vec<CompletionItem> {
+    if let Some((property, doc)) = meta_property(receiver) {
+        return vec![CompletionItem {
+            label: property.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            documentation: Some(markdown(doc)),
+            ..Default::default()
+        }];
+    }
     if !receiver.is_empty() {
         let prefix = format!("{receiver}.");
         let builtin_members: Vec<CompletionItem> = builtin_names()
@@ -80,6 +107,13 @@ fn member_completion(receiver: &str) -> Vec<CompletionItem> {
             return builtin_members;
         }
 
+        if let Some((info, ty)) = receiver_type(text, cursor, receiver) {
+            let items = typed_member_items(&info, &ty);
+            if !items.is_empty() {
+                return items;
+            }
+        }
+
         if is_known_global(receiver) {
             return member_items_for(Some(receiver));
         }
@@ -88,12 +122,60 @@ fn member_completion(receiver: &str) -> Vec<CompletionItem> {
     member_items_for(None)
 }
 
+/// `спиздить.мета` and `захуярить.цель`: the only thing that can follow these keywords and a dot.
+fn meta_property(keyword: &str) -> Option<(&'static str, &'static str)> {
+    match keyword {
+        "спиздить" => Some(("мета", "**import.meta** — `путь`, `каталог`, `url` и `главный` текущего модуля")),
+        "захуярить" => Some(("цель", "**new.target** — класс, вызванный через `захуярить`, или `неибу`")),
+        _ => None,
+    }
+}
+
+/// Infers the receiver's type with the unfinished `.member` blanked out, so the rest of the
+/// file still parses.
+fn receiver_type(text: &str, cursor: usize, receiver: &str) -> Option<(TypeInfo, Type)> {
+    let recv_end = member_receiver_span(text, cursor)?.end;
+    let recv_start = recv_end - receiver.len();
+    let mut patched = String::with_capacity(text.len());
+    patched.push_str(&text[..recv_end]);
+    patched.push(';');
+    patched.extend(std::iter::repeat_n(' ', cursor - recv_end - 1));
+    patched.push_str(&text[cursor..]);
+
+    let info = infer_source(&patched);
+    let ty = match info.type_at(recv_start) {
+        Some((name, ty)) if name == receiver && ty.is_known() => ty.clone(),
+        _ => info.type_before(receiver, recv_start)?.clone(),
+    };
+    Some((info, ty))
+}
+
+fn typed_member_items(info: &TypeInfo, ty: &Type) -> Vec<CompletionItem> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut items: Vec<CompletionItem> = info
+        .members_of(ty)
+        .into_iter()
+        .map(|member| CompletionItem {
+            label: member.name,
+            kind: Some(if member.is_method { CompletionItemKind::METHOD } else { CompletionItemKind::FIELD }),
+            detail: Some(member.ty.to_string()),
+            ..Default::default()
+        })
+        .filter(|item| seen.insert(item.label.clone()))
+        .collect();
+    for table in ty.alternatives().into_iter().filter_map(Type::receiver_table) {
+        items.extend(table_member_items(table).into_iter().filter(|item| seen.insert(item.label.clone())));
+    }
+    items
+}
+
 fn symbol_completion_kind(kind: tower_lsp::lsp_types::SymbolKind) -> CompletionItemKind {
     use tower_lsp::lsp_types::SymbolKind;
     match kind {
         SymbolKind::FUNCTION => CompletionItemKind::FUNCTION,
         SymbolKind::CLASS => CompletionItemKind::CLASS,
         SymbolKind::CONSTANT => CompletionItemKind::CONSTANT,
+        SymbolKind::ENUM => CompletionItemKind::ENUM,
         _ => CompletionItemKind::VARIABLE,
     }
 }
@@ -116,6 +198,8 @@ mod tests {
         let labels = labels(&items);
         assert!(labels.contains(&"йопта"));
         assert!(labels.contains(&"сказать"));
+        assert!(labels.contains(&"глобалЭтот"));
+        assert!(labels.contains(&"аргументы"));
     }
 
     #[test]
@@ -165,6 +249,14 @@ mod tests {
         assert!(!labels.contains(&"добавить"), "методы массива не относятся к Матан");
     }
 
+    #[test]
+    fn member_position_after_import_and_new_offers_meta_properties() {
+        let src = "спиздить.";
+        assert_eq!(labels(&items_for(src, Some(src.len()))), ["мета"]);
+        let src = "захуярить.";
+        assert_eq!(labels(&items_for(src, Some(src.len()))), ["цель"]);
+    }
+
     #[test]
     fn member_position_unknown_receiver_unions_members() {
         let src = "x.";
@@ -174,6 +266,36 @@ mod tests {
         assert!(labels.contains(&"добавить"));
     }
 
+    #[test]
+    fn member_position_uses_inferred_receiver_type() {
+        let src = "участковый х = [1, 2];\nх.";
+        let items = items_for(src, Some(src.len()));
+        let labels = labels(&items);
+        assert!(labels.contains(&"добавить"), "got {labels:?}");
+        assert!(!labels.contains(&"вВерхнийРегистр"), "методы строки не относятся к массиву");
+    }
+
+    #[test]
+    fn member_position_offers_user_class_members() {
+        let src = "клёво Кот {\n  конструктор() { тырыпыры.имя = \"Мурзик\"; }\n  мяу() { отвечаю 1; }\n}\nгыы к = захуярить Кот();\nсказать(к.м);";
+        let cursor = src.find("к.м").unwrap() + "к.м".len();
+        let items = items_for(src, Some(cursor));
+        let labels = labels(&items);
+        assert!(labels.contains(&"мяу"), "got {labels:?}");
+        assert!(labels.contains(&"имя"), "got {labels:?}");
+        assert!(!labels.contains(&"добавить"));
+    }
+
+    #[test]
+    fn member_position_offers_enum_members() {
+        let src = "еээ Цвет { Красный, Зелёный = 5 }\nсказать(Цвет.К);";
+        let cursor = src.find("Цвет.К").unwrap() + "Цвет.К".len();
+        let labels_at = items_for(src, Some(cursor));
+        let labels = labels(&labels_at);
+        assert!(labels.contains(&"Красный"), "got {labels:?}");
+        assert!(labels.contains(&"Зелёный"), "got {labels:?}");
+    }
+
     #[test]
     fn member_position_offers_console_family() {
         let src = "сказать.";
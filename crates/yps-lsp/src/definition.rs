@@ -2,11 +2,13 @@ use tower_lsp::lsp_types::Position;
 use yps_lexer::Span;
 use yps_parser::Program;
 use yps_parser::ast::{
-    Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, ObjectEntry, Param, Pattern, PropKey, Stmt,
+    Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, MatchPattern, ObjectEntry, Param, Pattern,
+    PropKey, Stmt,
 };
 
 use crate::position::{pos_to_byte, word_at};
 
+#[derive(Clone)]
 pub struct Declaration {
     pub name: String,
     pub span: Span,
@@ -133,7 +135,12 @@ fn collect_stmt(stmt: &Stmt, out: &mut Vec<Declaration>) {
         }
         Stmt::Expr { expr, .. } | Stmt::Throw { value: expr, .. } => collect_expr(expr, out),
         Stmt::Block(block) => collect_block(block, out),
-        Stmt::Empty { .. } | Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Debugger { .. } => {}
+        Stmt::Empty { .. }
+        | Stmt::Break { .. }
+        | Stmt::Continue { .. }
+        | Stmt::Debugger { .. }
+        | Stmt::TypeAlias { .. }
+        | Stmt::Error { .. } => {}
         Stmt::If { condition, then_branch, else_branch, .. } => {
             collect_expr(condition, out);
             collect_stmt(then_branch, out);
@@ -211,6 +218,15 @@ fn collect_stmt(stmt: &Stmt, out: &mut Vec<Declaration>) {
             push_ident(name, out);
             collect_expr(init, out);
         }
+        Stmt::EnumDecl { name, members, .. } => {
+            push_ident(name, out);
+            for member in members {
+                push_ident(&member.name, out);
+                if let Some(init) = &member.init {
+                    collect_expr(init, out);
+                }
+            }
+        }
         Stmt::Import { specifiers, .. } => {
             for spec in specifiers {
                 match spec {
@@ -272,7 +288,12 @@ fn collect_prop_key(key: &PropKey, out: &mut Vec<Declaration>) {
 
 fn collect_expr(expr: &Expr, out: &mut Vec<Declaration>) {
     match expr {
-        Expr::Identifier(_) | Expr::This { .. } | Expr::Super { .. } => {}
+        Expr::Identifier(_)
+        | Expr::This { .. }
+        | Expr::Super { .. }
+        | Expr::NewTarget { .. }
+        | Expr::ImportMeta { .. }
+        | Expr::Error { .. } => {}
         Expr::Literal(lit) => collect_literal(lit, out),
         Expr::Unary { expr, .. }
         | Expr::Postfix { expr, .. }
@@ -330,6 +351,26 @@ fn collect_expr(expr: &Expr, out: &mut Vec<Declaration>) {
             }
         }
         Expr::DynamicImport { source, .. } => collect_expr(source, out),
+        Expr::Match { subject, arms, .. } => {
+            collect_expr(subject, out);
+            for arm in arms {
+                match &arm.pattern {
+                    MatchPattern::Bind(pattern) => collect_pattern(pattern, out),
+                    MatchPattern::Instance { class, pattern, .. } => {
+                        collect_expr(class, out);
+                        if let Some(pattern) = pattern {
+                            collect_pattern(pattern, out);
+                        }
+                    }
+                    MatchPattern::Value(value) => collect_expr(value, out),
+                    MatchPattern::Wildcard { .. } => {}
+                }
+                if let Some(guard) = &arm.guard {
+                    collect_expr(guard, out);
+                }
+                collect_expr(&arm.body, out);
+            }
+        }
     }
 }
 
@@ -1,8 +1,10 @@
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
-use yps_lexer::Severity;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, TextEdit};
+use yps_lexer::{Applicability, Severity};
 
 use crate::position::span_to_range;
 
+pub const SOURCE: &str = "yps";
+
 #[must_use]
 pub fn to_lsp_diagnostics(
     text: &str,
@@ -20,6 +22,8 @@ pub fn to_lsp_diagnostics(
             Diagnostic {
                 range: span_to_range(text, d.span),
                 severity: Some(severity),
+                code: d.code.map(|code| NumberOrString::String(code.to_string())),
+                source: Some(SOURCE.to_string()),
                 message: d.message.clone(),
                 ..Default::default()
             }
@@ -27,6 +31,19 @@ pub fn to_lsp_diagnostics(
         .collect()
 }
 
+/// The suggestions attached to a lexer or parser diagnostic as quick fixes: title, edit and
+/// whether the fix is safe to apply without review.
+#[must_use]
+pub fn suggestion_fixes(text: &str, d: &yps_lexer::Diagnostic) -> Vec<(String, TextEdit, bool)> {
+    d.suggestions
+        .iter()
+        .map(|s| {
+            let edit = TextEdit { range: span_to_range(text, s.span), new_text: s.replacement.clone() };
+            (s.message.clone(), edit, s.applicability == Applicability::MachineApplicable)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,6 +54,19 @@ mod tests {
         assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
     }
 
+    #[test]
+    fn missing_semicolon_carries_code_and_fix() {
+        let text = "сказать(1)\nсказать(2);\n";
+        let analyzed = crate::analyze(text);
+        let diag = analyzed.diagnostics.iter().find(|d| d.source.as_deref() == Some(SOURCE)).unwrap();
+        assert_eq!(diag.code, Some(NumberOrString::String("E0012".into())));
+        let fixes = suggestion_fixes(text, &analyzed.lint.parse_errors[0]);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].1.new_text, ";");
+        assert_eq!(fixes[0].1.range.start, tower_lsp::lsp_types::Position { line: 0, character: 10 });
+        assert!(fixes[0].2);
+    }
+
     #[test]
     fn broken_source_reports_error() {
         let diags = crate::analyze("йопта (").diagnostics;
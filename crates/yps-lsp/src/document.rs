@@ -0,0 +1,132 @@
+use tower_lsp::lsp_types::TextDocumentContentChangeEvent;
+use yps_lexer::{Diagnostic, Edit, Lexer, SourceFile, Span, Token};
+use yps_parser::{Parser, PreviousParse, Program};
+use yps_types::infer;
+
+use crate::position::pos_to_byte;
+use crate::{Analyzed, definition, diagnostics, lint, symbols, types};
+
+/// An open buffer together with the tokens and syntax tree of its current text. Ranged
+/// `didChange` events are relexed and reparsed around the change only.
+pub struct Document {
+    text: String,
+    tokens: Vec<Token>,
+    lex_diags: Vec<Diagnostic>,
+    program: Program,
+    parse_diags: Vec<Diagnostic>,
+}
+
+/// Applies one change event to `text`; returns the edit unless the whole text was replaced.
+pub fn apply_change(text: &mut String, change: &TextDocumentContentChangeEvent) -> Option<Edit> {
+    let Some(range) = change.range else {
+        text.clone_from(&change.text);
+        return None;
+    };
+    let start = pos_to_byte(text, range.start);
+    let end = pos_to_byte(text, range.end).max(start);
+    text.replace_range(start..end, &change.text);
+    Some(Edit { span: Span { start, end }, new_len: change.text.len() })
+}
+
+impl Document {
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        let sf = SourceFile::new("inline".to_string(), text.to_string());
+        let (tokens, lex_diags) = Lexer::new(&sf).tokenize();
+        let (program, parse_diags) = Parser::new(&tokens, &sf).parse_program();
+        Self { text: text.to_string(), tokens, lex_diags, program, parse_diags }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn apply(&mut self, change: &TextDocumentContentChangeEvent) {
+        let Some(edit) = apply_change(&mut self.text, change) else {
+            *self = Self::new(&self.text);
+            return;
+        };
+        let sf = SourceFile::new("inline".to_string(), self.text.clone());
+        let (tokens, lex_diags) = Lexer::new(&sf).relex(&self.tokens, &self.lex_diags, edit);
+        let previous = PreviousParse { program: &self.program, tokens: &self.tokens, diagnostics: &self.parse_diags };
+        let (program, parse_diags) = Parser::new(&tokens, &sf).parse_program_reusing(&previous, edit);
+        *self = Self { text: std::mem::take(&mut self.text), tokens, lex_diags, program, parse_diags };
+    }
+
+    /// Lints and type-checks the current tree. `cancelled` is polled between the passes; once it
+    /// returns `true` the analysis is abandoned.
+    pub fn analyze(&self, cancelled: impl Fn() -> bool) -> Option<Analyzed> {
+        let text = &self.text;
+        let mut errors = self.lex_diags.clone();
+        errors.extend(self.parse_diags.iter().cloned());
+        let lint = yps_lint::lint_parsed(&self.program, errors);
+        if cancelled() {
+            return None;
+        }
+        let types = infer::infer(&self.program);
+        if cancelled() {
+            return None;
+        }
+
+        let mut diagnostics = diagnostics::to_lsp_diagnostics(text, &self.lex_diags, &self.parse_diags);
+        diagnostics.extend(lint::to_lsp_diagnostics(text, &lint.diagnostics));
+        if self.lex_diags.is_empty() && self.parse_diags.is_empty() {
+            diagnostics.extend(types::to_lsp_diagnostics(text, &types));
+        }
+
+        Some(Analyzed {
+            diagnostics,
+            symbols: symbols::document_symbols(&self.program, text),
+            declarations: definition::declarations(&self.program),
+            lint,
+            types,
+            text: text.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{DiagnosticSeverity, Position, Range};
+
+    fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range { start: Position::new(start.0, start.1), end: Position::new(end.0, end.1) }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn ranged_changes_edit_the_text_in_place() {
+        let mut doc = Document::new("гыы а = 1;\nсказать(а);\n");
+        doc.apply(&change((0, 8), (0, 9), "42"));
+        doc.apply(&change((1, 0), (1, 0), "а = а + 1;\n"));
+        assert_eq!(doc.text(), "гыы а = 42;\nа = а + 1;\nсказать(а);\n");
+        let fresh = Document::new(doc.text());
+        assert_eq!(format!("{:?}", doc.program), format!("{:?}", fresh.program));
+    }
+
+    #[test]
+    fn incremental_analysis_reports_new_errors() {
+        let errors = |analyzed: Analyzed| {
+            analyzed.diagnostics.iter().filter(|d| d.severity == Some(DiagnosticSeverity::ERROR)).count()
+        };
+        let mut doc = Document::new("гыы а = 1;\n");
+        assert_eq!(errors(doc.analyze(|| false).expect("analysis")), 0);
+        doc.apply(&change((0, 8), (0, 9), ""));
+        assert!(errors(doc.analyze(|| false).expect("analysis")) > 0);
+        assert!(doc.analyze(|| true).is_none());
+    }
+
+    #[test]
+    fn a_change_without_range_replaces_the_text() {
+        let mut doc = Document::new("гыы а = 1;");
+        doc.apply(&TextDocumentContentChangeEvent {
+            range: None, range_length: None, text: "сказать(2);".to_string()
+        });
+        assert_eq!(doc.text(), "сказать(2);");
+    }
+}
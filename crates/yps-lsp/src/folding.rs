@@ -0,0 +1,112 @@
+use tower_lsp::lsp_types::{FoldingRange, FoldingRangeKind};
+use yps_lexer::{Lexer, SourceFile, Span, TriviaKind};
+use yps_parser::Parser;
+use yps_parser::ast::{Expr, Literal, Stmt};
+
+use crate::position::byte_to_pos;
+use crate::walk::{Node, walk};
+
+/// Blocks, classes, `switch` bodies, multi-line object/array literals and runs of comments.
+#[must_use]
+pub fn folding_ranges(text: &str) -> Vec<FoldingRange> {
+    let sf = SourceFile::new("inline".to_string(), text.to_string());
+    let (tokens, trivia, _) = Lexer::new(&sf).tokenize_with_trivia();
+    let (program, _) = Parser::new(&tokens, &sf).parse_program();
+
+    let mut out = Vec::new();
+    walk(&program.items, &mut |node| {
+        let foldable = matches!(
+            node,
+            Node::Block(_)
+                | Node::Stmt(Stmt::ClassDecl { .. } | Stmt::EnumDecl { .. } | Stmt::Switch { .. })
+                | Node::Expr(Expr::Literal(Literal::Object { .. } | Literal::Array { .. }))
+        );
+        if foldable {
+            push_range(text, node.span(), &mut out);
+        }
+    });
+
+    let mut run: Option<Span> = None;
+    for comment in &trivia {
+        let line = byte_to_pos(text, comment.span.start).line;
+        match comment.kind {
+            TriviaKind::LineComment => {
+                run = match run {
+                    Some(prev) if byte_to_pos(text, prev.end).line + 1 == line => {
+                        Some(Span { start: prev.start, end: comment.span.end })
+                    }
+                    prev => {
+                        if let Some(prev) = prev {
+                            push_comment(text, prev, &mut out);
+                        }
+                        Some(comment.span)
+                    }
+                };
+            }
+            TriviaKind::BlockComment => {
+                if let Some(prev) = run.take() {
+                    push_comment(text, prev, &mut out);
+                }
+                push_comment(text, comment.span, &mut out);
+            }
+        }
+    }
+    if let Some(prev) = run {
+        push_comment(text, prev, &mut out);
+    }
+
+    out.sort_by_key(|r| (r.start_line, r.end_line));
+    out.dedup_by_key(|r| (r.start_line, r.end_line));
+    out
+}
+
+fn push_comment(text: &str, span: Span, out: &mut Vec<FoldingRange>) {
+    let start = byte_to_pos(text, span.start).line;
+    let end = byte_to_pos(text, span.end).line;
+    if end > start {
+        out.push(range(start, end, Some(FoldingRangeKind::Comment)));
+    }
+}
+
+/// Brace-delimited nodes fold up to the line before the closing brace so it stays visible.
+fn push_range(text: &str, span: Span, out: &mut Vec<FoldingRange>) {
+    let start = byte_to_pos(text, span.start).line;
+    let close = byte_to_pos(text, span.end).line;
+    if close > start + 1 {
+        out.push(range(start, close - 1, None));
+    }
+}
+
+fn range(start_line: u32, end_line: u32, kind: Option<FoldingRangeKind>) -> FoldingRange {
+    FoldingRange { start_line, start_character: None, end_line, end_character: None, kind, collapsed_text: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(src: &str) -> Vec<(u32, u32, bool)> {
+        folding_ranges(src)
+            .into_iter()
+            .map(|r| (r.start_line, r.end_line, r.kind == Some(FoldingRangeKind::Comment)))
+            .collect()
+    }
+
+    #[test]
+    fn blocks_and_classes_fold_to_the_line_before_the_brace() {
+        let src = "клёво К {\n  м() {\n    отвечаю 1;\n  }\n}\n";
+        assert_eq!(lines(src), [(0, 3, false), (1, 2, false)]);
+    }
+
+    #[test]
+    fn multiline_literals_fold_and_single_line_ones_do_not() {
+        let src = "гыы о = {\n  а: 1,\n  б: [1, 2]\n};\n";
+        assert_eq!(lines(src), [(0, 2, false)]);
+    }
+
+    #[test]
+    fn comment_runs_fold_together() {
+        let src = "// раз\n// два\n// три\nгыы х = 1;\n/* много\n строк */\n// одна\n";
+        assert_eq!(lines(src), [(0, 2, true), (4, 5, true)]);
+    }
+}
@@ -0,0 +1,191 @@
+use tower_lsp::lsp_types::SymbolKind;
+use yps_lexer::Span;
+use yps_parser::Program;
+use yps_parser::ast::{ClassMember, Expr, Pattern, Stmt};
+
+use crate::walk::Node;
+
+/// A function, class (called through `захуярить`) or method that calls can be attributed to.
+#[derive(Debug, Clone)]
+pub(crate) struct Callable {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub span: Span,
+    pub name_span: Span,
+    /// Owning class of a method.
+    pub container: Option<String>,
+}
+
+/// A call, `захуярить` or method-call site; `callee` is the called name's identifier.
+#[derive(Debug, Clone)]
+pub(crate) struct CallSite {
+    pub callee: Span,
+    pub name: String,
+    pub is_member: bool,
+    /// The object identifier of `обж.метод()`, used to follow namespace imports.
+    pub receiver: Option<Span>,
+    /// Index into [`Outline::callables`]; `None` for module top-level code.
+    pub caller: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClassEntry {
+    pub name: String,
+    pub span: Span,
+    pub name_span: Span,
+    /// The identifier after `батя`, if the superclass is named directly.
+    pub super_class: Option<Span>,
+}
+
+/// Calls and class declarations of one module, flattened for the workspace index.
+#[derive(Debug, Default)]
+pub(crate) struct Outline {
+    pub callables: Vec<Callable>,
+    pub calls: Vec<CallSite>,
+    pub classes: Vec<ClassEntry>,
+}
+
+impl Outline {
+    pub fn new(program: &Program) -> Self {
+        let mut outline = Self::default();
+        for stmt in &program.items {
+            outline.visit(Node::Stmt(stmt), None, None);
+        }
+        outline
+    }
+
+    #[must_use]
+    pub fn callable_at(&self, byte_pos: usize) -> Option<usize> {
+        self.callables.iter().position(|c| c.name_span.start <= byte_pos && byte_pos <= c.name_span.end)
+    }
+
+    #[must_use]
+    pub fn class_at(&self, byte_pos: usize) -> Option<&ClassEntry> {
+        self.classes.iter().find(|c| c.name_span.start <= byte_pos && byte_pos <= c.name_span.end)
+    }
+
+    #[must_use]
+    pub fn call_at(&self, byte_pos: usize) -> Option<&CallSite> {
+        self.calls.iter().find(|c| c.callee.start <= byte_pos && byte_pos <= c.callee.end)
+    }
+
+    fn add(&mut self, name: &str, kind: SymbolKind, span: Span, name_span: Span, container: Option<&str>) -> usize {
+        self.callables.push(Callable {
+            name: name.to_string(),
+            kind,
+            span,
+            name_span,
+            container: container.map(str::to_string),
+        });
+        self.callables.len() - 1
+    }
+
+    fn visit(&mut self, node: Node, caller: Option<usize>, class: Option<&str>) {
+        let mut caller = caller;
+        match node {
+            Node::Stmt(Stmt::FunctionDecl { name, span, .. }) => {
+                caller = Some(self.add(&name.name, SymbolKind::FUNCTION, *span, name.span, None));
+            }
+            Node::Stmt(Stmt::VarDecl {
+                pattern: Pattern::Identifier(name),
+                init: Expr::FunctionExpr { .. } | Expr::ArrowFunction { .. },
+                span,
+                ..
+            }) => {
+                let index = self.add(&name.name, SymbolKind::FUNCTION, *span, name.span, None);
+                for child in node.children() {
+                    let inner = if matches!(child, Node::Expr(_)) { Some(index) } else { caller };
+                    self.visit(child, inner, class);
+                }
+                return;
+            }
+            Node::Stmt(Stmt::ClassDecl { name, super_class, span, .. }) => {
+                self.classes.push(ClassEntry {
+                    name: name.name.clone(),
+                    span: *span,
+                    name_span: name.span,
+                    super_class: match super_class {
+                        Some(Expr::Identifier(ident)) => Some(ident.span),
+                        _ => None,
+                    },
+                });
+                let index = self.add(&name.name, SymbolKind::CLASS, *span, name.span, None);
+                for child in node.children() {
+                    let inner = if matches!(child, Node::Member(_)) { Some(index) } else { caller };
+                    self.visit(child, inner, Some(&name.name));
+                }
+                return;
+            }
+            Node::Member(ClassMember::Method { name, span, .. }) => {
+                caller = Some(self.add(&name.name, SymbolKind::METHOD, *span, name.span, class));
+            }
+            Node::Expr(Expr::Call { callee, .. } | Expr::OptionalCall { callee, .. } | Expr::New { callee, .. }) => {
+                let site = match callee.as_ref() {
+                    Expr::Identifier(ident) => Some((ident, None, false)),
+                    Expr::Member { object, property, .. } | Expr::OptionalMember { object, property, .. } => {
+                        let receiver = match object.as_ref() {
+                            Expr::Identifier(ident) => Some(ident.span),
+                            _ => None,
+                        };
+                        Some((property, receiver, true))
+                    }
+                    _ => None,
+                };
+                if let Some((ident, receiver, is_member)) = site {
+                    self.calls.push(CallSite {
+                        callee: ident.span,
+                        name: ident.name.clone(),
+                        is_member,
+                        receiver,
+                        caller,
+                    });
+                }
+            }
+            _ => {}
+        }
+        for child in node.children() {
+            self.visit(child, caller, class);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yps_lexer::{Lexer, SourceFile};
+    use yps_parser::Parser;
+
+    fn outline(src: &str) -> Outline {
+        let sf = SourceFile::new("inline".to_string(), src.to_string());
+        let (tokens, _) = Lexer::new(&sf).tokenize();
+        let (program, _) = Parser::new(&tokens, &sf).parse_program();
+        Outline::new(&program)
+    }
+
+    #[test]
+    fn calls_are_attributed_to_the_enclosing_callable() {
+        let src = "йопта а() { б(); }\nгыы б = () => в.г();\nклёво К { м() { а(); } }\nзахуярить К();\n";
+        let outline = outline(src);
+        let names: Vec<&str> = outline.callables.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["а", "б", "К", "м"]);
+        let calls: Vec<(&str, bool, Option<&str>)> = outline
+            .calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.is_member, c.caller.map(|i| outline.callables[i].name.as_str())))
+            .collect();
+        assert_eq!(
+            calls,
+            [("б", false, Some("а")), ("г", true, Some("б")), ("а", false, Some("м")), ("К", false, None)]
+        );
+        assert_eq!(outline.callables[3].container.as_deref(), Some("К"));
+    }
+
+    #[test]
+    fn classes_record_their_named_superclass() {
+        let src = "клёво А {}\nклёво Б батя А {}\n";
+        let outline = outline(src);
+        assert!(outline.classes[0].super_class.is_none());
+        let sup = outline.classes[1].super_class.expect("superclass");
+        assert_eq!(&src[sup.start..sup.end], "А");
+    }
+}
@@ -0,0 +1,54 @@
+use tower_lsp::lsp_types::{DocumentHighlight, DocumentHighlightKind};
+
+use crate::position::span_to_range;
+use crate::rename::occurrence_kinds_at;
+
+/// Occurrences of the binding under the cursor; declarations and assignments are writes.
+#[must_use]
+pub fn document_highlights(text: &str, byte_pos: usize) -> Option<Vec<DocumentHighlight>> {
+    let occurrences = occurrence_kinds_at(text, byte_pos)?;
+    Some(
+        occurrences
+            .into_iter()
+            .map(|(span, write)| DocumentHighlight {
+                range: span_to_range(text, span),
+                kind: Some(if write { DocumentHighlightKind::WRITE } else { DocumentHighlightKind::READ }),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(src: &str, byte_pos: usize) -> Vec<(u32, DocumentHighlightKind)> {
+        document_highlights(src, byte_pos)
+            .expect("binding under cursor")
+            .into_iter()
+            .map(|h| (h.range.start.line, h.kind.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn declarations_and_assignments_are_writes() {
+        let src = "гыы х = 1;\nсказать(х);\nх += 2;\nх++;\nх = х * 2;";
+        let w = DocumentHighlightKind::WRITE;
+        let r = DocumentHighlightKind::READ;
+        assert_eq!(kinds(src, src.rfind('х').unwrap()), [(0, w), (1, r), (2, w), (3, w), (4, w), (4, r)]);
+    }
+
+    #[test]
+    fn parameters_are_scoped_to_their_function() {
+        let src = "гыы а = 1;\nйопта фу(а) { отвечаю а; }\nсказать(а);";
+        let param = src.find("фу(а)").unwrap() + "фу(".len();
+        let w = DocumentHighlightKind::WRITE;
+        let r = DocumentHighlightKind::READ;
+        assert_eq!(kinds(src, param), [(1, w), (1, r)]);
+    }
+
+    #[test]
+    fn nothing_is_highlighted_off_an_identifier() {
+        assert!(document_highlights("сказать(1);", 9).is_none());
+    }
+}
@@ -1,3 +1,12 @@
+use yps_types::infer::TypeInfo;
+
+/// Inferred type of the identifier under the cursor.
+#[must_use]
+pub fn type_hover(types: &TypeInfo, byte_pos: usize) -> Option<String> {
+    let (name, ty) = types.type_at(byte_pos)?;
+    ty.is_known().then(|| format!("**{name}**: `{ty}`"))
+}
+
 #[must_use]
 pub fn keyword_hover(word: &str) -> Option<&'static str> {
     match word {
@@ -47,6 +56,7 @@ pub fn keyword_hover(word: &str) -> Option<&'static str> {
         "ёбнуть" | "ебнуть" => Some("**delete** — удалить свойство"),
         "куку" => Some("**void** — вычислить и вернуть undefined"),
         "юзай" => Some("**using** — управление ресурсом"),
+        "еээ" | "Еээ" => Some("**enum** — перечисление"),
         "базарпо" | "естьчо" => Some("**switch** — множественный выбор"),
         "тема" | "лещ" | "аеслинайду" => Some("**case** — ветвь switch"),
         "нуичо" | "пахану" | "апохуй" | "наотыбись" => {
@@ -75,6 +85,15 @@ mod tests {
         assert!(keyword_hover("foobar").is_none());
     }
 
+    #[test]
+    fn inferred_type_is_shown_for_identifiers() {
+        let src = "участковый х = [1, 2];\nсказать(х);";
+        let types = yps_types::infer::infer_source(src);
+        let hover = type_hover(&types, src.rfind('х').unwrap()).unwrap();
+        assert_eq!(hover, "**х**: `массив<число>`");
+        assert!(type_hover(&types, src.find("сказать").unwrap()).is_none());
+    }
+
     #[test]
     fn every_keyword_has_a_doc() {
         let missing: Vec<&str> = yps_lexer::KEYWORDS.iter().copied().filter(|k| keyword_hover(k).is_none()).collect();
@@ -0,0 +1,142 @@
+use tower_lsp::lsp_types::{InlayHint, InlayHintKind, InlayHintLabel};
+use yps_lexer::{Lexer, SourceFile, Span};
+use yps_parser::Parser;
+use yps_parser::ast::{Expr, Literal, Param, Pattern, Stmt};
+
+use crate::position::byte_to_pos;
+use crate::signature_help::collect_functions;
+use crate::walk::{Node, walk};
+use yps_types::infer::{Type, TypeInfo};
+
+/// Parameter names at call sites of user functions and inferred types of `ясенХуй` bindings
+/// whose initializer is not a plain literal. Only hints starting inside `range` are returned.
+#[must_use]
+pub fn inlay_hints(text: &str, types: &TypeInfo, range: Span) -> Vec<InlayHint> {
+    let sf = SourceFile::new("inline".to_string(), text.to_string());
+    let (tokens, _) = Lexer::new(&sf).tokenize();
+    let (program, _) = Parser::new(&tokens, &sf).parse_program();
+    let mut functions = Vec::new();
+    collect_functions(&program.items, &mut functions);
+
+    let mut hints: Vec<(usize, String, InlayHintKind)> = Vec::new();
+    walk(&program.items, &mut |node| match node {
+        Node::Expr(Expr::Call { callee, args, .. } | Expr::OptionalCall { callee, args, .. }) => {
+            let name = match callee.as_ref() {
+                Expr::Identifier(ident) => Some(&ident.name),
+                Expr::Member { object, property, .. } | Expr::OptionalMember { object, property, .. }
+                    if matches!(types.type_at(object.span().start), Some((_, Type::Instance(_)))) =>
+                {
+                    Some(&property.name)
+                }
+                _ => None,
+            };
+            if let Some((_, params)) = name.and_then(|name| functions.iter().find(|(f, _)| *f == name.as_str())) {
+                hints.extend(
+                    parameter_hints(params, args).into_iter().map(|(at, label)| (at, label, InlayHintKind::PARAMETER)),
+                );
+            }
+        }
+        Node::Stmt(Stmt::VarDecl { pattern: Pattern::Identifier(ident), init, is_const: true, .. })
+            if !is_plain_literal(init) =>
+        {
+            if let Some((_, ty)) = types.type_at(ident.span.start).filter(|(_, ty)| ty.is_known()) {
+                hints.push((ident.span.end, format!(": {ty}"), InlayHintKind::TYPE));
+            }
+        }
+        _ => {}
+    });
+
+    hints
+        .into_iter()
+        .filter(|(at, ..)| range.start <= *at && *at <= range.end)
+        .map(|(at, label, kind)| InlayHint {
+            position: byte_to_pos(text, at),
+            label: InlayHintLabel::String(label),
+            kind: Some(kind),
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: Some(kind == InlayHintKind::PARAMETER),
+            data: None,
+        })
+        .collect()
+}
+
+fn parameter_hints(params: &[Param], args: &[Expr]) -> Vec<(usize, String)> {
+    let mut out = Vec::new();
+    for (i, arg) in args.iter().enumerate() {
+        let Some(param) = params.get(i) else { break };
+        if matches!(arg, Expr::Spread { .. }) || param.pattern.is_some() {
+            break;
+        }
+        if param.is_rest {
+            out.push((arg.span().start, format!("...{}:", param.name.name)));
+            break;
+        }
+        if matches!(arg, Expr::Identifier(ident) if ident.name == param.name.name) {
+            continue;
+        }
+        out.push((arg.span().start, format!("{}:", param.name.name)));
+    }
+    out
+}
+
+fn is_plain_literal(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Literal(
+            Literal::Number { .. }
+                | Literal::String { .. }
+                | Literal::Boolean { .. }
+                | Literal::BigInt { .. }
+                | Literal::Null { .. }
+                | Literal::Undefined { .. }
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(src: &str) -> Vec<(u32, u32, String)> {
+        let types = yps_types::infer::infer_source(src);
+        inlay_hints(src, &types, Span { start: 0, end: src.len() })
+            .into_iter()
+            .map(|h| match h.label {
+                InlayHintLabel::String(label) => (h.position.line, h.position.character, label),
+                InlayHintLabel::LabelParts(_) => panic!("unexpected label parts"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parameter_names_are_shown_at_call_sites() {
+        let src = "йопта фу(а, б) { отвечаю а; }\nфу(1, 2);";
+        let labels: Vec<String> = hints(src).into_iter().map(|(_, _, l)| l).collect();
+        assert_eq!(labels, ["а:", "б:"]);
+        assert_eq!(hints(src)[1].0, 1);
+        assert_eq!(hints(src)[1].1, 6);
+    }
+
+    #[test]
+    fn matching_argument_names_and_builtins_get_no_hint() {
+        let src = "йопта фу(а, ...ост) { отвечаю а; }\nгыы а = 1;\nфу(а, 2, 3);\nсказать(а);";
+        let labels: Vec<String> = hints(src).into_iter().map(|(_, _, l)| l).collect();
+        assert_eq!(labels, ["...ост:"]);
+    }
+
+    #[test]
+    fn const_bindings_show_inferred_types() {
+        let src = "ясенХуй х = [1, 2];\nясенХуй у = 5;\nгыы з = [1];";
+        let hints = hints(src);
+        assert_eq!(hints, [(0, 9, ": массив<число>".to_string())]);
+    }
+
+    #[test]
+    fn hints_outside_the_range_are_dropped() {
+        let src = "йопта фу(а) { отвечаю а; }\nфу(1);";
+        let types = yps_types::infer::infer_source(src);
+        assert!(inlay_hints(src, &types, Span { start: 0, end: 5 }).is_empty());
+    }
+}
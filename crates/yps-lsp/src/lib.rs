@@ -1,34 +1,48 @@
+pub mod analysis;
 pub mod builtins;
 pub mod code_actions;
+pub mod code_lens;
+pub mod commands;
 pub mod completion;
 pub mod definition;
 pub mod diagnostics;
+pub mod document;
+pub mod folding;
 pub mod format;
+pub mod hierarchy;
+pub mod highlight;
 pub mod hover;
+pub mod inlay_hints;
 pub mod lint;
 pub mod position;
+pub mod refactor;
 pub mod references;
 pub mod rename;
+pub mod selection;
 pub mod semantic_tokens;
 pub mod signature_help;
 pub mod symbols;
 pub mod types;
+pub mod walk;
+pub mod workspace;
 
-use tower_lsp::lsp_types::{
-    CodeActionKind, CodeActionOptions, CodeActionProviderCapability, CompletionOptions, Diagnostic, DocumentSymbol,
-    HoverProviderCapability, OneOf, RenameOptions, SemanticTokensFullOptions, SemanticTokensOptions,
-    SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelpOptions, TextDocumentSyncCapability,
-    TextDocumentSyncKind, WorkDoneProgressOptions,
-};
-use yps_lexer::{Lexer, SourceFile};
-use yps_parser::Parser;
+use std::sync::Arc;
 
 use crate::definition::Declaration;
+use crate::document::Document;
+use tower_lsp::lsp_types::{
+    CallHierarchyServerCapability, CodeActionKind, CodeActionOptions, CodeActionProviderCapability, CodeLensOptions,
+    CompletionOptions, Diagnostic, DocumentSymbol, ExecuteCommandOptions, FoldingRangeProviderCapability,
+    HoverProviderCapability, OneOf, RenameOptions, SelectionRangeProviderCapability, SemanticTokensFullOptions,
+    SemanticTokensOptions, SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelpOptions,
+    TextDocumentContentChangeEvent, TextDocumentSyncCapability, TextDocumentSyncKind, WorkDoneProgressOptions,
+};
+use yps_types::infer;
 
 #[must_use]
 pub fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
-        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::INCREMENTAL)),
         completion_provider: Some(CompletionOptions {
             trigger_characters: Some(vec![".".to_string()]),
             ..Default::default()
@@ -42,6 +56,7 @@ pub fn server_capabilities() -> ServerCapabilities {
             work_done_progress_options: WorkDoneProgressOptions::default(),
         })),
         references_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
         semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
             SemanticTokensOptions {
                 legend: semantic_tokens::legend(),
@@ -56,10 +71,26 @@ pub fn server_capabilities() -> ServerCapabilities {
             work_done_progress_options: WorkDoneProgressOptions::default(),
         }),
         code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
-            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+            code_action_kinds: Some(vec![
+                CodeActionKind::QUICKFIX,
+                CodeActionKind::REFACTOR_EXTRACT,
+                CodeActionKind::REFACTOR_INLINE,
+                CodeActionKind::REFACTOR_REWRITE,
+                CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+            ]),
             resolve_provider: None,
             work_done_progress_options: WorkDoneProgressOptions::default(),
         })),
+        inlay_hint_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        document_highlight_provider: Some(OneOf::Left(true)),
+        selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+        call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+        code_lens_provider: Some(CodeLensOptions { resolve_provider: Some(false) }),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: commands::SERVER_COMMANDS.iter().map(ToString::to_string).collect(),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        }),
         ..Default::default()
     }
 }
@@ -70,25 +101,68 @@ pub struct Analyzed {
     pub symbols: Vec<DocumentSymbol>,
     pub declarations: Vec<Declaration>,
     pub lint: yps_lint::LintResult,
+    pub types: infer::TypeInfo,
+}
+
+impl Analyzed {
+    /// A document whose analysis has not finished yet.
+    #[must_use]
+    pub fn pending(text: String) -> Self {
+        Self {
+            text,
+            diagnostics: Vec::new(),
+            symbols: Vec::new(),
+            declarations: Vec::new(),
+            lint: yps_lint::LintResult { diagnostics: Vec::new(), parse_errors: Vec::new() },
+            types: infer::TypeInfo::default(),
+        }
+    }
+}
+
+/// An open document: its text as last edited, and the latest finished analysis. The analysis
+/// keeps the text it was made from, since its spans are byte offsets into that text and no
+/// other; it lags behind the edits until the background analysis catches up.
+pub struct OpenDocument {
+    text: String,
+    analyzed: Arc<Analyzed>,
+}
+
+impl OpenDocument {
+    #[must_use]
+    pub fn new(analyzed: Analyzed) -> Self {
+        Self { text: analyzed.text.clone(), analyzed: Arc::new(analyzed) }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The latest analysis; spans in it index `analyzed.text`, not `text()`.
+    #[must_use]
+    pub fn analyzed(&self) -> Arc<Analyzed> {
+        Arc::clone(&self.analyzed)
+    }
+
+    pub fn edit(&mut self, changes: &[TextDocumentContentChangeEvent]) {
+        for change in changes {
+            document::apply_change(&mut self.text, change);
+        }
+    }
+
+    /// Takes `analyzed` unless the document was edited again after it started.
+    pub fn accept(&mut self, analyzed: Analyzed) -> bool {
+        if analyzed.text != self.text {
+            return false;
+        }
+        self.analyzed = Arc::new(analyzed);
+        true
+    }
 }
 
 #[must_use]
 pub fn analyze(text: &str) -> Analyzed {
-    let sf = SourceFile::new("inline".to_string(), text.to_string());
-    let (tokens, lex_diags) = Lexer::new(&sf).tokenize();
-    let (program, parse_diags) = Parser::new(&tokens, &sf).parse_program();
-    let lint = yps_lint::lint_source(text);
-
-    let mut diagnostics = diagnostics::to_lsp_diagnostics(text, &lex_diags, &parse_diags);
-    diagnostics.extend(lint::to_lsp_diagnostics(text, &lint.diagnostics));
-
-    Analyzed {
-        diagnostics,
-        symbols: symbols::document_symbols(&program, text),
-        declarations: definition::declarations(&program),
-        lint,
-        text: text.to_string(),
-    }
+    Document::new(text).analyze(|| false).expect("анализ без отмены всегда завершается")
 }
 
 #[cfg(test)]
@@ -109,12 +183,26 @@ mod tests {
         assert!(caps.text_document_sync.is_some());
         assert!(caps.rename_provider.is_some());
         assert!(caps.references_provider.is_some());
+        assert!(caps.workspace_symbol_provider.is_some());
         assert!(caps.semantic_tokens_provider.is_some());
         assert_eq!(
             caps.signature_help_provider.as_ref().and_then(|s| s.trigger_characters.clone()),
             Some(vec!["(".to_string(), ",".to_string()])
         );
-        assert!(caps.code_action_provider.is_some());
+        let Some(CodeActionProviderCapability::Options(code_actions)) = &caps.code_action_provider else {
+            panic!("code actions");
+        };
+        assert!(code_actions.code_action_kinds.as_ref().is_some_and(|k| k.contains(&CodeActionKind::REFACTOR_EXTRACT)));
+        assert!(caps.inlay_hint_provider.is_some());
+        assert!(caps.folding_range_provider.is_some());
+        assert!(caps.document_highlight_provider.is_some());
+        assert!(caps.selection_range_provider.is_some());
+        assert!(caps.call_hierarchy_provider.is_some());
+        assert!(caps.code_lens_provider.is_some());
+        assert_eq!(
+            caps.execute_command_provider.map(|c| c.commands),
+            Some(vec![commands::RUN.to_string(), commands::DEBUG.to_string()])
+        );
     }
 
     #[test]
@@ -125,9 +213,39 @@ mod tests {
     }
 
     #[test]
-    fn broken_source_publishes_no_lint_diagnostics() {
-        let analyzed = analyze("йопта (");
-        assert!(analyzed.lint.diagnostics.is_empty());
-        assert!(!analyzed.diagnostics.iter().any(|d| d.source.as_deref() == Some(lint::SOURCE)));
+    fn unknown_methods_are_published_as_type_warnings() {
+        let analyzed = analyze("ясенХуй х = [1];\nх.вВерхнийРегистр();\n");
+        let warning = analyzed.diagnostics.iter().find(|d| d.source.as_deref() == Some(types::SOURCE));
+        assert!(warning.unwrap().message.contains("вВерхнийРегистр"));
+    }
+
+    #[test]
+    fn edits_never_pair_an_old_analysis_with_the_new_text() {
+        let mut document = OpenDocument::new(analyze("гыы а = 1;\n"));
+        let at = tower_lsp::lsp_types::Position::new(0, 0);
+        document.edit(&[TextDocumentContentChangeEvent {
+            range: Some(tower_lsp::lsp_types::Range { start: at, end: at }),
+            range_length: None,
+            text: "ж".to_string(),
+        }]);
+        assert_eq!(document.text(), "жгыы а = 1;\n");
+
+        let analyzed = document.analyzed();
+        assert_eq!(analyzed.text, "гыы а = 1;\n");
+        let unused = &analyzed.lint.diagnostics[0];
+        let (_, edits) = code_actions::quick_fix(&analyzed.text, unused).expect("переименование в _а");
+        assert_eq!(edits[0].new_text, "_а");
+
+        assert!(!document.accept(analyze("гыы а = 1;\n")), "анализ устаревшего текста");
+        assert!(document.accept(analyze("жгыы а = 1;\n")));
+        assert_eq!(document.analyzed().text, document.text());
+    }
+
+    #[test]
+    fn broken_source_keeps_symbols_and_lint_of_the_valid_parts() {
+        let analyzed = analyze("йопта ф() {\n  отвечаю 1;\n  сказать(2);\n}\nгыы х = ;\nйопта г() {}\n");
+        assert!(analyzed.diagnostics.iter().any(|d| d.source.as_deref() == Some(lint::SOURCE)));
+        let names: Vec<&str> = analyzed.symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["ф", "х", "г"]);
     }
 }
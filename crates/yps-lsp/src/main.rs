@@ -1,37 +1,97 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::sync::RwLock;
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{Error, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
+use yps_lexer::Span;
+use yps_lsp::analysis::Analyzer;
 use yps_lsp::builtins::builtin_doc;
 use yps_lsp::code_actions::quick_fix;
+use yps_lsp::code_lens::{exported_declarations, references_lens, run_lenses};
+use yps_lsp::commands::{self, debug_configuration, program_path, run_command, use_vm};
 use yps_lsp::completion::completion_items;
 use yps_lsp::definition::goto_definition;
+use yps_lsp::diagnostics;
+use yps_lsp::folding::folding_ranges;
 use yps_lsp::format::format_document;
-use yps_lsp::hover::keyword_hover;
+use yps_lsp::highlight::document_highlights;
+use yps_lsp::hover::{keyword_hover, type_hover};
+use yps_lsp::inlay_hints::inlay_hints;
 use yps_lsp::lint;
 use yps_lsp::position::{pos_to_byte, span_to_range, word_at};
+use yps_lsp::refactor::{add_import, refactorings, unresolved_name};
 use yps_lsp::references::references;
 use yps_lsp::rename::{prepare, rename_edits};
+use yps_lsp::selection::selection_ranges;
 use yps_lsp::semantic_tokens::semantic_tokens_full;
 use yps_lsp::signature_help::signature_help;
-use yps_lsp::types::{member_doc, type_doc};
-use yps_lsp::{Analyzed, analyze};
+use yps_lsp::workspace::WorkspaceIndex;
+use yps_lsp::{Analyzed, OpenDocument, analyze};
+use yps_types::catalog::{member_doc, type_doc};
 
 struct Backend {
     client: Client,
-    documents: Arc<RwLock<HashMap<Url, Arc<Analyzed>>>>,
+    documents: Arc<RwLock<HashMap<Url, OpenDocument>>>,
+    workspace: Arc<RwLock<WorkspaceIndex>>,
+    analyzer: Analyzer,
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        #[allow(deprecated)]
+        let roots: Vec<Url> = match params.workspace_folders {
+            Some(folders) => folders.into_iter().map(|f| f.uri).collect(),
+            None => params.root_uri.into_iter().collect(),
+        };
+        // Large folders take a while to read, so indexing runs off to the side and requests made
+        // meanwhile answer from the open documents alone.
+        let roots: Vec<PathBuf> = roots.iter().filter_map(|uri| uri.to_file_path().ok()).collect();
+        let workspace = Arc::clone(&self.workspace);
+        tokio::spawn(async move {
+            let scanned = tokio::task::spawn_blocking(move || {
+                let mut index = WorkspaceIndex::default();
+                for root in &roots {
+                    index.index_folder(root);
+                }
+                index
+            });
+            if let Ok(index) = scanned.await {
+                workspace.write().await.merge(index);
+            }
+        });
         Ok(InitializeResult { capabilities: yps_lsp::server_capabilities(), ..Default::default() })
     }
 
     async fn initialized(&self, _: InitializedParams) {
+        let watchers = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String("**/*.yopta".to_string()),
+                kind: None,
+            }],
+        };
+        let registration = Registration {
+            id: "yps-watch-yopta".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(watchers).ok(),
+        };
+        if self.client.register_capability(vec![registration]).await.is_err() {
+            self.client.log_message(MessageType::WARNING, "file watching is unavailable").await;
+        }
+        // `ServerCapabilities` has no type hierarchy field yet, so it is registered dynamically.
+        let registration = Registration {
+            id: "yps-type-hierarchy".to_string(),
+            method: "textDocument/prepareTypeHierarchy".to_string(),
+            register_options: serde_json::to_value(TypeHierarchyRegistrationOptions::default()).ok(),
+        };
+        if self.client.register_capability(vec![registration]).await.is_err() {
+            self.client.log_message(MessageType::WARNING, "type hierarchy is unavailable").await;
+        }
         self.client.log_message(MessageType::INFO, "yps-lsp initialized").await;
     }
 
@@ -40,26 +100,105 @@ impl LanguageServer for Backend {
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        self.update_document(params.text_document.uri, &params.text_document.text).await;
+        let TextDocumentItem { uri, text, .. } = params.text_document;
+        // Analyzed here rather than on the analysis thread, so requests have results right away.
+        self.update_document(uri.clone(), &text).await;
+        self.analyzer.open(uri, text);
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri;
-        if let Some(change) = params.content_changes.into_iter().last() {
-            self.update_document(uri, &change.text).await;
+        // Requests that only read the text see the edit right away; the rest keep answering from
+        // the previous analysis and its own text until the next one lands.
+        if let Some(document) = self.documents.write().await.get_mut(&uri) {
+            document.edit(&params.content_changes);
         }
+        self.analyzer.change(uri, params.content_changes);
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
-        self.documents.write().await.remove(&params.text_document.uri);
+        let uri = params.text_document.uri;
+        self.documents.write().await.remove(&uri);
+        self.analyzer.close(uri.clone());
+        if let Ok(path) = uri.to_file_path() {
+            self.workspace.write().await.reload(&path);
+        }
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let open = self.documents.read().await;
+        let mut workspace = self.workspace.write().await;
+        for change in params.changes {
+            let Ok(path) = change.uri.to_file_path() else { continue };
+            if change.typ == FileChangeType::DELETED {
+                workspace.remove(&path);
+            } else if !open.contains_key(&change.uri) {
+                workspace.reload(&path);
+            }
+        }
+    }
+
+    async fn symbol(&self, params: WorkspaceSymbolParams) -> Result<Option<Vec<SymbolInformation>>> {
+        Ok(Some(self.workspace.read().await.symbols(&params.query)))
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+        let (Some(analyzed), Ok(path)) = (self.get_document(uri).await, uri.to_file_path()) else {
+            return Ok(None);
+        };
+
+        let byte_pos = pos_to_byte(&analyzed.text, pos);
+        Ok(self.workspace.read().await.prepare_call_hierarchy(&path, byte_pos))
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        Ok(Some(self.workspace.read().await.incoming_calls(&params.item)))
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        Ok(Some(self.workspace.read().await.outgoing_calls(&params.item)))
+    }
+
+    async fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+        let (Some(analyzed), Ok(path)) = (self.get_document(uri).await, uri.to_file_path()) else {
+            return Ok(None);
+        };
+
+        let byte_pos = pos_to_byte(&analyzed.text, pos);
+        Ok(self.workspace.read().await.prepare_type_hierarchy(&path, byte_pos))
+    }
+
+    async fn supertypes(&self, params: TypeHierarchySupertypesParams) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(Some(self.workspace.read().await.supertypes(&params.item)))
+    }
+
+    async fn subtypes(&self, params: TypeHierarchySubtypesParams) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(Some(self.workspace.read().await.subtypes(&params.item)))
     }
 
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
         let uri = &params.text_document_position.text_document.uri;
         let pos = params.text_document_position.position;
         let analyzed = self.get_document(uri).await.unwrap_or_else(|| Arc::new(analyze("")));
-        let cursor = pos_to_byte(&analyzed.text, pos);
-        Ok(Some(CompletionResponse::Array(completion_items(&analyzed.symbols, &analyzed.text, Some(cursor)))))
+        let text = self.get_text(uri).await.unwrap_or_default();
+        let cursor = pos_to_byte(&text, pos);
+        Ok(Some(CompletionResponse::Array(completion_items(&analyzed.symbols, &text, Some(cursor)))))
     }
 
     async fn document_symbol(&self, params: DocumentSymbolParams) -> Result<Option<DocumentSymbolResponse>> {
@@ -77,15 +216,25 @@ impl LanguageServer for Backend {
             return Ok(None);
         };
 
+        if let Ok(path) = uri.to_file_path() {
+            let workspace = self.workspace.read().await;
+            let byte_pos = pos_to_byte(&analyzed.text, pos);
+            if let Some(target) = workspace.definition(&path, byte_pos)
+                && target.0 != yps_lsp::workspace::canonical(&path)
+            {
+                return Ok(workspace.locations(&[target]).pop().map(GotoDefinitionResponse::Scalar));
+            }
+        }
+
         Ok(goto_definition(&analyzed.declarations, &analyzed.text, pos)
             .map(|span| GotoDefinitionResponse::Scalar(Location { uri, range: span_to_range(&analyzed.text, span) })))
     }
 
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
-        let Some(analyzed) = self.get_document(&params.text_document.uri).await else {
+        let Some(text) = self.get_text(&params.text_document.uri).await else {
             return Ok(None);
         };
-        Ok(format_document(&analyzed.text))
+        Ok(format_document(&text))
     }
 
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
@@ -104,8 +253,8 @@ impl LanguageServer for Backend {
             return Ok(None);
         }
 
-        let doc = keyword_hover(word)
-            .map(str::to_string)
+        let doc = type_hover(&analyzed.types, byte_pos)
+            .or_else(|| keyword_hover(word).map(str::to_string))
             .or_else(|| builtin_doc(word).map(str::to_string))
             .or_else(|| type_doc(word))
             .or_else(|| member_doc(word));
@@ -138,6 +287,18 @@ impl LanguageServer for Backend {
         };
 
         let byte_pos = pos_to_byte(&analyzed.text, pos);
+        if let Some(edits) =
+            self.workspace_query(&uri, |ws, path| ws.rename_edits(path, byte_pos, &params.new_name)).await
+        {
+            let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+            for location in edits {
+                changes
+                    .entry(location.uri)
+                    .or_default()
+                    .push(TextEdit { range: location.range, new_text: params.new_name.clone() });
+            }
+            return Ok(Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }));
+        }
         let Some(spans) = rename_edits(&analyzed.text, byte_pos, &params.new_name) else {
             return Ok(None);
         };
@@ -162,6 +323,11 @@ impl LanguageServer for Backend {
         };
 
         let byte_pos = pos_to_byte(&analyzed.text, pos);
+        if let Some(locations) =
+            self.workspace_query(&uri, |ws, path| ws.references(path, byte_pos, include_declaration)).await
+        {
+            return Ok(Some(locations));
+        }
         let Some(spans) = references(&analyzed.text, byte_pos, include_declaration) else {
             return Ok(None);
         };
@@ -174,12 +340,50 @@ impl LanguageServer for Backend {
         ))
     }
 
-    async fn semantic_tokens_full(&self, params: SemanticTokensParams) -> Result<Option<SemanticTokensResult>> {
+    async fn document_highlight(&self, params: DocumentHighlightParams) -> Result<Option<Vec<DocumentHighlight>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+        let Some(text) = self.get_text(uri).await else {
+            return Ok(None);
+        };
+
+        let byte_pos = pos_to_byte(&text, pos);
+        Ok(document_highlights(&text, byte_pos))
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
         let Some(analyzed) = self.get_document(&params.text_document.uri).await else {
             return Ok(None);
         };
 
-        let data = semantic_tokens_full(&analyzed.text);
+        let text = &analyzed.text;
+        let range = Span { start: pos_to_byte(text, params.range.start), end: pos_to_byte(text, params.range.end) };
+        Ok(Some(inlay_hints(text, &analyzed.types, range)))
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let Some(text) = self.get_text(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+
+        Ok(Some(folding_ranges(&text)))
+    }
+
+    async fn selection_range(&self, params: SelectionRangeParams) -> Result<Option<Vec<SelectionRange>>> {
+        let Some(text) = self.get_text(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+
+        let positions: Vec<usize> = params.positions.iter().map(|&pos| pos_to_byte(&text, pos)).collect();
+        Ok(Some(selection_ranges(&text, &positions)))
+    }
+
+    async fn semantic_tokens_full(&self, params: SemanticTokensParams) -> Result<Option<SemanticTokensResult>> {
+        let Some(text) = self.get_text(&params.text_document.uri).await else {
+            return Ok(None);
+        };
+
+        let data = semantic_tokens_full(&text);
         Ok(Some(SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data })))
     }
 
@@ -187,12 +391,12 @@ impl LanguageServer for Backend {
         let uri = &params.text_document_position_params.text_document.uri;
         let pos = params.text_document_position_params.position;
 
-        let Some(analyzed) = self.get_document(uri).await else {
+        let Some(text) = self.get_text(uri).await else {
             return Ok(None);
         };
 
-        let byte_pos = pos_to_byte(&analyzed.text, pos);
-        Ok(signature_help(&analyzed.text, byte_pos))
+        let byte_pos = pos_to_byte(&text, pos);
+        Ok(signature_help(&text, byte_pos))
     }
 
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
@@ -204,6 +408,26 @@ impl LanguageServer for Backend {
 
         let mut actions = Vec::new();
         for lsp_diag in &params.context.diagnostics {
+            if lsp_diag.source.as_deref() == Some(diagnostics::SOURCE) {
+                let syntax = analyzed.lint.parse_errors.iter().filter(|d| {
+                    span_to_range(&analyzed.text, d.span) == lsp_diag.range && lsp_diag.message == d.message
+                });
+                for syntax_diag in syntax {
+                    for (title, edit, preferred) in diagnostics::suggestion_fixes(&analyzed.text, syntax_diag) {
+                        let mut changes = HashMap::new();
+                        changes.insert(uri.clone(), vec![edit]);
+                        actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                            title,
+                            kind: Some(CodeActionKind::QUICKFIX),
+                            diagnostics: Some(vec![lsp_diag.clone()]),
+                            edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+                            is_preferred: Some(preferred),
+                            ..Default::default()
+                        }));
+                    }
+                }
+                continue;
+            }
             if lsp_diag.source.as_deref() != Some(lint::SOURCE) {
                 continue;
             }
@@ -230,28 +454,186 @@ impl LanguageServer for Backend {
             }));
         }
 
+        // Refactorings edit the text as it is now, not the one the diagnostics above came from.
+        let Some(text) = self.get_text(&uri).await else {
+            return Ok((!actions.is_empty()).then_some(actions));
+        };
+        let text = &text;
+        let start = pos_to_byte(text, params.range.start);
+        let selection = Span { start, end: pos_to_byte(text, params.range.end).max(start) };
+        if let Some(name) = unresolved_name(text, start)
+            && let Ok(path) = uri.to_file_path()
+        {
+            let sources = self.workspace.read().await.import_sources(&path, &name);
+            for source in sources {
+                let Some(edit) = add_import(text, &name, &source) else { continue };
+                let mut changes = HashMap::new();
+                changes.insert(uri.clone(), vec![edit]);
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Импортировать «{name}» из \"{source}\""),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+                    ..Default::default()
+                }));
+            }
+        }
+        for refactoring in refactorings(text, selection) {
+            let mut changes = HashMap::new();
+            changes.insert(uri.clone(), vec![refactoring.edit]);
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: refactoring.title,
+                kind: Some(refactoring.kind),
+                edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+                ..Default::default()
+            }));
+        }
+
+        if let Some(only) = &params.context.only {
+            actions.retain(|action| match action {
+                CodeActionOrCommand::CodeAction(CodeAction { kind: Some(kind), .. }) => {
+                    only.iter().any(|wanted| kind.as_str().starts_with(wanted.as_str()))
+                }
+                _ => true,
+            });
+        }
+
         Ok((!actions.is_empty()).then_some(actions))
     }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        let uri = params.text_document.uri;
+        let Some(analyzed) = self.get_document(&uri).await else {
+            return Ok(None);
+        };
+
+        let text = &analyzed.text;
+        let mut lenses = run_lenses(&uri, text);
+        for name in exported_declarations(text) {
+            let references =
+                self.workspace_query(&uri, |ws, path| ws.references(path, name.start, false)).await.unwrap_or_default();
+            lenses.push(references_lens(&uri, text, name, &references));
+        }
+        Ok(Some(lenses))
+    }
+
+    async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
+        let Some(program) = program_path(&params.arguments) else {
+            return Err(Error::invalid_params("ожидался URI файла"));
+        };
+        match params.command.as_str() {
+            commands::RUN => {
+                tokio::spawn(run_program(self.client.clone(), program, use_vm(&params.arguments)));
+                Ok(None)
+            }
+            commands::DEBUG => Ok(Some(debug_configuration(&program))),
+            other => Err(Error::invalid_params(format!("неизвестная команда «{other}»"))),
+        }
+    }
 }
 
 impl Backend {
+    /// The latest analysis, together with the text its spans index.
     async fn get_document(&self, uri: &Url) -> Option<Arc<Analyzed>> {
-        self.documents.read().await.get(uri).cloned()
+        self.documents.read().await.get(uri).map(OpenDocument::analyzed)
+    }
+
+    /// The text as last edited.
+    async fn get_text(&self, uri: &Url) -> Option<String> {
+        self.documents.read().await.get(uri).map(|document| document.text().to_string())
+    }
+
+    /// Runs a cross-file query when `uri` is a file in the workspace index.
+    async fn workspace_query(
+        &self,
+        uri: &Url,
+        query: impl FnOnce(&WorkspaceIndex, &std::path::Path) -> Option<Vec<(std::path::PathBuf, Span)>>,
+    ) -> Option<Vec<Location>> {
+        let path = uri.to_file_path().ok()?;
+        let workspace = self.workspace.read().await;
+        if !workspace.contains(&path) {
+            return None;
+        }
+        query(&workspace, &path).map(|spans| workspace.locations(&spans))
     }
 
     async fn update_document(&self, uri: Url, text: &str) {
-        let analyzed = Arc::new(analyze(text));
+        if let Ok(path) = uri.to_file_path() {
+            self.workspace.write().await.update(&path, text);
+        }
+        let analyzed = analyze(text);
         let diagnostics = analyzed.diagnostics.clone();
-        self.documents.write().await.insert(uri.clone(), analyzed);
+        self.documents.write().await.insert(uri.clone(), OpenDocument::new(analyzed));
         self.client.publish_diagnostics(uri, diagnostics, None).await;
     }
 }
 
+/// Stores a finished background analysis unless the document was edited or closed meanwhile.
+async fn publish_analysis(
+    client: &Client,
+    documents: &RwLock<HashMap<Url, OpenDocument>>,
+    workspace: &RwLock<WorkspaceIndex>,
+    uri: Url,
+    analyzed: Analyzed,
+) {
+    let mut documents = documents.write().await;
+    let Some(document) = documents.get_mut(&uri).filter(|document| document.text() == analyzed.text) else {
+        return;
+    };
+    if let Ok(path) = uri.to_file_path() {
+        workspace.write().await.update(&path, &analyzed.text);
+    }
+    let diagnostics = analyzed.diagnostics.clone();
+    document.accept(analyzed);
+    drop(documents);
+    client.publish_diagnostics(uri, diagnostics, None).await;
+}
+
+/// Runs a program with `yps-cli`, forwarding stdout as info and stderr as error log messages.
+async fn run_program(client: Client, program: PathBuf, vm: bool) {
+    let mut child = match run_command(&program, vm).spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            client.log_message(MessageType::ERROR, format!("не удалось запустить yps-cli: {err}")).await;
+            return;
+        }
+    };
+    client.log_message(MessageType::INFO, format!("▶ {}", program.display())).await;
+    if let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) {
+        tokio::join!(
+            forward_lines(client.clone(), stdout, MessageType::INFO),
+            forward_lines(client.clone(), stderr, MessageType::ERROR)
+        );
+    }
+    let message = match child.wait().await {
+        Ok(status) if status.success() => (MessageType::INFO, "программа завершилась".to_string()),
+        Ok(status) => (MessageType::ERROR, format!("программа завершилась: {status}")),
+        Err(err) => (MessageType::ERROR, format!("ошибка ожидания процесса: {err}")),
+    };
+    client.log_message(message.0, message.1).await;
+}
+
+async fn forward_lines(client: Client, stream: impl AsyncRead + Unpin, kind: MessageType) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        client.log_message(kind, line).await;
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
-    let (service, socket) =
-        LspService::new(|client| Backend { client, documents: Arc::new(RwLock::new(HashMap::new())) });
+    let (service, socket) = LspService::new(|client| {
+        let documents = Arc::new(RwLock::new(HashMap::new()));
+        let workspace = Arc::new(RwLock::new(WorkspaceIndex::default()));
+        let analyzer = Analyzer::spawn({
+            let (client, documents, workspace) = (client.clone(), Arc::clone(&documents), Arc::clone(&workspace));
+            let runtime = tokio::runtime::Handle::current();
+            move |uri, analyzed| {
+                runtime.block_on(publish_analysis(&client, &documents, &workspace, uri, analyzed));
+            }
+        });
+        Backend { client, documents, workspace, analyzer }
+    });
     Server::new(stdin, stdout, socket).serve(service).await;
 }
@@ -76,6 +76,12 @@ pub fn word_at(src: &str, byte_pos: usize) -> &str {
 
 #[must_use]
 pub fn member_receiver(src: &str, byte_pos: usize) -> Option<&str> {
+    member_receiver_span(src, byte_pos).map(|span| &src[span.start..span.end])
+}
+
+/// Byte span of the receiver in `receiver.member|`; empty when the dot follows a non-identifier.
+#[must_use]
+pub fn member_receiver_span(src: &str, byte_pos: usize) -> Option<Span> {
     let clamped = clamp_to_boundary(src, byte_pos);
     let member_start = ident_start(src, clamped);
 
@@ -92,7 +98,7 @@ pub fn member_receiver(src: &str, byte_pos: usize) -> Option<&str> {
         return None;
     }
 
-    Some(receiver)
+    Some(Span { start: recv_start, end: recv_end })
 }
 
 #[cfg(test)]
@@ -0,0 +1,1197 @@
+use std::collections::BTreeMap;
+
+use tower_lsp::lsp_types::{CodeActionKind, TextEdit};
+use yps_lexer::{KeywordKind, Lexer, PunctuationKind, SourceFile, Span, Token, TokenKind};
+use yps_parser::ast::{BinaryOp, Block, Expr, ImportSpec, Literal, Param, Pattern, Stmt, UnaryOp};
+use yps_parser::{Parser, Program};
+
+use crate::position::span_to_range;
+use crate::rename::{BindingInfo, bindings, is_assignment, unresolved_at};
+use crate::walk::{Node, walk, walk_node};
+
+/// A refactoring applicable at the selection; the edit is already formatted with `yps-fmt`.
+pub struct Refactoring {
+    pub title: String,
+    pub kind: CodeActionKind,
+    pub edit: TextEdit,
+}
+
+/// Every refactoring that applies to `selection`. Broken sources get none.
+#[must_use]
+pub fn refactorings(text: &str, selection: Span) -> Vec<Refactoring> {
+    let Some(src) = Source::parse(text) else { return Vec::new() };
+    let selection = src.trim(selection);
+    let candidates = [
+        src.extract_variable(selection),
+        src.extract_function(selection),
+        src.inline_variable(selection.start),
+        src.inline_function(selection.start),
+        src.convert_function(selection.start),
+        src.promise_chain_to_async(selection.start),
+        src.concat_to_template(selection.start),
+        src.organize_imports(),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter_map(|(title, kind, edits)| Some(Refactoring { title, kind, edit: finish(text, edits)? }))
+        .collect()
+}
+
+/// The name under the cursor when nothing in the file declares it: a candidate for an import.
+#[must_use]
+pub fn unresolved_name(text: &str, byte_pos: usize) -> Option<String> {
+    unresolved_at(text, byte_pos)
+}
+
+/// Imports `name` from `source`, extending an existing `спиздить { … }` of that module if any.
+#[must_use]
+pub fn add_import(text: &str, name: &str, source: &str) -> Option<TextEdit> {
+    let src = Source::parse(text)?;
+    let imports: Vec<_> = src.program.items.iter().filter(|s| matches!(s, Stmt::Import { .. })).collect();
+    let existing = imports.iter().find_map(|stmt| match stmt {
+        Stmt::Import { specifiers, source: from, attributes, span } if from == source && attributes.is_empty() => {
+            let names: Option<Vec<&str>> = specifiers
+                .iter()
+                .map(|spec| match spec {
+                    ImportSpec::Named { imported, .. } => Some(imported.name.as_str()),
+                    _ => None,
+                })
+                .collect();
+            names.map(|names| (*span, names))
+        }
+        _ => None,
+    });
+    let edit = match existing {
+        Some((span, mut names)) => {
+            names.push(name);
+            names.sort_unstable();
+            (span, import_line(&names, source))
+        }
+        None => {
+            let at = imports.last().map_or(0, |stmt| stmt.span().end);
+            let line = import_line(&[name], source);
+            let new_text = if at == 0 { format!("{line}\n") } else { format!("\n{line}") };
+            (Span { start: at, end: at }, new_text)
+        }
+    };
+    finish(text, vec![edit])
+}
+
+fn import_line(names: &[&str], source: &str) -> String {
+    format!("спиздить {{ {} }} из {};", names.join(", "), quote(source))
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Applies `edits`, formats the code they put in and narrows the result to the changed region.
+/// Formatting covers the smallest statement around each new text that formats on its own, so the
+/// rest of the document keeps its layout.
+fn finish(text: &str, edits: Vec<(Span, String)>) -> Option<TextEdit> {
+    let (new_text, inserted) = splice_tracked(text, edits);
+    let sf = SourceFile::new("inline".to_string(), new_text.clone());
+    let (tokens, lex_diags) = Lexer::new(&sf).tokenize();
+    let (program, parse_diags) = Parser::new(&tokens, &sf).parse_program();
+    if !lex_diags.is_empty() || !parse_diags.is_empty() {
+        return None;
+    }
+    let mut statements = Vec::new();
+    walk(&program.items, &mut |node| {
+        if let Node::Stmt(stmt) = node {
+            statements.push(stmt.span());
+        }
+    });
+
+    let mut regions: Vec<Span> = Vec::new();
+    for span in inserted {
+        let Some(mut region) = region_around(&new_text, &statements, span)? else { continue };
+        // Regions are whole statements or runs of them, so overlapping ones merge into a run.
+        while let Some(i) = regions.iter().position(|r| r.start < region.end && region.start < r.end) {
+            let other = regions.swap_remove(i);
+            region = Span { start: region.start.min(other.start), end: region.end.max(other.end) };
+        }
+        regions.push(region);
+    }
+    let mut formatted_regions = Vec::new();
+    for region in regions {
+        formatted_regions.push((region, format_region(&new_text, region)?));
+    }
+    let formatted = splice(&new_text, formatted_regions);
+    if formatted == text {
+        return None;
+    }
+    let (old, new) = (text.as_bytes(), formatted.as_bytes());
+    let mut prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    while !text.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old.iter().rev().zip(new.iter().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+    while !text.is_char_boundary(old.len() - suffix) {
+        suffix -= 1;
+    }
+    let range = span_to_range(text, Span { start: prefix, end: old.len() - suffix });
+    Some(TextEdit { range, new_text: formatted[prefix..new.len() - suffix].to_string() })
+}
+
+/// The smallest statement of `text` around the non-blank part of `span` that formats on its own,
+/// or else the run of statements `span` crosses or touches, such as the declaration an inserted
+/// `ассо` precedes. `Some(None)` when `span` is blank, `None` when no candidate parses.
+fn region_around(text: &str, statements: &[Span], span: Span) -> Option<Option<Span>> {
+    let slice = &text[span.start..span.end];
+    let start = span.start + (slice.len() - slice.trim_start().len());
+    let trimmed = Span { start, end: start.max(span.start + slice.trim_end().len()) };
+    if trimmed.start == trimmed.end {
+        return Some(None);
+    }
+    let mut candidates: Vec<Span> = statements.iter().copied().filter(|s| contains(*s, trimmed)).collect();
+    candidates.sort_by_key(|s| s.end - s.start);
+    let crossed = statements.iter().filter(|s| s.start <= span.end && span.start <= s.end);
+    if let (Some(start), Some(end)) = (crossed.clone().map(|s| s.start).min(), crossed.map(|s| s.end).max()) {
+        candidates.push(Span { start: start.min(trimmed.start), end: end.max(trimmed.end) });
+    }
+    candidates.into_iter().find(|region| format_region(text, *region).is_some()).map(Some)
+}
+
+/// The statements at `region` formatted as a program of their own and indented like their first
+/// line; `None` when they do not parse alone.
+fn format_region(text: &str, region: Span) -> Option<String> {
+    let fragment = &text[region.start..region.end];
+    let formatted = match yps_fmt::format_source(fragment) {
+        Ok(outcome) => outcome.text,
+        Err(yps_fmt::FormatError::ParseError(_)) => return None,
+        Err(_) => fragment.to_string(),
+    };
+    let line_start = text[..region.start].rfind('\n').map_or(0, |i| i + 1);
+    let before = &text[line_start..region.start];
+    let indent = &before[..before.len() - before.trim_start().len()];
+    let lines: Vec<String> = formatted
+        .trim_end_matches('\n')
+        .lines()
+        .enumerate()
+        .map(|(i, line)| if i == 0 || line.is_empty() { line.to_string() } else { format!("{indent}{line}") })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// Applies `edits` like [`splice`] and also returns where each new text ended up.
+fn splice_tracked(text: &str, mut edits: Vec<(Span, String)>) -> (String, Vec<Span>) {
+    edits.sort_by_key(|(span, _)| (span.start, span.end));
+    let (mut added, mut removed) = (0, 0);
+    let mut placed = Vec::new();
+    for (span, new_text) in &edits {
+        let start = span.start + added - removed;
+        placed.push(Span { start, end: start + new_text.len() });
+        added += new_text.len();
+        removed += span.end - span.start;
+    }
+    (splice(text, edits), placed)
+}
+
+fn splice(text: &str, mut edits: Vec<(Span, String)>) -> String {
+    edits.sort_by_key(|(span, _)| std::cmp::Reverse((span.start, span.end)));
+    let mut out = text.to_string();
+    for (span, new_text) in edits {
+        out.replace_range(span.start..span.end, &new_text);
+    }
+    out
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+fn within(span: Span, byte_pos: usize) -> bool {
+    span.start <= byte_pos && byte_pos <= span.end
+}
+
+/// Expressions that never need parentheses when spliced into another expression.
+fn is_atomic(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Identifier(_)
+            | Expr::Literal(_)
+            | Expr::Call { .. }
+            | Expr::OptionalCall { .. }
+            | Expr::Member { .. }
+            | Expr::OptionalMember { .. }
+            | Expr::Index { .. }
+            | Expr::OptionalIndex { .. }
+            | Expr::Grouping { .. }
+            | Expr::This { .. }
+            | Expr::TemplateLiteral { .. }
+    )
+}
+
+fn has_side_effects(expr: &Expr) -> bool {
+    let mut found = false;
+    walk_node(Node::Expr(expr), &mut |node| {
+        found |= matches!(
+            node,
+            Node::Expr(
+                Expr::Call { .. }
+                    | Expr::OptionalCall { .. }
+                    | Expr::New { .. }
+                    | Expr::Await { .. }
+                    | Expr::Yield { .. }
+                    | Expr::Postfix { .. }
+                    | Expr::Assignment { .. }
+                    | Expr::TaggedTemplate { .. }
+                    | Expr::DynamicImport { .. }
+                    | Expr::Unary { op: UnaryOp::Delete, .. }
+            )
+        ) || matches!(node, Node::Expr(Expr::Binary { op, .. }) if is_assignment(*op));
+    });
+    found
+}
+
+/// What a piece of code does that would change meaning once it is moved into a new function.
+#[derive(Default)]
+struct Escapes {
+    jumps: bool,
+    this: bool,
+    yields: bool,
+    awaits: bool,
+}
+
+impl Escapes {
+    fn of(nodes: &[Node]) -> Self {
+        let mut escapes = Self::default();
+        for node in nodes {
+            escapes.scan(*node, 0, 0, false);
+        }
+        escapes
+    }
+
+    fn scan(&mut self, node: Node, loops: usize, switches: usize, in_arrow: bool) {
+        let (mut loops, mut switches, mut in_arrow) = (loops, switches, in_arrow);
+        match node {
+            Node::Stmt(Stmt::FunctionDecl { .. } | Stmt::ClassDecl { .. })
+            | Node::Expr(Expr::FunctionExpr { .. })
+            | Node::Member(_) => return,
+            Node::Expr(Expr::ArrowFunction { .. }) => in_arrow = true,
+            Node::Stmt(Stmt::Return { .. }) => self.jumps |= !in_arrow,
+            Node::Stmt(Stmt::Break { label, .. }) => self.jumps |= label.is_some() || loops + switches == 0,
+            Node::Stmt(Stmt::Continue { label, .. }) => self.jumps |= label.is_some() || loops == 0,
+            Node::Expr(Expr::This { .. } | Expr::Super { .. }) => self.this = true,
+            Node::Expr(Expr::Yield { .. }) => self.yields = true,
+            Node::Expr(Expr::Await { .. }) | Node::Stmt(Stmt::ForAwaitOf { .. }) => self.awaits |= !in_arrow,
+            Node::Stmt(Stmt::Switch { .. }) => switches += 1,
+            _ => {}
+        }
+        if matches!(
+            node,
+            Node::Stmt(
+                Stmt::While { .. }
+                    | Stmt::DoWhile { .. }
+                    | Stmt::For { .. }
+                    | Stmt::ForIn { .. }
+                    | Stmt::ForOf { .. }
+                    | Stmt::ForAwaitOf { .. }
+            )
+        ) {
+            loops += 1;
+        }
+        for child in node.children() {
+            self.scan(child, loops, switches, in_arrow);
+        }
+    }
+}
+
+struct Source<'a> {
+    text: &'a str,
+    program: Program,
+    tokens: Vec<Token>,
+    bindings: Vec<BindingInfo>,
+}
+
+type Action = Option<(String, CodeActionKind, Vec<(Span, String)>)>;
+
+impl<'a> Source<'a> {
+    fn parse(text: &'a str) -> Option<Self> {
+        let sf = SourceFile::new("inline".to_string(), text.to_string());
+        let (tokens, lex_diags) = Lexer::new(&sf).tokenize();
+        let (program, parse_diags) = Parser::new(&tokens, &sf).parse_program();
+        if !lex_diags.is_empty() || !parse_diags.is_empty() {
+            return None;
+        }
+        Some(Self { text, program, tokens, bindings: bindings(text) })
+    }
+
+    fn slice(&self, span: Span) -> &'a str {
+        &self.text[span.start..span.end]
+    }
+
+    fn trim(&self, span: Span) -> Span {
+        let slice = self.slice(span);
+        let start = span.start + (slice.len() - slice.trim_start().len());
+        let end = span.end - (slice.len() - slice.trim_end().len());
+        Span { start, end: end.max(start) }
+    }
+
+    /// The text of an expression ready to be spliced into another one.
+    fn operand(&self, expr: &Expr) -> String {
+        let text = self.slice(expr.span());
+        if is_atomic(expr) { text.to_string() } else { format!("({text})") }
+    }
+
+    fn is_braced(&self, block: &Block) -> bool {
+        self.text[block.span.start..].starts_with('{')
+    }
+
+    /// Nodes whose span covers `target`, outermost first.
+    fn path_to(&self, target: Span) -> Vec<Node<'_>> {
+        let mut out = Vec::new();
+        let mut children: Vec<Node> = self.program.items.iter().map(Node::Stmt).collect();
+        while let Some(node) = children.iter().copied().find(|n| contains(n.span(), target)) {
+            out.push(node);
+            children = node.children();
+        }
+        out
+    }
+
+    /// Whether `path[i]` is a statement directly inside the program or a braced block.
+    fn in_statement_list(&self, path: &[Node], i: usize) -> bool {
+        matches!(path[i], Node::Stmt(_))
+            && (i == 0 || matches!(path[i - 1], Node::Block(block) if self.is_braced(block)))
+    }
+
+    fn binding_at(&self, byte_pos: usize) -> Option<&BindingInfo> {
+        self.bindings.iter().find(|b| b.occurrences.iter().any(|(span, _)| within(*span, byte_pos)))
+    }
+
+    fn binding_of(&self, span: Span) -> Option<&BindingInfo> {
+        self.bindings.iter().find(|b| b.occurrences.iter().any(|(s, _)| *s == span))
+    }
+
+    fn declaration(binding: &BindingInfo) -> Span {
+        binding.occurrences[0].0
+    }
+
+    /// Whether any binding other than `except` is declared with `name`.
+    fn is_shadowed(&self, name: &str, except: &BindingInfo) -> bool {
+        self.bindings.iter().filter(|b| !std::ptr::eq(*b, except)).any(|b| self.slice(Self::declaration(b)) == name)
+    }
+
+    fn fresh_name(&self, base: &str) -> String {
+        let taken =
+            |name: &str| self.tokens.iter().any(|t| t.kind == TokenKind::Identifier && self.slice(t.span) == name);
+        if !taken(base) {
+            return base.to_string();
+        }
+        (2..).map(|i| format!("{base}{i}")).find(|name| !taken(name)).unwrap_or_default()
+    }
+
+    fn token_before(&self, byte_pos: usize) -> Option<&Token> {
+        self.tokens.iter().rev().find(|t| t.span.end <= byte_pos && t.kind != TokenKind::Eof)
+    }
+
+    /// Statement span widened to its whole line when nothing else shares it.
+    fn removal(&self, span: Span) -> Span {
+        let line_start = self.text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.text[span.end..].find('\n').map_or(self.text.len(), |i| span.end + i + 1);
+        if self.text[line_start..span.start].trim().is_empty() && self.text[span.end..line_end].trim().is_empty() {
+            Span { start: line_start, end: line_end }
+        } else {
+            span
+        }
+    }
+
+    /// Non-top-level bindings used inside `region` but declared outside it, in declaration order;
+    /// `None` when the region assigns one of them.
+    fn inputs(&self, region: Span) -> Option<Vec<&'a str>> {
+        let mut inputs: Vec<(usize, &'a str)> = Vec::new();
+        for binding in &self.bindings {
+            let decl = Self::declaration(binding);
+            if binding.top_level || contains(region, decl) {
+                continue;
+            }
+            let inside: Vec<_> = binding.occurrences.iter().filter(|(span, _)| contains(region, *span)).collect();
+            if inside.iter().any(|(_, write)| *write) {
+                return None;
+            }
+            if !inside.is_empty() {
+                inputs.push((decl.start, self.slice(decl)));
+            }
+        }
+        inputs.sort_unstable();
+        Some(inputs.into_iter().map(|(_, name)| name).collect())
+    }
+
+    fn new_function_text(&self, name: &str, params: &[&str], body: &str, is_async: bool) -> String {
+        let prefix = if is_async { "ассо " } else { "" };
+        format!("{prefix}йопта {name}({}) {{\n{body}\n}}\n\n", params.join(", "))
+    }
+
+    fn call_text(name: &str, args: &[&str], is_async: bool) -> String {
+        let prefix = if is_async { "сидетьНахуй " } else { "" };
+        format!("{prefix}{name}({})", args.join(", "))
+    }
+
+    /// The selected expression, unless it is written to or is a method callee.
+    fn selected_expr<'p>(&self, path: &[Node<'p>], selection: Span) -> Option<(usize, &'p Expr)> {
+        let index = path.iter().position(|n| matches!(n, Node::Expr(e) if e.span() == selection))?;
+        let Node::Expr(expr) = path[index] else { return None };
+        let is_target = index > 0
+            && match path[index - 1] {
+                Node::Expr(Expr::Binary { op, lhs, .. }) => is_assignment(*op) && lhs.span() == selection,
+                Node::Expr(Expr::Postfix { .. } | Expr::Unary { op: UnaryOp::Delete, .. }) => true,
+                Node::Expr(Expr::Call { callee, .. } | Expr::OptionalCall { callee, .. }) => {
+                    callee.span() == selection && matches!(expr, Expr::Member { .. } | Expr::OptionalMember { .. })
+                }
+                Node::Expr(Expr::ArrowFunction { .. }) | Node::Block(_) => true,
+                _ => false,
+            };
+        if is_target || matches!(expr, Expr::Spread { .. }) { None } else { Some((index, expr)) }
+    }
+
+    fn extract_variable(&self, selection: Span) -> Action {
+        let path = self.path_to(selection);
+        let (index, expr) = self.selected_expr(&path, selection)?;
+        if matches!(expr, Expr::Identifier(_)) {
+            return None;
+        }
+        let anchor = (0..index).rev().find(|&i| self.in_statement_list(&path, i))?;
+        if matches!(
+            path[anchor],
+            Node::Stmt(
+                Stmt::While { .. }
+                    | Stmt::DoWhile { .. }
+                    | Stmt::For { .. }
+                    | Stmt::ForIn { .. }
+                    | Stmt::ForOf { .. }
+                    | Stmt::ForAwaitOf { .. }
+            )
+        ) {
+            return None;
+        }
+        // Moving the expression out must not change whether or how often it is evaluated.
+        for pair in path[anchor..=index].windows(2) {
+            let child = pair[1].span();
+            let conditional = match pair[0] {
+                Node::Stmt(_) => pair[0].span() != path[anchor].span(),
+                Node::Expr(Expr::Binary {
+                    op: BinaryOp::And | BinaryOp::Or | BinaryOp::NullishCoalescing,
+                    rhs,
+                    ..
+                }) => rhs.span() == child,
+                Node::Expr(Expr::Conditional { condition, .. }) => condition.span() != child,
+                Node::Expr(Expr::ArrowFunction { .. } | Expr::FunctionExpr { .. }) | Node::Member(_) => true,
+                Node::Expr(Expr::OptionalMember { .. } | Expr::OptionalCall { .. } | Expr::OptionalIndex { .. }) => {
+                    true
+                }
+                _ => false,
+            };
+            if conditional {
+                return None;
+            }
+        }
+        let anchor_span = path[anchor].span();
+        let captures_local = self.bindings.iter().any(|b| {
+            let decl = Self::declaration(b);
+            contains(anchor_span, decl)
+                && !contains(selection, decl)
+                && b.occurrences.iter().any(|(span, _)| contains(selection, *span))
+        });
+        if captures_local {
+            return None;
+        }
+
+        let name = self.fresh_name("значение");
+        let line_start = self.text[..anchor_span.start].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &self.text[line_start..anchor_span.start];
+        let indent = if indent.trim().is_empty() { indent } else { "" };
+        let declaration = format!("ясенХуй {name} = {};\n{indent}", self.slice(expr.span()));
+        let edits = vec![(Span { start: anchor_span.start, end: anchor_span.start }, declaration), (selection, name)];
+        Some(("Извлечь в переменную".to_string(), CodeActionKind::REFACTOR_EXTRACT, edits))
+    }
+
+    fn extract_function(&self, selection: Span) -> Action {
+        if selection.start == selection.end {
+            return None;
+        }
+        let path = self.path_to(selection);
+        let top = path.first()?.span();
+        let name = self.fresh_name("извлечённая");
+
+        if let Some((_, expr)) = self.selected_expr(&path, selection) {
+            let escapes = Escapes::of(&[Node::Expr(expr)]);
+            if escapes.this || escapes.yields {
+                return None;
+            }
+            let params = self.inputs(selection)?;
+            let body = format!("отвечаю {};", self.slice(selection));
+            let function = self.new_function_text(&name, &params, &body, escapes.awaits);
+            let call = Self::call_text(&name, &params, escapes.awaits);
+            let edits = vec![(Span { start: top.start, end: top.start }, function), (selection, call)];
+            return Some(("Извлечь в функцию".to_string(), CodeActionKind::REFACTOR_EXTRACT, edits));
+        }
+
+        let mut lists: Vec<&[Stmt]> = vec![&self.program.items];
+        for node in &path {
+            if let Node::Block(block) = node
+                && self.is_braced(block)
+            {
+                lists.push(&block.stmts);
+            }
+        }
+        let stmts = lists.into_iter().rev().find_map(|stmts| {
+            let first = stmts.iter().position(|s| s.span().start == selection.start)?;
+            let last = stmts.iter().position(|s| s.span().end == selection.end)?;
+            (first <= last).then(|| &stmts[first..=last])
+        })?;
+        let nodes: Vec<Node> = stmts.iter().map(Node::Stmt).collect();
+        let escapes = Escapes::of(&nodes);
+        if escapes.jumps || escapes.this || escapes.yields {
+            return None;
+        }
+        let params = self.inputs(selection)?;
+
+        let mut outputs = self.bindings.iter().filter(|b| {
+            contains(selection, Self::declaration(b))
+                && b.occurrences.iter().any(|(span, _)| !contains(selection, *span))
+        });
+        let output = outputs.next();
+        if outputs.next().is_some() {
+            return None;
+        }
+        let output = match output {
+            Some(binding) => {
+                let decl = Self::declaration(binding);
+                let stmt = stmts.iter().find(
+                    |s| matches!(s, Stmt::VarDecl { pattern: Pattern::Identifier(ident), .. } if ident.span == decl),
+                )?;
+                let keyword = self.tokens.iter().find(|t| t.span.start == stmt.span().start)?;
+                Some((self.slice(keyword.span), self.slice(decl)))
+            }
+            None => None,
+        };
+
+        let mut body = self.slice(selection).to_string();
+        if let Some((_, out)) = output {
+            body.push_str(&format!("\nотвечаю {out};"));
+        }
+        let function = self.new_function_text(&name, &params, &body, escapes.awaits);
+        let call = Self::call_text(&name, &params, escapes.awaits);
+        let call = match output {
+            Some((keyword, out)) => format!("{keyword} {out} = {call};"),
+            None => format!("{call};"),
+        };
+        let edits = vec![(Span { start: top.start, end: top.start }, function), (selection, call)];
+        Some(("Извлечь в функцию".to_string(), CodeActionKind::REFACTOR_EXTRACT, edits))
+    }
+
+    /// `VarDecl`s and function declarations sitting directly in a statement list.
+    fn listed_statements(&self) -> Vec<&Stmt> {
+        let mut out: Vec<&Stmt> = self.program.items.iter().collect();
+        walk(&self.program.items, &mut |node| {
+            if let Node::Block(block) = node
+                && self.is_braced(block)
+            {
+                out.extend(block.stmts.iter());
+            }
+        });
+        out
+    }
+
+    fn inline_variable(&self, cursor: usize) -> Action {
+        let binding = self.binding_at(cursor)?;
+        let decl = Self::declaration(binding);
+        let stmt = self
+            .listed_statements()
+            .into_iter()
+            .find(|s| matches!(s, Stmt::VarDecl { pattern: Pattern::Identifier(ident), .. } if ident.span == decl))?;
+        let Stmt::VarDecl { init, .. } = stmt else { return None };
+        let reads = &binding.occurrences[1..];
+        if reads.is_empty() || reads.iter().any(|(span, write)| *write || span.start < stmt.span().end) {
+            return None;
+        }
+        if reads.len() > 1 && has_side_effects(init) {
+            return None;
+        }
+        let mut free_shadowed = false;
+        walk_node(Node::Expr(init), &mut |node| {
+            if let Node::Expr(Expr::Identifier(ident)) = node
+                && let Some(used) = self.binding_of(ident.span)
+            {
+                free_shadowed |= self.is_shadowed(&ident.name, used);
+            }
+        });
+        if free_shadowed {
+            return None;
+        }
+
+        let value = self.operand(init);
+        let mut edits: Vec<(Span, String)> = reads.iter().map(|(span, _)| (*span, value.clone())).collect();
+        edits.push((self.removal(stmt.span()), String::new()));
+        let title = format!("Встроить переменную «{}»", self.slice(decl));
+        Some((title, CodeActionKind::REFACTOR_INLINE, edits))
+    }
+
+    fn inline_function(&self, cursor: usize) -> Action {
+        let binding = self.binding_at(cursor)?;
+        let decl = Self::declaration(binding);
+        let stmt = self
+            .listed_statements()
+            .into_iter()
+            .find(|s| matches!(s, Stmt::FunctionDecl { name, .. } if name.span == decl))?;
+        let Stmt::FunctionDecl { params, body, is_generator: false, is_async: false, .. } = stmt else { return None };
+        let [Stmt::Return { value: Some(result), .. }] = body.stmts.as_slice() else { return None };
+        if params.iter().any(|p| p.pattern.is_some() || p.default.is_some() || p.is_rest) {
+            return None;
+        }
+        let escapes = Escapes::of(&[Node::Expr(result)]);
+        if escapes.this || escapes.yields || escapes.awaits {
+            return None;
+        }
+
+        let mut calls: Vec<(Span, &[Expr])> = Vec::new();
+        walk(&self.program.items, &mut |node| {
+            if let Node::Expr(Expr::Call { callee, args, span }) = node
+                && let Expr::Identifier(ident) = callee.as_ref()
+                && binding.occurrences.iter().any(|(s, _)| *s == ident.span)
+            {
+                calls.push((*span, args.as_slice()));
+            }
+        });
+        let uses = binding.occurrences.len() - 1;
+        if calls.is_empty() || calls.len() != uses {
+            return None;
+        }
+
+        let param_uses: Vec<(&Param, Vec<Span>)> = params
+            .iter()
+            .map(|param| {
+                let spans = self
+                    .binding_of(param.name.span)
+                    .map(|b| b.occurrences[1..].iter().map(|(span, _)| *span).collect())
+                    .unwrap_or_default();
+                (param, spans)
+            })
+            .collect();
+        let mut blocked = false;
+        walk_node(Node::Expr(result), &mut |node| {
+            if let Node::Expr(Expr::Identifier(ident)) = node
+                && let Some(used) = self.binding_of(ident.span)
+                && !param_uses.iter().any(|(p, _)| p.name.span == Self::declaration(used))
+            {
+                blocked |= std::ptr::eq(used, binding) || self.is_shadowed(&ident.name, used);
+            }
+        });
+        if blocked {
+            return None;
+        }
+
+        let mut edits = Vec::new();
+        for (span, args) in calls {
+            if args.len() != params.len() || args.iter().any(|a| matches!(a, Expr::Spread { .. })) {
+                return None;
+            }
+            let mut substitutions = Vec::new();
+            for ((_, uses), arg) in param_uses.iter().zip(args) {
+                if (uses.len() > 1 && !matches!(arg, Expr::Identifier(_) | Expr::Literal(_)))
+                    || (uses.is_empty() && has_side_effects(arg))
+                {
+                    return None;
+                }
+                let value = self.operand(arg);
+                substitutions.extend(uses.iter().map(|s| {
+                    (Span { start: s.start - result.span().start, end: s.end - result.span().start }, value.clone())
+                }));
+            }
+            let body = splice(self.slice(result.span()), substitutions);
+            edits.push((span, format!("({body})")));
+        }
+        edits.push((self.removal(stmt.span()), String::new()));
+        let title = format!("Встроить функцию «{}»", self.slice(decl));
+        Some((title, CodeActionKind::REFACTOR_INLINE, edits))
+    }
+
+    /// `(a, b)` of a declaration or function expression: from the `(` after `after` to the `)`
+    /// right before the body.
+    fn params_text(&self, after: usize, body: &Block) -> Option<&'a str> {
+        let open = self
+            .tokens
+            .iter()
+            .find(|t| t.span.start >= after && t.kind == TokenKind::Punctuation(PunctuationKind::LParen))?;
+        let close = self.token_before(body.span.start)?;
+        (close.kind == TokenKind::Punctuation(PunctuationKind::RParen))
+            .then(|| &self.text[open.span.start..close.span.end])
+    }
+
+    fn convert_function(&self, cursor: usize) -> Action {
+        // On the name of `ясенХуй ф = () => …` the arrow itself is meant.
+        let cursor = self
+            .listed_statements()
+            .into_iter()
+            .find_map(|stmt| match stmt {
+                Stmt::VarDecl {
+                    pattern: Pattern::Identifier(ident),
+                    init: init @ (Expr::ArrowFunction { .. } | Expr::FunctionExpr { .. }),
+                    ..
+                } if within(ident.span, cursor) => Some(init.span().start),
+                _ => None,
+            })
+            .unwrap_or(cursor);
+        let mut found: Option<(Node, Option<&Stmt>)> = None;
+        for stmt in self.listed_statements() {
+            walk_node(Node::Stmt(stmt), &mut |node| {
+                let head = match node {
+                    Node::Stmt(Stmt::FunctionDecl { span, body, .. })
+                    | Node::Expr(Expr::FunctionExpr { span, body, .. }) => {
+                        Span { start: span.start, end: body.span.start }
+                    }
+                    Node::Expr(Expr::ArrowFunction { span, body, .. }) => {
+                        let end = match body.stmts.as_slice() {
+                            [Stmt::Return { value: Some(value), .. }] if !self.is_braced(body) => value.span().start,
+                            _ => body.span.start,
+                        };
+                        Span { start: span.start, end }
+                    }
+                    _ => return,
+                };
+                if within(head, cursor) && found.as_ref().is_none_or(|(n, _)| contains(n.span(), node.span())) {
+                    found = Some((node, Some(stmt)));
+                }
+            });
+        }
+        let (node, stmt) = found?;
+        let uses_this = |body: &Block| {
+            let nodes: Vec<Node> = body.stmts.iter().map(Node::Stmt).collect();
+            Escapes::of(&nodes).this
+        };
+        let (title, edits) = match node {
+            Node::Stmt(Stmt::FunctionDecl { name, body, is_generator: false, is_async, span, .. }) => {
+                let binding = self.binding_of(name.span)?;
+                if uses_this(body) || binding.occurrences.iter().any(|(s, _)| s.start < span.start) {
+                    return None;
+                }
+                let start = if *is_async { self.token_before(span.start)?.span.start } else { span.start };
+                let prefix = if *is_async { "ассо " } else { "" };
+                let params = self.params_text(name.span.end, body)?;
+                let arrow = format!("ясенХуй {} = {prefix}{params} => {};", name.name, self.slice(body.span));
+                ("Преобразовать в стрелочную функцию", vec![(Span { start, end: span.end }, arrow)])
+            }
+            Node::Expr(Expr::FunctionExpr { name: None, body, is_generator: false, is_async, span, .. }) => {
+                if uses_this(body) {
+                    return None;
+                }
+                let prefix = if *is_async { "ассо " } else { "" };
+                let params = self.params_text(span.start, body)?;
+                let arrow = format!("{prefix}{params} => {}", self.slice(body.span));
+                ("Преобразовать в стрелочную функцию", vec![(*span, arrow)])
+            }
+            Node::Expr(Expr::ArrowFunction { params, body, is_async, span, .. }) => {
+                let (params_text, body_text) = match body.stmts.as_slice() {
+                    [Stmt::Return { value: Some(value), .. }] if !self.is_braced(body) => (
+                        self.arrow_params(*span, value.span().start, params)?,
+                        format!("{{\nотвечаю {};\n}}", self.slice(value.span())),
+                    ),
+                    _ => (self.arrow_params(*span, body.span.start, params)?, self.slice(body.span).to_string()),
+                };
+                let nodes: Vec<Node> = body.stmts.iter().map(Node::Stmt).collect();
+                if Escapes::of(&nodes).this {
+                    return None;
+                }
+                let prefix = if *is_async { "ассо " } else { "" };
+                let declared = stmt.and_then(|stmt| match stmt {
+                    Stmt::VarDecl { pattern: Pattern::Identifier(ident), init, span: decl_span, .. }
+                        if init.span() == *span =>
+                    {
+                        Some((ident, *decl_span))
+                    }
+                    _ => None,
+                });
+                let edit = match declared {
+                    Some((ident, decl_span)) => {
+                        (decl_span, format!("{prefix}йопта {}{params_text} {body_text}", ident.name))
+                    }
+                    None => (*span, format!("({prefix}йопта {params_text} {body_text})")),
+                };
+                ("Преобразовать в функцию йопта", vec![edit])
+            }
+            _ => return None,
+        };
+        Some((title.to_string(), CodeActionKind::REFACTOR_REWRITE, edits))
+    }
+
+    /// Parameter list of an arrow, parenthesized even when the source omits the parentheses.
+    fn arrow_params(&self, span: Span, body_start: usize, params: &[Param]) -> Option<String> {
+        let arrow = self.token_before(body_start)?;
+        if arrow.kind != TokenKind::Punctuation(PunctuationKind::Arrow) {
+            return None;
+        }
+        let mut start = span.start;
+        if let Some(token) = self.tokens.iter().find(|t| t.span.start == span.start)
+            && token.kind == TokenKind::Keyword(KeywordKind::Async)
+        {
+            start = token.span.end;
+        }
+        let text = self.text[start..arrow.span.start].trim();
+        Some(if text.starts_with('(') || params.len() != 1 { text.to_string() } else { format!("({text})") })
+    }
+
+    fn promise_chain_to_async(&self, cursor: usize) -> Action {
+        let path = self.path_to(Span { start: cursor, end: cursor });
+        let ret = path.iter().rposition(|n| {
+            matches!(n, Node::Stmt(Stmt::Return { value: Some(value), .. }) if Self::then_callbacks(value).is_some())
+        })?;
+        let Node::Stmt(Stmt::Return { value: Some(chain), span: ret_span }) = path[ret] else { return None };
+        let func = path[..ret].iter().rposition(|n| {
+            matches!(
+                n,
+                Node::Stmt(Stmt::FunctionDecl { .. })
+                    | Node::Expr(Expr::FunctionExpr { .. } | Expr::ArrowFunction { .. })
+                    | Node::Member(_)
+            )
+        })?;
+        let (func_span, is_async, body) = match path[func] {
+            Node::Stmt(Stmt::FunctionDecl { span, is_async, is_generator: false, body, .. })
+            | Node::Expr(Expr::FunctionExpr { span, is_async, is_generator: false, body, .. }) => {
+                (*span, *is_async, body)
+            }
+            Node::Expr(Expr::ArrowFunction { span, is_async, body, .. }) => (*span, *is_async, body),
+            _ => return None,
+        };
+        let desugared = !self.is_braced(body);
+        if !desugared && !matches!(path[ret - 1], Node::Block(block) if self.is_braced(block)) {
+            return None;
+        }
+        let (base, callbacks) = Self::then_callbacks(chain)?;
+
+        // Callback bodies are spliced into one scope, so their names must be unique file-wide.
+        let mut introduced = Vec::new();
+        for callback in &callbacks {
+            let (params, body) = match callback {
+                Expr::ArrowFunction { params, body, .. } | Expr::FunctionExpr { params, body, .. } => (params, body),
+                _ => return None,
+            };
+            if params.len() > 1 || params.iter().any(|p| p.pattern.is_some() || p.default.is_some() || p.is_rest) {
+                return None;
+            }
+            introduced.extend(params.iter().map(|p| p.name.span));
+            if self.is_braced(body) {
+                for stmt in &body.stmts {
+                    match stmt {
+                        Stmt::VarDecl { pattern, .. } => {
+                            walk_node(Node::Pattern(pattern), &mut |node| {
+                                if let Node::Ident(ident) = node {
+                                    introduced.push(ident.span);
+                                }
+                            });
+                        }
+                        Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } => introduced.push(name.span),
+                        _ => {}
+                    }
+                }
+            }
+            if matches!(callback, Expr::FunctionExpr { .. }) && Escapes::of(&[Node::Expr(callback)]).this {
+                return None;
+            }
+        }
+        for span in &introduced {
+            let name = self.slice(*span);
+            if self.bindings.iter().filter(|b| self.slice(Self::declaration(b)) == name).count() > 1 {
+                return None;
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut previous = Some(self.operand(base));
+        for callback in callbacks {
+            let (params, body) = match callback {
+                Expr::ArrowFunction { params, body, .. } | Expr::FunctionExpr { params, body, .. } => (params, body),
+                _ => return None,
+            };
+            let awaited = previous.take().map(|value| format!("сидетьНахуй {value}"));
+            match (params.first(), awaited) {
+                (Some(param), Some(value)) => {
+                    let reassigned = self
+                        .binding_of(param.name.span)
+                        .is_some_and(|b| b.occurrences.iter().filter(|(_, write)| *write).count() > 1);
+                    let keyword = if reassigned { "гыы" } else { "ясенХуй" };
+                    lines.push(format!("{keyword} {} = {value};", param.name.name));
+                }
+                (Some(param), None) => lines.push(format!("гыы {} = неибу;", param.name.name)),
+                (None, Some(value)) => lines.push(format!("{value};")),
+                (None, None) => {}
+            }
+            let (stmts, result) = match body.stmts.as_slice() {
+                [Stmt::Return { value, .. }] if !self.is_braced(body) => (&[][..], value.as_ref()),
+                [rest @ .., Stmt::Return { value, .. }] => (rest, value.as_ref()),
+                all => (all, None),
+            };
+            let nodes: Vec<Node> = stmts.iter().map(Node::Stmt).collect();
+            let escapes = Escapes::of(&nodes);
+            if escapes.jumps || escapes.yields || (escapes.this && matches!(callback, Expr::FunctionExpr { .. })) {
+                return None;
+            }
+            lines.extend(stmts.iter().map(|s| self.slice(s.span()).to_string()));
+            previous = result.map(|value| self.operand(value));
+        }
+        if let Some(value) = previous {
+            lines.push(format!("отвечаю {value};"));
+        }
+
+        let mut edits = Vec::new();
+        if desugared {
+            let value_start = chain.span().start;
+            edits.push((Span { start: value_start, end: body.span.end }, format!("{{\n{}\n}}", lines.join("\n"))));
+        } else {
+            edits.push((*ret_span, lines.join("\n")));
+        }
+        if !is_async {
+            edits.push((Span { start: func_span.start, end: func_span.start }, "ассо ".to_string()));
+        }
+        let title = "Преобразовать цепочку .потом в ассо/сидетьНахуй".to_string();
+        Some((title, CodeActionKind::REFACTOR_REWRITE, edits))
+    }
+
+    /// `base.потом(cb1).потом(cb2)` → `(base, [cb1, cb2])`.
+    fn then_callbacks(expr: &Expr) -> Option<(&Expr, Vec<&Expr>)> {
+        let mut callbacks = Vec::new();
+        let mut current = expr;
+        while let Expr::Call { callee, args, .. } = current
+            && let Expr::Member { object, property, .. } = callee.as_ref()
+            && property.name == "потом"
+        {
+            let [callback @ (Expr::ArrowFunction { .. } | Expr::FunctionExpr { .. })] = args.as_slice() else {
+                return None;
+            };
+            callbacks.push(callback);
+            current = object;
+        }
+        if callbacks.is_empty() {
+            return None;
+        }
+        callbacks.reverse();
+        Some((current, callbacks))
+    }
+
+    fn concat_to_template(&self, cursor: usize) -> Action {
+        let path = self.path_to(Span { start: cursor, end: cursor });
+        let is_add = |node: &Node| matches!(node, Node::Expr(Expr::Binary { op: BinaryOp::Add, .. }));
+        let mut index = path.iter().rposition(is_add)?;
+        while index > 0 && is_add(&path[index - 1]) {
+            index -= 1;
+        }
+        let Node::Expr(top) = path[index] else { return None };
+        let mut operands = Vec::new();
+        let mut current = top;
+        while let Expr::Binary { op: BinaryOp::Add, lhs, rhs, .. } = current {
+            operands.push(rhs.as_ref());
+            current = lhs;
+        }
+        operands.push(current);
+        operands.reverse();
+
+        let is_string = |e: &Expr| matches!(e, Expr::Literal(Literal::String { .. }));
+        let first = operands.iter().position(|e| is_string(e))?;
+        if operands.iter().all(|e| is_string(e)) {
+            return None;
+        }
+        let mut template = String::from("`");
+        if first > 0 {
+            let head = Span { start: operands[0].span().start, end: operands[first - 1].span().end };
+            template.push_str(&format!("${{{}}}", self.slice(head)));
+        }
+        for operand in &operands[first..] {
+            match operand {
+                Expr::Literal(Literal::String { value, .. }) => template.push_str(&escape_template(value)),
+                Expr::TemplateLiteral { span, .. } => template.push_str(&self.text[span.start + 1..span.end - 1]),
+                Expr::Grouping { expr, .. } => template.push_str(&format!("${{{}}}", self.slice(expr.span()))),
+                other => template.push_str(&format!("${{{}}}", self.slice(other.span()))),
+            }
+        }
+        template.push('`');
+        let edits = vec![(top.span(), template)];
+        Some(("Преобразовать в шаблонную строку".to_string(), CodeActionKind::REFACTOR_REWRITE, edits))
+    }
+
+    /// Drops unused specifiers, merges `{ … }` imports of the same module and sorts by source.
+    fn organize_imports(&self) -> Action {
+        let imports: Vec<&Stmt> = self.program.items.iter().filter(|s| matches!(s, Stmt::Import { .. })).collect();
+        let first = imports.first()?.span();
+        let used = |local: Span| self.binding_of(local).is_some_and(|b| b.occurrences.len() > 1);
+
+        let mut named: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        let mut lines: Vec<(String, String)> = Vec::new();
+        for stmt in &imports {
+            let Stmt::Import { specifiers, source, attributes, span } = stmt else { continue };
+            if !attributes.is_empty() {
+                lines.push((source.clone(), self.slice(*span).to_string()));
+                continue;
+            }
+            for spec in specifiers {
+                match spec {
+                    ImportSpec::Named { imported, local } if used(local.span) => {
+                        named.entry(source).or_default().push(&imported.name);
+                    }
+                    ImportSpec::Default { local } if used(local.span) => {
+                        lines.push((source.clone(), format!("спиздить {} из {};", local.name, quote(source))));
+                    }
+                    ImportSpec::Namespace { local } if used(local.span) => {
+                        lines.push((source.clone(), format!("спиздить * как {} из {};", local.name, quote(source))));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for (source, mut names) in named {
+            names.sort_unstable();
+            names.dedup();
+            lines.push((source.to_string(), import_line(&names, source)));
+        }
+        lines.sort();
+        lines.dedup();
+        let block = lines.into_iter().map(|(_, line)| line).collect::<Vec<_>>().join("\n");
+
+        let mut edits = vec![(first, block)];
+        edits.extend(imports[1..].iter().map(|stmt| (self.removal(stmt.span()), String::new())));
+        Some(("Упорядочить импорты".to_string(), CodeActionKind::SOURCE_ORGANIZE_IMPORTS, edits))
+    }
+}
+
+fn escape_template(value: &str) -> String {
+    let mut out = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '`' => out.push_str("\\`"),
+            '$' if chars.peek() == Some(&'{') => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str, edit: &TextEdit) -> String {
+        let start = crate::position::pos_to_byte(text, edit.range.start);
+        let end = crate::position::pos_to_byte(text, edit.range.end);
+        let mut out = text.to_string();
+        out.replace_range(start..end, &edit.new_text);
+        out
+    }
+
+    fn run(src: &str, selected: &str, title: &str) -> Option<String> {
+        let start = src.find(selected).expect("selection");
+        let selection = Span { start, end: start + selected.len() };
+        refactorings(src, selection).into_iter().find(|r| r.title.starts_with(title)).map(|r| apply(src, &r.edit))
+    }
+
+    #[test]
+    fn extracts_an_expression_into_a_constant() {
+        let src = "йопта ф(а) {\n    сказать(а * 2 + 1);\n}\n";
+        let out = run(src, "а * 2", "Извлечь в переменную").expect("action");
+        assert_eq!(out, "йопта ф(а) {\n    ясенХуй значение = а * 2;\n    сказать(значение + 1);\n}\n");
+    }
+
+    #[test]
+    fn extraction_refuses_short_circuited_operands() {
+        let src = "йопта ф(а) {\n    отвечаю а && а.б();\n}\n";
+        assert!(run(src, "а.б()", "Извлечь в переменную").is_none());
+    }
+
+    #[test]
+    fn extracts_statements_into_a_function_with_parameters_and_result() {
+        let src = "йопта ф(а) {\n    гыы б = а + 1;\n    сказать(б);\n    отвечаю б;\n}\n";
+        let out = run(src, "гыы б = а + 1;\n    сказать(б);", "Извлечь в функцию").expect("action");
+        assert_eq!(
+            out,
+            "йопта извлечённая(а) {\n    гыы б = а + 1;\n    сказать(б);\n    отвечаю б;\n}\n\n\
+             йопта ф(а) {\n    гыы б = извлечённая(а);\n    отвечаю б;\n}\n"
+        );
+    }
+
+    #[test]
+    fn extraction_refuses_escaping_control_flow() {
+        let src = "йопта ф(а) {\n    вилкойвглаз (а) {\n        отвечаю 1;\n    }\n    отвечаю 2;\n}\n";
+        assert!(run(src, "вилкойвглаз (а) {\n        отвечаю 1;\n    }", "Извлечь в функцию").is_none());
+    }
+
+    #[test]
+    fn inlines_a_variable() {
+        let src = "йопта ф(а) {\n    ясенХуй б = а + 1;\n    отвечаю б * 2;\n}\n";
+        let out = run(src, "б =", "Встроить переменную").expect("action");
+        assert_eq!(out, "йопта ф(а) {\n    отвечаю (а + 1) * 2;\n}\n");
+    }
+
+    #[test]
+    fn formats_only_the_rewritten_code() {
+        let src = "гыы   х=1;\nйопта ф(а) {\n    ясенХуй б = а + 1;\n    отвечаю б * 2;\n}\nсказать( х );\n";
+        let out = run(src, "б =", "Встроить переменную").expect("action");
+        assert_eq!(out, "гыы   х=1;\nйопта ф(а) {\n    отвечаю (а + 1) * 2;\n}\nсказать( х );\n");
+    }
+
+    #[test]
+    fn inlining_refuses_reassigned_variables() {
+        let src = "гыы б = 1;\nб = 2;\nсказать(б);\n";
+        assert!(run(src, "б = 1", "Встроить переменную").is_none());
+    }
+
+    #[test]
+    fn inlines_a_single_expression_function() {
+        let src = "йопта удвоить(х) {\n    отвечаю х * 2;\n}\nсказать(удвоить(3) + 1);\n";
+        let out = run(src, "удвоить(х)", "Встроить функцию").expect("action");
+        assert_eq!(out, "сказать(3 * 2 + 1);\n");
+    }
+
+    #[test]
+    fn converts_between_function_and_arrow() {
+        let src = "йопта ф(а, б) {\n    отвечаю а + б;\n}\n";
+        let arrow = run(src, "ф(", "Преобразовать в стрелочную").expect("to arrow");
+        assert_eq!(arrow, "ясенХуй ф = (а, б) => а + б;\n");
+        let back = run(&arrow, "ф =", "Преобразовать в функцию").expect("to function");
+        assert_eq!(back, src);
+    }
+
+    #[test]
+    fn arrow_functions_using_this_stay_arrows() {
+        let src = "ясенХуй ф = () => тырыпыры.а;\n";
+        assert!(run(src, "ф =", "Преобразовать в функцию").is_none());
+    }
+
+    #[test]
+    fn converts_a_promise_chain_to_await() {
+        let src = "йопта ф() {\n    отвечаю загрузить().потом((ответ) => ответ.текст).потом((текст) => {\n        сказать(текст);\n        отвечаю текст;\n    });\n}\n";
+        let out = run(src, "загрузить", "Преобразовать цепочку").expect("action");
+        assert_eq!(
+            out,
+            "ассо йопта ф() {\n    ясенХуй ответ = сидетьНахуй загрузить();\n    ясенХуй текст = сидетьНахуй ответ.текст;\n    сказать(текст);\n    отвечаю текст;\n}\n"
+        );
+    }
+
+    #[test]
+    fn converts_concatenation_to_a_template() {
+        let src = "ясенХуй с = \"Привет, `\" + имя + \"`!\";\n";
+        let out = run(src, "имя", "Преобразовать в шаблонную").expect("action");
+        assert_eq!(out, "ясенХуй с = `Привет, \\`${имя}\\`!`;\n");
+    }
+
+    #[test]
+    fn organizes_imports() {
+        let src = "спиздить { в, а } из \"./б\";\nспиздить { г } из \"./а\";\nспиздить { д } из \"./б\";\nсказать(а, г, д);\n";
+        let out = run(src, "сказать", "Упорядочить импорты").expect("action");
+        assert_eq!(out, "спиздить { г } из \"./а\";\nспиздить { а, д } из \"./б\";\nсказать(а, г, д);\n");
+    }
+
+    #[test]
+    fn adds_missing_imports() {
+        let src = "спиздить { а } из \"./б\";\nсказать(а, в);\n";
+        assert_eq!(unresolved_name(src, src.find("в)").unwrap()).as_deref(), Some("в"));
+        let merged = add_import(src, "в", "./б").expect("edit");
+        assert_eq!(apply(src, &merged), "спиздить { а, в } из \"./б\";\nсказать(а, в);\n");
+        let added = add_import(src, "в", "./г").expect("edit");
+        assert_eq!(apply(src, &added), "спиздить { а } из \"./б\";\nспиздить { в } из \"./г\";\nсказать(а, в);\n");
+    }
+}
@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use yps_lexer::{Lexer, SourceFile, Span, TokenKind};
 use yps_parser::ast::{
-    Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, ObjectEntry, Param, Pattern, PropKey, Stmt,
-    TemplatePart,
+    BinaryOp, Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, MatchPattern, ObjectEntry, Param,
+    Pattern, PropKey, Stmt, TemplatePart,
 };
 use yps_parser::{Parser, Program};
 
@@ -16,7 +16,7 @@ fn identifier_token_at(text: &str, byte_pos: usize) -> Option<Span> {
         .map(|t| t.span)
 }
 
-fn is_valid_new_name(new_name: &str) -> bool {
+pub(crate) fn is_valid_new_name(new_name: &str) -> bool {
     let sf = SourceFile::new("inline".to_string(), new_name.to_string());
     let (tokens, diags) = Lexer::new(&sf).tokenize();
     let non_eof: Vec<_> = tokens.into_iter().filter(|t| t.kind != TokenKind::Eof).collect();
@@ -28,6 +28,7 @@ fn is_valid_new_name(new_name: &str) -> bool {
 
 struct Binding {
     occurrences: Vec<Span>,
+    scope: usize,
 }
 
 struct Scope {
@@ -38,6 +39,8 @@ struct Scope {
 struct Resolver {
     scopes: Vec<Scope>,
     bindings: Vec<Binding>,
+    /// Start offsets of occurrences that bind or assign the name.
+    writes: HashSet<usize>,
 }
 
 impl Resolver {
@@ -45,7 +48,7 @@ impl Resolver {
         let sf = SourceFile::new("inline".to_string(), text.to_string());
         let (tokens, _) = Lexer::new(&sf).tokenize();
         let (program, _) = Parser::new(&tokens, &sf).parse_program();
-        let mut resolver = Self { scopes: Vec::new(), bindings: Vec::new() };
+        let mut resolver = Self { scopes: Vec::new(), bindings: Vec::new(), writes: HashSet::new() };
         let root = resolver.new_scope(None);
         resolver.resolve_program(root, &program);
         resolver
@@ -62,11 +65,12 @@ impl Resolver {
             existing
         } else {
             let bid = self.bindings.len();
-            self.bindings.push(Binding { occurrences: Vec::new() });
+            self.bindings.push(Binding { occurrences: Vec::new(), scope });
             self.scopes[scope].names.insert(ident.name.clone(), bid);
             bid
         };
         self.bindings[bid].occurrences.push(ident.span);
+        self.writes.insert(ident.span.start);
     }
 
     fn use_ident(&mut self, scope: usize, name: &str, span: Span) {
@@ -80,6 +84,12 @@ impl Resolver {
         }
     }
 
+    fn mark_write(&mut self, target: &Expr) {
+        if let Expr::Identifier(ident) = target {
+            self.writes.insert(ident.span.start);
+        }
+    }
+
     fn binding_occurrences_at(&self, byte_pos: usize) -> Option<Vec<Span>> {
         self.bindings
             .iter()
@@ -107,7 +117,10 @@ impl Resolver {
     fn hoist_stmt(&mut self, scope: usize, stmt: &Stmt) {
         match stmt {
             Stmt::VarDecl { pattern, .. } => self.declare_pattern(scope, pattern),
-            Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } | Stmt::Using { name, .. } => {
+            Stmt::FunctionDecl { name, .. }
+            | Stmt::ClassDecl { name, .. }
+            | Stmt::Using { name, .. }
+            | Stmt::EnumDecl { name, .. } => {
                 self.declare(scope, name);
             }
             Stmt::Import { specifiers, .. } => {
@@ -146,9 +159,19 @@ impl Resolver {
                 self.resolve_expr(scope, init);
             }
             Stmt::Using { init, .. } => self.resolve_expr(scope, init),
+            Stmt::EnumDecl { members, .. } => {
+                for init in members.iter().filter_map(|m| m.init.as_ref()) {
+                    self.resolve_expr(scope, init);
+                }
+            }
             Stmt::Expr { expr, .. } | Stmt::Throw { value: expr, .. } => self.resolve_expr(scope, expr),
             Stmt::Block(block) => self.resolve_block(scope, block),
-            Stmt::Empty { .. } | Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Debugger { .. } => {}
+            Stmt::Empty { .. }
+            | Stmt::Break { .. }
+            | Stmt::Continue { .. }
+            | Stmt::Debugger { .. }
+            | Stmt::TypeAlias { .. }
+            | Stmt::Error { .. } => {}
             Stmt::If { condition, then_branch, else_branch, .. } => {
                 self.resolve_expr(scope, condition);
                 self.resolve_stmt(scope, then_branch);
@@ -365,19 +388,30 @@ impl Resolver {
     fn resolve_expr(&mut self, scope: usize, expr: &Expr) {
         match expr {
             Expr::Identifier(ident) => self.use_ident(scope, &ident.name, ident.span),
-            Expr::This { .. } | Expr::Super { .. } => {}
+            Expr::This { .. }
+            | Expr::Super { .. }
+            | Expr::NewTarget { .. }
+            | Expr::ImportMeta { .. }
+            | Expr::Error { .. } => {}
             Expr::Literal(lit) => self.resolve_literal(scope, lit),
+            Expr::Postfix { expr, .. } => {
+                self.resolve_expr(scope, expr);
+                self.mark_write(expr);
+            }
             Expr::Unary { expr, .. }
-            | Expr::Postfix { expr, .. }
             | Expr::Grouping { expr, .. }
             | Expr::Spread { expr, .. }
             | Expr::Await { argument: expr, .. } => self.resolve_expr(scope, expr),
-            Expr::Binary { lhs, rhs, .. } => {
+            Expr::Binary { op, lhs, rhs, .. } => {
                 self.resolve_expr(scope, lhs);
                 self.resolve_expr(scope, rhs);
+                if is_assignment(*op) {
+                    self.mark_write(lhs);
+                }
             }
             Expr::Assignment { target, value, .. } => {
                 self.use_ident(scope, &target.name, target.span);
+                self.writes.insert(target.span.start);
                 self.resolve_expr(scope, value);
             }
             Expr::Call { callee, args, .. }
@@ -419,6 +453,32 @@ impl Resolver {
                 }
             }
             Expr::DynamicImport { source, .. } => self.resolve_expr(scope, source),
+            Expr::Match { subject, arms, .. } => {
+                self.resolve_expr(scope, subject);
+                for arm in arms {
+                    let arm_scope = self.new_scope(Some(scope));
+                    let pattern = match &arm.pattern {
+                        MatchPattern::Bind(pattern) => Some(pattern),
+                        MatchPattern::Instance { class, pattern, .. } => {
+                            self.resolve_expr(scope, class);
+                            pattern.as_ref()
+                        }
+                        MatchPattern::Value(value) => {
+                            self.resolve_expr(scope, value);
+                            None
+                        }
+                        MatchPattern::Wildcard { .. } => None,
+                    };
+                    if let Some(pattern) = pattern {
+                        self.declare_pattern(arm_scope, pattern);
+                        self.resolve_pattern_defaults(arm_scope, pattern);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.resolve_expr(arm_scope, guard);
+                    }
+                    self.resolve_expr(arm_scope, &arm.body);
+                }
+            }
         }
     }
 
@@ -479,6 +539,64 @@ pub fn occurrences_at(text: &str, byte_pos: usize) -> Option<Vec<Span>> {
     resolver.binding_occurrences_at(byte_pos)
 }
 
+/// Occurrences of the binding under the cursor, each flagged `true` when it writes the name.
+pub(crate) fn occurrence_kinds_at(text: &str, byte_pos: usize) -> Option<Vec<(Span, bool)>> {
+    identifier_token_at(text, byte_pos)?;
+    let resolver = Resolver::build(text);
+    let spans = resolver.binding_occurrences_at(byte_pos)?;
+    Some(spans.into_iter().map(|span| (span, resolver.writes.contains(&span.start))).collect())
+}
+
+/// A resolved binding as seen by refactorings: occurrences in resolution order (the declaration
+/// first), each flagged `true` when it writes the name.
+pub(crate) struct BindingInfo {
+    pub occurrences: Vec<(Span, bool)>,
+    pub top_level: bool,
+}
+
+pub(crate) fn bindings(text: &str) -> Vec<BindingInfo> {
+    let resolver = Resolver::build(text);
+    resolver
+        .bindings
+        .iter()
+        .map(|b| BindingInfo {
+            occurrences: b.occurrences.iter().map(|span| (*span, resolver.writes.contains(&span.start))).collect(),
+            top_level: b.scope == 0,
+        })
+        .collect()
+}
+
+/// An identifier under the cursor that no declaration in the file binds.
+pub(crate) fn unresolved_at(text: &str, byte_pos: usize) -> Option<String> {
+    let span = identifier_token_at(text, byte_pos)?;
+    if Resolver::build(text).resolves_at(byte_pos) {
+        return None;
+    }
+    Some(text[span.start..span.end].to_string())
+}
+
+pub(crate) fn is_assignment(op: BinaryOp) -> bool {
+    matches!(
+        op,
+        BinaryOp::Assign
+            | BinaryOp::PlusAssign
+            | BinaryOp::MinusAssign
+            | BinaryOp::MulAssign
+            | BinaryOp::DivAssign
+            | BinaryOp::ExpAssign
+            | BinaryOp::ModAssign
+            | BinaryOp::NullishAssign
+            | BinaryOp::AndAssign
+            | BinaryOp::OrAssign
+            | BinaryOp::BitAndAssign
+            | BinaryOp::BitOrAssign
+            | BinaryOp::BitXorAssign
+            | BinaryOp::ShlAssign
+            | BinaryOp::ShrAssign
+            | BinaryOp::UshrAssign
+    )
+}
+
 #[must_use]
 pub fn rename_edits(text: &str, byte_pos: usize, new_name: &str) -> Option<Vec<Span>> {
     if !is_valid_new_name(new_name) {
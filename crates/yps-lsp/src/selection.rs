@@ -0,0 +1,81 @@
+use tower_lsp::lsp_types::SelectionRange;
+use yps_lexer::{Lexer, SourceFile, Span};
+use yps_parser::Parser;
+
+use crate::position::span_to_range;
+use crate::walk::Node;
+
+/// One nested range chain per position, from the innermost AST node out to the whole document.
+#[must_use]
+pub fn selection_ranges(text: &str, positions: &[usize]) -> Vec<SelectionRange> {
+    let sf = SourceFile::new("inline".to_string(), text.to_string());
+    let (tokens, _) = Lexer::new(&sf).tokenize();
+    let (program, _) = Parser::new(&tokens, &sf).parse_program();
+    let root = Span { start: 0, end: text.len() };
+
+    positions
+        .iter()
+        .map(|&byte| {
+            let mut spans = vec![root];
+            let mut children: Vec<Node> = program.items.iter().map(Node::Stmt).collect();
+            while let Some(node) = innermost(&children, byte) {
+                let span = node.span();
+                if spans.last() != Some(&span) {
+                    spans.push(span);
+                }
+                children = node.children();
+            }
+            spans.into_iter().fold(None, |parent, span| {
+                Some(SelectionRange { range: span_to_range(text, span), parent: parent.map(Box::new) })
+            })
+        })
+        .map(|range| range.expect("root range is always present"))
+        .collect()
+}
+
+/// Prefers a node that strictly contains `byte`, falling back to one that ends right at it.
+fn innermost<'a>(nodes: &[Node<'a>], byte: usize) -> Option<Node<'a>> {
+    let inside = nodes.iter().find(|n| n.span().start <= byte && byte < n.span().end);
+    inside.or_else(|| nodes.iter().find(|n| n.span().start <= byte && byte <= n.span().end)).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(src: &str, byte: usize) -> Vec<&str> {
+        let mut out = Vec::new();
+        let mut current = selection_ranges(src, &[byte]).into_iter().next();
+        while let Some(range) = current {
+            let start = crate::position::pos_to_byte(src, range.range.start);
+            let end = crate::position::pos_to_byte(src, range.range.end);
+            out.push(&src[start..end]);
+            current = range.parent.map(|p| *p);
+        }
+        out
+    }
+
+    #[test]
+    fn expands_from_identifier_to_document() {
+        let src = "гыы х = фу(а + 1);";
+        let byte = src.find('а').unwrap();
+        assert_eq!(chain(src, byte), ["а", "а + 1", "фу(а + 1)", "гыы х = фу(а + 1);"]);
+    }
+
+    #[test]
+    fn nested_blocks_are_separate_steps() {
+        let src = "йопта ф() {\n  вилкойвглаз (да) {\n    сказать(1);\n  }\n}";
+        let byte = src.find('1').unwrap();
+        let steps = chain(src, byte);
+        assert_eq!(steps.first(), Some(&"1"));
+        assert!(steps.contains(&"сказать(1)"));
+        assert!(steps.contains(&"{\n    сказать(1);\n  }"));
+        assert_eq!(steps.last(), Some(&src));
+    }
+
+    #[test]
+    fn returns_one_range_per_position() {
+        let src = "гыы а = 1;\nгыы б = 2;";
+        assert_eq!(selection_ranges(src, &[4, src.rfind('б').unwrap()]).len(), 2);
+    }
+}
@@ -6,6 +6,7 @@ use yps_parser::Parser;
 use yps_parser::ast::{ClassMember, ExportKind, Expr, Param, Pattern, Stmt};
 
 use crate::builtins::builtin_doc;
+use yps_types::infer::{self, FunctionSig};
 
 struct BuiltinSig {
     name: &'static str,
@@ -90,6 +91,14 @@ fn param_label(param: &Param) -> String {
     if param.default.is_some() { format!("{base}?") } else { base }
 }
 
+fn typed_param_label(param: &Param, sig: Option<&FunctionSig>, index: usize) -> String {
+    let label = param_label(param);
+    match sig.and_then(|sig| sig.params.get(index)) {
+        Some((_, ty)) if ty.is_known() => format!("{label}: {ty}"),
+        _ => label,
+    }
+}
+
 fn register_expr_fn<'a>(name: &'a str, expr: &'a Expr, out: &mut Vec<(&'a str, &'a [Param])>) {
     match expr {
         Expr::FunctionExpr { params, .. } | Expr::ArrowFunction { params, .. } => out.push((name, params)),
@@ -104,7 +113,7 @@ fn collect_functions_expr<'a>(expr: &'a Expr, out: &mut Vec<(&'a str, &'a [Param
     }
 }
 
-fn collect_functions<'a>(stmts: &'a [Stmt], out: &mut Vec<(&'a str, &'a [Param])>) {
+pub(crate) fn collect_functions<'a>(stmts: &'a [Stmt], out: &mut Vec<(&'a str, &'a [Param])>) {
     for stmt in stmts {
         collect_functions_stmt(stmt, out);
     }
@@ -207,8 +216,13 @@ pub fn signature_help(text: &str, byte_pos: usize) -> Option<SignatureHelp> {
     collect_functions(&program.items, &mut functions);
 
     if let Some((_, params)) = functions.iter().find(|(name, _)| *name == callee_name) {
-        let param_labels: Vec<String> = params.iter().map(param_label).collect();
-        let label = format!("{callee_name}({})", param_labels.join(", "));
+        let types = infer::infer(&program);
+        let sig = types.function(&callee_name);
+        let param_labels: Vec<String> = params.iter().enumerate().map(|(i, p)| typed_param_label(p, sig, i)).collect();
+        let mut label = format!("{callee_name}({})", param_labels.join(", "));
+        if let Some(sig) = sig.filter(|sig| sig.ret.is_known()) {
+            label.push_str(&format!(": {}", sig.ret));
+        }
         return Some(build_signature(label, param_labels, None, active_param));
     }
 
@@ -267,7 +281,14 @@ mod tests {
     fn default_and_rest_params_are_marked() {
         let src = "йопта фу(a, b = 1, ...rest) { отвечаю a; }\nфу(1, ";
         let help = help_at(src, "фу(1, ").expect("should resolve signature");
-        assert_eq!(help.signatures[0].label, "фу(a, b?, ...rest)");
+        assert_eq!(help.signatures[0].label, "фу(a, b?: число, ...rest: массив)");
+    }
+
+    #[test]
+    fn inferred_parameter_and_return_types_are_shown() {
+        let src = "йопта имя(префикс = \"м\") { отвечаю префикс + 1; }\nимя(";
+        let help = help_at(src, "имя(").expect("should resolve signature");
+        assert_eq!(help.signatures[0].label, "имя(префикс?: строка): строка");
     }
 
     #[test]
@@ -1,7 +1,7 @@
 use tower_lsp::lsp_types::{DocumentSymbol, SymbolKind};
 use yps_lexer::Span;
 use yps_parser::Program;
-use yps_parser::ast::{ClassMember, Identifier, Pattern, Stmt};
+use yps_parser::ast::{ClassMember, ExportKind, Identifier, Pattern, Stmt};
 
 use crate::position::span_to_range;
 
@@ -24,12 +24,20 @@ fn collect(stmt: &Stmt, text: &str, out: &mut Vec<DocumentSymbol>) {
             let children = class_children(members, text);
             out.push(symbol(text, &name.name, None, SymbolKind::CLASS, *span, name.span, Some(children)));
         }
+        Stmt::EnumDecl { name, members, span } => {
+            let children = members
+                .iter()
+                .map(|m| symbol(text, &m.name.name, None, SymbolKind::ENUM_MEMBER, m.span, m.name.span, None))
+                .collect();
+            out.push(symbol(text, &name.name, None, SymbolKind::ENUM, *span, name.span, Some(children)));
+        }
         Stmt::VarDecl { pattern, is_const, span, .. } => {
             let kind = if *is_const { SymbolKind::CONSTANT } else { SymbolKind::VARIABLE };
             for ident in pattern_idents(pattern) {
                 out.push(symbol(text, &ident.name, None, kind, *span, ident.span, None));
             }
         }
+        Stmt::Export { kind: ExportKind::Declaration(inner), .. } => collect(inner, text, out),
         _ => {}
     }
 }
@@ -58,7 +66,7 @@ fn class_children(members: &[ClassMember], text: &str) -> Vec<DocumentSymbol> {
         .collect()
 }
 
-fn pattern_idents(pattern: &Pattern) -> Vec<&Identifier> {
+pub(crate) fn pattern_idents(pattern: &Pattern) -> Vec<&Identifier> {
     let mut out = Vec::new();
     push_pattern_idents(pattern, &mut out);
     out
@@ -147,6 +155,12 @@ mod tests {
         assert_eq!(syms[2].kind, SymbolKind::VARIABLE);
     }
 
+    #[test]
+    fn exported_declarations_are_listed() {
+        let src = "предъява йопта сложить(а, б) { отвечаю а + б; }\nпредъява ясенХуй база = 0;";
+        assert_eq!(names(&symbols_of(src)), vec!["сложить", "база"]);
+    }
+
     #[test]
     fn class_members_are_nested() {
         let src = "клёво Кот { constructor() {} мяу() {} }";
@@ -158,6 +172,15 @@ mod tests {
         assert!(child_names.contains(&"мяу"), "got {child_names:?}");
     }
 
+    #[test]
+    fn enum_members_are_nested() {
+        let syms = symbols_of("еээ Цвет { Красный, Зелёный = 5 }");
+        assert_eq!(syms[0].kind, SymbolKind::ENUM);
+        let children = syms[0].children.as_ref().unwrap();
+        assert_eq!(names(children), vec!["Красный", "Зелёный"]);
+        assert!(children.iter().all(|c| c.kind == SymbolKind::ENUM_MEMBER));
+    }
+
     #[test]
     fn selection_range_points_at_name() {
         let src = "йопта фу() {}";
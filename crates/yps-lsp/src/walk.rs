@@ -0,0 +1,365 @@
+use yps_lexer::Span;
+use yps_parser::ast::{
+    Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, MatchPattern, ObjectEntry, Param, Pattern,
+    PropKey, Stmt, TemplatePart,
+};
+
+/// A syntax node that has a span of its own.
+#[derive(Clone, Copy)]
+pub enum Node<'a> {
+    Stmt(&'a Stmt),
+    Block(&'a Block),
+    Expr(&'a Expr),
+    Pattern(&'a Pattern),
+    Ident(&'a Identifier),
+    Member(&'a ClassMember),
+    Param(&'a Param),
+}
+
+impl<'a> Node<'a> {
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Stmt(stmt) => stmt.span(),
+            Node::Block(block) => block.span,
+            Node::Expr(expr) => expr.span(),
+            Node::Pattern(pattern) => pattern.span(),
+            Node::Ident(ident) => ident.span,
+            Node::Member(member) => match member {
+                ClassMember::Constructor { span, .. }
+                | ClassMember::Method { span, .. }
+                | ClassMember::Field { span, .. }
+                | ClassMember::Getter { span, .. }
+                | ClassMember::Setter { span, .. }
+                | ClassMember::StaticBlock { span, .. } => *span,
+            },
+            Node::Param(param) => {
+                let start = param.pattern.as_ref().map_or(param.name.span, Pattern::span);
+                let end = param.default.as_ref().map_or(start.end, |d| d.span().end);
+                Span { start: start.start, end }
+            }
+        }
+    }
+
+    /// Direct children in source order.
+    #[must_use]
+    pub fn children(&self) -> Vec<Node<'a>> {
+        let mut out = Vec::new();
+        match *self {
+            Node::Stmt(stmt) => stmt_children(stmt, &mut out),
+            Node::Block(block) => out.extend(block.stmts.iter().map(Node::Stmt)),
+            Node::Expr(expr) => expr_children(expr, &mut out),
+            Node::Pattern(pattern) => match pattern {
+                Pattern::Identifier(ident) => out.push(Node::Ident(ident)),
+                Pattern::Array { elements, rest, .. } => {
+                    out.extend(elements.iter().flatten().map(Node::Pattern));
+                    out.extend(rest.as_deref().map(Node::Pattern));
+                }
+                Pattern::Object { properties, rest, .. } => {
+                    for prop in properties {
+                        out.push(Node::Ident(&prop.key));
+                        out.extend(prop.value.as_ref().map(Node::Pattern));
+                    }
+                    out.extend(rest.as_deref().map(Node::Pattern));
+                }
+                Pattern::Default { pattern, default, .. } => {
+                    out.push(Node::Pattern(pattern));
+                    out.push(Node::Expr(default));
+                }
+            },
+            Node::Ident(_) => {}
+            Node::Member(member) => match member {
+                ClassMember::Constructor { params, body, .. } => {
+                    out.extend(params.iter().map(Node::Param));
+                    out.push(Node::Block(body));
+                }
+                ClassMember::Method { name, params, body, decorators, .. } => {
+                    out.extend(decorators.iter().map(Node::Expr));
+                    out.push(Node::Ident(name));
+                    out.extend(params.iter().map(Node::Param));
+                    out.push(Node::Block(body));
+                }
+                ClassMember::Field { name, init, decorators, .. } => {
+                    out.extend(decorators.iter().map(Node::Expr));
+                    out.push(Node::Ident(name));
+                    out.extend(init.as_ref().map(Node::Expr));
+                }
+                ClassMember::Getter { name, body, decorators, .. } => {
+                    out.extend(decorators.iter().map(Node::Expr));
+                    out.push(Node::Ident(name));
+                    out.push(Node::Block(body));
+                }
+                ClassMember::Setter { name, param, body, decorators, .. } => {
+                    out.extend(decorators.iter().map(Node::Expr));
+                    out.push(Node::Ident(name));
+                    out.push(Node::Param(param));
+                    out.push(Node::Block(body));
+                }
+                ClassMember::StaticBlock { body, .. } => out.push(Node::Block(body)),
+            },
+            Node::Param(param) => match &param.pattern {
+                Some(pattern) => out.push(Node::Pattern(pattern)),
+                None => {
+                    out.push(Node::Ident(&param.name));
+                    out.extend(param.default.as_ref().map(Node::Expr));
+                }
+            },
+        }
+        out
+    }
+}
+
+fn stmt_children<'a>(stmt: &'a Stmt, out: &mut Vec<Node<'a>>) {
+    match stmt {
+        Stmt::VarDecl { pattern, init, .. } => {
+            out.push(Node::Pattern(pattern));
+            out.push(Node::Expr(init));
+        }
+        Stmt::Expr { expr, .. } | Stmt::Throw { value: expr, .. } => out.push(Node::Expr(expr)),
+        Stmt::Block(block) => out.push(Node::Block(block)),
+        Stmt::Empty { .. } | Stmt::Debugger { .. } | Stmt::TypeAlias { .. } | Stmt::Error { .. } => {}
+        Stmt::If { condition, then_branch, else_branch, .. } => {
+            out.push(Node::Expr(condition));
+            out.push(Node::Stmt(then_branch));
+            out.extend(else_branch.as_deref().map(Node::Stmt));
+        }
+        Stmt::While { condition, body, .. } => {
+            out.push(Node::Expr(condition));
+            out.push(Node::Stmt(body));
+        }
+        Stmt::DoWhile { body, condition, .. } => {
+            out.push(Node::Stmt(body));
+            out.push(Node::Expr(condition));
+        }
+        Stmt::For { init, condition, update, body, .. } => {
+            out.extend(init.as_deref().map(Node::Stmt));
+            out.extend(condition.as_ref().map(Node::Expr));
+            out.extend(update.as_ref().map(Node::Expr));
+            out.push(Node::Stmt(body));
+        }
+        Stmt::Break { label, .. } | Stmt::Continue { label, .. } => out.extend(label.as_ref().map(Node::Ident)),
+        Stmt::Labeled { label, body, .. } => {
+            out.push(Node::Ident(label));
+            out.push(Node::Stmt(body));
+        }
+        Stmt::FunctionDecl { name, params, body, .. } => {
+            out.push(Node::Ident(name));
+            out.extend(params.iter().map(Node::Param));
+            out.push(Node::Block(body));
+        }
+        Stmt::Return { value, .. } => out.extend(value.as_ref().map(Node::Expr)),
+        Stmt::TryCatch { try_block, catch_param, catch_block, finally_block, .. } => {
+            out.push(Node::Block(try_block));
+            out.extend(catch_param.as_ref().map(Node::Ident));
+            out.extend(catch_block.as_ref().map(Node::Block));
+            out.extend(finally_block.as_ref().map(Node::Block));
+        }
+        Stmt::Switch { expr, cases, default, .. } => {
+            out.push(Node::Expr(expr));
+            for case in cases {
+                out.push(Node::Expr(&case.value));
+                out.push(Node::Block(&case.body));
+            }
+            out.extend(default.as_ref().map(Node::Block));
+        }
+        Stmt::ForIn { variable, iterable, body, .. }
+        | Stmt::ForOf { variable, iterable, body, .. }
+        | Stmt::ForAwaitOf { variable, iterable, body, .. } => {
+            out.push(Node::Pattern(variable));
+            out.push(Node::Expr(iterable));
+            out.push(Node::Stmt(body));
+        }
+        Stmt::ClassDecl { name, super_class, members, decorators, .. } => {
+            out.extend(decorators.iter().map(Node::Expr));
+            out.push(Node::Ident(name));
+            out.extend(super_class.as_ref().map(Node::Expr));
+            out.extend(members.iter().map(Node::Member));
+        }
+        Stmt::Using { name, init, .. } => {
+            out.push(Node::Ident(name));
+            out.push(Node::Expr(init));
+        }
+        Stmt::EnumDecl { name, members, .. } => {
+            out.push(Node::Ident(name));
+            for member in members {
+                out.push(Node::Ident(&member.name));
+                out.extend(member.init.as_ref().map(Node::Expr));
+            }
+        }
+        Stmt::Import { specifiers, .. } => {
+            for spec in specifiers {
+                match spec {
+                    ImportSpec::Default { local } | ImportSpec::Namespace { local } => out.push(Node::Ident(local)),
+                    ImportSpec::Named { imported, local } => {
+                        out.push(Node::Ident(imported));
+                        if imported.span != local.span {
+                            out.push(Node::Ident(local));
+                        }
+                    }
+                }
+            }
+        }
+        Stmt::Export { kind, .. } => match kind {
+            ExportKind::Declaration(inner) => out.push(Node::Stmt(inner)),
+            ExportKind::Named(names) => out.extend(names.iter().map(Node::Ident)),
+        },
+    }
+}
+
+fn expr_children<'a>(expr: &'a Expr, out: &mut Vec<Node<'a>>) {
+    match expr {
+        Expr::Identifier(ident) => out.push(Node::Ident(ident)),
+        Expr::Literal(literal) => match literal {
+            Literal::Array { elements, .. } => out.extend(elements.iter().map(Node::Expr)),
+            Literal::Object { entries, .. } => {
+                for entry in entries {
+                    match entry {
+                        ObjectEntry::Property { key, value } => {
+                            push_key(key, out);
+                            if key_span(key) != Some(value.span()) {
+                                out.push(Node::Expr(value));
+                            }
+                        }
+                        ObjectEntry::Spread(expr) => out.push(Node::Expr(expr)),
+                        ObjectEntry::Getter { key, body, .. } => {
+                            push_key(key, out);
+                            out.push(Node::Block(body));
+                        }
+                        ObjectEntry::Setter { key, param, body, .. } => {
+                            push_key(key, out);
+                            out.push(Node::Param(param));
+                            out.push(Node::Block(body));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        },
+        Expr::Unary { expr, .. }
+        | Expr::Postfix { expr, .. }
+        | Expr::Grouping { expr, .. }
+        | Expr::Spread { expr, .. }
+        | Expr::Await { argument: expr, .. } => out.push(Node::Expr(expr)),
+        Expr::Binary { lhs, rhs, .. } => {
+            out.push(Node::Expr(lhs));
+            out.push(Node::Expr(rhs));
+        }
+        Expr::Assignment { target, value, .. } => {
+            out.push(Node::Ident(target));
+            out.push(Node::Expr(value));
+        }
+        Expr::Call { callee, args, .. } | Expr::OptionalCall { callee, args, .. } | Expr::New { callee, args, .. } => {
+            out.push(Node::Expr(callee));
+            out.extend(args.iter().map(Node::Expr));
+        }
+        Expr::Index { object, index, .. } | Expr::OptionalIndex { object, index, .. } => {
+            out.push(Node::Expr(object));
+            out.push(Node::Expr(index));
+        }
+        Expr::Member { object, property, .. } | Expr::OptionalMember { object, property, .. } => {
+            out.push(Node::Expr(object));
+            out.push(Node::Ident(property));
+        }
+        Expr::Conditional { condition, then_expr, else_expr, .. } => {
+            out.push(Node::Expr(condition));
+            out.push(Node::Expr(then_expr));
+            out.push(Node::Expr(else_expr));
+        }
+        Expr::ArrowFunction { params, body, .. } => {
+            out.extend(params.iter().map(Node::Param));
+            out.push(Node::Block(body));
+        }
+        Expr::FunctionExpr { name, params, body, .. } => {
+            out.extend(name.as_ref().map(Node::Ident));
+            out.extend(params.iter().map(Node::Param));
+            out.push(Node::Block(body));
+        }
+        Expr::TemplateLiteral { parts, .. } => {
+            for part in parts {
+                if let TemplatePart::Expr(expr) = part {
+                    out.push(Node::Expr(expr));
+                }
+            }
+        }
+        Expr::TaggedTemplate { tag, expressions, .. } => {
+            out.push(Node::Expr(tag));
+            out.extend(expressions.iter().map(Node::Expr));
+        }
+        Expr::Yield { argument, .. } => out.extend(argument.as_deref().map(Node::Expr)),
+        Expr::DynamicImport { source, .. } => out.push(Node::Expr(source)),
+        Expr::Match { subject, arms, .. } => {
+            out.push(Node::Expr(subject));
+            for arm in arms {
+                match &arm.pattern {
+                    MatchPattern::Bind(pattern) => out.push(Node::Pattern(pattern)),
+                    MatchPattern::Instance { class, pattern, .. } => {
+                        out.push(Node::Expr(class));
+                        out.extend(pattern.as_ref().map(Node::Pattern));
+                    }
+                    MatchPattern::Value(value) => out.push(Node::Expr(value)),
+                    MatchPattern::Wildcard { .. } => {}
+                }
+                out.extend(arm.guard.as_ref().map(Node::Expr));
+                out.push(Node::Expr(&arm.body));
+            }
+        }
+        Expr::This { .. }
+        | Expr::Super { .. }
+        | Expr::NewTarget { .. }
+        | Expr::ImportMeta { .. }
+        | Expr::Error { .. } => {}
+    }
+}
+
+fn push_key<'a>(key: &'a PropKey, out: &mut Vec<Node<'a>>) {
+    match key {
+        PropKey::Identifier(ident) => out.push(Node::Ident(ident)),
+        PropKey::Computed(expr) => out.push(Node::Expr(expr)),
+    }
+}
+
+fn key_span(key: &PropKey) -> Option<Span> {
+    match key {
+        PropKey::Identifier(ident) => Some(ident.span),
+        PropKey::Computed(_) => None,
+    }
+}
+
+/// Visits every node under `stmts` in pre-order.
+pub fn walk<'a>(stmts: &'a [Stmt], f: &mut impl FnMut(Node<'a>)) {
+    visit(stmts.iter().rev().map(Node::Stmt).collect(), f);
+}
+
+/// Visits `root` and every node under it in pre-order.
+pub fn walk_node<'a>(root: Node<'a>, f: &mut impl FnMut(Node<'a>)) {
+    visit(vec![root], f);
+}
+
+fn visit<'a>(mut stack: Vec<Node<'a>>, f: &mut impl FnMut(Node<'a>)) {
+    while let Some(node) = stack.pop() {
+        f(node);
+        stack.extend(node.children().into_iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yps_lexer::{Lexer, SourceFile};
+    use yps_parser::Parser;
+
+    #[test]
+    fn walk_reaches_nested_identifiers_in_source_order() {
+        let src = "йопта ф(а) { отвечаю [а, { б: а }]; }";
+        let sf = SourceFile::new("inline".to_string(), src.to_string());
+        let (tokens, _) = Lexer::new(&sf).tokenize();
+        let (program, _) = Parser::new(&tokens, &sf).parse_program();
+        let mut idents = Vec::new();
+        walk(&program.items, &mut |node| {
+            if let Node::Ident(ident) = node {
+                idents.push(ident.name.clone());
+            }
+        });
+        assert_eq!(idents, ["ф", "а", "а", "б", "а"]);
+    }
+}
@@ -0,0 +1,871 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tower_lsp::lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, DocumentSymbol, Location, Position, Range,
+    SymbolInformation, SymbolKind, TypeHierarchyItem, Url,
+};
+use yps_interpreter::interpreter::resolve_import;
+use yps_lexer::{Lexer, PunctuationKind, SourceFile, Span, TokenKind};
+use yps_parser::Parser;
+use yps_parser::ast::{ExportKind, ImportSpec, Stmt};
+
+use crate::hierarchy::{CallSite, ClassEntry, Outline};
+use crate::position::{pos_to_byte, span_to_range};
+use crate::rename::{is_valid_new_name, occurrences_at};
+use crate::symbols::{document_symbols, pattern_idents};
+
+const EXTENSION: &str = "yopta";
+/// Re-export chains longer than this are treated as cycles.
+const MAX_REEXPORT_DEPTH: usize = 32;
+
+struct ImportLink {
+    source: String,
+    /// Canonical path `source` resolved to, kept so queries need no file system access; `None`
+    /// while no such file exists.
+    target: Option<PathBuf>,
+    /// Imported export name; `None` for `спиздить * как ns`.
+    name: Option<String>,
+    local: Span,
+}
+
+struct ModuleIndex {
+    text: String,
+    imports: Vec<ImportLink>,
+    /// Export name to the identifier that exports it (the declaration or the `предъява { … }` entry).
+    exports: HashMap<String, Span>,
+    symbols: Vec<DocumentSymbol>,
+    outline: Outline,
+}
+
+impl ModuleIndex {
+    fn new(path: &Path, text: &str) -> Self {
+        let sf = SourceFile::new("inline".to_string(), text.to_string());
+        let (tokens, _) = Lexer::new(&sf).tokenize();
+        let (program, _) = Parser::new(&tokens, &sf).parse_program();
+
+        let mut imports = Vec::new();
+        let mut exports = HashMap::new();
+        for stmt in &program.items {
+            match stmt {
+                Stmt::Import { specifiers, source, .. } => {
+                    for spec in specifiers {
+                        let (name, local) = match spec {
+                            ImportSpec::Default { local } => (Some("default".to_string()), local),
+                            ImportSpec::Named { imported, local } => (Some(imported.name.clone()), local),
+                            ImportSpec::Namespace { local } => (None, local),
+                        };
+                        imports.push(ImportLink {
+                            source: source.clone(),
+                            target: resolve_from(path, source),
+                            name,
+                            local: local.span,
+                        });
+                    }
+                }
+                Stmt::Export { kind: ExportKind::Declaration(inner), .. } => match inner.as_ref() {
+                    Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } | Stmt::EnumDecl { name, .. } => {
+                        exports.insert(name.name.clone(), name.span);
+                    }
+                    Stmt::VarDecl { pattern, .. } => {
+                        for ident in pattern_idents(pattern) {
+                            exports.insert(ident.name.clone(), ident.span);
+                        }
+                    }
+                    _ => {}
+                },
+                Stmt::Export { kind: ExportKind::Named(idents), .. } => {
+                    for ident in idents {
+                        exports.insert(ident.name.clone(), ident.span);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            text: text.to_string(),
+            imports,
+            exports,
+            symbols: document_symbols(&program, text),
+            outline: Outline::new(&program),
+        }
+    }
+
+    /// `ns.name` accesses through the namespace import bound at `local`.
+    fn namespace_members(&self, local: Span, name: &str) -> Vec<Span> {
+        let Some(uses) = occurrences_at(&self.text, local.start) else {
+            return Vec::new();
+        };
+        let sf = SourceFile::new("inline".to_string(), self.text.clone());
+        let (tokens, _) = Lexer::new(&sf).tokenize();
+        tokens
+            .windows(3)
+            .filter(|w| {
+                uses.contains(&w[0].span)
+                    && w[1].kind == TokenKind::Punctuation(PunctuationKind::Dot)
+                    && w[2].kind == TokenKind::Identifier
+                    && &self.text[w[2].span.start..w[2].span.end] == name
+            })
+            .map(|w| w[2].span)
+            .collect()
+    }
+}
+
+/// Every `.yopta` module under the workspace folders, kept in sync with open buffers and
+/// file-watcher notifications. Paths are canonical, matching the module loader's cache keys.
+#[derive(Default)]
+pub struct WorkspaceIndex {
+    modules: HashMap<PathBuf, ModuleIndex>,
+}
+
+#[must_use]
+pub fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn resolve_from(importer: &Path, source: &str) -> Option<PathBuf> {
+    resolve_import(importer.parent()?, source).ok()
+}
+
+impl WorkspaceIndex {
+    /// Symlinked folders are not entered, so a link back up the tree cannot loop.
+    pub fn index_folder(&mut self, root: &Path) {
+        self.scan(root);
+        self.resolve_dangling();
+    }
+
+    fn scan(&mut self, root: &Path) {
+        let Ok(entries) = fs::read_dir(root) else { return };
+        for entry in entries.flatten() {
+            let Ok(kind) = entry.file_type() else { continue };
+            let path = entry.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if kind.is_dir() {
+                if !name.starts_with('.') && name != "target" && name != "node_modules" {
+                    self.scan(&path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == EXTENSION)
+                && let Ok(text) = fs::read_to_string(&path)
+            {
+                self.insert(&path, &text);
+            }
+        }
+    }
+
+    /// Adds the modules of `other`, an index built off to the side, except those this one already
+    /// has: those were opened or changed since, so they are newer than what `other` read.
+    pub fn merge(&mut self, other: WorkspaceIndex) {
+        for (path, module) in other.modules {
+            self.modules.entry(path).or_insert(module);
+        }
+        self.resolve_dangling();
+    }
+
+    pub fn update(&mut self, path: &Path, text: &str) {
+        if self.insert(path, text) {
+            self.resolve_dangling();
+        }
+    }
+
+    /// Whether `path` is new to the index.
+    fn insert(&mut self, path: &Path, text: &str) -> bool {
+        let path = canonical(path);
+        let module = ModuleIndex::new(&path, text);
+        self.modules.insert(path, module).is_none()
+    }
+
+    /// Imports of a file that did not exist yet may resolve now that a module appeared.
+    fn resolve_dangling(&mut self) {
+        for (path, module) in &mut self.modules {
+            for link in module.imports.iter_mut().filter(|link| link.target.is_none()) {
+                link.target = resolve_from(path, &link.source);
+            }
+        }
+    }
+
+    /// Re-reads `path` from disk, dropping it from the index if it is gone.
+    pub fn reload(&mut self, path: &Path) {
+        match fs::read_to_string(path) {
+            Ok(text) => self.update(path, &text),
+            Err(_) => self.remove(path),
+        }
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.modules.remove(&canonical(path));
+    }
+
+    #[must_use]
+    pub fn contains(&self, path: &Path) -> bool {
+        self.modules.contains_key(&canonical(path))
+    }
+
+    fn resolve(&self, importer: &Path, source: &str) -> Option<PathBuf> {
+        resolve_from(importer, source).filter(|p| self.modules.contains_key(p))
+    }
+
+    /// The indexed module `link` imports from.
+    fn target<'s>(&'s self, link: &'s ImportLink) -> Option<&'s PathBuf> {
+        link.target.as_ref().filter(|p| self.modules.contains_key(*p))
+    }
+
+    /// Follows imports (and re-exports) back to the binding's declaration.
+    #[must_use]
+    pub fn definition(&self, path: &Path, byte_pos: usize) -> Option<(PathBuf, Span)> {
+        let mut file = canonical(path);
+        let mut occurrences = occurrences_at(&self.modules.get(&file)?.text, byte_pos)?;
+        for _ in 0..MAX_REEXPORT_DEPTH {
+            let module = self.modules.get(&file)?;
+            let origin = module.imports.iter().filter(|link| occurrences.contains(&link.local)).find_map(|link| {
+                let target = self.target(link)?;
+                let span = *self.modules.get(target)?.exports.get(link.name.as_deref()?)?;
+                Some((target.clone(), span))
+            });
+            let Some((target, span)) = origin else {
+                return occurrences.first().map(|span| (file, *span));
+            };
+            occurrences = occurrences_at(&self.modules[&target].text, span.start)?;
+            file = target;
+        }
+        None
+    }
+
+    /// The binding at the cursor together with every binding linked to it through imports and
+    /// exports, in any module of the workspace.
+    fn linked_occurrences(&self, path: &Path, byte_pos: usize) -> Option<Vec<(PathBuf, Span)>> {
+        let file = canonical(path);
+        let start = occurrences_at(&self.modules.get(&file)?.text, byte_pos)?;
+        let mut out = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([(file, start)]);
+        while let Some((file, occurrences)) = queue.pop_front() {
+            let Some(first) = occurrences.first() else { continue };
+            if !seen.insert((file.clone(), first.start)) {
+                continue;
+            }
+            let module = &self.modules[&file];
+            for link in module.imports.iter().filter(|link| occurrences.contains(&link.local)) {
+                let Some(name) = &link.name else { continue };
+                let Some(target) = self.target(link) else { continue };
+                let exporter = &self.modules[target];
+                if let Some(span) = exporter.exports.get(name)
+                    && let Some(linked) = occurrences_at(&exporter.text, span.start)
+                {
+                    queue.push_back((target.clone(), linked));
+                }
+            }
+            for (name, _) in module.exports.iter().filter(|(_, span)| occurrences.contains(span)) {
+                for (other_path, other) in &self.modules {
+                    for link in &other.imports {
+                        if self.target(link) != Some(&file) {
+                            continue;
+                        }
+                        match &link.name {
+                            Some(imported) if imported == name => {
+                                if let Some(linked) = occurrences_at(&other.text, link.local.start) {
+                                    queue.push_back((other_path.clone(), linked));
+                                }
+                            }
+                            Some(_) => {}
+                            None => out.extend(
+                                other.namespace_members(link.local, name).into_iter().map(|s| (other_path.clone(), s)),
+                            ),
+                        }
+                    }
+                }
+            }
+            out.extend(occurrences.into_iter().map(|span| (file.clone(), span)));
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.start.cmp(&b.1.start)));
+        out.dedup();
+        Some(out)
+    }
+
+    #[must_use]
+    pub fn references(&self, path: &Path, byte_pos: usize, include_declaration: bool) -> Option<Vec<(PathBuf, Span)>> {
+        let mut refs = self.linked_occurrences(path, byte_pos)?;
+        if !include_declaration && let Some(decl) = self.definition(path, byte_pos) {
+            refs.retain(|r| *r != decl);
+        }
+        Some(refs)
+    }
+
+    #[must_use]
+    pub fn rename_edits(&self, path: &Path, byte_pos: usize, new_name: &str) -> Option<Vec<(PathBuf, Span)>> {
+        if !is_valid_new_name(new_name) {
+            return None;
+        }
+        self.linked_occurrences(path, byte_pos)
+    }
+
+    /// `workspace/symbol`: top-level declarations and class members whose name contains `query`.
+    #[must_use]
+    pub fn symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let query = query.to_lowercase();
+        let mut out = Vec::new();
+        for (path, module) in &self.modules {
+            let Ok(uri) = Url::from_file_path(path) else { continue };
+            for symbol in &module.symbols {
+                push_symbol(&mut out, symbol, None, &uri, &query);
+                for child in symbol.children.iter().flatten() {
+                    push_symbol(&mut out, child, Some(&symbol.name), &uri, &query);
+                }
+            }
+        }
+        out.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.location.uri.as_str().cmp(b.location.uri.as_str())));
+        out
+    }
+
+    /// Import specifiers (`"./lib"`, `"../util/строки"`) of the modules exporting `name`,
+    /// relative to `importer`.
+    #[must_use]
+    pub fn import_sources(&self, importer: &Path, name: &str) -> Vec<String> {
+        let importer = canonical(importer);
+        let Some(dir) = importer.parent() else { return Vec::new() };
+        let mut out: Vec<String> = self
+            .modules
+            .iter()
+            .filter(|(path, module)| **path != importer && module.exports.contains_key(name))
+            .filter_map(|(path, _)| {
+                let source = relative_specifier(dir, &path.with_extension(""))?;
+                (self.resolve(&importer, &source).as_ref() == Some(path)).then_some(source)
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// Converts per-file spans into LSP locations.
+    #[must_use]
+    pub fn locations(&self, spans: &[(PathBuf, Span)]) -> Vec<Location> {
+        spans
+            .iter()
+            .filter_map(|(path, span)| {
+                let module = self.modules.get(path)?;
+                let uri = Url::from_file_path(path).ok()?;
+                Some(Location { uri, range: span_to_range(&module.text, *span) })
+            })
+            .collect()
+    }
+}
+
+/// `target` as a `./` or `../` specifier seen from `dir`.
+fn relative_specifier(dir: &Path, target: &Path) -> Option<String> {
+    let common = dir.components().zip(target.components()).take_while(|(a, b)| a == b).count();
+    let ups = dir.components().count() - common;
+    let rest: Vec<_> = target.components().skip(common).map(|c| c.as_os_str().to_str()).collect::<Option<_>>()?;
+    let prefix = if ups == 0 { "./".to_string() } else { "../".repeat(ups) };
+    Some(format!("{prefix}{}", rest.join("/")))
+}
+
+/// A callable of the call hierarchy: a function, class or method in `path`, or the module's
+/// top-level code when the index is `None`.
+type CallTarget = (PathBuf, Option<usize>);
+
+impl WorkspaceIndex {
+    /// `textDocument/prepareCallHierarchy`: the callable declared at the cursor, or the targets of
+    /// the call under it.
+    #[must_use]
+    pub fn prepare_call_hierarchy(&self, path: &Path, byte_pos: usize) -> Option<Vec<CallHierarchyItem>> {
+        let file = canonical(path);
+        let module = self.modules.get(&file)?;
+        let targets = match module.outline.callable_at(byte_pos) {
+            Some(index) => vec![(file, Some(index))],
+            None => self.call_targets(&file, module.outline.call_at(byte_pos)?),
+        };
+        let items: Vec<_> = targets.iter().filter_map(|target| self.call_item(target)).collect();
+        if items.is_empty() { None } else { Some(items) }
+    }
+
+    /// Every call site that reaches `item`, grouped by the calling function.
+    #[must_use]
+    pub fn incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        let Some((path, Some(index))) = self.call_target(item) else { return Vec::new() };
+        let callable = &self.modules[&path].outline.callables[index];
+        let linked: HashSet<(PathBuf, usize)> = if callable.kind == SymbolKind::METHOD {
+            HashSet::new()
+        } else {
+            self.linked_occurrences(&path, callable.name_span.start)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(file, span)| (file, span.start))
+                .collect()
+        };
+
+        let mut grouped: Vec<(CallTarget, Vec<Span>)> = Vec::new();
+        for (file, module) in &self.modules {
+            for call in &module.outline.calls {
+                let reaches = if callable.kind == SymbolKind::METHOD {
+                    call.is_member
+                        && call.name == callable.name
+                        && self.call_targets(file, call).contains(&(path.clone(), Some(index)))
+                } else {
+                    linked.contains(&(file.clone(), call.callee.start))
+                };
+                if reaches {
+                    push_grouped(&mut grouped, (file.clone(), call.caller), call.callee);
+                }
+            }
+        }
+        grouped.sort_by(|a, b| a.0.cmp(&b.0));
+        grouped
+            .into_iter()
+            .filter_map(|(caller, spans)| {
+                let text = &self.modules[&caller.0].text;
+                let from_ranges = spans.iter().map(|span| span_to_range(text, *span)).collect();
+                Some(CallHierarchyIncomingCall { from: self.call_item(&caller)?, from_ranges })
+            })
+            .collect()
+    }
+
+    /// Calls made directly from `item`'s body, grouped by the function they reach.
+    #[must_use]
+    pub fn outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        let Some((path, index)) = self.call_target(item) else { return Vec::new() };
+        let module = &self.modules[&path];
+        let mut grouped: Vec<(CallTarget, Vec<Span>)> = Vec::new();
+        for call in module.outline.calls.iter().filter(|call| call.caller == index) {
+            for target in self.call_targets(&path, call) {
+                push_grouped(&mut grouped, target, call.callee);
+            }
+        }
+        grouped.sort_by_key(|(_, spans)| spans[0].start);
+        grouped
+            .into_iter()
+            .filter_map(|(target, spans)| {
+                let from_ranges = spans.iter().map(|span| span_to_range(&module.text, *span)).collect();
+                Some(CallHierarchyOutgoingCall { to: self.call_item(&target)?, from_ranges })
+            })
+            .collect()
+    }
+
+    /// `textDocument/prepareTypeHierarchy`: the class declared at the cursor or the one the
+    /// identifier under it refers to.
+    #[must_use]
+    pub fn prepare_type_hierarchy(&self, path: &Path, byte_pos: usize) -> Option<Vec<TypeHierarchyItem>> {
+        let file = canonical(path);
+        let module = self.modules.get(&file)?;
+        if let Some(class) = module.outline.class_at(byte_pos) {
+            return Some(vec![self.type_item(&file, class)?]);
+        }
+        let (target, span) = self.definition(&file, byte_pos)?;
+        let class = self.modules[&target].outline.class_at(span.start)?;
+        Some(vec![self.type_item(&target, class)?])
+    }
+
+    /// The class named after `батя` in `item`'s declaration.
+    #[must_use]
+    pub fn supertypes(&self, item: &TypeHierarchyItem) -> Vec<TypeHierarchyItem> {
+        let Some((path, byte_pos)) = self.item_offset(&item.uri, item.selection_range.start) else { return Vec::new() };
+        let Some(super_class) = self.modules[&path].outline.class_at(byte_pos).and_then(|c| c.super_class) else {
+            return Vec::new();
+        };
+        let Some((target, span)) = self.definition(&path, super_class.start) else { return Vec::new() };
+        self.modules[&target]
+            .outline
+            .class_at(span.start)
+            .and_then(|class| self.type_item(&target, class))
+            .into_iter()
+            .collect()
+    }
+
+    /// Classes in any module that extend `item` directly.
+    #[must_use]
+    pub fn subtypes(&self, item: &TypeHierarchyItem) -> Vec<TypeHierarchyItem> {
+        let Some((path, byte_pos)) = self.item_offset(&item.uri, item.selection_range.start) else { return Vec::new() };
+        let Some(class) = self.modules[&path].outline.class_at(byte_pos) else { return Vec::new() };
+        let linked: HashSet<(PathBuf, usize)> = self
+            .linked_occurrences(&path, class.name_span.start)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(file, span)| (file, span.start))
+            .collect();
+        let mut out: Vec<(PathBuf, &ClassEntry)> = Vec::new();
+        for (file, module) in &self.modules {
+            for sub in &module.outline.classes {
+                if sub.super_class.is_some_and(|span| linked.contains(&(file.clone(), span.start))) {
+                    out.push((file.clone(), sub));
+                }
+            }
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.span.start.cmp(&b.1.span.start)));
+        out.into_iter().filter_map(|(file, sub)| self.type_item(&file, sub)).collect()
+    }
+
+    fn item_offset(&self, uri: &Url, position: Position) -> Option<(PathBuf, usize)> {
+        let path = canonical(&uri.to_file_path().ok()?);
+        let byte_pos = pos_to_byte(&self.modules.get(&path)?.text, position);
+        Some((path, byte_pos))
+    }
+
+    fn call_target(&self, item: &CallHierarchyItem) -> Option<CallTarget> {
+        let (path, byte_pos) = self.item_offset(&item.uri, item.selection_range.start)?;
+        if item.kind == SymbolKind::FILE {
+            return Some((path, None));
+        }
+        let index = self.modules[&path].outline.callable_at(byte_pos)?;
+        Some((path, Some(index)))
+    }
+
+    /// Callables a call site may reach. Plain calls follow scope resolution and imports;
+    /// `ns.ф()` follows a namespace import; other method calls match methods by name, preferring
+    /// the caller's own class.
+    fn call_targets(&self, file: &Path, call: &CallSite) -> Vec<CallTarget> {
+        let module = &self.modules[file];
+        if !call.is_member {
+            return self
+                .definition(file, call.callee.start)
+                .and_then(|(target, span)| {
+                    let index = self.modules[&target].outline.callable_at(span.start)?;
+                    Some(vec![(target, Some(index))])
+                })
+                .unwrap_or_default();
+        }
+        if let Some(receiver) = call.receiver
+            && let Some(uses) = occurrences_at(&module.text, receiver.start)
+            && let Some(link) = module.imports.iter().find(|link| link.name.is_none() && uses.contains(&link.local))
+        {
+            return self
+                .target(link)
+                .and_then(|target| {
+                    let span = *self.modules[target].exports.get(&call.name)?;
+                    let (target, span) = self.definition(target, span.start)?;
+                    let index = self.modules[&target].outline.callable_at(span.start)?;
+                    Some(vec![(target, Some(index))])
+                })
+                .unwrap_or_default();
+        }
+
+        let own_class = call.caller.and_then(|i| {
+            let caller = &module.outline.callables[i];
+            if caller.kind == SymbolKind::CLASS { Some(&caller.name) } else { caller.container.as_ref() }
+        });
+        let mut methods: Vec<(CallTarget, bool)> = Vec::new();
+        for (path, other) in &self.modules {
+            for (index, callable) in other.outline.callables.iter().enumerate() {
+                if callable.kind == SymbolKind::METHOD && callable.name == call.name {
+                    let own = path == file && own_class.is_some() && callable.container.as_ref() == own_class;
+                    methods.push(((path.clone(), Some(index)), own));
+                }
+            }
+        }
+        if methods.iter().any(|(_, own)| *own) {
+            methods.retain(|(_, own)| *own);
+        }
+        let mut out: Vec<CallTarget> = methods.into_iter().map(|(target, _)| target).collect();
+        out.sort();
+        out
+    }
+
+    fn call_item(&self, (path, index): &CallTarget) -> Option<CallHierarchyItem> {
+        let module = self.modules.get(path)?;
+        let uri = Url::from_file_path(path).ok()?;
+        let Some(index) = index else {
+            let range = span_to_range(&module.text, Span { start: 0, end: module.text.len() });
+            return Some(CallHierarchyItem {
+                name: path.file_name()?.to_string_lossy().into_owned(),
+                kind: SymbolKind::FILE,
+                tags: None,
+                detail: None,
+                uri,
+                range,
+                selection_range: Range { start: range.start, end: range.start },
+                data: None,
+            });
+        };
+        let callable = module.outline.callables.get(*index)?;
+        Some(CallHierarchyItem {
+            name: callable.name.clone(),
+            kind: callable.kind,
+            tags: None,
+            detail: callable.container.clone(),
+            uri,
+            range: span_to_range(&module.text, callable.span),
+            selection_range: span_to_range(&module.text, callable.name_span),
+            data: None,
+        })
+    }
+
+    fn type_item(&self, path: &Path, class: &ClassEntry) -> Option<TypeHierarchyItem> {
+        let module = self.modules.get(path)?;
+        Some(TypeHierarchyItem {
+            name: class.name.clone(),
+            kind: SymbolKind::CLASS,
+            tags: None,
+            detail: None,
+            uri: Url::from_file_path(path).ok()?,
+            range: span_to_range(&module.text, class.span),
+            selection_range: span_to_range(&module.text, class.name_span),
+            data: None,
+        })
+    }
+}
+
+fn push_grouped(grouped: &mut Vec<(CallTarget, Vec<Span>)>, key: CallTarget, span: Span) {
+    match grouped.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, spans)) => spans.push(span),
+        None => grouped.push((key, vec![span])),
+    }
+}
+
+fn push_symbol(
+    out: &mut Vec<SymbolInformation>,
+    symbol: &DocumentSymbol,
+    container: Option<&str>,
+    uri: &Url,
+    query: &str,
+) {
+    if !symbol.name.to_lowercase().contains(query) {
+        return;
+    }
+    #[allow(deprecated)]
+    out.push(SymbolInformation {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        tags: None,
+        deprecated: None,
+        location: Location { uri: uri.clone(), range: symbol.selection_range },
+        container_name: container.map(str::to_string),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(prefix: &str) -> Self {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let dir = std::env::temp_dir().join(format!("yps_lsp_{prefix}_{}_{n}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(canonical(&dir))
+        }
+
+        fn write(&self, name: &str, content: &str) -> PathBuf {
+            let path = self.0.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).unwrap();
+            }
+            fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const LIB: &str = "предъява йопта сложить(а, б) { отвечаю а + б; }\nпредъява ясенХуй база = 0;\n";
+    const MAIN: &str = "спиздить { сложить } из \"./lib\";\nсказать(сложить(1, 2));\n";
+
+    fn project() -> (TempDir, WorkspaceIndex) {
+        let dir = TempDir::new("project");
+        dir.write("lib.yopta", LIB);
+        dir.write("main.yopta", MAIN);
+        dir.write("sub/other.yopta", "спиздить * как биб из \"../lib\";\nбиб.сложить(биб.база, 1);\n");
+        dir.write(".hidden/skip.yopta", "спиздить { сложить } из \"../lib\";\n");
+        let mut index = WorkspaceIndex::default();
+        index.index_folder(&dir.0);
+        (dir, index)
+    }
+
+    #[test]
+    fn indexes_modules_but_skips_hidden_folders() {
+        let (dir, index) = project();
+        assert!(index.contains(&dir.0.join("lib.yopta")));
+        assert!(index.contains(&dir.0.join("sub/other.yopta")));
+        assert!(!index.contains(&dir.0.join(".hidden/skip.yopta")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_folders_are_not_followed() {
+        let (dir, mut index) = project();
+        std::os::unix::fs::symlink(&dir.0, dir.0.join("sub/loop")).unwrap();
+        index.index_folder(&dir.0);
+        assert!(index.contains(&dir.0.join("sub/other.yopta")));
+        assert!(!index.modules.keys().any(|path| path.starts_with(dir.0.join("sub/loop"))));
+    }
+
+    #[test]
+    fn merging_a_scan_keeps_modules_indexed_meanwhile() {
+        let (dir, mut index) = project();
+        let edited = "предъява йопта сложить(а) { отвечаю а; }\n";
+        index.update(&dir.0.join("lib.yopta"), edited);
+        let mut scanned = WorkspaceIndex::default();
+        scanned.index_folder(&dir.0);
+        index.merge(scanned);
+        assert_eq!(index.modules[&dir.0.join("lib.yopta")].text, edited);
+    }
+
+    #[test]
+    fn imports_resolve_once_their_module_appears() {
+        let dir = TempDir::new("late");
+        let mut index = WorkspaceIndex::default();
+        index.update(&dir.write("main.yopta", MAIN), MAIN);
+        let usage = MAIN.rfind("сложить").unwrap();
+        assert_eq!(index.definition(&dir.0.join("main.yopta"), usage).unwrap().0, dir.0.join("main.yopta"));
+
+        index.update(&dir.write("lib.yopta", LIB), LIB);
+        assert_eq!(index.definition(&dir.0.join("main.yopta"), usage).unwrap().0, dir.0.join("lib.yopta"));
+    }
+
+    #[test]
+    fn definition_jumps_into_the_exporting_file() {
+        let (dir, index) = project();
+        let usage = MAIN.rfind("сложить").unwrap();
+        let (path, span) = index.definition(&dir.0.join("main.yopta"), usage).expect("definition");
+        assert_eq!(path, dir.0.join("lib.yopta"));
+        assert_eq!(span.start, LIB.find("сложить").unwrap());
+    }
+
+    #[test]
+    fn import_sources_are_relative_to_the_importer() {
+        let (dir, index) = project();
+        assert_eq!(index.import_sources(&dir.0.join("main.yopta"), "база"), ["./lib"]);
+        assert_eq!(index.import_sources(&dir.0.join("sub/other.yopta"), "сложить"), ["../lib"]);
+        assert!(index.import_sources(&dir.0.join("lib.yopta"), "сложить").is_empty());
+        assert!(index.import_sources(&dir.0.join("main.yopta"), "нет").is_empty());
+    }
+
+    #[test]
+    fn definition_follows_reexports() {
+        let (dir, mut index) = project();
+        let facade = "спиздить { сложить } из \"./lib\";\nпредъява { сложить };\n";
+        index.update(&dir.write("facade.yopta", facade), facade);
+        let user = "спиздить { сложить } из \"./facade\";\nсложить(1, 1);\n";
+        index.update(&dir.write("user.yopta", user), user);
+        let (path, _) = index.definition(&dir.0.join("user.yopta"), user.rfind("сложить").unwrap()).unwrap();
+        assert_eq!(path, dir.0.join("lib.yopta"));
+    }
+
+    #[test]
+    fn references_cover_every_importer_including_namespace_members() {
+        let (dir, index) = project();
+        let refs = index.references(&dir.0.join("lib.yopta"), LIB.find("сложить").unwrap(), true).unwrap();
+        let count = |name: &str| refs.iter().filter(|(p, _)| *p == dir.0.join(name)).count();
+        assert_eq!(count("lib.yopta"), 1);
+        assert_eq!(count("main.yopta"), 2);
+        assert_eq!(count("sub/other.yopta"), 1);
+
+        let without = index.references(&dir.0.join("main.yopta"), MAIN.rfind("сложить").unwrap(), false).unwrap();
+        assert_eq!(without.len(), 3);
+        assert!(without.iter().all(|(p, _)| *p != dir.0.join("lib.yopta")));
+    }
+
+    #[test]
+    fn rename_from_an_importer_edits_the_exporter() {
+        let (dir, index) = project();
+        let edits = index.rename_edits(&dir.0.join("main.yopta"), 0, "плюс");
+        assert!(edits.is_none(), "cursor on a keyword");
+        let edits = index.rename_edits(&dir.0.join("main.yopta"), MAIN.rfind("сложить").unwrap(), "плюс").unwrap();
+        assert_eq!(edits.len(), 4);
+        assert!(index.rename_edits(&dir.0.join("main.yopta"), MAIN.rfind("сложить").unwrap(), "гыы").is_none());
+    }
+
+    #[test]
+    fn unrelated_locals_with_the_same_name_are_untouched() {
+        let (dir, mut index) = project();
+        let text = "гыы сложить = 5;\nсказать(сложить);\n";
+        index.update(&dir.write("local.yopta", text), text);
+        let refs = index.references(&dir.0.join("lib.yopta"), LIB.find("сложить").unwrap(), true).unwrap();
+        assert!(refs.iter().all(|(p, _)| *p != dir.0.join("local.yopta")));
+    }
+
+    #[test]
+    fn removed_module_drops_out_of_results() {
+        let (dir, mut index) = project();
+        index.remove(&dir.0.join("main.yopta"));
+        let refs = index.references(&dir.0.join("lib.yopta"), LIB.find("сложить").unwrap(), true).unwrap();
+        assert!(refs.iter().all(|(p, _)| *p != dir.0.join("main.yopta")));
+    }
+
+    #[test]
+    fn workspace_symbols_filter_by_query() {
+        let (_dir, index) = project();
+        let names: Vec<_> = index.symbols("слож").into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["сложить".to_string()]);
+        assert!(index.symbols("").len() >= 2);
+    }
+
+    fn call_item(index: &WorkspaceIndex, path: &Path, text: &str, needle: &str) -> CallHierarchyItem {
+        let mut items = index.prepare_call_hierarchy(path, text.find(needle).unwrap()).expect("call hierarchy item");
+        items.remove(0)
+    }
+
+    #[test]
+    fn incoming_calls_span_importers_and_namespace_members() {
+        let (dir, index) = project();
+        let item = call_item(&index, &dir.0.join("lib.yopta"), LIB, "сложить");
+        assert_eq!(item.kind, SymbolKind::FUNCTION);
+        let incoming = index.incoming_calls(&item);
+        let callers: Vec<(String, SymbolKind, usize)> =
+            incoming.iter().map(|c| (c.from.name.clone(), c.from.kind, c.from_ranges.len())).collect();
+        assert_eq!(
+            callers,
+            [("main.yopta".to_string(), SymbolKind::FILE, 1), ("other.yopta".to_string(), SymbolKind::FILE, 1)]
+        );
+    }
+
+    #[test]
+    fn outgoing_calls_resolve_functions_methods_and_constructors() {
+        let (dir, mut index) = project();
+        let text = "спиздить { сложить } из \"./lib\";\nклёво Счёт {\n  плюс(а) { отвечаю сложить(а, 1); }\n  два() { отвечаю тырыпыры.плюс(1) + тырыпыры.плюс(2); }\n}\nйопта старт() { отвечаю захуярить Счёт().два(); }\n";
+        let path = dir.write("calc.yopta", text);
+        index.update(&path, text);
+
+        let start = call_item(&index, &path, text, "старт");
+        let outgoing: Vec<String> = index.outgoing_calls(&start).into_iter().map(|c| c.to.name).collect();
+        assert_eq!(outgoing, ["Счёт", "два"]);
+
+        let two = call_item(&index, &path, text, "два");
+        let calls = index.outgoing_calls(&two);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].to.detail.as_deref(), Some("Счёт"));
+        assert_eq!(calls[0].from_ranges.len(), 2);
+
+        let plus = call_item(&index, &path, text, "плюс");
+        let to: Vec<String> = index.outgoing_calls(&plus).into_iter().map(|c| c.to.uri.path().to_string()).collect();
+        assert!(to[0].ends_with("lib.yopta"));
+        let from: Vec<String> = index.incoming_calls(&plus).into_iter().map(|c| c.from.name).collect();
+        assert_eq!(from, ["два"]);
+    }
+
+    #[test]
+    fn prepare_on_a_call_site_returns_the_callee() {
+        let (dir, index) = project();
+        let item = call_item(&index, &dir.0.join("main.yopta"), MAIN, "сложить(1");
+        assert_eq!(item.uri, Url::from_file_path(dir.0.join("lib.yopta")).unwrap());
+    }
+
+    #[test]
+    fn type_hierarchy_follows_extends_across_modules() {
+        let (dir, mut index) = project();
+        let base = "предъява клёво Зверь {}\n";
+        let cats = "спиздить { Зверь } из \"./base\";\nклёво Кот батя Зверь {}\nклёво Лев батя Кот {}\nклёво Пёс батя Зверь {}\n";
+        index.update(&dir.write("base.yopta", base), base);
+        let cats_path = dir.write("cats.yopta", cats);
+        index.update(&cats_path, cats);
+
+        let root = index.prepare_type_hierarchy(&dir.0.join("base.yopta"), base.find("Зверь").unwrap()).unwrap();
+        let subs: Vec<String> = index.subtypes(&root[0]).into_iter().map(|t| t.name).collect();
+        assert_eq!(subs, ["Кот", "Пёс"]);
+
+        let lion = index.prepare_type_hierarchy(&cats_path, cats.find("Лев").unwrap()).unwrap();
+        let sup = index.supertypes(&lion[0]);
+        assert_eq!(sup[0].name, "Кот");
+        let top = index.supertypes(&sup[0]);
+        assert_eq!(top[0].uri, Url::from_file_path(dir.0.join("base.yopta")).unwrap());
+        assert!(index.supertypes(&top[0]).is_empty());
+    }
+}
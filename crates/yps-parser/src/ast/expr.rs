@@ -1,7 +1,7 @@
 use std::rc::Rc;
 
 use crate::ast::stmt::Block;
-use crate::ast::{BinaryOp, Identifier, Literal, Param, PostfixOp, UnaryOp};
+use crate::ast::{BinaryOp, Identifier, Literal, MatchArm, Param, PostfixOp, TypeExpr, UnaryOp};
 use yps_lexer::Span;
 
 #[derive(Debug, Clone)]
@@ -96,6 +96,7 @@ pub enum Expr {
 
     ArrowFunction {
         params: Rc<[Param]>,
+        return_type: Option<Box<TypeExpr>>,
         body: Rc<Block>,
         is_async: bool,
         span: Span,
@@ -104,6 +105,7 @@ pub enum Expr {
     FunctionExpr {
         name: Option<Identifier>,
         params: Rc<[Param]>,
+        return_type: Option<Box<TypeExpr>>,
         body: Rc<Block>,
         is_generator: bool,
         is_async: bool,
@@ -156,6 +158,28 @@ pub enum Expr {
         source: Box<Expr>,
         span: Span,
     },
+
+    /// `спиздить.мета`: the current module's `путь`, `url` and whether it is `главный`.
+    ImportMeta {
+        span: Span,
+    },
+
+    /// `захуярить.цель`: the class or function called with `захуярить`, `неибу` otherwise.
+    NewTarget {
+        span: Span,
+    },
+
+    /// `базарпо (значение) { тема образец => результат, ... }`: the first matching arm wins.
+    Match {
+        subject: Box<Expr>,
+        arms: Vec<MatchArm>,
+        span: Span,
+    },
+
+    /// A missing or malformed operand; the parser reported it and kept the surrounding tree.
+    Error {
+        span: Span,
+    },
 }
 
 impl Expr {
@@ -196,7 +220,11 @@ impl Expr {
             | Self::Super { span, .. }
             | Self::Yield { span, .. }
             | Self::Await { span, .. }
-            | Self::DynamicImport { span, .. } => *span,
+            | Self::DynamicImport { span, .. }
+            | Self::ImportMeta { span }
+            | Self::NewTarget { span }
+            | Self::Match { span, .. }
+            | Self::Error { span } => *span,
         }
     }
 }
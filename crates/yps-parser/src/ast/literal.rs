@@ -12,7 +12,7 @@ pub enum ObjectEntry {
     Property { key: PropKey, value: Expr },
     Spread(Expr),
     Getter { key: PropKey, body: crate::ast::Block, span: Span },
-    Setter { key: PropKey, param: Param, body: crate::ast::Block, span: Span },
+    Setter { key: PropKey, param: Box<Param>, body: crate::ast::Block, span: Span },
 }
 
 #[derive(Debug, Clone)]
@@ -6,12 +6,14 @@ pub mod param;
 pub mod pattern;
 pub mod program;
 pub mod stmt;
+pub mod ty;
 
 pub use expr::{Expr, TemplatePart, TemplateQuasi};
 pub use ident::Identifier;
 pub use literal::{Literal, ObjectEntry, PropKey};
 pub use ops::{BinaryOp, PostfixOp, UnaryOp};
 pub use param::Param;
-pub use pattern::{ObjectPatternProp, Pattern};
+pub use pattern::{MatchArm, MatchPattern, ObjectPatternProp, Pattern};
 pub use program::Program;
-pub use stmt::{Block, ClassMember, ExportKind, ImportSpec, Stmt, SwitchCase};
+pub use stmt::{Block, ClassMember, EnumMember, ExportKind, ImportSpec, Stmt, SwitchCase};
+pub use ty::{TypeExpr, TypeField};
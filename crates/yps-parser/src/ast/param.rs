@@ -1,4 +1,4 @@
-use crate::ast::{Expr, Identifier, Pattern};
+use crate::ast::{Expr, Identifier, Pattern, TypeExpr};
 
 #[derive(Debug, Clone)]
 pub struct Param {
@@ -6,4 +6,5 @@ pub struct Param {
     pub default: Option<Expr>,
     pub is_rest: bool,
     pub pattern: Option<Pattern>,
+    pub ty: Option<TypeExpr>,
 }
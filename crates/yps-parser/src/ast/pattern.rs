@@ -25,3 +25,41 @@ pub struct ObjectPatternProp {
     pub value: Option<Pattern>,
     pub span: Span,
 }
+
+/// One arm of a `базарпо` expression: `тема образец вилкойвглаз (условие) => значение`.
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: MatchPattern,
+    pub guard: Option<Expr>,
+    pub body: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum MatchPattern {
+    /// `нуичо`: matches anything and binds nothing.
+    Wildcard { span: Span },
+    /// A destructuring pattern; arrays and objects must have the pattern's shape.
+    Bind(Pattern),
+    /// A literal or a dotted path such as `Цвет.Красный`, compared with `===`.
+    Value(Expr),
+    /// `шкура Класс`, optionally followed by a pattern for the instance.
+    Instance { class: Expr, pattern: Option<Pattern>, span: Span },
+}
+
+impl MatchPattern {
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::Wildcard { span } | Self::Instance { span, .. } => *span,
+            Self::Bind(pattern) => pattern.span(),
+            Self::Value(expr) => expr.span(),
+        }
+    }
+
+    /// True when the pattern accepts every value, so later arms can never run.
+    #[must_use]
+    pub const fn is_irrefutable(&self) -> bool {
+        matches!(self, Self::Wildcard { .. } | Self::Bind(Pattern::Identifier(_)))
+    }
+}
@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use crate::ast::{Expr, Identifier, Param, Pattern};
+use crate::ast::{Expr, Identifier, Param, Pattern, TypeExpr};
 use yps_lexer::Span;
 
 #[derive(Debug, Clone)]
@@ -13,6 +13,7 @@ pub enum ClassMember {
     Method {
         name: Identifier,
         params: Rc<[Param]>,
+        return_type: Option<TypeExpr>,
         body: Rc<Block>,
         is_static: bool,
         is_private: bool,
@@ -21,6 +22,7 @@ pub enum ClassMember {
     },
     Field {
         name: Identifier,
+        ty: Option<TypeExpr>,
         init: Option<Expr>,
         is_static: bool,
         is_private: bool,
@@ -54,6 +56,7 @@ pub enum ClassMember {
 pub enum Stmt {
     VarDecl {
         pattern: Pattern,
+        ty: Option<TypeExpr>,
         init: Expr,
         is_const: bool,
         span: Span,
@@ -100,6 +103,7 @@ pub enum Stmt {
     FunctionDecl {
         name: Identifier,
         params: Rc<[Param]>,
+        return_type: Option<TypeExpr>,
         body: Rc<Block>,
         is_generator: bool,
         is_async: bool,
@@ -162,6 +166,20 @@ pub enum Stmt {
         is_await: bool,
         span: Span,
     },
+    /// `еээ Цвет { Красный, Зелёный = 5 }`: a frozen object mapping member names to values, and
+    /// numeric values back to names.
+    EnumDecl {
+        name: Identifier,
+        members: Vec<EnumMember>,
+        span: Span,
+    },
+    /// `тип Имя = тип;` or `хуёво Имя { поле: тип }`; only `yps check` looks at it.
+    TypeAlias {
+        name: Identifier,
+        ty: TypeExpr,
+        is_interface: bool,
+        span: Span,
+    },
     Debugger {
         span: Span,
     },
@@ -175,6 +193,11 @@ pub enum Stmt {
         kind: ExportKind,
         span: Span,
     },
+    /// A statement that failed to parse; the parser reported it and skipped to the next
+    /// synchronisation point.
+    Error {
+        span: Span,
+    },
 }
 
 impl Stmt {
@@ -202,9 +225,12 @@ impl Stmt {
             | Self::ForAwaitOf { span, .. }
             | Self::ClassDecl { span, .. }
             | Self::Using { span, .. }
+            | Self::EnumDecl { span, .. }
+            | Self::TypeAlias { span, .. }
             | Self::Debugger { span }
             | Self::Import { span, .. }
-            | Self::Export { span, .. } => *span,
+            | Self::Export { span, .. }
+            | Self::Error { span } => *span,
         }
     }
 }
@@ -222,6 +248,15 @@ pub enum ExportKind {
     Named(Vec<Identifier>),
 }
 
+/// One member of an `еээ` declaration. Without `init` it takes the previous numeric value plus one,
+/// starting from 0.
+#[derive(Debug, Clone)]
+pub struct EnumMember {
+    pub name: Identifier,
+    pub init: Option<Expr>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct SwitchCase {
     pub value: Expr,
@@ -0,0 +1,59 @@
+use crate::ast::{Identifier, Literal};
+use yps_lexer::Span;
+
+/// A type annotation such as `число`, `строка[]`, `Точка | ноль`, `{ х: число, у?: число }` or
+/// `(а: число) => строка`. Both runtimes erase it; `yps check` reads it.
+#[derive(Debug, Clone)]
+pub enum TypeExpr {
+    /// A builtin type, a class or a `тип` alias, with optional arguments: `СловоПацана<строка>`.
+    Named {
+        name: Identifier,
+        args: Vec<TypeExpr>,
+        span: Span,
+    },
+    /// A string, number or boolean literal, `ноль` or `неибу`.
+    Literal {
+        value: Literal,
+        span: Span,
+    },
+    Array {
+        element: Box<TypeExpr>,
+        span: Span,
+    },
+    Union {
+        types: Vec<TypeExpr>,
+        span: Span,
+    },
+    Object {
+        fields: Vec<TypeField>,
+        span: Span,
+    },
+    Function {
+        params: Vec<TypeField>,
+        ret: Box<TypeExpr>,
+        span: Span,
+    },
+}
+
+impl TypeExpr {
+    #[must_use]
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::Named { span, .. }
+            | Self::Literal { span, .. }
+            | Self::Array { span, .. }
+            | Self::Union { span, .. }
+            | Self::Object { span, .. }
+            | Self::Function { span, .. } => *span,
+        }
+    }
+}
+
+/// A field of an object type or a parameter of a function type; `?` makes it optional.
+#[derive(Debug, Clone)]
+pub struct TypeField {
+    pub name: Identifier,
+    pub ty: TypeExpr,
+    pub optional: bool,
+    pub span: Span,
+}
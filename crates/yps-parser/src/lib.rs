@@ -1,9 +1,10 @@
 pub mod ast;
+pub mod highlight;
 pub mod parser;
 pub mod precedence;
 
 pub use ast::*;
-pub use parser::Parser;
+pub use parser::{Parser, PreviousParse};
 pub use precedence::{
     ASSIGN_PRECEDENCE, CALL_PRECEDENCE, POSTFIX_PRECEDENCE, TERNARY_PRECEDENCE, UNARY_PRECEDENCE,
     binary_is_right_assoc, binary_precedence,
@@ -28,14 +28,23 @@ impl<'a> Parser<'a> {
             None
         };
 
-        self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{' после имени класса")?;
+        let open = self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{' после имени класса")?;
 
         let mut members = Vec::new();
         while !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RBrace) | TokenKind::Eof) {
-            members.push(self.parse_class_member(&name.name)?);
+            let before = self.position;
+            match self.parse_class_member(&name.name) {
+                Ok(member) => members.push(member),
+                Err(()) => {
+                    self.synchronize_class_member();
+                    if self.position == before && !self.is_at_end() {
+                        self.advance();
+                    }
+                }
+            }
         }
 
-        let end = self.expect_punct(PunctuationKind::RBrace, "Ожидалась '}' в конце класса")?.end;
+        let end = self.expect_closing_brace(open, "Ожидалась '}' в конце класса")?;
 
         Ok(Stmt::ClassDecl { name, super_class, members, decorators, span: Span { start, end } })
     }
@@ -121,6 +130,7 @@ impl<'a> Parser<'a> {
 
             self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после параметров метода")?;
 
+            let return_type = self.parse_optional_annotation()?;
             let body = self.parse_block()?;
             let end = body.span.end;
 
@@ -129,11 +139,16 @@ impl<'a> Parser<'a> {
                     self.push_error(Span { start, end }, "Декораторы нельзя применять к конструктору");
                     return Err(());
                 }
+                if let Some(ty) = return_type {
+                    self.push_error(ty.span(), "Конструктор не объявляет тип результата");
+                    return Err(());
+                }
                 Ok(ClassMember::Constructor { params: params.into(), body: Rc::new(body), span: Span { start, end } })
             } else {
                 Ok(ClassMember::Method {
                     name: member_name,
                     params: params.into(),
+                    return_type,
                     body: Rc::new(body),
                     is_static,
                     is_private,
@@ -142,6 +157,7 @@ impl<'a> Parser<'a> {
                 })
             }
         } else {
+            let ty = self.parse_optional_annotation()?;
             let init = if matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
                 self.advance();
                 Some(self.parse_expr()?)
@@ -156,6 +172,7 @@ impl<'a> Parser<'a> {
             let end = self.current().span.start;
             Ok(ClassMember::Field {
                 name: member_name,
+                ty,
                 init,
                 is_static,
                 is_private,
@@ -132,7 +132,13 @@ impl<'a> Parser<'a> {
             TokenKind::Keyword(KeywordKind::Async) => self.parse_async_expr()?,
             TokenKind::Keyword(KeywordKind::Yopta) => self.parse_function_expr()?,
             TokenKind::Keyword(KeywordKind::GeneratorFn) => self.parse_generator_expr()?,
+            TokenKind::Keyword(KeywordKind::New | KeywordKind::Import)
+                if matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::Dot)) =>
+            {
+                self.parse_meta_property()?
+            }
             TokenKind::Keyword(KeywordKind::New) => self.parse_new_expr()?,
+            TokenKind::Keyword(KeywordKind::Switch) => self.parse_match_expr()?,
             TokenKind::Keyword(KeywordKind::Import)
                 if matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::LParen)) =>
             {
@@ -177,7 +183,9 @@ impl<'a> Parser<'a> {
         Ok(expr)
     }
 
+    /// Comma-separated expressions up to `close`; the opening delimiter has just been consumed.
     pub(super) fn parse_arguments(&mut self, close: PunctuationKind, msg: &str) -> Result<(Vec<Expr>, Span), ()> {
+        let open = self.previous().map_or(self.current().span, |t| t.span);
         let mut args = Vec::new();
         if !matches!(&self.current().kind, TokenKind::Punctuation(k) if *k == close) {
             loop {
@@ -202,7 +210,7 @@ impl<'a> Parser<'a> {
             }
         }
 
-        let close_span = self.expect_punct(close, msg)?;
+        let close_span = self.expect_closing(close, open, msg)?;
         Ok((args, close_span))
     }
 
@@ -265,6 +273,24 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// `захуярить.цель` and `спиздить.мета`; the keyword is the current token.
+    fn parse_meta_property(&mut self) -> Result<Expr, ()> {
+        let start = self.current().span.start;
+        let is_new = matches!(self.current().kind, TokenKind::Keyword(KeywordKind::New));
+        let (keyword, property) =
+            if is_new { ("захуярить", "цель") } else { ("спиздить", "мета") };
+        self.advance();
+        self.advance();
+        let name = self.current().span;
+        if !matches!(self.current().kind, TokenKind::Identifier) || self.source.slice(name) != property {
+            self.push_error(name, format!("Ожидалось '{property}' после '{keyword}.'"));
+            return Err(());
+        }
+        self.advance();
+        let span = Span { start, end: name.end };
+        Ok(if is_new { Expr::NewTarget { span } } else { Expr::ImportMeta { span } })
+    }
+
     pub(super) fn parse_new_expr(&mut self) -> Result<Expr, ()> {
         let start = self.current().span.start;
         self.advance();
@@ -1,7 +1,7 @@
 use super::*;
 
 impl<'a> Parser<'a> {
-    fn paren_group_is_arrow_head(&self) -> bool {
+    pub(super) fn paren_group_is_arrow_head(&self) -> bool {
         let mut depth = 0usize;
         let mut i = self.position;
         while let Some(tok) = self.tokens.get(i) {
@@ -13,10 +13,11 @@ impl<'a> Parser<'a> {
                     }
                     depth -= 1;
                     if depth == 0 {
-                        return matches!(
-                            self.tokens.get(i + 1).map(|t| &t.kind),
-                            Some(TokenKind::Punctuation(PunctuationKind::Arrow))
-                        );
+                        return match self.tokens.get(i + 1).map(|t| &t.kind) {
+                            Some(TokenKind::Punctuation(PunctuationKind::Arrow)) => true,
+                            Some(TokenKind::Punctuation(PunctuationKind::Colon)) => self.return_type_then_arrow(i + 2),
+                            _ => false,
+                        };
                     }
                 }
                 _ => {}
@@ -26,12 +27,62 @@ impl<'a> Parser<'a> {
         false
     }
 
+    /// Whether the tokens from `i` look like a return type annotation followed by `=>`.
+    fn return_type_then_arrow(&self, mut i: usize) -> bool {
+        let mut depth = 0usize;
+        while let Some(tok) = self.tokens.get(i) {
+            match tok.kind {
+                TokenKind::Punctuation(PunctuationKind::Arrow) if depth == 0 => return true,
+                TokenKind::Punctuation(
+                    PunctuationKind::LParen | PunctuationKind::LBracket | PunctuationKind::LBrace,
+                )
+                | TokenKind::Operator(OperatorKind::Less) => depth += 1,
+                TokenKind::Punctuation(
+                    PunctuationKind::RParen | PunctuationKind::RBracket | PunctuationKind::RBrace,
+                )
+                | TokenKind::Operator(OperatorKind::Greater) => {
+                    if depth == 0 {
+                        return false;
+                    }
+                    depth -= 1;
+                }
+                TokenKind::Operator(OperatorKind::RightShift) => {
+                    if depth < 2 {
+                        return false;
+                    }
+                    depth -= 2;
+                }
+                TokenKind::Punctuation(PunctuationKind::Arrow)
+                | TokenKind::Punctuation(
+                    PunctuationKind::Comma | PunctuationKind::Semicolon | PunctuationKind::Colon,
+                )
+                | TokenKind::Punctuation(PunctuationKind::Question)
+                    if depth > 0 => {}
+                TokenKind::Identifier
+                | TokenKind::Number
+                | TokenKind::StringLiteral
+                | TokenKind::Operator(OperatorKind::BitOr)
+                | TokenKind::Keyword(
+                    KeywordKind::Pravda
+                    | KeywordKind::Lozh
+                    | KeywordKind::Nol
+                    | KeywordKind::Undefined
+                    | KeywordKind::Void,
+                ) => {}
+                _ => return false,
+            }
+            i += 1;
+        }
+        false
+    }
+
     pub(super) fn try_parse_arrow_function(&mut self) -> Result<Option<Expr>, ()> {
         if !self.paren_group_is_arrow_head() {
             return Ok(None);
         }
         let saved_pos = self.position;
         let saved_diag_len = self.diagnostics.len();
+        let start = self.current().span.start;
 
         self.advance();
 
@@ -39,9 +90,14 @@ impl<'a> Parser<'a> {
 
         if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RParen)) {
             self.advance();
+            let Ok(return_type) = self.parse_optional_annotation() else {
+                self.position = saved_pos;
+                self.diagnostics.truncate(saved_diag_len);
+                return Ok(None);
+            };
             if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Arrow)) {
                 self.advance();
-                return Ok(Some(self.parse_arrow_body(params, saved_pos)?));
+                return Ok(Some(self.parse_arrow_body_with_async(params, start, false, return_type)?));
             }
             self.position = saved_pos;
             self.diagnostics.truncate(saved_diag_len);
@@ -78,6 +134,11 @@ impl<'a> Parser<'a> {
                 };
                 let pat_span = pat.span();
                 let synthetic = Identifier { name: "__пат__".to_string(), span: pat_span };
+                let Ok(ty) = self.parse_optional_annotation() else {
+                    self.position = saved_pos;
+                    self.diagnostics.truncate(saved_diag_len);
+                    return Ok(None);
+                };
                 let default = if matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
                     self.advance();
                     match self.parse_expr() {
@@ -91,7 +152,7 @@ impl<'a> Parser<'a> {
                 } else {
                     None
                 };
-                params.push(Param { name: synthetic, default, is_rest, pattern: Some(pat) });
+                params.push(Param { name: synthetic, default, is_rest, pattern: Some(pat), ty });
             } else {
                 if !matches!(self.current().kind, TokenKind::Identifier) {
                     self.position = saved_pos;
@@ -102,6 +163,11 @@ impl<'a> Parser<'a> {
                 let name_str = self.source.slice(span).to_string();
                 self.advance();
                 let name = Identifier { name: name_str, span };
+                let Ok(ty) = self.parse_optional_annotation() else {
+                    self.position = saved_pos;
+                    self.diagnostics.truncate(saved_diag_len);
+                    return Ok(None);
+                };
 
                 let default = if !is_rest && matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
                     self.advance();
@@ -117,7 +183,7 @@ impl<'a> Parser<'a> {
                     None
                 };
 
-                params.push(Param { name, default, is_rest, pattern: None });
+                params.push(Param { name, default, is_rest, pattern: None, ty });
             }
 
             if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
@@ -134,6 +200,11 @@ impl<'a> Parser<'a> {
         }
         self.advance();
 
+        let Ok(return_type) = self.parse_optional_annotation() else {
+            self.position = saved_pos;
+            self.diagnostics.truncate(saved_diag_len);
+            return Ok(None);
+        };
         if !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Arrow)) {
             self.position = saved_pos;
             self.diagnostics.truncate(saved_diag_len);
@@ -141,19 +212,15 @@ impl<'a> Parser<'a> {
         }
         self.advance();
 
-        Ok(Some(self.parse_arrow_body(params, saved_pos)?))
+        Ok(Some(self.parse_arrow_body_with_async(params, start, false, return_type)?))
     }
 
     pub(super) fn parse_single_param_arrow(&mut self) -> Result<Expr, ()> {
         let start = self.current().span.start;
         let ident = self.parse_identifier()?;
-        let param = Param { name: ident, default: None, is_rest: false, pattern: None };
+        let param = Param { name: ident, default: None, is_rest: false, pattern: None, ty: None };
         self.advance();
-        self.parse_arrow_body(vec![param], start)
-    }
-
-    pub(super) fn parse_arrow_body(&mut self, params: Vec<Param>, start: usize) -> Result<Expr, ()> {
-        self.parse_arrow_body_with_async(params, start, false)
+        self.parse_arrow_body_with_async(vec![param], start, false, None)
     }
 
     pub(super) fn parse_arrow_body_with_async(
@@ -161,11 +228,18 @@ impl<'a> Parser<'a> {
         params: Vec<Param>,
         start: usize,
         is_async: bool,
+        return_type: Option<TypeExpr>,
     ) -> Result<Expr, ()> {
         if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::LBrace)) {
             let body = self.parse_block()?;
             let end = body.span.end;
-            Ok(Expr::ArrowFunction { params: params.into(), body: Rc::new(body), is_async, span: Span { start, end } })
+            Ok(Expr::ArrowFunction {
+                params: params.into(),
+                return_type: return_type.map(Box::new),
+                body: Rc::new(body),
+                is_async,
+                span: Span { start, end },
+            })
         } else {
             let expr = self.parse_expr()?;
             let end = expr.span().end;
@@ -173,7 +247,13 @@ impl<'a> Parser<'a> {
                 stmts: vec![Stmt::Return { value: Some(expr), span: Span { start, end } }],
                 span: Span { start, end },
             };
-            Ok(Expr::ArrowFunction { params: params.into(), body: Rc::new(body), is_async, span: Span { start, end } })
+            Ok(Expr::ArrowFunction {
+                params: params.into(),
+                return_type: return_type.map(Box::new),
+                body: Rc::new(body),
+                is_async,
+                span: Span { start, end },
+            })
         }
     }
 
@@ -203,15 +283,17 @@ impl<'a> Parser<'a> {
                     let pat = self.parse_pattern()?;
                     let pat_span = pat.span();
                     let synthetic = Identifier { name: "__пат__".to_string(), span: pat_span };
+                    let ty = self.parse_optional_annotation()?;
                     let default = if matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
                         self.advance();
                         Some(self.parse_expr()?)
                     } else {
                         None
                     };
-                    params.push(Param { name: synthetic, default, is_rest, pattern: Some(pat) });
+                    params.push(Param { name: synthetic, default, is_rest, pattern: Some(pat), ty });
                 } else {
                     let name = self.parse_identifier()?;
+                    let ty = self.parse_optional_annotation()?;
                     let default =
                         if !is_rest && matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
                             self.advance();
@@ -219,7 +301,7 @@ impl<'a> Parser<'a> {
                         } else {
                             None
                         };
-                    params.push(Param { name, default, is_rest, pattern: None });
+                    params.push(Param { name, default, is_rest, pattern: None, ty });
                 }
 
                 if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
@@ -245,12 +327,13 @@ impl<'a> Parser<'a> {
         self.advance();
 
         let name = self.parse_identifier()?;
-        let (params, body) = self.parse_function_params_and_body()?;
+        let (params, return_type, body) = self.parse_function_params_and_body()?;
         let end = body.span.end;
 
         Ok(Stmt::FunctionDecl {
             name,
             params: params.into(),
+            return_type,
             body: Rc::new(body),
             is_generator,
             is_async,
@@ -258,15 +341,16 @@ impl<'a> Parser<'a> {
         })
     }
 
-    pub(super) fn parse_function_params_and_body(&mut self) -> Result<(Vec<Param>, Block), ()> {
+    pub(super) fn parse_function_params_and_body(&mut self) -> Result<(Vec<Param>, Option<TypeExpr>, Block), ()> {
         self.expect_punct(PunctuationKind::LParen, "Ожидалась '(' после имени функции")?;
 
         let params = self.parse_function_params()?;
 
         self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после параметров функции")?;
 
+        let return_type = self.parse_optional_annotation()?;
         let body = self.parse_block()?;
-        Ok((params, body))
+        Ok((params, return_type, body))
     }
 
     pub(super) fn parse_function_expr(&mut self) -> Result<Expr, ()> {
@@ -289,12 +373,13 @@ impl<'a> Parser<'a> {
 
         let name =
             if matches!(self.current().kind, TokenKind::Identifier) { Some(self.parse_identifier()?) } else { None };
-        let (params, body) = self.parse_function_params_and_body()?;
+        let (params, return_type, body) = self.parse_function_params_and_body()?;
         let end = body.span.end;
 
         Ok(Expr::FunctionExpr {
             name,
             params: params.into(),
+            return_type: return_type.map(Box::new),
             body: Rc::new(body),
             is_generator,
             is_async,
@@ -353,6 +438,10 @@ impl<'a> Parser<'a> {
                             break;
                         }
                     };
+                    let Ok(ty) = self.parse_optional_annotation() else {
+                        malformed = true;
+                        break;
+                    };
                     let default =
                         if !is_rest && matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
                             self.advance();
@@ -366,7 +455,7 @@ impl<'a> Parser<'a> {
                         } else {
                             None
                         };
-                    params.push(Param { name, default, is_rest, pattern: None });
+                    params.push(Param { name, default, is_rest, pattern: None, ty });
                     if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
                         self.advance();
                     } else {
@@ -381,6 +470,7 @@ impl<'a> Parser<'a> {
                 return Err(());
             }
             self.advance();
+            let return_type = self.parse_optional_annotation()?;
             if !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Arrow)) {
                 self.position = saved_pos;
                 self.diagnostics.truncate(saved_diag_len);
@@ -388,14 +478,14 @@ impl<'a> Parser<'a> {
                 return Err(());
             }
             self.advance();
-            self.parse_arrow_body_with_async(params, start, true)
+            self.parse_arrow_body_with_async(params, start, true, return_type)
         } else if matches!(self.current().kind, TokenKind::Identifier)
             && matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::Arrow))
         {
             let ident = self.parse_identifier()?;
-            let param = Param { name: ident, default: None, is_rest: false, pattern: None };
+            let param = Param { name: ident, default: None, is_rest: false, pattern: None, ty: None };
             self.advance();
-            self.parse_arrow_body_with_async(vec![param], start, true)
+            self.parse_arrow_body_with_async(vec![param], start, true, None)
         } else {
             self.push_error(self.current().span, "После 'ассо' ожидалась 'йопта', 'пиздюли' или стрелочная функция");
             Err(())
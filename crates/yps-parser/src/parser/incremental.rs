@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+
+use yps_lexer::Edit;
+
+use crate::ast::{ExportKind, ImportSpec, ObjectEntry};
+
+use super::*;
+
+/// Tokens past the end of a statement the parser may have looked at while parsing it.
+const REUSE_LOOKAHEAD: usize = 2;
+
+/// The parse of a document as it was before `edit`, for [`Parser::parse_program_reusing`].
+pub struct PreviousParse<'p> {
+    pub program: &'p Program,
+    pub tokens: &'p [Token],
+    pub diagnostics: &'p [Diagnostic],
+}
+
+impl Parser<'_> {
+    /// Parses the program again after `edit`, reusing every top-level item of `previous` whose
+    /// tokens (and the few after them) came through the edit unchanged and that parsed cleanly.
+    /// Reused items have their spans moved instead of being parsed anew.
+    pub fn parse_program_reusing(mut self, previous: &PreviousParse, edit: Edit) -> (Program, Vec<Diagnostic>) {
+        let starts: HashMap<usize, usize> =
+            previous.program.items.iter().enumerate().map(|(i, item)| (item.span().start, i)).collect();
+        let mut items = Vec::new();
+
+        while !self.is_at_end() {
+            if let Some((item, len)) = self.reusable_item(previous, &starts, edit) {
+                items.push(item);
+                self.position += len;
+                continue;
+            }
+            items.push(self.parse_statement_recovering());
+        }
+
+        (Program { items }, self.diagnostics)
+    }
+
+    fn reusable_item(
+        &self,
+        previous: &PreviousParse,
+        starts: &HashMap<usize, usize>,
+        edit: Edit,
+    ) -> Option<(Stmt, usize)> {
+        let start = self.current().span.start;
+        let old_start = if start < edit.span.start {
+            start
+        } else if start >= edit.new_end() {
+            start + (edit.span.end - edit.span.start) - edit.new_len
+        } else {
+            return None;
+        };
+        let item = &previous.program.items[*starts.get(&old_start)?];
+        let first = previous.tokens.partition_point(|t| t.span.start < old_start);
+        let end = previous.tokens.partition_point(|t| t.span.end <= item.span().end);
+        let checked = &previous.tokens[first..(end + REUSE_LOOKAHEAD).min(previous.tokens.len())];
+
+        for (offset, old) in checked.iter().enumerate() {
+            let new = self.tokens.get(self.position + offset)?;
+            if new.kind != old.kind || edit.map_span(old.span) != Some(new.span) {
+                return None;
+            }
+        }
+        let region = Span { start: old_start, end: checked.last()?.span.end };
+        if previous.diagnostics.iter().any(|d| d.span.start <= region.end && d.span.end >= region.start) {
+            return None;
+        }
+
+        let mut item = item.clone();
+        if item.span().end > edit.span.start {
+            Shift(edit).stmt(&mut item);
+        }
+        Some((item, end - first))
+    }
+}
+
+/// Moves every span of a reused subtree past an edit.
+struct Shift(Edit);
+
+impl Shift {
+    fn span(&self, span: &mut Span) {
+        *span = self.0.move_span(*span);
+    }
+
+    fn ident(&self, ident: &mut Identifier) {
+        self.span(&mut ident.span);
+    }
+
+    fn block(&self, block: &mut Block) {
+        self.span(&mut block.span);
+        for stmt in &mut block.stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn params(&self, params: &mut [Param]) {
+        for param in params {
+            self.param(param);
+        }
+    }
+
+    fn param(&self, param: &mut Param) {
+        self.ident(&mut param.name);
+        if let Some(default) = &mut param.default {
+            self.expr(default);
+        }
+        if let Some(pattern) = &mut param.pattern {
+            self.pattern(pattern);
+        }
+        self.ty_opt(&mut param.ty);
+    }
+
+    fn ty_opt(&self, ty: &mut Option<TypeExpr>) {
+        if let Some(ty) = ty {
+            self.ty(ty);
+        }
+    }
+
+    fn ty(&self, ty: &mut TypeExpr) {
+        match ty {
+            TypeExpr::Named { name, args, span } => {
+                self.ident(name);
+                for arg in args {
+                    self.ty(arg);
+                }
+                self.span(span);
+            }
+            TypeExpr::Literal { value, span } => {
+                self.literal(value);
+                self.span(span);
+            }
+            TypeExpr::Array { element, span } => {
+                self.ty(element);
+                self.span(span);
+            }
+            TypeExpr::Union { types, span } => {
+                for ty in types {
+                    self.ty(ty);
+                }
+                self.span(span);
+            }
+            TypeExpr::Object { fields, span } => {
+                self.type_fields(fields);
+                self.span(span);
+            }
+            TypeExpr::Function { params, ret, span } => {
+                self.type_fields(params);
+                self.ty(ret);
+                self.span(span);
+            }
+        }
+    }
+
+    fn type_fields(&self, fields: &mut [TypeField]) {
+        for field in fields {
+            self.ident(&mut field.name);
+            self.ty(&mut field.ty);
+            self.span(&mut field.span);
+        }
+    }
+
+    fn exprs(&self, exprs: &mut [Expr]) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+
+    fn stmt(&self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::VarDecl { pattern, ty, init, span, .. } => {
+                self.pattern(pattern);
+                self.ty_opt(ty);
+                self.expr(init);
+                self.span(span);
+            }
+            Stmt::Expr { expr, span } | Stmt::Throw { value: expr, span } => {
+                self.expr(expr);
+                self.span(span);
+            }
+            Stmt::Block(block) => self.block(block),
+            Stmt::Empty { span } | Stmt::Debugger { span } | Stmt::Error { span } => self.span(span),
+            Stmt::TypeAlias { name, ty, span, .. } => {
+                self.ident(name);
+                self.ty(ty);
+                self.span(span);
+            }
+            Stmt::If { condition, then_branch, else_branch, span } => {
+                self.expr(condition);
+                self.stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.stmt(else_branch);
+                }
+                self.span(span);
+            }
+            Stmt::While { condition, body, span } | Stmt::DoWhile { body, condition, span } => {
+                self.expr(condition);
+                self.stmt(body);
+                self.span(span);
+            }
+            Stmt::For { init, condition, update, body, span } => {
+                if let Some(init) = init {
+                    self.stmt(init);
+                }
+                for expr in [condition, update].into_iter().flatten() {
+                    self.expr(expr);
+                }
+                self.stmt(body);
+                self.span(span);
+            }
+            Stmt::Break { label, span } | Stmt::Continue { label, span } => {
+                if let Some(label) = label {
+                    self.ident(label);
+                }
+                self.span(span);
+            }
+            Stmt::Labeled { label, body, span } => {
+                self.ident(label);
+                self.stmt(body);
+                self.span(span);
+            }
+            Stmt::FunctionDecl { name, params, return_type, body, span, .. } => {
+                self.ident(name);
+                self.params(Rc::make_mut(params));
+                self.ty_opt(return_type);
+                self.block(Rc::make_mut(body));
+                self.span(span);
+            }
+            Stmt::Return { value, span } => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+                self.span(span);
+            }
+            Stmt::TryCatch { try_block, catch_param, catch_block, finally_block, span } => {
+                self.block(try_block);
+                if let Some(param) = catch_param {
+                    self.ident(param);
+                }
+                for block in [catch_block, finally_block].into_iter().flatten() {
+                    self.block(block);
+                }
+                self.span(span);
+            }
+            Stmt::Switch { expr, cases, default, span } => {
+                self.expr(expr);
+                for case in cases {
+                    self.expr(&mut case.value);
+                    self.block(&mut case.body);
+                    self.span(&mut case.span);
+                }
+                if let Some(default) = default {
+                    self.block(default);
+                }
+                self.span(span);
+            }
+            Stmt::ForIn { variable, iterable, body, span }
+            | Stmt::ForOf { variable, iterable, body, span }
+            | Stmt::ForAwaitOf { variable, iterable, body, span } => {
+                self.pattern(variable);
+                self.expr(iterable);
+                self.stmt(body);
+                self.span(span);
+            }
+            Stmt::ClassDecl { name, super_class, members, decorators, span } => {
+                self.ident(name);
+                if let Some(super_class) = super_class {
+                    self.expr(super_class);
+                }
+                for member in members {
+                    self.member(member);
+                }
+                self.exprs(decorators);
+                self.span(span);
+            }
+            Stmt::Using { name, init, span, .. } => {
+                self.ident(name);
+                self.expr(init);
+                self.span(span);
+            }
+            Stmt::EnumDecl { name, members, span } => {
+                self.ident(name);
+                for member in members {
+                    self.ident(&mut member.name);
+                    if let Some(init) = &mut member.init {
+                        self.expr(init);
+                    }
+                    self.span(&mut member.span);
+                }
+                self.span(span);
+            }
+            Stmt::Import { specifiers, span, .. } => {
+                for spec in specifiers {
+                    match spec {
+                        ImportSpec::Named { imported, local } => {
+                            self.ident(imported);
+                            self.ident(local);
+                        }
+                        ImportSpec::Default { local } | ImportSpec::Namespace { local } => self.ident(local),
+                    }
+                }
+                self.span(span);
+            }
+            Stmt::Export { kind, span } => {
+                match kind {
+                    ExportKind::Declaration(decl) => self.stmt(decl),
+                    ExportKind::Named(names) => {
+                        for name in names {
+                            self.ident(name);
+                        }
+                    }
+                }
+                self.span(span);
+            }
+        }
+    }
+
+    fn member(&self, member: &mut ClassMember) {
+        match member {
+            ClassMember::Constructor { params, body, span } => {
+                self.params(Rc::make_mut(params));
+                self.block(Rc::make_mut(body));
+                self.span(span);
+            }
+            ClassMember::Method { name, params, return_type, body, decorators, span, .. } => {
+                self.ident(name);
+                self.params(Rc::make_mut(params));
+                self.ty_opt(return_type);
+                self.block(Rc::make_mut(body));
+                self.exprs(decorators);
+                self.span(span);
+            }
+            ClassMember::Field { name, ty, init, decorators, span, .. } => {
+                self.ident(name);
+                self.ty_opt(ty);
+                if let Some(init) = init {
+                    self.expr(init);
+                }
+                self.exprs(decorators);
+                self.span(span);
+            }
+            ClassMember::Getter { name, body, decorators, span, .. } => {
+                self.ident(name);
+                self.block(Rc::make_mut(body));
+                self.exprs(decorators);
+                self.span(span);
+            }
+            ClassMember::Setter { name, param, body, decorators, span, .. } => {
+                self.ident(name);
+                self.param(param);
+                self.block(Rc::make_mut(body));
+                self.exprs(decorators);
+                self.span(span);
+            }
+            ClassMember::StaticBlock { body, span } => {
+                self.block(Rc::make_mut(body));
+                self.span(span);
+            }
+        }
+    }
+
+    fn pattern(&self, pattern: &mut Pattern) {
+        match pattern {
+            Pattern::Identifier(ident) => self.ident(ident),
+            Pattern::Array { elements, rest, span } => {
+                for element in elements.iter_mut().flatten() {
+                    self.pattern(element);
+                }
+                if let Some(rest) = rest {
+                    self.pattern(rest);
+                }
+                self.span(span);
+            }
+            Pattern::Object { properties, rest, span } => {
+                for prop in properties {
+                    self.ident(&mut prop.key);
+                    if let Some(value) = &mut prop.value {
+                        self.pattern(value);
+                    }
+                    self.span(&mut prop.span);
+                }
+                if let Some(rest) = rest {
+                    self.pattern(rest);
+                }
+                self.span(span);
+            }
+            Pattern::Default { pattern, default, span } => {
+                self.pattern(pattern);
+                self.expr(default);
+                self.span(span);
+            }
+        }
+    }
+
+    fn prop_key(&self, key: &mut PropKey) {
+        match key {
+            PropKey::Identifier(ident) => self.ident(ident),
+            PropKey::Computed(expr) => self.expr(expr),
+        }
+    }
+
+    fn literal(&self, literal: &mut Literal) {
+        match literal {
+            Literal::Number { span, .. }
+            | Literal::BigInt { span, .. }
+            | Literal::String { span, .. }
+            | Literal::Boolean { span, .. }
+            | Literal::Null { span }
+            | Literal::Undefined { span }
+            | Literal::RegExp { span, .. } => self.span(span),
+            Literal::Array { elements, span } => {
+                self.exprs(elements);
+                self.span(span);
+            }
+            Literal::Object { entries, span } => {
+                for entry in entries {
+                    match entry {
+                        ObjectEntry::Property { key, value } => {
+                            self.prop_key(key);
+                            self.expr(value);
+                        }
+                        ObjectEntry::Spread(expr) => self.expr(expr),
+                        ObjectEntry::Getter { key, body, span } => {
+                            self.prop_key(key);
+                            self.block(body);
+                            self.span(span);
+                        }
+                        ObjectEntry::Setter { key, param, body, span } => {
+                            self.prop_key(key);
+                            self.param(param);
+                            self.block(body);
+                            self.span(span);
+                        }
+                    }
+                }
+                self.span(span);
+            }
+        }
+    }
+
+    fn expr(&self, expr: &mut Expr) {
+        match expr {
+            Expr::Identifier(ident) => self.ident(ident),
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Unary { expr, span, .. }
+            | Expr::Postfix { expr, span, .. }
+            | Expr::Grouping { expr, span }
+            | Expr::Spread { expr, span }
+            | Expr::Await { argument: expr, span }
+            | Expr::DynamicImport { source: expr, span } => {
+                self.expr(expr);
+                self.span(span);
+            }
+            Expr::Binary { lhs, rhs, span, .. }
+            | Expr::Index { object: lhs, index: rhs, span }
+            | Expr::OptionalIndex { object: lhs, index: rhs, span } => {
+                self.expr(lhs);
+                self.expr(rhs);
+                self.span(span);
+            }
+            Expr::Assignment { target, value, span } => {
+                self.ident(target);
+                self.expr(value);
+                self.span(span);
+            }
+            Expr::Call { callee, args, span }
+            | Expr::OptionalCall { callee, args, span }
+            | Expr::New { callee, args, span } => {
+                self.expr(callee);
+                self.exprs(args);
+                self.span(span);
+            }
+            Expr::Member { object, property, span } | Expr::OptionalMember { object, property, span } => {
+                self.expr(object);
+                self.ident(property);
+                self.span(span);
+            }
+            Expr::Conditional { condition, then_expr, else_expr, span } => {
+                self.expr(condition);
+                self.expr(then_expr);
+                self.expr(else_expr);
+                self.span(span);
+            }
+            Expr::ArrowFunction { params, return_type, body, span, .. } => {
+                self.params(Rc::make_mut(params));
+                if let Some(ty) = return_type {
+                    self.ty(ty);
+                }
+                self.block(Rc::make_mut(body));
+                self.span(span);
+            }
+            Expr::FunctionExpr { name, params, return_type, body, span, .. } => {
+                if let Some(name) = name {
+                    self.ident(name);
+                }
+                self.params(Rc::make_mut(params));
+                if let Some(ty) = return_type {
+                    self.ty(ty);
+                }
+                self.block(Rc::make_mut(body));
+                self.span(span);
+            }
+            Expr::TemplateLiteral { parts, span } => {
+                for part in parts {
+                    if let TemplatePart::Expr(expr) = part {
+                        self.expr(expr);
+                    }
+                }
+                self.span(span);
+            }
+            Expr::TaggedTemplate { tag, expressions, span, .. } => {
+                self.expr(tag);
+                self.exprs(expressions);
+                self.span(span);
+            }
+            Expr::This { span }
+            | Expr::Super { span }
+            | Expr::ImportMeta { span }
+            | Expr::NewTarget { span }
+            | Expr::Error { span } => self.span(span),
+            Expr::Yield { argument, span, .. } => {
+                if let Some(argument) = argument {
+                    self.expr(argument);
+                }
+                self.span(span);
+            }
+            Expr::Match { subject, arms, span } => {
+                self.expr(subject);
+                for arm in arms {
+                    self.match_pattern(&mut arm.pattern);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.expr(&mut arm.body);
+                    self.span(&mut arm.span);
+                }
+                self.span(span);
+            }
+        }
+    }
+
+    fn match_pattern(&self, pattern: &mut MatchPattern) {
+        match pattern {
+            MatchPattern::Wildcard { span } => self.span(span),
+            MatchPattern::Bind(pattern) => self.pattern(pattern),
+            MatchPattern::Value(expr) => self.expr(expr),
+            MatchPattern::Instance { class, pattern, span } => {
+                self.expr(class);
+                if let Some(pattern) = pattern {
+                    self.pattern(pattern);
+                }
+                self.span(span);
+            }
+        }
+    }
+}
@@ -56,14 +56,35 @@ impl<'a> Parser<'a> {
             }
             TokenKind::Punctuation(PunctuationKind::LBracket) => self.parse_array(),
             TokenKind::Punctuation(PunctuationKind::LBrace) => self.parse_object(),
+            // A missing operand in front of a closing token: keep the surrounding expression.
+            TokenKind::Punctuation(
+                PunctuationKind::Semicolon
+                | PunctuationKind::Comma
+                | PunctuationKind::Colon
+                | PunctuationKind::RParen
+                | PunctuationKind::RBracket
+                | PunctuationKind::RBrace,
+            )
+            | TokenKind::Eof => {
+                let span = self.current().span;
+                self.push_unexpected_token(span);
+                Ok(Expr::Error { span: Span { start: span.start, end: span.start } })
+            }
             _ => {
                 let span = self.current().span;
-                self.push_error(span, format!("Неожиданный токен: {:?}", self.current().kind));
+                self.push_unexpected_token(span);
                 Err(())
             }
         }
     }
 
+    fn push_unexpected_token(&mut self, span: Span) {
+        let message = format!("Неожиданный токен: {:?}", self.current().kind);
+        self.push_diagnostic(
+            Diagnostic::error(message, span).with_code(codes::UNEXPECTED_TOKEN).with_label("здесь ожидалось выражение"),
+        );
+    }
+
     pub(super) fn parse_number(&mut self) -> Expr {
         let span = self.current().span;
         let raw = self.source.slice(span).to_string();
@@ -269,7 +290,9 @@ impl<'a> Parser<'a> {
     pub(super) fn parse_identifier(&mut self) -> Result<Identifier, ()> {
         if !matches!(self.current().kind, TokenKind::Identifier) {
             let span = self.current().span;
-            self.push_error(span, "Ожидался идентификатор");
+            self.push_diagnostic(
+                Diagnostic::error("Ожидался идентификатор", span).with_code(codes::EXPECTED_IDENTIFIER),
+            );
             return Err(());
         }
 
@@ -280,14 +303,14 @@ impl<'a> Parser<'a> {
     }
 
     pub(super) fn parse_grouping(&mut self) -> Result<Expr, ()> {
-        let start = self.current().span.start;
+        let open = self.current().span;
         self.advance();
 
         let expr = self.parse_expr()?;
 
-        let end = self.expect_punct(PunctuationKind::RParen, "Ожидался ')'")?.end;
+        let end = self.expect_closing(PunctuationKind::RParen, open, "Ожидался ')'")?.end;
 
-        Ok(Expr::Grouping { expr: Box::new(expr), span: Span { start, end } })
+        Ok(Expr::Grouping { expr: Box::new(expr), span: Span { start: open.start, end } })
     }
 
     pub(super) fn parse_array(&mut self) -> Result<Expr, ()> {
@@ -318,10 +341,12 @@ impl<'a> Parser<'a> {
                         self.advance();
                         let params = self.parse_function_params()?;
                         self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после параметров метода")?;
+                        let return_type = self.parse_optional_annotation()?;
                         let body = self.parse_block()?;
                         let func_span = body.span;
                         let value = Expr::ArrowFunction {
                             params: params.into(),
+                            return_type: return_type.map(Box::new),
                             body: Rc::new(body),
                             is_async: false,
                             span: func_span,
@@ -383,7 +408,7 @@ impl<'a> Parser<'a> {
                     let param = params.into_iter().next().unwrap();
                     entries.push(ObjectEntry::Setter {
                         key: PropKey::Identifier(key),
-                        param,
+                        param: Box::new(param),
                         body,
                         span: Span { start: gs_start, end: gs_end },
                     });
@@ -397,10 +422,12 @@ impl<'a> Parser<'a> {
                         self.advance();
                         let params = self.parse_function_params()?;
                         self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после параметров метода")?;
+                        let return_type = self.parse_optional_annotation()?;
                         let body = self.parse_block()?;
                         let func_span = Span { start: key.span.start, end: body.span.end };
                         let value = Expr::ArrowFunction {
                             params: params.into(),
+                            return_type: return_type.map(Box::new),
                             body: Rc::new(body),
                             is_async: false,
                             span: func_span,
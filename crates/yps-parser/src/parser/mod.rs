@@ -1,10 +1,14 @@
 use std::rc::Rc;
 
 use crate::ast::{
-    BinaryOp, Block, ClassMember, Expr, Identifier, Literal, ObjectEntry, ObjectPatternProp, Param, Pattern, PostfixOp,
-    Program, PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi, UnaryOp,
+    BinaryOp, Block, ClassMember, EnumMember, Expr, Identifier, Literal, MatchArm, MatchPattern, ObjectEntry,
+    ObjectPatternProp, Param, Pattern, PostfixOp, Program, PropKey, Stmt, SwitchCase, TemplatePart, TemplateQuasi,
+    TypeExpr, TypeField, UnaryOp,
+};
+use yps_lexer::{
+    Applicability, Diagnostic, KeywordKind, OperatorKind, PunctuationKind, SourceFile, Span, Suggestion, Token,
+    TokenKind, codes,
 };
-use yps_lexer::{Diagnostic, KeywordKind, OperatorKind, PunctuationKind, Severity, SourceFile, Span, Token, TokenKind};
 
 use crate::precedence::{TERNARY_PRECEDENCE, UNARY_PRECEDENCE};
 
@@ -20,6 +24,12 @@ pub struct Parser<'a> {
     diagnostics: Vec<Diagnostic>,
     unexpected_eof: bool,
     depth: usize,
+    /// Index of the first token of the statement being parsed.
+    statement_start: usize,
+    /// The second half of a `>>` that closed a nested type argument list.
+    pending_gt: bool,
+    /// `;` may be left out before `}` and at the end of input.
+    optional_semicolons: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -28,13 +38,37 @@ impl<'a> Parser<'a> {
             matches!(tokens.last().map(|t| &t.kind), Some(TokenKind::Eof)),
             "Parser::new требует, чтобы tokens заканчивался TokenKind::Eof"
         );
-        Self { tokens, source, position: 0, diagnostics: Vec::new(), unexpected_eof: false, depth: 0 }
+        Self {
+            tokens,
+            source,
+            position: 0,
+            diagnostics: Vec::new(),
+            unexpected_eof: false,
+            depth: 0,
+            statement_start: 0,
+            pending_gt: false,
+            optional_semicolons: false,
+        }
+    }
+
+    /// Lets statements before `}` and at the end of input go without `;`, for code that is
+    /// built from strings at runtime, such as `ебал("сказать(1)")`.
+    pub fn with_optional_semicolons(mut self) -> Self {
+        self.optional_semicolons = true;
+        self
     }
 
     fn expect_punct(&mut self, kind: PunctuationKind, msg: &str) -> Result<Span, ()> {
         if !matches!(&self.current().kind, TokenKind::Punctuation(k) if *k == kind) {
             let span = self.current().span;
-            self.push_error(span, msg);
+            let code = match kind {
+                PunctuationKind::Semicolon => codes::MISSING_SEMICOLON,
+                PunctuationKind::RParen | PunctuationKind::RBracket | PunctuationKind::RBrace => {
+                    codes::UNCLOSED_DELIMITER
+                }
+                _ => codes::SYNTAX_ERROR,
+            };
+            self.push_diagnostic(Diagnostic::error(msg, span).with_code(code));
             return Err(());
         }
         let span = self.current().span;
@@ -42,6 +76,73 @@ impl<'a> Parser<'a> {
         Ok(span)
     }
 
+    /// Expects the `;` (or `нах`) that ends a statement. A missing one before a line break, a `}`
+    /// or the end of input is reported, and the statement is kept as if it had been there.
+    fn expect_semicolon(&mut self, msg: &str) -> Result<usize, ()> {
+        if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Semicolon)) {
+            let end = self.current().span.end;
+            self.advance();
+            return Ok(end);
+        }
+        let span = self.current().span;
+        let at_end = matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RBrace) | TokenKind::Eof);
+        if self.optional_semicolons
+            && at_end
+            && let Some(previous) = self.previous()
+        {
+            return Ok(previous.span.end);
+        }
+        let Some(previous) = self.previous().map(|t| t.span) else {
+            self.push_diagnostic(Diagnostic::error(msg, span).with_code(codes::MISSING_SEMICOLON));
+            return Err(());
+        };
+        // Only a statement that surely ends here gets a fix that can be applied blindly; in the
+        // middle of a line the missing ';' is usually the echo of another error.
+        let line_break = self.source.slice(Span { start: previous.end, end: span.start }).contains('\n');
+        let ends_here = line_break || at_end;
+        let applicability = if ends_here { Applicability::MachineApplicable } else { Applicability::MaybeIncorrect };
+        let insert_at = Span { start: previous.end, end: previous.end };
+        self.push_diagnostic(
+            Diagnostic::error(msg, span)
+                .with_code(codes::MISSING_SEMICOLON)
+                .with_secondary(previous, "инструкция должна закончиться здесь")
+                .with_suggestion("добавьте ';'", insert_at, ";", applicability),
+        );
+        if ends_here { Ok(previous.end) } else { Err(()) }
+    }
+
+    /// Expects the `}` that closes a block or a class body opened at `open`. Running into the end
+    /// of input is reported, but what was parsed so far is kept.
+    fn expect_closing_brace(&mut self, open: Span, msg: &str) -> Result<usize, ()> {
+        if self.is_at_end() {
+            let span = self.current().span;
+            self.push_unclosed(open, span, msg);
+            return Ok(self.previous().map_or(span.start, |t| t.span.end));
+        }
+        self.expect_punct(PunctuationKind::RBrace, msg).map(|span| span.end)
+    }
+
+    /// Expects a closing `)`, `]` or `}` and points back at the `open` delimiter when it is missing.
+    fn expect_closing(&mut self, kind: PunctuationKind, open: Span, msg: &str) -> Result<Span, ()> {
+        if matches!(&self.current().kind, TokenKind::Punctuation(k) if *k == kind) {
+            let span = self.current().span;
+            self.advance();
+            return Ok(span);
+        }
+        let span = self.current().span;
+        self.push_unclosed(open, span, msg);
+        Err(())
+    }
+
+    fn push_unclosed(&mut self, open: Span, span: Span, msg: &str) {
+        let opener = self.source.slice(open).to_string();
+        self.push_diagnostic(
+            Diagnostic::error(msg, span)
+                .with_code(codes::UNCLOSED_DELIMITER)
+                .with_secondary(open, format!("'{opener}' открыта здесь")),
+        );
+    }
+
     fn expect_keyword(&mut self, kind: KeywordKind, msg: &str) -> Result<Span, ()> {
         if !matches!(&self.current().kind, TokenKind::Keyword(k) if *k == kind) {
             let span = self.current().span;
@@ -67,7 +168,9 @@ impl<'a> Parser<'a> {
     fn enter_depth(&mut self) -> Result<(), ()> {
         if self.depth >= MAX_PARSE_DEPTH {
             let span = self.current().span;
-            self.push_error(span, "Слишком глубокая вложенность конструкций");
+            self.push_diagnostic(
+                Diagnostic::error("Слишком глубокая вложенность конструкций", span).with_code(codes::NESTING_TOO_DEEP),
+            );
             return Err(());
         }
         self.depth += 1;
@@ -78,16 +181,7 @@ impl<'a> Parser<'a> {
         let mut items = Vec::new();
 
         while !self.is_at_end() {
-            let pos_before = self.position;
-            match self.parse_statement() {
-                Ok(stmt) => items.push(stmt),
-                Err(()) => {
-                    self.synchronize();
-                    if self.position == pos_before && !self.is_at_end() {
-                        self.advance();
-                    }
-                }
-            }
+            items.push(self.parse_statement_recovering());
         }
 
         let program = Program { items };
@@ -125,13 +219,46 @@ impl<'a> Parser<'a> {
     }
 
     fn push_error(&mut self, span: Span, message: impl Into<String>) {
+        self.push_diagnostic(Diagnostic::error(message, span).with_code(codes::SYNTAX_ERROR));
+    }
+
+    fn push_diagnostic(&mut self, mut diagnostic: Diagnostic) {
+        // Recovery may stop in front of the token that was just reported; saying it twice helps no one.
+        if self.diagnostics.last().is_some_and(|last| last.span == diagnostic.span) {
+            return;
+        }
         if self.diagnostics.is_empty()
             && let Some(eof_tok) = self.tokens.last()
-            && span.start >= eof_tok.span.start
+            && diagnostic.span.start >= eof_tok.span.start
         {
             self.unexpected_eof = true;
         }
-        self.diagnostics.push(Diagnostic { severity: Severity::Error, message: message.into(), span });
+        if let Some((span, keyword)) = self.misspelled_keyword() {
+            for suggestion in &mut diagnostic.suggestions {
+                suggestion.applicability = Applicability::MaybeIncorrect;
+            }
+            diagnostic.suggestions.insert(
+                0,
+                Suggestion {
+                    message: format!("возможно, имелось в виду '{keyword}'"),
+                    span,
+                    replacement: keyword.to_string(),
+                    applicability: Applicability::MaybeIncorrect,
+                },
+            );
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// An identifier next to a syntax error that looks like a misspelled keyword: the token before
+    /// the error, the first token of the statement or the token at the error.
+    fn misspelled_keyword(&self) -> Option<(Span, &'static str)> {
+        [self.position.checked_sub(1), Some(self.statement_start), Some(self.position)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.tokens.get(i))
+            .filter(|t| matches!(t.kind, TokenKind::Identifier))
+            .find_map(|t| yps_lexer::keyword_suggestion(self.source.slice(t.span)).map(|keyword| (t.span, keyword)))
     }
 
     fn skip_to_for_recovery(&mut self) {
@@ -166,6 +293,88 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses a statement; on a syntax error skips to the next statement boundary and returns a
+    /// [`Stmt::Error`] covering the skipped tokens. Always consumes at least one token.
+    fn parse_statement_recovering(&mut self) -> Stmt {
+        let before = self.position;
+        let start = self.current().span.start;
+        let outer_statement = std::mem::replace(&mut self.statement_start, before);
+        let parsed = self.parse_statement();
+        self.statement_start = outer_statement;
+        if let Ok(stmt) = parsed
+            && self.position > before
+        {
+            return stmt;
+        }
+        self.skip_unclosed_groups(before);
+        self.synchronize();
+        if self.position == before && !self.is_at_end() {
+            self.advance();
+        }
+        let end = self.previous().map_or(start, |t| t.span.end).max(start);
+        Stmt::Error { span: Span { start, end } }
+    }
+
+    /// Skips a malformed class member: up to a `;` or the end of a member body, stopping in front
+    /// of the `}` that closes the class.
+    fn synchronize_class_member(&mut self) {
+        let mut depth = 0usize;
+        while !self.is_at_end() {
+            match self.current().kind {
+                TokenKind::Punctuation(
+                    PunctuationKind::LBrace | PunctuationKind::LParen | PunctuationKind::LBracket,
+                ) => {
+                    depth += 1;
+                }
+                TokenKind::Punctuation(PunctuationKind::RBrace) if depth == 0 => return,
+                TokenKind::Punctuation(PunctuationKind::RBrace) if depth == 1 => {
+                    self.advance();
+                    return;
+                }
+                TokenKind::Punctuation(
+                    PunctuationKind::RBrace | PunctuationKind::RParen | PunctuationKind::RBracket,
+                ) => {
+                    depth = depth.saturating_sub(1);
+                }
+                TokenKind::Punctuation(PunctuationKind::Semicolon) if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    /// Skips to the close of every `(` and `[` the broken statement starting at `from` left open,
+    /// so that their contents are not parsed again as statements of their own. Stops early at a
+    /// `;` or an unmatched `}`.
+    fn skip_unclosed_groups(&mut self, from: usize) {
+        let mut open = 0usize;
+        for token in &self.tokens[from..self.position.min(self.tokens.len())] {
+            match token.kind {
+                TokenKind::Punctuation(PunctuationKind::LParen | PunctuationKind::LBracket) => open += 1,
+                TokenKind::Punctuation(PunctuationKind::RParen | PunctuationKind::RBracket) => {
+                    open = open.saturating_sub(1);
+                }
+                _ => {}
+            }
+        }
+        let mut braces = 0usize;
+        while open > 0 && !self.is_at_end() {
+            match self.current().kind {
+                TokenKind::Punctuation(PunctuationKind::LParen | PunctuationKind::LBracket) => open += 1,
+                TokenKind::Punctuation(PunctuationKind::RParen | PunctuationKind::RBracket) => open -= 1,
+                TokenKind::Punctuation(PunctuationKind::LBrace) => braces += 1,
+                TokenKind::Punctuation(PunctuationKind::RBrace) if braces == 0 => return,
+                TokenKind::Punctuation(PunctuationKind::RBrace) => braces -= 1,
+                TokenKind::Punctuation(PunctuationKind::Semicolon) if braces == 0 => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
     fn synchronize(&mut self) {
         while !self.is_at_end() {
             if matches!(
@@ -196,10 +405,14 @@ impl<'a> Parser<'a> {
 mod class;
 mod expr;
 mod functions;
+mod incremental;
 mod literals;
 mod modules;
 mod patterns;
 mod stmt;
+mod types;
+
+pub use incremental::PreviousParse;
 
 #[cfg(test)]
 mod tests;
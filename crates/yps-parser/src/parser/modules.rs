@@ -77,7 +77,7 @@ impl<'a> Parser<'a> {
             Vec::new()
         };
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после импорта")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после импорта")?;
 
         Ok(Stmt::Import { specifiers, source, attributes, span: Span { start, end } })
     }
@@ -151,7 +151,7 @@ impl<'a> Parser<'a> {
                 }
             }
             self.expect_punct(PunctuationKind::RBrace, "Ожидалась '}' в списке экспортов")?;
-            let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после экспорта")?.end;
+            let end = self.expect_semicolon("Ожидалась ';' после экспорта")?;
             return Ok(Stmt::Export { kind: crate::ast::ExportKind::Named(names), span: Span { start, end } });
         }
 
@@ -160,10 +160,11 @@ impl<'a> Parser<'a> {
             Stmt::VarDecl { span, .. }
             | Stmt::FunctionDecl { span, .. }
             | Stmt::ClassDecl { span, .. }
+            | Stmt::EnumDecl { span, .. }
             | Stmt::Expr { span, .. } => span.end,
             _ => {
                 let span = self.current().span;
-                self.push_error(span, "После 'предъява' ожидается переменная, функция или класс");
+                self.push_error(span, "После 'предъява' ожидается переменная, функция, класс или перечисление");
                 return Err(());
             }
         };
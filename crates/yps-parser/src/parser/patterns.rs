@@ -121,4 +121,136 @@ impl<'a> Parser<'a> {
 
         Ok(Pattern::Object { properties, rest, span: Span { start, end } })
     }
+
+    /// At statement start `базарпо` is a match expression when its first arm uses `=>` rather than
+    /// the `:` of a switch case.
+    pub(super) fn match_expr_ahead(&self) -> bool {
+        let mut depth = 0usize;
+        let mut in_body = false;
+        let mut offset = 1;
+        loop {
+            match &self.peek(offset).kind {
+                TokenKind::Eof => return false,
+                TokenKind::Punctuation(
+                    PunctuationKind::LParen | PunctuationKind::LBracket | PunctuationKind::LBrace,
+                ) => {
+                    depth += 1;
+                }
+                TokenKind::Punctuation(
+                    PunctuationKind::RParen | PunctuationKind::RBracket | PunctuationKind::RBrace,
+                ) => {
+                    if depth == 0 || (in_body && depth == 1) {
+                        return false;
+                    }
+                    depth -= 1;
+                    in_body |= depth == 0;
+                }
+                TokenKind::Punctuation(PunctuationKind::Colon) if in_body && depth == 1 => return false,
+                TokenKind::Punctuation(PunctuationKind::Arrow) if in_body && depth == 1 => return true,
+                _ => {}
+            }
+            offset += 1;
+        }
+    }
+
+    /// `базарпо (значение) { тема образец [вилкойвглаз (условие)] => результат, ... }` in expression position.
+    pub(super) fn parse_match_expr(&mut self) -> Result<Expr, ()> {
+        let start = self.current().span.start;
+        self.advance();
+
+        self.expect_punct(PunctuationKind::LParen, "Ожидалась '(' после 'базарпо'")?;
+        let subject = self.parse_expr()?;
+        self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после выражения")?;
+        self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{' после 'базарпо'")?;
+
+        let mut arms = Vec::new();
+        while !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RBrace) | TokenKind::Eof) {
+            let arm_start = self.current().span.start;
+            let pattern = match self.current().kind {
+                TokenKind::Keyword(KeywordKind::Case) => {
+                    self.advance();
+                    self.parse_match_pattern()?
+                }
+                TokenKind::Keyword(KeywordKind::Default) => {
+                    let span = self.current().span;
+                    self.advance();
+                    MatchPattern::Wildcard { span }
+                }
+                _ => {
+                    let span = self.current().span;
+                    self.push_error(span, "Ожидалось 'тема' или 'нуичо' внутри 'базарпо'");
+                    return Err(());
+                }
+            };
+
+            let guard = if matches!(self.current().kind, TokenKind::Keyword(KeywordKind::Vilkoyvglaz)) {
+                self.advance();
+                self.expect_punct(PunctuationKind::LParen, "Ожидалась '(' после 'вилкойвглаз'")?;
+                let guard = self.parse_expr()?;
+                self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после условия")?;
+                Some(guard)
+            } else {
+                None
+            };
+
+            self.expect_punct(PunctuationKind::Arrow, "Ожидалась '=>' после образца")?;
+            let body = self.parse_expr()?;
+            let span = Span { start: arm_start, end: body.span().end };
+            arms.push(MatchArm { pattern, guard, body, span });
+
+            if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        let end = self.expect_punct(PunctuationKind::RBrace, "Ожидалась '}' после 'базарпо'")?.end;
+
+        Ok(Expr::Match { subject: Box::new(subject), arms, span: Span { start, end } })
+    }
+
+    fn parse_match_pattern(&mut self) -> Result<MatchPattern, ()> {
+        match self.current().kind {
+            TokenKind::Keyword(KeywordKind::Instanceof) => {
+                let start = self.current().span.start;
+                self.advance();
+                let mut class = self.parse_primary()?;
+                while matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Dot)) {
+                    class = self.parse_member(class)?;
+                }
+                let pattern = if matches!(
+                    self.current().kind,
+                    TokenKind::Punctuation(PunctuationKind::LBrace | PunctuationKind::LBracket)
+                ) {
+                    Some(self.parse_pattern()?)
+                } else {
+                    None
+                };
+                let end = pattern.as_ref().map_or(class.span().end, |p| p.span().end);
+                Ok(MatchPattern::Instance { class, pattern, span: Span { start, end } })
+            }
+            TokenKind::Number
+            | TokenKind::StringLiteral
+            | TokenKind::TemplateNoSub
+            | TokenKind::Keyword(KeywordKind::Pravda | KeywordKind::Lozh | KeywordKind::Nol | KeywordKind::Undefined) => {
+                Ok(MatchPattern::Value(self.parse_primary()?))
+            }
+            TokenKind::Operator(OperatorKind::Minus) if matches!(self.peek(1).kind, TokenKind::Number) => {
+                let start = self.current().span.start;
+                self.advance();
+                let number = self.parse_primary()?;
+                let span = Span { start, end: number.span().end };
+                Ok(MatchPattern::Value(Expr::Unary { op: UnaryOp::Minus, expr: Box::new(number), span }))
+            }
+            TokenKind::Identifier if matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::Dot)) => {
+                let mut path = self.parse_identifier().map(Expr::Identifier)?;
+                while matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Dot)) {
+                    path = self.parse_member(path)?;
+                }
+                Ok(MatchPattern::Value(path))
+            }
+            _ => self.parse_pattern().map(MatchPattern::Bind),
+        }
+    }
 }
@@ -30,10 +30,17 @@ impl<'a> Parser<'a> {
             TokenKind::Keyword(KeywordKind::Otvechayu) => self.parse_return_stmt(),
             TokenKind::Keyword(KeywordKind::Try) => self.parse_try_stmt(),
             TokenKind::Keyword(KeywordKind::Throw) => self.parse_throw_stmt(),
-            TokenKind::Keyword(KeywordKind::Switch) => self.parse_switch_stmt(),
+            TokenKind::Keyword(KeywordKind::Switch) => {
+                if self.match_expr_ahead() {
+                    self.parse_expr_stmt()
+                } else {
+                    self.parse_switch_stmt()
+                }
+            }
             TokenKind::Keyword(KeywordKind::DoWhile) => self.parse_do_while_stmt(),
             TokenKind::Keyword(KeywordKind::Class) => self.parse_class_decl(),
             TokenKind::Keyword(KeywordKind::Using) => self.parse_using_stmt(),
+            TokenKind::Keyword(KeywordKind::Enum) => self.parse_enum_decl(),
             TokenKind::Keyword(KeywordKind::Debugger) => {
                 let span = self.current().span;
                 self.advance();
@@ -43,7 +50,7 @@ impl<'a> Parser<'a> {
                 Ok(Stmt::Debugger { span })
             }
             TokenKind::Keyword(KeywordKind::Import) => {
-                if matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::LParen)) {
+                if matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::LParen | PunctuationKind::Dot)) {
                     self.parse_expr_stmt()
                 } else {
                     self.parse_import_stmt()
@@ -60,6 +67,7 @@ impl<'a> Parser<'a> {
                     Err(())
                 }
             }
+            TokenKind::Identifier if self.type_alias_ahead() => self.parse_type_alias(),
             TokenKind::Punctuation(PunctuationKind::LBrace) => self.parse_block().map(Stmt::Block),
             TokenKind::Punctuation(PunctuationKind::Semicolon) => {
                 let span = self.current().span;
@@ -76,12 +84,13 @@ impl<'a> Parser<'a> {
         self.advance();
 
         let pattern = self.parse_pattern()?;
+        let ty = self.parse_optional_annotation()?;
         self.expect_operator(OperatorKind::Assign, "Ожидался '=' после имени переменной")?;
 
         let init = self.parse_expr()?;
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после объявления переменной")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после объявления переменной")?;
 
-        Ok(Stmt::VarDecl { pattern, init, is_const, span: Span { start, end } })
+        Ok(Stmt::VarDecl { pattern, ty, init, is_const, span: Span { start, end } })
     }
 
     pub(super) fn parse_using_stmt(&mut self) -> Result<Stmt, ()> {
@@ -97,40 +106,74 @@ impl<'a> Parser<'a> {
         self.expect_operator(OperatorKind::Assign, "Ожидался '=' после имени ресурса в 'юзай'")?;
 
         let init = self.parse_expr()?;
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после объявления 'юзай'")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после объявления 'юзай'")?;
 
         Ok(Stmt::Using { name, init, is_await, span: Span { start, end } })
     }
 
+    pub(super) fn parse_enum_decl(&mut self) -> Result<Stmt, ()> {
+        let start = self.current().span.start;
+        self.advance();
+
+        let name = self.parse_identifier()?;
+        let open = self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{' после имени перечисления")?;
+
+        let mut members: Vec<EnumMember> = Vec::new();
+        while !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RBrace) | TokenKind::Eof) {
+            let member = self.parse_identifier()?;
+            if let Some(prev) = members.iter().find(|m| m.name.name == member.name) {
+                let message = format!("Элемент '{}' уже объявлен в перечислении", member.name);
+                let diagnostic = Diagnostic::error(message, member.span)
+                    .with_code(codes::SYNTAX_ERROR)
+                    .with_secondary(prev.name.span, "впервые объявлен здесь");
+                self.push_diagnostic(diagnostic);
+            }
+            let init = if matches!(self.current().kind, TokenKind::Operator(OperatorKind::Assign)) {
+                self.advance();
+                Some(self.parse_expr()?)
+            } else {
+                None
+            };
+            let end = init.as_ref().map_or(member.span.end, |e| e.span().end);
+            members.push(EnumMember { span: Span { start: member.span.start, end }, name: member, init });
+
+            if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        let end = self.expect_closing_brace(open, "Ожидалась '}' в конце перечисления")?;
+        Ok(Stmt::EnumDecl { name, members, span: Span { start, end } })
+    }
+
     pub(super) fn parse_block(&mut self) -> Result<Block, ()> {
         let start = self.current().span.start;
 
-        self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{'")?;
+        let open = self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{'")?;
 
         let mut stmts = Vec::new();
 
         while !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RBrace)) && !self.is_at_end() {
-            let before = self.position;
-            match self.parse_statement() {
-                Ok(stmt) => stmts.push(stmt),
-                Err(()) => {
-                    self.synchronize();
-                    if self.position == before && !self.is_at_end() {
-                        self.advance();
-                    }
-                }
-            }
+            stmts.push(self.parse_statement_recovering());
         }
 
-        let end = self.expect_punct(PunctuationKind::RBrace, "Ожидалась '}'")?.end;
+        let end = self.expect_closing_brace(open, "Ожидалась '}'")?;
 
         Ok(Block { stmts, span: Span { start, end } })
     }
 
     pub(super) fn parse_expr_stmt(&mut self) -> Result<Stmt, ()> {
         let expr = self.parse_expr()?;
+        // The broken expression has been reported already; a missing ';' after it is only an echo.
+        if matches!(expr, Expr::Error { .. })
+            && !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Semicolon))
+        {
+            return Err(());
+        }
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после выражения")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после выражения")?;
 
         let span = Span { start: expr.span().start, end };
 
@@ -274,7 +317,7 @@ impl<'a> Parser<'a> {
         let label =
             if matches!(self.current().kind, TokenKind::Identifier) { Some(self.parse_identifier()?) } else { None };
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после 'харэ'")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после 'харэ'")?;
 
         Ok(Stmt::Break { label, span: Span { start, end } })
     }
@@ -286,7 +329,7 @@ impl<'a> Parser<'a> {
         let label =
             if matches!(self.current().kind, TokenKind::Identifier) { Some(self.parse_identifier()?) } else { None };
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после 'двигай'")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после 'двигай'")?;
 
         Ok(Stmt::Continue { label, span: Span { start, end } })
     }
@@ -309,7 +352,7 @@ impl<'a> Parser<'a> {
             Some(self.parse_expr()?)
         };
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после 'отвечаю'")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после 'отвечаю'")?;
 
         Ok(Stmt::Return { value, span: Span { start, end } })
     }
@@ -366,7 +409,7 @@ impl<'a> Parser<'a> {
 
         let value = self.parse_expr()?;
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после 'кидай'")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после 'кидай'")?;
 
         Ok(Stmt::Throw { value, span: Span { start, end } })
     }
@@ -462,7 +505,7 @@ impl<'a> Parser<'a> {
 
         self.expect_punct(PunctuationKind::RParen, "Ожидалась ')' после условия")?;
 
-        let end = self.expect_punct(PunctuationKind::Semicolon, "Ожидалась ';' после 'крутани...потрещим'")?.end;
+        let end = self.expect_semicolon("Ожидалась ';' после 'крутани...потрещим'")?;
 
         Ok(Stmt::DoWhile { body, condition, span: Span { start, end } })
     }
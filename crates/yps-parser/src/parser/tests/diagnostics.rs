@@ -10,11 +10,16 @@ fn test_parser_recovers_from_unknown_keyword_after_brace() {
     );
     assert_eq!(
         program.items.len(),
-        2,
+        3,
         "expected parser to resynchronize and parse the trailing statement, got: {:?}",
         program.items
     );
-    assert!(matches!(program.items[1], Stmt::VarDecl { .. }), "expected trailing VarDecl, got: {:?}", program.items[1]);
+    assert!(
+        matches!(program.items[1], Stmt::Error { .. }),
+        "expected an error placeholder, got: {:?}",
+        program.items[1]
+    );
+    assert!(matches!(program.items[2], Stmt::VarDecl { .. }), "expected trailing VarDecl, got: {:?}", program.items[2]);
 }
 
 #[test]
@@ -256,3 +261,100 @@ fn very_long_binary_chain_yields_diagnostic_not_crash() {
         diag_messages(&diags)
     );
 }
+
+#[test]
+fn missing_semicolon_before_line_break_keeps_both_statements() {
+    let (program, diags) = parse_program_from_source("сказать(1)\nсказать(2);\n");
+    assert_eq!(diag_messages(&diags), ["Ожидалась ';' после выражения"]);
+    assert_eq!(program.items.len(), 2);
+    assert!(program.items.iter().all(|item| matches!(item, Stmt::Expr { .. })));
+}
+
+#[test]
+fn missing_operand_becomes_an_error_expression() {
+    let (program, diags) = parse_program_from_source("гыы а = ;\nгыы б = 1 + ;\n");
+    assert_eq!(diags.len(), 2);
+    let inits: Vec<&Expr> = program
+        .items
+        .iter()
+        .map(|item| match item {
+            Stmt::VarDecl { init, .. } => init,
+            other => panic!("expected VarDecl, got: {other:?}"),
+        })
+        .collect();
+    assert!(matches!(inits[0], Expr::Error { .. }));
+    assert!(matches!(inits[1], Expr::Binary { rhs, .. } if matches!(rhs.as_ref(), Expr::Error { .. })));
+}
+
+#[test]
+fn unclosed_function_body_keeps_the_function() {
+    let (program, diags) = parse_program_from_source("йопта ф() {\n  гыы а = 1;\n");
+    assert_eq!(diag_messages(&diags), ["Ожидалась '}'"]);
+    let [Stmt::FunctionDecl { body, .. }] = program.items.as_slice() else {
+        panic!("expected a single FunctionDecl, got: {:?}", program.items);
+    };
+    assert_eq!(body.stmts.len(), 1);
+}
+
+#[test]
+fn malformed_class_member_is_skipped() {
+    let (program, diags) = parse_program_from_source("клёво К {\n  а(х,, у) { отвечаю х; }\n  б() {}\n}\nгыы в = 1;\n");
+    assert!(!diags.is_empty());
+    let Stmt::ClassDecl { members, .. } = &program.items[0] else {
+        panic!("expected ClassDecl, got: {:?}", program.items[0]);
+    };
+    assert!(matches!(members.as_slice(), [ClassMember::Method { name, .. }] if name.name == "б"));
+    assert!(matches!(program.items[1], Stmt::VarDecl { .. }));
+}
+
+#[test]
+fn failed_statements_leave_error_placeholders_in_blocks() {
+    let (program, _) = parse_program_from_source("йопта ф() {\n  гыы = 1;\n  отвечаю 2;\n}\n");
+    let Stmt::FunctionDecl { body, .. } = &program.items[0] else { panic!("expected FunctionDecl") };
+    assert!(matches!(body.stmts.as_slice(), [Stmt::Error { .. }, Stmt::Return { .. }]));
+}
+
+#[test]
+fn misspelled_keyword_next_to_an_error_is_suggested() {
+    let src = "вилкойвглаз (1) { сказать(1); } илижопураз { сказать(2); }\n";
+    let (_, diags) = parse_program_from_source(src);
+    let suggestion = &diags[0].suggestions[0];
+    assert_eq!(suggestion.message, "возможно, имелось в виду 'иливжопураз'");
+    assert_eq!(suggestion.replacement, "иливжопураз");
+    assert_eq!(&src[suggestion.span.start..suggestion.span.end], "илижопураз");
+    assert!(diags[0].suggestions.iter().all(|s| s.applicability == Applicability::MaybeIncorrect));
+}
+
+#[test]
+fn ordinary_names_are_not_mistaken_for_keywords() {
+    let (_, diags) = parse_program_from_source("сказать(1)\nсказать(2);\n");
+    assert_eq!(diags[0].code, Some(yps_lexer::codes::MISSING_SEMICOLON));
+    assert!(diags[0].suggestions.iter().all(|s| s.replacement == ";"));
+}
+
+#[test]
+fn missing_semicolon_is_machine_applicable_only_at_a_statement_end() {
+    let applicability = |src: &str| {
+        let (_, diags) = parse_program_from_source(src);
+        let diag = diags.iter().find(|d| d.code == Some(yps_lexer::codes::MISSING_SEMICOLON)).expect("нет E0012");
+        diag.suggestions.iter().find(|s| s.replacement == ";").expect("нет замены").applicability
+    };
+    assert_eq!(applicability("сказать(1)\nсказать(2);\n"), Applicability::MachineApplicable);
+    assert_eq!(applicability("{ сказать(1) }"), Applicability::MachineApplicable);
+    assert_eq!(applicability("сказать(1)"), Applicability::MachineApplicable);
+    assert_eq!(applicability("сказать(1) сказать(2);"), Applicability::MaybeIncorrect);
+    let (_, diags) = parse_program_from_source("гыы р = 1;\nсказать(шкура р);\n");
+    assert!(
+        diags.iter().flat_map(|d| &d.suggestions).all(|s| s.applicability != Applicability::MachineApplicable),
+        "{diags:?}"
+    );
+}
+
+#[test]
+fn one_diagnostic_per_broken_statement() {
+    let (_, diags) = parse_program_from_source("гыы р = 1;\nклёво А {}\nсказать(шкура р);\n");
+    assert_eq!(diag_messages(&diags), vec!["Неожиданный токен: Keyword(Instanceof)"]);
+
+    let (_, diags) = parse_program_from_source("сказать(шкура р);\nсказать(1 +);\nсказать(2);\n");
+    assert_eq!(diags.len(), 2, "{:?}", diag_messages(&diags));
+}
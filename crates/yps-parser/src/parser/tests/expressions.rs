@@ -323,3 +323,57 @@ fn test_parse_async_arrow() {
         other => panic!("Expected async ArrowFunction, got {other:?}"),
     }
 }
+
+#[test]
+fn parenthesized_arrow_span_starts_at_its_paren() {
+    let expr = parse_expr_from_source("ф(1, (x) => x)").unwrap();
+    let Expr::Call { args, .. } = expr else { panic!("ожидался вызов") };
+    assert_eq!(args[1].span().start, 6, "смещение в байтах, а не номер токена");
+}
+
+#[test]
+fn test_parse_match_expr() {
+    let expr = parse_expr_from_source(
+        "базарпо (х) { тема 0 => \"ноль\", тема [а, ...б] вилкойвглаз (а > 0) => а, тема шкура Точка { х } => х, \
+         нуичо => 1 }",
+    )
+    .unwrap();
+    let Expr::Match { subject, arms, .. } = expr else { panic!("ожидался базарпо") };
+    assert!(matches!(*subject, Expr::Identifier(_)));
+    assert_eq!(arms.len(), 4);
+    assert!(matches!(arms[0].pattern, MatchPattern::Value(Expr::Literal(Literal::Number { .. }))));
+    assert!(matches!(arms[1].pattern, MatchPattern::Bind(Pattern::Array { .. })));
+    assert!(arms[1].guard.is_some());
+    assert!(matches!(arms[2].pattern, MatchPattern::Instance { pattern: Some(Pattern::Object { .. }), .. }));
+    assert!(matches!(arms[3].pattern, MatchPattern::Wildcard { .. }));
+}
+
+#[test]
+fn test_parse_match_dotted_path_is_a_value() {
+    let expr = parse_expr_from_source("базарпо (ц) { тема Цвет.Красный => 1, тема ц => 2 }").unwrap();
+    let Expr::Match { arms, .. } = expr else { panic!("ожидался базарпо") };
+    assert!(matches!(arms[0].pattern, MatchPattern::Value(Expr::Member { .. })));
+    assert!(matches!(arms[1].pattern, MatchPattern::Bind(Pattern::Identifier(_))));
+}
+
+#[test]
+fn test_parse_match_requires_arrow() {
+    let result = parse_expr_from_source("базарпо (х) { тема 1 2 }");
+    let diags = result.unwrap_err();
+    assert!(diag_messages(&diags).contains(&"Ожидалась '=>' после образца"), "{:?}", diag_messages(&diags));
+}
+
+#[test]
+fn meta_properties_after_import_and_new() {
+    let expr = parse_expr_from_source("спиздить.мета.путь").unwrap();
+    assert!(matches!(expr, Expr::Member { object, .. } if matches!(*object, Expr::ImportMeta { .. })));
+    assert!(matches!(parse_expr_from_source("захуярить.цель").unwrap(), Expr::NewTarget { .. }));
+    let (program, _) = parse_program_from_source("спиздить.мета;");
+    assert!(matches!(program.items.as_slice(), [Stmt::Expr { expr: Expr::ImportMeta { .. }, .. }]));
+}
+
+#[test]
+fn unknown_meta_property_is_reported() {
+    let (_, diags) = parse_program_from_source("гыы а = захуярить.что;");
+    assert_eq!(diag_messages(&diags), ["Ожидалось 'цель' после 'захуярить.'"]);
+}
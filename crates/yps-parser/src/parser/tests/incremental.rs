@@ -0,0 +1,42 @@
+use super::*;
+use yps_lexer::{Edit, Lexer};
+
+fn reparse(before: &str, start: usize, end: usize, insert: &str) -> (Program, Program) {
+    let old = SourceFile::new("test.yopta".to_string(), before.to_string());
+    let (tokens, _) = Lexer::new(&old).tokenize();
+    let (program, diagnostics) = Parser::new(&tokens, &old).parse_program();
+
+    let mut after = before.to_string();
+    after.replace_range(start..end, insert);
+    let new = SourceFile::new("test.yopta".to_string(), after.clone());
+    let (new_tokens, _) = Lexer::new(&new).tokenize();
+    let edit = Edit { span: Span { start, end }, new_len: insert.len() };
+    let previous = PreviousParse { program: &program, tokens: &tokens, diagnostics: &diagnostics };
+    let (reused, reused_diags) = Parser::new(&new_tokens, &new).parse_program_reusing(&previous, edit);
+    let (full, full_diags) = Parser::new(&new_tokens, &new).parse_program();
+    assert_eq!(format!("{reused:?}"), format!("{full:?}"), "{after:?}");
+    assert_eq!(diag_messages(&reused_diags), diag_messages(&full_diags));
+    (program, reused)
+}
+
+const SRC: &str = "йопта а() { отвечаю 1; }\nгыы б = [1, 2];\nклёво В { м(х) { отвечаю х; } }\nсказать(б);\n";
+
+#[test]
+fn reparsing_with_reuse_matches_a_full_parse() {
+    let at = |needle: &str| SRC.find(needle).unwrap();
+    reparse(SRC, at("2]"), at("2]") + 1, "2, 3");
+    reparse(SRC, at("гыы"), at("гыы"), "// коммент\n");
+    reparse(SRC, at("отвечаю х"), at("отвечаю х"), "сказать(х);\n");
+    reparse(SRC, at(";\nклёво"), at(";\nклёво") + 1, "");
+    reparse(SRC, SRC.len(), SRC.len(), "б(");
+}
+
+#[test]
+fn items_before_the_edit_are_shared_with_the_previous_tree() {
+    let at = SRC.find("сказать").unwrap();
+    let (old, new) = reparse(SRC, at, at, "гыы г = 1;\n");
+    match (&old.items[0], &new.items[0]) {
+        (Stmt::FunctionDecl { body: a, .. }, Stmt::FunctionDecl { body: b, .. }) => assert!(Rc::ptr_eq(a, b)),
+        other => panic!("Expected FunctionDecl, got {other:?}"),
+    }
+}
@@ -29,6 +29,7 @@ fn diag_messages(diags: &[Diagnostic]) -> Vec<&str> {
 mod diagnostics;
 mod expressions;
 mod functions;
+mod incremental;
 mod literals;
 mod modules;
 mod statements;
@@ -680,3 +680,99 @@ fn test_parse_class_static_block_decorator_rejected() {
         diag_messages(&diags)
     );
 }
+
+#[test]
+fn test_parse_enum_decl() {
+    let (program, diags) = parse_program_from_source("еээ Цвет жЫ Красный, Зелёный = 5, Синий, }");
+    assert!(diags.is_empty(), "Expected no errors, got: {diags:?}");
+    match &program.items[0] {
+        Stmt::EnumDecl { name, members, .. } => {
+            assert_eq!(name.name, "Цвет");
+            let names: Vec<&str> = members.iter().map(|m| m.name.name.as_str()).collect();
+            assert_eq!(names, ["Красный", "Зелёный", "Синий"]);
+            assert!(members[0].init.is_none());
+            assert!(matches!(members[1].init, Some(Expr::Literal(Literal::Number { .. }))));
+        }
+        other => panic!("Expected EnumDecl, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_enum_duplicate_member() {
+    let (_, diags) = parse_program_from_source("еээ Цвет { Красный, Красный }");
+    let msgs = diag_messages(&diags);
+    assert!(msgs.iter().any(|m| m.contains("'Красный' уже объявлен")), "Expected duplicate error, got: {msgs:?}");
+}
+
+#[test]
+fn test_parse_match_at_statement_start() {
+    let (program, diags) = parse_program_from_source("базарпо (х) { тема [а] => а };\nбазарпо (х) { лещ 1: {} }");
+    assert!(diags.is_empty(), "Expected no errors, got: {diags:?}");
+    assert!(matches!(program.items[0], Stmt::Expr { expr: Expr::Match { .. }, .. }), "{:?}", program.items[0]);
+    assert!(matches!(program.items[1], Stmt::Switch { .. }), "{:?}", program.items[1]);
+}
+
+#[test]
+fn test_parse_type_annotations() {
+    let src = "гыы а: число | строка[] = 1;\nгыы б: Карта<строка, Помойка<число>> | куку = 1;\nйопта ф(х: Карта<строка, Помойка<число>>, у: (а: число) => куку): булево {}";
+    let (program, diags) = parse_program_from_source(src);
+    assert!(diags.is_empty(), "Expected no errors, got: {diags:?}");
+    match &program.items[0] {
+        Stmt::VarDecl { ty: Some(TypeExpr::Union { types, .. }), .. } => {
+            assert!(matches!(types[0], TypeExpr::Named { .. }));
+            assert!(matches!(types[1], TypeExpr::Array { .. }));
+        }
+        other => panic!("Expected annotated VarDecl, got {other:?}"),
+    }
+    match &program.items[1] {
+        Stmt::VarDecl { ty: Some(TypeExpr::Union { types, .. }), .. } => {
+            assert!(matches!(&types[0], TypeExpr::Named { args, .. } if args.len() == 2));
+        }
+        other => panic!("Expected annotated VarDecl, got {other:?}"),
+    }
+    match &program.items[2] {
+        Stmt::FunctionDecl { params, return_type: Some(TypeExpr::Named { name, .. }), .. } => {
+            assert_eq!(name.name, "булево");
+            assert!(matches!(&params[0].ty, Some(TypeExpr::Named { args, .. }) if args.len() == 2));
+            assert!(matches!(params[1].ty, Some(TypeExpr::Function { .. })));
+        }
+        other => panic!("Expected annotated FunctionDecl, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_type_alias_and_interface() {
+    let src = "тип Ид = число | строка;\nхуёво Точка { x: число; y?: число; сдвиг(д: число): Точка }\nтип(1);";
+    let (program, diags) = parse_program_from_source(src);
+    assert!(diags.is_empty(), "Expected no errors, got: {diags:?}");
+    assert!(matches!(&program.items[0], Stmt::TypeAlias { is_interface: false, .. }));
+    match &program.items[1] {
+        Stmt::TypeAlias { name, ty: TypeExpr::Object { fields, .. }, is_interface: true, .. } => {
+            assert_eq!(name.name, "Точка");
+            assert!(fields[1].optional);
+            assert!(matches!(fields[2].ty, TypeExpr::Function { .. }));
+        }
+        other => panic!("Expected interface, got {other:?}"),
+    }
+    assert!(matches!(program.items[2], Stmt::Expr { .. }));
+}
+
+#[test]
+fn test_parse_annotated_arrow_functions() {
+    let (program, diags) = parse_program_from_source("гыы ф = (а: число, б = 1): число => а + б;");
+    assert!(diags.is_empty(), "Expected no errors, got: {diags:?}");
+    match &program.items[0] {
+        Stmt::VarDecl { init: Expr::ArrowFunction { params, return_type, .. }, .. } => {
+            assert!(params[0].ty.is_some());
+            assert!(return_type.is_some());
+        }
+        other => panic!("Expected arrow function, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_stray_gt_after_type() {
+    let (_, diags) = parse_program_from_source("гыы а: Помойка<число>> = 1;");
+    let msgs = diag_messages(&diags);
+    assert!(msgs.iter().any(|m| m.contains("Лишняя '>'")), "Expected stray '>' error, got: {msgs:?}");
+}
@@ -0,0 +1,276 @@
+use super::*;
+
+impl<'a> Parser<'a> {
+    /// `: тип` after a parameter, a variable, a field or a parameter list.
+    pub(super) fn parse_optional_annotation(&mut self) -> Result<Option<TypeExpr>, ()> {
+        if !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Colon)) {
+            return Ok(None);
+        }
+        self.advance();
+        self.parse_top_type().map(Some)
+    }
+
+    /// A whole annotation: a `>>` may not close more argument lists than were opened.
+    fn parse_top_type(&mut self) -> Result<TypeExpr, ()> {
+        let result = self.parse_type();
+        let pending_gt = std::mem::take(&mut self.pending_gt);
+        let ty = result?;
+        if pending_gt {
+            let span = self.previous().map_or(ty.span(), |t| t.span);
+            self.push_error(span, "Лишняя '>' после типа");
+            return Err(());
+        }
+        Ok(ty)
+    }
+
+    /// Parses a standalone type such as a stdlib signature; the whole input must be one type.
+    pub fn parse_type_source(mut self) -> (Option<TypeExpr>, Vec<Diagnostic>) {
+        let ty = self.parse_top_type().ok();
+        if ty.is_some() && !self.is_at_end() {
+            let span = self.current().span;
+            self.push_error(span, "Лишний текст после типа");
+            return (None, self.diagnostics);
+        }
+        (ty, self.diagnostics)
+    }
+
+    /// A type annotation: a union of array, named, literal, object and function types.
+    pub(super) fn parse_type(&mut self) -> Result<TypeExpr, ()> {
+        self.enter_depth()?;
+        let result = self.parse_union_type();
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_union_type(&mut self) -> Result<TypeExpr, ()> {
+        if matches!(self.current().kind, TokenKind::Operator(OperatorKind::BitOr)) {
+            self.advance();
+        }
+        let first = self.parse_array_type()?;
+        if self.pending_gt || !matches!(self.current().kind, TokenKind::Operator(OperatorKind::BitOr)) {
+            return Ok(first);
+        }
+        let start = first.span().start;
+        let mut types = vec![first];
+        while !self.pending_gt && matches!(self.current().kind, TokenKind::Operator(OperatorKind::BitOr)) {
+            self.advance();
+            types.push(self.parse_array_type()?);
+        }
+        let end = types.last().map_or(start, |t| t.span().end);
+        Ok(TypeExpr::Union { types, span: Span { start, end } })
+    }
+
+    fn parse_array_type(&mut self) -> Result<TypeExpr, ()> {
+        let mut ty = self.parse_primary_type()?;
+        while !self.pending_gt
+            && matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::LBracket))
+            && matches!(self.peek(1).kind, TokenKind::Punctuation(PunctuationKind::RBracket))
+        {
+            self.advance();
+            let end = self.current().span.end;
+            self.advance();
+            let start = ty.span().start;
+            ty = TypeExpr::Array { element: Box::new(ty), span: Span { start, end } };
+        }
+        Ok(ty)
+    }
+
+    fn parse_primary_type(&mut self) -> Result<TypeExpr, ()> {
+        let span = self.current().span;
+        match &self.current().kind {
+            TokenKind::Identifier => self.parse_named_type(),
+            TokenKind::Keyword(KeywordKind::Void) => {
+                self.advance();
+                let name = Identifier { name: self.source.slice(span).to_string(), span };
+                Ok(TypeExpr::Named { name, args: Vec::new(), span })
+            }
+            TokenKind::Number
+            | TokenKind::StringLiteral
+            | TokenKind::Keyword(KeywordKind::Pravda | KeywordKind::Lozh | KeywordKind::Nol | KeywordKind::Undefined) => {
+                match self.parse_primary()? {
+                    Expr::Literal(value) => Ok(TypeExpr::Literal { value, span }),
+                    _ => {
+                        self.push_error(span, "Ожидался тип");
+                        Err(())
+                    }
+                }
+            }
+            TokenKind::Punctuation(PunctuationKind::LBrace) => self.parse_object_type(),
+            TokenKind::Punctuation(PunctuationKind::LParen) if self.paren_group_is_arrow_head() => {
+                self.parse_function_type()
+            }
+            TokenKind::Punctuation(PunctuationKind::LParen) => {
+                let open = span;
+                self.advance();
+                let ty = self.parse_type()?;
+                self.expect_closing(PunctuationKind::RParen, open, "Ожидалась ')' после типа")?;
+                Ok(ty)
+            }
+            _ => {
+                self.push_error(span, "Ожидался тип");
+                Err(())
+            }
+        }
+    }
+
+    fn parse_named_type(&mut self) -> Result<TypeExpr, ()> {
+        let name = self.parse_identifier()?;
+        let start = name.span.start;
+        let mut end = name.span.end;
+        let mut args = Vec::new();
+        if matches!(self.current().kind, TokenKind::Operator(OperatorKind::Less)) {
+            self.advance();
+            loop {
+                args.push(self.parse_type()?);
+                if !self.pending_gt && matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+            end = self.expect_type_args_end()?;
+        }
+        Ok(TypeExpr::Named { name, args, span: Span { start, end } })
+    }
+
+    /// Closes `<...>`. A `>>` that closes two argument lists at once is consumed by the inner one
+    /// and remembered for the outer one.
+    fn expect_type_args_end(&mut self) -> Result<usize, ()> {
+        if self.pending_gt {
+            self.pending_gt = false;
+            return Ok(self.previous().map_or(0, |t| t.span.end));
+        }
+        let span = self.current().span;
+        match self.current().kind {
+            TokenKind::Operator(OperatorKind::Greater) => {
+                self.advance();
+                Ok(span.end)
+            }
+            TokenKind::Operator(OperatorKind::RightShift) => {
+                self.advance();
+                self.pending_gt = true;
+                Ok(span.end - 1)
+            }
+            _ => {
+                self.push_error(span, "Ожидалась '>' после аргументов типа");
+                Err(())
+            }
+        }
+    }
+
+    fn parse_object_type(&mut self) -> Result<TypeExpr, ()> {
+        let start = self.current().span.start;
+        let open = self.expect_punct(PunctuationKind::LBrace, "Ожидалась '{'")?;
+        let fields = self.parse_type_fields()?;
+        let end = self.expect_closing(PunctuationKind::RBrace, open, "Ожидалась '}' после полей типа")?.end;
+        Ok(TypeExpr::Object { fields, span: Span { start, end } })
+    }
+
+    /// Fields of an object type, separated by `,` or `;`: `имя: тип`, `имя?: тип` or a method
+    /// signature `имя(а: тип): тип`.
+    pub(super) fn parse_type_fields(&mut self) -> Result<Vec<TypeField>, ()> {
+        let mut fields: Vec<TypeField> = Vec::new();
+        while !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RBrace) | TokenKind::Eof) {
+            let name = self.parse_identifier()?;
+            let optional = matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Question));
+            if optional {
+                self.advance();
+            }
+            let ty = if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::LParen)) {
+                self.parse_method_type()?
+            } else {
+                self.expect_punct(PunctuationKind::Colon, "Ожидалось ':' после имени поля")?;
+                self.parse_type()?
+            };
+            if let Some(prev) = fields.iter().find(|f| f.name.name == name.name) {
+                let message = format!("Поле '{}' уже объявлено в типе", name.name);
+                let diagnostic = Diagnostic::error(message, name.span)
+                    .with_code(codes::SYNTAX_ERROR)
+                    .with_secondary(prev.name.span, "впервые объявлено здесь");
+                self.push_diagnostic(diagnostic);
+            }
+            let span = Span { start: name.span.start, end: ty.span().end };
+            fields.push(TypeField { name, ty, optional, span });
+            if matches!(
+                self.current().kind,
+                TokenKind::Punctuation(PunctuationKind::Comma | PunctuationKind::Semicolon)
+            ) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        Ok(fields)
+    }
+
+    /// `(а: тип, б?: тип) => тип`.
+    fn parse_function_type(&mut self) -> Result<TypeExpr, ()> {
+        let start = self.current().span.start;
+        let params = self.parse_type_params()?;
+        self.expect_punct(PunctuationKind::Arrow, "Ожидалась '=>' в типе функции")?;
+        let ret = self.parse_type()?;
+        let end = ret.span().end;
+        Ok(TypeExpr::Function { params, ret: Box::new(ret), span: Span { start, end } })
+    }
+
+    /// `(а: тип): тип` in an object type.
+    fn parse_method_type(&mut self) -> Result<TypeExpr, ()> {
+        let start = self.current().span.start;
+        let params = self.parse_type_params()?;
+        self.expect_punct(PunctuationKind::Colon, "Ожидалось ':' перед типом результата")?;
+        let ret = self.parse_type()?;
+        let end = ret.span().end;
+        Ok(TypeExpr::Function { params, ret: Box::new(ret), span: Span { start, end } })
+    }
+
+    fn parse_type_params(&mut self) -> Result<Vec<TypeField>, ()> {
+        let open = self.expect_punct(PunctuationKind::LParen, "Ожидалась '('")?;
+        let mut params = Vec::new();
+        while !matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::RParen) | TokenKind::Eof) {
+            let name = self.parse_identifier()?;
+            let optional = matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Question));
+            if optional {
+                self.advance();
+            }
+            self.expect_punct(PunctuationKind::Colon, "Ожидалось ':' после имени параметра")?;
+            let ty = self.parse_type()?;
+            let span = Span { start: name.span.start, end: ty.span().end };
+            params.push(TypeField { name, ty, optional, span });
+            if matches!(self.current().kind, TokenKind::Punctuation(PunctuationKind::Comma)) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect_closing(PunctuationKind::RParen, open, "Ожидалась ')' после параметров")?;
+        Ok(params)
+    }
+
+    /// At statement start: `тип Имя = ...` or `хуёво Имя { ... }`. Both words stay ordinary
+    /// identifiers everywhere else.
+    pub(super) fn type_alias_ahead(&self) -> bool {
+        let word = self.source.slice(self.current().span);
+        matches!(self.peek(1).kind, TokenKind::Identifier)
+            && match word {
+                "тип" => matches!(self.peek(2).kind, TokenKind::Operator(OperatorKind::Assign)),
+                "хуёво" => matches!(self.peek(2).kind, TokenKind::Punctuation(PunctuationKind::LBrace)),
+                _ => false,
+            }
+    }
+
+    pub(super) fn parse_type_alias(&mut self) -> Result<Stmt, ()> {
+        let start = self.current().span.start;
+        let is_interface = self.source.slice(self.current().span) == "хуёво";
+        self.advance();
+        let name = self.parse_identifier()?;
+        if is_interface {
+            let ty = self.parse_object_type()?;
+            let end = ty.span().end;
+            return Ok(Stmt::TypeAlias { name, ty, is_interface, span: Span { start, end } });
+        }
+        self.expect_operator(OperatorKind::Assign, "Ожидался '=' после имени типа")?;
+        let ty = self.parse_top_type()?;
+        let end = self.expect_semicolon("Ожидалась ';' после объявления типа")?;
+        Ok(Stmt::TypeAlias { name, ty, is_interface, span: Span { start, end } })
+    }
+}
@@ -0,0 +1,843 @@
+#[derive(Clone, Copy)]
+pub struct Member {
+    pub ru: &'static str,
+    pub js: &'static str,
+    pub desc: &'static str,
+    pub is_property: bool,
+    /// Signature in annotation syntax, e.g. `(х: число) => число`; empty when not typed yet.
+    pub sig: &'static str,
+}
+
+const fn meth(ru: &'static str, js: &'static str, desc: &'static str) -> Member {
+    Member { ru, js, desc, is_property: false, sig: "" }
+}
+
+const fn typed(ru: &'static str, js: &'static str, desc: &'static str, sig: &'static str) -> Member {
+    Member { ru, js, desc, is_property: false, sig }
+}
+
+const fn prop(ru: &'static str, js: &'static str, desc: &'static str) -> Member {
+    Member { ru, js, desc, is_property: true, sig: "" }
+}
+
+/// What a catalog entry names, for editors to pick an icon.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A namespace object such as `Матан`.
+    Module,
+    Class,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Global,
+    Receiver,
+}
+
+pub struct BuiltinType {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub js: &'static str,
+    pub kind: Kind,
+    pub surface: Surface,
+    pub desc: &'static str,
+    pub members: &'static [Member],
+}
+
+impl BuiltinType {
+    #[must_use]
+    pub fn matches(&self, word: &str) -> bool {
+        self.name == word || self.aliases.contains(&word)
+    }
+}
+
+pub const TYPES: &[BuiltinType] = &[
+    BuiltinType {
+        name: "Матан",
+        aliases: &["Math"],
+        js: "Math",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "математические константы и функции",
+        members: &[
+            prop("ПИ", "PI", "число π"),
+            prop("Е", "E", "число Эйлера e"),
+            typed("пол", "floor", "округление вниз", "(х: число) => число"),
+            typed("потолок", "ceil", "округление вверх", "(х: число) => число"),
+            typed("округлить", "round", "округление к ближайшему", "(х: число) => число"),
+            typed("модуль", "abs", "модуль числа", "(х: число) => число"),
+            meth("мин", "min", "минимум из аргументов"),
+            meth("макс", "max", "максимум из аргументов"),
+            typed("степень", "pow", "возведение в степень", "(основание: число, показатель: число) => число"),
+            typed("корень", "sqrt", "квадратный корень", "(х: число) => число"),
+            typed("рандом", "random", "случайное число [0, 1)", "() => число"),
+            typed("знак", "sign", "знак числа", "(х: число) => число"),
+            typed("обрезать", "trunc", "отбросить дробную часть", "(х: число) => число"),
+            typed("лог", "log", "натуральный логарифм", "(х: число) => число"),
+            typed("синус", "sin", "синус", "(х: число) => число"),
+            typed("косинус", "cos", "косинус", "(х: число) => число"),
+            typed("тангенс", "tan", "тангенс", "(х: число) => число"),
+        ],
+    },
+    BuiltinType {
+        name: "Кент",
+        aliases: &["Object"],
+        js: "Object",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "статические методы для работы с объектами",
+        members: &[
+            typed("ключи", "keys", "массив ключей объекта", "(объект: объект) => строка[]"),
+            meth("значения", "values", "массив значений объекта"),
+            meth("записи", "entries", "массив пар [ключ, значение]"),
+            meth("назначить", "assign", "скопировать свойства в цель"),
+            typed("имеетСвоё", "hasOwn", "есть ли собственное свойство", "(объект: объект, ключ: строка) => булево"),
+            meth("изЗаписей", "fromEntries", "объект из пар [ключ, значение]"),
+            meth("группировать", "groupBy", "сгруппировать по ключу"),
+            meth("создать", "create", "объект с заданным прототипом"),
+            meth("прототип", "getPrototypeOf", "прототип объекта"),
+            meth("назначитьПрототип", "setPrototypeOf", "задать прототип"),
+            meth("заморозить", "freeze", "запретить изменения объекта"),
+            typed("заморожен", "isFrozen", "заморожен ли объект", "(объект: неизвестно) => булево"),
+        ],
+    },
+    BuiltinType {
+        name: "Жсон",
+        aliases: &["JSON"],
+        js: "JSON",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "сериализация и разбор JSON",
+        members: &[
+            typed("разобрать", "parse", "разобрать строку JSON в значение", "(текст: строка) => неизвестно"),
+            typed(
+                "вСтроку",
+                "stringify",
+                "сериализовать значение в JSON",
+                "(значение: неизвестно, заменитель?: неизвестно, отступ?: число | строка) => строка",
+            ),
+        ],
+    },
+    BuiltinType {
+        name: "Помойка",
+        aliases: &["Array"],
+        js: "Array",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "статические методы конструктора массива",
+        members: &[
+            typed("являетсяПомойкой", "isArray", "является ли значение массивом", "(значение: неизвестно) => булево"),
+            meth("извне", "from", "массив из итерируемого/массивоподобного"),
+            meth("нового", "of", "массив из переданных аргументов"),
+        ],
+    },
+    BuiltinType {
+        name: "Хуйня",
+        aliases: &["Number"],
+        js: "Number",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "статические методы конструктора числа",
+        members: &[
+            typed("конечна", "isFinite", "конечное ли число", "(значение: неизвестно) => булево"),
+            typed("целая", "isInteger", "целое ли число", "(значение: неизвестно) => булево"),
+            typed("нихуя", "isNaN", "является ли NaN", "(значение: неизвестно) => булево"),
+            typed(
+                "разобратьЦелое",
+                "parseInt",
+                "разобрать целое из строки",
+                "(текст: строка, основание?: число) => число",
+            ),
+            typed("разобратьЧисло", "parseFloat", "разобрать число из строки", "(текст: строка) => число"),
+        ],
+    },
+    BuiltinType {
+        name: "Отражение",
+        aliases: &["Reflect"],
+        js: "Reflect",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "низкоуровневые операции над объектами",
+        members: &[
+            meth("получить", "get", "прочитать свойство"),
+            meth("есть", "has", "есть ли свойство"),
+            meth("прототипОт", "getPrototypeOf", "прототип объекта"),
+            meth("собственныеКлючи", "ownKeys", "собственные ключи объекта"),
+            meth("применить", "apply", "вызвать функцию с массивом аргументов"),
+            meth("построить", "construct", "создать экземпляр конструктором"),
+        ],
+    },
+    BuiltinType {
+        name: "Итератор",
+        aliases: &["Iterator"],
+        js: "Iterator",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "хелперы итераторов",
+        members: &[
+            meth("от", "from", "обернуть итерируемое в итератор-хелпер"),
+            meth("склеить", "concat", "склеить несколько итераторов"),
+        ],
+    },
+    BuiltinType {
+        name: "Карта",
+        aliases: &["Map"],
+        js: "Map",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "коллекция пар ключ-значение (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "Набор",
+        aliases: &["Set"],
+        js: "Set",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "коллекция уникальных значений (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "СлабаяКарта",
+        aliases: &["WeakMap"],
+        js: "WeakMap",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "карта со слабыми ссылками на ключи (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "СлабыйНабор",
+        aliases: &["WeakSet"],
+        js: "WeakSet",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "набор со слабыми ссылками (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "СлабаяСсылка",
+        aliases: &["WeakRef"],
+        js: "WeakRef",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "слабая ссылка на объект (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "РеестрФинализации",
+        aliases: &["FinalizationRegistry"],
+        js: "FinalizationRegistry",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "реестр колбэков финализации (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "Симбол",
+        aliases: &["Symbol"],
+        js: "Symbol",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "уникальные символы и общеизвестные символы",
+        members: &[
+            prop("итератор", "iterator", "общеизвестный символ Symbol.iterator"),
+            prop("асинхИтератор", "asyncIterator", "Symbol.asyncIterator"),
+            prop("вПримитив", "toPrimitive", "Symbol.toPrimitive"),
+            prop("строковыйТег", "toStringTag", "Symbol.toStringTag"),
+            meth("для", "for", "символ из глобального реестра по ключу"),
+            meth("ключДля", "keyFor", "ключ символа из реестра"),
+        ],
+    },
+    BuiltinType {
+        name: "Дата",
+        aliases: &["Date"],
+        js: "Date",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "дата и время (new)",
+        members: &[meth("сейчас", "now", "текущее время в миллисекундах")],
+    },
+    BuiltinType {
+        name: "СловоПацана",
+        aliases: &["Promise"],
+        js: "Promise",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "промис — асинхронное значение (new)",
+        members: &[
+            meth("решить", "resolve", "промис, разрешённый значением"),
+            meth("отвергнуть", "reject", "промис, отклонённый причиной"),
+            meth("всех", "all", "ждать все промисы"),
+            meth("всехУстаканить", "allSettled", "ждать завершения всех"),
+            meth("любой", "any", "первый успешный промис"),
+            meth("гонка", "race", "первый завершившийся промис"),
+            meth("отПодождать", "whenAborted", "промис, разрешающийся при срабатывании сигнала отмены"),
+            meth("сРешалками", "withResolvers", "промис с внешними resolve/reject"),
+            meth("попробовать", "try", "обернуть вызов в промис"),
+        ],
+    },
+    BuiltinType {
+        name: "КонтроллёрОтмены",
+        aliases: &["AbortController"],
+        js: "AbortController",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "контроллёр сигнала отмены (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "СигналОтмены",
+        aliases: &["AbortSignal"],
+        js: "AbortSignal",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "сигнал отмены операций",
+        members: &[
+            meth("любой", "any", "сигнал, срабатывающий по любому из переданных"),
+            meth("отВремени", "timeout", "сигнал, срабатывающий через N мс"),
+        ],
+    },
+    BuiltinType {
+        name: "Воркер",
+        aliases: &["Worker"],
+        js: "Worker",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "файл, запущенный в отдельном потоке (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "Посредник",
+        aliases: &["Proxy"],
+        js: "Proxy",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "перехват операций над объектом (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "ОбластьБайтов",
+        aliases: &["ArrayBuffer"],
+        js: "ArrayBuffer",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "буфер сырых байтов (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "ОбщийБуфер",
+        aliases: &["SharedArrayBuffer"],
+        js: "SharedArrayBuffer",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "буфер байтов, общий с воркерами (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "Атомики",
+        aliases: &["Atomics"],
+        js: "Atomics",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "атомарные операции над общей памятью",
+        members: &[
+            meth("добавить", "add", "прибавить к элементу, вернуть прежнее значение"),
+            meth("сравнитьИОбменять", "compareExchange", "заменить элемент, если он равен ожидаемому"),
+            meth("загрузить", "load", "прочитать элемент"),
+            meth("сохранить", "store", "записать элемент"),
+            meth("ждать", "wait", "уснуть, пока элемент равен значению"),
+            meth("разбудить", "notify", "разбудить ждущих на элементе"),
+        ],
+    },
+    BuiltinType {
+        name: "ОбзорБайтов",
+        aliases: &["DataView"],
+        js: "DataView",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "типизированный доступ к буферу байтов (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "КодировщикТекста",
+        aliases: &["TextEncoder"],
+        js: "TextEncoder",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "строка в байты: utf-8, utf-16le, windows-1251 (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "ДекодировщикТекста",
+        aliases: &["TextDecoder"],
+        js: "TextDecoder",
+        kind: Kind::Class,
+        surface: Surface::Global,
+        desc: "байты в строку: utf-8, utf-16le, windows-1251 (new)",
+        members: &[],
+    },
+    BuiltinType {
+        name: "Интл",
+        aliases: &["Intl"],
+        js: "Intl",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "форматирование чисел, дат и строк по локали (ru, en)",
+        members: &[
+            meth("ФорматЧисла", "NumberFormat", "формат чисел: группы разрядов, валюта, проценты"),
+            meth("ФорматДаты", "DateTimeFormat", "формат дат и времени"),
+            meth("Сравнитель", "Collator", "сравнение строк по алфавиту локали"),
+            meth("ПравилаМножественного", "PluralRules", "категория множественного числа"),
+            meth("ОтносительноеВремя", "RelativeTimeFormat", "«через 3 дня», «вчера»"),
+        ],
+    },
+    BuiltinType {
+        name: "Время",
+        aliases: &["Temporal"],
+        js: "Temporal",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "даты, время, часовые зоны и длительности (Temporal)",
+        members: &[
+            meth("Мгновение", "Instant", "точка на шкале времени, наносекунды от эпохи"),
+            meth("ПростаяДата", "PlainDate", "календарная дата без времени и зоны"),
+            meth("ПростоеВремя", "PlainTime", "время суток без даты и зоны"),
+            meth("ЗонированноеВремя", "ZonedDateTime", "дата и время в часовой зоне IANA"),
+            meth("Длительность", "Duration", "длительность ISO 8601: P1DT2H"),
+            meth("сейчас", "Now", "текущие мгновение, дата, время и зона"),
+        ],
+    },
+    BuiltinType {
+        name: "ФС",
+        aliases: &["fs"],
+        js: "fs",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "файловая система",
+        members: &[
+            meth("прочитать", "readFileSync", "прочитать файл"),
+            meth("записать", "writeFileSync", "записать файл"),
+            meth("дописать", "appendFileSync", "дописать в файл"),
+            meth("удалить", "unlinkSync", "удалить файл"),
+            meth("существует", "existsSync", "существует ли путь"),
+            meth("этоПапка", "isDirectory", "путь — это папка"),
+            meth("этоФайл", "isFile", "путь — это файл"),
+            meth("список", "readdirSync", "список содержимого папки"),
+            meth("создатьПапку", "mkdirSync", "создать папку"),
+            meth("удалитьПапку", "rmdirSync", "удалить папку"),
+        ],
+    },
+    BuiltinType {
+        name: "Процесс",
+        aliases: &["process"],
+        js: "process",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "текущий процесс",
+        members: &[
+            meth("выход", "exit", "завершить процесс с кодом"),
+            meth("сменитьПапку", "chdir", "сменить рабочую папку"),
+            meth("снимокКучи", "writeHeapSnapshot", "записать снимок кучи в формате V8"),
+            prop("перем", "env", "переменные окружения"),
+        ],
+    },
+    BuiltinType {
+        name: "Сеть",
+        aliases: &["net"],
+        js: "net",
+        kind: Kind::Module,
+        surface: Surface::Global,
+        desc: "сетевые запросы",
+        members: &[meth("достать", "fetch", "выполнить HTTP-запрос")],
+    },
+    BuiltinType {
+        name: "Строка",
+        aliases: &[],
+        js: "String",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы строки",
+        members: &[
+            prop("длина", "length", "длина строки"),
+            typed("символВ", "charAt", "символ по индексу", "(индекс: число) => строка"),
+            typed("кодСимволаВ", "charCodeAt", "код символа по индексу", "(индекс: число) => число"),
+            typed("найтиПодстроку", "indexOf", "индекс первого вхождения", "(подстрока: строка, с?: число) => число"),
+            typed(
+                "найтиПодстрокуСконца",
+                "lastIndexOf",
+                "индекс последнего вхождения",
+                "(подстрока: строка, с?: число) => число",
+            ),
+            typed("содержит", "includes", "содержит ли подстроку", "(подстрока: строка, с?: число) => булево"),
+            typed("отрезать", "slice", "подстрока по индексам", "(начало?: число, конец?: число) => строка"),
+            typed("подстрока", "substring", "подстрока между индексами", "(начало: число, конец?: число) => строка"),
+            typed("вВерхнийРегистр", "toUpperCase", "в верхний регистр", "() => строка"),
+            typed("вНижнийРегистр", "toLowerCase", "в нижний регистр", "() => строка"),
+            typed("обрезать", "trim", "убрать пробелы по краям", "() => строка"),
+            typed("обрезатьСлева", "trimStart", "убрать пробелы слева", "() => строка"),
+            typed("обрезатьСправа", "trimEnd", "убрать пробелы справа", "() => строка"),
+            typed(
+                "разбить",
+                "split",
+                "разбить строку на массив",
+                "(разделитель?: строка | регэксп, предел?: число) => строка[]",
+            ),
+            typed(
+                "заменить",
+                "replace",
+                "заменить первое совпадение",
+                "(что: строка | регэксп, на: строка | функция) => строка",
+            ),
+            typed(
+                "заменитьВсе",
+                "replaceAll",
+                "заменить все совпадения",
+                "(что: строка | регэксп, на: строка | функция) => строка",
+            ),
+            meth("совпадает", "match", "совпадение с регуляркой"),
+            meth("найтиВсе", "matchAll", "все совпадения с регуляркой"),
+            typed("найтиИндекс", "search", "индекс совпадения с регуляркой", "(шаблон: строка | регэксп) => число"),
+            typed(
+                "начинаетсяС",
+                "startsWith",
+                "начинается ли с подстроки",
+                "(подстрока: строка, позиция?: число) => булево",
+            ),
+            typed(
+                "заканчиваетсяНа",
+                "endsWith",
+                "заканчивается ли подстрокой",
+                "(подстрока: строка, длина?: число) => булево",
+            ),
+            typed("повторить", "repeat", "повторить строку N раз", "(раз: число) => строка"),
+            typed(
+                "дополнитьСлева",
+                "padStart",
+                "дополнить слева до длины",
+                "(длина: число, заполнитель?: строка) => строка",
+            ),
+            typed(
+                "дополнитьСправа",
+                "padEnd",
+                "дополнить справа до длины",
+                "(длина: число, заполнитель?: строка) => строка",
+            ),
+            typed("поИндексу", "at", "символ по индексу (можно отрицательный)", "(индекс: число) => строка | неибу"),
+            meth("присоединить", "concat", "склеить со строками"),
+            typed("кодТочки", "codePointAt", "кодовая точка по индексу", "(индекс: число) => число | неибу"),
+            meth("нормализовать", "normalize", "юникод-нормализация"),
+        ],
+    },
+    BuiltinType {
+        name: "Массив",
+        aliases: &[],
+        js: "Array",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы массива",
+        members: &[
+            prop("длина", "length", "длина массива"),
+            meth("добавить", "push", "добавить в конец"),
+            meth("вытолкнуть", "pop", "снять с конца"),
+            meth("снять", "shift", "снять с начала"),
+            meth("подсунуть", "unshift", "добавить в начало"),
+            typed("отрезать", "slice", "копия среза массива", "(начало?: число, конец?: число) => неизвестно"),
+            meth("найтиИндекс", "indexOf", "индекс первого вхождения"),
+            meth("найтиПоследнийПо", "lastIndexOf", "индекс последнего вхождения"),
+            meth("включает", "includes", "содержит ли элемент"),
+            typed("склеить", "join", "объединить в строку", "(разделитель?: строка) => строка"),
+            meth("перевернуть", "reverse", "перевернуть на месте"),
+            meth("склеитьМассивы", "concat", "склеить массивы"),
+            meth("сортировать", "sort", "отсортировать на месте"),
+            typed("преобразовать", "map", "преобразовать каждый элемент", "(обработчик: функция) => неизвестно"),
+            typed("отфильтровать", "filter", "оставить подходящие", "(условие: функция) => неизвестно"),
+            meth("свернуть", "reduce", "свернуть к одному значению"),
+            meth("свернутьСправа", "reduceRight", "свернуть справа налево"),
+            typed("каждый", "forEach", "выполнить для каждого", "(обработчик: функция) => куку"),
+            typed("найти", "find", "первый подходящий элемент", "(условие: функция) => неизвестно"),
+            typed("найтиИндексПо", "findIndex", "индекс первого подходящего", "(условие: функция) => число"),
+            typed("некоторые", "some", "есть ли хоть один подходящий", "(условие: функция) => булево"),
+            typed("все", "every", "все ли подходят", "(условие: функция) => булево"),
+            typed("поИндексу", "at", "элемент по индексу", "(индекс: число) => неизвестно"),
+            typed("плоский", "flat", "развернуть вложенные массивы", "(глубина?: число) => неизвестно"),
+            meth("плоскоПреобразовать", "flatMap", "map + flat"),
+            meth("найтиПоследний", "findLast", "последний подходящий"),
+            meth("найтиПоследнийИндекс", "findLastIndex", "индекс последнего подходящего"),
+            meth("перевёрнутый", "toReversed", "перевёрнутая копия"),
+            meth("отсортированный", "toSorted", "отсортированная копия"),
+            meth("вырезать", "splice", "удалить/вставить на месте"),
+            meth("вырезанный", "toSpliced", "splice без мутации"),
+            typed(
+                "сЗаменой",
+                "with",
+                "копия с заменой по индексу",
+                "(индекс: число, значение: неизвестно) => неизвестно",
+            ),
+            meth("заполнить", "fill", "заполнить значением на месте"),
+            meth("копироватьВнутри", "copyWithin", "скопировать участок внутри массива"),
+            meth("записи", "entries", "итератор пар [индекс, значение]"),
+            meth("ключи", "keys", "итератор индексов"),
+            meth("значения", "values", "итератор значений"),
+            meth("вЛокальнуюСтроку", "toLocaleString", "элементы по правилам локали через запятую"),
+        ],
+    },
+    BuiltinType {
+        name: "Число",
+        aliases: &[],
+        js: "Number",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы числа",
+        members: &[
+            typed("вСтроку", "toString", "число в строку", "(основание?: число) => строка"),
+            typed("фиксированный", "toFixed", "строка с N знаками после точки", "(знаков?: число) => строка"),
+            typed("вЛокальнуюСтроку", "toLocaleString", "число по правилам локали", "(локаль?: строка) => строка"),
+        ],
+    },
+    BuiltinType {
+        name: "Карта (экземпляр)",
+        aliases: &[],
+        js: "Map",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы экземпляра карты",
+        members: &[
+            prop("размер", "size", "число записей"),
+            meth("поставить", "set", "записать пару ключ-значение"),
+            meth("взять", "get", "значение по ключу"),
+            meth("имеет", "has", "есть ли ключ"),
+            meth("удалить", "delete", "удалить ключ"),
+            meth("очистить", "clear", "очистить карту"),
+            meth("ключи", "keys", "итератор ключей"),
+            meth("значения", "values", "итератор значений"),
+            meth("записи", "entries", "итератор записей"),
+            meth("взятьИлиВставить", "getOrInsert", "взять или вставить значение"),
+            meth("взятьИлиВычислить", "getOrInsertComputed", "взять или вычислить значение"),
+            meth("каждый", "forEach", "выполнить для каждой записи"),
+        ],
+    },
+    BuiltinType {
+        name: "Набор (экземпляр)",
+        aliases: &[],
+        js: "Set",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы экземпляра набора",
+        members: &[
+            prop("размер", "size", "число элементов"),
+            meth("добавить", "add", "добавить элемент"),
+            meth("имеет", "has", "есть ли элемент"),
+            meth("удалить", "delete", "удалить элемент"),
+            meth("очистить", "clear", "очистить набор"),
+            meth("значения", "values", "итератор значений"),
+            meth("каждый", "forEach", "выполнить для каждого"),
+            meth("объединение", "union", "объединение наборов"),
+            meth("пересечение", "intersection", "пересечение наборов"),
+            meth("разница", "difference", "разность наборов"),
+            meth("симметричнаяРазница", "symmetricDifference", "симметрическая разность"),
+            meth("подмножествоОт", "isSubsetOf", "является ли подмножеством"),
+            meth("надмножествоОт", "isSupersetOf", "является ли надмножеством"),
+            meth("непересекаетсяС", "isDisjointFrom", "не пересекается ли"),
+        ],
+    },
+    BuiltinType {
+        name: "Дата (экземпляр)",
+        aliases: &[],
+        js: "Date",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы экземпляра даты",
+        members: &[
+            meth("времяМс", "getTime", "время в миллисекундах"),
+            meth("вЧисло", "valueOf", "время числом"),
+            meth("год", "getFullYear", "год"),
+            meth("месяц", "getMonth", "месяц (0–11)"),
+            meth("день", "getDate", "день месяца"),
+            meth("деньНедели", "getDay", "день недели"),
+            meth("часы", "getHours", "часы"),
+            meth("минуты", "getMinutes", "минуты"),
+            meth("секунды", "getSeconds", "секунды"),
+            meth("миллисекунды", "getMilliseconds", "миллисекунды"),
+            meth("вИСО", "toISOString", "строка в формате ISO"),
+            meth("вСтроку", "toString", "строковое представление"),
+            meth("вЛокальнуюСтроку", "toLocaleString", "дата и время по правилам локали"),
+            meth("вЛокальнуюДату", "toLocaleDateString", "дата по правилам локали"),
+            meth("вЛокальноеВремя", "toLocaleTimeString", "время по правилам локали"),
+        ],
+    },
+    BuiltinType {
+        name: "Регулярка",
+        aliases: &[],
+        js: "RegExp",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы регулярного выражения",
+        members: &[
+            typed("проверить", "test", "проверить совпадение", "(строка: строка) => булево"),
+            meth("найти", "exec", "найти совпадение с группами"),
+            meth("вСтроку", "toString", "строковое представление"),
+            prop("источник", "source", "исходный шаблон"),
+            prop("флаги", "flags", "строка флагов"),
+            prop("последнийИндекс", "lastIndex", "индекс для следующего поиска"),
+        ],
+    },
+    BuiltinType {
+        name: "Итератор (экземпляр)",
+        aliases: &[],
+        js: "Iterator",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы итератора",
+        members: &[
+            meth("следующий", "next", "следующий элемент"),
+            meth("вернуть", "return", "завершить итератор"),
+            meth("кинуть", "throw", "бросить в итератор"),
+            meth("преобразовать", "map", "ленивое преобразование"),
+            meth("отфильтровать", "filter", "ленивая фильтрация"),
+            meth("взять", "take", "взять первые N"),
+            meth("пропустить", "drop", "пропустить первые N"),
+            meth("вМассив", "toArray", "собрать в массив"),
+            meth("каждый", "forEach", "выполнить для каждого"),
+            meth("свернуть", "reduce", "свернуть к значению"),
+            meth("некоторые", "some", "есть ли подходящий"),
+            meth("все", "every", "все ли подходят"),
+            meth("найти", "find", "первый подходящий"),
+        ],
+    },
+    BuiltinType {
+        name: "СловоПацана (экземпляр)",
+        aliases: &[],
+        js: "Promise",
+        kind: Kind::Class,
+        surface: Surface::Receiver,
+        desc: "методы экземпляра промиса",
+        members: &[
+            meth("потом", "then", "обработать результат"),
+            meth("ловить", "catch", "обработать ошибку"),
+            meth("наконец", "finally", "выполнить в любом случае"),
+        ],
+    },
+];
+
+#[must_use]
+pub fn type_doc(word: &str) -> Option<String> {
+    let ty = global(word)?;
+    Some(format!("**{}** — {}", ty.js, ty.desc))
+}
+
+#[must_use]
+pub fn member_doc(word: &str) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for ty in TYPES {
+        for m in ty.members {
+            if m.ru == word || m.js == word {
+                lines.push(format!("**{}.{}** — {} *(JS: {})*", ty.js, m.ru, m.desc, m.js));
+            }
+        }
+    }
+    if lines.is_empty() { None } else { Some(lines.join("\n\n")) }
+}
+
+/// Written signature of `member` in a receiver table such as `"Строка"`.
+#[must_use]
+pub fn signature(table: &str, member: &str) -> Option<&'static str> {
+    typed_member(self::table(table)?, member)
+}
+
+/// Written signature of `Пространство.член(...)`, e.g. `Матан.пол`.
+#[must_use]
+pub fn global_signature(namespace: &str, member: &str) -> Option<&'static str> {
+    typed_member(global(namespace)?, member)
+}
+
+fn typed_member(ty: &BuiltinType, member: &str) -> Option<&'static str> {
+    ty.members.iter().find(|m| (m.ru == member || m.js == member) && !m.sig.is_empty()).map(|m| m.sig)
+}
+
+#[must_use]
+pub fn is_known_global(word: &str) -> bool {
+    global(word).is_some()
+}
+
+/// The global entry `word` names, by its Russian name or an alias.
+#[must_use]
+pub fn global(word: &str) -> Option<&'static BuiltinType> {
+    TYPES.iter().find(|t| t.surface == Surface::Global && t.matches(word))
+}
+
+/// A receiver table such as `"Строка"` or `"Карта (экземпляр)"`.
+#[must_use]
+pub fn table(name: &str) -> Option<&'static BuiltinType> {
+    TYPES.iter().find(|t| t.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yps_interpreter::builtins::builtin_names;
+    use yps_interpreter::stdlib::build_globals;
+
+    #[test]
+    fn global_types_are_real_runtime_globals() {
+        let globals: std::collections::HashSet<String> = build_globals().into_iter().map(|(name, _)| name).collect();
+        let builtins: std::collections::HashSet<&str> = builtin_names().iter().copied().collect();
+        for ty in TYPES.iter().filter(|t| t.surface == Surface::Global) {
+            assert!(
+                globals.contains(ty.name) || builtins.contains(ty.name),
+                "тип '{}' каталога не зарегистрирован как глобал интерпретатора",
+                ty.name
+            );
+        }
+    }
+
+    #[test]
+    fn type_doc_resolves_namespaces() {
+        assert!(type_doc("Матан").unwrap().contains("Math"));
+        assert!(type_doc("Жсон").unwrap().contains("JSON"));
+        assert!(type_doc("Карта").unwrap().contains("Map"));
+        assert!(type_doc("неизвестно").is_none());
+    }
+
+    #[test]
+    fn member_doc_resolves_and_lists_owners() {
+        assert!(member_doc("пол").unwrap().contains("Math.пол"));
+        let foreach = member_doc("каждый").unwrap();
+        assert!(foreach.contains("forEach"));
+    }
+
+    #[test]
+    fn receiver_tables_cover_runtime_methods() {
+        for (table, exists) in [
+            ("Строка", yps_interpreter::stdlib::string::method_exists as fn(&str) -> bool),
+            ("Массив", yps_interpreter::stdlib::array::method_exists),
+        ] {
+            let ty = TYPES.iter().find(|t| t.name == table).unwrap();
+            for m in ty.members.iter().filter(|m| !m.is_property) {
+                assert!(exists(m.ru) && exists(m.js), "{table}.{} не существует в рантайме", m.ru);
+            }
+        }
+        assert!(table("Массив").unwrap().members.iter().any(|m| m.ru == "заполнить"));
+    }
+
+    #[test]
+    fn signatures_parse_as_function_types() {
+        for ty in TYPES {
+            for m in ty.members.iter().filter(|m| !m.sig.is_empty()) {
+                let sf = yps_lexer::SourceFile::new("sig".to_string(), m.sig.to_string());
+                let (tokens, _) = yps_lexer::Lexer::new(&sf).tokenize();
+                let (parsed, _) = yps_parser::Parser::new(&tokens, &sf).parse_type_source();
+                assert!(
+                    matches!(parsed, Some(yps_parser::TypeExpr::Function { .. })),
+                    "{}.{}: сигнатура '{}' не разбирается",
+                    ty.name,
+                    m.ru,
+                    m.sig
+                );
+            }
+        }
+        assert_eq!(global_signature("Math", "floor"), Some("(х: число) => число"));
+        assert_eq!(signature("Строка", "повторить"), Some("(раз: число) => строка"));
+        assert!(signature("Строка", "нормализовать").is_none());
+    }
+}
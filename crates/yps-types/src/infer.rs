@@ -0,0 +1,2372 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use yps_lexer::{Lexer, SourceFile, Span};
+use yps_parser::Parser;
+use yps_parser::ast::{
+    BinaryOp, ClassMember, ExportKind, Expr, ImportSpec, Literal, MatchArm, MatchPattern, ObjectEntry, Param, Pattern,
+    Program, PropKey, Stmt, TemplatePart, TypeExpr, TypeField, UnaryOp,
+};
+
+use crate::catalog;
+
+/// Loops are re-walked once so that types assigned in a later iteration reach earlier reads.
+const LOOP_PASSES: usize = 2;
+
+/// Guards superclass walks against cyclic `батя` chains.
+const MAX_CLASS_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Type {
+    #[default]
+    Unknown,
+    Number,
+    String,
+    Boolean,
+    BigInt,
+    Null,
+    Undefined,
+    RegExp,
+    Array(Box<Type>),
+    Object(Vec<(String, Type)>),
+    Function(Box<FunctionSig>),
+    Class(String),
+    Instance(String),
+    Promise(Box<Type>),
+    Union(Vec<Type>),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FunctionSig {
+    pub params: Vec<(String, Type)>,
+    pub ret: Type,
+    /// Parameters with a written type; only these are checked at call sites.
+    pub declared: Vec<bool>,
+    /// How many arguments a call has to pass.
+    pub required: usize,
+    /// The last parameter collects the remaining arguments.
+    pub rest: bool,
+}
+
+impl Type {
+    #[must_use]
+    pub fn is_known(&self) -> bool {
+        *self != Type::Unknown
+    }
+
+    /// The alternatives of a union, or the type itself.
+    #[must_use]
+    pub fn alternatives(&self) -> Vec<&Type> {
+        match self {
+            Type::Union(types) => types.iter().collect(),
+            other => vec![other],
+        }
+    }
+
+    #[must_use]
+    pub fn union(a: Type, b: Type) -> Type {
+        if a == b {
+            return a;
+        }
+        if a == Type::Unknown || b == Type::Unknown {
+            return Type::Unknown;
+        }
+        let mut out: Vec<Type> = Vec::new();
+        for t in a.into_members().into_iter().chain(b.into_members()) {
+            if let Type::Array(inner) = &t
+                && let Some(Type::Array(existing)) = out.iter_mut().find(|o| matches!(o, Type::Array(_)))
+            {
+                **existing = Type::union(std::mem::take(existing), (**inner).clone());
+                continue;
+            }
+            if !out.contains(&t) {
+                out.push(t);
+            }
+        }
+        if out.len() == 1 { out.pop().unwrap_or_default() } else { Type::Union(out) }
+    }
+
+    /// Builtin member table in `types` that describes values of this type.
+    #[must_use]
+    pub fn receiver_table(&self) -> Option<&'static str> {
+        match self {
+            Type::String => Some("Строка"),
+            Type::Array(_) => Some("Массив"),
+            Type::Number => Some("Число"),
+            Type::RegExp => Some("Регулярка"),
+            Type::Promise(_) => Some("СловоПацана (экземпляр)"),
+            Type::Instance(name) => match name.as_str() {
+                "Карта" => Some("Карта (экземпляр)"),
+                "Набор" => Some("Набор (экземпляр)"),
+                "Дата" => Some("Дата (экземпляр)"),
+                "Итератор" => Some("Итератор (экземпляр)"),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn into_members(self) -> Vec<Type> {
+        match self {
+            Type::Union(types) => types,
+            other => vec![other],
+        }
+    }
+
+    fn from_members(members: Vec<Type>) -> Type {
+        members.into_iter().reduce(Type::union).unwrap_or_default()
+    }
+
+    /// What `чезажижан` reports for a value of this type.
+    fn typeof_tag(&self) -> Option<&'static str> {
+        match self {
+            Type::Number => Some("число"),
+            Type::String => Some("строка"),
+            Type::Boolean => Some("булево"),
+            Type::BigInt => Some("бигцелое"),
+            Type::Undefined => Some("неопределено"),
+            Type::Function(_) => Some("функция"),
+            Type::Null
+            | Type::RegExp
+            | Type::Array(_)
+            | Type::Object(_)
+            | Type::Class(_)
+            | Type::Instance(_)
+            | Type::Promise(_) => Some("объект"),
+            Type::Unknown | Type::Union(_) => None,
+        }
+    }
+
+    fn element(&self) -> Type {
+        match self {
+            Type::Array(inner) => (**inner).clone(),
+            Type::String => Type::String,
+            Type::Union(types) => Type::from_members(types.iter().map(Type::element).collect()),
+            _ => Type::Unknown,
+        }
+    }
+
+    fn awaited(self) -> Type {
+        match self {
+            Type::Promise(inner) => *inner,
+            other => other,
+        }
+    }
+
+    fn without_nullish(self) -> Type {
+        if !matches!(self, Type::Union(_)) {
+            return self;
+        }
+        let kept: Vec<Type> =
+            self.clone().into_members().into_iter().filter(|t| !matches!(t, Type::Null | Type::Undefined)).collect();
+        if kept.is_empty() { self } else { Type::from_members(kept) }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Unknown => write!(f, "неизвестно"),
+            Type::Number => write!(f, "число"),
+            Type::String => write!(f, "строка"),
+            Type::Boolean => write!(f, "булево"),
+            Type::BigInt => write!(f, "бигцелое"),
+            Type::Null => write!(f, "нулл"),
+            Type::Undefined => write!(f, "неопределено"),
+            Type::RegExp => write!(f, "регэксп"),
+            Type::Array(inner) if **inner == Type::Unknown => write!(f, "массив"),
+            Type::Array(inner) => write!(f, "массив<{inner}>"),
+            Type::Object(fields) if fields.is_empty() => write!(f, "объект"),
+            Type::Object(fields) => {
+                let fields: Vec<String> = fields.iter().map(|(name, ty)| format!("{name}: {ty}")).collect();
+                write!(f, "{{ {} }}", fields.join(", "))
+            }
+            Type::Function(sig) => write!(f, "{sig}"),
+            Type::Class(name) => write!(f, "класс {name}"),
+            Type::Instance(name) => write!(f, "{name}"),
+            Type::Promise(inner) if **inner == Type::Unknown => write!(f, "обещание"),
+            Type::Promise(inner) => write!(f, "обещание<{inner}>"),
+            Type::Union(types) => {
+                let parts: Vec<String> = types.iter().map(ToString::to_string).collect();
+                write!(f, "{}", parts.join(" | "))
+            }
+        }
+    }
+}
+
+impl FunctionSig {
+    fn placeholder(params: &[Param]) -> Self {
+        FunctionSig {
+            params: params.iter().map(|p| (p.name.name.clone(), Type::Unknown)).collect(),
+            ..FunctionSig::default()
+        }
+    }
+}
+
+impl fmt::Display for FunctionSig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(name, ty)| if ty.is_known() { format!("{name}: {ty}") } else { name.clone() })
+            .collect();
+        write!(f, "({}) => {}", params.join(", "), self.ret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberInfo {
+    pub name: String,
+    pub ty: Type,
+    pub is_method: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClassInfo {
+    pub super_class: Option<String>,
+    /// Members may come from somewhere we can't see: decorators, an unresolved superclass,
+    /// computed assignments.
+    pub open: bool,
+    pub instance: Vec<MemberInfo>,
+    pub statics: Vec<MemberInfo>,
+    /// Instance fields with a written type.
+    pub fields: HashMap<String, Type>,
+    pub constructor: Option<FunctionSig>,
+}
+
+impl ClassInfo {
+    fn set(&mut self, is_static: bool, name: &str, ty: Type, is_method: bool) {
+        let members = if is_static { &mut self.statics } else { &mut self.instance };
+        match members.iter_mut().find(|m| m.name == name) {
+            Some(member) => member.ty = ty,
+            None => members.push(MemberInfo { name: name.to_string(), ty, is_method }),
+        }
+    }
+
+    fn assign_field(&mut self, name: &str, ty: Type) {
+        match self.instance.iter_mut().find(|m| m.name == name) {
+            Some(member) if member.is_method => {}
+            Some(member) if member.ty == Type::Unknown => member.ty = ty,
+            Some(member) => member.ty = Type::union(std::mem::take(&mut member.ty), ty),
+            None => self.instance.push(MemberInfo { name: name.to_string(), ty, is_method: false }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeInfo {
+    occurrences: BTreeMap<usize, (Span, String, Type)>,
+    functions: HashMap<String, FunctionSig>,
+    classes: HashMap<String, ClassInfo>,
+    warnings: BTreeMap<usize, (Span, String)>,
+    errors: BTreeMap<usize, (Span, String)>,
+}
+
+impl TypeInfo {
+    /// Name and type of the identifier occurrence covering `byte`.
+    #[must_use]
+    pub fn type_at(&self, byte: usize) -> Option<(&str, &Type)> {
+        let (_, (span, name, ty)) = self.occurrences.range(..=byte).next_back()?;
+        (byte < span.end).then_some((name.as_str(), ty))
+    }
+
+    /// Type of the closest known occurrence of `name` before `byte`.
+    #[must_use]
+    pub fn type_before(&self, name: &str, byte: usize) -> Option<&Type> {
+        self.occurrences
+            .range(..byte)
+            .rev()
+            .find(|(_, (_, n, ty))| n == name && ty.is_known())
+            .map(|(_, (_, _, ty))| ty)
+    }
+
+    #[must_use]
+    pub fn function(&self, name: &str) -> Option<&FunctionSig> {
+        self.functions.get(name)
+    }
+
+    #[must_use]
+    pub fn class(&self, name: &str) -> Option<&ClassInfo> {
+        self.classes.get(name)
+    }
+
+    #[must_use]
+    pub fn warnings(&self) -> Vec<(Span, &str)> {
+        self.warnings.values().map(|(span, msg)| (*span, msg.as_str())).collect()
+    }
+
+    /// Values that contradict a written type annotation.
+    #[must_use]
+    pub fn errors(&self) -> Vec<(Span, &str)> {
+        self.errors.values().map(|(span, msg)| (*span, msg.as_str())).collect()
+    }
+
+    /// Whether a value of type `from` may go where `to` is expected. Anything unknown fits.
+    #[must_use]
+    pub fn assignable(&self, from: &Type, to: &Type) -> bool {
+        match (from, to) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Union(types), _) => types.iter().all(|t| self.assignable(t, to)),
+            (_, Type::Union(types)) => types.iter().any(|t| self.assignable(from, t)),
+            (Type::Array(a), Type::Array(b)) | (Type::Promise(a), Type::Promise(b)) => self.assignable(a, b),
+            (Type::Function(a), Type::Function(b)) => self.assignable(&a.ret, &b.ret),
+            (Type::Class(_), Type::Function(_)) => true,
+            (Type::Instance(a), Type::Instance(b)) => {
+                self.extends(a, b)
+                    || (self.classes.contains_key(a.as_str()) && !self.chain_is_closed(a))
+                    || (self.classes.contains_key(a.as_str())
+                        && self.classes.contains_key(b.as_str())
+                        && self
+                            .class_chain(b)
+                            .flat_map(|c| c.instance.iter())
+                            .all(|m| self.user_member(a, &m.name).is_some()))
+            }
+            (Type::Object(fields), Type::Instance(class)) => {
+                self.classes.contains_key(class.as_str())
+                    && self
+                        .class_chain(class)
+                        .flat_map(|c| c.instance.iter())
+                        .all(|m| fields.iter().any(|(name, _)| *name == m.name))
+            }
+            (_, Type::Object(expected)) => {
+                if matches!(
+                    from,
+                    Type::Number | Type::String | Type::Boolean | Type::BigInt | Type::Null | Type::Undefined
+                ) {
+                    return false;
+                }
+                if let Type::Instance(class) = from
+                    && !self.chain_is_closed(class)
+                {
+                    return true;
+                }
+                let members = self.members_of(from);
+                expected.iter().all(|(name, ty)| match members.iter().find(|m| m.name == *name) {
+                    Some(member) => self.assignable(&member.ty, ty),
+                    None => self.assignable(&Type::Undefined, ty),
+                })
+            }
+            _ => from == to,
+        }
+    }
+
+    /// User-defined members reachable on a value of type `ty`, including inherited ones.
+    #[must_use]
+    pub fn members_of(&self, ty: &Type) -> Vec<MemberInfo> {
+        let mut out: Vec<MemberInfo> = Vec::new();
+        for t in ty.alternatives() {
+            let found = match t {
+                Type::Instance(name) => self.class_chain(name).flat_map(|c| c.instance.iter().cloned()).collect(),
+                Type::Class(name) => self.classes.get(name).map(|c| c.statics.clone()).unwrap_or_default(),
+                Type::Object(fields) => fields
+                    .iter()
+                    .map(|(name, ty)| MemberInfo {
+                        name: name.clone(),
+                        ty: ty.clone(),
+                        is_method: matches!(ty, Type::Function(_)),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            for member in found {
+                if !out.iter().any(|m| m.name == member.name) {
+                    out.push(member);
+                }
+            }
+        }
+        out
+    }
+
+    fn class_chain<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a ClassInfo> {
+        let mut current = self.classes.get(name);
+        std::iter::from_fn(move || {
+            let class = current?;
+            current = class.super_class.as_deref().and_then(|s| self.classes.get(s));
+            Some(class)
+        })
+        .take(MAX_CLASS_DEPTH)
+    }
+
+    fn extends(&self, class: &str, ancestor: &str) -> bool {
+        class == ancestor || self.class_chain(class).any(|c| c.super_class.as_deref() == Some(ancestor))
+    }
+
+    fn user_member(&self, class: &str, name: &str) -> Option<&MemberInfo> {
+        self.class_chain(class).find_map(|c| c.instance.iter().find(|m| m.name == name))
+    }
+
+    /// Every class in the chain is user-defined and closed, so missing members are real.
+    fn chain_is_closed(&self, class: &str) -> bool {
+        let mut name = Some(class);
+        for _ in 0..MAX_CLASS_DEPTH {
+            let Some(current) = name else { return true };
+            let Some(info) = self.classes.get(current) else { return false };
+            if info.open {
+                return false;
+            }
+            name = info.super_class.as_deref();
+        }
+        false
+    }
+
+    fn lacks_method(&self, recv: &Type, method: &str) -> bool {
+        match recv {
+            Type::String => !yps_interpreter::stdlib::string::method_exists(method),
+            Type::Array(_) => !yps_interpreter::stdlib::array::method_exists(method),
+            Type::Number => !yps_interpreter::stdlib::number::INSTANCE_METHODS.contains(&method),
+            Type::Instance(class) => {
+                self.classes.contains_key(class)
+                    && self.chain_is_closed(class)
+                    && self.user_member(class, method).is_none()
+            }
+            Type::Union(types) => types.iter().all(|t| self.lacks_method(t, method)),
+            _ => false,
+        }
+    }
+}
+
+#[must_use]
+pub fn infer_source(text: &str) -> TypeInfo {
+    let sf = SourceFile::new("inline".to_string(), text.to_string());
+    let (tokens, _) = Lexer::new(&sf).tokenize();
+    let (program, _) = Parser::new(&tokens, &sf).parse_program();
+    infer(&program)
+}
+
+#[must_use]
+pub fn infer(program: &Program) -> TypeInfo {
+    let mut inferencer = Inferencer::default();
+    inferencer.scopes.push(HashMap::new());
+    inferencer.declared.push(HashMap::new());
+    inferencer.block(&program.items);
+    inferencer.finish()
+}
+
+/// Type of the value `program` completes with, that is of its trailing expression statement,
+/// worked out without running anything.
+#[must_use]
+pub fn completion_type(program: &Program) -> Type {
+    let mut inferencer = Inferencer::default();
+    inferencer.scopes.push(HashMap::new());
+    inferencer.declared.push(HashMap::new());
+    let Some((Stmt::Expr { expr, .. }, rest)) = program.items.split_last() else {
+        inferencer.block(&program.items);
+        return Type::Undefined;
+    };
+    inferencer.hoist(&program.items);
+    for stmt in rest {
+        inferencer.stmt(stmt);
+    }
+    inferencer.expr(expr)
+}
+
+type Scopes = Vec<HashMap<String, Type>>;
+
+/// What a function header says about its result.
+#[derive(Clone, Copy, Default)]
+struct Head<'a> {
+    is_async: bool,
+    is_generator: bool,
+    return_type: Option<&'a TypeExpr>,
+}
+
+#[derive(Default)]
+struct Inferencer {
+    info: TypeInfo,
+    scopes: Scopes,
+    /// Written types of the bindings in `scopes`, one map per scope.
+    declared: Scopes,
+    this: Vec<Type>,
+    returns: Vec<Vec<Type>>,
+    /// Written return type of each function being walked.
+    expected: Vec<Option<Type>>,
+    aliases: HashMap<String, TypeExpr>,
+    /// `еээ` names used as types: the union of their member values.
+    enums: HashMap<String, Type>,
+    resolving: Vec<String>,
+    hoisted: HashSet<usize>,
+    method_calls: BTreeMap<usize, (Span, Type, String)>,
+}
+
+impl Inferencer {
+    fn finish(mut self) -> TypeInfo {
+        for (start, (span, recv, method)) in std::mem::take(&mut self.method_calls) {
+            if self.info.lacks_method(&recv, &method) {
+                let owner = match &recv {
+                    Type::Instance(name) => name.clone(),
+                    other => other.to_string(),
+                };
+                self.info.warnings.insert(start, (span, format!("У типа '{owner}' нет метода '{method}'")));
+            }
+        }
+        self.info
+    }
+
+    fn record(&mut self, span: Span, name: &str, ty: &Type) {
+        self.info.occurrences.insert(span.start, (span, name.to_string(), ty.clone()));
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Type> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn declare(&mut self, name: &str, span: Span, ty: Type) {
+        self.record(span, name, &ty);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), ty);
+        }
+        if let Some(declared) = self.declared.last_mut() {
+            declared.remove(name);
+        }
+    }
+
+    fn declare_typed(&mut self, name: &str, span: Span, ty: Type) {
+        self.declare(name, span, ty.clone());
+        if let Some(declared) = self.declared.last_mut() {
+            declared.insert(name.to_string(), ty);
+        }
+    }
+
+    /// Written type of the binding `name` resolves to.
+    fn declared_type(&self, name: &str) -> Option<Type> {
+        let depth = self.scopes.iter().rposition(|scope| scope.contains_key(name))?;
+        self.declared.get(depth)?.get(name).cloned()
+    }
+
+    fn error(&mut self, span: Span, message: String) {
+        self.info.errors.insert(span.start, (span, message));
+    }
+
+    /// Reports `from` flowing into a place written as `to`; returns whether it fits.
+    fn check(&mut self, from: &Type, to: &Type, span: Span) -> bool {
+        if self.info.assignable(from, to) {
+            return true;
+        }
+        self.error(span, format!("Тип '{from}' нельзя присвоить типу '{to}'"));
+        false
+    }
+
+    /// Assigning to a binding with a written type keeps the value type only when it fits.
+    fn assign_checked(&mut self, name: &str, ty: Type, span: Span) -> Type {
+        match self.declared_type(name) {
+            Some(declared) if !self.check(&ty, &declared, span) => declared,
+            _ => ty,
+        }
+    }
+
+    fn assign(&mut self, name: &str, ty: Type) {
+        if let Some(slot) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(name)) {
+            *slot = ty;
+        }
+    }
+
+    fn refine(&mut self, name: &str, f: impl FnOnce(&TypeInfo, Type) -> Type) {
+        let Some(current) = self.lookup(name).cloned() else { return };
+        let refined = f(&self.info, current);
+        self.assign(name, refined);
+    }
+
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(HashMap::new());
+        self.declared.push(HashMap::new());
+        let out = f(self);
+        self.declared.pop();
+        self.scopes.pop();
+        out
+    }
+
+    // ---- statements ----
+
+    /// Walks a statement list, returning whether control never falls off its end.
+    fn block(&mut self, stmts: &[Stmt]) -> bool {
+        self.hoist(stmts);
+        let mut diverges = false;
+        for stmt in stmts {
+            diverges |= self.stmt(stmt);
+        }
+        diverges
+    }
+
+    /// Function bodies are walked up front so that earlier calls see their return types.
+    /// Class names are bound too (their members are filled in when the declaration is reached).
+    fn hoist(&mut self, stmts: &[Stmt]) {
+        let decls: Vec<&Stmt> = stmts
+            .iter()
+            .map(|stmt| match stmt {
+                Stmt::Export { kind: ExportKind::Declaration(inner), .. } => inner.as_ref(),
+                other => other,
+            })
+            .collect();
+        for stmt in &decls {
+            if let Stmt::TypeAlias { name, ty, .. } = stmt {
+                self.aliases.insert(name.name.clone(), ty.clone());
+            }
+        }
+        for stmt in &decls {
+            match stmt {
+                Stmt::FunctionDecl { name, params, .. } => {
+                    let placeholder = Type::Function(Box::new(FunctionSig::placeholder(params)));
+                    self.declare(&name.name, name.span, placeholder);
+                }
+                Stmt::ClassDecl { name, .. } => {
+                    self.info
+                        .classes
+                        .entry(name.name.clone())
+                        .or_insert_with(|| ClassInfo { open: true, ..ClassInfo::default() });
+                    self.declare(&name.name, name.span, Type::Class(name.name.clone()));
+                }
+                Stmt::EnumDecl { name, members, .. } => {
+                    let ty = members
+                        .iter()
+                        .map(|m| match &m.init {
+                            None => Type::Number,
+                            Some(Expr::Literal(literal)) => self.literal(literal),
+                            Some(_) => Type::Unknown,
+                        })
+                        .reduce(Type::union)
+                        .unwrap_or_default();
+                    self.enums.insert(name.name.clone(), ty);
+                }
+                _ => {}
+            }
+        }
+        for stmt in decls {
+            if matches!(stmt, Stmt::FunctionDecl { .. }) {
+                self.function_decl(stmt);
+            }
+        }
+    }
+
+    fn function_decl(&mut self, stmt: &Stmt) {
+        let Stmt::FunctionDecl { name, params, return_type, body, is_generator, is_async, span } = stmt else {
+            return;
+        };
+        self.hoisted.insert(span.start);
+        let this = Type::Unknown;
+        let head = Head { is_async: *is_async, is_generator: *is_generator, return_type: return_type.as_ref() };
+        let sig = self.function(params, &body.stmts, &[], head, this);
+        let ty = Type::Function(Box::new(sig.clone()));
+        self.record(name.span, &name.name, &ty);
+        if self.lookup(&name.name).is_some() {
+            self.assign(&name.name, ty);
+        } else {
+            self.declare(&name.name, name.span, ty);
+        }
+        self.info.functions.insert(name.name.clone(), sig);
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> bool {
+        match stmt {
+            Stmt::VarDecl { pattern, ty: None, init, .. } => {
+                let ty = self.expr(init);
+                if let (Pattern::Identifier(ident), Type::Function(sig)) = (pattern, &ty) {
+                    self.info.functions.insert(ident.name.clone(), (**sig).clone());
+                }
+                self.bind(pattern, ty);
+                false
+            }
+            Stmt::VarDecl { pattern, ty: Some(annotation), init, .. } => {
+                let declared = self.resolve(annotation);
+                let ty = self.expr(init);
+                self.check(&ty, &declared, init.span());
+                let ty = if declared.is_known() { declared } else { ty };
+                if let (Pattern::Identifier(ident), Type::Function(sig)) = (pattern, &ty) {
+                    self.info.functions.insert(ident.name.clone(), (**sig).clone());
+                }
+                match pattern {
+                    Pattern::Identifier(ident) => self.declare_typed(&ident.name, ident.span, ty),
+                    other => self.bind(other, ty),
+                }
+                false
+            }
+            Stmt::Expr { expr, .. } => {
+                self.expr(expr);
+                false
+            }
+            Stmt::Block(block) => self.scoped(|s| s.block(&block.stmts)),
+            Stmt::Empty { .. } | Stmt::Debugger { .. } | Stmt::Error { .. } => false,
+            Stmt::TypeAlias { ty, .. } => {
+                self.resolve(ty);
+                false
+            }
+            Stmt::If { condition, then_branch, else_branch, .. } => {
+                self.expr(condition);
+                let before = self.scopes.clone();
+                self.narrow(condition, true);
+                let then_diverges = self.scoped(|s| s.stmt(then_branch));
+                let after_then = std::mem::replace(&mut self.scopes, before);
+                self.narrow(condition, false);
+                let else_diverges = match else_branch {
+                    Some(branch) => self.scoped(|s| s.stmt(branch)),
+                    None => false,
+                };
+                match (then_diverges, else_diverges) {
+                    (true, true) => true,
+                    (true, false) => false,
+                    (false, true) => {
+                        self.scopes = after_then;
+                        false
+                    }
+                    (false, false) => {
+                        self.merge_from(&after_then);
+                        false
+                    }
+                }
+            }
+            Stmt::While { condition, body, .. } => {
+                self.expr(condition);
+                self.loop_body(|s| {
+                    s.narrow(condition, true);
+                    s.scoped(|s| s.stmt(body));
+                    s.expr(condition);
+                });
+                self.narrow(condition, false);
+                false
+            }
+            Stmt::DoWhile { body, condition, .. } => {
+                self.loop_body(|s| {
+                    s.scoped(|s| s.stmt(body));
+                    s.expr(condition);
+                });
+                self.narrow(condition, false);
+                false
+            }
+            Stmt::For { init, condition, update, body, .. } => {
+                self.scoped(|s| {
+                    if let Some(init) = init {
+                        s.stmt(init);
+                    }
+                    if let Some(condition) = condition {
+                        s.expr(condition);
+                    }
+                    s.loop_body(|s| {
+                        if let Some(condition) = condition {
+                            s.narrow(condition, true);
+                        }
+                        s.scoped(|s| s.stmt(body));
+                        if let Some(update) = update {
+                            s.expr(update);
+                        }
+                        if let Some(condition) = condition {
+                            s.expr(condition);
+                        }
+                    });
+                });
+                false
+            }
+            Stmt::ForIn { variable, iterable, body, .. } => {
+                self.expr(iterable);
+                self.for_each(variable, Type::String, body);
+                false
+            }
+            Stmt::ForOf { variable, iterable, body, .. } => {
+                let element = self.expr(iterable).element();
+                self.for_each(variable, element, body);
+                false
+            }
+            Stmt::ForAwaitOf { variable, iterable, body, .. } => {
+                let element = self.expr(iterable).element().awaited();
+                self.for_each(variable, element, body);
+                false
+            }
+            Stmt::Break { .. } | Stmt::Continue { .. } => true,
+            Stmt::Labeled { body, .. } => self.stmt(body),
+            Stmt::FunctionDecl { span, .. } => {
+                if !self.hoisted.contains(&span.start) {
+                    self.function_decl(stmt);
+                }
+                false
+            }
+            Stmt::Return { value, span } => {
+                let ty = value.as_ref().map_or(Type::Undefined, |v| self.expr(v));
+                if let Some(Some(expected)) = self.expected.last().cloned() {
+                    let at = value.as_ref().map_or(*span, Expr::span);
+                    self.check(&ty.clone().awaited(), &expected, at);
+                }
+                if let Some(returns) = self.returns.last_mut() {
+                    returns.push(ty);
+                }
+                true
+            }
+            Stmt::TryCatch { try_block, catch_param, catch_block, finally_block, .. } => {
+                let before = self.scopes.clone();
+                let try_diverges = self.scoped(|s| s.block(&try_block.stmts));
+                let mut diverges = try_diverges;
+                if let Some(catch_block) = catch_block {
+                    let after_try = std::mem::replace(&mut self.scopes, before);
+                    let catch_diverges = self.scoped(|s| {
+                        if let Some(param) = catch_param {
+                            s.declare(&param.name, param.span, Type::Unknown);
+                        }
+                        s.block(&catch_block.stmts)
+                    });
+                    if !try_diverges {
+                        self.merge_from(&after_try);
+                    }
+                    diverges = try_diverges && catch_diverges;
+                }
+                if let Some(finally_block) = finally_block {
+                    diverges |= self.scoped(|s| s.block(&finally_block.stmts));
+                }
+                diverges
+            }
+            Stmt::Throw { value, .. } => {
+                self.expr(value);
+                true
+            }
+            Stmt::Switch { expr, cases, default, .. } => {
+                self.expr(expr);
+                let before = self.scopes.clone();
+                let mut merged = before.clone();
+                for case in cases {
+                    self.scopes = before.clone();
+                    self.expr(&case.value);
+                    self.scoped(|s| s.block(&case.body.stmts));
+                    merge_scopes(&mut merged, &self.scopes);
+                }
+                if let Some(default) = default {
+                    self.scopes = before;
+                    self.scoped(|s| s.block(&default.stmts));
+                    merge_scopes(&mut merged, &self.scopes);
+                }
+                self.scopes = merged;
+                false
+            }
+            Stmt::ClassDecl { name, super_class, members, decorators, .. } => {
+                self.class_decl(&name.name, name.span, super_class.as_ref(), members, decorators);
+                false
+            }
+            Stmt::Using { name, init, .. } => {
+                let ty = self.expr(init);
+                self.declare(&name.name, name.span, ty);
+                false
+            }
+            Stmt::EnumDecl { name, members, .. } => {
+                let fields = members
+                    .iter()
+                    .map(|m| (m.name.name.clone(), m.init.as_ref().map_or(Type::Number, |init| self.expr(init))))
+                    .collect();
+                self.declare(&name.name, name.span, Type::Object(fields));
+                false
+            }
+            Stmt::Import { specifiers, .. } => {
+                for spec in specifiers {
+                    let local = match spec {
+                        ImportSpec::Default { local }
+                        | ImportSpec::Named { local, .. }
+                        | ImportSpec::Namespace { local } => local,
+                    };
+                    self.declare(&local.name, local.span, Type::Unknown);
+                }
+                false
+            }
+            Stmt::Export { kind, .. } => match kind {
+                ExportKind::Declaration(inner) => self.stmt(inner),
+                ExportKind::Named(names) => {
+                    for ident in names {
+                        let ty = self.lookup(&ident.name).cloned().unwrap_or_default();
+                        self.record(ident.span, &ident.name, &ty);
+                    }
+                    false
+                }
+            },
+        }
+    }
+
+    fn for_each(&mut self, variable: &Pattern, element: Type, body: &Stmt) {
+        self.loop_body(|s| {
+            s.scoped(|s| {
+                s.bind(variable, element.clone());
+                s.stmt(body);
+            });
+        });
+    }
+
+    fn loop_body(&mut self, mut pass: impl FnMut(&mut Self)) {
+        for _ in 0..LOOP_PASSES {
+            let before = self.scopes.clone();
+            pass(self);
+            let after = std::mem::replace(&mut self.scopes, before);
+            self.merge_from(&after);
+        }
+    }
+
+    fn merge_from(&mut self, other: &Scopes) {
+        merge_scopes(&mut self.scopes, other);
+    }
+
+    fn bind(&mut self, pattern: &Pattern, ty: Type) {
+        match pattern {
+            Pattern::Identifier(ident) => self.declare(&ident.name, ident.span, ty),
+            Pattern::Array { elements, rest, .. } => {
+                let element = ty.element();
+                for pattern in elements.iter().flatten() {
+                    self.bind(pattern, element.clone());
+                }
+                if let Some(rest) = rest {
+                    let rest_ty = match &ty {
+                        Type::String => Type::Array(Box::new(Type::String)),
+                        _ => Type::Array(Box::new(element)),
+                    };
+                    self.bind(rest, rest_ty);
+                }
+            }
+            Pattern::Object { properties, rest, .. } => {
+                for prop in properties {
+                    let prop_ty = self.member_type(&ty, &prop.key.name);
+                    match &prop.value {
+                        Some(pattern) => self.bind(pattern, prop_ty),
+                        None => self.declare(&prop.key.name, prop.key.span, prop_ty),
+                    }
+                }
+                if let Some(rest) = rest {
+                    let taken: Vec<&str> = properties.iter().map(|p| p.key.name.as_str()).collect();
+                    let remaining = match &ty {
+                        Type::Object(fields) => {
+                            fields.iter().filter(|(name, _)| !taken.contains(&name.as_str())).cloned().collect()
+                        }
+                        _ => Vec::new(),
+                    };
+                    self.bind(rest, Type::Object(remaining));
+                }
+            }
+            Pattern::Default { pattern, default, .. } => {
+                let fallback = self.expr(default);
+                let ty = if ty.is_known() { Type::union(ty.without_nullish(), fallback) } else { fallback };
+                self.bind(pattern, ty);
+            }
+        }
+    }
+
+    // ---- functions and classes ----
+
+    fn function(&mut self, params: &[Param], body: &[Stmt], hints: &[Type], head: Head<'_>, this: Type) -> FunctionSig {
+        let declared_ret = head.return_type.map(|ty| self.resolve(ty));
+        let expected = declared_ret.clone().filter(|_| !head.is_generator).map(|ty| match head.is_async {
+            true => ty.awaited(),
+            false => ty,
+        });
+        let saved = self.scopes.clone();
+        self.scopes.push(HashMap::new());
+        self.declared.push(HashMap::new());
+        self.this.push(this);
+        self.returns.push(Vec::new());
+        self.expected.push(expected.clone());
+
+        let mut sig = FunctionSig { rest: params.last().is_some_and(|p| p.is_rest), ..FunctionSig::default() };
+        for (i, param) in params.iter().enumerate() {
+            let declared = param.ty.as_ref().map(|ty| self.resolve(ty));
+            let ty = match (&declared, &param.default) {
+                (Some(declared), Some(default)) => {
+                    let fallback = self.expr(default);
+                    self.check(&fallback, declared, default.span());
+                    declared.clone()
+                }
+                (Some(declared), None) => declared.clone(),
+                (None, default) => {
+                    let hint = hints.get(i).cloned().unwrap_or_default();
+                    let ty = match default {
+                        Some(default) => {
+                            let fallback = self.expr(default);
+                            if hint.is_known() { Type::union(hint, fallback) } else { fallback }
+                        }
+                        None => hint,
+                    };
+                    if param.is_rest { Type::Array(Box::new(ty)) } else { ty }
+                }
+            };
+            match (&param.pattern, declared.is_some()) {
+                (Some(pattern), _) => self.bind(pattern, ty.clone()),
+                (None, true) => self.declare_typed(&param.name.name, param.name.span, ty.clone()),
+                (None, false) => self.declare(&param.name.name, param.name.span, ty.clone()),
+            }
+            if declared.is_some() && param.default.is_none() && !param.is_rest && sig.required == i {
+                sig.required += 1;
+            }
+            sig.declared.push(declared.is_some());
+            sig.params.push((param.name.name.clone(), ty));
+        }
+
+        let diverges = self.block(body);
+        let mut returns = self.returns.pop().unwrap_or_default();
+        self.expected.pop();
+        self.this.pop();
+        self.declared.pop();
+        self.scopes = saved;
+
+        if !diverges {
+            if let (Some(expected), Some(annotation)) = (&expected, head.return_type)
+                && !self.info.assignable(&Type::Undefined, expected)
+            {
+                self.error(annotation.span(), format!("Функция должна вернуть значение типа '{expected}'"));
+            }
+            returns.push(Type::Undefined);
+        }
+        let ret = declared_ret.filter(Type::is_known).unwrap_or_else(|| Type::from_members(returns));
+        sig.ret = if head.is_generator {
+            Type::Instance("Итератор".to_string())
+        } else if head.is_async {
+            Type::Promise(Box::new(ret.awaited()))
+        } else {
+            ret
+        };
+        sig
+    }
+
+    fn class_decl(
+        &mut self,
+        name: &str,
+        name_span: Span,
+        super_class: Option<&Expr>,
+        members: &[ClassMember],
+        decorators: &[Expr],
+    ) {
+        for decorator in decorators {
+            self.expr(decorator);
+        }
+        let super_name = super_class.and_then(|expr| {
+            self.expr(expr);
+            match expr {
+                Expr::Identifier(ident) => Some(ident.name.clone()),
+                _ => None,
+            }
+        });
+        let open = !decorators.is_empty()
+            || (super_class.is_some() && super_name.as_ref().is_none_or(|s| !self.info.classes.contains_key(s)))
+            || members.iter().any(|m| match m {
+                ClassMember::Method { decorators, .. }
+                | ClassMember::Field { decorators, .. }
+                | ClassMember::Getter { decorators, .. }
+                | ClassMember::Setter { decorators, .. } => !decorators.is_empty(),
+                _ => false,
+            });
+
+        let mut class = ClassInfo { super_class: super_name, open, ..ClassInfo::default() };
+        for member in members {
+            match member {
+                ClassMember::Method { name, params, is_static, .. } => {
+                    class.set(*is_static, &name.name, Type::Function(Box::new(FunctionSig::placeholder(params))), true);
+                }
+                ClassMember::Field { name, ty: Some(annotation), is_static, .. } => {
+                    let ty = self.resolve(annotation);
+                    if !*is_static {
+                        class.fields.insert(name.name.clone(), ty.clone());
+                    }
+                    class.set(*is_static, &name.name, ty, false);
+                }
+                ClassMember::Field { name, is_static, .. }
+                | ClassMember::Getter { name, is_static, .. }
+                | ClassMember::Setter { name, is_static, .. } => {
+                    class.set(*is_static, &name.name, Type::Unknown, false)
+                }
+                _ => {}
+            }
+        }
+        self.info.classes.insert(name.to_string(), class);
+        self.declare(name, name_span, Type::Class(name.to_string()));
+
+        let instance = Type::Instance(name.to_string());
+        let statics = Type::Class(name.to_string());
+        for _ in 0..2 {
+            for member in members {
+                match member {
+                    ClassMember::Constructor { params, body, .. } => {
+                        let sig = self.function(params, &body.stmts, &[], Head::default(), instance.clone());
+                        if let Some(info) = self.info.classes.get_mut(name) {
+                            info.constructor = Some(sig);
+                        }
+                    }
+                    ClassMember::Method { name: method, params, return_type, body, is_static, .. } => {
+                        let this = if *is_static { statics.clone() } else { instance.clone() };
+                        let head = Head { return_type: return_type.as_ref(), ..Head::default() };
+                        let sig = self.function(params, &body.stmts, &[], head, this);
+                        let ty = Type::Function(Box::new(sig.clone()));
+                        self.record(method.span, &method.name, &ty);
+                        self.set_member(name, *is_static, &method.name, ty, true);
+                        self.info.functions.insert(method.name.clone(), sig);
+                    }
+                    ClassMember::Field { name: field, ty: annotation, init, is_static, .. } => {
+                        let declared = annotation.as_ref().map(|ty| self.resolve(ty));
+                        let mut ty = match init {
+                            Some(init) => {
+                                self.this.push(if *is_static { statics.clone() } else { instance.clone() });
+                                let ty = self.expr(init);
+                                self.this.pop();
+                                if let Some(declared) = &declared {
+                                    self.check(&ty, declared, init.span());
+                                }
+                                ty
+                            }
+                            None => Type::Unknown,
+                        };
+                        if let Some(declared) = declared.filter(Type::is_known) {
+                            ty = declared;
+                        }
+                        self.record(field.span, &field.name, &ty);
+                        if ty.is_known() {
+                            self.set_member(name, *is_static, &field.name, ty, false);
+                        }
+                    }
+                    ClassMember::Getter { name: getter, body, is_static, .. } => {
+                        let this = if *is_static { statics.clone() } else { instance.clone() };
+                        let ret = self.function(&[], &body.stmts, &[], Head::default(), this).ret;
+                        self.record(getter.span, &getter.name, &ret);
+                        self.set_member(name, *is_static, &getter.name, ret, false);
+                    }
+                    ClassMember::Setter { param, body, is_static, .. } => {
+                        let this = if *is_static { statics.clone() } else { instance.clone() };
+                        self.function(std::slice::from_ref(param), &body.stmts, &[], Head::default(), this);
+                    }
+                    ClassMember::StaticBlock { body, .. } => {
+                        self.function(&[], &body.stmts, &[], Head::default(), statics.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn set_member(&mut self, class: &str, is_static: bool, name: &str, ty: Type, is_method: bool) {
+        if let Some(info) = self.info.classes.get_mut(class) {
+            info.set(is_static, name, ty, is_method);
+        }
+    }
+
+    // ---- narrowing ----
+
+    fn narrow(&mut self, cond: &Expr, positive: bool) {
+        match cond {
+            Expr::Grouping { expr, .. } => self.narrow(expr, positive),
+            Expr::Unary { op: UnaryOp::Not, expr, .. } => self.narrow(expr, !positive),
+            Expr::Binary { op: BinaryOp::And, lhs, rhs, .. } if positive => {
+                self.narrow(lhs, true);
+                self.narrow(rhs, true);
+            }
+            Expr::Binary { op: BinaryOp::Or, lhs, rhs, .. } if !positive => {
+                self.narrow(lhs, false);
+                self.narrow(rhs, false);
+            }
+            Expr::Binary {
+                op: op @ (BinaryOp::StrictEquals | BinaryOp::Equals | BinaryOp::StrictNotEquals | BinaryOp::NotEquals),
+                lhs,
+                rhs,
+                ..
+            } => {
+                let equal = matches!(op, BinaryOp::StrictEquals | BinaryOp::Equals) == positive;
+                let strict = matches!(op, BinaryOp::StrictEquals | BinaryOp::StrictNotEquals);
+                if let Some((name, tag)) = typeof_test(lhs, rhs).or_else(|| typeof_test(rhs, lhs)) {
+                    self.refine(name, |_, ty| narrow_typeof(ty, tag, equal));
+                } else if let Some((name, nullish)) = nullish_test(lhs, rhs).or_else(|| nullish_test(rhs, lhs)) {
+                    self.refine(name, |_, ty| narrow_nullish(ty, &nullish, strict, equal));
+                }
+            }
+            Expr::Binary { op: BinaryOp::Instanceof, lhs, rhs, .. } => {
+                if let (Expr::Identifier(value), Some(instance)) = (lhs.as_ref(), self.instance_of(rhs)) {
+                    self.refine(&value.name, |info, ty| narrow_instance(info, ty, &instance, positive));
+                }
+            }
+            Expr::Identifier(ident) if positive => self.refine(&ident.name, |_, ty| ty.without_nullish()),
+            _ => {}
+        }
+    }
+
+    fn instance_of(&self, class: &Expr) -> Option<Type> {
+        let Expr::Identifier(ident) = class else { return None };
+        if let Some(Type::Class(name)) = self.lookup(&ident.name) {
+            return Some(Type::Instance(name.clone()));
+        }
+        self.constructed(&ident.name)
+    }
+
+    /// Type of `захуярить Имя(...)` for builtin constructors.
+    fn constructed(&self, name: &str) -> Option<Type> {
+        if self.lookup(name).is_some() {
+            return None;
+        }
+        Some(match name {
+            "Помойка" | "Array" => Type::Array(Box::new(Type::Unknown)),
+            "СловоПацана" | "Promise" => Type::Promise(Box::new(Type::Unknown)),
+            "RegExp" => Type::RegExp,
+            "Карта" | "Map" => Type::Instance("Карта".to_string()),
+            "Набор" | "Set" => Type::Instance("Набор".to_string()),
+            "Дата" | "Date" => Type::Instance("Дата".to_string()),
+            _ => return None,
+        })
+    }
+
+    // ---- expressions ----
+
+    fn this_type(&self) -> Type {
+        self.this.last().cloned().unwrap_or_default()
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Type {
+        match expr {
+            Expr::Identifier(ident) => match self.lookup(&ident.name).cloned() {
+                Some(ty) => {
+                    self.record(ident.span, &ident.name, &ty);
+                    ty
+                }
+                None => Type::Unknown,
+            },
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Unary { op, expr, .. } => {
+                let ty = self.expr(expr);
+                match op {
+                    UnaryOp::Typeof => Type::String,
+                    UnaryOp::Not | UnaryOp::Delete => Type::Boolean,
+                    UnaryOp::Void => Type::Undefined,
+                    UnaryOp::Plus => Type::Number,
+                    UnaryOp::Minus | UnaryOp::BitwiseNot => numeric(&ty, &ty),
+                }
+            }
+            Expr::Binary { op, lhs, rhs, .. } => self.binary(*op, lhs, rhs),
+            Expr::Assignment { target, value, .. } => {
+                let ty = self.expr(value);
+                let ty = self.assign_checked(&target.name, ty, value.span());
+                self.assign(&target.name, ty.clone());
+                self.record(target.span, &target.name, &ty);
+                ty
+            }
+            Expr::Postfix { expr, .. } => {
+                let ty = self.expr(expr);
+                numeric(&ty, &ty)
+            }
+            Expr::Grouping { expr, .. } => self.expr(expr),
+            Expr::Call { callee, args, span } | Expr::OptionalCall { callee, args, span } => {
+                self.call(callee, args, *span)
+            }
+            Expr::Index { object, index, .. } | Expr::OptionalIndex { object, index, .. } => {
+                let ty = self.expr(object);
+                self.expr(index);
+                match ty {
+                    Type::Array(_) | Type::String => ty.element(),
+                    _ => Type::Unknown,
+                }
+            }
+            Expr::Member { object, property, .. } | Expr::OptionalMember { object, property, .. } => {
+                let ty = match self.namespace_member(object, &property.name) {
+                    Some(ty) => ty,
+                    None => {
+                        let recv = self.expr(object);
+                        self.member_type(&recv, &property.name)
+                    }
+                };
+                if ty.is_known() {
+                    self.record(property.span, &property.name, &ty);
+                }
+                ty
+            }
+            Expr::Conditional { condition, then_expr, else_expr, .. } => {
+                self.expr(condition);
+                let before = self.scopes.clone();
+                self.narrow(condition, true);
+                let then_ty = self.expr(then_expr);
+                self.scopes = before.clone();
+                self.narrow(condition, false);
+                let else_ty = self.expr(else_expr);
+                self.scopes = before;
+                Type::union(then_ty, else_ty)
+            }
+            Expr::ArrowFunction { params, return_type, body, is_async, .. } => {
+                let this = self.this_type();
+                let head = Head { is_async: *is_async, is_generator: false, return_type: return_type.as_deref() };
+                Type::Function(Box::new(self.function(params, &body.stmts, &[], head, this)))
+            }
+            Expr::FunctionExpr { name, params, return_type, body, is_generator, is_async, .. } => {
+                let head =
+                    Head { is_async: *is_async, is_generator: *is_generator, return_type: return_type.as_deref() };
+                let sig = self.scoped(|s| {
+                    if let Some(name) = name {
+                        let placeholder = Type::Function(Box::new(FunctionSig::placeholder(params)));
+                        s.declare(&name.name, name.span, placeholder);
+                    }
+                    s.function(params, &body.stmts, &[], head, Type::Unknown)
+                });
+                Type::Function(Box::new(sig))
+            }
+            Expr::TemplateLiteral { parts, .. } => {
+                for part in parts {
+                    if let TemplatePart::Expr(expr) = part {
+                        self.expr(expr);
+                    }
+                }
+                Type::String
+            }
+            Expr::TaggedTemplate { tag, expressions, .. } => {
+                let tag = self.expr(tag);
+                for expr in expressions {
+                    self.expr(expr);
+                }
+                match tag {
+                    Type::Function(sig) => sig.ret,
+                    _ => Type::Unknown,
+                }
+            }
+            Expr::Spread { expr, .. } => self.expr(expr),
+            Expr::This { span } => {
+                let ty = self.this_type();
+                if ty.is_known() {
+                    self.record(*span, "тырыпыры", &ty);
+                }
+                ty
+            }
+            Expr::New { callee, args, span } => {
+                let ty = match callee.as_ref() {
+                    Expr::Identifier(ident) => {
+                        let bound = self.expr(callee);
+                        match bound {
+                            Type::Class(name) => Type::Instance(name),
+                            _ => self.constructed(&ident.name).unwrap_or_default(),
+                        }
+                    }
+                    other => {
+                        self.expr(other);
+                        Type::Unknown
+                    }
+                };
+                let hints = match &ty {
+                    Type::Promise(_) => vec![vec![Type::Unknown, Type::Unknown]],
+                    _ => Vec::new(),
+                };
+                let arg_types = self.args(args, &hints);
+                if let Type::Instance(class) = &ty
+                    && let Some(sig) = self.info.classes.get(class).and_then(|c| c.constructor.clone())
+                {
+                    self.check_call(&sig, args, &arg_types, *span);
+                }
+                ty
+            }
+            Expr::Super { .. } | Expr::NewTarget { .. } | Expr::ImportMeta { .. } | Expr::Error { .. } => Type::Unknown,
+            Expr::Yield { argument, .. } => {
+                if let Some(argument) = argument {
+                    self.expr(argument);
+                }
+                Type::Unknown
+            }
+            Expr::Await { argument, .. } => self.expr(argument).awaited(),
+            Expr::DynamicImport { source, .. } => {
+                self.expr(source);
+                Type::Promise(Box::new(Type::Unknown))
+            }
+            Expr::Match { subject, arms, .. } => {
+                let subject = self.expr(subject);
+                arms.iter().map(|arm| self.match_arm(arm, &subject)).reduce(Type::union).unwrap_or_default()
+            }
+        }
+    }
+
+    fn match_arm(&mut self, arm: &MatchArm, subject: &Type) -> Type {
+        self.scoped(|s| {
+            match &arm.pattern {
+                MatchPattern::Bind(pattern) => s.bind(pattern, subject.clone()),
+                MatchPattern::Instance { class, pattern, .. } => {
+                    s.expr(class);
+                    if let Some(pattern) = pattern {
+                        let instance = s.instance_of(class).unwrap_or_default();
+                        s.bind(pattern, instance);
+                    }
+                }
+                MatchPattern::Value(value) => {
+                    s.expr(value);
+                }
+                MatchPattern::Wildcard { .. } => {}
+            }
+            if let Some(guard) = &arm.guard {
+                s.expr(guard);
+            }
+            s.expr(&arm.body)
+        })
+    }
+
+    fn literal(&mut self, literal: &Literal) -> Type {
+        match literal {
+            Literal::Number { .. } => Type::Number,
+            Literal::BigInt { .. } => Type::BigInt,
+            Literal::String { .. } => Type::String,
+            Literal::Boolean { .. } => Type::Boolean,
+            Literal::Null { .. } => Type::Null,
+            Literal::Undefined { .. } => Type::Undefined,
+            Literal::RegExp { .. } => Type::RegExp,
+            Literal::Array { elements, .. } => {
+                let mut element: Option<Type> = None;
+                for expr in elements {
+                    let ty = match expr {
+                        Expr::Spread { expr, .. } => self.expr(expr).element(),
+                        other => self.expr(other),
+                    };
+                    element = Some(match element {
+                        Some(prev) => Type::union(prev, ty),
+                        None => ty,
+                    });
+                }
+                Type::Array(Box::new(element.unwrap_or_default()))
+            }
+            Literal::Object { entries, .. } => {
+                let mut fields: Vec<(String, Type)> = Vec::new();
+                let put = |fields: &mut Vec<(String, Type)>, name: String, ty: Type| {
+                    fields.retain(|(n, _)| *n != name);
+                    fields.push((name, ty));
+                };
+                for entry in entries {
+                    match entry {
+                        ObjectEntry::Property { key, value } => {
+                            let ty = self.expr(value);
+                            match key {
+                                PropKey::Identifier(ident) => put(&mut fields, ident.name.clone(), ty),
+                                PropKey::Computed(expr) => {
+                                    self.expr(expr);
+                                }
+                            }
+                        }
+                        ObjectEntry::Spread(expr) => {
+                            if let Type::Object(spread) = self.expr(expr) {
+                                for (name, ty) in spread {
+                                    put(&mut fields, name, ty);
+                                }
+                            }
+                        }
+                        ObjectEntry::Getter { key, body, .. } => {
+                            let ret = self.function(&[], &body.stmts, &[], Head::default(), Type::Unknown).ret;
+                            if let PropKey::Identifier(ident) = key {
+                                put(&mut fields, ident.name.clone(), ret);
+                            }
+                        }
+                        ObjectEntry::Setter { param, body, .. } => {
+                            self.function(
+                                std::slice::from_ref(param),
+                                &body.stmts,
+                                &[],
+                                Head::default(),
+                                Type::Unknown,
+                            );
+                        }
+                    }
+                }
+                Type::Object(fields)
+            }
+        }
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: &Expr, rhs: &Expr) -> Type {
+        match op {
+            BinaryOp::Assign => {
+                let ty = self.expr(rhs);
+                self.assign_to(lhs, ty.clone(), rhs.span());
+                ty
+            }
+            BinaryOp::And | BinaryOp::Or | BinaryOp::NullishCoalescing => {
+                let left = self.expr(lhs);
+                let before = self.scopes.clone();
+                match op {
+                    BinaryOp::And => self.narrow(lhs, true),
+                    BinaryOp::Or => self.narrow(lhs, false),
+                    _ => {}
+                }
+                let right = self.expr(rhs);
+                self.scopes = before;
+                match op {
+                    BinaryOp::NullishCoalescing => Type::union(left.without_nullish(), right),
+                    _ => Type::union(left, right),
+                }
+            }
+            BinaryOp::AndAssign | BinaryOp::OrAssign | BinaryOp::NullishAssign => {
+                let left = self.expr(lhs);
+                let right = self.expr(rhs);
+                let ty = Type::union(left, right);
+                self.assign_to(lhs, ty.clone(), rhs.span());
+                ty
+            }
+            BinaryOp::PlusAssign
+            | BinaryOp::MinusAssign
+            | BinaryOp::MulAssign
+            | BinaryOp::DivAssign
+            | BinaryOp::ExpAssign
+            | BinaryOp::ModAssign
+            | BinaryOp::BitAndAssign
+            | BinaryOp::BitOrAssign
+            | BinaryOp::BitXorAssign
+            | BinaryOp::ShlAssign
+            | BinaryOp::ShrAssign
+            | BinaryOp::UshrAssign => {
+                let left = self.expr(lhs);
+                let right = self.expr(rhs);
+                let ty = if op == BinaryOp::PlusAssign { add(&left, &right) } else { numeric(&left, &right) };
+                self.assign_to(lhs, ty.clone(), rhs.span());
+                ty
+            }
+            BinaryOp::Add => {
+                let left = self.expr(lhs);
+                let right = self.expr(rhs);
+                add(&left, &right)
+            }
+            BinaryOp::Sub
+            | BinaryOp::Mul
+            | BinaryOp::Div
+            | BinaryOp::Mod
+            | BinaryOp::Exp
+            | BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::LeftShift
+            | BinaryOp::RightShift
+            | BinaryOp::UnsignedRightShift => {
+                let left = self.expr(lhs);
+                let right = self.expr(rhs);
+                numeric(&left, &right)
+            }
+            BinaryOp::Equals
+            | BinaryOp::StrictEquals
+            | BinaryOp::NotEquals
+            | BinaryOp::StrictNotEquals
+            | BinaryOp::Less
+            | BinaryOp::Greater
+            | BinaryOp::LessOrEqual
+            | BinaryOp::GreaterOrEqual
+            | BinaryOp::Instanceof
+            | BinaryOp::In => {
+                self.expr(lhs);
+                self.expr(rhs);
+                Type::Boolean
+            }
+            BinaryOp::Pipeline => {
+                self.expr(lhs);
+                match self.expr(rhs) {
+                    Type::Function(sig) => sig.ret,
+                    _ => Type::Unknown,
+                }
+            }
+        }
+    }
+
+    fn assign_to(&mut self, target: &Expr, ty: Type, value_span: Span) {
+        match target {
+            Expr::Identifier(ident) => {
+                let ty = self.assign_checked(&ident.name, ty, value_span);
+                self.assign(&ident.name, ty.clone());
+                self.record(ident.span, &ident.name, &ty);
+            }
+            Expr::Member { object, property, .. } => {
+                let recv = self.expr(object);
+                if let Expr::Identifier(ident) = object.as_ref()
+                    && let Some(Type::Object(fields)) = self.declared_type(&ident.name)
+                    && !fields.is_empty()
+                {
+                    match fields.into_iter().find(|(name, _)| *name == property.name) {
+                        Some((_, field)) => {
+                            self.check(&ty, &field, value_span);
+                        }
+                        None => self.error(property.span, format!("У типа '{recv}' нет поля '{}'", property.name)),
+                    }
+                    return;
+                }
+                match &recv {
+                    Type::Instance(class) => {
+                        let declared = self.info.class_chain(class).find_map(|c| c.fields.get(&property.name)).cloned();
+                        match declared {
+                            Some(declared) => {
+                                self.check(&ty, &declared, value_span);
+                            }
+                            None => {
+                                if let Some(info) = self.info.classes.get_mut(class) {
+                                    info.assign_field(&property.name, ty.clone());
+                                }
+                            }
+                        }
+                    }
+                    Type::Object(fields) => {
+                        if let Expr::Identifier(ident) = object.as_ref() {
+                            let mut fields = fields.clone();
+                            match fields.iter_mut().find(|(name, _)| *name == property.name) {
+                                Some((_, existing)) => *existing = ty.clone(),
+                                None => fields.push((property.name.clone(), ty.clone())),
+                            }
+                            self.assign(&ident.name, Type::Object(fields));
+                        }
+                    }
+                    _ => {}
+                }
+                if ty.is_known() {
+                    self.record(property.span, &property.name, &ty);
+                }
+            }
+            Expr::Index { object, index, .. } => {
+                if let Type::Instance(class) = self.expr(object)
+                    && let Some(info) = self.info.classes.get_mut(&class)
+                {
+                    info.open = true;
+                }
+                self.expr(index);
+            }
+            other => {
+                self.expr(other);
+            }
+        }
+    }
+
+    fn args(&mut self, args: &[Expr], hints: &[Vec<Type>]) -> Vec<Type> {
+        args.iter()
+            .enumerate()
+            .map(|(i, arg)| match (arg, hints.get(i)) {
+                (Expr::ArrowFunction { params, return_type, body, is_async, .. }, Some(hints)) => {
+                    let this = self.this_type();
+                    let head = Head { is_async: *is_async, is_generator: false, return_type: return_type.as_deref() };
+                    Type::Function(Box::new(self.function(params, &body.stmts, hints, head, this)))
+                }
+                (Expr::FunctionExpr { params, return_type, body, is_async, is_generator, .. }, Some(hints)) => {
+                    let head =
+                        Head { is_async: *is_async, is_generator: *is_generator, return_type: return_type.as_deref() };
+                    Type::Function(Box::new(self.function(params, &body.stmts, hints, head, Type::Unknown)))
+                }
+                (other, _) => self.expr(other),
+            })
+            .collect()
+    }
+
+    fn call(&mut self, callee: &Expr, args: &[Expr], span: Span) -> Type {
+        let (Expr::Member { object, property, .. } | Expr::OptionalMember { object, property, .. }) = callee else {
+            let ty = self.expr(callee);
+            let arg_types = self.args(args, &[]);
+            return match (ty, callee) {
+                (Type::Function(sig), _) => {
+                    self.check_call(&sig, args, &arg_types, span);
+                    sig.ret
+                }
+                (Type::Unknown, Expr::Identifier(ident)) if self.lookup(&ident.name).is_none() => {
+                    builtin_return(&ident.name)
+                }
+                _ => Type::Unknown,
+            };
+        };
+
+        if let Expr::Identifier(ns) = object.as_ref()
+            && self.lookup(&ns.name).is_none()
+        {
+            let arg_types = self.args(args, &[]);
+            let ret = namespace_call(&ns.name, &property.name, &arg_types);
+            let Some(sig) = catalog::global_signature(&ns.name, &property.name).and_then(|sig| self.stdlib_sig(sig))
+            else {
+                return ret;
+            };
+            self.check_call(&sig, args, &arg_types, span);
+            return if ret.is_known() { ret } else { sig.ret };
+        }
+
+        let recv = self.expr(object);
+        let hints = callback_hints(&recv, &property.name);
+        let arg_types = if matches!(property.name.as_str(), "свернуть" | "reduce" | "свернутьСправа" | "reduceRight")
+            && args.len() > 1
+        {
+            let initial = self.expr(&args[1]);
+            let element = recv.element();
+            let callback = self.args(&args[..1], &[vec![initial.clone(), element, Type::Number, recv.clone()]]);
+            let mut types = callback;
+            types.push(initial);
+            for extra in &args[2..] {
+                types.push(self.expr(extra));
+            }
+            types
+        } else {
+            self.args(args, &hints)
+        };
+
+        self.method_calls.insert(property.span.start, (property.span, recv.clone(), property.name.clone()));
+        let mut ret = self.method_return(&recv, &property.name, &arg_types);
+        let member = self.member_type(&recv, &property.name);
+        if member.is_known() {
+            self.record(property.span, &property.name, &member);
+        }
+        let sig = match &member {
+            Type::Function(sig) => Some((**sig).clone()),
+            _ => recv
+                .receiver_table()
+                .and_then(|table| catalog::signature(table, &property.name))
+                .and_then(|sig| self.stdlib_sig(sig)),
+        };
+        if let Some(sig) = sig {
+            self.check_call(&sig, args, &arg_types, span);
+            if !ret.is_known() {
+                ret = sig.ret;
+            }
+        }
+        ret
+    }
+
+    /// Checks the arguments of a call against the parameters that have a written type.
+    fn check_call(&mut self, sig: &FunctionSig, args: &[Expr], arg_types: &[Type], span: Span) {
+        if args.iter().any(|arg| matches!(arg, Expr::Spread { .. })) {
+            return;
+        }
+        if args.len() < sig.required {
+            self.error(span, format!("Ожидалось аргументов: {}, передано: {}", sig.required, args.len()));
+        }
+        let last = sig.params.len().saturating_sub(1);
+        for (i, (arg, ty)) in args.iter().zip(arg_types).enumerate() {
+            let (index, collects) = if sig.rest && i >= last { (last, true) } else { (i, false) };
+            let (Some((name, param)), Some(true)) = (sig.params.get(index), sig.declared.get(index)) else {
+                continue;
+            };
+            let param = if collects { param.element() } else { param.clone() };
+            if !self.info.assignable(ty, &param) {
+                self.error(arg.span(), format!("Аргумент типа '{ty}' не подходит параметру '{name}' типа '{param}'"));
+            }
+        }
+    }
+
+    /// A stdlib signature from `types`, written in annotation syntax.
+    fn stdlib_sig(&mut self, text: &str) -> Option<FunctionSig> {
+        let sf = SourceFile::new("сигнатура".to_string(), text.to_string());
+        let (tokens, _) = Lexer::new(&sf).tokenize();
+        let ty = Parser::new(&tokens, &sf).parse_type_source().0?;
+        match self.resolve(&ty) {
+            Type::Function(sig) => Some(*sig),
+            _ => None,
+        }
+    }
+
+    // ---- annotations ----
+
+    /// The type an annotation stands for. Unknown names are reported and treated as unknown.
+    fn resolve(&mut self, ty: &TypeExpr) -> Type {
+        match ty {
+            TypeExpr::Named { name, args, span } => {
+                let arg = |s: &mut Self, i: usize| args.get(i).map(|a| s.resolve(a)).unwrap_or_default();
+                match name.name.as_str() {
+                    "число" | "Хуйня" | "Number" => Type::Number,
+                    "строка" | "String" => Type::String,
+                    "булево" | "Boolean" => Type::Boolean,
+                    "бигцелое" | "БигЦелое" | "BigInt" => Type::BigInt,
+                    "куку" => Type::Undefined,
+                    "неизвестно" => Type::Unknown,
+                    "регэксп" | "Регулярка" | "RegExp" => Type::RegExp,
+                    "объект" | "Кент" | "Object" => Type::Object(Vec::new()),
+                    "функция" => Type::Function(Box::default()),
+                    "массив" | "Помойка" | "Array" => Type::Array(Box::new(arg(self, 0))),
+                    "обещание" | "СловоПацана" | "Promise" => Type::Promise(Box::new(arg(self, 0))),
+                    "Карта" | "Map" => Type::Instance("Карта".to_string()),
+                    "Набор" | "Set" => Type::Instance("Набор".to_string()),
+                    "Дата" | "Date" => Type::Instance("Дата".to_string()),
+                    "Итератор" => Type::Instance("Итератор".to_string()),
+                    other if self.info.classes.contains_key(other) => Type::Instance(other.to_string()),
+                    other if let Some(ty) = self.enums.get(other) => ty.clone(),
+                    other => {
+                        let Some(alias) = self.aliases.get(other).cloned() else {
+                            self.error(*span, format!("Неизвестный тип '{other}'"));
+                            return Type::Unknown;
+                        };
+                        if self.resolving.iter().any(|n| n == other) {
+                            return Type::Unknown;
+                        }
+                        self.resolving.push(other.to_string());
+                        let ty = self.resolve(&alias);
+                        self.resolving.pop();
+                        ty
+                    }
+                }
+            }
+            TypeExpr::Literal { value, .. } => self.literal(value),
+            TypeExpr::Array { element, .. } => Type::Array(Box::new(self.resolve(element))),
+            TypeExpr::Union { types, .. } => {
+                let members = types.iter().map(|t| self.resolve(t)).collect();
+                Type::from_members(members)
+            }
+            TypeExpr::Object { fields, .. } => {
+                Type::Object(fields.iter().map(|f| (f.name.name.clone(), self.field_type(f))).collect())
+            }
+            TypeExpr::Function { params, ret, .. } => {
+                let mut sig = FunctionSig::default();
+                for param in params {
+                    if !param.optional && sig.required == sig.params.len() {
+                        sig.required += 1;
+                    }
+                    sig.params.push((param.name.name.clone(), self.field_type(param)));
+                    sig.declared.push(true);
+                }
+                sig.ret = self.resolve(ret);
+                Type::Function(Box::new(sig))
+            }
+        }
+    }
+
+    fn field_type(&mut self, field: &TypeField) -> Type {
+        let ty = self.resolve(&field.ty);
+        if field.optional { Type::union(ty, Type::Undefined) } else { ty }
+    }
+
+    fn namespace_member(&self, object: &Expr, property: &str) -> Option<Type> {
+        let Expr::Identifier(ns) = object else { return None };
+        if self.lookup(&ns.name).is_some() {
+            return None;
+        }
+        match (ns.name.as_str(), property) {
+            ("Матан" | "Math", _) => Some(Type::Number),
+            _ => None,
+        }
+    }
+
+    fn member_type(&self, recv: &Type, name: &str) -> Type {
+        match recv {
+            Type::String | Type::Array(_) if matches!(name, "длина" | "length") => Type::Number,
+            Type::Instance(class)
+                if matches!(class.as_str(), "Карта" | "Набор") && matches!(name, "размер" | "size") =>
+            {
+                Type::Number
+            }
+            Type::RegExp => match name {
+                "источник" | "source" | "флаги" | "flags" => Type::String,
+                "последнийИндекс" | "lastIndex" => Type::Number,
+                _ => Type::Unknown,
+            },
+            Type::Instance(class) => self.info.user_member(class, name).map(|m| m.ty.clone()).unwrap_or_default(),
+            Type::Class(class) => self
+                .info
+                .classes
+                .get(class)
+                .and_then(|c| c.statics.iter().find(|m| m.name == name))
+                .map(|m| m.ty.clone())
+                .unwrap_or_default(),
+            Type::Object(fields) => {
+                fields.iter().find(|(n, _)| n == name).map(|(_, ty)| ty.clone()).unwrap_or_default()
+            }
+            Type::Union(types) => Type::from_members(types.iter().map(|t| self.member_type(t, name)).collect()),
+            _ => Type::Unknown,
+        }
+    }
+
+    fn method_return(&self, recv: &Type, method: &str, args: &[Type]) -> Type {
+        let callback_ret = || match args.first() {
+            Some(Type::Function(sig)) => sig.ret.clone(),
+            _ => Type::Unknown,
+        };
+        match recv {
+            Type::String => string_method_return(method),
+            Type::Array(element) => match method {
+                "добавить"
+                | "push"
+                | "втолкнуть"
+                | "подсунуть"
+                | "unshift"
+                | "найтиИндекс"
+                | "indexOf"
+                | "найтиПоследнийПо"
+                | "lastIndexOf"
+                | "найтиИндексПо"
+                | "findIndex"
+                | "найтиПоследнийИндекс"
+                | "findLastIndex" => Type::Number,
+                "вытолкнуть"
+                | "pop"
+                | "снять"
+                | "shift"
+                | "найти"
+                | "find"
+                | "найтиПоследний"
+                | "findLast"
+                | "поИндексу"
+                | "at" => (**element).clone(),
+                "отрезать"
+                | "slice"
+                | "перевернуть"
+                | "reverse"
+                | "сортировать"
+                | "sort"
+                | "отфильтровать"
+                | "filter"
+                | "перевёрнутый"
+                | "toReversed"
+                | "отсортированный"
+                | "toSorted"
+                | "вырезать"
+                | "splice"
+                | "вырезанный"
+                | "toSpliced"
+                | "сЗаменой"
+                | "with"
+                | "заполнить"
+                | "fill"
+                | "копироватьВнутри"
+                | "copyWithin" => recv.clone(),
+                "склеитьМассивы" | "concat" => args.iter().fold(recv.clone(), |acc, arg| match arg {
+                    Type::Array(_) => Type::union(acc, arg.clone()),
+                    other => Type::union(acc, Type::Array(Box::new(other.clone()))),
+                }),
+                "склеить" | "join" | "вЛокальнуюСтроку" | "toLocaleString" => Type::String,
+                "включает" | "includes" | "некоторые" | "some" | "все" | "every" => Type::Boolean,
+                "каждый" | "forEach" => Type::Undefined,
+                "преобразовать" | "map" => Type::Array(Box::new(callback_ret())),
+                "плоскоПреобразовать" | "flatMap" => Type::Array(Box::new(callback_ret().element())),
+                "плоский" | "flat" => Type::Array(Box::new(element.element())),
+                "свернуть" | "reduce" | "свернутьСправа" | "reduceRight" => match args.get(1) {
+                    Some(initial) => initial.clone(),
+                    None => (**element).clone(),
+                },
+                "ключи" | "keys" | "значения" | "values" | "записи" | "entries" => {
+                    Type::Instance("Итератор".to_string())
+                }
+                _ => Type::Unknown,
+            },
+            Type::Number => match method {
+                "вСтроку" | "фиксированный" | "вЛокальнуюСтроку" | "toLocaleString" => {
+                    Type::String
+                }
+                _ => Type::Unknown,
+            },
+            Type::RegExp => match method {
+                "проверить" | "test" => Type::Boolean,
+                "вСтроку" | "toString" => Type::String,
+                _ => Type::Unknown,
+            },
+            Type::Promise(inner) => match method {
+                "потом" | "then" => Type::Promise(Box::new(callback_ret().awaited())),
+                "ловить" | "catch" => {
+                    Type::Promise(Box::new(Type::union((**inner).clone(), callback_ret().awaited())))
+                }
+                "наконец" | "finally" => recv.clone(),
+                _ => Type::Unknown,
+            },
+            Type::Instance(class) if class == "Карта" || class == "Набор" => match method {
+                "поставить" | "set" | "добавить" | "add" => recv.clone(),
+                "имеет" | "has" | "удалить" | "delete" => Type::Boolean,
+                "ключи" | "keys" | "значения" | "values" | "записи" | "entries" => {
+                    Type::Instance("Итератор".to_string())
+                }
+                "очистить" | "clear" | "каждый" | "forEach" => Type::Undefined,
+                _ => Type::Unknown,
+            },
+            Type::Instance(class) if class == "Дата" => match method {
+                "вИСО"
+                | "toISOString"
+                | "вСтроку"
+                | "toString"
+                | "вЛокальнуюСтроку"
+                | "toLocaleString"
+                | "вЛокальнуюДату"
+                | "toLocaleDateString"
+                | "вЛокальноеВремя"
+                | "toLocaleTimeString" => Type::String,
+                _ => Type::Number,
+            },
+            Type::Instance(class) if class == "Итератор" => match method {
+                "вМассив" | "toArray" => Type::Array(Box::new(Type::Unknown)),
+                "некоторые" | "some" | "все" | "every" => Type::Boolean,
+                "преобразовать" | "map" | "отфильтровать" | "filter" | "взять" | "take" | "пропустить" | "drop" => {
+                    recv.clone()
+                }
+                _ => Type::Unknown,
+            },
+            Type::Instance(_) | Type::Class(_) | Type::Object(_) => match self.member_type(recv, method) {
+                Type::Function(sig) => sig.ret,
+                _ => Type::Unknown,
+            },
+            Type::Union(types) => {
+                Type::from_members(types.iter().map(|t| self.method_return(t, method, args)).collect())
+            }
+            _ => Type::Unknown,
+        }
+    }
+}
+
+fn merge_scopes(into: &mut Scopes, other: &Scopes) {
+    for (target, source) in into.iter_mut().zip(other) {
+        for (name, ty) in target.iter_mut() {
+            if let Some(other_ty) = source.get(name) {
+                *ty = Type::union(std::mem::take(ty), other_ty.clone());
+            }
+        }
+    }
+}
+
+fn add(left: &Type, right: &Type) -> Type {
+    match (left, right) {
+        (Type::String, _) | (_, Type::String) => Type::String,
+        (Type::Unknown, _) | (_, Type::Unknown) => Type::Unknown,
+        _ => numeric(left, right),
+    }
+}
+
+fn numeric(left: &Type, right: &Type) -> Type {
+    if *left == Type::BigInt || *right == Type::BigInt { Type::BigInt } else { Type::Number }
+}
+
+fn typeof_test<'a>(operand: &'a Expr, tag: &'a Expr) -> Option<(&'a str, &'a str)> {
+    let Expr::Unary { op: UnaryOp::Typeof, expr, .. } = operand else { return None };
+    let Expr::Identifier(ident) = expr.as_ref() else { return None };
+    let Expr::Literal(Literal::String { value, .. }) = tag else { return None };
+    Some((&ident.name, value))
+}
+
+fn nullish_test<'a>(operand: &'a Expr, literal: &Expr) -> Option<(&'a str, Type)> {
+    let Expr::Identifier(ident) = operand else { return None };
+    match literal {
+        Expr::Literal(Literal::Null { .. }) => Some((&ident.name, Type::Null)),
+        Expr::Literal(Literal::Undefined { .. }) => Some((&ident.name, Type::Undefined)),
+        _ => None,
+    }
+}
+
+fn narrow_typeof(ty: Type, tag: &str, keep: bool) -> Type {
+    if ty == Type::Unknown {
+        if !keep {
+            return ty;
+        }
+        return match tag {
+            "число" => Type::Number,
+            "строка" => Type::String,
+            "булево" => Type::Boolean,
+            "бигцелое" => Type::BigInt,
+            "неопределено" => Type::Undefined,
+            _ => Type::Unknown,
+        };
+    }
+    let kept: Vec<Type> =
+        ty.clone().into_members().into_iter().filter(|t| (t.typeof_tag() == Some(tag)) == keep).collect();
+    if kept.is_empty() { ty } else { Type::from_members(kept) }
+}
+
+fn narrow_nullish(ty: Type, nullish: &Type, strict: bool, keep: bool) -> Type {
+    let matches = |t: &Type| {
+        if strict { t == nullish } else { matches!(t, Type::Null | Type::Undefined) }
+    };
+    if ty == Type::Unknown {
+        return if keep && strict { nullish.clone() } else { ty };
+    }
+    let kept: Vec<Type> = ty.clone().into_members().into_iter().filter(|t| matches(t) == keep).collect();
+    if kept.is_empty() { ty } else { Type::from_members(kept) }
+}
+
+fn narrow_instance(info: &TypeInfo, ty: Type, instance: &Type, keep: bool) -> Type {
+    let is_instance = |t: &Type| match (t, instance) {
+        (Type::Instance(name), Type::Instance(target)) => info.extends(name, target),
+        (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+    };
+    if ty == Type::Unknown {
+        return if keep { instance.clone() } else { ty };
+    }
+    let kept: Vec<Type> = ty.clone().into_members().into_iter().filter(|t| is_instance(t) == keep).collect();
+    match (kept.is_empty(), keep) {
+        (true, true) => instance.clone(),
+        (true, false) => ty,
+        (false, _) => Type::from_members(kept),
+    }
+}
+
+fn callback_hints(recv: &Type, method: &str) -> Vec<Vec<Type>> {
+    match recv {
+        Type::Array(element) => {
+            let element = (**element).clone();
+            match method {
+                "каждый"
+                | "forEach"
+                | "преобразовать"
+                | "map"
+                | "отфильтровать"
+                | "filter"
+                | "найти"
+                | "find"
+                | "найтиИндексПо"
+                | "findIndex"
+                | "некоторые"
+                | "some"
+                | "все"
+                | "every"
+                | "плоскоПреобразовать"
+                | "flatMap"
+                | "найтиПоследний"
+                | "findLast"
+                | "найтиПоследнийИндекс"
+                | "findLastIndex" => {
+                    vec![vec![element, Type::Number, recv.clone()]]
+                }
+                "сортировать" | "sort" | "отсортированный" | "toSorted" => {
+                    vec![vec![element.clone(), element]]
+                }
+                "свернуть" | "reduce" | "свернутьСправа" | "reduceRight" => {
+                    vec![vec![element.clone(), element, Type::Number, recv.clone()]]
+                }
+                _ => Vec::new(),
+            }
+        }
+        Type::Promise(inner) => match method {
+            "потом" | "then" => vec![vec![(**inner).clone()]],
+            _ => Vec::new(),
+        },
+        Type::Union(types) => {
+            types.iter().map(|t| callback_hints(t, method)).find(|h| !h.is_empty()).unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn string_method_return(method: &str) -> Type {
+    match method {
+        "разбить" | "split" => Type::Array(Box::new(Type::String)),
+        "кодСимволаВ"
+        | "charCodeAt"
+        | "кодТочки"
+        | "codePointAt"
+        | "найтиПодстроку"
+        | "indexOf"
+        | "найтиПодстрокуСконца"
+        | "lastIndexOf"
+        | "найтиИндекс"
+        | "search" => Type::Number,
+        "содержит" | "includes" | "начинаетсяС" | "startsWith" | "заканчиваетсяНа" | "endsWith" => {
+            Type::Boolean
+        }
+        "найтиВсе" | "matchAll" => Type::Instance("Итератор".to_string()),
+        "совпадает" | "match" => Type::Unknown,
+        _ if yps_interpreter::stdlib::string::method_exists(method) => Type::String,
+        _ => Type::Unknown,
+    }
+}
+
+fn builtin_return(name: &str) -> Type {
+    match name {
+        "строка" | "тип" => Type::String,
+        "число" | "длина" => Type::Number,
+        "БигЦелое" => Type::BigInt,
+        "сказать" => Type::Undefined,
+        "подождать" => Type::Promise(Box::new(Type::Undefined)),
+        "Помойка" | "Array" => Type::Array(Box::new(Type::Unknown)),
+        "RegExp" => Type::RegExp,
+        "Дата" => Type::String,
+        _ => Type::Unknown,
+    }
+}
+
+fn namespace_call(namespace: &str, method: &str, args: &[Type]) -> Type {
+    let first = || args.first().cloned().unwrap_or_default();
+    match (namespace, method) {
+        ("Матан" | "Math", _) => Type::Number,
+        ("Жсон" | "JSON", "вСтроку" | "stringify") => Type::String,
+        ("Помойка" | "Array", "являетсяПомойкой" | "isArray") => Type::Boolean,
+        ("Помойка" | "Array", "нового" | "of") => Type::Array(Box::new(Type::from_members(args.to_vec()))),
+        ("Помойка" | "Array", "извне" | "from") => match first() {
+            ty @ (Type::Array(_) | Type::String) => Type::Array(Box::new(ty.element())),
+            _ => Type::Array(Box::new(Type::Unknown)),
+        },
+        ("Кент" | "Object", "ключи" | "keys") => Type::Array(Box::new(Type::String)),
+        ("Кент" | "Object", "значения" | "values") => match first() {
+            Type::Object(fields) => {
+                Type::Array(Box::new(Type::from_members(fields.into_iter().map(|(_, t)| t).collect())))
+            }
+            _ => Type::Array(Box::new(Type::Unknown)),
+        },
+        ("Кент" | "Object", "записи" | "entries") => {
+            Type::Array(Box::new(Type::Array(Box::new(Type::Unknown))))
+        }
+        ("Хуйня" | "Number", "конечна" | "isFinite" | "целая" | "isInteger" | "нихуя" | "isNaN") => {
+            Type::Boolean
+        }
+        ("Хуйня" | "Number", "разобратьЦелое" | "parseInt" | "разобратьЧисло" | "parseFloat") => {
+            Type::Number
+        }
+        ("СловоПацана" | "Promise", "решить" | "resolve") => {
+            Type::Promise(Box::new(first().awaited()))
+        }
+        ("СловоПацана" | "Promise", _) => Type::Promise(Box::new(Type::Unknown)),
+        ("Дата" | "Date", "сейчас" | "now") => Type::Number,
+        _ => Type::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_of(src: &str, needle: &str) -> String {
+        let info = infer_source(src);
+        let byte = src.rfind(needle).unwrap();
+        info.type_at(byte).map(|(_, ty)| ty.to_string()).unwrap_or_default()
+    }
+
+    fn warnings(src: &str) -> Vec<String> {
+        infer_source(src).warnings().into_iter().map(|(_, msg)| msg.to_string()).collect()
+    }
+
+    #[test]
+    fn literals_and_arrays() {
+        let src = "участковый х = [1, 2];\nучастковый с = \"а\";\nучастковый б = 10n;\nсказать(х, с, б);";
+        assert_eq!(type_of(src, "х, с"), "массив<число>");
+        assert_eq!(type_of(src, "с, б"), "строка");
+        assert_eq!(type_of(src, "б)"), "бигцелое");
+    }
+
+    #[test]
+    fn stdlib_calls_and_constructors() {
+        let src = "гыы к = захуярить Карта();\nгыы ч = Матан.корень(4);\nгыы ч2 = строка(1).разбить(\",\");\nсказать(к, ч, ч2);";
+        assert_eq!(type_of(src, "к, ч"), "Карта");
+        assert_eq!(type_of(src, "ч, ч2"), "число");
+        assert_eq!(type_of(src, "ч2)"), "массив<строка>");
+    }
+
+    #[test]
+    fn completion_type_is_the_trailing_expression() {
+        let completion = |src: &str| {
+            let sf = SourceFile::new("inline".to_string(), src.to_string());
+            let (tokens, _) = Lexer::new(&sf).tokenize();
+            completion_type(&Parser::new(&tokens, &sf).parse_program().0).to_string()
+        };
+        assert_eq!(completion("гыы а = 1;\nа + [1, 2].длина;"), "число");
+        assert_eq!(completion("йопта ф() { отвечаю \"а\"; }\nф();"), "строка");
+        assert_eq!(completion("гыы а = 1;"), "неопределено");
+    }
+
+    #[test]
+    fn function_return_types_are_inferred() {
+        let src = "йопта фу(а = 1) { вилкойвглаз (а > 0) { отвечаю \"да\"; } отвечаю а; }\nгыы р = фу();\nсказать(р);";
+        assert_eq!(type_of(src, "р)"), "строка | число");
+        let info = infer_source(src);
+        let sig = info.function("фу").unwrap();
+        assert_eq!(sig.params[0].1, Type::Number);
+    }
+
+    #[test]
+    fn class_instances_know_their_members() {
+        let src = "клёво Кот {\n  конструктор(имя) { тырыпыры.имя = \"Мурзик\"; }\n  мяу() { отвечаю 1; }\n}\nгыы к = захуярить Кот();\nгыы н = к.мяу();\nсказать(н, к.имя);";
+        assert_eq!(type_of(src, "к.имя"), "Кот");
+        assert_eq!(type_of(src, "н,"), "число");
+        assert_eq!(type_of(src, "имя);"), "строка");
+    }
+
+    #[test]
+    fn destructuring_binds_element_and_field_types() {
+        let src = "гыы [а, ...б] = [1, 2];\nгыы { в, г = \"x\" } = { в: правда };\nсказать(а, б, в, г);";
+        assert_eq!(type_of(src, "а, б, в"), "число");
+        assert_eq!(type_of(src, "б, в"), "массив<число>");
+        assert_eq!(type_of(src, "в, г"), "булево");
+        assert_eq!(type_of(src, "г);"), "строка");
+    }
+
+    #[test]
+    fn typeof_checks_narrow_unions() {
+        let src = "йопта ф(х = 1) {\n  вилкойвглаз (ф) { х = \"с\"; }\n  вилкойвглаз (чезажижан х === \"строка\") {\n    сказать(х);\n  } иливжопураз {\n    сказать(х + 1, х);\n  }\n}";
+        assert_eq!(type_of(src, "х);\n  } иливжопураз"), "строка");
+        assert_eq!(type_of(src, "х);\n  }\n}"), "число");
+    }
+
+    #[test]
+    fn early_return_narrows_the_rest_of_the_block() {
+        let src = "йопта ф(х = 1) {\n  вилкойвглаз (ф) { х = \"с\"; }\n  вилкойвглаз (чезажижан х !== \"строка\") { отвечаю; }\n  сказать(х);\n}";
+        assert_eq!(type_of(src, "х);"), "строка");
+    }
+
+    #[test]
+    fn instanceof_narrows_to_the_class() {
+        let src = "клёво А { а() {} }\nклёво Б { б() {} }\nйопта ф(п) {\n  вилкойвглаз (п шкура Б) { п.б(); }\n}";
+        assert_eq!(type_of(src, "п.б"), "Б");
+    }
+
+    #[test]
+    fn callbacks_receive_element_types() {
+        let src = "гыы слова = [\"а\"];\nслова.каждый((с) => сказать(с));";
+        assert_eq!(type_of(src, "с))"), "строка");
+    }
+
+    #[test]
+    fn unknown_methods_are_reported() {
+        let src = "гыы х = [1];\nх.добавить(2);\nх.вВерхнийРегистр();\nгыы с = \"а\";\nс.вВерхнийРегистр();\nс.нормализовать();\nс.добавить();";
+        let warnings = warnings(src);
+        assert_eq!(warnings.len(), 2, "got {warnings:?}");
+        assert!(warnings[0].contains("массив") && warnings[0].contains("вВерхнийРегистр"));
+        assert!(warnings[1].contains("строка") && warnings[1].contains("добавить"));
+    }
+
+    #[test]
+    fn unknown_methods_on_user_classes_are_reported() {
+        let src = "клёво А { а() {} }\nклёво Б батя А { б() {} }\nгыы б = захуярить Б();\nб.а();\nб.б();\nб.в();";
+        let warnings = warnings(src);
+        assert_eq!(warnings, vec!["У типа 'Б' нет метода 'в'".to_string()]);
+    }
+
+    #[test]
+    fn unresolved_types_are_not_reported() {
+        let src = "клёво А батя Косяк { }\nгыы а = захуярить А();\nа.что();\nгыы х = неизвестно();\nх.что();";
+        assert!(warnings(src).is_empty());
+    }
+
+    fn errors(src: &str) -> Vec<String> {
+        infer_source(src).errors().into_iter().map(|(_, msg)| msg.to_string()).collect()
+    }
+
+    #[test]
+    fn annotations_fix_the_declared_type() {
+        let src = "гыы х: число | строка = 1;\nйопта ф(а: строка): булево { отвечаю правда; }\nсказать(х, ф);";
+        assert_eq!(type_of(src, "х, ф"), "число | строка");
+        assert_eq!(type_of(src, "ф);"), "(а: строка) => булево");
+        assert!(errors(src).is_empty());
+    }
+
+    #[test]
+    fn mismatched_values_are_errors() {
+        let src = "гыы х: число = \"а\";\nгыы у: число = 1;\nу = правда;\nйопта ф(): строка { отвечаю 1; }";
+        assert_eq!(
+            errors(src),
+            vec![
+                "Тип 'строка' нельзя присвоить типу 'число'".to_string(),
+                "Тип 'булево' нельзя присвоить типу 'число'".to_string(),
+                "Тип 'число' нельзя присвоить типу 'строка'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_return_is_an_error() {
+        let src = "йопта ф(а): число { вилкойвглаз (а) { отвечаю 1; } }\nйопта г(): куку { }";
+        assert_eq!(errors(src), vec!["Функция должна вернуть значение типа 'число'".to_string()]);
+    }
+
+    #[test]
+    fn calls_check_arity_and_argument_types() {
+        let src = "йопта ф(а: число, б = 1, ...в: строка[]) {}\nф();\nф(1, 2, \"а\", 3);\nф(\"а\");";
+        assert_eq!(
+            errors(src),
+            vec![
+                "Ожидалось аргументов: 1, передано: 0".to_string(),
+                "Аргумент типа 'число' не подходит параметру 'в' типа 'строка'".to_string(),
+                "Аргумент типа 'строка' не подходит параметру 'а' типа 'число'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn stdlib_signatures_check_arguments() {
+        let src = "Матан.пол(\"а\");\nгыы с = \"абв\".отрезать(\"1\");\nМатан.макс(1, 2, 3);";
+        let errors = errors(src);
+        assert_eq!(errors.len(), 2, "got {errors:?}");
+        assert!(errors[0].contains("'строка'") && errors[0].contains("'число'"));
+    }
+
+    #[test]
+    fn aliases_and_interfaces_check_object_shapes() {
+        let src = "тип Ид = число | строка;\nхуёво Точка { x: число; y?: число; }\nгыы т: Точка = { x: 1 };\nт.z = 1;\nгыы п: Точка = { y: 1 };\nгыы и: Ид = 1;\nи = ноль;";
+        let errors = errors(src);
+        assert_eq!(errors.len(), 3, "got {errors:?}");
+        assert!(errors[0].contains("нет поля 'z'"));
+        assert!(errors[2].contains("'число | строка'"));
+    }
+
+    #[test]
+    fn class_fields_and_constructors_are_checked() {
+        let src = "клёво Кот {\n  имя: строка = \"\";\n  Кот(имя: строка) { тырыпыры.имя = имя; }\n}\nгыы к = захуярить Кот(1);\nк.имя = 2;\nгыы м: Кот = захуярить Кот(\"а\");";
+        assert_eq!(errors(src).len(), 2, "got {:?}", errors(src));
+    }
+
+    #[test]
+    fn unknown_type_names_are_reported() {
+        let src = "гыы х: Фигня = 1;\nгыы у: Карта<строка, число> = захуярить Карта();";
+        assert_eq!(errors(src), vec!["Неизвестный тип 'Фигня'".to_string()]);
+    }
+
+    #[test]
+    fn enums_name_the_type_of_their_members() {
+        let src = "еээ Ц { А, Б }\nгыы ц: Ц = Ц.А;\nгыы д: Ц = \"а\";\nеээ Т { Да = \"да\", Нет = \"нет\" }\nгыы т: Т = Т.Да;";
+        assert_eq!(errors(src).len(), 1, "got {:?}", errors(src));
+        assert!(errors(src)[0].contains("'строка'"), "{:?}", errors(src));
+    }
+
+    #[test]
+    fn unannotated_code_has_no_type_errors() {
+        let src = "гыы х = 1;\nх = \"а\";\nйопта ф(а) { отвечаю а; }\nф();\nф(1, 2);";
+        assert!(errors(src).is_empty());
+    }
+}
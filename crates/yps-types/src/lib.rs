@@ -0,0 +1,5 @@
+//! Static types of YoptaScript programs: the inference pass behind `yps check` and the language
+//! server, and the catalog of typed stdlib signatures it reads.
+
+pub mod catalog;
+pub mod infer;
@@ -1,5 +1,6 @@
 use std::cell::Cell;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::rc::Weak;
 
@@ -14,7 +15,7 @@ pub const NAMESPACES: &[&str] = &["Матан", "Кент", "Жсон", "Хуй
 
 pub const ERROR_CTOR: &str = "Косяк";
 
-const PURE_NAMESPACE_GLOBALS: &[&str] = &["Итератор", "ФС", "Процесс", "Сеть"];
+const PURE_NAMESPACE_GLOBALS: &[&str] = &["Итератор", "ФС", "Процесс", "Сеть", "Атомики", "Интл", "Время"];
 
 const HOST_CONSTRUCTORS: &[&str] = &[
     "Карта",
@@ -28,6 +29,7 @@ const HOST_CONSTRUCTORS: &[&str] = &[
     "Посредник",
     "ОбластьБайтов",
     "ОбзорБайтов",
+    "ОбщийБуфер",
     "Ц8Массив",
     "Ц8ОграниченныйМассив",
     "Ч8Массив",
@@ -39,8 +41,14 @@ const HOST_CONSTRUCTORS: &[&str] = &[
     "Др64Массив",
     "КонтроллёрОтмены",
     "СигналОтмены",
+    "Воркер",
+    "КодировщикТекста",
+    "ДекодировщикТекста",
 ];
 
+/// Plain builtins the VM leaves to the interpreter's implementation.
+const BRIDGED_BUILTINS: &[&str] = &["вБазу64", "изБазы64"];
+
 pub(crate) const IDENTITY_CACHE_PRUNE_THRESHOLD: usize = 1024;
 
 enum CacheKeepalive {
@@ -59,9 +67,11 @@ impl CacheKeepalive {
 
 thread_local! {
     static ACTIVE_VM: Cell<*mut Vm> = const { Cell::new(std::ptr::null_mut()) };
-    static IDENTITY_CACHE: RefCell<std::collections::HashMap<usize, (CacheKeepalive, IValue)>> =
-        RefCell::new(std::collections::HashMap::new());
+    static IDENTITY_CACHE: RefCell<HashMap<usize, (CacheKeepalive, IValue)>> =
+        RefCell::new(HashMap::new());
     static IDENTITY_CACHE_PRUNE_AT: Cell<usize> = const { Cell::new(IDENTITY_CACHE_PRUNE_THRESHOLD) };
+    /// VM closures behind the host callbacks made by [`wrap_vm_callback`], by callback name.
+    static VM_CALLBACKS: RefCell<HashMap<String, Value>> = RefCell::new(HashMap::new());
 }
 
 fn cached_identity(ptr: usize) -> Option<IValue> {
@@ -142,6 +152,9 @@ pub fn namespace_value(name: &str) -> Option<Value> {
         "ФС" => yps_interpreter::stdlib::fs::build_object(),
         "Процесс" => yps_interpreter::stdlib::process::build_object(),
         "Сеть" => yps_interpreter::stdlib::network::build_object(),
+        "Атомики" => yps_interpreter::stdlib::atomics::build_object(),
+        "Интл" => yps_interpreter::stdlib::intl::build_object(),
+        "Время" => yps_interpreter::stdlib::temporal::build_object(),
         ERROR_CTOR => return Some(Value::Builtin(Rc::from(ERROR_CTOR))),
         _ if HOST_CONSTRUCTORS.contains(&name) => return Some(Value::Builtin(Rc::from(name))),
         _ => return None,
@@ -151,7 +164,7 @@ pub fn namespace_value(name: &str) -> Option<Value> {
 
 #[must_use]
 pub fn is_bridged_call(name: &str) -> bool {
-    if name == ERROR_CTOR || HOST_CONSTRUCTORS.contains(&name) {
+    if name == ERROR_CTOR || HOST_CONSTRUCTORS.contains(&name) || BRIDGED_BUILTINS.contains(&name) {
         return true;
     }
     if let Some((ns, _)) = name.split_once('.') {
@@ -297,10 +310,18 @@ fn map_err(e: yps_interpreter::RuntimeError) -> VmError {
         let vm_thrown = interp_to_vm(thrown).unwrap_or_else(|_| Value::Str(Rc::from(e.message.as_str())));
         return VmError::new(e.message, e.span).with_thrown(vm_thrown);
     }
-    VmError::new(e.message, e.span)
+    let mut err = VmError::new(e.message, e.span);
+    err.help = e.help;
+    err
 }
 
 pub fn vm_to_interp(value: &Value, span: Span) -> Result<IValue, VmError> {
+    to_interp(value, span, &mut HashMap::new())
+}
+
+/// `converting` maps the arrays and objects being converted to their interpreter stores, so that a
+/// value reachable from itself comes out as the same store instead of recursing forever.
+fn to_interp(value: &Value, span: Span, converting: &mut HashMap<usize, IValue>) -> Result<IValue, VmError> {
     match value {
         Value::Number(n) => Ok(IValue::Number(*n)),
         Value::BigInt(n) => Ok(IValue::BigInt(*n)),
@@ -309,33 +330,59 @@ pub fn vm_to_interp(value: &Value, span: Span) -> Result<IValue, VmError> {
         Value::Null => Ok(IValue::Null),
         Value::Undefined => Ok(IValue::Undefined),
         Value::Host(iv) => Ok(iv.clone()),
+        Value::RegExp { pattern, flags, compiled, last_index } => {
+            Ok(IValue::RegExp(Rc::new(yps_interpreter::value::RegExpData {
+                pattern: pattern.to_string(),
+                flags: flags.to_string(),
+                compiled: Rc::clone(compiled),
+                last_index: Rc::clone(last_index),
+            })))
+        }
         Value::Array(items) => {
-            let converted: Vec<IValue> =
-                items.borrow().iter().map(|v| vm_to_interp(v, span)).collect::<Result<_, _>>()?;
             let ptr = Rc::as_ptr(items) as usize;
-            if let Some(IValue::Array(store)) = cached_identity(ptr) {
+            if let Some(value) = converting.get(&ptr) {
+                return Ok(value.clone());
+            }
+            let value = match cached_identity(ptr) {
+                Some(value @ IValue::Array(_)) => value,
+                _ => {
+                    let value = IValue::array(Vec::new());
+                    store_identity(ptr, CacheKeepalive::Array(Rc::downgrade(items)), value.clone());
+                    value
+                }
+            };
+            converting.insert(ptr, value.clone());
+            let converted: Vec<IValue> =
+                items.borrow().iter().map(|v| to_interp(v, span, converting)).collect::<Result<_, _>>()?;
+            if let IValue::Array(store) = &value {
                 store.borrow_mut().0 = converted;
-                return Ok(IValue::Array(store));
             }
-            let value = IValue::array(converted);
-            store_identity(ptr, CacheKeepalive::Array(Rc::downgrade(items)), value.clone());
             Ok(value)
         }
         Value::Object(map) => {
+            let ptr = Rc::as_ptr(map) as usize;
+            if let Some(value) = converting.get(&ptr) {
+                return Ok(value.clone());
+            }
+            let value = match cached_identity(ptr) {
+                Some(value @ IValue::Object(_)) => value,
+                _ => {
+                    let value = IValue::object(indexmap::IndexMap::new());
+                    store_identity(ptr, CacheKeepalive::Object(Rc::downgrade(map)), value.clone());
+                    value
+                }
+            };
+            converting.insert(ptr, value.clone());
             let mut out = indexmap::IndexMap::new();
             for (k, v) in map.borrow().iter() {
                 if crate::value::is_internal_key(k) {
                     continue;
                 }
-                out.insert(k.clone(), vm_to_interp(v, span)?);
+                out.insert(k.clone(), to_interp(v, span, converting)?);
             }
-            let ptr = Rc::as_ptr(map) as usize;
-            if let Some(IValue::Object(store)) = cached_identity(ptr) {
+            if let IValue::Object(store) = &value {
                 store.borrow_mut().map = out;
-                return Ok(IValue::Object(store));
             }
-            let value = IValue::object(out);
-            store_identity(ptr, CacheKeepalive::Object(Rc::downgrade(map)), value.clone());
             Ok(value)
         }
         Value::Function(_)
@@ -352,8 +399,16 @@ pub fn vm_to_interp(value: &Value, span: Span) -> Result<IValue, VmError> {
     }
 }
 
+/// The VM closure `handler` wraps, if it is one; lets values that belong to the VM anyway, such as
+/// a worker message, reach it without a detour through the interpreter.
+#[must_use]
+pub fn vm_callback(handler: &IValue) -> Option<Value> {
+    let IValue::BuiltinFunction(name) = handler else { return None };
+    VM_CALLBACKS.with(|c| c.borrow().get(name.as_str()).cloned())
+}
+
 fn wrap_vm_callback(callee: Value) -> IValue {
-    let cell = RefCell::new(callee);
+    let cell = RefCell::new(callee.clone());
     let marker = yps_interpreter::host_callback::register(Box::new(move |iargs, span| {
         let vm_ptr = ACTIVE_VM.with(Cell::get);
         if vm_ptr.is_null() {
@@ -382,10 +437,16 @@ fn wrap_vm_callback(callee: Value) -> IValue {
             },
         }
     }));
+    VM_CALLBACKS.with(|c| c.borrow_mut().insert(marker.clone(), callee));
     IValue::BuiltinFunction(marker)
 }
 
 pub fn interp_to_vm(value: &IValue) -> Result<Value, String> {
+    to_vm(value, &mut HashMap::new())
+}
+
+/// The reverse of [`to_interp`], with the same guard against values that reach themselves.
+fn to_vm(value: &IValue, converting: &mut HashMap<usize, Value>) -> Result<Value, String> {
     match value {
         IValue::Number(n) => Ok(Value::Number(*n)),
         IValue::BigInt(n) => Ok(Value::BigInt(*n)),
@@ -395,16 +456,37 @@ pub fn interp_to_vm(value: &IValue) -> Result<Value, String> {
         IValue::Undefined => Ok(Value::Undefined),
         IValue::BuiltinFunction(name) => Ok(Value::Builtin(Rc::from(name.as_str()))),
         IValue::Array(items) => {
-            let converted: Vec<Value> = items.borrow().0.iter().map(interp_to_vm).collect::<Result<_, _>>()?;
-            Ok(Value::Array(Rc::new(RefCell::new(converted))))
+            let ptr = Rc::as_ptr(items) as usize;
+            if let Some(value) = converting.get(&ptr) {
+                return Ok(value.clone());
+            }
+            let store = Rc::new(RefCell::new(Vec::new()));
+            converting.insert(ptr, Value::Array(Rc::clone(&store)));
+            let converted: Vec<Value> =
+                items.borrow().0.iter().map(|v| to_vm(v, converting)).collect::<Result<_, _>>()?;
+            *store.borrow_mut() = converted;
+            Ok(Value::Array(store))
         }
         IValue::Object(obj) => {
+            let ptr = Rc::as_ptr(obj) as usize;
+            if let Some(value) = converting.get(&ptr) {
+                return Ok(value.clone());
+            }
+            let store = Rc::new(RefCell::new(ObjMap::new()));
+            converting.insert(ptr, Value::Object(Rc::clone(&store)));
             let mut map = ObjMap::new();
             for (k, v) in &obj.borrow().map {
-                map.insert(k.clone(), interp_to_vm(v)?);
+                map.insert(k.clone(), to_vm(v, converting)?);
             }
-            Ok(Value::Object(Rc::new(RefCell::new(map))))
+            *store.borrow_mut() = map;
+            Ok(Value::Object(store))
         }
+        IValue::RegExp(re) => Ok(Value::RegExp {
+            pattern: Rc::from(re.pattern.as_str()),
+            flags: Rc::from(re.flags.as_str()),
+            compiled: Rc::clone(&re.compiled),
+            last_index: Rc::clone(&re.last_index),
+        }),
         IValue::Map(_)
         | IValue::Set(_)
         | IValue::Date(_)
@@ -419,7 +501,11 @@ pub fn interp_to_vm(value: &IValue) -> Result<Value, String> {
         | IValue::FinalizationRegistry(_)
         | IValue::AbortController { .. }
         | IValue::AbortSignal { .. }
-        | IValue::Iterator(_) => Ok(Value::Host(value.clone())),
+        | IValue::Iterator(_)
+        | IValue::Worker(_)
+        | IValue::TextCodec(_)
+        | IValue::Intl(_)
+        | IValue::Temporal(_) => Ok(Value::Host(value.clone())),
         other => Err(format!("значение типа '{}' из stdlib пока нельзя вернуть в VM", other.type_name())),
     }
 }
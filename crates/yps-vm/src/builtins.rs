@@ -25,6 +25,11 @@ pub fn is_builtin(name: &str) -> bool {
             | "сОчередить"
             | "отменаЧутки"
             | "отменаИнтервала"
+            | "ебал"
+            | "Функция"
+            | "структурныйКлон"
+            | "вБазу64"
+            | "изБазы64"
     )
 }
 
@@ -27,9 +27,27 @@ pub enum Constant {
     Str(Rc<str>),
     Proto(Rc<FnProto>),
     Class(Rc<ClassBlueprint>),
+    Enum(Rc<EnumBlueprint>),
+    Shape(Rc<MatchShape>),
     Template(Rc<TemplateStrings>),
     RegExp { pattern: Rc<str>, flags: Rc<str> },
     Import(Rc<ImportRequest>),
+    EvalScope(Rc<EvalScope>),
+}
+
+/// Names a direct `ебал` call can see, outermost first: the caller's locals by stack slot and the
+/// variables of enclosing functions by the caller's upvalue index.
+#[derive(Debug, Clone, Default)]
+pub struct EvalScope {
+    pub bindings: Vec<EvalBinding>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EvalBinding {
+    pub name: String,
+    pub is_const: bool,
+    pub from_local: bool,
+    pub index: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +102,29 @@ pub struct ClassBlueprint {
     pub class_decorator_count: u32,
 }
 
+/// Members of an `еээ` in declaration order; `true` marks a member whose initializer value is on the
+/// stack.
+#[derive(Debug, Clone)]
+pub struct EnumBlueprint {
+    pub members: Vec<(String, bool)>,
+}
+
+/// Shape a `базарпо` arm checks before destructuring: arrays of the right length and objects that
+/// own every named key.
+#[derive(Debug, Clone)]
+pub enum MatchShape {
+    Any,
+    /// The pattern has a default, so `неибу` fits as well.
+    Defaulted(Box<MatchShape>),
+    Array {
+        elements: Vec<MatchShape>,
+        rest: bool,
+    },
+    Object {
+        props: Vec<(String, MatchShape)>,
+    },
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct UpvalueDesc {
     pub from_parent_local: bool,
@@ -98,6 +139,8 @@ pub struct FnProto {
     pub is_method: bool,
     pub is_generator: bool,
     pub is_async: bool,
+    /// The body reads `аргументы`, which then takes the local slot right after the parameters.
+    pub uses_arguments: bool,
     pub upvalues: Vec<UpvalueDesc>,
     pub chunk: Chunk,
 }
@@ -175,12 +218,15 @@ pub enum Op {
 
     Call(u16),
     CallSpread,
+    DirectEval(ConstIdx, u16),
     Closure(ConstIdx),
     Return,
     Yield,
     YieldDelegate,
     Await,
     DynamicImport,
+    ImportMeta,
+    ClassOf,
 
     NewArray(u32),
     ArrPush,
@@ -208,6 +254,9 @@ pub enum Op {
     RecordExport(ConstIdx),
 
     BuildClass(ConstIdx),
+    BuildEnum(ConstIdx),
+    MatchShape(ConstIdx),
+    NoMatch,
     New(u16),
     NewSpread,
     Invoke(ConstIdx, u16),
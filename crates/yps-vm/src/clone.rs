@@ -0,0 +1,90 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yps_interpreter::value::Value as IValue;
+use yps_interpreter::worker::Message;
+use yps_lexer::Span;
+
+use crate::error::VmError;
+use crate::value::{ObjMap, Value, is_internal_key};
+use crate::vm::Vm;
+
+impl Vm {
+    /// `структурныйКлон(значение)`: arrays and objects are copied here so the copies stay VM
+    /// values; host values go through the same [`Message`] a `Воркер` uses.
+    pub(crate) fn structured_clone(&mut self, args: &[Value], span: Span) -> Result<Value, VmError> {
+        let value = args.first().cloned().unwrap_or(Value::Undefined);
+        let mut cloner = Cloner { vm: self, made: HashMap::new(), hosts: Vec::new() };
+        cloner.clone_value(&value).map_err(|m| VmError::new(format!("'структурныйКлон': {m}"), span))
+    }
+}
+
+struct Cloner<'a> {
+    vm: &'a Vm,
+    /// Copies of the arrays and objects met so far, by address of the original.
+    made: HashMap<usize, Value>,
+    /// Copies of the host values met so far, next to their originals.
+    hosts: Vec<(IValue, Value)>,
+}
+
+impl Cloner<'_> {
+    fn clone_value(&mut self, value: &Value) -> Result<Value, String> {
+        Ok(match value {
+            Value::Number(_) | Value::BigInt(_) | Value::Str(_) | Value::Bool(_) | Value::Null | Value::Undefined => {
+                value.clone()
+            }
+            Value::Array(rc) => {
+                let ptr = Rc::as_ptr(rc) as usize;
+                if let Some(copy) = self.made.get(&ptr) {
+                    return Ok(copy.clone());
+                }
+                let copy = self.vm.gc_array(Vec::new());
+                self.made.insert(ptr, copy.clone());
+                let items = rc.borrow().clone();
+                let cloned = items.iter().map(|item| self.clone_value(item)).collect::<Result<Vec<_>, _>>()?;
+                if let Value::Array(target) = &copy {
+                    *target.borrow_mut() = cloned;
+                }
+                copy
+            }
+            Value::Object(rc) => {
+                let ptr = Rc::as_ptr(rc) as usize;
+                if let Some(copy) = self.made.get(&ptr) {
+                    return Ok(copy.clone());
+                }
+                let copy = self.vm.gc_object(ObjMap::new());
+                self.made.insert(ptr, copy.clone());
+                let entries: Vec<(String, Value)> = rc
+                    .borrow()
+                    .iter()
+                    .filter(|(key, _)| !is_internal_key(key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                for (key, value) in entries {
+                    let value = self.clone_value(&value)?;
+                    if let Value::Object(target) = &copy {
+                        target.borrow_mut().insert(key, value);
+                    }
+                }
+                copy
+            }
+            Value::RegExp { pattern, flags, compiled, .. } => Value::RegExp {
+                pattern: Rc::clone(pattern),
+                flags: Rc::clone(flags),
+                compiled: Rc::clone(compiled),
+                last_index: Rc::new(RefCell::new(0)),
+            },
+            Value::Host(iv) => {
+                if let Some((_, copy)) = self.hosts.iter().find(|(original, _)| original == iv) {
+                    return Ok(copy.clone());
+                }
+                let copy = Message::new(iv, &[]).map(Message::into_value)?;
+                let copy = crate::bridge::interp_to_vm(&copy)?;
+                self.hosts.push((iv.clone(), copy.clone()));
+                copy
+            }
+            other => return Err(format!("значение типа '{}' нельзя клонировать", other.type_name())),
+        })
+    }
+}
@@ -1,19 +1,21 @@
 use std::rc::Rc;
 
+use yps_interpreter::symbols;
 use yps_lexer::Span;
 use yps_parser::ast::{
-    BinaryOp, Block, ClassMember, ExportKind, Expr, Identifier, ImportSpec, Literal, ObjectEntry, Param, Pattern,
-    PostfixOp, Program, PropKey, Stmt, TemplatePart, UnaryOp,
+    BinaryOp, Block, ClassMember, EnumMember, ExportKind, Expr, Identifier, ImportSpec, Literal, MatchArm,
+    MatchPattern, ObjectEntry, Param, Pattern, PostfixOp, Program, PropKey, Stmt, TemplatePart, UnaryOp,
 };
 
 use crate::chunk::{
-    Chunk, ClassBlueprint, ClassMemberDesc, Constant, FnProto, ImportBinding, ImportRequest, MemberKind, Op, Slot,
-    TemplateStrings, UpvalueDesc,
+    Chunk, ClassBlueprint, ClassMemberDesc, Constant, EnumBlueprint, EvalBinding, EvalScope, FnProto, ImportBinding,
+    ImportRequest, MatchShape, MemberKind, Op, Slot, TemplateStrings, UpvalueDesc,
 };
 use crate::error::CompileError;
 use crate::value::{string_to_number, to_int32, to_uint32};
 
 const THIS_LOCAL: &str = "\0this";
+const EVAL: &str = "ебал";
 
 struct Local {
     name: String,
@@ -65,6 +67,7 @@ struct FnState {
     is_method: bool,
     is_generator: bool,
     is_async: bool,
+    uses_arguments: bool,
     locals: Vec<Local>,
     upvalues: Vec<UpvalueDesc>,
     scope_depth: i32,
@@ -93,6 +96,7 @@ impl FnState {
             is_method: false,
             is_generator: false,
             is_async: false,
+            uses_arguments: false,
             locals,
             upvalues: Vec::new(),
             scope_depth: 0,
@@ -113,17 +117,37 @@ enum VarLoc {
 
 pub struct Compiler {
     funcs: Vec<FnState>,
+    /// Set for code compiled at runtime: its spans point into a string, so every instruction
+    /// reports the call instead.
+    call_site: Option<Span>,
 }
 
 pub fn compile_program(program: &Program) -> Result<Rc<FnProto>, CompileError> {
-    let mut c = Compiler { funcs: vec![FnState::new(FnKind::Script, String::from("<скрипт>"))] };
+    compile_script(program, false)
+}
+
+/// Like [`compile_program`], but a trailing expression statement becomes the script's return value.
+pub fn compile_repl(program: &Program) -> Result<Rc<FnProto>, CompileError> {
+    compile_script(program, true)
+}
+
+fn compile_script(program: &Program, keep_last: bool) -> Result<Rc<FnProto>, CompileError> {
+    let mut c = Compiler { funcs: vec![FnState::new(FnKind::Script, String::from("<скрипт>"))], call_site: None };
     let span = Span { start: 0, end: 0 };
-    c.compile_stmt_list(&program.items)?;
+    match program.items.split_last() {
+        Some((Stmt::Expr { expr, .. }, rest)) if keep_last => {
+            c.compile_stmt_list(rest)?;
+            c.compile_expr(expr)?;
+        }
+        _ => {
+            c.compile_stmt_list(&program.items)?;
+            c.emit(Op::Undefined, span);
+        }
+    }
     let global_using = c.cur().using_counts.last().copied().unwrap_or(0);
     if global_using > 0 {
         c.emit(Op::DisposeScope(global_using), span);
     }
-    c.emit(Op::Undefined, span);
     c.emit(Op::Return, span);
     let state = c.funcs.pop().expect("script frame");
     Ok(Rc::new(FnProto {
@@ -133,6 +157,49 @@ pub fn compile_program(program: &Program) -> Result<Rc<FnProto>, CompileError> {
         is_method: false,
         is_generator: false,
         is_async: false,
+        uses_arguments: false,
+        upvalues: state.upvalues,
+        chunk: state.chunk,
+    }))
+}
+
+/// Compiles the code of `ебал` or `Функция` into a function that returns the value of its last
+/// expression statement. Its upvalues index `scope.bindings` from 1; declarations stay local to it.
+pub fn compile_eval(program: &Program, scope: &EvalScope, call_site: Span) -> Result<Rc<FnProto>, CompileError> {
+    let mut outer = FnState::new(FnKind::Function, String::new());
+    for binding in &scope.bindings {
+        outer.locals.push(Local {
+            name: binding.name.clone(),
+            depth: 0,
+            is_const: binding.is_const,
+            is_captured: false,
+            initialized: true,
+        });
+    }
+    let mut c = Compiler {
+        funcs: vec![outer, FnState::new(FnKind::Function, format!("<{EVAL}>"))],
+        call_site: Some(call_site),
+    };
+    match program.items.split_last() {
+        Some((Stmt::Expr { expr, .. }, rest)) => {
+            c.compile_stmt_list(rest)?;
+            c.compile_expr(expr)?;
+        }
+        _ => {
+            c.compile_stmt_list(&program.items)?;
+            c.emit(Op::Undefined, call_site);
+        }
+    }
+    c.emit(Op::Return, call_site);
+    let state = c.funcs.pop().expect("eval frame");
+    Ok(Rc::new(FnProto {
+        name: state.name,
+        arity: 0,
+        has_rest: false,
+        is_method: false,
+        is_generator: false,
+        is_async: false,
+        uses_arguments: false,
         upvalues: state.upvalues,
         chunk: state.chunk,
     }))
@@ -144,6 +211,7 @@ impl Compiler {
     }
 
     fn emit(&mut self, op: Op, span: Span) -> usize {
+        let span = self.call_site.unwrap_or(span);
         self.cur().chunk.push_op(op, span)
     }
 
@@ -268,7 +336,7 @@ impl Compiler {
 
     fn compile_global_list(&mut self, stmts: &[Stmt]) -> Result<(), CompileError> {
         for stmt in stmts {
-            if let Stmt::FunctionDecl { name, params, body, is_generator, is_async, span } = stmt {
+            if let Stmt::FunctionDecl { name, params, body, is_generator, is_async, span, .. } = stmt {
                 self.compile_function_decl(name, params, body, *is_generator, *is_async, *span)?;
             }
         }
@@ -284,7 +352,7 @@ impl Compiler {
     fn compile_local_list(&mut self, stmts: &[Stmt]) -> Result<(), CompileError> {
         for stmt in stmts {
             match stmt {
-                Stmt::VarDecl { pattern: Pattern::Identifier(id), is_const, span, init: _ } => {
+                Stmt::VarDecl { pattern: Pattern::Identifier(id), is_const, span, .. } => {
                     self.emit(Op::Undefined, *span);
                     self.reserve_local(&id.name, *is_const);
                 }
@@ -296,7 +364,7 @@ impl Compiler {
             }
         }
         for stmt in stmts {
-            if let Stmt::FunctionDecl { name, params, body, is_generator, is_async, span } = stmt {
+            if let Stmt::FunctionDecl { name, params, body, is_generator, is_async, span, .. } = stmt {
                 self.compile_named_callable(&name.name, params, body, *is_generator, *is_async, *span)?;
                 self.store_reserved_local(&name.name, *span);
                 self.mark_initialized(&name.name);
@@ -325,8 +393,10 @@ impl Compiler {
 
     fn compile_stmt(&mut self, stmt: &Stmt) -> Result<(), CompileError> {
         match stmt {
-            Stmt::VarDecl { pattern, init, is_const, span } => self.compile_var_decl(pattern, init, *is_const, *span),
-            Stmt::FunctionDecl { name, params, body, is_generator, is_async, span } => {
+            Stmt::VarDecl { pattern, init, is_const, span, .. } => {
+                self.compile_var_decl(pattern, init, *is_const, *span)
+            }
+            Stmt::FunctionDecl { name, params, body, is_generator, is_async, span, .. } => {
                 self.compile_function_decl(name, params, body, *is_generator, *is_async, *span)
             }
             Stmt::Expr { expr, span } => {
@@ -340,7 +410,7 @@ impl Compiler {
                 self.end_scope(block.span);
                 Ok(())
             }
-            Stmt::Empty { .. } => Ok(()),
+            Stmt::Empty { .. } | Stmt::TypeAlias { .. } => Ok(()),
             Stmt::If { condition, then_branch, else_branch, span } => {
                 self.compile_expr(condition)?;
                 let else_jump = self.emit(Op::JumpIfFalse(0), *span);
@@ -404,10 +474,12 @@ impl Compiler {
                 self.compile_for_of(variable, iterable, body, true, *span)
             }
             Stmt::Debugger { .. } => Ok(()),
+            Stmt::Error { span } => Err(CompileError::new("синтаксическая ошибка в этом месте", *span)),
             Stmt::ClassDecl { name, super_class, members, decorators, span } => {
                 self.compile_class_decl(name, super_class.as_ref(), members, decorators, *span)
             }
             Stmt::Using { name, init, is_await, span } => self.compile_using(name, init, *is_await, *span),
+            Stmt::EnumDecl { name, members, span } => self.compile_enum(name, members, *span),
             Stmt::Import { specifiers, source, attributes, span } => {
                 self.compile_import(specifiers, source, attributes, *span)
             }
@@ -759,6 +831,22 @@ impl Compiler {
         Ok(())
     }
 
+    fn compile_enum(&mut self, name: &Identifier, members: &[EnumMember], span: Span) -> Result<(), CompileError> {
+        for init in members.iter().filter_map(|m| m.init.as_ref()) {
+            self.compile_expr(init)?;
+        }
+        let members = members.iter().map(|m| (m.name.name.clone(), m.init.is_some())).collect();
+        let idx = self.cur().chunk.add_constant(Constant::Enum(Rc::new(EnumBlueprint { members })));
+        self.emit(Op::BuildEnum(idx), span);
+        if self.is_global_scope() {
+            let idx = self.str_const(&name.name);
+            self.emit(Op::DefineGlobal(idx, true), span);
+        } else {
+            self.add_local(&name.name, true);
+        }
+        Ok(())
+    }
+
     fn compile_import(
         &mut self,
         specifiers: &[ImportSpec],
@@ -1002,6 +1090,10 @@ impl Compiler {
             let slot_name = if param.pattern.is_some() { "\0param" } else { param.name.name.as_str() };
             self.add_local(slot_name, false);
         }
+        if kind.binds_this && yps_interpreter::reads_arguments(params, body) {
+            self.cur().uses_arguments = true;
+            self.add_local(symbols::ARGUMENTS, false);
+        }
         for (i, param) in params.iter().enumerate() {
             if let Some(default) = &param.default {
                 let slot = (i + 1) as Slot;
@@ -1035,6 +1127,7 @@ impl Compiler {
             is_method: state.is_method,
             is_generator: state.is_generator,
             is_async: state.is_async,
+            uses_arguments: state.uses_arguments,
             upvalues: state.upvalues,
             chunk: state.chunk,
         });
@@ -1043,6 +1136,99 @@ impl Compiler {
         Ok(())
     }
 
+    /// `базарпо` compiles to a closure called on the subject right away: arm bindings become locals of
+    /// their own frame instead of landing between temporaries of the enclosing expression.
+    fn compile_match(&mut self, subject: &Expr, arms: &[MatchArm], span: Span) -> Result<(), CompileError> {
+        self.funcs.push(FnState::new(FnKind::Function, String::new()));
+        self.cur().arity = 1;
+        self.add_local("\0subject", false);
+        for arm in arms {
+            self.compile_match_arm(arm)?;
+        }
+        self.emit(Op::GetLocal(1), span);
+        self.emit(Op::NoMatch, span);
+
+        let state = self.funcs.pop().expect("function frame");
+        if state.chunk.code.iter().any(|op| matches!(op, Op::Yield | Op::YieldDelegate)) {
+            return Err(CompileError::new("'поебалу' внутри 'базарпо' не поддерживается VM", span));
+        }
+        let is_async = state.chunk.code.contains(&Op::Await);
+        let proto = Rc::new(FnProto {
+            name: state.name,
+            arity: state.arity,
+            has_rest: false,
+            is_method: false,
+            is_generator: false,
+            is_async,
+            uses_arguments: false,
+            upvalues: state.upvalues,
+            chunk: state.chunk,
+        });
+        let idx = self.cur().chunk.add_constant(Constant::Proto(proto));
+        self.emit(Op::Closure(idx), span);
+        self.compile_expr(subject)?;
+        self.emit(Op::Call(1), span);
+        if is_async {
+            self.emit(Op::Await, span);
+        }
+        Ok(())
+    }
+
+    fn compile_match_arm(&mut self, arm: &MatchArm) -> Result<(), CompileError> {
+        let span = arm.span;
+        let mut misses = Vec::new();
+        let pattern = match &arm.pattern {
+            MatchPattern::Wildcard { .. } => None,
+            MatchPattern::Bind(pattern) => Some(pattern),
+            MatchPattern::Value(expected) => {
+                self.emit(Op::GetLocal(1), span);
+                self.compile_expr(expected)?;
+                self.emit(Op::StrictEq, span);
+                misses.push(self.emit(Op::JumpIfFalse(0), span));
+                None
+            }
+            MatchPattern::Instance { class, pattern, .. } => {
+                self.emit(Op::GetLocal(1), span);
+                self.compile_expr(class)?;
+                self.emit(Op::Instanceof, span);
+                misses.push(self.emit(Op::JumpIfFalse(0), span));
+                pattern.as_ref()
+            }
+        };
+        if let Some(pattern) = pattern
+            && !matches!(pattern, Pattern::Identifier(_))
+        {
+            let idx = self.cur().chunk.add_constant(Constant::Shape(Rc::new(match_shape(pattern))));
+            self.emit(Op::GetLocal(1), span);
+            self.emit(Op::MatchShape(idx), span);
+            misses.push(self.emit(Op::JumpIfFalse(0), span));
+        }
+        self.begin_scope();
+        if let Some(pattern) = pattern {
+            self.emit(Op::GetLocal(1), span);
+            self.destructure_pattern(pattern, false, false, span)?;
+        }
+        let rejected = match &arm.guard {
+            Some(guard) => {
+                self.compile_expr(guard)?;
+                Some(self.emit(Op::JumpIfFalse(0), span))
+            }
+            None => None,
+        };
+        self.compile_expr(&arm.body)?;
+        self.emit(Op::Return, span);
+        if let Some(rejected) = rejected {
+            let here = self.cur().chunk.code.len();
+            self.cur().chunk.patch_jump(rejected, here);
+        }
+        self.end_scope(span);
+        let here = self.cur().chunk.code.len();
+        for miss in misses {
+            self.cur().chunk.patch_jump(miss, here);
+        }
+        Ok(())
+    }
+
     fn compile_class_decl(
         &mut self,
         name: &Identifier,
@@ -1086,7 +1272,9 @@ impl Compiler {
         for m in members {
             match m {
                 ClassMember::Constructor { .. } => {}
-                ClassMember::Method { name: mn, params, body, is_static, is_private, decorators, span: mspan } => {
+                ClassMember::Method {
+                    name: mn, params, body, is_static, is_private, decorators, span: mspan, ..
+                } => {
                     self.compile_method(&mn.name, params, body, false, *mspan)?;
                     descs.push(ClassMemberDesc {
                         kind: if *is_static { MemberKind::StaticMethod } else { MemberKind::Method },
@@ -1119,7 +1307,7 @@ impl Compiler {
                         decorator_count: decorators.len() as u32,
                     });
                 }
-                ClassMember::Field { name: fn_, init, is_static, is_private, decorators, span: fspan } => {
+                ClassMember::Field { name: fn_, init, is_static, is_private, decorators, span: fspan, .. } => {
                     let has_value = init.is_some();
                     if let Some(init_expr) = init {
                         let body = Block {
@@ -1456,8 +1644,10 @@ impl Compiler {
                 self.emit(Op::GetProp(idx), *span);
                 Ok(())
             }
-            Expr::ArrowFunction { params, body, is_async, span } => self.compile_arrow(params, body, *is_async, *span),
-            Expr::FunctionExpr { name, params, body, is_generator, is_async, span } => {
+            Expr::ArrowFunction { params, body, is_async, span, .. } => {
+                self.compile_arrow(params, body, *is_async, *span)
+            }
+            Expr::FunctionExpr { name, params, body, is_generator, is_async, span, .. } => {
                 let fname = name.as_ref().map(|n| n.name.as_str()).unwrap_or("");
                 self.compile_named_callable(fname, params, body, *is_generator, *is_async, *span)
             }
@@ -1492,6 +1682,26 @@ impl Compiler {
             Expr::Super { span } => {
                 Err(CompileError::new("'яга' (super) допустим только как 'яга(...)' или 'яга.член'", *span))
             }
+            Expr::NewTarget { span } => {
+                // Arrow functions see the target of the function that binds their `тырыпыры`.
+                let in_ctor = self
+                    .funcs
+                    .iter()
+                    .rev()
+                    .find(|f| f.locals[0].name == THIS_LOCAL)
+                    .is_some_and(|f| f.is_method && f.name == "конструктор");
+                if in_ctor {
+                    self.compile_var_get(THIS_LOCAL, *span)?;
+                    self.emit(Op::ClassOf, *span);
+                } else {
+                    self.emit(Op::Undefined, *span);
+                }
+                Ok(())
+            }
+            Expr::ImportMeta { span } => {
+                self.emit(Op::ImportMeta, *span);
+                Ok(())
+            }
             Expr::TaggedTemplate { tag, quasis, expressions, span } => {
                 self.compile_tagged_template(tag, quasis, expressions, *span)
             }
@@ -1516,6 +1726,7 @@ impl Compiler {
                 self.emit(if *delegate { Op::YieldDelegate } else { Op::Yield }, *span);
                 Ok(())
             }
+            Expr::Match { subject, arms, span } => self.compile_match(subject, arms, *span),
             other => {
                 Err(CompileError::new(format!("выражение не поддерживается VM: {}", expr_kind(other)), other.span()))
             }
@@ -1652,7 +1863,7 @@ impl Compiler {
     }
 
     fn compile_object_value(&mut self, value: &Expr) -> Result<(), CompileError> {
-        if let Expr::ArrowFunction { params, body, is_async, span } = value {
+        if let Expr::ArrowFunction { params, body, is_async, span, .. } = value {
             return self.compile_function("", params, body, *is_async, *span);
         }
         self.compile_expr(value)
@@ -1992,11 +2203,60 @@ impl Compiler {
                 self.emit(Op::Invoke(idx, args.len() as u16), span);
                 return Ok(());
             }
+            Expr::Identifier(id)
+                if id.name == EVAL
+                    && !args.iter().any(|a| matches!(a, Expr::Spread { .. }))
+                    && matches!(self.resolve(EVAL), VarLoc::Global(_)) =>
+            {
+                self.compile_expr(callee)?;
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
+                let scope = self.eval_scope();
+                let idx = self.cur().chunk.add_constant(Constant::EvalScope(Rc::new(scope)));
+                self.emit(Op::DirectEval(idx, args.len() as u16), span);
+                return Ok(());
+            }
             _ => self.compile_expr(callee)?,
         }
         self.compile_call_args(args, span)
     }
 
+    /// Everything a direct `ебал` here can reach. The caller's locals are marked captured, since
+    /// the evaluated code may keep closures over them.
+    fn eval_scope(&mut self) -> EvalScope {
+        let top = self.funcs.len() - 1;
+        let visible = |name: &str| !name.is_empty() && (!name.starts_with('\0') || name == THIS_LOCAL);
+        let mut outer: Vec<(String, bool)> = Vec::new();
+        for local in self.funcs[..top].iter().flat_map(|f| &f.locals).rev() {
+            if visible(&local.name) && !outer.iter().any(|(name, _)| *name == local.name) {
+                outer.push((local.name.clone(), local.is_const));
+            }
+        }
+        let mut bindings = Vec::new();
+        for (name, is_const) in outer.into_iter().rev() {
+            if Self::resolve_local(&self.funcs, top, &name).is_some() {
+                continue;
+            }
+            if let Some(slot) = Self::resolve_upvalue(&mut self.funcs, top, &name) {
+                bindings.push(EvalBinding { name, is_const, from_local: false, index: slot as usize });
+            }
+        }
+        for (i, local) in self.cur().locals.iter_mut().enumerate() {
+            if (i == 0 && local.name != THIS_LOCAL) || !visible(&local.name) {
+                continue;
+            }
+            local.is_captured = true;
+            bindings.push(EvalBinding {
+                name: local.name.clone(),
+                is_const: local.is_const,
+                from_local: true,
+                index: i,
+            });
+        }
+        EvalScope { bindings }
+    }
+
     fn compile_optional_chain(
         &mut self,
         base: &Expr,
@@ -2084,6 +2344,23 @@ fn is_loop_stmt(stmt: &Stmt) -> bool {
     matches!(stmt, Stmt::While { .. } | Stmt::For { .. } | Stmt::DoWhile { .. })
 }
 
+fn match_shape(pattern: &Pattern) -> MatchShape {
+    match pattern {
+        Pattern::Identifier(_) => MatchShape::Any,
+        Pattern::Default { pattern, .. } => MatchShape::Defaulted(Box::new(match_shape(pattern))),
+        Pattern::Array { elements, rest, .. } => MatchShape::Array {
+            elements: elements.iter().map(|element| element.as_ref().map_or(MatchShape::Any, match_shape)).collect(),
+            rest: rest.is_some(),
+        },
+        Pattern::Object { properties, .. } => MatchShape::Object {
+            props: properties
+                .iter()
+                .map(|prop| (prop.key.name.clone(), prop.value.as_ref().map_or(MatchShape::Any, match_shape)))
+                .collect(),
+        },
+    }
+}
+
 fn decl_names(stmt: &Stmt) -> Vec<String> {
     match stmt {
         Stmt::VarDecl { pattern, .. } => {
@@ -2091,7 +2368,9 @@ fn decl_names(stmt: &Stmt) -> Vec<String> {
             pattern_names(pattern, &mut names);
             names
         }
-        Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } => vec![name.name.clone()],
+        Stmt::FunctionDecl { name, .. } | Stmt::ClassDecl { name, .. } | Stmt::EnumDecl { name, .. } => {
+            vec![name.name.clone()]
+        }
         _ => Vec::new(),
     }
 }
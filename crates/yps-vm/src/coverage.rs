@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yps_interpreter::coverage;
+use yps_lexer::Span;
+
+use crate::chunk::{Constant, FnProto};
+
+/// `sources` id of the file every compiled function came from, shared with the VMs that run
+/// imported modules; coverage and the profiler both read it.
+#[derive(Default)]
+pub(crate) struct ProtoFiles {
+    files: HashMap<*const FnProto, usize>,
+    /// Keeps registered protos alive so that their addresses are never reused.
+    keep: Vec<Rc<FnProto>>,
+}
+
+impl ProtoFiles {
+    pub(crate) fn register(&mut self, proto: &Rc<FnProto>, file: usize) {
+        if self.files.insert(Rc::as_ptr(proto), file).is_some() {
+            return;
+        }
+        self.keep.push(Rc::clone(proto));
+        for constant in &proto.chunk.constants {
+            if let Constant::Proto(inner) = constant {
+                self.register(inner, file);
+            }
+        }
+    }
+
+    pub(crate) fn file(&self, proto: &Rc<FnProto>) -> Option<usize> {
+        self.files.get(&Rc::as_ptr(proto)).copied()
+    }
+
+    /// Each instruction is its own site, so that two instructions sharing a span count once.
+    pub(crate) fn hit(&self, proto: &Rc<FnProto>, ip: usize, span: Span) {
+        if let Some(&file) = self.files.get(&Rc::as_ptr(proto)) {
+            coverage::hit(file, span, (Rc::as_ptr(proto) as usize, ip));
+        }
+    }
+}
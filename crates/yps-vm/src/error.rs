@@ -27,11 +27,22 @@ pub struct VmError {
     pub message: String,
     pub span: Span,
     pub thrown: Option<Box<crate::value::Value>>,
+    /// A hint for fixing the error, e.g. the name a misspelled one probably meant.
+    pub help: Option<String>,
 }
 
 impl VmError {
     pub fn new(message: impl Into<String>, span: Span) -> Self {
-        Self { message: message.into(), span, thrown: None }
+        Self { message: message.into(), span, thrown: None, help: None }
+    }
+
+    /// Adds "возможно, имелось в виду …" when one of `candidates` is close to `name`.
+    #[must_use]
+    pub fn with_did_you_mean<'a>(mut self, name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Self {
+        if let Some(found) = yps_lexer::suggest::closest(name, candidates) {
+            self.help = Some(format!("возможно, имелось в виду '{found}'"));
+        }
+        self
     }
 
     pub fn with_thrown(mut self, value: crate::value::Value) -> Self {
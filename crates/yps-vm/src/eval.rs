@@ -0,0 +1,63 @@
+use std::rc::Rc;
+
+use yps_interpreter::interpreter::eval_code::{function_source, parse_code};
+use yps_lexer::Span;
+
+use crate::chunk::{EvalScope, FnProto};
+use crate::compiler::compile_eval;
+use crate::error::VmError;
+use crate::value::{Closure, Value};
+use crate::vm::Vm;
+
+pub(crate) use yps_interpreter::interpreter::eval_code::{EVAL, FUNCTION_CTOR};
+
+impl Vm {
+    /// Hosts that run untrusted programs switch this off: `ебал` and `Функция` then throw instead
+    /// of turning strings into code.
+    pub fn set_allow_eval(&mut self, allow: bool) {
+        self.allow_eval = allow;
+    }
+
+    /// Indirect `ебал` and `Функция`; a direct `ебал` goes through `Op::DirectEval` instead.
+    pub(crate) fn try_call_code_builtin(
+        &mut self,
+        name: &str,
+        args: &[Value],
+        span: Span,
+    ) -> Option<Result<Value, VmError>> {
+        let code = match name {
+            EVAL => match args.first() {
+                Some(Value::Str(text)) => self.compile_code(EVAL, text, &EvalScope::default(), span),
+                other => return Some(Ok(other.cloned().unwrap_or(Value::Undefined))),
+            },
+            FUNCTION_CTOR => {
+                let (body, params) = match args.split_last() {
+                    Some((body, params)) => (body.to_string(), params.iter().map(Value::to_string).collect::<Vec<_>>()),
+                    None => (String::new(), Vec::new()),
+                };
+                self.compile_code(FUNCTION_CTOR, &function_source(&params, &body), &EvalScope::default(), span)
+            }
+            _ => return None,
+        };
+        Some(code.and_then(|proto| {
+            let closure = Rc::new(Closure { proto, upvalues: Vec::new() });
+            self.call_value(Value::Function(closure), None, &[], span)
+        }))
+    }
+
+    /// Parses and compiles `text` at runtime; see [`parse_code`].
+    pub(crate) fn compile_code(
+        &self,
+        what: &str,
+        text: &str,
+        scope: &EvalScope,
+        span: Span,
+    ) -> Result<Rc<FnProto>, VmError> {
+        if !self.allow_eval {
+            return Err(VmError::new(format!("'{what}' запрещён: выполнение кода из строки отключено"), span));
+        }
+        let program = parse_code(what, text).map_err(|message| VmError::new(message, span))?;
+        compile_eval(&program, scope, span)
+            .map_err(|e| VmError::new(format!("Ошибка разбора в '{what}': {}", e.message), span))
+    }
+}
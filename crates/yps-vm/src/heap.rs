@@ -0,0 +1,316 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use yps_interpreter::heap_snapshot::{EdgeType, GC_ROOTS, HeapGraph, NodeType};
+use yps_interpreter::profile::frame_name;
+
+use crate::value::{
+    CLASS_TAG, ClassDef, Closure, Delegate, ForIter, GenState, PROTO_KEY, PromiseState, UpvalueState, Value,
+};
+
+type UpCell = Rc<RefCell<UpvalueState>>;
+
+enum Expand {
+    Value(Value),
+    Closure(Rc<Closure>),
+    Class(Rc<ClassDef>),
+    Gen(Rc<RefCell<GenState>>),
+    Promise(Rc<RefCell<PromiseState>>),
+    Upvalue(UpCell),
+}
+
+/// Builds the heap graph from the roots `Vm::collect_cycles` marks. Open upvalues point into the
+/// VM stack, which the walker gets up front to resolve them.
+pub(crate) struct Walker<'a> {
+    graph: HeapGraph,
+    stack: &'a [Value],
+    work: Vec<(usize, Expand)>,
+    roots: usize,
+}
+
+impl<'a> Walker<'a> {
+    pub(crate) fn new(stack: &'a [Value]) -> Self {
+        Self { graph: HeapGraph::new(), stack, work: Vec::new(), roots: 0 }
+    }
+
+    pub(crate) fn root(&mut self, value: &Value) {
+        if let Some(node) = self.value(value) {
+            self.add_root(node);
+        }
+    }
+
+    pub(crate) fn root_upvalue(&mut self, up: &UpCell) {
+        let node = self.upvalue(up);
+        self.add_root(node);
+    }
+
+    fn add_root(&mut self, node: usize) {
+        self.graph.add_element(GC_ROOTS, self.roots, node);
+        self.roots += 1;
+    }
+
+    pub(crate) fn finish(mut self) -> HeapGraph {
+        while let Some((node, item)) = self.work.pop() {
+            match item {
+                Expand::Value(value) => self.expand_value(node, &value),
+                Expand::Closure(closure) => self.expand_closure(node, &closure),
+                Expand::Class(class) => self.expand_class(node, &class),
+                Expand::Gen(state) => self.expand_gen(node, &state.borrow()),
+                Expand::Promise(state) => self.expand_promise(node, &state.borrow()),
+                Expand::Upvalue(up) => {
+                    let value = match &*up.borrow() {
+                        UpvalueState::Open(slot) => self.stack.get(*slot).cloned(),
+                        UpvalueState::Closed(value) => Some(value.clone()),
+                    };
+                    if let Some(value) = value {
+                        self.edge(node, EdgeType::Internal, "value", &value);
+                    }
+                }
+            }
+        }
+        self.graph
+    }
+
+    fn node(&mut self, ptr: usize, kind: NodeType, name: &str, size: usize, expand: Expand) -> usize {
+        let (node, fresh) = self.graph.node_for(ptr, kind, name, size);
+        if fresh {
+            self.work.push((node, expand));
+        }
+        node
+    }
+
+    fn closure(&mut self, closure: &Rc<Closure>) -> usize {
+        let name = frame_name(&closure.proto.name);
+        let size = closure.upvalues.len();
+        self.node(Rc::as_ptr(closure) as usize, NodeType::Closure, &name, size, Expand::Closure(Rc::clone(closure)))
+    }
+
+    fn class(&mut self, class: &Rc<ClassDef>) -> usize {
+        self.node(Rc::as_ptr(class) as usize, NodeType::Closure, &class.name, 0, Expand::Class(Rc::clone(class)))
+    }
+
+    fn generator(&mut self, state: &Rc<RefCell<GenState>>) -> usize {
+        self.node(Rc::as_ptr(state) as usize, NodeType::Object, "Генератор", 0, Expand::Gen(Rc::clone(state)))
+    }
+
+    fn promise(&mut self, state: &Rc<RefCell<PromiseState>>) -> usize {
+        self.node(Rc::as_ptr(state) as usize, NodeType::Object, "Обещание", 0, Expand::Promise(Rc::clone(state)))
+    }
+
+    fn upvalue(&mut self, up: &UpCell) -> usize {
+        self.node(Rc::as_ptr(up) as usize, NodeType::Hidden, "(захват)", 0, Expand::Upvalue(Rc::clone(up)))
+    }
+
+    /// Node for a heap value; `None` for primitives that live inline.
+    fn value(&mut self, value: &Value) -> Option<usize> {
+        let node = match value {
+            Value::Number(_)
+            | Value::BigInt(_)
+            | Value::Bool(_)
+            | Value::Null
+            | Value::Undefined
+            | Value::Builtin(_) => {
+                return None;
+            }
+            Value::Str(s) => {
+                let ptr = Rc::as_ptr(s).cast::<u8>() as usize;
+                self.node(ptr, NodeType::String, s, s.len(), Expand::Value(Value::Undefined))
+            }
+            Value::Array(rc) => {
+                let size = rc.borrow().len();
+                self.node(Rc::as_ptr(rc) as usize, NodeType::Object, "Массив", size, Expand::Value(value.clone()))
+            }
+            Value::Object(rc) => {
+                let (name, size) = {
+                    let map = rc.borrow();
+                    let name = match map.get(CLASS_TAG) {
+                        Some(Value::Class(class)) => class.name.clone(),
+                        _ => "Объект".to_string(),
+                    };
+                    (name, map.len())
+                };
+                self.node(Rc::as_ptr(rc) as usize, NodeType::Object, &name, size, Expand::Value(value.clone()))
+            }
+            Value::Function(closure) => self.closure(closure),
+            Value::Class(class) => self.class(class),
+            Value::Generator(state) => self.generator(state),
+            Value::Promise { state } | Value::PromiseCapability { state, .. } => self.promise(state),
+            Value::RegExp { pattern, .. } => {
+                let ptr = Rc::as_ptr(pattern).cast::<u8>() as usize;
+                self.node(ptr, NodeType::Regexp, pattern, 0, Expand::Value(Value::Undefined))
+            }
+            Value::Host(host) => self.graph.add_node(NodeType::Native, host.type_name(), 0),
+            _ => {
+                let node = self.graph.add_node(NodeType::Hidden, value.type_name(), 0);
+                self.work.push((node, Expand::Value(value.clone())));
+                node
+            }
+        };
+        Some(node)
+    }
+
+    fn edge(&mut self, from: usize, kind: EdgeType, name: &str, value: &Value) {
+        if let Some(to) = self.value(value) {
+            self.graph.add_edge(from, kind, name, to);
+        }
+    }
+
+    fn element(&mut self, from: usize, index: usize, value: &Value) {
+        if let Some(to) = self.value(value) {
+            self.graph.add_element(from, index, to);
+        }
+    }
+
+    fn expand_value(&mut self, node: usize, value: &Value) {
+        match value {
+            Value::Array(rc) => {
+                let elements = rc.borrow().clone();
+                for (i, element) in elements.iter().enumerate() {
+                    self.element(node, i, element);
+                }
+            }
+            Value::Object(rc) => {
+                let entries: Vec<(String, Value)> = rc.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                for (key, value) in &entries {
+                    let kind =
+                        if key == PROTO_KEY || key == CLASS_TAG { EdgeType::Internal } else { EdgeType::Property };
+                    self.edge(node, kind, key, value);
+                }
+            }
+            Value::BoundMethod { receiver, .. } => self.edge(node, EdgeType::Internal, "receiver", receiver),
+            Value::ForIter(rc) => {
+                let (values, generator) = match &*rc.borrow() {
+                    ForIter::Values { values, .. } => (values.clone(), None),
+                    ForIter::Generator(state) => (Vec::new(), Some(Rc::clone(state))),
+                };
+                for (i, value) in values.iter().enumerate() {
+                    self.element(node, i, value);
+                }
+                if let Some(state) = generator {
+                    let to = self.generator(&state);
+                    self.graph.add_edge(node, EdgeType::Internal, "generator", to);
+                }
+            }
+            Value::AsyncResume { coroutine, outer, .. } => {
+                let to = self.generator(coroutine);
+                self.graph.add_edge(node, EdgeType::Internal, "coroutine", to);
+                let to = self.promise(outer);
+                self.graph.add_edge(node, EdgeType::Internal, "promise", to);
+            }
+            Value::PromiseThenHandler { handler, resolve, reject, .. } => {
+                self.edge(node, EdgeType::Internal, "handler", handler);
+                self.edge(node, EdgeType::Internal, "resolve", resolve);
+                self.edge(node, EdgeType::Internal, "reject", reject);
+            }
+            Value::PromiseFinallyHandler { cb, cap } => {
+                self.edge(node, EdgeType::Internal, "callback", cb);
+                self.edge(node, EdgeType::Internal, "capability", cap);
+            }
+            Value::PromiseAggregateHandler { state, .. } => {
+                let (results, resolve, reject) = {
+                    let state = state.borrow();
+                    (state.results.clone(), state.resolve.clone(), state.reject.clone())
+                };
+                for (i, value) in results.iter().enumerate() {
+                    self.element(node, i, value);
+                }
+                self.edge(node, EdgeType::Internal, "resolve", &resolve);
+                self.edge(node, EdgeType::Internal, "reject", &reject);
+            }
+            _ => {}
+        }
+    }
+
+    fn expand_closure(&mut self, node: usize, closure: &Closure) {
+        for (i, up) in closure.upvalues.iter().enumerate() {
+            let to = self.upvalue(up);
+            self.graph.add_edge(node, EdgeType::Context, &i.to_string(), to);
+        }
+    }
+
+    fn expand_class(&mut self, node: usize, class: &ClassDef) {
+        let members = &class.members;
+        let methods: Vec<(String, Rc<Closure>)> = class
+            .constructor
+            .iter()
+            .map(|def| ("constructor", def))
+            .chain(members.methods.iter())
+            .chain(members.getters.iter())
+            .chain(members.setters.iter())
+            .chain(members.static_methods.iter())
+            .chain(members.static_getters.iter())
+            .chain(members.static_setters.iter())
+            .chain(
+                members.field_inits.iter().filter_map(|(name, init, _)| init.as_ref().map(|def| (name.as_str(), def))),
+            )
+            .map(|(name, def)| (name.to_string(), Rc::clone(def)))
+            .collect();
+        for (name, def) in &methods {
+            let to = self.closure(def);
+            self.graph.add_edge(node, EdgeType::Property, name, to);
+        }
+        for (name, _, default) in &members.field_inits {
+            if let Some(value) = default {
+                self.edge(node, EdgeType::Internal, name, value);
+            }
+        }
+        let statics: Vec<(String, Value)> =
+            class.static_fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        for (name, value) in &statics {
+            self.edge(node, EdgeType::Property, name, value);
+        }
+        if let Some(parent) = &class.parent {
+            let to = self.class(parent);
+            self.graph.add_edge(node, EdgeType::Internal, "parent", to);
+        }
+    }
+
+    fn expand_gen(&mut self, node: usize, state: &GenState) {
+        let to = self.closure(&state.closure);
+        self.graph.add_edge(node, EdgeType::Internal, "function", to);
+        if let Some(owner) = &state.owner {
+            let to = self.class(owner);
+            self.graph.add_edge(node, EdgeType::Internal, "owner", to);
+        }
+        for (i, value) in state.stack.iter().enumerate() {
+            self.element(node, i, value);
+        }
+        for frame in &state.frames {
+            let to = self.closure(&frame.closure);
+            self.graph.add_edge(node, EdgeType::Internal, "frame", to);
+        }
+        for up in &state.open_upvalues {
+            let to = self.upvalue(up);
+            self.graph.add_edge(node, EdgeType::Context, "open", to);
+        }
+        self.edge(node, EdgeType::Internal, "this", &state.this);
+        for value in &state.args {
+            self.edge(node, EdgeType::Internal, "argument", value);
+        }
+        match &state.delegate {
+            Some(Delegate::Generator(inner)) => {
+                let to = self.generator(inner);
+                self.graph.add_edge(node, EdgeType::Internal, "delegate", to);
+            }
+            Some(Delegate::Values { values, .. }) => {
+                for value in values {
+                    self.edge(node, EdgeType::Internal, "delegate", value);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn expand_promise(&mut self, node: usize, state: &PromiseState) {
+        match state {
+            PromiseState::Pending { on_resolve, on_reject } => {
+                for handler in on_resolve.iter().chain(on_reject.iter()) {
+                    self.edge(node, EdgeType::Internal, "reaction", handler);
+                }
+            }
+            PromiseState::Fulfilled(value) | PromiseState::Rejected(value) => {
+                self.edge(node, EdgeType::Internal, "value", value);
+            }
+        }
+    }
+}
@@ -7,20 +7,26 @@ use yps_parser::ast::Program;
 pub mod bridge;
 mod builtins;
 pub mod chunk;
+mod clone;
 pub mod compiler;
+mod coverage;
 pub mod error;
+mod eval;
 mod gc;
+mod heap;
+mod meta;
 mod methods;
 mod promise;
 pub mod regexp;
 pub mod value;
 pub mod vm;
+mod worker;
 
 #[cfg(test)]
 mod tests;
 
 pub use chunk::{FnProto, disassemble};
-pub use compiler::compile_program;
+pub use compiler::{compile_program, compile_repl};
 pub use error::{CompileError, ExecError, VmError};
 pub use value::Value;
 pub use vm::Vm;
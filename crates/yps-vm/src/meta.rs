@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::value::{CLASS_TAG, ObjMap, Value};
+use crate::vm::Vm;
+
+impl Vm {
+    /// The file this program was loaded from, reported by `спиздить.мета`.
+    pub fn set_module_path(&mut self, path: PathBuf) {
+        self.module_path = Some(path.canonicalize().unwrap_or(path));
+        self.import_meta = None;
+    }
+
+    /// `спиздить.мета`, built once per module. Without a file (`-e`, stdin) only `главный` is set.
+    pub(crate) fn import_meta(&mut self) -> Value {
+        if let Some(meta) = &self.import_meta {
+            return meta.clone();
+        }
+        let path = self.module_path.as_ref();
+        let text = |s: String| Value::Str(s.into());
+        let mut map = ObjMap::new();
+        map.insert("путь".to_string(), path.map_or(Value::Undefined, |p| text(p.display().to_string())));
+        map.insert(
+            "каталог".to_string(),
+            path.and_then(|p| p.parent()).map_or(Value::Undefined, |p| text(p.display().to_string())),
+        );
+        map.insert("url".to_string(), path.map_or(Value::Undefined, |p| text(format!("file://{}", p.display()))));
+        map.insert("главный".to_string(), Value::Bool(self.is_main_module));
+        let meta = self.gc_object(map);
+        self.import_meta = Some(meta.clone());
+        meta
+    }
+
+    /// `глобалЭтот`: a fresh object with every global the program could name at the moment it is
+    /// read. Namespaces in it are the same objects their names read as.
+    pub(crate) fn global_this(&mut self) -> Value {
+        let mut names: Vec<String> = self.globals.keys().cloned().collect();
+        names.extend(yps_interpreter::stdlib::build_globals().into_iter().map(|(name, _)| name));
+        names.extend(yps_interpreter::builtins::builtin_names().iter().map(|name| name.to_string()));
+        names.sort();
+        names.dedup();
+        let mut map = ObjMap::new();
+        for name in names {
+            if name.starts_with("__") || name.contains('.') {
+                continue;
+            }
+            if let Some(value) = self.global_value(&Rc::from(name.as_str())) {
+                map.insert(name, value);
+            }
+        }
+        self.gc_object(map)
+    }
+
+    /// `захуярить.цель` inside a constructor: the class `захуярить` was applied to, which every
+    /// instance records under its class tag.
+    pub(crate) fn class_of(this: &Value) -> Value {
+        match this {
+            Value::Object(map) => match map.borrow().get(CLASS_TAG) {
+                Some(class @ Value::Class(_)) => class.clone(),
+                _ => Value::Undefined,
+            },
+            _ => Value::Undefined,
+        }
+    }
+}
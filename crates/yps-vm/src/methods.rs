@@ -130,6 +130,8 @@ pub(crate) fn array_method_exists(name: &str) -> bool {
             | "включает"
             | "join"
             | "склеить"
+            | "toLocaleString"
+            | "вЛокальнуюСтроку"
             | "reverse"
             | "перевернуть"
             | "concat"
@@ -661,6 +663,10 @@ pub(crate) fn call_array(
             let found = snapshot.iter().skip(start).any(|v| same_value_zero(v, target));
             Ok(Value::Bool(found))
         }
+        "toLocaleString" | "вЛокальнуюСтроку" => {
+            let receiver = crate::bridge::vm_to_interp(&Value::Array(rc), span)?;
+            crate::bridge::call_host_method(vm, &receiver, method, args, span)
+        }
         "join" | "склеить" => {
             let sep = if args.is_empty() {
                 ",".to_string()
@@ -4,6 +4,8 @@ use std::collections::{BinaryHeap, HashSet};
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
+use yps_interpreter::trace;
+use yps_interpreter::worker::{self, Delivery, Envelope};
 use yps_lexer::Span;
 
 use crate::error::VmError;
@@ -48,11 +50,17 @@ pub(crate) struct MacrotaskQueue {
     pub cancelled: HashSet<u64>,
     next_id: u64,
     next_seq: u64,
+    /// Whether events of this thread's worker ports arrive here; off in imported modules.
+    worker_events: bool,
 }
 
 impl MacrotaskQueue {
     pub fn new() -> Self {
-        Self { heap: BinaryHeap::new(), cancelled: HashSet::new(), next_id: 1, next_seq: 0 }
+        Self { heap: BinaryHeap::new(), cancelled: HashSet::new(), next_id: 1, next_seq: 0, worker_events: true }
+    }
+
+    pub fn ignore_worker_events(&mut self) {
+        self.worker_events = false;
     }
 
     pub fn schedule(&mut self, delay: Duration, roots: Vec<Value>, task: Macrotask) -> u64 {
@@ -84,8 +92,13 @@ impl MacrotaskQueue {
         id
     }
 
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.cancelled.clear();
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.heap.iter().all(|t| self.cancelled.contains(&t.id))
+        self.heap.iter().all(|t| self.cancelled.contains(&t.id)) && !(self.worker_events && worker::has_live_ports())
     }
 
     fn next_deadline(&self) -> Option<Instant> {
@@ -112,17 +125,59 @@ impl MacrotaskQueue {
     }
 
     pub fn pop_next_blocking(&mut self) -> Option<ScheduledTask> {
+        // A replayed run pops exactly the task the recorded run did, without waiting for it.
+        if let Some(seq) = trace::replayed_task() {
+            match self.take_seq(seq) {
+                Some(task) => return Some(task),
+                None => trace::reject_replayed(format!("задачи {seq} нет в очереди")),
+            }
+        }
         loop {
-            let next_deadline = self.next_deadline()?;
+            if self.worker_events
+                && let Some(envelope) = worker::poll()
+            {
+                return Some(self.worker_task(envelope));
+            }
+            let Some(next_deadline) = self.next_deadline() else {
+                let envelope = if self.worker_events { worker::wait(None) } else { None };
+                return envelope.map(|envelope| self.worker_task(envelope));
+            };
             let now = Instant::now();
             if next_deadline > now {
-                std::thread::sleep(next_deadline - now);
+                if self.worker_events {
+                    if let Some(envelope) = worker::wait(Some(next_deadline - now)) {
+                        return Some(self.worker_task(envelope));
+                    }
+                } else {
+                    std::thread::sleep(next_deadline - now);
+                }
             }
             if let Some(task) = self.pop_ready() {
+                trace::record_task(task.seq);
                 return Some(task);
             }
         }
     }
+
+    fn worker_task(&mut self, envelope: Envelope) -> ScheduledTask {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        ScheduledTask {
+            deadline: Instant::now(),
+            seq,
+            id: self.allocate_id(),
+            roots: Vec::new(),
+            task: Box::new(move |vm, sp| vm.deliver_worker_event(envelope, sp)),
+        }
+    }
+
+    fn take_seq(&mut self, seq: u64) -> Option<ScheduledTask> {
+        let mut tasks = std::mem::take(&mut self.heap).into_vec();
+        let index = tasks.iter().position(|t| t.seq == seq && !self.cancelled.contains(&t.id));
+        let task = index.map(|i| tasks.swap_remove(i));
+        self.heap = tasks.into();
+        task
+    }
 }
 
 impl Default for MacrotaskQueue {
@@ -182,6 +237,28 @@ impl Vm {
         }
     }
 
+    fn deliver_worker_event(&mut self, envelope: Envelope, span: Span) -> Result<(), VmError> {
+        match worker::dispatch(envelope) {
+            Delivery::Call(handler, args) => {
+                let args = args
+                    .iter()
+                    .map(crate::bridge::interp_to_vm)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|m| VmError::new(m, span))?;
+                match crate::bridge::vm_callback(&handler) {
+                    Some(callee) => self.call_value(callee, None, &args, span).map(|_| ()),
+                    None => crate::bridge::host_call(self, &handler, &args, span).map(|_| ()),
+                }
+            }
+            Delivery::Stop => {
+                self.microtasks.clear();
+                self.macrotasks.clear();
+                Ok(())
+            }
+            Delivery::Skip => Ok(()),
+        }
+    }
+
     pub(crate) fn settle_promise(
         state: &Rc<RefCell<PromiseState>>,
         kind: CapKind,
@@ -2,7 +2,7 @@ use yps_lexer::{Lexer, SourceFile};
 use yps_parser::Parser;
 use yps_parser::ast::Program;
 
-use crate::{Vm, compile_program, run_to_string};
+use crate::{Vm, compile_program, compile_repl, run_to_string};
 
 fn parse(src: &str) -> Program {
     let source = SourceFile::new("<тест>".to_string(), src.to_string());
@@ -108,6 +108,30 @@ fn const_mutation_is_runtime_error() {
     assert!(msg.contains("константу"), "сообщение: {msg}");
 }
 
+#[test]
+fn undefined_global_suggests_the_closest_name() {
+    let err = run_to_string(&parse("гыы счётчик = 1;\nсказать(счетчик);")).unwrap_err();
+    let crate::ExecError::Runtime(e) = err else {
+        panic!("ожидалась ошибка выполнения: {err}")
+    };
+    assert_eq!(e.help.as_deref(), Some("возможно, имелось в виду 'счётчик'"));
+}
+
+#[test]
+fn misspelled_method_suggests_the_closest_name() {
+    for (src, hint) in [
+        ("гыы а = [1];\nа.втолкуть(2);", "возможно, имелось в виду 'втолкнуть'"),
+        ("гыы к = захуярить Карта();\nк.поставитъ(1, 2);", "возможно, имелось в виду 'поставить'"),
+    ] {
+        let err = run_to_string(&parse(src)).unwrap_err();
+        let crate::ExecError::Runtime(e) = err else {
+            panic!("ожидалась ошибка выполнения: {err}")
+        };
+        assert!(e.message.contains("нет метода"), "сообщение: {}", e.message);
+        assert_eq!(e.help.as_deref(), Some(hint));
+    }
+}
+
 #[test]
 fn compound_assignment_and_postfix() {
     let src = r#"
@@ -855,6 +879,48 @@ fn using_disposes_lifo() {
     assert_eq!(run(src), "ба\n");
 }
 
+#[test]
+fn enum_builds_a_frozen_object_with_reverse_mapping() {
+    let src = r#"
+        йопта база() { отвечаю 10; }
+        еээ Код { Ок, Ошибка = база(), Таймаут }
+        Код.Ок = 7;
+        сказать(Код.Ок, Код.Ошибка, Код.Таймаут, Код[11], Кент.заморожен(Код));
+    "#;
+    assert_eq!(run(src), "0 10 11 Таймаут true\n");
+    assert_eq!(run(src), run_interp(src.replace("Код.Ок = 7;", "").as_str()));
+}
+
+#[test]
+fn enum_member_after_a_string_needs_a_value() {
+    let err = run_err("еээ Ц { А = \"а\", Б }");
+    assert!(err.contains("Элементу 'Б' нужно значение"), "ошибка: {err}");
+}
+
+#[test]
+fn match_binds_arm_locals_in_their_own_frame() {
+    let src = r#"
+        гыы сдвиг = 100;
+        йопта описать(х) {
+            отвечаю 1 + базарпо (х) {
+                тема [а, ...б] вилкойвглаз (б.length > 0) => а + б.length + сдвиг,
+                тема { имя = "аноним" } => имя,
+                тема н вилкойвглаз (н < 0) => -н,
+                нуичо => 0,
+            };
+        }
+        сказать(описать([5, 6, 7]), описать({}), описать(-4), описать([5]));
+    "#;
+    assert_eq!(run(src), "108 1аноним 5 1\n");
+    assert_eq!(run(src), run_interp(src));
+}
+
+#[test]
+fn match_without_fitting_arm_reports_the_type() {
+    let err = run_err("базарпо ([1]) { тема [а, б] => а };");
+    assert!(err.contains("Ни одна ветка 'базарпо' не подошла для значения типа"), "ошибка: {err}");
+}
+
 #[test]
 fn using_requires_dispose_method() {
     let err = run_err("{ юзай р = { данные: 1 }; }");
@@ -1930,3 +1996,190 @@ fn ic_set_alternating_identities() {
 "#;
     assert_eq!(run(src), "11 22 33\n");
 }
+
+#[test]
+fn repl_inputs_share_globals_and_return_trailing_expression() {
+    let mut vm = Vm::new();
+    let first = vm.run_repl(compile_repl(&parse("гыы а = 40; йопта ф() { отвечаю 2; }")).unwrap()).unwrap();
+    assert_eq!(first.to_string(), "undefined");
+    let sum = vm.run_repl(compile_repl(&parse("а + ф();")).unwrap()).unwrap();
+    assert_eq!(sum.to_string(), "42");
+    let awaited = vm.run_repl(compile_repl(&parse("сидетьНахуй СловоПацана.решить(а);")).unwrap()).unwrap();
+    assert_eq!(awaited.to_string(), "40");
+    assert!(vm.run_repl(compile_repl(&parse("кидай 1;")).unwrap()).is_err());
+    let after_error = vm.run_repl(compile_repl(&parse("а;")).unwrap()).unwrap();
+    assert_eq!(after_error.to_string(), "40");
+}
+
+#[test]
+fn direct_eval_captures_locals_and_upvalues() {
+    let src = r#"
+йопта счётчик() {
+    гыы н = 0;
+    отвечаю () => ебал("н = н + 1");
+}
+гыы с = счётчик();
+с();
+йопта внешний() {
+    гыы а = 1;
+    йопта внутр() {
+        гыы б = 2;
+        гыы ф = ебал("() => а + б");
+        б = 10;
+        отвечаю ф();
+    }
+    отвечаю внутр();
+}
+клёво Т {
+    Т() { тырыпыры.х = 3; }
+    м() { отвечаю ебал("тырыпыры.х * 2"); }
+}
+сказать(с(), внешний(), захуярить Т().м());
+"#;
+    assert_eq!(run(src), "2 11 6\n");
+}
+
+#[test]
+fn indirect_eval_and_function_constructor_match_interpreter() {
+    let src = r#"
+гыы х = 4;
+йопта ф() { гыы х = 1; гыы е = ебал; отвечаю [ебал("х"), е("х * 2")]; }
+гыы сумма = Функция("а", "б", "отвечаю а + б + х;");
+сказать(ф(), ебал("гыы в = 3; в * 2"), ебал(42), сумма(1, 2), захуярить Функция("отвечаю 7;")());
+хапнуть { ебал("1 +"); } гоп (е) { сказать(е.message); }
+"#;
+    assert_eq!(run(src), run_interp(src));
+}
+
+#[test]
+fn code_strings_need_no_semicolon_before_a_closing_brace() {
+    let src = r#"
+гыы сумма = Функция("а", "б", "отвечаю а + б");
+ебал("{ сказать(7) }");
+сказать(сумма(2, 3), ебал("йопта г() { отвечаю 5 }\nг()"));
+"#;
+    assert_eq!(run(src), run_interp(src));
+    assert_eq!(run(src), "7\n5 5\n");
+}
+
+#[test]
+fn eval_can_be_disabled() {
+    let mut vm = Vm::new();
+    vm.set_allow_eval(false);
+    let err = vm.run(compile_program(&parse("Функция(\"отвечаю 1;\");")).unwrap()).unwrap_err();
+    assert!(err.message.contains("'Функция' запрещён"), "{}", err.message);
+}
+
+#[test]
+fn new_target_and_arguments_match_interpreter() {
+    let src = r#"
+клёво Зверь { Зверь() { сказать(захуярить.цель == Зверь, (() => захуярить.цель)() == Пёс); } }
+клёво Пёс батя Зверь { Пёс() { яга(); сказать(захуярить.цель == Пёс); } }
+захуярить Зверь();
+захуярить Пёс();
+йопта обычная() { отвечаю захуярить.цель; }
+йопта сумма(а, б = аргументы.длина) { отвечаю [а, б, аргументы.длина, (() => аргументы[0])()]; }
+пиздюли ген() { поебалу аргументы.длина; }
+сказать(обычная(), сумма(1), сумма(1, 2, 3), ген(4, 5).следующий().значение);
+"#;
+    assert_eq!(run(src), run_interp(src));
+}
+
+#[test]
+fn global_this_and_import_meta_without_a_file() {
+    let src = r#"
+гыы х = 5;
+сказать(глобалЭтот.х, тип(глобалЭтот.сказать), глобалЭтот.нету);
+сказать(спиздить.мета.главный, спиздить.мета.путь);
+"#;
+    assert_eq!(run(src), "5 функция undefined\ntrue undefined\n");
+    assert_eq!(run(src), run_interp(src));
+}
+
+#[test]
+fn atomics_on_shared_buffer_match_interpreter() {
+    let src = r#"
+        гыы б = захуярить ОбщийБуфер(8);
+        гыы м = захуярить Ч32Массив(б);
+        сказать(б, Атомики.добавить(м, 0, 5), Атомики.сравнитьИОбменять(м, 0, 5, 8), Атомики.загрузить(м, 0));
+        сказать(Атомики.ждать(м, 1, 1), Атомики.ждать(м, 1, 0, 5), Атомики.разбудить(м, 1));
+    "#;
+    assert_eq!(run(src), run_interp(src));
+    assert_eq!(run(src), "ОбщийБуфер(8) 0 5 8\nнеравно истекло 0\n");
+}
+
+#[test]
+fn structured_clone_and_encodings_match_interpreter() {
+    let src = r#"
+        гыы о = {а: [1, 2], д: захуярить Дата(0), р: /аб/g};
+        о.я = о;
+        гыы к = структурныйКлон(о);
+        сказать(к.я === к, к === о, к.а !== о.а, к.а[1], к.д.времяМс(), к.р.source);
+        хапнуть { структурныйКлон([йопта() {}]); } гоп (е) { сказать(е.message); }
+        гыы б = захуярить КодировщикТекста("windows-1251").закодировать("Ёжик");
+        сказать(б.вХекс(), захуярить ДекодировщикТекста("windows-1251").раскодировать(б));
+        сказать(вБазу64("Привет"), захуярить ДекодировщикТекста().раскодировать(изБазы64("0J_RgNC40LLQtdGC")));
+        сказать(Ц8Массив.изХекса("cafe").вБазу64(), Ц8Массив.изБазы64("yv4=").вХекс());
+    "#;
+    assert_eq!(run(src), run_interp(src));
+    assert_eq!(
+        run(src),
+        "true false true 2 0 аб\n'структурныйКлон': значение типа 'функция' нельзя клонировать\n\
+         a8e6e8ea Ёжик\n0J/RgNC40LLQtdGC Привет\nyv4= cafe\n"
+    );
+}
+
+#[test]
+fn intl_formatting_matches_interpreter() {
+    let src = r#"
+        гыы д = захуярить Дата("2026-10-17T14:05:09Z");
+        сказать(захуярить Интл.ФорматЧисла("ru", {стиль: "валюта", валюта: "RUB"}).форматировать(1234.5));
+        сказать(захуярить Интл.ФорматДаты("ru", {стильДаты: "длинный"}).форматировать(д), д.вЛокальноеВремя("en"));
+        гыы с = захуярить Интл.Сравнитель("ru");
+        сказать(["ёж", "яблоко", "ель", "еда"].сортировать((а, б) => с.сравнить(а, б)));
+        сказать(захуярить Интл.ПравилаМножественного("ru").выбрать(22), захуярить Интл.ОтносительноеВремя("ru").форматировать(-2, "час"));
+        сказать((1234.5).вЛокальнуюСтроку("en"), [0.5, 1000].вЛокальнуюСтроку(), чезажижан с);
+    "#;
+    assert_eq!(run(src), run_interp(src));
+    assert_eq!(
+        run(src),
+        "1\u{202F}234,50\u{A0}₽\n17 октября 2026 г. 2:05:09\u{202F}PM\n[еда, ёж, ель, яблоко]\nfew 2 часа назад\n\
+         1,234.5 0,5,1\u{202F}000 объект\n"
+    );
+}
+
+#[test]
+fn temporal_matches_interpreter() {
+    let src = r#"
+        гыы м = Время.Мгновение.от("2026-10-17T10:20:30.5Z");
+        сказать(м.вЗону("+03:00").вСтроку(), м.эпохаНс, чезажижан м);
+        гыы д = Время.ПростаяДата.от("2024-01-31");
+        сказать(д.прибавить({месяцы: 1}).вСтроку(), д.доМомента("2026-10-17", {наибольшаяЕдиница: "год"}).вСтроку());
+        гыы п = Время.Длительность.от("PT1H30M");
+        сказать(п.итого("минута"), п.округлить("час").вСтроку(), Время.Длительность.сравнить(п, "PT1H"));
+        сказать(Жсон.вСтроку({д: д}), захуярить Время.ПростоеВремя(23, 30).прибавить("PT1H").вСтроку());
+    "#;
+    assert_eq!(run(src), run_interp(src));
+    assert_eq!(
+        run(src),
+        "2026-10-17T13:20:30.5+03:00[+03:00] 1792232430500000000n объект\n2024-02-29 P2Y8M17D\n90 PT2H 1\n\
+         {\"д\":\"2024-01-31\"} 00:30:00\n"
+    );
+}
+
+#[test]
+fn cyclic_message_reaches_a_worker_with_its_cycle() {
+    let dir = std::env::temp_dir().join(format!("yps_vm_worker_cycle_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let task = dir.join("задача.yopta");
+    let worker = "родитель.приСообщении(йопта(с) {\n  родитель.отправить(с.я === с);\n  родитель.закрыть();\n});\n";
+    std::fs::write(&task, worker).unwrap();
+    let src = format!(
+        "гыы в = захуярить Воркер({:?});\nв.приСообщении(йопта(о) {{ сказать(о); }});\n\
+         гыы ц = {{}};\nц.я = ц;\nв.отправить(ц);\n",
+        task.display().to_string()
+    );
+    let out = run(&src);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(out, "true\n");
+}
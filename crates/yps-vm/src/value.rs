@@ -72,6 +72,10 @@ impl MethodTable {
     pub(crate) fn defs(&self) -> impl Iterator<Item = &MethodDef> {
         self.entries.iter().map(|(_, def)| def)
     }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &MethodDef)> {
+        self.entries.iter().map(|(name, def)| (name.as_str(), def))
+    }
 }
 
 #[derive(Debug, Default)]
@@ -3,10 +3,12 @@ use std::cmp::Ordering;
 use std::io::{self, Write};
 use std::rc::Rc;
 
+use yps_interpreter::heap_snapshot::HeapGraph;
+use yps_interpreter::profile::{MODULE_FRAME, Profile, SampleFrame, Sampler, frame_name};
 use yps_lexer::Span;
 
 use crate::builtins;
-use crate::chunk::{ClassBlueprint, Constant, FnProto, MemberKind, Op};
+use crate::chunk::{ClassBlueprint, Constant, EnumBlueprint, EvalScope, FnProto, MatchShape, MemberKind, Op};
 use crate::error::VmError;
 use crate::promise::{MacrotaskQueue, Microtask};
 use crate::value::{
@@ -94,14 +96,14 @@ pub struct Handler {
 pub struct Vm {
     stack: Vec<Value>,
     frames: Vec<CallFrame>,
-    globals: std::collections::HashMap<String, (Value, bool)>,
+    pub(crate) globals: std::collections::HashMap<String, (Value, bool)>,
     open_upvalues: Vec<Upvalue>,
     handlers: Vec<Handler>,
     region_floor: usize,
     coroutine_depth: usize,
     gen_yield: Option<Value>,
     disposables: Vec<(Value, bool)>,
-    base_path: Option<std::path::PathBuf>,
+    pub(crate) base_path: Option<std::path::PathBuf>,
     module_cache: ModuleCache,
     module_loading: ModuleLoading,
     exports: ModuleExports,
@@ -110,6 +112,19 @@ pub struct Vm {
     gc: Rc<crate::gc::GcRegistry>,
     gc_counter: usize,
     out: Box<dyn Write>,
+    profiler: Option<(Rc<RefCell<Profile>>, Sampler)>,
+    /// Top-level locals kept alive after the script body returns, so that an end-of-run heap
+    /// snapshot sees what the interpreter's global scope would hold.
+    script_scope: Option<Vec<Value>>,
+    /// Present while coverage or the profiler needs to know which file a function came from.
+    files: Option<Rc<RefCell<crate::coverage::ProtoFiles>>>,
+    coverage: bool,
+    pub(crate) allow_eval: bool,
+    pub(crate) module_path: Option<std::path::PathBuf>,
+    pub(crate) is_main_module: bool,
+    pub(crate) import_meta: Option<Value>,
+    /// Raised by `завершить` on the thread that started this worker.
+    pub(crate) interrupt: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
 }
 
 impl Default for Vm {
@@ -143,6 +158,15 @@ impl Vm {
             gc: Rc::new(crate::gc::GcRegistry::default()),
             gc_counter: 0,
             out,
+            profiler: None,
+            script_scope: None,
+            files: None,
+            coverage: false,
+            allow_eval: true,
+            module_path: None,
+            is_main_module: true,
+            import_meta: None,
+            interrupt: None,
         }
     }
 
@@ -150,6 +174,70 @@ impl Vm {
         self.base_path = Some(path);
     }
 
+    /// Functions of the entry script need no registration: a function of no known file is
+    /// reported as `sources` id 0.
+    pub fn set_profiler(&mut self, profile: Rc<RefCell<Profile>>, sampler: Sampler) {
+        self.profiler = Some((profile, sampler));
+        self.files.get_or_insert_with(Default::default);
+    }
+
+    /// Reports executed instructions of `proto` and everything it compiles to, as `file`.
+    pub fn track_coverage(&mut self, proto: &Rc<FnProto>, file: usize) {
+        self.coverage = true;
+        self.files.get_or_insert_with(Default::default).borrow_mut().register(proto, file);
+    }
+
+    pub fn retain_script_scope(&mut self) {
+        self.script_scope = Some(Vec::new());
+    }
+
+    /// Attributes allocations to the file of the function making them; with no file map, every
+    /// site is reported in the entry script.
+    pub fn track_allocations(&mut self) {
+        self.files.get_or_insert_with(Default::default);
+    }
+
+    /// Counts a new array, object or closure at `span` of the running function's file when
+    /// allocation tracking is on.
+    pub(crate) fn record_allocation(&self, value: &Value, span: Span) {
+        if !yps_interpreter::heap_snapshot::is_tracking() {
+            return;
+        }
+        let file =
+            self.frames.last().and_then(|frame| self.files.as_ref()?.borrow().file(&frame.closure.proto)).unwrap_or(0);
+        match value {
+            Value::Array(rc) => yps_interpreter::heap_snapshot::record_allocation_at(rc, file, span),
+            Value::Object(rc) => yps_interpreter::heap_snapshot::record_allocation_at(rc, file, span),
+            Value::Function(rc) => yps_interpreter::heap_snapshot::record_allocation_at(rc, file, span),
+            _ => {}
+        }
+    }
+
+    /// Each frame's current position is the span of the instruction it last dispatched.
+    fn take_profile_sample(&self, span: Span) {
+        let Some((profile, _)) = &self.profiler else { return };
+        let files = self.files.as_ref().map(|files| files.borrow());
+        let last = self.frames.len().saturating_sub(1);
+        let stack: Vec<_> = self
+            .frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let proto = &frame.closure.proto;
+                let name = if i == 0 { Rc::from(MODULE_FRAME) } else { frame_name(&proto.name) };
+                let spans = &proto.chunk.spans;
+                let at = if i == last { span } else { spans.get(frame.ip.saturating_sub(1)).copied().unwrap_or(span) };
+                SampleFrame {
+                    name,
+                    file: files.as_ref().and_then(|files| files.file(proto)).unwrap_or(0),
+                    function: Rc::as_ptr(proto) as usize,
+                    span: at,
+                }
+            })
+            .collect();
+        profile.borrow_mut().sample(&stack);
+    }
+
     pub fn run(&mut self, proto: Rc<FnProto>) -> Result<(), VmError> {
         let closure = Rc::new(Closure { proto, upvalues: Vec::new() });
         self.stack.push(Value::Function(Rc::clone(&closure)));
@@ -158,10 +246,58 @@ impl Vm {
         self.drive_event_loop(Span { start: 0, end: 0 })
     }
 
+    /// Runs one REPL input over the persistent globals and returns its completion value.
+    /// Microtasks are drained; leftover timers are dropped, as in the interpreter's REPL.
+    pub fn run_repl(&mut self, proto: Rc<FnProto>) -> Result<Value, VmError> {
+        self.stack.clear();
+        self.frames.clear();
+        self.handlers.clear();
+        self.open_upvalues.clear();
+        let closure = Rc::new(Closure { proto, upvalues: Vec::new() });
+        self.stack.push(Value::Function(Rc::clone(&closure)));
+        self.frames.push(CallFrame { closure, ip: 0, base: 0, owner: None });
+        let result = self.run_loop().and_then(|()| {
+            let value = self.stack.pop().unwrap_or(Value::Undefined);
+            self.drain_microtasks(Span { start: 0, end: 0 }).map(|()| value)
+        });
+        self.microtasks.clear();
+        self.macrotasks = MacrotaskQueue::new();
+        result
+    }
+
     fn global_get(&self, name: &str) -> Option<&Value> {
         self.globals.get(name).map(|(v, _)| v)
     }
 
+    /// What a global name reads as: a declared global, then a builtin function, then a namespace.
+    /// A namespace is built on first read and kept as a global, so that every read sees the same
+    /// object.
+    pub(crate) fn global_value(&mut self, name: &Rc<str>) -> Option<Value> {
+        if let Some(v) = self.global_get(name) {
+            Some(v.clone())
+        } else if builtins::is_builtin(name) {
+            Some(Value::Builtin(Rc::clone(name)))
+        } else {
+            let value = crate::bridge::namespace_value(name)?;
+            self.globals.insert(name.to_string(), (value.clone(), true));
+            Some(value)
+        }
+    }
+
+    /// "переменная не определена", with the closest global, builtin or keyword as a hint.
+    fn undefined_global(&self, name: &str, span: Span) -> VmError {
+        let namespaces: Vec<String> =
+            yps_interpreter::stdlib::build_globals().into_iter().map(|(name, _)| name).collect();
+        let candidates = self
+            .globals
+            .keys()
+            .chain(&namespaces)
+            .map(String::as_str)
+            .chain(yps_interpreter::builtins::builtin_names().iter().copied())
+            .chain(yps_lexer::KEYWORDS.iter().copied());
+        VmError::new(format!("переменная не определена: '{name}'"), span).with_did_you_mean(name, candidates)
+    }
+
     fn run_loop(&mut self) -> Result<(), VmError> {
         self.run_to_depth(0)
     }
@@ -192,6 +328,21 @@ impl Vm {
             let ip = self.frames[frame_idx].ip;
             let op = chunk.code[ip];
             let span = chunk.spans[ip];
+            if let Some((_, sampler)) = &self.profiler
+                && sampler.take()
+            {
+                self.take_profile_sample(span);
+            }
+            if self.coverage
+                && let Some(files) = &self.files
+            {
+                files.borrow().hit(&closure.proto, ip, span);
+            }
+            if let Some(flag) = &self.interrupt
+                && flag.load(std::sync::atomic::Ordering::Relaxed)
+            {
+                return Err(VmError::new(yps_interpreter::worker::WORKER_TERMINATED, span));
+            }
             self.frames[frame_idx].ip = ip + 1;
             let base = self.frames[frame_idx].base;
 
@@ -243,9 +394,12 @@ impl Vm {
                     Constant::Str(s) => self.stack.push(Value::Str(Rc::clone(s))),
                     Constant::Proto(_)
                     | Constant::Class(_)
+                    | Constant::Enum(_)
+                    | Constant::Shape(_)
                     | Constant::Template(_)
                     | Constant::RegExp { .. }
-                    | Constant::Import(_) => {
+                    | Constant::Import(_)
+                    | Constant::EvalScope(_) => {
                         return Err(VmError::new("нечисловая константа загружена как значение", span));
                     }
                 },
@@ -428,15 +582,18 @@ impl Vm {
                 }
                 Op::GetGlobal(idx) => {
                     let name = self.const_str(chunk, idx);
-                    if let Some(v) = self.global_get(&name) {
-                        let v = v.clone();
-                        self.stack.push(v);
-                    } else if builtins::is_builtin(&name) {
-                        self.stack.push(Value::Builtin(Rc::clone(&name)));
-                    } else if let Some(ns) = crate::bridge::namespace_value(&name) {
-                        self.stack.push(ns);
-                    } else {
-                        return Err(VmError::new(format!("переменная не определена: '{name}'"), span));
+                    match self.global_value(&name) {
+                        Some(v) => self.stack.push(v),
+                        None if &*name == yps_interpreter::symbols::GLOBAL_THIS => {
+                            let v = self.global_this();
+                            self.stack.push(v);
+                        }
+                        None if let Some(port) = Vm::parent_port()
+                            && &*name == yps_interpreter::symbols::PARENT_PORT =>
+                        {
+                            self.stack.push(port);
+                        }
+                        None => return Err(self.undefined_global(&name, span)),
                     }
                 }
                 Op::SetGlobal(idx) => {
@@ -450,7 +607,7 @@ impl Vm {
                             *slot = value;
                         }
                         None => {
-                            return Err(VmError::new(format!("переменная не определена: '{name}'"), span));
+                            return Err(self.undefined_global(&name, span));
                         }
                     }
                 }
@@ -610,6 +767,13 @@ impl Vm {
                 }
 
                 Op::Call(argc) => self.do_call(argc as usize, span)?,
+                Op::DirectEval(idx, argc) => {
+                    let Constant::EvalScope(scope) = &chunk.constants[idx as usize] else {
+                        return Err(VmError::new("DirectEval ожидает константу области видимости", span));
+                    };
+                    let scope = Rc::clone(scope);
+                    self.direct_eval(&scope, argc as usize, base, span)?;
+                }
                 Op::CallSpread => {
                     let args_arr = self.pop();
                     let argv: Vec<Value> = match args_arr {
@@ -620,11 +784,20 @@ impl Vm {
                     self.stack.extend(argv);
                     self.do_call(argc, span)?;
                 }
-                Op::Closure(idx) => self.do_closure(chunk, idx, base),
+                Op::Closure(idx) => {
+                    self.do_closure(chunk, idx, base);
+                    self.record_allocation(self.peek(0), span);
+                }
                 Op::Return => {
                     let result = self.pop();
                     let frame = self.frames.pop().unwrap();
                     self.close_upvalues(frame.base);
+                    if self.frames.is_empty()
+                        && let Some(scope) = &mut self.script_scope
+                        && scope.is_empty()
+                    {
+                        scope.extend(self.stack.drain(frame.base..));
+                    }
                     self.stack.truncate(frame.base);
                     self.stack.push(result);
                     if self.frames.len() <= self.region_floor {
@@ -660,12 +833,21 @@ impl Vm {
                     let promise = self.dynamic_import(source, span)?;
                     self.stack.push(promise);
                 }
+                Op::ImportMeta => {
+                    let meta = self.import_meta();
+                    self.stack.push(meta);
+                }
+                Op::ClassOf => {
+                    let this = self.pop();
+                    self.stack.push(Self::class_of(&this));
+                }
 
                 Op::NewArray(n) => {
                     let n = n as usize;
                     let at = self.stack.len() - n;
                     let elems: Vec<Value> = self.stack.split_off(at);
                     let arr = self.gc_array(elems);
+                    self.record_allocation(&arr, span);
                     self.stack.push(arr);
                 }
                 Op::ArrPush => {
@@ -755,6 +937,7 @@ impl Vm {
                         map.insert(k.to_ecma_string(), v);
                     }
                     let obj = self.gc_object(map);
+                    self.record_allocation(&obj, span);
                     self.stack.push(obj);
                 }
                 Op::ObjSet => {
@@ -882,10 +1065,8 @@ impl Vm {
                     let index = self.pop();
                     let obj = self.pop();
                     match &obj {
-                        Value::Object(map) => {
-                            if map.borrow().can_delete() {
-                                map.borrow_mut().remove(&index.to_ecma_string());
-                            }
+                        Value::Object(map) if map.borrow().can_delete() => {
+                            map.borrow_mut().remove(&index.to_ecma_string());
                         }
                         Value::Array(a) => {
                             let n = index.to_number();
@@ -943,11 +1124,34 @@ impl Vm {
                 }
 
                 Op::BuildClass(idx) => self.build_class(chunk, idx, span)?,
+                Op::BuildEnum(idx) => {
+                    let blueprint = match &chunk.constants[idx as usize] {
+                        Constant::Enum(b) => Rc::clone(b),
+                        _ => return Err(VmError::new("BuildEnum ожидает перечисление", span)),
+                    };
+                    let obj = self.build_enum(&blueprint, span)?;
+                    self.stack.push(obj);
+                }
+                Op::MatchShape(idx) => {
+                    let shape = match &chunk.constants[idx as usize] {
+                        Constant::Shape(shape) => Rc::clone(shape),
+                        _ => return Err(VmError::new("MatchShape ожидает образец", span)),
+                    };
+                    let value = self.pop();
+                    self.stack.push(Value::Bool(fits_shape(&shape, &value)));
+                }
+                Op::NoMatch => {
+                    let value = self.pop();
+                    let message =
+                        format!("Ни одна ветка 'базарпо' не подошла для значения типа '{}'", value.type_name());
+                    return Err(VmError::new(message, span));
+                }
                 Op::New(argc) => {
                     let argc = argc as usize;
                     let args: Vec<Value> = self.pop_args(argc);
                     let callee = self.pop();
                     let instance = self.instantiate(callee, args, span)?;
+                    self.record_allocation(&instance, span);
                     self.stack.push(instance);
                 }
                 Op::NewSpread => {
@@ -1112,6 +1316,38 @@ impl Vm {
             let arr = self.gc_array(rest);
             self.stack.push(arr);
         }
+        if proto.uses_arguments {
+            let arr = self.gc_array(args.to_vec());
+            self.stack.push(arr);
+        }
+    }
+
+    /// `ебал(код)` called by name: the code is compiled as a function whose upvalues are the
+    /// caller's variables listed in `scope`. Anything else under that name is an ordinary call.
+    fn direct_eval(&mut self, scope: &EvalScope, argc: usize, base: usize, span: Span) -> Result<(), VmError> {
+        let callee_idx = self.callee_slot(argc);
+        if !matches!(&self.stack[callee_idx], Value::Builtin(name) if &**name == crate::eval::EVAL) {
+            return self.do_call(argc, span);
+        }
+        let code = self.pop_args(argc).into_iter().next().unwrap_or(Value::Undefined);
+        self.pop();
+        let Value::Str(text) = code else {
+            self.stack.push(code);
+            return Ok(());
+        };
+        let proto = self.compile_code(crate::eval::EVAL, &text, scope, span)?;
+        let parent = Rc::clone(&self.frames.last().unwrap().closure);
+        let mut upvalues = Vec::with_capacity(proto.upvalues.len());
+        for desc in &proto.upvalues {
+            let binding = &scope.bindings[desc.index - 1];
+            if binding.from_local {
+                upvalues.push(self.capture_upvalue(base + binding.index));
+            } else {
+                upvalues.push(Rc::clone(&parent.upvalues[binding.index]));
+            }
+        }
+        self.stack.push(Value::Function(Rc::new(Closure { proto, upvalues })));
+        self.do_call(0, span)
     }
 
     fn do_call(&mut self, argc: usize, span: Span) -> Result<(), VmError> {
@@ -1335,6 +1571,18 @@ impl Vm {
         if let Some(res) = self.try_call_timer_builtin(name, &args, span) {
             return res;
         }
+        if let Some(res) = self.try_call_code_builtin(name, &args, span) {
+            return res;
+        }
+        if name == "Воркер" {
+            return self.construct_worker(args, span);
+        }
+        if name == "структурныйКлон" {
+            return self.structured_clone(&args, span);
+        }
+        if name == "Процесс.снимокКучи" {
+            return self.write_heap_snapshot(&args, span);
+        }
         if crate::bridge::is_host_callback(name) {
             return crate::bridge::call_host_callback(self, name, args, span);
         }
@@ -1770,6 +2018,11 @@ impl Vm {
             let ip = self.frames[frame_idx].ip;
             let op = chunk.code[ip];
             let op_span = chunk.spans[ip];
+            if self.coverage
+                && let Some(files) = &self.files
+            {
+                files.borrow().hit(&closure.proto, ip, op_span);
+            }
 
             if matches!(op, Op::Return) && frame_idx == 0 {
                 let result = self.stack.pop().unwrap_or(Value::Undefined);
@@ -2387,12 +2640,7 @@ impl Vm {
 
     fn resolve_module_path(&self, source: &str, span: Span) -> Result<std::path::PathBuf, VmError> {
         let base = self.base_path.clone().unwrap_or_else(|| std::path::PathBuf::from("."));
-        let mut candidate = base.join(source);
-        if candidate.extension().is_none() {
-            candidate.set_extension("yopta");
-        }
-        candidate
-            .canonicalize()
+        yps_interpreter::interpreter::resolve_import(&base, source)
             .map_err(|e| VmError::new(format!("Не удалось разрешить путь модуля '{source}': {e}"), span))
     }
 
@@ -2433,6 +2681,19 @@ impl Vm {
         sub.module_cache = Rc::clone(&self.module_cache);
         sub.module_loading = Rc::clone(&self.module_loading);
         sub.base_path = resolved.parent().map(std::path::Path::to_path_buf);
+        sub.module_path = Some(resolved.to_path_buf());
+        sub.is_main_module = false;
+        sub.macrotasks.ignore_worker_events();
+        if let Some(files) = &self.files {
+            let file = if self.coverage {
+                yps_interpreter::coverage::register_file(&source_file, &program)
+            } else {
+                yps_interpreter::sources::register(&source_file)
+            };
+            files.borrow_mut().register(&proto, file);
+            sub.files = Some(Rc::clone(files));
+            sub.coverage = self.coverage;
+        }
         sub.run_uninstrumented(proto)?;
         Ok(Rc::new(std::mem::take(&mut sub.exports)))
     }
@@ -2613,6 +2874,17 @@ impl Vm {
         self.invoke_dispose(resource, span)
     }
 
+    /// How the interpreter names a builtin receiver in "нет метода" errors, and its methods.
+    fn builtin_methods(receiver: &Value) -> Option<(&'static str, &'static [&'static str])> {
+        use yps_interpreter::stdlib::{array, number, string};
+        match receiver {
+            Value::Array(_) => Some(("массива", array::METHODS)),
+            Value::Str(_) => Some(("строки", string::METHODS)),
+            Value::Number(_) => Some(("числа", number::INSTANCE_METHODS)),
+            _ => None,
+        }
+    }
+
     fn do_invoke(&mut self, name: &str, argc: usize, span: Span) -> Result<(), VmError> {
         let recv_idx = self.callee_slot(argc);
         let receiver = self.stack[recv_idx].clone();
@@ -2696,6 +2968,18 @@ impl Vm {
             return Ok(());
         }
 
+        if let Value::Number(n) = &receiver
+            && yps_interpreter::stdlib::number::INSTANCE_METHODS.contains(&name)
+        {
+            let n = *n;
+            let args: Vec<Value> = self.pop_args(argc);
+            self.pop();
+            let result =
+                crate::bridge::call_host_method(self, &yps_interpreter::value::Value::Number(n), name, args, span)?;
+            self.stack.push(result);
+            return Ok(());
+        }
+
         if let Value::Str(s) = &receiver
             && crate::methods::string_method_exists(name)
         {
@@ -2717,6 +3001,12 @@ impl Vm {
         }
 
         let prop = self.get_property(&receiver, name, span)?;
+        if let Value::Undefined = prop
+            && let Some((what, known)) = Self::builtin_methods(&receiver)
+        {
+            return Err(VmError::new(format!("У {what} нет метода '{name}'"), span)
+                .with_did_you_mean(name, known.iter().copied()));
+        }
         self.stack[recv_idx] = prop;
         if let Value::Object(_) = &receiver {
             self.do_call_with_this(argc, receiver, span)
@@ -2906,6 +3196,35 @@ impl Vm {
         uv
     }
 
+    /// The frozen object of an `еээ`: names map to values and numeric values map back to names.
+    fn build_enum(&mut self, blueprint: &EnumBlueprint, span: Span) -> Result<Value, VmError> {
+        let inits = blueprint.members.iter().filter(|(_, has_init)| *has_init).count();
+        let at = self.stack.len() - inits;
+        let mut inits = self.stack.split_off(at).into_iter();
+        let mut map = ObjMap::new();
+        let mut next = Some(0.0);
+        for (name, has_init) in &blueprint.members {
+            let value = match (*has_init, next) {
+                (true, _) => inits.next().expect("значение инициализатора на стеке"),
+                (false, Some(n)) => Value::Number(n),
+                (false, None) => {
+                    let message = format!("Элементу '{name}' нужно значение: предыдущий элемент не число");
+                    return Err(VmError::new(message, span));
+                }
+            };
+            next = match value {
+                Value::Number(n) => Some(n + 1.0),
+                _ => None,
+            };
+            if let Value::Number(_) = value {
+                map.insert(value.to_ecma_string(), Value::Str(name.as_str().into()));
+            }
+            map.insert(name.clone(), value);
+        }
+        map.freeze();
+        Ok(self.gc_object(map))
+    }
+
     pub(crate) fn gc_object(&self, map: ObjMap) -> Value {
         let rc = Rc::new(RefCell::new(map));
         self.gc.track_object(&rc);
@@ -2936,7 +3255,7 @@ impl Vm {
 
     pub fn collect_cycles(&mut self) -> usize {
         let mut marker = crate::gc::Marker::default();
-        for value in &self.stack {
+        for value in self.stack.iter().chain(self.script_scope.iter().flatten()) {
             marker.push_value(value);
         }
         for frame in &self.frames {
@@ -2977,6 +3296,59 @@ impl Vm {
         marker.sweep(&self.gc)
     }
 
+    /// Heap graph reachable from the roots `collect_cycles` marks (`Процесс.снимокКучи`).
+    pub fn heap_snapshot(&self) -> HeapGraph {
+        let mut walker = crate::heap::Walker::new(&self.stack);
+        for value in self.stack.iter().chain(self.script_scope.iter().flatten()) {
+            walker.root(value);
+        }
+        for frame in &self.frames {
+            walker.root(&Value::Function(Rc::clone(&frame.closure)));
+            if let Some(owner) = &frame.owner {
+                walker.root(&Value::Class(Rc::clone(owner)));
+            }
+        }
+        for (value, _) in self.globals.values() {
+            walker.root(value);
+        }
+        for up in &self.open_upvalues {
+            walker.root_upvalue(up);
+        }
+        if let Some(value) = &self.gen_yield {
+            walker.root(value);
+        }
+        for (value, _) in &self.disposables {
+            walker.root(value);
+        }
+        for value in self.exports.values() {
+            walker.root(value);
+        }
+        for module in self.module_cache.borrow().values() {
+            for value in module.values() {
+                walker.root(value);
+            }
+        }
+        for task in &self.microtasks {
+            for root in &task.roots {
+                walker.root(root);
+            }
+        }
+        for root in self.macrotasks.roots() {
+            walker.root(root);
+        }
+        walker.finish()
+    }
+
+    /// Goes through the VM rather than the bridge: a bridged call sees a fresh interpreter's heap.
+    fn write_heap_snapshot(&self, args: &[Value], span: Span) -> Result<Value, VmError> {
+        let Some(Value::Str(path)) = args.first() else {
+            return Err(VmError::new("'Процесс.снимокКучи' требует путь", span));
+        };
+        yps_interpreter::stdlib::process::write_heap_snapshot(&self.heap_snapshot(), path, span)
+            .map_err(|e| VmError::new(e.message, e.span))?;
+        Ok(Value::Str(Rc::clone(path)))
+    }
+
     fn close_upvalues(&mut self, from: usize) {
         let mut i = 0;
         while i < self.open_upvalues.len() {
@@ -3433,3 +3805,28 @@ fn get_prop(obj: &Value, name: &str, span: Span) -> Result<Value, VmError> {
         other => Err(VmError::new(format!("нельзя читать свойство '{name}' у типа '{}'", other.type_name()), span)),
     }
 }
+
+fn fits_shape(shape: &MatchShape, value: &Value) -> bool {
+    match shape {
+        MatchShape::Any => true,
+        MatchShape::Defaulted(inner) => matches!(value, Value::Undefined) || fits_shape(inner, value),
+        MatchShape::Array { elements, rest } => {
+            let Value::Array(arr) = value else {
+                return false;
+            };
+            let items = arr.borrow().clone();
+            let length_ok = if *rest { items.len() >= elements.len() } else { items.len() == elements.len() };
+            length_ok && elements.iter().zip(&items).all(|(shape, item)| fits_shape(shape, item))
+        }
+        MatchShape::Object { props } => {
+            let Value::Object(map) = value else {
+                return false;
+            };
+            props.iter().all(|(key, shape)| {
+                let item = map.borrow().get(key).cloned().unwrap_or(Value::Undefined);
+                (!matches!(item, Value::Undefined) || matches!(shape, MatchShape::Defaulted(_)))
+                    && fits_shape(shape, &item)
+            })
+        }
+    }
+}
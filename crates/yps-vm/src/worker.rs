@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use yps_interpreter::value::Value as IValue;
+use yps_lexer::Span;
+
+use crate::error::VmError;
+use crate::value::Value;
+use crate::vm::Vm;
+
+impl Vm {
+    /// Makes the run stop with [`yps_interpreter::worker::WORKER_TERMINATED`] once `flag` is raised.
+    pub fn set_interrupt(&mut self, flag: Arc<AtomicBool>) {
+        self.interrupt = Some(flag);
+    }
+
+    /// `захуярить Воркер("задача.yopta")`: the worker runs on the VM as well.
+    pub(crate) fn construct_worker(&mut self, args: Vec<Value>, span: Span) -> Result<Value, VmError> {
+        let base = self.base_path.clone().unwrap_or_else(|| PathBuf::from("."));
+        let args = args.iter().map(|a| crate::bridge::vm_to_interp(a, span)).collect::<Result<Vec<_>, _>>()?;
+        let worker = yps_interpreter::stdlib::worker::construct(&base, args, run_file, self.allow_eval, span)
+            .map_err(|e| VmError::new(e.message, e.span))?;
+        Ok(Value::Host(worker))
+    }
+
+    /// `родитель` inside a worker.
+    pub(crate) fn parent_port() -> Option<Value> {
+        yps_interpreter::worker::parent_port().map(|port| Value::Host(IValue::Worker(port)))
+    }
+}
+
+fn run_file(path: &Path, stop: Arc<AtomicBool>, allow_eval: bool) -> Result<(), String> {
+    let program = yps_interpreter::worker::parse_file(path)?;
+    let proto = crate::compiler::compile_program(&program).map_err(|e| e.message)?;
+    let mut vm = Vm::new();
+    vm.set_allow_eval(allow_eval);
+    if let Some(dir) = path.parent() {
+        vm.set_base_path(dir.to_path_buf());
+    }
+    vm.set_module_path(path.to_path_buf());
+    vm.set_interrupt(stop);
+    vm.run(proto).map_err(|e| e.message)
+}
@@ -1,5 +1,6 @@
 import * as vscode from "vscode";
 import { existsSync } from "node:fs";
+import * as path from "node:path";
 import {
   LanguageClient,
   LanguageClientOptions,
@@ -34,6 +35,30 @@ export function activate(context: vscode.ExtensionContext): void {
 
   const serverPath = resolved.path;
 
+  context.subscriptions.push(
+    vscode.debug.registerDebugAdapterDescriptorFactory("yoptascript", {
+      createDebugAdapterDescriptor(session) {
+        return new vscode.DebugAdapterExecutable(debugAdapterPath(session.configuration, serverPath));
+      }
+    }),
+    vscode.commands.registerCommand(
+      "yps.showReferences",
+      (uri: string, position: LspPosition, locations: LspLocation[]) =>
+        vscode.commands.executeCommand(
+          "editor.action.showReferences",
+          vscode.Uri.parse(uri),
+          toPosition(position),
+          locations.map(
+            (loc) =>
+              new vscode.Location(
+                vscode.Uri.parse(loc.uri),
+                new vscode.Range(toPosition(loc.range.start), toPosition(loc.range.end))
+              )
+          )
+        )
+    )
+  );
+
   const serverOptions: ServerOptions = {
     run: { command: serverPath, transport: TransportKind.stdio },
     debug: { command: serverPath, transport: TransportKind.stdio }
@@ -43,6 +68,16 @@ export function activate(context: vscode.ExtensionContext): void {
     documentSelector: [{ scheme: "file", language: "yoptascript" }],
     synchronize: {
       fileEvents: vscode.workspace.createFileSystemWatcher("**/*.yopta")
+    },
+    middleware: {
+      // yps.debug answers with a launch configuration; the session itself is started here.
+      async executeCommand(command, args, next) {
+        const result = await next(command, args);
+        if (command === "yps.debug" && result) {
+          await vscode.debug.startDebugging(undefined, result as vscode.DebugConfiguration);
+        }
+        return result;
+      }
     }
   };
 
@@ -63,6 +98,29 @@ export function activate(context: vscode.ExtensionContext): void {
   });
 }
 
+interface LspPosition {
+  line: number;
+  character: number;
+}
+
+interface LspLocation {
+  uri: string;
+  range: { start: LspPosition; end: LspPosition };
+}
+
+function toPosition(position: LspPosition): vscode.Position {
+  return new vscode.Position(position.line, position.character);
+}
+
+// yps-dap from the launch configuration, else next to the language server, else from PATH.
+function debugAdapterPath(configuration: vscode.DebugConfiguration, serverPath: string): string {
+  if (typeof configuration.debugAdapter === "string" && configuration.debugAdapter !== "") {
+    return configuration.debugAdapter;
+  }
+  const sibling = path.join(path.dirname(serverPath), process.platform === "win32" ? "yps-dap.exe" : "yps-dap");
+  return existsSync(sibling) ? sibling : "yps-dap";
+}
+
 export function deactivate(): Thenable<void> | undefined {
   if (!client) {
     return undefined;